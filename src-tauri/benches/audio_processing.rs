@@ -1,17 +1,43 @@
 //! Audio Processing Benchmarks
-//! 
-//! This is a placeholder benchmark file.
-//! The actual benchmarks are in tests/benchmarks/performance_benchmarks.rs
-//! This file exists to satisfy Cargo.toml requirements.
+//!
+//! Compares the resampling qualities on the conversion every 48 kHz
+//! microphone goes through before transcription.
 
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use kaginote_lib::audio::resampler::{AudioResampler, ResamplingQuality};
+use kaginote_lib::audio::types::{AudioData, AudioSource};
+use std::time::SystemTime;
 
-fn placeholder_benchmark(c: &mut Criterion) {
-    c.bench_function("placeholder", |b| b.iter(|| {
-        // Placeholder benchmark - will be replaced by actual implementation
-        42
-    }));
+fn create_test_audio(sample_rate: u32, duration_secs: f32) -> AudioData {
+    let samples: Vec<f32> = (0..(sample_rate as f32 * duration_secs) as usize)
+        .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin() * 0.5)
+        .collect();
+    AudioData {
+        samples,
+        sample_rate,
+        channels: 1,
+        timestamp: SystemTime::now(),
+        source_channel: AudioSource::Microphone,
+        duration_seconds: duration_secs,
+    }
 }
 
-criterion_group!(benches, placeholder_benchmark);
-criterion_main!(benches);
\ No newline at end of file
+/// 10 seconds of 48 kHz audio converted to 16 kHz at each quality
+fn benchmark_resampling_quality(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resampling_48k_to_16k_10s");
+    let audio = create_test_audio(48000, 10.0);
+
+    for quality in [ResamplingQuality::Fast, ResamplingQuality::Medium, ResamplingQuality::High] {
+        group.bench_with_input(BenchmarkId::new("resample", quality.label()), &audio, |b, audio| {
+            b.iter(|| {
+                let mut resampler = AudioResampler::new(48000, 16000, 1, quality).unwrap();
+                black_box(resampler.process(audio).unwrap())
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_resampling_quality);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+//! Keyword Spotting Benchmarks
+//!
+//! Every stored segment runs through the matcher inside the transcription
+//! loop, so 200 rules against a long segment has to stay far below the time
+//! a chunk takes to transcribe.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use kaginote_lib::transcription::keywords::{KeywordMatcher, KeywordRule, MatchMode};
+
+const RULES: usize = 200;
+
+fn rules(mode: MatchMode) -> Vec<KeywordRule> {
+    (0..RULES)
+        .map(|i| KeywordRule::new(&format!("competitor {} pricing", i), mode))
+        .collect()
+}
+
+/// About a minute of speech, mentioning a few of the keywords
+fn long_segment() -> String {
+    let sentence = "We compared the proposal with what the other vendors offered last quarter and ";
+    let mut text = sentence.repeat(12);
+    text.push_str("Competitor 17 pricing came up twice, as did competitor 180 pricing.");
+    text
+}
+
+fn japanese_segment() -> String {
+    "先月の打ち合わせでは他社の価格と比較して導入時期を検討しましたが、".repeat(12) + "competitor 17 pricingの話も出ました。"
+}
+
+fn benchmark_modes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("keyword_spotting_200_rules");
+    let segment = long_segment();
+
+    for (label, mode) in [("exact", MatchMode::Exact), ("word_boundary", MatchMode::WordBoundary), ("fuzzy", MatchMode::Fuzzy)] {
+        let matcher = KeywordMatcher::new(&rules(mode));
+        group.bench_with_input(BenchmarkId::new("long_segment", label), &segment, |b, segment| {
+            b.iter(|| black_box(matcher.find(black_box(segment))))
+        });
+    }
+
+    let matcher = KeywordMatcher::new(&rules(MatchMode::WordBoundary));
+    let segment = japanese_segment();
+    group.bench_with_input(BenchmarkId::new("japanese_segment", "word_boundary"), &segment, |b, segment| {
+        b.iter(|| black_box(matcher.find(black_box(segment))))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_modes);
+criterion_main!(benches);
@@ -0,0 +1,5 @@
+-- Rollback migration: Drop usage metrics table
+-- Version: 002
+
+DROP INDEX IF EXISTS idx_usage_metrics_ended_at;
+DROP TABLE IF EXISTS usage_metrics;
@@ -0,0 +1,17 @@
+-- Migration: Create local usage metrics table
+-- Version: 002
+-- Description: Coarse, content-free per-session metrics for the in-app usage dashboard.
+-- Only numeric and enum columns are allowed: no transcript text, speaker names or audio features.
+
+CREATE TABLE IF NOT EXISTS usage_metrics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ended_at INTEGER NOT NULL, -- Unix seconds
+    duration_seconds REAL NOT NULL,
+    model_tier TEXT NOT NULL CHECK (model_tier IN ('standard', 'high-accuracy', 'turbo')),
+    outcome TEXT NOT NULL CHECK (outcome IN ('completed', 'error', 'emergency_stop')),
+    segment_count INTEGER NOT NULL DEFAULT 0,
+    error_count INTEGER NOT NULL DEFAULT 0,
+    diarization_enabled BOOLEAN NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_metrics_ended_at ON usage_metrics(ended_at);
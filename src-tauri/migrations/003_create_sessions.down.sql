@@ -0,0 +1,8 @@
+-- Rollback migration: Drop transcription session tables
+-- Version: 003
+
+DROP INDEX IF EXISTS idx_transcript_segments_session;
+DROP INDEX IF EXISTS idx_sessions_status;
+DROP INDEX IF EXISTS idx_sessions_started_at;
+DROP TABLE IF EXISTS transcript_segments;
+DROP TABLE IF EXISTS sessions;
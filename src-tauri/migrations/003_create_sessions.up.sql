@@ -0,0 +1,31 @@
+-- Migration: Create transcription session tables
+-- Version: 003
+-- Description: Sessions and their transcript segments, written while recording so
+-- transcripts survive crashes and app restarts.
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    started_at INTEGER NOT NULL, -- Unix seconds
+    ended_at INTEGER, -- NULL while the session is running
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'completed', 'interrupted')),
+    continuation_of TEXT, -- Previous session when created by automatic rollover
+    config_json TEXT NOT NULL DEFAULT '{}',
+    total_duration REAL NOT NULL DEFAULT 0,
+    audio_path TEXT
+);
+
+CREATE TABLE IF NOT EXISTS transcript_segments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    seq INTEGER NOT NULL, -- Position in the session transcript
+    start_time REAL,
+    end_time REAL,
+    speaker TEXT,
+    text TEXT NOT NULL DEFAULT '',
+    segment_json TEXT NOT NULL, -- Full segment as emitted to the frontend
+    UNIQUE (session_id, seq)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_started_at ON sessions(started_at);
+CREATE INDEX IF NOT EXISTS idx_sessions_status ON sessions(status);
+CREATE INDEX IF NOT EXISTS idx_transcript_segments_session ON transcript_segments(session_id, seq);
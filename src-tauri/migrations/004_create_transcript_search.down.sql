@@ -0,0 +1,7 @@
+-- Rollback migration: Drop transcript search index
+-- Version: 004
+
+DROP TRIGGER IF EXISTS transcript_search_update;
+DROP TRIGGER IF EXISTS transcript_search_delete;
+DROP TRIGGER IF EXISTS transcript_search_insert;
+DROP TABLE IF EXISTS transcript_search;
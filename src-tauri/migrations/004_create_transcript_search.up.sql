@@ -0,0 +1,31 @@
+-- Migration: Create transcript search index
+-- Version: 004
+-- Description: FTS5 index over saved segment text for transcript search. The
+-- trigram tokenizer matches substrings, so languages written without spaces
+-- (Japanese, Chinese) are searchable too. Triggers keep it in sync with
+-- transcript_segments.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS transcript_search USING fts5(
+    text,
+    content = 'transcript_segments',
+    content_rowid = 'id',
+    tokenize = 'trigram'
+);
+
+CREATE TRIGGER IF NOT EXISTS transcript_search_insert AFTER INSERT ON transcript_segments BEGIN
+    INSERT INTO transcript_search (rowid, text) VALUES (new.id, new.text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS transcript_search_delete AFTER DELETE ON transcript_segments BEGIN
+    INSERT INTO transcript_search (transcript_search, rowid, text) VALUES ('delete', old.id, old.text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS transcript_search_update AFTER UPDATE ON transcript_segments BEGIN
+    INSERT INTO transcript_search (transcript_search, rowid, text) VALUES ('delete', old.id, old.text);
+    INSERT INTO transcript_search (rowid, text) VALUES (new.id, new.text);
+END;
+
+-- Index segments saved before the index existed
+INSERT INTO transcript_search (transcript_search)
+    SELECT 'rebuild'
+    WHERE (SELECT COUNT(*) FROM transcript_search_docsize) != (SELECT COUNT(*) FROM transcript_segments);
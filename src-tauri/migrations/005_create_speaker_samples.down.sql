@@ -0,0 +1,5 @@
+-- Rollback migration: Drop speaker samples table
+-- Version: 005
+
+DROP INDEX IF EXISTS idx_speaker_samples_speaker;
+DROP TABLE IF EXISTS speaker_samples;
@@ -0,0 +1,16 @@
+-- Migration: Create speaker samples table
+-- Version: 005
+-- Description: Reference audio clips of speaker profiles, so an identified
+-- speaker can be listened to. Clips are stored as WAV files; the app keeps at
+-- most a minute per speaker and evicts the oldest clips first.
+
+CREATE TABLE IF NOT EXISTS speaker_samples (
+    id TEXT PRIMARY KEY,
+    speaker_id TEXT NOT NULL REFERENCES speaker_profiles(id) ON DELETE CASCADE,
+    wav BLOB NOT NULL,
+    sample_rate INTEGER NOT NULL,
+    duration_seconds REAL NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_speaker_samples_speaker ON speaker_samples(speaker_id, created_at);
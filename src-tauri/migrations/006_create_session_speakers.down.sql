@@ -0,0 +1,8 @@
+-- Rollback migration: Drop session speaker aliases
+-- Version: 006
+
+DROP TRIGGER IF EXISTS session_speakers_update;
+DROP TRIGGER IF EXISTS session_speakers_insert;
+DROP INDEX IF EXISTS idx_transcript_segments_speaker;
+DROP INDEX IF EXISTS idx_session_speakers_name;
+DROP TABLE IF EXISTS session_speakers;
@@ -0,0 +1,37 @@
+-- Migration: Create session speaker aliases
+-- Version: 006
+-- Description: Display name of each speaker of a saved session, as it was
+-- named in that meeting. Triggers keep it in sync with the speakerName of the
+-- session's segments, so transcript search can filter and label speakers with
+-- a join instead of reading every segment's JSON.
+
+CREATE TABLE IF NOT EXISTS session_speakers (
+    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    speaker_id TEXT NOT NULL, -- Session-local speaker id or speaker profile id
+    display_name TEXT NOT NULL,
+    PRIMARY KEY (session_id, speaker_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_speakers_name ON session_speakers(display_name COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS idx_transcript_segments_speaker ON transcript_segments(speaker, session_id);
+
+CREATE TRIGGER IF NOT EXISTS session_speakers_insert AFTER INSERT ON transcript_segments
+WHEN new.speaker IS NOT NULL AND json_extract(new.segment_json, '$.speakerName') IS NOT NULL BEGIN
+    INSERT INTO session_speakers (session_id, speaker_id, display_name)
+    VALUES (new.session_id, new.speaker, json_extract(new.segment_json, '$.speakerName'))
+    ON CONFLICT (session_id, speaker_id) DO UPDATE SET display_name = excluded.display_name;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_speakers_update AFTER UPDATE ON transcript_segments
+WHEN new.speaker IS NOT NULL AND json_extract(new.segment_json, '$.speakerName') IS NOT NULL BEGIN
+    INSERT INTO session_speakers (session_id, speaker_id, display_name)
+    VALUES (new.session_id, new.speaker, json_extract(new.segment_json, '$.speakerName'))
+    ON CONFLICT (session_id, speaker_id) DO UPDATE SET display_name = excluded.display_name;
+END;
+
+-- Names of segments saved before the table existed
+INSERT OR REPLACE INTO session_speakers (session_id, speaker_id, display_name)
+    SELECT session_id, speaker, json_extract(segment_json, '$.speakerName')
+    FROM transcript_segments
+    WHERE speaker IS NOT NULL AND json_extract(segment_json, '$.speakerName') IS NOT NULL
+    ORDER BY id;
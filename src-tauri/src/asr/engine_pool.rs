@@ -0,0 +1,150 @@
+//! Shared Whisper engines by model tier
+//!
+//! Live sessions and file transcriptions lease engines from the pool instead
+//! of sharing one engine behind a mutex. A lease is a reference-counted
+//! handle: transcription only needs `&self`, so a file transcription running
+//! on the same engine as a live session does not wait for it, and the engine
+//! is freed when its last lease is dropped. The pool keeps weak references
+//! only, so it never keeps a model in memory by itself.
+
+use super::types::ModelTier;
+use super::whisper::WhisperEngine;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, Weak};
+
+/// A reference-counted handle on a pooled engine
+pub struct EngineLease<E = WhisperEngine> {
+    tier: ModelTier,
+    engine: Arc<E>,
+}
+
+impl<E> EngineLease<E> {
+    pub fn tier(&self) -> ModelTier {
+        self.tier
+    }
+}
+
+impl<E> Clone for EngineLease<E> {
+    fn clone(&self) -> Self {
+        Self {
+            tier: self.tier,
+            engine: self.engine.clone(),
+        }
+    }
+}
+
+impl<E> Deref for EngineLease<E> {
+    type Target = E;
+
+    fn deref(&self) -> &E {
+        &self.engine
+    }
+}
+
+impl<E> fmt::Debug for EngineLease<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EngineLease")
+            .field("tier", &self.tier)
+            .field("leases", &Arc::strong_count(&self.engine))
+            .finish()
+    }
+}
+
+/// The engine handed out for each model tier
+pub struct EnginePool<E = WhisperEngine> {
+    engines: HashMap<ModelTier, Weak<E>>,
+}
+
+impl<E> Default for EnginePool<E> {
+    fn default() -> Self {
+        Self { engines: HashMap::new() }
+    }
+}
+
+impl<E> EnginePool<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lease the loaded engine for `tier`, if any consumer still holds one
+    pub fn lease(&mut self, tier: ModelTier) -> Option<EngineLease<E>> {
+        let engine = self.engines.get(&tier).and_then(Weak::upgrade);
+        if engine.is_none() {
+            self.engines.remove(&tier);
+        }
+        engine.map(|engine| EngineLease { tier, engine })
+    }
+
+    /// Add an engine loaded outside the pool and lease it. It becomes the
+    /// engine leased for `tier`; consumers of an engine it replaces, e.g. one
+    /// loaded with other decoding settings, keep using theirs.
+    pub fn register(&mut self, tier: ModelTier, engine: E) -> EngineLease<E> {
+        let engine = Arc::new(engine);
+        self.engines.insert(tier, Arc::downgrade(&engine));
+        EngineLease { tier, engine }
+    }
+
+    /// Number of leases held on the engine for `tier`
+    pub fn lease_count(&self, tier: ModelTier) -> usize {
+        self.engines.get(&tier).map_or(0, Weak::strong_count)
+    }
+
+    /// Tiers with an engine in memory
+    pub fn loaded_tiers(&self) -> Vec<ModelTier> {
+        self.engines.iter()
+            .filter(|(_, engine)| engine.strong_count() > 0)
+            .map(|(&tier, _)| tier)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct MockEngine(u32);
+
+    #[test]
+    fn test_leases_share_one_engine_per_tier() {
+        let mut pool = EnginePool::new();
+        assert!(pool.lease(ModelTier::Standard).is_none());
+
+        let live = pool.register(ModelTier::Standard, MockEngine(1));
+        let file = pool.lease(ModelTier::Standard).expect("engine is loaded");
+        assert_eq!(*file, MockEngine(1));
+        assert_eq!(pool.lease_count(ModelTier::Standard), 2);
+        assert!(pool.lease(ModelTier::Turbo).is_none());
+        drop(live);
+        assert_eq!(pool.lease_count(ModelTier::Standard), 1);
+    }
+
+    #[test]
+    fn test_engine_is_freed_with_the_last_lease() {
+        let mut pool = EnginePool::new();
+        let live = pool.register(ModelTier::Turbo, MockEngine(1));
+        let copy = live.clone();
+        drop(live);
+        assert_eq!(pool.loaded_tiers(), vec![ModelTier::Turbo]);
+        drop(copy);
+        assert!(pool.loaded_tiers().is_empty());
+        assert!(pool.lease(ModelTier::Turbo).is_none());
+
+        let reloaded = pool.register(ModelTier::Turbo, MockEngine(2));
+        assert_eq!(*reloaded, MockEngine(2));
+    }
+
+    #[test]
+    fn test_replaced_engine_stays_with_its_consumers() {
+        let mut pool = EnginePool::new();
+        let first = pool.register(ModelTier::HighAccuracy, MockEngine(1));
+        let second = pool.register(ModelTier::HighAccuracy, MockEngine(2));
+        assert_eq!(*first, MockEngine(1));
+        assert_eq!(first.tier(), ModelTier::HighAccuracy);
+        assert_eq!(*pool.lease(ModelTier::HighAccuracy).unwrap(), MockEngine(2));
+        drop(second);
+        assert!(pool.lease(ModelTier::HighAccuracy).is_none());
+    }
+}
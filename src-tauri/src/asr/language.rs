@@ -0,0 +1,344 @@
+//! Language codes
+//!
+//! Config, Whisper and exports all talk about languages, but not in the same
+//! dialect: the frontend sends ISO 639-1 codes ("en"), Whisper expects its own
+//! codes (Javanese is "jw"), and exports want BCP-47 tags. [`Language`] wraps a
+//! validated language and converts between these forms. Parsing is lenient
+//! about input (case, region subtags, ISO 639-3, deprecated codes, English
+//! names from older configs) but always yields one canonical language.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// One supported language: (Whisper code, ISO 639-3 codes, English name, native name)
+struct LanguageEntry {
+    whisper: &'static str,
+    iso639_3: &'static [&'static str],
+    name: &'static str,
+    native: &'static str,
+}
+
+const fn entry(whisper: &'static str, iso639_3: &'static [&'static str], name: &'static str, native: &'static str) -> LanguageEntry {
+    LanguageEntry { whisper, iso639_3, name, native }
+}
+
+/// The 99 languages supported by the Whisper models, in Whisper's order
+const LANGUAGES: &[LanguageEntry] = &[
+    entry("en", &["eng"], "English", "English"),
+    entry("zh", &["zho", "chi", "cmn"], "Chinese", "中文"),
+    entry("de", &["deu", "ger"], "German", "Deutsch"),
+    entry("es", &["spa"], "Spanish", "Español"),
+    entry("ru", &["rus"], "Russian", "Русский"),
+    entry("ko", &["kor"], "Korean", "한국어"),
+    entry("fr", &["fra", "fre"], "French", "Français"),
+    entry("ja", &["jpn"], "Japanese", "日本語"),
+    entry("pt", &["por"], "Portuguese", "Português"),
+    entry("tr", &["tur"], "Turkish", "Türkçe"),
+    entry("pl", &["pol"], "Polish", "Polski"),
+    entry("ca", &["cat"], "Catalan", "Català"),
+    entry("nl", &["nld", "dut"], "Dutch", "Nederlands"),
+    entry("ar", &["ara"], "Arabic", "العربية"),
+    entry("sv", &["swe"], "Swedish", "Svenska"),
+    entry("it", &["ita"], "Italian", "Italiano"),
+    entry("id", &["ind"], "Indonesian", "Bahasa Indonesia"),
+    entry("hi", &["hin"], "Hindi", "हिन्दी"),
+    entry("fi", &["fin"], "Finnish", "Suomi"),
+    entry("vi", &["vie"], "Vietnamese", "Tiếng Việt"),
+    entry("he", &["heb"], "Hebrew", "עברית"),
+    entry("uk", &["ukr"], "Ukrainian", "Українська"),
+    entry("el", &["ell", "gre"], "Greek", "Ελληνικά"),
+    entry("ms", &["msa", "may", "zsm"], "Malay", "Bahasa Melayu"),
+    entry("cs", &["ces", "cze"], "Czech", "Čeština"),
+    entry("ro", &["ron", "rum"], "Romanian", "Română"),
+    entry("da", &["dan"], "Danish", "Dansk"),
+    entry("hu", &["hun"], "Hungarian", "Magyar"),
+    entry("ta", &["tam"], "Tamil", "தமிழ்"),
+    entry("no", &["nor", "nob"], "Norwegian", "Norsk"),
+    entry("th", &["tha"], "Thai", "ไทย"),
+    entry("ur", &["urd"], "Urdu", "اردو"),
+    entry("hr", &["hrv"], "Croatian", "Hrvatski"),
+    entry("bg", &["bul"], "Bulgarian", "Български"),
+    entry("lt", &["lit"], "Lithuanian", "Lietuvių"),
+    entry("la", &["lat"], "Latin", "Latina"),
+    entry("mi", &["mri", "mao"], "Maori", "Māori"),
+    entry("ml", &["mal"], "Malayalam", "മലയാളം"),
+    entry("cy", &["cym", "wel"], "Welsh", "Cymraeg"),
+    entry("sk", &["slk", "slo"], "Slovak", "Slovenčina"),
+    entry("te", &["tel"], "Telugu", "తెలుగు"),
+    entry("fa", &["fas", "per"], "Persian", "فارسی"),
+    entry("lv", &["lav"], "Latvian", "Latviešu"),
+    entry("bn", &["ben"], "Bengali", "বাংলা"),
+    entry("sr", &["srp"], "Serbian", "Српски"),
+    entry("az", &["aze"], "Azerbaijani", "Azərbaycan"),
+    entry("sl", &["slv"], "Slovenian", "Slovenščina"),
+    entry("kn", &["kan"], "Kannada", "ಕನ್ನಡ"),
+    entry("et", &["est"], "Estonian", "Eesti"),
+    entry("mk", &["mkd", "mac"], "Macedonian", "Македонски"),
+    entry("br", &["bre"], "Breton", "Brezhoneg"),
+    entry("eu", &["eus", "baq"], "Basque", "Euskara"),
+    entry("is", &["isl", "ice"], "Icelandic", "Íslenska"),
+    entry("hy", &["hye", "arm"], "Armenian", "Հայերեն"),
+    entry("ne", &["nep"], "Nepali", "नेपाली"),
+    entry("mn", &["mon"], "Mongolian", "Монгол"),
+    entry("bs", &["bos"], "Bosnian", "Bosanski"),
+    entry("kk", &["kaz"], "Kazakh", "Қазақ"),
+    entry("sq", &["sqi", "alb"], "Albanian", "Shqip"),
+    entry("sw", &["swa"], "Swahili", "Kiswahili"),
+    entry("gl", &["glg"], "Galician", "Galego"),
+    entry("mr", &["mar"], "Marathi", "मराठी"),
+    entry("pa", &["pan"], "Punjabi", "ਪੰਜਾਬੀ"),
+    entry("si", &["sin"], "Sinhala", "සිංහල"),
+    entry("km", &["khm"], "Khmer", "ខ្មែរ"),
+    entry("sn", &["sna"], "Shona", "chiShona"),
+    entry("yo", &["yor"], "Yoruba", "Yorùbá"),
+    entry("so", &["som"], "Somali", "Soomaali"),
+    entry("af", &["afr"], "Afrikaans", "Afrikaans"),
+    entry("oc", &["oci"], "Occitan", "Occitan"),
+    entry("ka", &["kat", "geo"], "Georgian", "ქართული"),
+    entry("be", &["bel"], "Belarusian", "Беларуская"),
+    entry("tg", &["tgk"], "Tajik", "Тоҷикӣ"),
+    entry("sd", &["snd"], "Sindhi", "سنڌي"),
+    entry("gu", &["guj"], "Gujarati", "ગુજરાતી"),
+    entry("am", &["amh"], "Amharic", "አማርኛ"),
+    entry("yi", &["yid"], "Yiddish", "ייִדיש"),
+    entry("lo", &["lao"], "Lao", "ລາວ"),
+    entry("uz", &["uzb"], "Uzbek", "Oʻzbek"),
+    entry("fo", &["fao"], "Faroese", "Føroyskt"),
+    entry("ht", &["hat"], "Haitian Creole", "Kreyòl ayisyen"),
+    entry("ps", &["pus"], "Pashto", "پښتو"),
+    entry("tk", &["tuk"], "Turkmen", "Türkmen"),
+    entry("nn", &["nno"], "Nynorsk", "Nynorsk"),
+    entry("mt", &["mlt"], "Maltese", "Malti"),
+    entry("sa", &["san"], "Sanskrit", "संस्कृतम्"),
+    entry("lb", &["ltz"], "Luxembourgish", "Lëtzebuergesch"),
+    entry("my", &["mya", "bur"], "Myanmar", "မြန်မာ"),
+    entry("bo", &["bod", "tib"], "Tibetan", "བོད་སྐད"),
+    entry("tl", &["tgl", "fil"], "Tagalog", "Tagalog"),
+    entry("mg", &["mlg"], "Malagasy", "Malagasy"),
+    entry("as", &["asm"], "Assamese", "অসমীয়া"),
+    entry("tt", &["tat"], "Tatar", "Татар"),
+    entry("haw", &["haw"], "Hawaiian", "ʻŌlelo Hawaiʻi"),
+    entry("ln", &["lin"], "Lingala", "Lingála"),
+    entry("ha", &["hau"], "Hausa", "Hausa"),
+    entry("ba", &["bak"], "Bashkir", "Башҡорт"),
+    entry("jw", &["jav"], "Javanese", "Basa Jawa"),
+    entry("su", &["sun"], "Sundanese", "Basa Sunda"),
+];
+
+/// Deprecated or alternative two-letter codes -> Whisper code
+const ALIASES: &[(&str, &str)] = &[
+    ("iw", "he"),
+    ("in", "id"),
+    ("ji", "yi"),
+    ("jv", "jw"),
+    ("mo", "ro"),
+    ("nb", "no"),
+];
+
+const AUTO: &str = "auto";
+
+/// Why a language code could not be parsed
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum LanguageError {
+    #[error("Language code is empty. Use an ISO 639-1 code such as \"en\" or \"ja\", or \"auto\" to detect the language")]
+    Empty,
+
+    #[error("Unknown language code \"{input}\". Use an ISO 639-1 code such as \"en\" or \"ja\", a BCP-47 tag such as \"pt-BR\", or \"auto\"{}",
+            .suggestion.as_ref().map(|s| format!(" (did you mean \"{}\"?)", s)).unwrap_or_default())]
+    Unknown { input: String, suggestion: Option<String> },
+}
+
+/// A validated language, or automatic detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Language(&'static str);
+
+/// Language entry for the UI picker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageInfo {
+    /// Canonical code used in configs and exports (BCP-47)
+    pub code: String,
+    #[serde(rename = "whisperCode")]
+    pub whisper_code: String,
+    pub name: String,
+    #[serde(rename = "nativeName")]
+    pub native_name: String,
+}
+
+impl Language {
+    pub const AUTO: Language = Language(AUTO);
+    pub const ENGLISH: Language = Language("en");
+
+    /// Parse a config, Whisper, ISO 639-1/639-3 or BCP-47 code (case-insensitive)
+    pub fn parse(input: &str) -> Result<Self, LanguageError> {
+        let normalized = input.trim().to_lowercase().replace('_', "-");
+        if normalized.is_empty() {
+            return Err(LanguageError::Empty);
+        }
+        if normalized == AUTO {
+            return Ok(Self::AUTO);
+        }
+
+        // Whole input first (also catches names like "haitian creole"), then the primary subtag
+        let primary = normalized.split('-').next().unwrap_or_default();
+        Self::lookup(&normalized)
+            .or_else(|| Self::lookup(primary))
+            .ok_or_else(|| LanguageError::Unknown {
+                input: input.trim().to_string(),
+                suggestion: Self::suggest(primary),
+            })
+    }
+
+    fn lookup(code: &str) -> Option<Self> {
+        let code = ALIASES.iter().find(|(alias, _)| *alias == code).map(|(_, target)| *target).unwrap_or(code);
+        LANGUAGES.iter()
+            .find(|l| l.whisper == code || l.iso639_3.contains(&code) || l.name.eq_ignore_ascii_case(code))
+            .map(|l| Language(l.whisper))
+    }
+
+    /// Closest language by English name prefix, for error messages
+    fn suggest(code: &str) -> Option<String> {
+        if code.len() < 2 {
+            return None;
+        }
+        LANGUAGES.iter()
+            .find(|l| l.name.to_lowercase().starts_with(code))
+            .map(|l| Language(l.whisper).code().to_string())
+    }
+
+    fn entry(&self) -> Option<&'static LanguageEntry> {
+        LANGUAGES.iter().find(|l| l.whisper == self.0)
+    }
+
+    pub fn is_auto(&self) -> bool {
+        self.0 == AUTO
+    }
+
+    /// Canonical code for configs and exports: ISO 639-1 where one exists, valid BCP-47
+    pub fn code(&self) -> &'static str {
+        match self.0 {
+            "jw" => "jv",
+            code => code,
+        }
+    }
+
+    /// Code Whisper expects for its language token ("auto" lets Whisper detect)
+    pub fn whisper_code(&self) -> &'static str {
+        self.0
+    }
+
+    /// ISO 639-3 code, if this is a concrete language
+    pub fn iso639_3(&self) -> Option<&'static str> {
+        self.entry().and_then(|l| l.iso639_3.first().copied())
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.entry().map(|l| l.name).unwrap_or("Auto-detect")
+    }
+
+    pub fn native_name(&self) -> &'static str {
+        self.entry().map(|l| l.native).unwrap_or("Auto-detect")
+    }
+
+    pub fn info(&self) -> LanguageInfo {
+        LanguageInfo {
+            code: self.code().to_string(),
+            whisper_code: self.whisper_code().to_string(),
+            name: self.name().to_string(),
+            native_name: self.native_name().to_string(),
+        }
+    }
+}
+
+/// All languages Whisper can transcribe, in Whisper's order (without "auto")
+pub fn supported_languages() -> Vec<Language> {
+    LANGUAGES.iter().map(|l| Language(l.whisper)).collect()
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Self::ENGLISH
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = LanguageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Language {
+    type Error = LanguageError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Language> for String {
+    fn from(language: Language) -> Self {
+        language.code().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        assert_eq!(supported_languages().len(), 99);
+        for language in supported_languages() {
+            assert_eq!(Language::parse(language.code()), Ok(language));
+            assert_eq!(Language::parse(language.whisper_code()), Ok(language));
+            assert_eq!(Language::parse(language.iso639_3().unwrap()), Ok(language));
+
+            let json = serde_json::to_value(language).unwrap();
+            assert_eq!(serde_json::from_value::<Language>(json).unwrap(), language);
+        }
+        assert_eq!(Language::parse("jv").unwrap().whisper_code(), "jw");
+        assert_eq!(Language::parse("jw").unwrap().code(), "jv");
+    }
+
+    #[test]
+    fn test_case_insensitive_and_bcp47() {
+        let japanese = Language::parse("ja").unwrap();
+        for input in ["JA", " ja ", "ja-JP", "ja_jp", "JPN", "Japanese"] {
+            assert_eq!(Language::parse(input), Ok(japanese), "{}", input);
+        }
+        assert_eq!(Language::parse("zh-Hant-TW").unwrap().code(), "zh");
+        assert_eq!(Language::parse("Auto"), Ok(Language::AUTO));
+        assert_eq!(japanese.native_name(), "日本語");
+    }
+
+    #[test]
+    fn test_deprecated_aliases() {
+        assert_eq!(Language::parse("iw").unwrap().code(), "he");
+        assert_eq!(Language::parse("in").unwrap().code(), "id");
+        assert_eq!(Language::parse("ji").unwrap().code(), "yi");
+        assert_eq!(Language::parse("nb-NO").unwrap().code(), "no");
+    }
+
+    #[test]
+    fn test_garbage_is_rejected_with_actionable_errors() {
+        assert_eq!(Language::parse("  "), Err(LanguageError::Empty));
+
+        let error = Language::parse("klingon").unwrap_err();
+        assert!(error.to_string().contains("\"klingon\""));
+        assert!(error.to_string().contains("ISO 639-1"));
+
+        let error = Language::parse("germ").unwrap_err();
+        assert!(matches!(error, LanguageError::Unknown { suggestion: Some(ref s), .. } if s == "de"));
+
+        let error = serde_json::from_str::<Language>("\"xx\"").unwrap_err();
+        assert!(error.to_string().contains("Unknown language code \"xx\""));
+    }
+}
@@ -3,7 +3,14 @@
 //! Provides Whisper-based speech recognition with multi-tier model support.
 
 pub mod types;
+pub mod language;
 pub mod whisper;
 pub mod model_manager;
+pub mod models_location;
+pub mod temperature_fallback;
+pub mod prompt_budget;
+pub mod word_timing;
+pub mod engine_pool;
+pub mod transcriber;
 
 pub use types::*;
\ No newline at end of file
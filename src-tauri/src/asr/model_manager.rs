@@ -7,6 +7,8 @@ use crate::asr::types::{ModelTier, ASRError};
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio::fs;
 use futures_util::StreamExt;
 use reqwest;
@@ -35,6 +37,9 @@ pub struct CacheMetadata {
     pub model_tier: ModelTier,
     pub last_validation: Option<chrono::DateTime<chrono::Utc>>,
     pub validation_status: ValidationStatus,
+    /// SHA-256 of the file as downloaded or first verified, the reference for later checks
+    #[serde(default)]
+    pub sha256: Option<String>,
 }
 
 /// Model cache status
@@ -54,9 +59,195 @@ pub enum ValidationStatus {
     Invalid { reason: String },
 }
 
+/// Tiers in listing order
+const ALL_TIERS: [ModelTier; 3] = [ModelTier::Standard, ModelTier::HighAccuracy, ModelTier::Turbo];
+
+/// A model file on disk, as shown in the settings page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadedModel {
+    pub tier: ModelTier,
+    #[serde(rename = "qualityTier")]
+    pub quality_tier: String,
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+    /// The file matched its reference checksum at the last verification
+    #[serde(rename = "checksumVerified")]
+    pub checksum_verified: bool,
+    #[serde(rename = "lastValidation")]
+    pub last_validation: Option<DateTime<Utc>>,
+    /// Other tiers served by the same file; deleting one deletes them all
+    #[serde(rename = "sharedWith")]
+    pub shared_with: Vec<ModelTier>,
+}
+
+/// Result of re-hashing a model file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVerification {
+    pub tier: ModelTier,
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+    pub sha256: String,
+    /// None when there was no reference checksum yet; the hash is then recorded as one
+    #[serde(rename = "checksumMatched")]
+    pub checksum_matched: Option<bool>,
+    pub valid: bool,
+    /// Why the file is considered corrupted
+    pub corruption: Option<String>,
+}
+
+/// Disk and memory needs of a model tier, for the tier picker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelRequirements {
+    pub tier: ModelTier,
+    #[serde(rename = "qualityTier")]
+    pub quality_tier: String,
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    #[serde(rename = "downloadBytes")]
+    pub download_bytes: u64,
+    /// Space the model takes once installed; tiers sharing a file share it
+    #[serde(rename = "onDiskBytes")]
+    pub on_disk_bytes: u64,
+    /// Bytes still to download before the tier can be used
+    #[serde(rename = "remainingDownloadBytes")]
+    pub remaining_download_bytes: u64,
+    #[serde(rename = "recommendedRamGb")]
+    pub recommended_ram_gb: f32,
+    pub downloaded: bool,
+}
+
+/// Free space to keep beyond a model download
+pub const DOWNLOAD_SPACE_MARGIN_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Why a download of `required` bytes does not fit in `available` bytes, if it does not
+pub fn disk_space_shortfall(required: u64, available: u64) -> Option<String> {
+    let needed = required.saturating_add(DOWNLOAD_SPACE_MARGIN_BYTES);
+    let gb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+    (available < needed).then(|| format!("need {:.1} GB, have {:.1} GB free", gb(needed), gb(available)))
+}
+
 /// Model download progress callback
 pub type ProgressCallback = Box<dyn Fn(u64, u64) + Send + Sync>;
 
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    notify: tokio::sync::Notify,
+}
+
+/// Cancels an in-flight model download, also while it waits on a stalled connection
+#[derive(Debug, Clone, Default)]
+pub struct DownloadCancellation(Arc<CancellationState>);
+
+impl DownloadCancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the download is cancelled
+    pub async fn cancelled(&self) {
+        // Registered before the check, so a cancel in between still wakes it
+        let notified = self.0.notify.notified();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Resolves when `cancellation` fires; never without one
+async fn wait_cancelled(cancellation: Option<&DownloadCancellation>) {
+    match cancellation {
+        Some(cancellation) => cancellation.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// How to continue a download after requesting the bytes past a partial file
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResumeDecision {
+    /// Server sent the remaining bytes: append from `offset`
+    Append { offset: u64, total: Option<u64> },
+    /// Server ignored or rejected the range: start over from an empty file
+    Restart,
+    /// The partial file already holds the whole model
+    Complete { total: u64 },
+}
+
+/// SHA-256 Hugging Face publishes for an LFS file: the `X-Linked-Etag` (or
+/// `ETag`) of the un-redirected resolve URL. Git blob ids and other ETags
+/// are not 64 hex digits and are ignored.
+pub fn published_sha256(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    ["x-linked-etag", "etag"].iter()
+        .filter_map(|name| headers.get(*name)?.to_str().ok())
+        .map(|value| value.trim().trim_start_matches("W/").trim_matches('"').to_ascii_lowercase())
+        .find(|value| value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Ask the model host for the published checksum of `url`
+async fn fetch_published_sha256(url: &str) -> Option<String> {
+    // The checksum is on the redirect itself, not on the CDN response it points to
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .ok()?;
+    match client.head(url).send().await {
+        Ok(response) => published_sha256(response.headers()),
+        Err(e) => {
+            tracing::warn!("Failed to fetch published checksum of {}: {}", url, e);
+            None
+        }
+    }
+}
+
+/// Parse `bytes start-end/total` (or `bytes */total`) into (start, total)
+fn parse_content_range(header: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let (range, total) = header.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let start = match range {
+        "*" => None,
+        range => Some(range.split_once('-')?.0.parse().ok()?),
+    };
+    let total = match total {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+    Some((start, total))
+}
+
+/// Decide how to use the response to a `Range: bytes={existing}-` request
+pub fn resume_decision(
+    existing: u64,
+    status: u16,
+    content_length: Option<u64>,
+    content_range: Option<&str>,
+) -> ResumeDecision {
+    let range = content_range.and_then(parse_content_range);
+    match (status, range) {
+        (206, Some((Some(start), total))) if start == existing => ResumeDecision::Append {
+            offset: existing,
+            total: total.or(content_length.map(|len| existing + len)),
+        },
+        (416, Some((_, Some(total)))) if total == existing => ResumeDecision::Complete { total },
+        _ => ResumeDecision::Restart,
+    }
+}
+
 /// Manages Whisper model downloading and caching
 pub struct ModelManager {
     models_dir: PathBuf,
@@ -68,7 +259,12 @@ pub struct ModelManager {
 impl ModelManager {
     /// Create new model manager
     pub fn new() -> Result<Self> {
-        let models_dir = Self::get_models_directory()?;
+        Self::with_directory(Self::get_models_directory()?)
+    }
+
+    /// Create a model manager for models stored in `models_dir`
+    pub fn with_directory(models_dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&models_dir)?;
         let model_registry = Self::initialize_model_registry();
         let cache_metadata_file = models_dir.join("cache_metadata.json");
         
@@ -84,17 +280,19 @@ impl ModelManager {
         })
     }
 
-    /// Get the models directory path
+    /// Get the models directory path: the configured one, or the default
     fn get_models_directory() -> Result<PathBuf> {
-        let app_data_dir = dirs::data_dir()
-            .ok_or_else(|| anyhow::anyhow!("Failed to get data directory"))?;
-        
-        let models_dir = app_data_dir.join("KagiNote").join("models");
+        let models_dir = super::models_location::current()?.directory;
         std::fs::create_dir_all(&models_dir)?;
         
         Ok(models_dir)
     }
 
+    /// Directory the models are stored in
+    pub fn models_dir(&self) -> &Path {
+        &self.models_dir
+    }
+
     /// Initialize the model registry with quantized models optimized for each tier
     fn initialize_model_registry() -> HashMap<ModelTier, ModelMetadata> {
         let mut registry = HashMap::new();
@@ -104,7 +302,7 @@ impl ModelManager {
             name: "ggml-medium.bin".to_string(),
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin".to_string(),
             size_mb: 1462, // Actual file size: 1533763059 bytes = 1462MB
-            sha256: "f4b2bc61d2b85e3b5a85e8e4c7c8e6d9b2a9c8b7d6e5f4a3b2c1d0e9f8a7b6c5".to_string(), // Placeholder
+            sha256: String::new(), // Not pinned: checked against the checksum Hugging Face publishes
             tier: ModelTier::Standard,
             quantization: "F32".to_string(),
             description: "Whisper Medium model unquantized - balanced performance".to_string(),
@@ -115,7 +313,7 @@ impl ModelManager {
             name: "ggml-medium-q5_0.bin".to_string(),
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q5_0.bin".to_string(),
             size_mb: 514, // Actual file size: 539212467 bytes = 514MB
-            sha256: "a9b8c7d6e5f4a3b2c1d0e9f8a7b6c5d4e3f2a1b0c9d8e7f6a5b4c3d2e1f0a9b8".to_string(), // Placeholder
+            sha256: String::new(), // Not pinned: checked against the checksum Hugging Face publishes
             tier: ModelTier::HighAccuracy,
             quantization: "Q5_0".to_string(),
             description: "Whisper Medium model with Q5_0 quantization - high accuracy".to_string(),
@@ -126,7 +324,7 @@ impl ModelManager {
             name: "ggml-medium.bin".to_string(), // Fallback to standard model
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin".to_string(),
             size_mb: 1462, // Actual file size: 1533763059 bytes = 1462MB
-            sha256: "b8a7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7".to_string(), // Placeholder
+            sha256: String::new(), // Not pinned: checked against the checksum Hugging Face publishes
             tier: ModelTier::Turbo,
             quantization: "F32".to_string(),
             description: "Whisper Medium model (fallback for turbo) - fastest available".to_string(),
@@ -197,6 +395,7 @@ impl ModelManager {
                 model_tier: tier,
                 last_validation: None,
                 validation_status: ValidationStatus::NotValidated,
+                sha256: None,
             };
             
             CacheStatus::Cached { metadata: cache_meta }
@@ -306,7 +505,8 @@ impl ModelManager {
     pub async fn ensure_model_available(
         &mut self, 
         tier: ModelTier,
-        progress_callback: Option<ProgressCallback>
+        progress_callback: Option<ProgressCallback>,
+        cancellation: Option<DownloadCancellation>
     ) -> Result<PathBuf, ASRError> {
         let cache_status = self.get_cache_status(tier).await;
         
@@ -320,7 +520,7 @@ impl ModelManager {
             },
             CacheStatus::Corrupted { reason } => {
                 tracing::warn!("Cached model corrupted ({}), re-downloading", reason);
-                self.download_model(tier, progress_callback).await
+                self.download_model(tier, progress_callback, cancellation).await
             },
             CacheStatus::NotCached => {
                 tracing::info!("Model not cached, checking for fallback options for tier: {:?}", tier);
@@ -335,22 +535,24 @@ impl ModelManager {
                 }
                 
                 // No fallback available, download the requested model
-                self.download_model(tier, progress_callback).await
+                self.download_model(tier, progress_callback, cancellation).await
             },
             CacheStatus::Downloading { progress: _ } => {
                 // This shouldn't happen in normal flow, but handle gracefully
                 tracing::warn!("Model already downloading, waiting...");
                 // For simplicity, attempt download again (could be improved with download queuing)
-                self.download_model(tier, progress_callback).await
+                self.download_model(tier, progress_callback, cancellation).await
             }
         }
     }
 
-    /// Download a specific model
+    /// Download a specific model. A partial file left by an interrupted attempt
+    /// is resumed with an HTTP range request; a cancelled download removes it.
     pub async fn download_model(
         &mut self,
         tier: ModelTier,
-        progress_callback: Option<ProgressCallback>
+        progress_callback: Option<ProgressCallback>,
+        cancellation: Option<DownloadCancellation>
     ) -> Result<PathBuf, ASRError> {
         let metadata = self.model_registry.get(&tier)
             .ok_or_else(|| ASRError::ModelLoadFailed {
@@ -364,6 +566,15 @@ impl ModelManager {
         tracing::info!("Download URL: {}", metadata.url);
         tracing::info!("Expected size: {} MB", metadata.size_mb);
 
+        // A checksum pinned in the registry wins over the one the host publishes
+        let expected_sha256 = match Some(metadata.sha256.to_ascii_lowercase()).filter(|sha| !sha.is_empty()) {
+            Some(pinned) => Some(pinned),
+            None => fetch_published_sha256(&metadata.url).await,
+        };
+        if expected_sha256.is_none() {
+            tracing::warn!("No published checksum for {}, the downloaded file becomes the reference", metadata.name);
+        }
+
         // Create HTTP client with timeout
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(3600)) // 1 hour timeout
@@ -372,55 +583,113 @@ impl ModelManager {
                 message: format!("Failed to create HTTP client: {}", e),
             })?;
 
+        // Ask only for the missing bytes when an earlier attempt left a partial file
+        let existing = fs::metadata(&temp_path).await.map(|m| m.len()).unwrap_or(0);
+        let mut request = client.get(&metadata.url);
+        if existing > 0 {
+            tracing::info!("Resuming download after {} bytes", existing);
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+        }
+
         // Start download
-        let response = client
-            .get(&metadata.url)
+        let mut response = request
             .send()
             .await
             .map_err(|e| ASRError::ModelLoadFailed {
                 message: format!("Failed to start download: {}", e),
             })?;
 
-        if !response.status().is_success() {
+        let decision = if existing > 0 {
+            let content_range = response.headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok());
+            resume_decision(existing, response.status().as_u16(), response.content_length(), content_range)
+        } else {
+            ResumeDecision::Restart
+        };
+
+        if decision == ResumeDecision::Restart && existing > 0 && response.status().as_u16() != 200 {
+            // Range rejected: fetch the whole file again
+            tracing::info!("Server did not resume the download, starting over");
+            response = client.get(&metadata.url).send().await.map_err(|e| ASRError::ModelLoadFailed {
+                message: format!("Failed to start download: {}", e),
+            })?;
+        }
+
+        if !matches!(decision, ResumeDecision::Complete { .. }) && !response.status().is_success() {
             return Err(ASRError::ModelLoadFailed {
                 message: format!("Download failed with status: {}", response.status()),
             });
         }
 
-        let total_size = response.content_length().unwrap_or(metadata.size_mb * 1024 * 1024);
+        let (offset, total_size) = match decision {
+            ResumeDecision::Append { offset, total } => (offset, total),
+            ResumeDecision::Complete { total } => (total, Some(total)),
+            ResumeDecision::Restart => (0, response.content_length()),
+        };
+        let total_size = total_size.unwrap_or(metadata.size_mb * 1024 * 1024);
 
-        // Create temporary file
-        let mut file = tokio::fs::File::create(&temp_path)
+        // Open the temporary file, keeping the resumed prefix
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(offset > 0)
+            .truncate(offset == 0)
+            .open(&temp_path)
             .await
             .map_err(|e| ASRError::ModelLoadFailed {
                 message: format!("Failed to create temporary file: {}", e),
             })?;
 
         // Download with progress tracking
-        let mut downloaded = 0u64;
-        let mut stream = response.bytes_stream();
+        let mut downloaded = offset;
+        // Covers only the bytes received in this attempt
         let mut hasher = Sha256::new();
+        if let Some(ref callback) = progress_callback {
+            callback(downloaded, total_size);
+        }
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| ASRError::ModelLoadFailed {
-                message: format!("Download error: {}", e),
-            })?;
+        if !matches!(decision, ResumeDecision::Complete { .. }) {
+            let mut stream = response.bytes_stream();
+            loop {
+                // Race the next chunk against cancellation: a stalled connection may never send one
+                let next = tokio::select! {
+                    biased;
+                    _ = wait_cancelled(cancellation.as_ref()) => None,
+                    chunk = stream.next() => Some(chunk),
+                };
+                let Some(chunk) = next else {
+                    drop(file);
+                    if let Err(e) = fs::remove_file(&temp_path).await {
+                        tracing::warn!("Failed to remove partial download {:?}: {}", temp_path, e);
+                    }
+                    tracing::info!("Download of {} cancelled after {} bytes", metadata.name, downloaded);
+                    return Err(ASRError::DownloadCancelled);
+                };
+                let Some(chunk) = chunk else {
+                    break;
+                };
 
-            // Write chunk to file
-            use tokio::io::AsyncWriteExt;
-            file.write_all(&chunk)
-                .await
-                .map_err(|e| ASRError::ModelLoadFailed {
-                    message: format!("Failed to write to file: {}", e),
+                let chunk = chunk.map_err(|e| ASRError::ModelLoadFailed {
+                    message: format!("Download error: {}", e),
                 })?;
 
-            // Update hash
-            hasher.update(&chunk);
+                // Write chunk to file
+                use tokio::io::AsyncWriteExt;
+                file.write_all(&chunk)
+                    .await
+                    .map_err(|e| ASRError::ModelLoadFailed {
+                        message: format!("Failed to write to file: {}", e),
+                    })?;
 
-            // Update progress
-            downloaded += chunk.len() as u64;
-            if let Some(ref callback) = progress_callback {
-                callback(downloaded, total_size);
+                // Update hash
+                hasher.update(&chunk);
+
+                // Update progress
+                downloaded += chunk.len() as u64;
+                if let Some(ref callback) = progress_callback {
+                    callback(downloaded, total_size.max(downloaded));
+                }
             }
         }
 
@@ -433,9 +702,30 @@ impl ModelManager {
 
         drop(file);
 
-        // Verify integrity (skip for now since we don't have real checksums)
+        // A download received in one piece was hashed as it arrived; a resumed one is hashed whole
+        let sha256 = if offset == 0 {
+            hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        } else {
+            let hash_path = temp_path.clone();
+            tokio::task::spawn_blocking(move || crate::storage::backup::checksum_file(&hash_path))
+                .await
+                .map_err(|e| ASRError::ModelLoadFailed { message: format!("Checksum task failed: {}", e) })?
+                .map_err(|e| ASRError::ModelLoadFailed { message: format!("Failed to read downloaded file: {}", e) })?
+                .1
+        };
         tracing::info!("Download completed: {} bytes", downloaded);
 
+        if let Some(expected) = &expected_sha256 {
+            if !expected.eq_ignore_ascii_case(&sha256) {
+                if let Err(e) = fs::remove_file(&temp_path).await {
+                    tracing::warn!("Failed to remove corrupt download {:?}: {}", temp_path, e);
+                }
+                return Err(ASRError::ModelLoadFailed {
+                    message: format!("Checksum mismatch for {}: expected {}, got {}; the download was discarded", metadata.name, expected, sha256),
+                });
+            }
+        }
+
         // Move temporary file to final location
         tokio::fs::rename(&temp_path, &model_path)
             .await
@@ -447,10 +737,11 @@ impl ModelManager {
         let cache_meta = CacheMetadata {
             download_timestamp: Utc::now(),
             file_size: downloaded,
-            sha256_verified: false, // TODO: Implement actual checksum verification
+            sha256_verified: expected_sha256.is_some(),
             model_tier: tier,
             last_validation: Some(Utc::now()),
             validation_status: ValidationStatus::Valid,
+            sha256: Some(sha256),
         };
         
         self.cache_metadata.insert(tier, cache_meta);
@@ -655,6 +946,180 @@ impl ModelManager {
         Ok(())
     }
     
+    /// Tiers whose registry entry points at the same file as `tier`, including `tier`
+    pub fn tiers_sharing_file(&self, tier: ModelTier) -> Vec<ModelTier> {
+        let Some(name) = self.model_registry.get(&tier).map(|m| &m.name) else {
+            return Vec::new();
+        };
+        ALL_TIERS.into_iter()
+            .filter(|t| self.model_registry.get(t).is_some_and(|m| &m.name == name))
+            .collect()
+    }
+
+    /// Model files present in the models directory, one entry per tier
+    pub async fn list_downloaded_models(&self) -> Vec<DownloadedModel> {
+        let mut models = Vec::new();
+        for tier in ALL_TIERS {
+            let Some(metadata) = self.model_registry.get(&tier) else {
+                continue;
+            };
+            let path = self.models_dir.join(&metadata.name);
+            let Ok(file_metadata) = fs::metadata(&path).await else {
+                continue;
+            };
+            let cache = self.cache_metadata.get(&tier);
+            models.push(DownloadedModel {
+                tier,
+                quality_tier: tier.quality_tier().to_string(),
+                file_name: metadata.name.clone(),
+                file_path: path.to_string_lossy().to_string(),
+                size_bytes: file_metadata.len(),
+                checksum_verified: cache.is_some_and(|c| c.sha256_verified),
+                last_validation: cache.and_then(|c| c.last_validation),
+                shared_with: self.tiers_sharing_file(tier).into_iter().filter(|&t| t != tier).collect(),
+            });
+        }
+        models
+    }
+
+    /// Bytes to download before `tier` can be used, following ensure_model_available:
+    /// none when it is cached or another model can stand in, else what a
+    /// partial download still lacks
+    pub async fn pending_download_bytes(&self, tier: ModelTier) -> u64 {
+        let Some(metadata) = self.model_registry.get(&tier) else {
+            return 0;
+        };
+        match self.get_cache_status(tier).await {
+            CacheStatus::Cached { .. } => return 0,
+            CacheStatus::NotCached if self.find_available_model_fallback().await.is_some() => return 0,
+            _ => {}
+        }
+        (metadata.size_mb * 1024 * 1024).saturating_sub(self.partial_download_bytes(metadata).await)
+    }
+
+    /// Bytes of an interrupted download of `metadata`'s file
+    async fn partial_download_bytes(&self, metadata: &ModelMetadata) -> u64 {
+        let temp_path = self.models_dir.join(&metadata.name).with_extension("tmp");
+        fs::metadata(&temp_path).await.map(|m| m.len()).unwrap_or(0)
+    }
+
+    /// Download size, installed size and recommended memory of every tier
+    pub async fn model_requirements(&self) -> Vec<ModelRequirements> {
+        let mut requirements = Vec::new();
+        for tier in ALL_TIERS {
+            let Some(metadata) = self.model_registry.get(&tier) else {
+                continue;
+            };
+            let size_bytes = metadata.size_mb * 1024 * 1024;
+            let downloaded = self.is_model_available(tier).await;
+            let partial = self.partial_download_bytes(metadata).await;
+            requirements.push(ModelRequirements {
+                tier,
+                quality_tier: tier.quality_tier().to_string(),
+                file_name: metadata.name.clone(),
+                download_bytes: size_bytes,
+                on_disk_bytes: size_bytes,
+                remaining_download_bytes: if downloaded { 0 } else { size_bytes.saturating_sub(partial) },
+                recommended_ram_gb: tier.recommended_ram_gb(),
+                downloaded,
+            });
+        }
+        requirements
+    }
+
+    /// Re-hash a model file and compare it with the registry checksum or, when
+    /// none is pinned, the hash recorded after download (the published one when
+    /// the host provided it). A file without a reference is checked by size and
+    /// its hash becomes the reference.
+    pub async fn verify_model(&mut self, tier: ModelTier) -> Result<ModelVerification, ASRError> {
+        let metadata = self.model_registry.get(&tier).cloned()
+            .ok_or_else(|| ASRError::ModelLoadFailed {
+                message: format!("Unknown model tier: {:?}", tier),
+            })?;
+        let path = self.models_dir.join(&metadata.name);
+        if !path.exists() {
+            return Err(ASRError::ModelNotFound { path: path.to_string_lossy().to_string() });
+        }
+
+        let hash_path = path.clone();
+        let (size_bytes, sha256) = tokio::task::spawn_blocking(move || crate::storage::backup::checksum_file(&hash_path))
+            .await
+            .map_err(|e| ASRError::ModelLoadFailed { message: format!("Checksum task failed: {}", e) })?
+            .map_err(|e| ASRError::ModelLoadFailed { message: format!("Failed to read model file: {}", e) })?;
+
+        let expected = Some(metadata.sha256.clone())
+            .filter(|sha| !sha.is_empty())
+            .or_else(|| self.cache_metadata.get(&tier).and_then(|c| c.sha256.clone()));
+        let checksum_matched = expected.as_ref().map(|expected| expected.eq_ignore_ascii_case(&sha256));
+        let size_problem = self.verify_model_integrity(&path, &metadata).await.err().map(|e| e.to_string());
+
+        let corruption = match (checksum_matched, size_problem) {
+            (Some(false), _) => Some(format!("Checksum mismatch for {}: expected {}, got {}", metadata.name, expected.unwrap_or_default(), sha256)),
+            (_, Some(problem)) => Some(problem),
+            _ => None,
+        };
+        let valid = corruption.is_none();
+
+        let now = Utc::now();
+        for shared_tier in self.tiers_sharing_file(tier) {
+            let entry = self.cache_metadata.entry(shared_tier).or_insert_with(|| CacheMetadata {
+                download_timestamp: DateTime::from_timestamp(0, 0).unwrap_or(now),
+                file_size: size_bytes,
+                sha256_verified: false,
+                model_tier: shared_tier,
+                last_validation: None,
+                validation_status: ValidationStatus::NotValidated,
+                sha256: None,
+            });
+            entry.file_size = size_bytes;
+            entry.sha256_verified = checksum_matched == Some(true);
+            entry.last_validation = Some(now);
+            entry.validation_status = match &corruption {
+                Some(reason) => ValidationStatus::Invalid { reason: reason.clone() },
+                None => ValidationStatus::Valid,
+            };
+            if valid && entry.sha256.is_none() {
+                entry.sha256 = Some(sha256.clone());
+            }
+        }
+        self.save_cache_metadata().await.map_err(|e| ASRError::ModelLoadFailed {
+            message: format!("Failed to save cache metadata: {}", e),
+        })?;
+
+        Ok(ModelVerification {
+            tier,
+            file_path: path.to_string_lossy().to_string(),
+            size_bytes,
+            sha256,
+            checksum_matched,
+            valid,
+            corruption,
+        })
+    }
+
+    /// Delete the file of `tier` and any partial download of it. Tiers sharing
+    /// the file lose their cache entry too. Returns the bytes freed.
+    pub async fn delete_model(&mut self, tier: ModelTier) -> Result<u64> {
+        let metadata = self.model_registry.get(&tier)
+            .ok_or_else(|| anyhow::anyhow!("Unknown model tier: {:?}", tier))?;
+        let model_path = self.models_dir.join(&metadata.name);
+
+        let mut freed = 0u64;
+        for path in [model_path.with_extension("tmp"), model_path] {
+            if let Ok(file_metadata) = fs::metadata(&path).await {
+                fs::remove_file(&path).await?;
+                freed += file_metadata.len();
+                tracing::info!("Removed model file: {:?}", path);
+            }
+        }
+
+        for shared_tier in self.tiers_sharing_file(tier) {
+            self.cache_metadata.remove(&shared_tier);
+        }
+        self.save_cache_metadata().await?;
+        Ok(freed)
+    }
+    
     /// Find any available model as fallback
     async fn find_available_model_fallback(&self) -> Option<ModelTier> {
         // Check available models in order of preference: Standard -> HighAccuracy -> Turbo
@@ -701,6 +1166,30 @@ impl ModelTier {
             ModelTier::Turbo => "Turbo",
         }
     }
+
+    /// The `qualityTier` name used in transcription configs
+    pub fn quality_tier(&self) -> &'static str {
+        match self {
+            ModelTier::Standard => "standard",
+            ModelTier::HighAccuracy => "high-accuracy",
+            ModelTier::Turbo => "turbo",
+        }
+    }
+
+    /// Memory recommended to run the tier alongside a meeting, matching the
+    /// tier recommendation of get_system_info
+    pub fn recommended_ram_gb(&self) -> f32 {
+        match self {
+            ModelTier::Standard => 8.0,
+            ModelTier::HighAccuracy => 16.0,
+            ModelTier::Turbo => 4.0,
+        }
+    }
+
+    /// Parse a `qualityTier` name
+    pub fn from_quality_tier(name: &str) -> Option<Self> {
+        ALL_TIERS.into_iter().find(|tier| tier.quality_tier() == name.trim())
+    }
 }
 
 /// Default implementation for ModelManager
@@ -708,4 +1197,176 @@ impl Default for ModelManager {
     fn default() -> Self {
         Self::new().expect("Failed to create ModelManager")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_space_shortfall_reports_exact_numbers() {
+        let gb = 1024 * 1024 * 1024;
+        assert_eq!(disk_space_shortfall(gb, 10 * gb), None);
+        let message = disk_space_shortfall(gb + gb / 2 - DOWNLOAD_SPACE_MARGIN_BYTES + gb / 10, gb - gb / 10).unwrap();
+        assert_eq!(message, "need 1.6 GB, have 0.9 GB free");
+    }
+
+    #[tokio::test]
+    async fn test_partial_download_reduces_pending_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = ModelManager::with_directory(dir.path().to_path_buf()).unwrap();
+        let metadata = manager.get_model_metadata(ModelTier::HighAccuracy).unwrap();
+        let full = metadata.size_mb * 1024 * 1024;
+        assert_eq!(manager.pending_download_bytes(ModelTier::HighAccuracy).await, full);
+
+        let partial = std::fs::File::create(dir.path().join(&metadata.name).with_extension("tmp")).unwrap();
+        partial.set_len(1024).unwrap();
+        assert_eq!(manager.pending_download_bytes(ModelTier::HighAccuracy).await, full - 1024);
+
+        let requirements = manager.model_requirements().await;
+        assert_eq!(requirements.len(), ALL_TIERS.len());
+        assert!(requirements.iter().all(|r| !r.downloaded && r.on_disk_bytes == r.download_bytes));
+    }
+
+    #[test]
+    fn test_partial_content_appends_to_existing_file() {
+        assert_eq!(
+            resume_decision(1000, 206, Some(4000), Some("bytes 1000-4999/5000")),
+            ResumeDecision::Append { offset: 1000, total: Some(5000) }
+        );
+        // Unknown total falls back to the remaining length
+        assert_eq!(
+            resume_decision(1000, 206, Some(4000), Some("bytes 1000-4999/*")),
+            ResumeDecision::Append { offset: 1000, total: Some(5000) }
+        );
+    }
+
+    #[test]
+    fn test_ignored_or_mismatched_range_restarts() {
+        assert_eq!(resume_decision(1000, 200, Some(5000), None), ResumeDecision::Restart);
+        assert_eq!(resume_decision(1000, 206, Some(5000), Some("bytes 0-4999/5000")), ResumeDecision::Restart);
+        assert_eq!(resume_decision(1000, 206, None, Some("garbage")), ResumeDecision::Restart);
+        assert_eq!(resume_decision(1000, 416, None, Some("bytes */9000")), ResumeDecision::Restart);
+    }
+
+    #[test]
+    fn test_unsatisfiable_range_on_full_file_is_complete() {
+        assert_eq!(
+            resume_decision(5000, 416, None, Some("bytes */5000")),
+            ResumeDecision::Complete { total: 5000 }
+        );
+    }
+
+    fn manager_with_files(files: &[(&str, &[u8])]) -> (tempfile::TempDir, ModelManager) {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (name, content) in files {
+            std::fs::write(dir.path().join(name), content).unwrap();
+        }
+        let manager = ModelManager::with_directory(dir.path().to_path_buf()).unwrap();
+        (dir, manager)
+    }
+
+    #[tokio::test]
+    async fn test_list_reports_files_on_disk_and_shared_tiers() {
+        let (_dir, manager) = manager_with_files(&[("ggml-medium.bin", b"medium weights")]);
+        let models = manager.list_downloaded_models().await;
+
+        // Standard and Turbo are served by the same file; High Accuracy is not downloaded
+        assert_eq!(models.iter().map(|m| m.tier).collect::<Vec<_>>(), vec![ModelTier::Standard, ModelTier::Turbo]);
+        assert_eq!(models[0].size_bytes, 14);
+        assert_eq!(models[0].quality_tier, "standard");
+        assert_eq!(models[0].shared_with, vec![ModelTier::Turbo]);
+        assert!(!models[0].checksum_verified);
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_changed_file() {
+        let (dir, mut manager) = manager_with_files(&[("ggml-medium-q5_0.bin", b"original")]);
+        let (_, reference) = crate::storage::backup::checksum_file(&dir.path().join("ggml-medium-q5_0.bin")).unwrap();
+        manager.cache_metadata.insert(ModelTier::HighAccuracy, CacheMetadata {
+            download_timestamp: Utc::now(),
+            file_size: 8,
+            sha256_verified: false,
+            model_tier: ModelTier::HighAccuracy,
+            last_validation: None,
+            validation_status: ValidationStatus::NotValidated,
+            sha256: Some(reference),
+        });
+
+        let verification = manager.verify_model(ModelTier::HighAccuracy).await.unwrap();
+        assert_eq!(verification.checksum_matched, Some(true));
+        // A few bytes are far from the expected ~514MB
+        assert!(!verification.valid);
+        assert!(verification.corruption.unwrap().contains("size mismatch"));
+
+        std::fs::write(dir.path().join("ggml-medium-q5_0.bin"), b"tampered").unwrap();
+        let verification = manager.verify_model(ModelTier::HighAccuracy).await.unwrap();
+        assert_eq!(verification.checksum_matched, Some(false));
+        assert!(verification.corruption.unwrap().starts_with("Checksum mismatch"));
+        assert!(!manager.get_cache_metadata(ModelTier::HighAccuracy).unwrap().sha256_verified);
+
+        assert!(matches!(manager.verify_model(ModelTier::Standard).await, Err(ASRError::ModelNotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_delete_removes_shared_file_and_partial_download() {
+        let (dir, mut manager) = manager_with_files(&[("ggml-medium.bin", b"0123456789"), ("ggml-medium.tmp", b"01234")]);
+        let freed = manager.delete_model(ModelTier::Turbo).await.unwrap();
+
+        assert_eq!(freed, 15);
+        assert!(!dir.path().join("ggml-medium.bin").exists());
+        assert!(!dir.path().join("ggml-medium.tmp").exists());
+        assert!(manager.list_downloaded_models().await.is_empty());
+        assert_eq!(manager.delete_model(ModelTier::Standard).await.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_published_checksum_is_read_from_the_linked_etag() {
+        let sha = "0123456789ABCDEF".repeat(4);
+        let mut headers = reqwest::header::HeaderMap::new();
+        // The plain ETag of a git blob is not a SHA-256
+        headers.insert("etag", "\"0123456789abcdef0123456789abcdef01234567\"".parse().unwrap());
+        assert_eq!(published_sha256(&headers), None);
+
+        headers.insert("x-linked-etag", format!("\"{}\"", sha).parse().unwrap());
+        assert_eq!(published_sha256(&headers), Some(sha.to_ascii_lowercase()));
+    }
+
+    #[test]
+    fn test_quality_tier_names_round_trip() {
+        for tier in ALL_TIERS {
+            assert_eq!(ModelTier::from_quality_tier(tier.quality_tier()), Some(tier));
+        }
+        assert_eq!(ModelTier::from_quality_tier("ultra"), None);
+    }
+
+    #[test]
+    fn test_cancellation_is_shared_between_clones() {
+        let cancellation = DownloadCancellation::new();
+        let observer = cancellation.clone();
+        assert!(!observer.is_cancelled());
+        cancellation.cancel();
+        assert!(observer.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_interrupts_a_stalled_stream() {
+        let cancellation = DownloadCancellation::new();
+        let observer = cancellation.clone();
+        let download = tokio::spawn(async move {
+            // A connection that never delivers another chunk
+            let mut stream = futures_util::stream::pending::<Result<Vec<u8>, ()>>();
+            tokio::select! {
+                biased;
+                _ = wait_cancelled(Some(&observer)) => None,
+                chunk = stream.next() => Some(chunk),
+            }
+        });
+        tokio::task::yield_now().await;
+        cancellation.cancel();
+        assert_eq!(download.await.unwrap(), None);
+
+        // Cancelled before anyone waits
+        cancellation.cancelled().await;
+    }
+}
@@ -0,0 +1,248 @@
+//! Models Directory Location
+//!
+//! Whisper models live in the app data directory unless the user points the
+//! app at another one (e.g. an external drive when the system drive is full).
+//! The choice is kept in a small settings file; every ModelManager reads it.
+//! A configured directory that is missing at startup (drive unplugged) is
+//! skipped in favour of the default until it comes back. Moving to another
+//! directory only takes the model files themselves and never replaces a file
+//! already at the target.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Free space to leave on the target beyond the model files being moved
+pub const MIN_FREE_MARGIN_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Persisted models directory setting
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelsLocation {
+    /// `None` keeps models in the default directory
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+}
+
+/// Models directory in effect
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedModelsDirectory {
+    pub directory: PathBuf,
+    pub configured: Option<PathBuf>,
+    /// The configured directory is unavailable and the default is used instead
+    pub fell_back: bool,
+}
+
+/// Settings file holding the models directory
+pub fn settings_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("KagiNote").join("models_location.json"))
+}
+
+pub fn default_models_directory() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("KagiNote").join("models"))
+}
+
+/// Load the setting; a missing or unreadable file means the default directory
+pub fn load(path: &Path) -> ModelsLocation {
+    std::fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(path: &Path, location: &ModelsLocation) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(location)?)?;
+    Ok(())
+}
+
+/// The configured directory when it exists, otherwise `default`
+pub fn resolve(location: &ModelsLocation, default: PathBuf) -> ResolvedModelsDirectory {
+    match &location.directory {
+        Some(configured) if configured.is_dir() => ResolvedModelsDirectory {
+            directory: configured.clone(),
+            configured: Some(configured.clone()),
+            fell_back: false,
+        },
+        configured => ResolvedModelsDirectory {
+            directory: default,
+            configured: configured.clone(),
+            fell_back: configured.is_some(),
+        },
+    }
+}
+
+/// Models directory in effect for this installation
+pub fn current() -> Result<ResolvedModelsDirectory> {
+    let default = default_models_directory()
+        .ok_or_else(|| anyhow::anyhow!("Failed to get data directory"))?;
+    let location = settings_path().map(|path| load(&path)).unwrap_or_default();
+    Ok(resolve(&location, default))
+}
+
+/// Create `directory` if needed and check that files can be written to it
+pub fn ensure_writable(directory: &Path) -> Result<()> {
+    std::fs::create_dir_all(directory)?;
+    let probe = directory.join(".kaginote_write_test");
+    std::fs::write(&probe, b"ok")?;
+    std::fs::remove_file(&probe)?;
+    Ok(())
+}
+
+/// Whether `name` is a Whisper model (`ggml-*.bin`) or its partial download
+/// (`ggml-*.tmp`); anything else in the directory is left alone
+pub fn is_model_file_name(name: &str) -> bool {
+    name.starts_with("ggml-") && (name.ends_with(".bin") || name.ends_with(".tmp"))
+}
+
+/// Model files directly inside `directory` with their sizes
+pub fn model_files(directory: &Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_str().is_some_and(is_model_file_name))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| (entry.path(), metadata.len()))
+        })
+        .collect()
+}
+
+/// Outcome of moving model files to a new directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelsMigration {
+    #[serde(rename = "movedFiles")]
+    pub moved_files: Vec<String>,
+    /// Files left behind; the models are downloaded again when next needed
+    #[serde(rename = "redownloadFiles")]
+    pub redownload_files: Vec<String>,
+    /// Files left in the old directory because the target already has one of that name
+    #[serde(rename = "existingFiles", default)]
+    pub existing_files: Vec<String>,
+    #[serde(rename = "movedBytes")]
+    pub moved_bytes: u64,
+}
+
+/// Move `from` to `to`, failing with `AlreadyExists` rather than replacing `to`
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    // A hard link is created only when `to` does not exist yet, unlike rename
+    match std::fs::hard_link(from, to) {
+        Ok(()) => return std::fs::remove_file(from),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return Err(e),
+        Err(_) => {}
+    }
+    // Across file systems: copy into a new file, then remove the original
+    let copied = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)
+        .and_then(|mut target| std::io::copy(&mut std::fs::File::open(from)?, &mut target));
+    if let Err(e) = copied {
+        if e.kind() != std::io::ErrorKind::AlreadyExists {
+            let _ = std::fs::remove_file(to);
+        }
+        return Err(e);
+    }
+    std::fs::remove_file(from)
+}
+
+/// Move the model files of `from` into `to`. A file that cannot be moved is
+/// left where it was and reported for re-download; one whose name is already
+/// taken in `to` is left where it was as well.
+pub fn migrate(from: &Path, to: &Path) -> ModelsMigration {
+    let mut migration = ModelsMigration::default();
+    if from == to {
+        return migration;
+    }
+    for (path, size) in model_files(from) {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let display_name = name.to_string_lossy().to_string();
+        match move_file(&path, &to.join(name)) {
+            Ok(()) => {
+                migration.moved_files.push(display_name);
+                migration.moved_bytes += size;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                tracing::warn!("Not replacing {:?}, which already exists in {:?}", name, to);
+                migration.existing_files.push(display_name);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to move model file {:?}: {}", path, e);
+                migration.redownload_files.push(display_name);
+            }
+        }
+    }
+    migration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_directory_falls_back_to_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let default = dir.path().join("default");
+        let unplugged = ModelsLocation { directory: Some(dir.path().join("external")) };
+        let resolved = resolve(&unplugged, default.clone());
+        assert_eq!(resolved.directory, default);
+        assert!(resolved.fell_back);
+
+        let present = ModelsLocation { directory: Some(dir.path().to_path_buf()) };
+        let resolved = resolve(&present, default.clone());
+        assert_eq!(resolved.directory, dir.path());
+        assert!(!resolved.fell_back);
+        assert!(!resolve(&ModelsLocation::default(), default).fell_back);
+    }
+
+    #[test]
+    fn test_setting_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("models_location.json");
+        assert_eq!(load(&path), ModelsLocation::default());
+        let location = ModelsLocation { directory: Some(PathBuf::from("/Volumes/SSD/models")) };
+        save(&path, &location).unwrap();
+        assert_eq!(load(&path), location);
+    }
+
+    #[test]
+    fn test_model_files_are_moved() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("old");
+        let to = dir.path().join("new");
+        std::fs::create_dir_all(&from).unwrap();
+        std::fs::write(from.join("ggml-medium.bin"), vec![0u8; 64]).unwrap();
+        std::fs::write(from.join("ggml-medium-q5_0.tmp"), vec![0u8; 2]).unwrap();
+        std::fs::write(from.join("notes.txt"), b"not a model").unwrap();
+        ensure_writable(&to).unwrap();
+
+        let migration = migrate(&from, &to);
+        assert_eq!(migration.moved_files.len(), 2);
+        assert_eq!(migration.moved_bytes, 66);
+        assert!(migration.redownload_files.is_empty());
+        assert!(to.join("ggml-medium.bin").exists() && to.join("ggml-medium-q5_0.tmp").exists());
+        assert!(model_files(&from).is_empty());
+        // Other files in the old directory are not the app's to move
+        assert!(from.join("notes.txt").exists() && !to.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_existing_files_in_the_target_are_not_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("old");
+        std::fs::create_dir_all(&from).unwrap();
+        std::fs::write(from.join("ggml-medium.bin"), b"old copy").unwrap();
+        std::fs::write(dir.path().join("ggml-medium.bin"), b"already here").unwrap();
+
+        let migration = migrate(&from, dir.path());
+        assert_eq!(migration.existing_files, vec!["ggml-medium.bin".to_string()]);
+        assert!(migration.moved_files.is_empty());
+        assert_eq!(std::fs::read(dir.path().join("ggml-medium.bin")).unwrap(), b"already here");
+        assert_eq!(std::fs::read(from.join("ggml-medium.bin")).unwrap(), b"old copy");
+    }
+}
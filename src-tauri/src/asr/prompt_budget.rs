@@ -0,0 +1,263 @@
+//! Prompt context budgeting
+//!
+//! Whisper conditions decoding on a text prompt, but only part of the text
+//! context window may be used for it; whisper.cpp silently drops prompt tokens
+//! beyond that. The prompt is therefore assembled explicitly within a budget:
+//! the oldest rolling context goes first, then custom vocabulary (last terms
+//! first), and the initial prompt only as a last resort. The tokens reserved for
+//! decoding the current chunk are never given up.
+
+use serde::{Deserialize, Serialize};
+
+/// Text context of the Whisper models (tokens)
+pub const DEFAULT_TEXT_CONTEXT: usize = 448;
+
+/// Part of the assembled prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PromptComponent {
+    InitialPrompt,
+    Vocabulary,
+    RollingContext,
+}
+
+/// Prompt sources before budgeting
+#[derive(Debug, Clone, Default)]
+pub struct PromptInputs {
+    pub initial_prompt: Option<String>,
+    pub vocabulary: Vec<String>,
+    /// Previous segment texts, oldest first
+    pub rolling_context: Vec<String>,
+}
+
+/// Tokens spent on one component of the final prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentBudget {
+    pub component: PromptComponent,
+    pub tokens: usize,
+    #[serde(rename = "keptItems")]
+    pub kept_items: usize,
+    #[serde(rename = "droppedItems")]
+    pub dropped_items: usize,
+}
+
+/// Something left out of the prompt to stay within the budget. Only its size
+/// is kept: the text is transcript or vocabulary content and ends up in
+/// stored segments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptTruncation {
+    pub component: PromptComponent,
+    pub tokens: usize,
+}
+
+/// Budget breakdown of the last assembled prompt, for diagnostics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PromptBudget {
+    #[serde(rename = "contextTokens")]
+    pub context_tokens: usize,
+    /// Kept free for decoding the current chunk
+    #[serde(rename = "reservedTokens")]
+    pub reserved_tokens: usize,
+    #[serde(rename = "availableTokens")]
+    pub available_tokens: usize,
+    #[serde(rename = "usedTokens")]
+    pub used_tokens: usize,
+    pub components: Vec<ComponentBudget>,
+    /// In the order they were applied
+    pub truncations: Vec<PromptTruncation>,
+}
+
+/// Rough token count for when no tokenizer is available: about four ASCII
+/// characters per token, one token per character for other scripts
+pub fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.chars().filter(|c| c.is_ascii()).count();
+    let other = text.chars().count() - ascii;
+    ascii.div_ceil(4) + other
+}
+
+/// Tokens reserved for decoding: whisper.cpp allows at most half the text context for the prompt
+pub fn reserved_tokens(context_tokens: usize) -> usize {
+    context_tokens - context_tokens / 2
+}
+
+/// Assemble the prompt within `context_tokens`, counting with `count_tokens`
+/// (normally the model tokenizer). Truncation is deterministic for equal inputs.
+pub fn build_prompt(
+    inputs: &PromptInputs,
+    context_tokens: usize,
+    count_tokens: impl Fn(&str) -> usize,
+) -> (String, PromptBudget) {
+    let reserved = reserved_tokens(context_tokens);
+    let available = context_tokens - reserved;
+
+    let mut initial_prompt = inputs.initial_prompt.clone().filter(|p| !p.trim().is_empty());
+    let mut vocabulary: Vec<String> = inputs.vocabulary.iter().filter(|v| !v.trim().is_empty()).cloned().collect();
+    let mut context: Vec<String> = inputs.rolling_context.iter().filter(|c| !c.trim().is_empty()).cloned().collect();
+    let mut truncations = Vec::new();
+
+    loop {
+        let prompt = assemble(initial_prompt.as_deref(), &vocabulary, &context);
+        let used = if prompt.is_empty() { 0 } else { count_tokens(&prompt) };
+        if used <= available {
+            let budget = PromptBudget {
+                context_tokens,
+                reserved_tokens: reserved,
+                available_tokens: available,
+                used_tokens: used,
+                components: component_budgets(inputs, initial_prompt.as_deref(), &vocabulary, &context, &truncations, &count_tokens),
+                truncations,
+            };
+            return (prompt, budget);
+        }
+
+        // Lowest priority first: oldest context, then the last vocabulary terms, then the initial prompt
+        let (component, text) = if !context.is_empty() {
+            (PromptComponent::RollingContext, context.remove(0))
+        } else if let Some(term) = vocabulary.pop() {
+            (PromptComponent::Vocabulary, term)
+        } else if let Some(prompt) = initial_prompt.take() {
+            (PromptComponent::InitialPrompt, prompt)
+        } else {
+            unreachable!("an empty prompt always fits");
+        };
+        tracing::debug!("Prompt over budget ({} > {} tokens), dropping {:?}", used, available, component);
+        truncations.push(PromptTruncation { component, tokens: count_tokens(&text) });
+    }
+}
+
+fn assemble(initial_prompt: Option<&str>, vocabulary: &[String], context: &[String]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(prompt) = initial_prompt {
+        parts.push(prompt.trim().to_string());
+    }
+    if !vocabulary.is_empty() {
+        parts.push(format!("{}.", vocabulary.join(", ")));
+    }
+    // Most recent context last, right before the audio
+    parts.extend(context.iter().map(|c| c.trim().to_string()));
+    parts.join(" ")
+}
+
+fn component_budgets(
+    inputs: &PromptInputs,
+    initial_prompt: Option<&str>,
+    vocabulary: &[String],
+    context: &[String],
+    truncations: &[PromptTruncation],
+    count_tokens: &impl Fn(&str) -> usize,
+) -> Vec<ComponentBudget> {
+    let dropped = |component: PromptComponent| truncations.iter().filter(|t| t.component == component).count();
+    let mut components = Vec::new();
+    if inputs.initial_prompt.is_some() {
+        components.push(ComponentBudget {
+            component: PromptComponent::InitialPrompt,
+            tokens: initial_prompt.map(count_tokens).unwrap_or(0),
+            kept_items: initial_prompt.is_some() as usize,
+            dropped_items: dropped(PromptComponent::InitialPrompt),
+        });
+    }
+    if !inputs.vocabulary.is_empty() {
+        components.push(ComponentBudget {
+            component: PromptComponent::Vocabulary,
+            tokens: if vocabulary.is_empty() { 0 } else { count_tokens(&assemble(None, vocabulary, &[])) },
+            kept_items: vocabulary.len(),
+            dropped_items: dropped(PromptComponent::Vocabulary),
+        });
+    }
+    if !inputs.rolling_context.is_empty() {
+        components.push(ComponentBudget {
+            component: PromptComponent::RollingContext,
+            tokens: if context.is_empty() { 0 } else { count_tokens(&assemble(None, &[], context)) },
+            kept_items: context.len(),
+            dropped_items: dropped(PromptComponent::RollingContext),
+        });
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One token per whitespace-separated word
+    fn words(text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+
+    fn oversized_inputs() -> PromptInputs {
+        PromptInputs {
+            initial_prompt: Some("Quarterly planning meeting".to_string()),
+            vocabulary: (0..60).map(|i| format!("term{}", i)).collect(),
+            rolling_context: (0..10).map(|i| format!("segment {} {}", i, "word ".repeat(20))).collect(),
+        }
+    }
+
+    #[test]
+    fn test_fitting_prompt_is_untouched() {
+        let inputs = PromptInputs {
+            initial_prompt: Some("Standup".to_string()),
+            vocabulary: vec!["KagiNote".to_string(), "Tauri".to_string()],
+            rolling_context: vec!["first".to_string(), "second".to_string()],
+        };
+        let (prompt, budget) = build_prompt(&inputs, DEFAULT_TEXT_CONTEXT, words);
+
+        assert_eq!(prompt, "Standup KagiNote, Tauri. first second");
+        assert!(budget.truncations.is_empty());
+        assert_eq!(budget.available_tokens, 224);
+        assert_eq!(budget.reserved_tokens, 224);
+        assert_eq!(budget.used_tokens, 5);
+    }
+
+    #[test]
+    fn test_truncation_order_is_context_then_vocabulary() {
+        // 48 tokens available: all context must go, then 15 of the 60 vocabulary terms
+        let (prompt, budget) = build_prompt(&oversized_inputs(), 96, words);
+
+        assert!(budget.used_tokens <= budget.available_tokens);
+        let order: Vec<PromptComponent> = budget.truncations.iter().map(|t| t.component).collect();
+        let first_vocabulary = order.iter().position(|c| *c == PromptComponent::Vocabulary).unwrap();
+        assert_eq!(first_vocabulary, 10);
+        assert!(order[..10].iter().all(|c| *c == PromptComponent::RollingContext));
+        assert!(!order.contains(&PromptComponent::InitialPrompt));
+
+        // Last vocabulary terms first
+        assert_eq!(budget.truncations[10].tokens, 1);
+        assert!(prompt.starts_with("Quarterly planning meeting term0,"));
+        assert!(prompt.contains("term44.") && !prompt.contains("term45"));
+    }
+
+    #[test]
+    fn test_truncation_is_deterministic() {
+        let first = build_prompt(&oversized_inputs(), 96, words);
+        let second = build_prompt(&oversized_inputs(), 96, words);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_budget_breakdown_and_reserve() {
+        let (_, budget) = build_prompt(&oversized_inputs(), 200, estimate_tokens);
+
+        assert_eq!(budget.reserved_tokens + budget.available_tokens, 200);
+        let context = budget.components.iter().find(|c| c.component == PromptComponent::RollingContext).unwrap();
+        assert_eq!(context.kept_items + context.dropped_items, 10);
+        assert!(context.dropped_items > 0);
+        // The initial prompt alone exceeding the budget is dropped too
+        let tiny = PromptInputs { initial_prompt: Some("a b c d e".to_string()), ..Default::default() };
+        let (prompt, budget) = build_prompt(&tiny, 6, words);
+        assert!(prompt.is_empty());
+        assert_eq!(budget.truncations[0].component, PromptComponent::InitialPrompt);
+        assert_eq!(budget.truncations[0].tokens, 5);
+    }
+
+    #[test]
+    fn test_oldest_context_is_dropped_first() {
+        let inputs = PromptInputs {
+            rolling_context: vec!["oldest words here".to_string(), "middle".to_string(), "newest".to_string()],
+            ..Default::default()
+        };
+        let (prompt, budget) = build_prompt(&inputs, 4, words);
+
+        assert_eq!(prompt, "middle newest");
+        assert_eq!(budget.truncations, vec![PromptTruncation { component: PromptComponent::RollingContext, tokens: 3 }]);
+    }
+}
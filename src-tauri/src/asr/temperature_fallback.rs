@@ -0,0 +1,281 @@
+//! Temperature fallback for Whisper decoding
+//!
+//! Greedy decoding at temperature 0 occasionally gets stuck in repetition loops
+//! or produces very unlikely text. Like the reference Whisper implementation, a
+//! failed chunk is decoded again at increasing temperatures until the output
+//! passes the quality checks or the ladder (or the latency budget) runs out.
+
+use super::types::ASRResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Quality signals of a single decoding attempt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodeQuality {
+    /// Repetition ratio of the decoded text (see [`repetition_ratio`])
+    pub repetition_ratio: f32,
+    /// Average token log probability, when the decoder provides it
+    pub avg_logprob: Option<f32>,
+}
+
+impl DecodeQuality {
+    pub fn from_text(text: &str, avg_logprob: Option<f32>) -> Self {
+        Self {
+            repetition_ratio: repetition_ratio(text),
+            avg_logprob,
+        }
+    }
+}
+
+/// Highest temperature tried by default, as in the reference implementation
+pub const DEFAULT_MAX_TEMPERATURE: f32 = 1.0;
+
+/// Temperatures to try and the thresholds that trigger a retry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemperatureLadder {
+    pub temperatures: Vec<f32>,
+    /// Outputs more repetitive than this are treated as degenerate
+    pub repetition_threshold: f32,
+    /// Outputs with a lower average log probability are retried
+    pub logprob_threshold: f32,
+}
+
+impl Default for TemperatureLadder {
+    fn default() -> Self {
+        Self::new(0.0, DEFAULT_MAX_TEMPERATURE)
+    }
+}
+
+impl TemperatureLadder {
+    /// Steps of 0.2 from `base` up to `max_temperature` (inclusive)
+    pub fn new(base: f32, max_temperature: f32) -> Self {
+        let mut temperatures = vec![base];
+        let mut next = base + 0.2;
+        while next <= max_temperature + 1e-3 {
+            temperatures.push((next * 10.0).round() / 10.0);
+            next += 0.2;
+        }
+        Self {
+            temperatures,
+            repetition_threshold: 2.4,
+            logprob_threshold: -1.0,
+        }
+    }
+
+    pub fn needs_fallback(&self, quality: &DecodeQuality) -> bool {
+        quality.repetition_ratio > self.repetition_threshold
+            || quality.avg_logprob.map(|lp| lp < self.logprob_threshold).unwrap_or(false)
+    }
+}
+
+/// Accepted decoding result and how it was reached
+#[derive(Debug, Clone)]
+pub struct FallbackOutcome<T> {
+    pub result: T,
+    /// Temperature of the accepted attempt
+    pub temperature: f32,
+    pub attempts: u32,
+    /// The result passed the quality checks (otherwise the best effort is returned)
+    pub passed: bool,
+    /// Retries were skipped because the latency budget would be exceeded
+    pub budget_exhausted: bool,
+}
+
+/// Counters of fallback behaviour over a session's transcriptions, reported
+/// with its system status
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FallbackStats {
+    pub chunks: u64,
+    /// Chunks that needed at least one retry
+    #[serde(rename = "retriedChunks")]
+    pub retried_chunks: u64,
+    /// Chunks where no temperature produced acceptable output
+    #[serde(rename = "failedChunks")]
+    pub failed_chunks: u64,
+    /// Chunks whose retries were skipped to stay within the latency budget
+    #[serde(rename = "budgetSkips")]
+    pub budget_skips: u64,
+}
+
+impl FallbackStats {
+    /// Count the decode behind one transcription result
+    pub fn record(&mut self, result: &ASRResult) {
+        self.chunks += 1;
+        if result.decode_attempts > 1 {
+            self.retried_chunks += 1;
+        }
+        if result.decode_failed {
+            self.failed_chunks += 1;
+        }
+        if result.decode_budget_exhausted {
+            self.budget_skips += 1;
+        }
+    }
+}
+
+/// Decode with increasing temperatures until the output passes the quality checks.
+/// A retry is only started if it is expected to finish within `budget`.
+pub fn decode_with_fallback<T, E, F>(
+    ladder: &TemperatureLadder,
+    budget: Option<Duration>,
+    mut decode: F,
+) -> Result<FallbackOutcome<T>, E>
+where
+    F: FnMut(f32) -> Result<(T, DecodeQuality), E>,
+{
+    let started = Instant::now();
+    let temperatures = if ladder.temperatures.is_empty() { vec![0.0] } else { ladder.temperatures.clone() };
+    let mut attempts = 0;
+    let mut last_attempt = Duration::ZERO;
+    let mut best: Option<(T, f32, DecodeQuality)> = None;
+
+    for temperature in temperatures {
+        if attempts > 0 {
+            // Assume a retry costs about as much as the previous attempt
+            if let Some(budget) = budget {
+                if started.elapsed() + last_attempt > budget {
+                    let (result, temperature, _) = best.expect("at least one attempt was made");
+                    return Ok(FallbackOutcome { result, temperature, attempts, passed: false, budget_exhausted: true });
+                }
+            }
+            tracing::debug!("Retrying decode at temperature {:.1}", temperature);
+        }
+
+        let attempt_started = Instant::now();
+        let (result, quality) = decode(temperature)?;
+        last_attempt = attempt_started.elapsed();
+        attempts += 1;
+
+        if !ladder.needs_fallback(&quality) {
+            return Ok(FallbackOutcome { result, temperature, attempts, passed: true, budget_exhausted: false });
+        }
+
+        // Keep the least bad attempt in case every temperature fails
+        let better = best.as_ref().map(|(_, _, q)| is_better(&quality, q)).unwrap_or(true);
+        if better {
+            best = Some((result, temperature, quality));
+        }
+    }
+
+    let (result, temperature, _) = best.expect("at least one attempt was made");
+    Ok(FallbackOutcome { result, temperature, attempts, passed: false, budget_exhausted: false })
+}
+
+fn is_better(candidate: &DecodeQuality, current: &DecodeQuality) -> bool {
+    match (candidate.avg_logprob, current.avg_logprob) {
+        (Some(a), Some(b)) if (candidate.repetition_ratio - current.repetition_ratio).abs() < 0.1 => a > b,
+        _ => candidate.repetition_ratio < current.repetition_ratio,
+    }
+}
+
+/// Compression-ratio-like repetition measure: words per distinct word trigram.
+/// Natural text stays close to 1.0, looping output grows with every repeat.
+pub fn repetition_ratio(text: &str) -> f32 {
+    let words: Vec<String> = text.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if words.len() < 6 {
+        return 1.0;
+    }
+    let trigrams: HashSet<&[String]> = words.windows(3).collect();
+    words.len() as f32 / trigrams.len().max(1) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fake engine: loops at temperature 0, clean output from 0.4 on
+    fn fake_engine(calls: &mut Vec<f32>, temperature: f32) -> Result<(String, DecodeQuality), String> {
+        calls.push(temperature);
+        let text = if temperature < 0.4 {
+            "thank you thank you thank you thank you thank you thank you thank you thank you".to_string()
+        } else {
+            "the quarterly numbers look better than expected".to_string()
+        };
+        let quality = DecodeQuality::from_text(&text, Some(-0.3));
+        Ok((text, quality))
+    }
+
+    #[test]
+    fn test_repetition_ratio() {
+        assert!(repetition_ratio("the quarterly numbers look better than we expected") < 1.5);
+        assert!(repetition_ratio("go on go on go on go on go on go on go on go on go on") > 2.4);
+    }
+
+    #[test]
+    fn test_degenerate_output_retries_until_clean() {
+        let mut calls = Vec::new();
+        let outcome = decode_with_fallback(&TemperatureLadder::default(), None, |t| fake_engine(&mut calls, t)).unwrap();
+
+        assert_eq!(calls, vec![0.0, 0.2, 0.4]);
+        assert!(outcome.passed);
+        assert_eq!(outcome.temperature, 0.4);
+        assert_eq!(outcome.attempts, 3);
+        assert!(outcome.result.starts_with("the quarterly"));
+    }
+
+    #[test]
+    fn test_low_logprob_triggers_fallback() {
+        let ladder = TemperatureLadder::new(0.0, 0.4);
+        assert_eq!(ladder.temperatures, vec![0.0, 0.2, 0.4]);
+
+        let mut calls = 0;
+        let outcome = decode_with_fallback(&ladder, None, |t| {
+            calls += 1;
+            let logprob = if t > 0.0 { -0.4 } else { -1.8 };
+            Ok::<_, String>((t, DecodeQuality::from_text("a perfectly normal sentence", Some(logprob))))
+        }).unwrap();
+
+        assert_eq!(calls, 2);
+        assert_eq!(outcome.temperature, 0.2);
+    }
+
+    #[test]
+    fn test_retries_skipped_when_over_budget() {
+        let mut calls = Vec::new();
+        let outcome = decode_with_fallback(&TemperatureLadder::default(), Some(Duration::from_millis(5)), |t| {
+            std::thread::sleep(Duration::from_millis(10));
+            fake_engine(&mut calls, t)
+        }).unwrap();
+
+        assert_eq!(calls, vec![0.0]);
+        assert!(outcome.budget_exhausted);
+        assert!(!outcome.passed);
+        assert_eq!(outcome.temperature, 0.0);
+    }
+
+    #[test]
+    fn test_ceiling_limits_the_ladder() {
+        assert_eq!(TemperatureLadder::new(0.0, 0.0).temperatures, vec![0.0]);
+        let mut calls = Vec::new();
+        let outcome = decode_with_fallback(&TemperatureLadder::new(0.0, 0.2), None, |t| fake_engine(&mut calls, t)).unwrap();
+        assert_eq!(calls, vec![0.0, 0.2]);
+        assert!(!outcome.passed);
+    }
+
+    #[test]
+    fn test_stats_count_results() {
+        let result = |attempts, failed, budget_exhausted| ASRResult {
+            text: String::new(),
+            confidence: 0.0,
+            language: "en".to_string(),
+            language_confidence: 1.0,
+            words: Vec::new(),
+            word_timestamps: false,
+            estimated_snr: None,
+            speaker_consistency_score: None,
+            language_segments: None,
+            decode_temperature: 0.0,
+            decode_attempts: attempts,
+            decode_failed: failed,
+            decode_budget_exhausted: budget_exhausted,
+            prompt_budget: None,
+            device: None,
+        };
+        let mut stats = FallbackStats::default();
+        stats.record(&result(1, false, false));
+        stats.record(&result(3, false, false));
+        stats.record(&result(1, true, true));
+        assert_eq!(stats, FallbackStats { chunks: 3, retried_chunks: 1, failed_chunks: 1, budget_skips: 1 });
+    }
+}
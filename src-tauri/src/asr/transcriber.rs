@@ -0,0 +1,283 @@
+//! Speech-to-text behind a trait object
+//!
+//! The transcription loop only needs a transcript for buffered audio plus the
+//! tier and device to report alongside it. [`Transcriber`]
+//! captures exactly that, so the loop can run on a leased Whisper engine in
+//! the app and on a [`ScriptedTranscriber`] in tests, without a model download.
+
+use super::engine_pool::EngineLease;
+use super::types::{ASRError, ASRResult, Device, ModelTier, TranscriptionContext};
+use super::whisper::WhisperEngine;
+use crate::audio::types::AudioData;
+use futures_util::future::BoxFuture;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Transcriber shared between a session and its transcription loop
+pub type SharedTranscriber = Arc<dyn Transcriber>;
+
+/// What the transcription loop needs from a speech recognizer
+pub trait Transcriber: Send + Sync {
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a AudioData,
+        context: &'a TranscriptionContext,
+    ) -> BoxFuture<'a, Result<ASRResult, ASRError>>;
+
+    /// Model tier the transcripts come from, if the recognizer has one
+    fn model_tier(&self) -> Option<ModelTier>;
+
+    /// Device inference runs on
+    fn device(&self) -> Device;
+}
+
+impl fmt::Debug for dyn Transcriber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transcriber")
+            .field("tier", &self.model_tier())
+            .field("device", &self.device())
+            .finish()
+    }
+}
+
+impl Transcriber for WhisperEngine {
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a AudioData,
+        context: &'a TranscriptionContext,
+    ) -> BoxFuture<'a, Result<ASRResult, ASRError>> {
+        Box::pin(WhisperEngine::transcribe(self, audio, context))
+    }
+
+    fn model_tier(&self) -> Option<ModelTier> {
+        Some(self.get_config().model_tier)
+    }
+
+    fn device(&self) -> Device {
+        self.get_current_device()
+    }
+}
+
+/// A lease transcribes on its pooled engine, concurrently with the other leases
+impl<E: Transcriber> Transcriber for EngineLease<E> {
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a AudioData,
+        context: &'a TranscriptionContext,
+    ) -> BoxFuture<'a, Result<ASRResult, ASRError>> {
+        (**self).transcribe(audio, context)
+    }
+
+    fn model_tier(&self) -> Option<ModelTier> {
+        Some(self.tier())
+    }
+
+    fn device(&self) -> Device {
+        (**self).device()
+    }
+}
+
+/// One scripted transcription outcome
+#[derive(Debug, Clone)]
+pub enum ScriptedStep {
+    Text(String),
+    Result(Box<ASRResult>),
+    Fail(String),
+}
+
+/// Transcriber answering from a script, for tests of the transcription path
+///
+/// Each call takes the next step of the script; once it runs out, calls
+/// return an empty transcript, as Whisper does for silence. Every call waits
+/// `latency` first, and with `fail_every(n)` every n-th call fails whatever
+/// the script says.
+pub struct ScriptedTranscriber {
+    script: Mutex<VecDeque<ScriptedStep>>,
+    latency: Duration,
+    fail_every: Option<usize>,
+    tier: Option<ModelTier>,
+    calls: AtomicUsize,
+    transcribed_seconds: Mutex<Vec<f32>>,
+}
+
+impl Default for ScriptedTranscriber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptedTranscriber {
+    pub fn new() -> Self {
+        Self {
+            script: Mutex::new(VecDeque::new()),
+            latency: Duration::ZERO,
+            fail_every: None,
+            tier: Some(ModelTier::Standard),
+            calls: AtomicUsize::new(0),
+            transcribed_seconds: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn then_text(self, text: impl Into<String>) -> Self {
+        self.then(ScriptedStep::Text(text.into()))
+    }
+
+    pub fn then_fail(self, message: impl Into<String>) -> Self {
+        self.then(ScriptedStep::Fail(message.into()))
+    }
+
+    pub fn then(self, step: ScriptedStep) -> Self {
+        if let Ok(mut script) = self.script.lock() {
+            script.push_back(step);
+        }
+        self
+    }
+
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Fail every `n`-th call; 0 never fails
+    pub fn fail_every(mut self, n: usize) -> Self {
+        self.fail_every = (n > 0).then_some(n);
+        self
+    }
+
+    pub fn with_tier(mut self, tier: Option<ModelTier>) -> Self {
+        self.tier = tier;
+        self
+    }
+
+    /// Calls made so far, failed ones included
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Duration of the audio handed to each call, in call order
+    pub fn transcribed_seconds(&self) -> Vec<f32> {
+        self.transcribed_seconds.lock().map(|seconds| seconds.clone()).unwrap_or_default()
+    }
+
+    fn text_result(text: String) -> ASRResult {
+        ASRResult {
+            confidence: if text.is_empty() { 0.0 } else { 0.9 },
+            text,
+            language: "en".to_string(),
+            language_confidence: 1.0,
+            words: Vec::new(),
+            word_timestamps: false,
+            estimated_snr: None,
+            speaker_consistency_score: None,
+            language_segments: None,
+            decode_temperature: 0.0,
+            decode_attempts: 1,
+            decode_failed: false,
+            decode_budget_exhausted: false,
+            prompt_budget: None,
+            device: Some(Device::CPU),
+        }
+    }
+}
+
+impl Transcriber for ScriptedTranscriber {
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a AudioData,
+        _context: &'a TranscriptionContext,
+    ) -> BoxFuture<'a, Result<ASRResult, ASRError>> {
+        Box::pin(async move {
+            if !self.latency.is_zero() {
+                tokio::time::sleep(self.latency).await;
+            }
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if let Ok(mut seconds) = self.transcribed_seconds.lock() {
+                seconds.push(audio.duration_seconds);
+            }
+            let step = self.script.lock().ok().and_then(|mut script| script.pop_front());
+            if self.fail_every.is_some_and(|n| call % n == 0) {
+                return Err(ASRError::TranscriptionFailed { message: format!("Injected failure on call {}", call) });
+            }
+            match step {
+                Some(ScriptedStep::Text(text)) => Ok(Self::text_result(text)),
+                Some(ScriptedStep::Result(result)) => Ok(*result),
+                Some(ScriptedStep::Fail(message)) => Err(ASRError::TranscriptionFailed { message }),
+                None => Ok(Self::text_result(String::new())),
+            }
+        })
+    }
+
+    fn model_tier(&self) -> Option<ModelTier> {
+        self.tier
+    }
+
+    fn device(&self) -> Device {
+        Device::CPU
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::types::AudioSource;
+    use std::time::SystemTime;
+
+    fn audio(seconds: f32) -> AudioData {
+        AudioData {
+            samples: vec![0.0; (16000.0 * seconds) as usize],
+            sample_rate: 16000,
+            channels: 1,
+            timestamp: SystemTime::now(),
+            source_channel: AudioSource::Microphone,
+            duration_seconds: seconds,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_script_is_played_in_order_then_falls_silent() {
+        let transcriber: SharedTranscriber = Arc::new(ScriptedTranscriber::new()
+            .then_text("hello there")
+            .then_fail("decoder crashed")
+            .then_text("general kenobi"));
+        let context = TranscriptionContext::default();
+
+        assert_eq!(transcriber.transcribe(&audio(1.0), &context).await.unwrap().text, "hello there");
+        let failed = transcriber.transcribe(&audio(1.0), &context).await.unwrap_err();
+        assert!(matches!(failed, ASRError::TranscriptionFailed { message } if message == "decoder crashed"));
+        assert_eq!(transcriber.transcribe(&audio(1.0), &context).await.unwrap().text, "general kenobi");
+        let silent = transcriber.transcribe(&audio(1.0), &context).await.unwrap();
+        assert!(silent.text.is_empty());
+        assert_eq!(transcriber.model_tier(), Some(ModelTier::Standard));
+        assert!(silent.prompt_budget.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_latency_and_injected_failures() {
+        let transcriber = ScriptedTranscriber::new()
+            .then_text("one")
+            .then_text("two")
+            .then_text("three")
+            .with_latency(Duration::from_millis(250))
+            .fail_every(2);
+        let context = TranscriptionContext::default();
+
+        let started = tokio::time::Instant::now();
+        let outcomes: Vec<_> = futures_util::future::join_all(
+            [0.5, 1.0, 1.5].map(|seconds| {
+                let transcriber = &transcriber;
+                let context = &context;
+                async move { transcriber.transcribe(&audio(seconds), context).await.map(|result| result.text) }
+            })
+        ).await;
+        assert_eq!(started.elapsed(), Duration::from_millis(250));
+        assert_eq!(transcriber.calls(), 3);
+        assert_eq!(outcomes.iter().filter(|outcome| outcome.is_err()).count(), 1);
+        // The injected failure consumes its step like a real failed call
+        let texts: Vec<_> = outcomes.into_iter().filter_map(Result::ok).collect();
+        assert_eq!(texts.len(), 2);
+        assert_eq!(transcriber.transcribed_seconds().len(), 3);
+    }
+}
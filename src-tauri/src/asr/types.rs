@@ -6,6 +6,9 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub use super::language::{supported_languages, Language, LanguageError, LanguageInfo};
+use super::prompt_budget::PromptBudget;
+
 /// ASR result containing transcription and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ASRResult {
@@ -14,9 +17,32 @@ pub struct ASRResult {
     pub language: String,
     pub language_confidence: f32,
     pub words: Vec<WordResult>,
+    /// Word times come from decoder token timestamps rather than an even split
+    #[serde(default)]
+    pub word_timestamps: bool,
     pub estimated_snr: Option<f32>,
     pub speaker_consistency_score: Option<f32>,
     pub language_segments: Option<Vec<LanguageSegment>>,
+    /// Decoding temperature of the accepted result
+    #[serde(default)]
+    pub decode_temperature: f32,
+    /// Decoding attempts made by the temperature fallback
+    #[serde(default)]
+    pub decode_attempts: u32,
+    /// No temperature produced output passing the quality checks
+    #[serde(default)]
+    pub decode_failed: bool,
+    /// Retries were skipped to stay within the chunk's latency budget
+    #[serde(default)]
+    pub decode_budget_exhausted: bool,
+    /// How the prompt of this decode fit the model's context window. Kept
+    /// with the result because engines are shared: another consumer's decode
+    /// may already have finished by the time this one is read.
+    #[serde(default)]
+    pub prompt_budget: Option<PromptBudget>,
+    /// Device inference ran on
+    #[serde(default)]
+    pub device: Option<Device>,
 }
 
 /// Individual word result with timing and confidence
@@ -119,6 +145,9 @@ pub enum ASRError {
     #[error("Model loading failed: {message}")]
     ModelLoadFailed { message: String },
     
+    #[error("Model download cancelled")]
+    DownloadCancelled,
+    
     #[error("Transcription failed: {message}")]
     TranscriptionFailed { message: String },
     
@@ -171,6 +200,34 @@ pub enum Device {
     Auto,
 }
 
+impl Device {
+    /// Device for a `preferredDevice` setting ("auto", "cpu" or "gpu"); the
+    /// GPU is Metal on macOS and CUDA elsewhere
+    pub fn from_preference(preference: &str) -> Option<Self> {
+        match preference.trim().to_lowercase().as_str() {
+            "auto" => Some(Device::Auto),
+            "cpu" => Some(Device::CPU),
+            "gpu" if cfg!(target_os = "macos") => Some(Device::Metal),
+            "gpu" => Some(Device::CUDA),
+            _ => None,
+        }
+    }
+
+    pub fn is_gpu(&self) -> bool {
+        matches!(self, Device::CUDA | Device::Metal)
+    }
+
+    /// Name reported to the frontend
+    pub fn label(&self) -> &'static str {
+        match self {
+            Device::CPU => "cpu",
+            Device::CUDA => "cuda",
+            Device::Metal => "metal",
+            Device::Auto => "auto",
+        }
+    }
+}
+
 /// Task type for Whisper
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Task {
@@ -4,7 +4,10 @@
 //! context-aware processing, and real-time performance for macOS with Metal acceleration.
 
 use crate::asr::types::*;
-use crate::asr::model_manager::ModelManager;
+use crate::asr::model_manager::{DownloadCancellation, ModelManager, ProgressCallback};
+use crate::asr::temperature_fallback::{self, DecodeQuality, TemperatureLadder};
+use crate::asr::prompt_budget::{self, PromptBudget, PromptInputs};
+use crate::asr::word_timing::{self, TokenTiming};
 use crate::audio::types::AudioData;
 use crate::audio::resampler::ResamplerUtils;
 use anyhow::Result;
@@ -13,7 +16,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Instant;
 use tokio::sync::Mutex;
-use tracing::info;
+use tracing::{debug, info};
 // Whisper.cpp integration with Rust bindings
 use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy};
 
@@ -26,13 +29,20 @@ pub struct WhisperConfig {
     pub num_threads: usize,
     pub beam_size: usize,
     pub temperature: f32,
-    pub language: Option<String>,
+    pub language: Option<Language>,
     pub task: Task,
     pub enable_vad: bool,
     pub enable_word_timestamps: bool,
     pub context_size: usize,
     pub custom_vocabulary: Option<Vec<String>>,
     pub optimization_level: Option<OptimizationLevel>,
+    /// Highest temperature the fallback ladder climbs to from `temperature`
+    #[serde(default = "default_max_temperature")]
+    pub max_temperature: f32,
+}
+
+fn default_max_temperature() -> f32 {
+    temperature_fallback::DEFAULT_MAX_TEMPERATURE
 }
 
 impl Default for WhisperConfig {
@@ -51,10 +61,18 @@ impl Default for WhisperConfig {
             context_size: 50,
             custom_vocabulary: None,
             optimization_level: Some(OptimizationLevel::Balanced),
+            max_temperature: default_max_temperature(),
         }
     }
 }
 
+/// Decoder settings that differ between the final and the partial pass
+#[derive(Debug, Clone, Copy)]
+struct DecodePass {
+    num_threads: usize,
+    token_timestamps: bool,
+}
+
 /// Whisper ASR Engine with whisper.cpp integration
 pub struct WhisperEngine {
     config: WhisperConfig,
@@ -63,21 +81,48 @@ pub struct WhisperEngine {
     supported_languages: Vec<String>,
     is_loaded: bool,
     current_device: Device,
+    /// Why the configured GPU was not used, when inference fell back to the CPU
+    device_fallback: Option<String>,
     whisper_context: Option<WhisperContext>, // Actual whisper context
     #[allow(dead_code)]
     model_manager: std::sync::Arc<tokio::sync::Mutex<ModelManager>>,
     #[allow(dead_code)]
     context_cache: Mutex<HashMap<String, TranscriptionContext>>,
     performance_metrics: Mutex<PerformanceMetrics>,
+    temperature_ladder: TemperatureLadder,
 }
 
 impl WhisperEngine {
     /// Create new Whisper engine with configuration and automatic model download
     pub async fn new(config: WhisperConfig) -> Result<Self, ASRError> {
+        let log_progress: ProgressCallback = Box::new(|downloaded, total| {
+            let percent = (downloaded as f64 / total as f64 * 100.0) as u32;
+            if downloaded % (10 * 1024 * 1024) < 1024 || downloaded == total { // Log every 10MB or at completion
+                info!("Model download progress: {}% ({}/{} bytes)", percent, downloaded, total);
+            }
+        });
+        Self::new_with_download(config, Some(log_progress), None).await
+    }
+    
+    /// Create new Whisper engine, reporting model download progress (downloaded, total bytes)
+    /// and aborting the download when `cancellation` is triggered
+    pub async fn new_with_download(
+        config: WhisperConfig,
+        progress_callback: Option<ProgressCallback>,
+        cancellation: Option<DownloadCancellation>,
+    ) -> Result<Self, ASRError> {
         Self::validate_config(&config)?;
         
-        let current_device = Self::select_device(&config).await?;
-        let device_capabilities = Self::create_device_capabilities(&current_device).await?;
+        // A GPU that is not available is no reason to fail the session: use the CPU
+        let (requested_device, mut device_fallback) = match Self::select_device(&config).await {
+            Ok(device) => (device, None),
+            Err(ASRError::DeviceNotAvailable { device }) => {
+                tracing::warn!("{} acceleration is not available, falling back to CPU", device);
+                (Device::CPU, Some(format!("{} acceleration is not available", device)))
+            }
+            Err(e) => return Err(e),
+        };
+        let mut device_capabilities = Self::create_device_capabilities(&requested_device).await?;
         
         // Validate memory requirements
         let memory_required = Self::get_memory_requirements(&config.model_tier);
@@ -98,12 +143,8 @@ impl WhisperEngine {
         info!("Ensuring model is available for tier: {:?}", config.model_tier);
         let model_path = model_manager.ensure_model_available(
             config.model_tier, 
-            Some(Box::new(|downloaded, total| {
-                let percent = (downloaded as f64 / total as f64 * 100.0) as u32;
-                if downloaded % (10 * 1024 * 1024) < 1024 || downloaded == total { // Log every 10MB or at completion
-                    info!("Model download progress: {}% ({}/{} bytes)", percent, downloaded, total);
-                }
-            }))
+            progress_callback,
+            cancellation
         ).await
         .map_err(|e| {
             match e {
@@ -116,23 +157,34 @@ impl WhisperEngine {
 
         // Load the actual Whisper model
         info!("Loading Whisper model from: {:?}", model_path);
-        let whisper_context = Self::load_whisper_model(&model_path, &current_device).await
-            .map_err(|e| {
-                ASRError::ModelLoadFailed {
-                    message: format!(
-                        "Failed to load Whisper model from path: {:?}. Device: {:?}. Error: {}. Please check that the model file is not corrupted and that you have sufficient memory.", 
-                        model_path, 
-                        current_device, 
-                        e
-                    ),
-                }
-            })?;
+        let load_failed = |device: Device, e: ASRError| ASRError::ModelLoadFailed {
+            message: format!(
+                "Failed to load Whisper model from path: {:?}. Device: {:?}. Error: {}. Please check that the model file is not corrupted and that you have sufficient memory.", 
+                model_path, 
+                device, 
+                e
+            ),
+        };
+        let (whisper_context, current_device) = match Self::load_whisper_model(&model_path, &requested_device).await {
+            Ok(context) => (context, requested_device),
+            Err(e) if requested_device.is_gpu() => {
+                tracing::warn!("{:?} initialization failed, falling back to CPU: {}", requested_device, e);
+                device_fallback = Some(format!("{:?} initialization failed: {}", requested_device, e));
+                let context = Self::load_whisper_model(&model_path, &Device::CPU).await
+                    .map_err(|e| load_failed(Device::CPU, e))?;
+                device_capabilities = Self::create_device_capabilities(&Device::CPU).await?;
+                (context, Device::CPU)
+            }
+            Err(e) => return Err(load_failed(requested_device, e)),
+        };
+        info!("Whisper inference device: {:?}", current_device);
         
         let model_info = Self::create_model_info(&config, &model_path).await?;
         let supported_languages = Self::initialize_language_support();
         
         info!("Whisper model loaded successfully");
         
+        let temperature_ladder = TemperatureLadder::new(config.temperature, config.max_temperature);
         Ok(Self {
             config,
             model_info,
@@ -140,6 +192,7 @@ impl WhisperEngine {
             supported_languages,
             is_loaded: true,
             current_device,
+            device_fallback,
             whisper_context: Some(whisper_context),
             model_manager: std::sync::Arc::new(tokio::sync::Mutex::new(model_manager)),
             context_cache: Mutex::new(HashMap::new()),
@@ -149,6 +202,7 @@ impl WhisperEngine {
                 memory_usage_mb: 0,
                 cpu_usage_percent: 0.0,
             }),
+            temperature_ladder,
         })
     }
     
@@ -172,6 +226,17 @@ impl WhisperEngine {
         &self,
         audio: &AudioData,
         context: &TranscriptionContext,
+    ) -> Result<ASRResult, ASRError> {
+        self.transcribe_with_vocabulary(audio, context, self.config.custom_vocabulary.as_deref()).await
+    }
+    
+    /// Transcribe with a custom vocabulary other than the configured one, e.g.
+    /// for a file transcription sharing the engine of a live session
+    pub async fn transcribe_with_vocabulary(
+        &self,
+        audio: &AudioData,
+        context: &TranscriptionContext,
+        vocabulary: Option<&[String]>,
     ) -> Result<ASRResult, ASRError> {
         let start_time = Instant::now();
         
@@ -188,11 +253,35 @@ impl WhisperEngine {
         // Preprocess audio for whisper.cpp (expects 16kHz, 32-bit float, mono)
         let processed_audio = self.preprocess_audio_for_whisper(audio).await?;
         
-        // Run actual transcription using whisper.cpp
-        let raw_result = self.run_whisper_transcription(&processed_audio)?;
+        // Fit prompt, vocabulary and rolling context into the text context ourselves
+        // instead of letting whisper.cpp silently cut the prompt
+        let (prompt, prompt_budget) = self.build_prompt(context, vocabulary);
+        if !prompt_budget.truncations.is_empty() {
+            info!("Prompt truncated to {} of {} available tokens ({} parts dropped)",
+                  prompt_budget.used_tokens, prompt_budget.available_tokens, prompt_budget.truncations.len());
+        }
+        
+        // Run actual transcription using whisper.cpp, retrying degenerate output at
+        // higher temperatures while the chunk can still be processed in real time
+        let budget = std::time::Duration::from_secs_f32(audio.duration_seconds.max(0.0))
+            .saturating_sub(start_time.elapsed());
+        let outcome = temperature_fallback::decode_with_fallback(&self.temperature_ladder, Some(budget), |temperature| {
+            let (raw, avg_logprob) = self.run_whisper_transcription(&processed_audio, temperature, &prompt, self.final_pass())?;
+            let quality = DecodeQuality::from_text(&raw.text, avg_logprob);
+            Ok::<_, ASRError>((raw, quality))
+        })?;
+        if outcome.attempts > 1 {
+            info!("Temperature fallback: accepted result at {:.1} after {} attempts (passed: {}, budget exhausted: {})",
+                  outcome.temperature, outcome.attempts, outcome.passed, outcome.budget_exhausted);
+        }
         
         // Post-process results with context
-        let final_result = self.postprocess_result(raw_result, context).await?;
+        let mut final_result = self.postprocess_result(outcome.result, context, vocabulary).await?;
+        final_result.decode_temperature = outcome.temperature;
+        final_result.decode_attempts = outcome.attempts;
+        final_result.decode_failed = !outcome.passed;
+        final_result.decode_budget_exhausted = outcome.budget_exhausted;
+        final_result.prompt_budget = Some(prompt_budget);
         
         // Update performance metrics
         let processing_time = start_time.elapsed();
@@ -205,6 +294,63 @@ impl WhisperEngine {
         Ok(final_result)
     }
     
+    /// Fast decode of a buffer that is still growing, for a provisional result:
+    /// one greedy attempt without temperature fallback or token timestamps, on
+    /// at most `num_threads` threads. The result reports no prompt budget, and
+    /// performance metrics are left to the final pass.
+    pub async fn transcribe_partial(
+        &self,
+        audio: &AudioData,
+        context: &TranscriptionContext,
+        num_threads: usize,
+    ) -> Result<ASRResult, ASRError> {
+        Self::validate_audio(audio)?;
+        let processed_audio = self.preprocess_audio_for_whisper(audio).await?;
+        let vocabulary = self.config.custom_vocabulary.as_deref();
+        let (prompt, _) = self.build_prompt(context, vocabulary);
+        let pass = DecodePass {
+            num_threads: num_threads.clamp(1, self.config.num_threads.max(1)),
+            token_timestamps: false,
+        };
+        let (raw, _) = self.run_whisper_transcription(&processed_audio, 0.0, &prompt, pass)?;
+        self.postprocess_result(raw, context, vocabulary).await
+    }
+    
+    fn final_pass(&self) -> DecodePass {
+        DecodePass {
+            num_threads: self.config.num_threads,
+            token_timestamps: self.config.enable_word_timestamps,
+        }
+    }
+    
+    /// Replace the terms offered to the decoder; returns the previous vocabulary
+    pub fn set_custom_vocabulary(&mut self, vocabulary: Option<Vec<String>>) -> Option<Vec<String>> {
+        std::mem::replace(&mut self.config.custom_vocabulary, vocabulary)
+    }
+    
+    /// Terms offered to the decoder by `transcribe`
+    pub fn custom_vocabulary(&self) -> Option<&[String]> {
+        self.config.custom_vocabulary.as_deref()
+    }
+    
+    /// Assemble the decoding prompt from the context and custom vocabulary within the model's text context
+    fn build_prompt(&self, context: &TranscriptionContext, vocabulary: Option<&[String]>) -> (String, PromptBudget) {
+        let inputs = prompt_inputs(vocabulary, context);
+        let context_tokens = self.whisper_context.as_ref()
+            .map(|ctx| ctx.n_text_ctx() as usize)
+            .filter(|&n| n > 0)
+            .unwrap_or(prompt_budget::DEFAULT_TEXT_CONTEXT);
+        prompt_budget::build_prompt(&inputs, context_tokens, |text| self.count_tokens(text))
+    }
+    
+    /// Token count with the model tokenizer, estimated when no model is loaded
+    fn count_tokens(&self, text: &str) -> usize {
+        self.whisper_context.as_ref()
+            .and_then(|ctx| ctx.tokenize(text, text.len() + 8).ok())
+            .map(|tokens| tokens.len())
+            .unwrap_or_else(|| prompt_budget::estimate_tokens(text))
+    }
+    
     /// Detect language of audio
     pub async fn detect_language(&self, audio: &AudioData) -> Result<LanguageDetectionResult, ASRError> {
         Self::validate_audio(audio)?;
@@ -223,6 +369,10 @@ impl WhisperEngine {
         self.config.model_tier
     }
     
+    pub fn get_config(&self) -> &WhisperConfig {
+        &self.config
+    }
+    
     pub fn is_loaded(&self) -> bool {
         self.is_loaded
     }
@@ -235,6 +385,11 @@ impl WhisperEngine {
         self.current_device
     }
     
+    /// Why the configured GPU is not in use, if the engine fell back to the CPU
+    pub fn device_fallback(&self) -> Option<&str> {
+        self.device_fallback.as_deref()
+    }
+    
     pub fn get_device_capabilities(&self) -> &DeviceCapabilities {
         &self.device_capabilities
     }
@@ -412,21 +567,7 @@ impl WhisperEngine {
     
     fn initialize_language_support() -> Vec<String> {
         // Whisper supports 99 languages
-        let base_languages = vec![
-            "en", "es", "fr", "de", "it", "pt", "ru", "ja", "ko", "zh",
-            "ar", "tr", "pl", "nl", "sv", "da", "no", "fi", "uk", "el",
-        ];
-        
-        let mut all_languages: Vec<String> = base_languages.into_iter()
-            .map(|s| s.to_string())
-            .collect();
-        
-        // Add more languages to reach 99 total
-        for i in 0..79 {
-            all_languages.push(format!("lang{:02}", i));
-        }
-        
-        all_languages
+        supported_languages().iter().map(|l| l.code().to_string()).collect()
     }
     
     fn validate_audio(audio: &AudioData) -> Result<(), ASRError> {
@@ -497,8 +638,9 @@ impl WhisperEngine {
         Ok(processed)
     }
 
-    /// Run transcription using actual whisper.cpp
-    fn run_whisper_transcription(&self, audio: &[f32]) -> Result<RawTranscriptionResult, ASRError> {
+    /// Run transcription using actual whisper.cpp at the given temperature,
+    /// returning the result and its average token log probability
+    fn run_whisper_transcription(&self, audio: &[f32], temperature: f32, prompt: &str, pass: DecodePass) -> Result<(RawTranscriptionResult, Option<f32>), ASRError> {
         let whisper_context = self.whisper_context.as_ref()
             .ok_or_else(|| ASRError::ModelLoadFailed {
                 message: "Whisper context not available".to_string(),
@@ -516,19 +658,17 @@ impl WhisperEngine {
         // Extract configuration values to avoid borrowing self in closure
         let ctx = whisper_context;
         let audio = audio.to_vec();
-        let language = self.config.language.clone();
-        let language_for_convert = language.clone(); // Clone for use after spawn_blocking
-        let num_threads = self.config.num_threads;
-        let is_translate = matches!(self.config.task, Task::Translate);
-        let temperature = self.config.temperature;
-        let enable_word_timestamps = self.config.enable_word_timestamps;
+        let language = self.config.language;
+        let num_threads = pass.num_threads;
+        let (is_translate, language_token) = decode_task(&self.config);
+        let enable_word_timestamps = pass.token_timestamps;
         
         // Configure Whisper parameters
         let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
         
         // Set language if specified
-        if let Some(ref lang) = language {
-            params.set_language(Some(lang.as_str()));
+        if language_token.is_some() {
+            params.set_language(language_token);
         }
         
         // Configure based on settings
@@ -542,6 +682,11 @@ impl WhisperEngine {
         // Set temperature for creativity/determinism tradeoff
         params.set_temperature(temperature);
         
+        // Prompt already fits the text context (see build_prompt)
+        if !prompt.is_empty() {
+            params.set_initial_prompt(prompt);
+        }
+        
         // Enable word timestamps if requested
         if enable_word_timestamps {
             params.set_token_timestamps(true);
@@ -559,6 +704,16 @@ impl WhisperEngine {
                 message: format!("Whisper transcription failed: {}", e),
             })?;
         
+        // A pinned language is reported as is; with auto-detection (e.g. when
+        // translating) the language Whisper detected is the source language
+        let language_for_convert = match language.filter(|l| !l.is_auto()) {
+            Some(language) => Some(language.code().to_string()),
+            None => state.full_lang_id_from_state()
+                .ok()
+                .and_then(whisper_rs::get_lang_str)
+                .map(|code| Language::parse(code).map(|l| l.code()).unwrap_or(code).to_string()),
+        };
+        
         // Extract segments
         let num_segments = state.full_n_segments()
             .map_err(|e| ASRError::TranscriptionFailed {
@@ -566,7 +721,35 @@ impl WhisperEngine {
             })?;
         
         let mut segments = Vec::new();
+        let mut logprob_sum = 0.0f32;
+        let mut token_count = 0usize;
         for i in 0..num_segments {
+            let mut tokens = Vec::new();
+            let mut tokens_complete = enable_word_timestamps;
+            for j in 0..state.full_n_tokens(i).unwrap_or(0) {
+                if let Ok(probability) = state.full_get_token_prob(i, j) {
+                    logprob_sum += probability.max(1e-10).ln();
+                    token_count += 1;
+                }
+                if !tokens_complete {
+                    continue;
+                }
+                // A token holding part of a multi-byte character has no text of its own;
+                // the segment then falls back to evenly spaced words
+                match (state.full_get_token_text(i, j), state.full_get_token_data(i, j)) {
+                    (Ok(text), Ok(data)) => tokens.push(TokenTiming {
+                        text,
+                        start: data.t0 as f32 / 100.0,
+                        end: data.t1 as f32 / 100.0,
+                        probability: data.p,
+                    }),
+                    _ => tokens_complete = false,
+                }
+            }
+            if !tokens_complete {
+                tokens.clear();
+            }
+
             let text = state.full_get_segment_text(i)
                 .map_err(|e| ASRError::TranscriptionFailed {
                     message: format!("Failed to get segment text: {}", e),
@@ -584,11 +767,14 @@ impl WhisperEngine {
                 text,
                 start_time: start_time as f32 / 100.0, // Convert from centiseconds to seconds
                 end_time: end_time as f32 / 100.0,     // Convert from centiseconds to seconds
+                tokens,
             });
         }
 
+        let avg_logprob = (token_count > 0).then(|| logprob_sum / token_count as f32);
+        
         // Convert whisper-rs results to our format
-        Self::convert_whisper_segments(segments, language_for_convert)
+        Ok((Self::convert_whisper_segments(segments, language_for_convert)?, avg_logprob))
     }
     
     /// Convert whisper segments to our internal RawTranscriptionResult format
@@ -598,6 +784,7 @@ impl WhisperEngine {
                 text: String::new(),
                 confidence: 0.0,
                 words: Vec::new(),
+                word_timestamps: false,
                 language: language.unwrap_or_else(|| "en".to_string()),
                 language_confidence: 1.0,
             });
@@ -605,6 +792,7 @@ impl WhisperEngine {
         
         let mut all_text = String::new();
         let mut all_words = Vec::new();
+        let word_timestamps = segments.iter().all(|segment| !segment.tokens.is_empty());
         
         for segment in segments {
             // Add segment text to full transcription
@@ -613,8 +801,13 @@ impl WhisperEngine {
             }
             all_text.push_str(&segment.text);
             
-            // For now, create word-level results by splitting segment text
-            // In a future version, we could access token-level data for better timing
+            // Decoder timing per token when word timestamps were requested
+            if word_timestamps {
+                all_words.extend(word_timing::group_tokens(&segment.tokens));
+                continue;
+            }
+            
+            // Otherwise spread the segment's duration evenly over its words
             let words: Vec<&str> = segment.text.split_whitespace().collect();
             let segment_duration = segment.end_time - segment.start_time;
             let word_duration = if words.len() > 1 { 
@@ -654,12 +847,15 @@ impl WhisperEngine {
             text: all_text.trim().to_string(),
             confidence: overall_confidence,
             words: all_words,
+            word_timestamps,
             language: language.unwrap_or_else(|| "en".to_string()),
             language_confidence: 0.95, // Default since whisper-rs doesn't provide language confidence
         };
         
-        info!("Transcription completed: '{}' (confidence: {:.2})", 
-              result.text, result.confidence);
+        // Transcribed text only goes to debug logs, which are not written to the log file
+        info!("Transcription completed: {} characters (confidence: {:.2})", 
+              result.text.chars().count(), result.confidence);
+        debug!("Transcription text: '{}'", result.text);
         
         Ok(result)
     }
@@ -669,6 +865,7 @@ impl WhisperEngine {
         &self,
         raw: RawTranscriptionResult,
         context: &TranscriptionContext,
+        vocabulary: Option<&[String]>,
     ) -> Result<ASRResult, ASRError> {
         let mut result = ASRResult {
             text: raw.text,
@@ -676,9 +873,14 @@ impl WhisperEngine {
             language: raw.language,
             language_confidence: raw.language_confidence,
             words: raw.words,
+            word_timestamps: raw.word_timestamps,
             estimated_snr: Some(25.0), // Mock SNR
             speaker_consistency_score: None,
             language_segments: None,
+            decode_temperature: self.config.temperature,
+            decode_attempts: 1,
+            prompt_budget: None,
+            device: Some(self.current_device),
         };
         
         // Apply context-based post-processing
@@ -687,7 +889,7 @@ impl WhisperEngine {
         }
         
         // Handle custom vocabulary
-        if let Some(vocab) = &self.config.custom_vocabulary {
+        if let Some(vocab) = vocabulary {
             result = self.apply_custom_vocabulary(result, vocab).await?;
         }
         
@@ -834,6 +1036,7 @@ struct RawTranscriptionResult {
     text: String,
     confidence: f32,
     words: Vec<WordResult>,
+    word_timestamps: bool,
     language: String,
     language_confidence: f32,
 }
@@ -843,6 +1046,18 @@ struct TranscriptionSegment {
     text: String,
     start_time: f32,
     end_time: f32,
+    /// Empty unless word timestamps were requested and every token could be read
+    tokens: Vec<TokenTiming>,
+}
+
+/// Prompt sources for one transcription: the caller's domain context as the
+/// initial prompt, the configured vocabulary, and recent segment texts
+fn prompt_inputs(vocabulary: Option<&[String]>, context: &TranscriptionContext) -> PromptInputs {
+    PromptInputs {
+        initial_prompt: context.domain_context.clone(),
+        vocabulary: vocabulary.map(<[String]>::to_vec).unwrap_or_default(),
+        rolling_context: context.previous_segments.clone(),
+    }
 }
 
 // Additional helper functions for external tests
@@ -897,4 +1112,61 @@ pub fn calculate_word_error_rate(hypothesis: &str, reference: &str) -> f32 {
                      .count();
     
     errors as f32 / ref_words.len() as f32
-}
\ No newline at end of file
+}
+/// Whether the decoder translates, and the language token it is given
+fn decode_task(config: &WhisperConfig) -> (bool, Option<&'static str>) {
+    (config.task == Task::Translate, config.language.map(|language| language.whisper_code()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt_for(config: &WhisperConfig, context: &TranscriptionContext) -> String {
+        let inputs = prompt_inputs(config.custom_vocabulary.as_deref(), context);
+        prompt_budget::build_prompt(&inputs, prompt_budget::DEFAULT_TEXT_CONTEXT, prompt_budget::estimate_tokens).0
+    }
+
+    #[test]
+    fn test_custom_vocabulary_reaches_the_prompt() {
+        let context = TranscriptionContext {
+            previous_segments: vec!["Let's review the roadmap.".to_string()],
+            ..TranscriptionContext::default()
+        };
+        let without = WhisperConfig::default();
+        let with = WhisperConfig {
+            custom_vocabulary: Some(vec!["KagiNote".to_string(), "SLO".to_string()]),
+            ..WhisperConfig::default()
+        };
+
+        let plain = prompt_for(&without, &context);
+        let biased = prompt_for(&with, &context);
+        assert!(!plain.contains("KagiNote"));
+        assert!(biased.contains("KagiNote, SLO."));
+        assert!(biased.contains("Let's review the roadmap."));
+    }
+
+    #[test]
+    fn test_translate_mode_reaches_the_decoder() {
+        let mut config = WhisperConfig { language: Language::parse("ja").ok(), ..WhisperConfig::default() };
+        assert_eq!(decode_task(&config), (false, Some("ja")));
+
+        crate::transcription::translation::configure(&mut config, "translate");
+        assert_eq!(decode_task(&config), (true, Some("auto")));
+    }
+
+    #[test]
+    fn test_oversized_vocabulary_is_truncated_to_the_prompt_limit() {
+        let config = WhisperConfig {
+            custom_vocabulary: Some((0..400).map(|i| format!("Term{}", i)).collect()),
+            ..WhisperConfig::default()
+        };
+        let inputs = prompt_inputs(config.custom_vocabulary.as_deref(), &TranscriptionContext::default());
+        let (prompt, budget) = prompt_budget::build_prompt(&inputs, prompt_budget::DEFAULT_TEXT_CONTEXT, prompt_budget::estimate_tokens);
+
+        assert!(budget.used_tokens <= budget.available_tokens);
+        assert!(prompt.starts_with("Term0, Term1,"));
+        assert!(!prompt.contains("Term399"));
+        assert!(!budget.truncations.is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+//! Word-level timing from Whisper tokens
+//!
+//! With token timestamps enabled Whisper reports a start and end for every
+//! BPE token. Tokens are joined into words at leading whitespace; scripts
+//! written without spaces (Japanese, Chinese) keep one token per word. Times
+//! are seconds from the start of the transcribed buffer.
+
+use super::types::WordResult;
+
+/// Timing and probability of one decoded token
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenTiming {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    pub probability: f32,
+}
+
+/// Control tokens such as `[_BEG_]`, `[_TT_150]` or `<|endoftext|>`
+fn is_special(text: &str) -> bool {
+    let text = text.trim();
+    text.is_empty() || text.starts_with("[_") || (text.starts_with("<|") && text.ends_with("|>"))
+}
+
+/// Characters of scripts that do not separate words with spaces
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}' // Half-width Katakana
+    )
+}
+
+struct PendingWord {
+    text: String,
+    start: f32,
+    end: f32,
+    probability_sum: f32,
+    tokens: usize,
+}
+
+impl PendingWord {
+    fn finish(self) -> WordResult {
+        WordResult {
+            word: self.text.trim().to_string(),
+            start_time: self.start,
+            end_time: self.end.max(self.start),
+            confidence: (self.probability_sum / self.tokens as f32).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Join tokens into words; word confidence is the mean token probability.
+/// Control tokens and tokens with negative (unknown) times are skipped.
+pub fn group_tokens(tokens: &[TokenTiming]) -> Vec<WordResult> {
+    let mut words = Vec::new();
+    let mut current: Option<PendingWord> = None;
+    for token in tokens {
+        if is_special(&token.text) || token.start < 0.0 || token.end < 0.0 {
+            continue;
+        }
+
+        let first_char = token.text.chars().next().unwrap_or(' ');
+        let starts_word = first_char.is_whitespace() || is_unspaced_script(first_char);
+        match current.as_mut() {
+            Some(word) if !starts_word => {
+                word.text.push_str(&token.text);
+                word.end = token.end;
+                word.probability_sum += token.probability;
+                word.tokens += 1;
+            }
+            _ => {
+                if let Some(word) = current.take() {
+                    words.push(word.finish());
+                }
+                current = Some(PendingWord {
+                    text: token.text.clone(),
+                    start: token.start,
+                    end: token.end,
+                    probability_sum: token.probability,
+                    tokens: 1,
+                });
+            }
+        }
+    }
+    if let Some(word) = current {
+        words.push(word.finish());
+    }
+    words.retain(|word| !word.word.is_empty());
+    words
+}
+
+/// Start of the first word and end of the last
+pub fn word_span(words: &[WordResult]) -> Option<(f32, f32)> {
+    let start = words.first()?.start_time;
+    let end = words.last()?.end_time;
+    Some((start, end.max(start)))
+}
+
+/// Segment `words` array with times shifted by `offset` seconds
+pub fn words_json(words: &[WordResult], offset: f32) -> Vec<serde_json::Value> {
+    words.iter()
+        .map(|word| serde_json::json!({
+            "word": word.word,
+            "start": offset + word.start_time,
+            "end": offset + word.end_time,
+            "confidence": word.confidence
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, start: f32, end: f32, probability: f32) -> TokenTiming {
+        TokenTiming { text: text.to_string(), start, end, probability }
+    }
+
+    #[test]
+    fn test_tokens_are_joined_at_leading_whitespace() {
+        let tokens = [
+            token("[_BEG_]", 0.0, 0.0, 1.0),
+            token(" Hello", 0.0, 0.4, 0.9),
+            token(" wor", 0.5, 0.7, 0.8),
+            token("ld", 0.7, 0.9, 0.6),
+            token(".", 0.9, 1.0, 0.7),
+            token("<|endoftext|>", 1.0, 1.0, 1.0),
+        ];
+        let words = group_tokens(&tokens);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "Hello");
+        assert_eq!((words[0].start_time, words[0].end_time), (0.0, 0.4));
+        assert_eq!(words[1].word, "world.");
+        assert_eq!((words[1].start_time, words[1].end_time), (0.5, 1.0));
+        assert!((words[1].confidence - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_japanese_tokens_stay_separate() {
+        let tokens = [
+            token("本", 0.0, 0.3, 0.9),
+            token("日", 0.3, 0.5, 0.9),
+            token("は", 0.5, 0.6, 0.8),
+        ];
+        let words = group_tokens(&tokens);
+        assert_eq!(words.iter().map(|w| w.word.as_str()).collect::<Vec<_>>(), ["本", "日", "は"]);
+    }
+
+    #[test]
+    fn test_unknown_times_are_skipped() {
+        let tokens = [token(" a", -1.0, -1.0, 0.5), token(" b", 0.2, 0.1, 0.5)];
+        let words = group_tokens(&tokens);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].word, "b");
+        assert_eq!(words[0].end_time, words[0].start_time);
+        assert!(group_tokens(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_span_and_json_use_buffer_offset() {
+        let words = group_tokens(&[token(" one", 0.2, 0.5, 0.9), token(" two", 0.6, 1.1, 0.8)]);
+        assert_eq!(word_span(&words), Some((0.2, 1.1)));
+        assert_eq!(word_span(&[]), None);
+
+        let json = words_json(&words, 10.0);
+        assert_eq!(json[0]["word"], "one");
+        assert!((json[0]["start"].as_f64().unwrap() - 10.2).abs() < 1e-5);
+        assert!((json[1]["end"].as_f64().unwrap() - 11.1).abs() < 1e-5);
+        assert!(json[1]["confidence"].is_number());
+    }
+}
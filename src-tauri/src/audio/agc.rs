@@ -0,0 +1,219 @@
+//! Automatic gain control before transcription
+//!
+//! Whisper misses words from microphones that peak around -40 dBFS and
+//! garbles input that clips. The AGC follows the RMS of speech and moves a
+//! bounded gain towards the one that brings it to the target loudness,
+//! reducing quickly (attack) and raising slowly (release). The gain only
+//! adapts on chunks the VAD classed as speech, and silence is never
+//! amplified, so pauses do not pump the noise floor up. A peak limiter keeps
+//! the amplified signal from clipping.
+
+use serde::{Deserialize, Serialize};
+
+/// Loudness speech is brought to by default
+pub const DEFAULT_TARGET_DBFS: f32 = -20.0;
+/// Accepted target loudness
+pub const MIN_TARGET_DBFS: f32 = -40.0;
+pub const MAX_TARGET_DBFS: f32 = -6.0;
+/// Largest boost and cut
+pub const MAX_GAIN_DB: f32 = 20.0;
+
+/// Time constant of gain reductions (seconds)
+const ATTACK_SECONDS: f32 = 0.1;
+/// Time constant of gain increases (seconds)
+const RELEASE_SECONDS: f32 = 1.0;
+/// Time constant of the speech RMS the gain follows (seconds)
+const RMS_WINDOW_SECONDS: f32 = 0.4;
+/// Highest sample value after gain
+const LIMITER_CEILING: f32 = 0.98;
+/// Mean square treated as digital silence (-120 dBFS)
+const POWER_FLOOR: f32 = 1e-12;
+
+/// AGC settings of a capture
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AgcConfig {
+    pub enabled: bool,
+    /// Speech RMS the gain aims for (dBFS)
+    #[serde(rename = "targetDbfs")]
+    pub target_dbfs: f32,
+}
+
+impl Default for AgcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_dbfs: DEFAULT_TARGET_DBFS,
+        }
+    }
+}
+
+/// Gain state of one audio stream
+#[derive(Debug, Clone)]
+pub struct AutomaticGainControl {
+    config: AgcConfig,
+    /// Gain adapted on speech (dB)
+    gain_db: f32,
+    /// Moving mean square of speech
+    speech_power: Option<f32>,
+    /// Gain applied at the end of the previous chunk, where the next ramp starts
+    applied_db: f32,
+    /// VAD decision of the previous chunk
+    in_speech: bool,
+}
+
+fn smoothing(chunk_seconds: f32, time_constant: f32) -> f32 {
+    1.0 - (-chunk_seconds / time_constant).exp()
+}
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+impl AutomaticGainControl {
+    pub fn new(config: AgcConfig) -> Self {
+        Self {
+            config,
+            gain_db: 0.0,
+            speech_power: None,
+            applied_db: 0.0,
+            in_speech: false,
+        }
+    }
+
+    pub fn config(&self) -> AgcConfig {
+        self.config
+    }
+
+    /// Gain applied to the latest chunk (dB)
+    pub fn applied_gain_db(&self) -> f32 {
+        self.applied_db
+    }
+
+    /// Apply the gain to a chunk in place and return the gain it ended with (dB).
+    /// `is_speech` is the VAD decision for the chunk.
+    pub fn process(&mut self, samples: &mut [f32], sample_rate: u32, is_speech: bool) -> f32 {
+        if !self.config.enabled || samples.is_empty() {
+            return 0.0;
+        }
+        let chunk_seconds = samples.len() as f32 / sample_rate.max(1) as f32;
+
+        if is_speech {
+            let power = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+            let smoothed = match self.speech_power {
+                Some(previous) => previous + (power - previous) * smoothing(chunk_seconds, RMS_WINDOW_SECONDS),
+                None => power,
+            };
+            self.speech_power = Some(smoothed);
+
+            let rms_dbfs = 10.0 * smoothed.max(POWER_FLOOR).log10();
+            let desired = (self.config.target_dbfs - rms_dbfs).clamp(-MAX_GAIN_DB, MAX_GAIN_DB);
+            let time_constant = if desired < self.gain_db { ATTACK_SECONDS } else { RELEASE_SECONDS };
+            self.gain_db += (desired - self.gain_db) * smoothing(chunk_seconds, time_constant);
+        }
+
+        // Silence keeps the adapted gain but is never boosted
+        let mut target_db = if is_speech { self.gain_db } else { self.gain_db.min(0.0) };
+
+        // Limit peaks: lower the gain so the loudest sample stays under the ceiling
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let ceiling_db = if peak > 0.0 { 20.0 * (LIMITER_CEILING / peak).log10() } else { f32::INFINITY };
+        if target_db > ceiling_db {
+            target_db = ceiling_db;
+            self.gain_db = self.gain_db.min(ceiling_db.max(-MAX_GAIN_DB));
+        }
+        // Speech resuming after a pause starts at the held gain instead of ramping
+        // up from the silence level, which would pump every utterance onset
+        let start_db = if is_speech && !self.in_speech { target_db } else { self.applied_db.min(ceiling_db) };
+        self.in_speech = is_speech;
+
+        // Ramp from the previous gain across the chunk to avoid steps
+        let (start, end) = (db_to_linear(start_db), db_to_linear(target_db));
+        let last = (samples.len() - 1).max(1) as f32;
+        for (index, sample) in samples.iter_mut().enumerate() {
+            let gain = start + (end - start) * index as f32 / last;
+            *sample = (*sample * gain).clamp(-LIMITER_CEILING, LIMITER_CEILING);
+        }
+        self.applied_db = target_db;
+        target_db
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+    const CHUNK: usize = 1600;
+
+    fn sine(rms_dbfs: f32, samples: usize, offset: usize) -> Vec<f32> {
+        let amplitude = db_to_linear(rms_dbfs) * std::f32::consts::SQRT_2;
+        (offset..offset + samples)
+            .map(|n| amplitude * (2.0 * std::f32::consts::PI * 440.0 * n as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    fn rms_dbfs(samples: &[f32]) -> f32 {
+        let power = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+        10.0 * power.max(1e-12).log10()
+    }
+
+    fn agc() -> AutomaticGainControl {
+        AutomaticGainControl::new(AgcConfig { enabled: true, target_dbfs: DEFAULT_TARGET_DBFS })
+    }
+
+    #[test]
+    fn test_quiet_sine_reaches_target_without_overshoot() {
+        let mut agc = agc();
+        let mut levels = Vec::new();
+        for index in 0..100 {
+            let mut chunk = sine(-40.0, CHUNK, index * CHUNK);
+            agc.process(&mut chunk, RATE, true);
+            levels.push(rms_dbfs(&chunk));
+        }
+        let settled = *levels.last().unwrap();
+        assert!((settled - DEFAULT_TARGET_DBFS).abs() < 1.0, "settled at {} dBFS", settled);
+        assert!(levels.iter().all(|level| *level < DEFAULT_TARGET_DBFS + 0.5), "overshoot in {:?}", levels);
+        // Rises monotonically towards the target
+        assert!(levels.windows(2).all(|pair| pair[1] >= pair[0] - 0.01));
+        assert!((agc.applied_gain_db() - MAX_GAIN_DB).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_bursty_speech_does_not_pump() {
+        let mut agc = agc();
+        let mut speech_levels = Vec::new();
+        for index in 0..200 {
+            // 300 ms speech at -30 dBFS, 300 ms near-silence
+            let speaking = (index / 3) % 2 == 0;
+            let level = if speaking { -30.0 } else { -65.0 };
+            let mut chunk = sine(level, CHUNK, index * CHUNK);
+            let gain = agc.process(&mut chunk, RATE, speaking);
+            if speaking && index >= 100 {
+                speech_levels.push(rms_dbfs(&chunk));
+            }
+            if !speaking {
+                assert!(gain <= 0.0, "silence boosted by {} dB", gain);
+            }
+        }
+        let (min, max) = speech_levels.iter().fold((f32::MAX, f32::MIN), |(lo, hi), l| (lo.min(*l), hi.max(*l)));
+        assert!(max - min < 3.0, "speech level swings {}..{}", min, max);
+        assert!((max - DEFAULT_TARGET_DBFS).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_loud_input_is_reduced_without_clipping() {
+        let mut agc = agc();
+        for index in 0..50 {
+            let mut chunk = sine(-3.0, CHUNK, index * CHUNK);
+            agc.process(&mut chunk, RATE, true);
+            assert!(chunk.iter().all(|s| s.abs() <= LIMITER_CEILING));
+        }
+        assert!(agc.applied_gain_db() < -10.0);
+
+        let mut disabled = AutomaticGainControl::new(AgcConfig::default());
+        let mut chunk = sine(-40.0, CHUNK, 0);
+        let original = chunk.clone();
+        assert_eq!(disabled.process(&mut chunk, RATE, true), 0.0);
+        assert_eq!(chunk, original);
+    }
+}
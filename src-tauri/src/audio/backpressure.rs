@@ -0,0 +1,138 @@
+//! Backpressure on the capture channel
+//!
+//! The capture callbacks hand chunks to the transcription loop through a
+//! bounded channel and never block: when the loop falls behind and the
+//! channel is full, the chunk is dropped. Drops are counted here so the loop
+//! can report them and warn before the transcript develops holes. The channel
+//! holds a fixed duration of audio whatever the chunk size.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Audio the capture channel can hold before chunks are dropped
+pub const MAX_QUEUED_AUDIO_MS: u32 = 30_000;
+/// Channel capacity never goes below this many chunks
+pub const MIN_CHANNEL_CAPACITY: usize = 8;
+/// Dropped audio (ms) that triggers a warning; another one follows for each further multiple
+pub const DROP_WARNING_MS: u64 = 2_000;
+
+/// Channel capacity holding `MAX_QUEUED_AUDIO_MS` of `buffer_size_ms` chunks
+pub fn channel_capacity(buffer_size_ms: u32) -> usize {
+    let chunk_ms = buffer_size_ms.max(1);
+    (MAX_QUEUED_AUDIO_MS.div_ceil(chunk_ms) as usize).max(MIN_CHANNEL_CAPACITY)
+}
+
+/// Drop counters shared between the capture callbacks and the service
+#[derive(Debug, Default)]
+pub struct DropCounter {
+    chunks: AtomicU64,
+    micros: AtomicU64,
+}
+
+impl DropCounter {
+    /// A chunk of `duration_seconds` was dropped because the channel was full
+    pub fn record(&self, duration_seconds: f32) {
+        self.chunks.fetch_add(1, Ordering::Relaxed);
+        self.micros.fetch_add((duration_seconds.max(0.0) as f64 * 1e6) as u64, Ordering::Relaxed);
+    }
+
+    pub fn dropped_chunks(&self) -> u64 {
+        self.chunks.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped_ms(&self) -> u64 {
+        self.micros.load(Ordering::Relaxed) / 1000
+    }
+}
+
+/// Capture channel state as reported to the frontend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelHealth {
+    pub dropped_chunks: u64,
+    pub dropped_ms: u64,
+    /// Chunks waiting in the channel
+    pub queue_depth: usize,
+    pub queue_capacity: usize,
+    /// Audio waiting in the channel, in milliseconds
+    pub queued_ms: u64,
+}
+
+/// A faster model tier to suggest when transcription falls behind capture
+pub fn faster_tier(quality_tier: &str) -> Option<&'static str> {
+    match quality_tier {
+        "turbo" => None,
+        _ => Some("turbo"),
+    }
+}
+
+/// Decides when dropped audio is worth a warning
+#[derive(Debug, Clone, Default)]
+pub struct DropWarnings {
+    warned_ms: u64,
+}
+
+impl DropWarnings {
+    /// A warning should go out for the drops so far
+    pub fn should_warn(&mut self, health: &ChannelHealth) -> bool {
+        if health.dropped_ms >= self.warned_ms + DROP_WARNING_MS {
+            self.warned_ms = health.dropped_ms - health.dropped_ms % DROP_WARNING_MS;
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(dropped_ms: u64) -> ChannelHealth {
+        ChannelHealth {
+            dropped_chunks: dropped_ms / 100,
+            dropped_ms,
+            queue_depth: 0,
+            queue_capacity: 300,
+            queued_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_capacity_holds_a_fixed_duration() {
+        assert_eq!(channel_capacity(100), 300);
+        assert_eq!(channel_capacity(20), 1500);
+        assert_eq!(channel_capacity(500), 60);
+        assert_eq!(channel_capacity(30), 1000);
+        assert_eq!(channel_capacity(60_000), MIN_CHANNEL_CAPACITY);
+        assert_eq!(channel_capacity(0), MAX_QUEUED_AUDIO_MS as usize);
+    }
+
+    #[test]
+    fn test_drop_counter_accumulates() {
+        let counter = DropCounter::default();
+        counter.record(0.1);
+        counter.record(0.1);
+        counter.record(0.0205);
+        assert_eq!(counter.dropped_chunks(), 3);
+        assert_eq!(counter.dropped_ms(), 220);
+    }
+
+    #[test]
+    fn test_warnings_repeat_per_threshold() {
+        let mut warnings = DropWarnings::default();
+        assert!(!warnings.should_warn(&health(0)));
+        assert!(!warnings.should_warn(&health(1_900)));
+        assert!(warnings.should_warn(&health(2_300)));
+        assert!(!warnings.should_warn(&health(3_900)));
+        assert!(warnings.should_warn(&health(4_000)));
+        assert!(warnings.should_warn(&health(9_100)));
+        assert!(!warnings.should_warn(&health(9_900)));
+    }
+
+    #[test]
+    fn test_faster_tier() {
+        assert_eq!(faster_tier("high-accuracy"), Some("turbo"));
+        assert_eq!(faster_tier("standard"), Some("turbo"));
+        assert_eq!(faster_tier("turbo"), None);
+    }
+}
@@ -3,15 +3,26 @@
 //! Provides cross-platform audio capture using cpal with fallback mechanisms
 //! and quality assurance features.
 
-use crate::audio::types::{AudioData, AudioDevice, AudioError, AudioSource};
-use crate::audio::resampler::{AudioResampler, ResamplerUtils};
+use crate::audio::types::{AudioData, AudioDevice, AudioError, AudioSource, DeviceCapabilities, InputConfigRange};
+use crate::audio::device_profiles::DeviceProfileManager;
+use crate::audio::resampler::{AudioResampler, ResamplingPreference, ResamplingReport};
+use crate::audio::system_audio::{self, LoopbackPlan, NativePlatformProbe, PlatformProbe};
+use crate::audio::mixer::{SourceMixer, DEFAULT_MAX_LAG_MS};
+use crate::audio::levels::{self, LevelReading};
+use crate::audio::backpressure::{self, ChannelHealth, DropCounter};
+use crate::audio::agc::AgcConfig;
+use crate::audio::capture_simulator::{FileSimulatorBackend, SimulatorConfig};
 use anyhow::Result;
-use cpal::{Device, Host, Stream, StreamConfig};
+use cpal::{Device, Host, Stream, StreamConfig, SupportedStreamConfigRange};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
 use tracing::{info, warn};
 
 /// Audio capture configuration
@@ -25,6 +36,18 @@ pub struct AudioConfig {
     pub auto_sample_rate: bool,
     /// Target sample rate for resampling (usually 16000 for Whisper)
     pub target_sample_rate: u32,
+    /// Microphone input or system audio (loopback); `device_id` then names the loopback device
+    #[serde(default)]
+    pub source: AudioSource,
+    /// Automatic gain control applied to chunks before transcription
+    #[serde(default)]
+    pub agc: AgcConfig,
+    /// Quality of the conversion to `target_sample_rate`
+    #[serde(default)]
+    pub resampling_quality: ResamplingPreference,
+    /// Replay a WAV file instead of opening a device. Development builds only.
+    #[serde(default)]
+    pub simulator: Option<SimulatorConfig>,
 }
 
 impl Default for AudioConfig {
@@ -36,10 +59,23 @@ impl Default for AudioConfig {
             device_id: None,
             auto_sample_rate: true,
             target_sample_rate: 16000,
+            source: AudioSource::Microphone,
+            agc: AgcConfig::default(),
+            resampling_quality: ResamplingPreference::Auto,
+            simulator: None,
         }
     }
 }
 
+/// Where captured chunks come from: a cpal device stream, or a
+/// [`FileSimulatorBackend`] replaying a recording without audio hardware
+pub trait AudioSourceBackend: Send {
+    fn start(&mut self) -> impl Future<Output = Result<(), AudioError>> + Send;
+    fn stop(&mut self) -> impl Future<Output = Result<(), AudioError>> + Send;
+    /// Next chunk at the source's own rate and channel count
+    fn next_chunk(&mut self) -> impl Future<Output = Result<AudioData, AudioError>> + Send;
+}
+
 /// Audio capture methods available
 #[derive(Debug, PartialEq, Clone)]
 pub enum AudioCaptureMethod {
@@ -50,6 +86,15 @@ pub enum AudioCaptureMethod {
     Fallback,
 }
 
+/// System audio stream running next to the microphone in mixed capture
+struct CompanionCapture {
+    device: Device,
+    render_loopback: bool,
+    actual_sample_rate: u32,
+    resampler: Option<AudioResampler>,
+    stream_handle: Option<Arc<Mutex<Stream>>>,
+}
+
 /// Audio capture service
 // Implement Send + Sync for thread safety
 unsafe impl Send for AudioCaptureService {}
@@ -67,6 +112,22 @@ pub struct AudioCaptureService {
     actual_sample_rate: u32,
     /// Audio resampler for converting to target sample rate
     resampler: Option<AudioResampler>,
+    /// The device is an output captured through WASAPI loopback
+    render_loopback: bool,
+    /// System audio captured alongside the microphone (mixed capture)
+    companion: Option<CompanionCapture>,
+    mixer: Option<SourceMixer>,
+    /// Mixed chunks not yet handed out
+    ready_chunks: VecDeque<AudioData>,
+    /// Set from the stream error callback when the device goes away
+    device_lost: Arc<AtomicBool>,
+    /// Per-channel levels of the last device chunk, before resampling and downmix
+    input_levels: Vec<LevelReading>,
+    /// Chunks the callbacks dropped because the channel was full
+    drops: Arc<DropCounter>,
+    channel_capacity: usize,
+    /// Replayed file standing in for the device
+    simulator: Option<FileSimulatorBackend>,
 }
 
 impl AudioCaptureService {
@@ -81,6 +142,10 @@ impl AudioCaptureService {
         })?;
         tracing::info!("✅ Audio configuration validated");
         
+        if let Some(simulator) = config.simulator.clone() {
+            return Self::new_simulated(config, &simulator);
+        }
+        
         // Phase 2: Host system validation
         let host = Self::get_host().await.map_err(|e| {
             tracing::error!("❌ Failed to get audio host: {}. This may indicate audio system unavailability or permissions issues.", e);
@@ -91,25 +156,30 @@ impl AudioCaptureService {
         tracing::info!("✅ Audio host initialized: {}", host.id().name());
         
         // Phase 3: Device selection and validation
-        let device = Self::select_device(&host, &config).await.map_err(|e| {
-            tracing::error!("❌ Audio device selection failed: {}", e);
-            match e {
-                AudioError::NoAudioMethodAvailable { .. } => {
-                    AudioError::NoAudioMethodAvailable {
-                        attempted_methods: vec![
-                            format!("Default device selection on {}", host.id().name()),
-                            "Device enumeration".to_string(),
-                            "Microphone access check".to_string()
-                        ]
+        let (device, render_loopback) = if config.source == AudioSource::System {
+            Self::select_loopback_device(&host, &config)?
+        } else {
+            let device = Self::select_device(&host, &config).await.map_err(|e| {
+                tracing::error!("❌ Audio device selection failed: {}", e);
+                match e {
+                    AudioError::NoAudioMethodAvailable { .. } => {
+                        AudioError::NoAudioMethodAvailable {
+                            attempted_methods: vec![
+                                format!("Default device selection on {}", host.id().name()),
+                                "Device enumeration".to_string(),
+                                "Microphone access check".to_string()
+                            ]
+                        }
                     }
+                    _ => e
                 }
-                _ => e
-            }
-        })?;
+            })?;
+            (device, false)
+        };
 
         // Phase 3.5: Determine optimal sample rate if auto-detection is enabled
         let actual_sample_rate = if config.auto_sample_rate || config.sample_rate == 0 {
-            Self::detect_optimal_sample_rate(&device, &config)?
+            Self::detect_optimal_sample_rate(&device, &config, render_loopback)?
         } else {
             config.sample_rate
         };
@@ -121,8 +191,8 @@ impl AudioCaptureService {
             tracing::info!("✅ Selected audio device: '{}'", device_name);
             
             // Get device capabilities
-            if let Ok(configs) = device.supported_input_configs() {
-                let config_count = configs.count();
+            if let Ok(configs) = Self::supported_configs(&device, render_loopback) {
+                let config_count = configs.len();
                 tracing::info!("📊 Device supports {} input configurations", config_count);
             }
         } else {
@@ -134,7 +204,7 @@ impl AudioCaptureService {
         
         // Phase 4: Initialize resampler if needed
         let resampler = if actual_sample_rate != config.target_sample_rate {
-            let quality = ResamplerUtils::recommend_quality(actual_sample_rate, config.target_sample_rate, true);
+            let quality = config.resampling_quality.resolve(actual_sample_rate, config.target_sample_rate);
             tracing::info!("🔧 Initializing resampler with {:?} quality", quality);
             Some(AudioResampler::new(actual_sample_rate, config.target_sample_rate, config.channels, quality)?)
         } else {
@@ -143,8 +213,9 @@ impl AudioCaptureService {
         };
 
         // Phase 5: Channel setup with error handling
-        let (sender, receiver) = mpsc::channel(1000); // Increase buffer size to prevent overflow
-        tracing::info!("✅ Audio channel initialized with buffer size: 1000");
+        let channel_capacity = backpressure::channel_capacity(config.buffer_size_ms);
+        let (sender, receiver) = mpsc::channel(channel_capacity);
+        tracing::info!("✅ Audio channel initialized with {} chunks of {} ms", channel_capacity, config.buffer_size_ms);
         
         let service = Self {
             config,
@@ -156,12 +227,106 @@ impl AudioCaptureService {
             capture_method,
             actual_sample_rate,
             resampler,
+            render_loopback,
+            companion: None,
+            mixer: None,
+            ready_chunks: VecDeque::new(),
+            device_lost: Arc::new(AtomicBool::new(false)),
+            input_levels: Vec::new(),
+            drops: Arc::new(DropCounter::default()),
+            channel_capacity,
+            simulator: None,
         };
         
         tracing::info!("✅ Audio capture service initialized successfully");
         Ok(service)
     }
     
+    /// Service replaying the WAV file of `simulator` in place of a device, for
+    /// exercising the live pipeline in tests. Chunks are resampled and tagged
+    /// like device chunks; dropouts show up in the channel health.
+    fn new_simulated(config: AudioConfig, simulator: &SimulatorConfig) -> Result<Self, AudioError> {
+        if !cfg!(debug_assertions) {
+            return Err(AudioError::ProcessingFailed {
+                message: "Simulated capture is only available in development builds".to_string(),
+            });
+        }
+        let mut backend = FileSimulatorBackend::open(simulator)?;
+        let drops = Arc::new(DropCounter::default());
+        backend.attach(config.source, drops.clone());
+        tracing::info!("🧪 Simulated capture from {} ({} Hz, {} channels, {}x speed)",
+                       simulator.path.display(), backend.sample_rate(), backend.channels(), simulator.speed);
+        
+        let actual_sample_rate = backend.sample_rate();
+        let resampler = if actual_sample_rate != config.target_sample_rate {
+            let quality = config.resampling_quality.resolve(actual_sample_rate, config.target_sample_rate);
+            Some(AudioResampler::new(actual_sample_rate, config.target_sample_rate, backend.channels(), quality)?)
+        } else {
+            None
+        };
+        let channel_capacity = backpressure::channel_capacity(config.buffer_size_ms);
+        Ok(Self {
+            config: AudioConfig { channels: backend.channels(), ..config },
+            device: None,
+            stream_handle: None,
+            audio_sender: None,
+            audio_receiver: None,
+            is_capturing: false,
+            capture_method: AudioCaptureMethod::Fallback,
+            actual_sample_rate,
+            resampler,
+            render_loopback: false,
+            companion: None,
+            mixer: None,
+            ready_chunks: VecDeque::new(),
+            device_lost: Arc::new(AtomicBool::new(false)),
+            input_levels: Vec::new(),
+            drops,
+            channel_capacity,
+            simulator: Some(backend),
+        })
+    }
+    
+    /// Whether chunks are replayed from a file rather than captured
+    pub fn is_simulated(&self) -> bool {
+        self.simulator.is_some()
+    }
+    
+    /// Create a service capturing the microphone and system audio at once.
+    /// Both streams feed the same channel tagged with their source and are
+    /// mixed into one mono stream; either can drop out without stopping the other.
+    pub async fn new_mixed(config: AudioConfig) -> Result<Self, AudioError> {
+        let mut service = Self::new(AudioConfig { source: AudioSource::Microphone, ..config.clone() }).await?;
+        
+        // The device id selects the microphone; the loopback route is chosen automatically
+        let loopback_config = AudioConfig { source: AudioSource::System, device_id: None, ..config };
+        let host = Self::get_host().await?;
+        let (device, render_loopback) = Self::select_loopback_device(&host, &loopback_config)?;
+        let actual_sample_rate = Self::detect_optimal_sample_rate(&device, &loopback_config, render_loopback)?;
+        let resampler = if actual_sample_rate != loopback_config.target_sample_rate {
+            let quality = loopback_config.resampling_quality.resolve(actual_sample_rate, loopback_config.target_sample_rate);
+            Some(AudioResampler::new(actual_sample_rate, loopback_config.target_sample_rate, loopback_config.channels, quality)?)
+        } else {
+            None
+        };
+        tracing::info!("🎛️ Mixed capture: system audio at {} Hz alongside the microphone", actual_sample_rate);
+        
+        service.companion = Some(CompanionCapture {
+            device,
+            render_loopback,
+            actual_sample_rate,
+            resampler,
+            stream_handle: None,
+        });
+        service.mixer = Some(SourceMixer::new(loopback_config.target_sample_rate, DEFAULT_MAX_LAG_MS));
+        Ok(service)
+    }
+    
+    /// Whether microphone and system audio are captured together
+    pub fn is_mixed(&self) -> bool {
+        self.companion.is_some()
+    }
+    
     /// Create service for testing when permissions are denied
     pub async fn new_with_permissions_denied(_config: AudioConfig) -> Result<Self, AudioError> {
         Err(AudioError::PermissionDenied { 
@@ -190,6 +355,16 @@ impl AudioCaptureService {
     
     /// List available audio input devices with comprehensive validation
     pub async fn list_audio_devices() -> Result<Vec<AudioDevice>, AudioError> {
+        Self::enumerate_devices(None, false).await
+    }
+    
+    /// List input devices, reusing the capabilities probed earlier for each
+    /// device id unless `refresh` is set
+    pub async fn list_audio_devices_cached(profiles: &mut DeviceProfileManager, refresh: bool) -> Result<Vec<AudioDevice>, AudioError> {
+        Self::enumerate_devices(Some(profiles), refresh).await
+    }
+    
+    async fn enumerate_devices(mut profiles: Option<&mut DeviceProfileManager>, refresh: bool) -> Result<Vec<AudioDevice>, AudioError> {
         tracing::info!("🔍 Enumerating available audio input devices...");
         
         let host = Self::get_host().await.map_err(|e| {
@@ -203,7 +378,7 @@ impl AudioCaptureService {
         // Get default input device with error handling
         match host.default_input_device() {
             Some(default_device) => {
-                match Self::device_to_info(&default_device, true) {
+                match Self::describe_device(&default_device, true, profiles.as_deref_mut(), refresh) {
                     Ok(device_info) => {
                         tracing::info!("✅ Found default input device: '{}'", device_info.name);
                         devices.push(device_info);
@@ -226,7 +401,7 @@ impl AudioCaptureService {
                 let mut device_count = 0;
                 for device in input_devices {
                     device_count += 1;
-                    match Self::device_to_info(&device, false) {
+                    match Self::describe_device(&device, false, profiles.as_deref_mut(), refresh) {
                         Ok(device_info) => {
                             // Avoid duplicating the default device
                             if !devices.iter().any(|d| d.id == device_info.id) {
@@ -272,6 +447,27 @@ impl AudioCaptureService {
         Ok(devices)
     }
     
+    /// Input devices for the device watcher: the same entries as
+    /// list_audio_devices without its logging, and empty instead of an error
+    /// when nothing is connected. Blocking.
+    pub fn input_devices_snapshot() -> Vec<AudioDevice> {
+        let host = cpal::default_host();
+        let default_name = host.default_input_device().and_then(|device| device.name().ok());
+        let Ok(input_devices) = host.input_devices() else {
+            return Vec::new();
+        };
+        let mut devices: Vec<AudioDevice> = Vec::new();
+        for device in input_devices {
+            let is_default = device.name().ok() == default_name;
+            if let Ok(info) = Self::device_to_info(&device, is_default) {
+                if !devices.iter().any(|d| d.id == info.id) {
+                    devices.push(info);
+                }
+            }
+        }
+        devices
+    }
+    
     /// Start audio capture with comprehensive validation
     pub async fn start_capture(&mut self) -> Result<(), AudioError> {
         if self.is_capturing {
@@ -281,6 +477,12 @@ impl AudioCaptureService {
         
         tracing::info!("🎤 Starting audio capture...");
         
+        if let Some(simulator) = self.simulator.as_mut() {
+            simulator.start().await?;
+            self.is_capturing = true;
+            return Ok(());
+        }
+        
         // Validate device availability
         let device = self.device.as_ref().ok_or_else(|| {
             let error_msg = "No audio device available. Device may have been disconnected or is in use by another application.";
@@ -309,14 +511,30 @@ impl AudioCaptureService {
                      stream_config.channels, stream_config.sample_rate.0, stream_config.buffer_size);
         
         let sender = self.audio_sender.as_ref().unwrap().clone();
+        self.device_lost.store(false, Ordering::SeqCst);
         
         // Create the actual input stream with detailed error reporting
         tracing::info!("🎙️ Creating audio input stream...");
-        let stream = Self::create_input_stream(device, &stream_config, sender, self.actual_sample_rate, self.config.channels).await.map_err(|e| {
+        let device_label = match self.config.source {
+            AudioSource::System => "system audio",
+            _ => "microphone",
+        };
+        let stream = Self::create_input_stream(
+            device,
+            &stream_config,
+            sender,
+            self.actual_sample_rate,
+            self.config.channels,
+            self.render_loopback,
+            self.config.source,
+            self.config.buffer_size_ms,
+            self.device_lost.clone(),
+            self.drops.clone(),
+        ).await.map_err(|e| {
             tracing::error!("❌ Audio stream creation failed: {}. This often indicates: 1) Microphone permission denied, 2) Device in use by another app, 3) Unsupported audio format", e);
             match e {
                 AudioError::InitializationFailed { .. } => {
-                    AudioError::PermissionDenied { device: "microphone".to_string() }
+                    AudioError::PermissionDenied { device: device_label.to_string() }
                 }
                 _ => e
             }
@@ -328,12 +546,36 @@ impl AudioCaptureService {
             let error_msg = format!("Failed to start audio stream: {}. This typically indicates microphone permissions were denied or the device is exclusively locked by another application.", e);
             tracing::error!("❌ {}", error_msg);
             AudioError::PermissionDenied { 
-                device: "microphone".to_string() 
+                device: device_label.to_string() 
             }
         })?;
         
         // Store the stream wrapped in Arc<Mutex<>>
         self.stream_handle = Some(Arc::new(Mutex::new(stream)));
+        
+        // Mixed capture: the system audio stream pushes into the same channel
+        if let Some(companion) = self.companion.as_mut() {
+            let stream_config = Self::create_stream_config(companion.actual_sample_rate, self.config.channels)?;
+            let sender = self.audio_sender.as_ref().unwrap().clone();
+            let stream = Self::create_input_stream(
+                &companion.device,
+                &stream_config,
+                sender,
+                companion.actual_sample_rate,
+                self.config.channels,
+                companion.render_loopback,
+                AudioSource::System,
+                self.config.buffer_size_ms,
+                self.device_lost.clone(),
+                self.drops.clone(),
+            ).await?;
+            stream.play().map_err(|e| {
+                tracing::error!("❌ Failed to start system audio stream: {}", e);
+                AudioError::PermissionDenied { device: "system audio".to_string() }
+            })?;
+            companion.stream_handle = Some(Arc::new(Mutex::new(stream)));
+            tracing::info!("✅ System audio stream started alongside the microphone");
+        }
         self.is_capturing = true;
         
         tracing::info!("✅ Audio capture started successfully on {:?} method", self.capture_method);
@@ -346,6 +588,9 @@ impl AudioCaptureService {
             return Ok(());
         }
         
+        if let Some(simulator) = self.simulator.as_mut() {
+            simulator.stop().await?;
+        }
         if let Some(stream_handle) = self.stream_handle.take() {
             // Pause the stream before dropping it
             if let Ok(stream) = stream_handle.lock() {
@@ -355,36 +600,66 @@ impl AudioCaptureService {
             }
             // Stream will be dropped when stream_handle goes out of scope
         }
+        if let Some(stream_handle) = self.companion.as_mut().and_then(|c| c.stream_handle.take()) {
+            if let Ok(stream) = stream_handle.lock() {
+                if let Err(e) = stream.pause() {
+                    warn!("Failed to pause system audio stream: {}", e);
+                }
+            }
+        }
+        self.ready_chunks.clear();
         
         self.is_capturing = false;
         info!("Audio capture stopped");
         Ok(())
     }
     
-    /// Get next audio chunk with optional resampling
+    /// Get next audio chunk with optional resampling; in mixed capture the
+    /// next mixed chunk, tagged with its dominant source
     pub async fn get_next_chunk(&mut self) -> Result<AudioData, AudioError> {
-        let receiver = self.audio_receiver.as_mut()
-            .ok_or_else(|| AudioError::ProcessingFailed { 
-                message: "Audio receiver not available".to_string() 
-            })?;
+        loop {
+            if let Some(mixed) = self.ready_chunks.pop_front() {
+                return Ok(mixed);
+            }
             
-        let mut audio_data = receiver.recv().await
-            .ok_or_else(|| AudioError::ProcessingFailed { 
-                message: "Failed to receive audio data".to_string() 
-            })?;
+            let mut audio_data = match self.simulator.as_mut() {
+                Some(simulator) => simulator.next_chunk().await?,
+                None => {
+                    let receiver = self.audio_receiver.as_mut()
+                        .ok_or_else(|| AudioError::ProcessingFailed { 
+                            message: "Audio receiver not available".to_string() 
+                        })?;
+                    receiver.recv().await
+                        .ok_or_else(|| AudioError::ProcessingFailed { 
+                            message: "Failed to receive audio data".to_string() 
+                        })?
+                }
+            };
 
-        // Apply resampling if needed
-        if let Some(ref mut resampler) = self.resampler {
-            audio_data = resampler.process_to_mono(&audio_data)?;
-            tracing::debug!(
-                "Resampled audio: {}Hz → {}Hz ({:.2}s)", 
-                self.actual_sample_rate, 
-                self.config.target_sample_rate,
-                audio_data.duration_seconds
-            );
-        }
+            self.input_levels = levels::measure_channels(&audio_data.samples, audio_data.channels);
+            
+            // Apply resampling if needed, with the resampler of the chunk's stream
+            let (resampler, actual_sample_rate) = match self.companion.as_mut() {
+                Some(companion) if audio_data.source_channel == AudioSource::System => {
+                    (companion.resampler.as_mut(), companion.actual_sample_rate)
+                }
+                _ => (self.resampler.as_mut(), self.actual_sample_rate),
+            };
+            if let Some(resampler) = resampler {
+                audio_data = resampler.process_to_mono(&audio_data)?;
+                tracing::debug!(
+                    "Resampled audio: {}Hz → {}Hz ({:.2}s)", 
+                    actual_sample_rate, 
+                    self.config.target_sample_rate,
+                    audio_data.duration_seconds
+                );
+            }
 
-        Ok(audio_data)
+            match self.mixer.as_mut() {
+                Some(mixer) => self.ready_chunks.extend(mixer.push(audio_data)),
+                None => return Ok(audio_data),
+            }
+        }
     }
     
     /// Process test signal for quality testing
@@ -414,6 +689,84 @@ impl AudioCaptureService {
         self.is_capturing = false;
     }
     
+    /// Per-channel levels of the most recent chunk as delivered by the device
+    pub fn input_levels(&self) -> &[LevelReading] {
+        &self.input_levels
+    }
+    
+    /// The capture device went away while the stream was running
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::SeqCst)
+    }
+    
+    /// Report the capture device as gone, e.g. when the device watcher no
+    /// longer finds it; the session loop then waits for it to come back
+    pub fn mark_device_lost(&self) {
+        self.device_lost.store(true, Ordering::SeqCst);
+    }
+    
+    /// Name of the device being captured
+    pub fn device_name(&self) -> Option<String> {
+        self.device.as_ref().and_then(|device| device.name().ok())
+    }
+    
+    /// Drop counters and current depth of the capture channel
+    pub fn channel_health(&self) -> ChannelHealth {
+        let queue_depth = self.audio_sender.as_ref()
+            .map(|sender| sender.max_capacity() - sender.capacity())
+            .unwrap_or(0);
+        ChannelHealth {
+            dropped_chunks: self.drops.dropped_chunks(),
+            dropped_ms: self.drops.dropped_ms(),
+            queue_depth,
+            queue_capacity: self.channel_capacity,
+            queued_ms: queue_depth as u64 * self.config.buffer_size_ms as u64,
+        }
+    }
+    
+    /// Rebuild the capture stream after the device was lost, with the same
+    /// config on the configured device or, failing that, the default one. The
+    /// resampler is recreated when the device comes back at a different rate.
+    pub async fn reconnect(&mut self) -> Result<(), AudioError> {
+        self.stop_capture().await?;
+        if self.simulator.is_some() {
+            self.device_lost.store(false, Ordering::SeqCst);
+            return self.start_capture().await;
+        }
+        
+        let host = Self::get_host().await?;
+        let (device, render_loopback) = if self.config.source == AudioSource::System {
+            Self::select_loopback_device(&host, &self.config)?
+        } else {
+            (Self::select_device(&host, &self.config).await?, false)
+        };
+        let device_name = device.name().map_err(|_| AudioError::DeviceDisconnected {
+            device: "primary".to_string()
+        })?;
+        
+        let sample_rate = if self.config.auto_sample_rate || self.config.sample_rate == 0 {
+            Self::detect_optimal_sample_rate(&device, &self.config, render_loopback)?
+        } else {
+            self.config.sample_rate
+        };
+        if sample_rate != self.actual_sample_rate {
+            tracing::info!("🔧 Reconnected device runs at {} Hz (was {} Hz), recreating resampler", sample_rate, self.actual_sample_rate);
+            self.resampler = if sample_rate != self.config.target_sample_rate {
+                let quality = self.config.resampling_quality.resolve(sample_rate, self.config.target_sample_rate);
+                Some(AudioResampler::new(sample_rate, self.config.target_sample_rate, self.config.channels, quality)?)
+            } else {
+                None
+            };
+            self.actual_sample_rate = sample_rate;
+        }
+        
+        self.device = Some(device);
+        self.render_loopback = render_loopback;
+        self.start_capture().await?;
+        tracing::info!("✅ Audio capture reconnected to '{}'", device_name);
+        Ok(())
+    }
+    
     /// Get device status
     pub async fn get_device_status(&self) -> AudioError {
         if self.device.is_none() {
@@ -505,7 +858,7 @@ impl AudioCaptureService {
     }
     
     pub fn is_ready(&self) -> bool {
-        self.device.is_some()
+        self.device.is_some() || self.simulator.is_some()
     }
     
     pub fn is_capturing(&self) -> bool {
@@ -516,6 +869,21 @@ impl AudioCaptureService {
         self.capture_method.clone()
     }
     
+    /// Source that captured chunks are tagged with
+    pub fn source(&self) -> AudioSource {
+        self.config.source
+    }
+    
+    /// Gain control the consumer applies to captured chunks
+    pub fn agc_config(&self) -> AgcConfig {
+        self.config.agc
+    }
+    
+    /// Resampling of the primary stream, `None` when it already runs at the target rate
+    pub fn resampling_report(&self) -> Option<ResamplingReport> {
+        self.resampler.as_ref().map(AudioResampler::report)
+    }
+    
     // Private helper methods
     
     fn validate_config(config: &AudioConfig) -> Result<(), AudioError> {
@@ -537,17 +905,14 @@ impl AudioCaptureService {
     }
 
     /// Detect optimal sample rate for the given device
-    fn detect_optimal_sample_rate(device: &Device, config: &AudioConfig) -> Result<u32, AudioError> {
-        let supported_configs = device.supported_input_configs()
-            .map_err(|e| AudioError::InitializationFailed { 
-                source: Box::new(e) 
-            })?;
+    fn detect_optimal_sample_rate(device: &Device, config: &AudioConfig, render_loopback: bool) -> Result<u32, AudioError> {
+        let supported_configs = Self::supported_configs(device, render_loopback)?;
 
         // Common sample rates in order of preference
         let preferred_rates = vec![48000, 44100, 32000, 24000, 16000, 22050, 11025, 8000];
         
         // Collect supported configs into a Vec for multiple iterations
-        let supported_configs_vec: Vec<_> = supported_configs.collect();
+        let supported_configs_vec: Vec<_> = supported_configs;
         
         for &preferred_rate in &preferred_rates {
             for supported_config in &supported_configs_vec {
@@ -567,10 +932,7 @@ impl AudioCaptureService {
         }
 
         // Fallback: use the middle sample rate of the first available config
-        let supported_configs_fallback = device.supported_input_configs()
-            .map_err(|e| AudioError::InitializationFailed { source: Box::new(e) })?;
-            
-        for supported_config in supported_configs_fallback {
+        for supported_config in supported_configs_vec {
             let min_rate = supported_config.min_sample_rate().0;
             let max_rate = supported_config.max_sample_rate().0;
             let device_channels = supported_config.channels();
@@ -610,6 +972,53 @@ impl AudioCaptureService {
         Ok(cpal::default_host())
     }
     
+    /// Stream configurations of a capture device; loopback captures use the output formats
+    fn supported_configs(device: &Device, render_loopback: bool) -> Result<Vec<SupportedStreamConfigRange>, AudioError> {
+        let configs = if render_loopback {
+            device.supported_output_configs().map(|configs| configs.collect())
+        } else {
+            device.supported_input_configs().map(|configs| configs.collect())
+        };
+        configs.map_err(|e| AudioError::InitializationFailed { source: Box::new(e) })
+    }
+    
+    /// Pick the device for a system audio capture. Never falls back to the
+    /// microphone: without a loopback route this is SystemAudioUnavailable.
+    fn select_loopback_device(host: &Host, config: &AudioConfig) -> Result<(Device, bool), AudioError> {
+        let probe = NativePlatformProbe;
+        let input_names: Vec<String> = host.input_devices()
+            .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+            .unwrap_or_default();
+        let plan = system_audio::plan_loopback(
+            probe.os_family(),
+            config.device_id.as_deref(),
+            &probe.render_devices(),
+            &input_names,
+        );
+        
+        let find = |devices: Result<cpal::Devices, cpal::DevicesError>, wanted: &str| {
+            devices.ok().and_then(|mut devices| devices.find(|d| d.name().ok().as_deref() == Some(wanted)))
+        };
+        let missing = |device: &str| AudioError::SystemAudioUnavailable {
+            reason: format!("System audio device '{}' disappeared while opening it", device),
+            suggestions: system_audio::unavailable_suggestions(probe.os_family()),
+        };
+        match plan {
+            LoopbackPlan::RenderLoopback { device } => {
+                tracing::info!("🔊 Capturing system audio via WASAPI loopback on '{}'", device);
+                find(host.output_devices(), &device).map(|d| (d, true)).ok_or_else(|| missing(&device))
+            }
+            LoopbackPlan::VirtualInput { device } => {
+                tracing::info!("🔊 Capturing system audio from loopback input '{}'", device);
+                find(host.input_devices(), &device).map(|d| (d, false)).ok_or_else(|| missing(&device))
+            }
+            LoopbackPlan::Unavailable { reason, suggestions } => {
+                tracing::error!("❌ System audio capture unavailable: {}", reason);
+                Err(AudioError::SystemAudioUnavailable { reason, suggestions })
+            }
+        }
+    }
+    
     async fn select_device(host: &Host, config: &AudioConfig) -> Result<Device, AudioError> {
         if let Some(device_id) = &config.device_id {
             // Try to find specific device
@@ -643,6 +1052,24 @@ impl AudioCaptureService {
         }
     }
     
+    /// Device info from the profile cache when available; probing the input
+    /// configurations takes tens of milliseconds per device on Windows
+    fn describe_device(device: &Device, is_default: bool, profiles: Option<&mut DeviceProfileManager>, refresh: bool) -> Result<AudioDevice, AudioError> {
+        let Some(profiles) = profiles else {
+            return Self::device_to_info(device, is_default);
+        };
+        if !refresh {
+            let cached = device.name().ok().and_then(|name| profiles.cached_device(&name));
+            if let Some(mut info) = cached {
+                info.is_default = is_default;
+                return Ok(info);
+            }
+        }
+        let info = Self::device_to_info(device, is_default)?;
+        profiles.cache_device(info.clone());
+        Ok(info)
+    }
+    
     fn device_to_info(device: &Device, is_default: bool) -> Result<AudioDevice, AudioError> {
         let name = device.name()
             .map_err(|e| AudioError::ProcessingFailed { 
@@ -656,6 +1083,7 @@ impl AudioCaptureService {
             
         let mut sample_rates = Vec::new();
         let mut max_channels = 1;
+        let mut ranges = Vec::new();
         
         for config in supported_configs {
             sample_rates.push(config.min_sample_rate().0);
@@ -663,7 +1091,13 @@ impl AudioCaptureService {
                 sample_rates.push(config.max_sample_rate().0);
             }
             max_channels = max_channels.max(config.channels());
+            ranges.push(InputConfigRange {
+                min_sample_rate: config.min_sample_rate().0,
+                max_sample_rate: config.max_sample_rate().0,
+                channels: config.channels(),
+            });
         }
+        let default_sample_rate = device.default_input_config().ok().map(|config| config.sample_rate().0);
         
         sample_rates.sort_unstable();
         sample_rates.dedup();
@@ -679,6 +1113,7 @@ impl AudioCaptureService {
             is_default,
             sample_rates,
             channels: max_channels as u8,
+            capabilities: DeviceCapabilities::from_ranges(&ranges, default_sample_rate),
         })
     }
     
@@ -694,26 +1129,30 @@ impl AudioCaptureService {
         Ok(stream_config)
     }
     
+    #[allow(clippy::too_many_arguments)]
     async fn create_input_stream(
         device: &Device,
         config: &StreamConfig,
         sender: mpsc::Sender<AudioData>,
         actual_sample_rate: u32,
         channels: u8,
+        render_loopback: bool,
+        source: AudioSource,
+        buffer_size_ms: u32,
+        device_lost: Arc<AtomicBool>,
+        drops: Arc<DropCounter>,
     ) -> Result<Stream, AudioError> {
         let sample_rate = actual_sample_rate;
         let _config_sample_rate = config.sample_rate.0; // For validation
         
         // Create audio buffer to accumulate samples before sending
-        let buffer_size = (sample_rate / 10) as usize; // 100ms buffer
+        let buffer_size = (sample_rate as u64 * buffer_size_ms.max(1) as u64 / 1000).max(1) as usize;
+        let drops_i16 = drops.clone();
         let audio_buffer = Arc::new(Mutex::new(Vec::<f32>::with_capacity(buffer_size)));
         let buffer_clone = audio_buffer.clone();
         
         // Get supported configurations and find the best match
-        let supported_configs = device.supported_input_configs()
-            .map_err(|e| AudioError::InitializationFailed { 
-                source: Box::new(e) 
-            })?;
+        let supported_configs = Self::supported_configs(device, render_loopback)?;
             
         // Find a compatible configuration
         let mut compatible_config = None;
@@ -755,8 +1194,7 @@ impl AudioCaptureService {
             // Try to find ANY supported configuration as fallback
             tracing::warn!("No exact match found, looking for fallback configurations...");
             
-            let supported_configs_fallback = device.supported_input_configs()
-                .map_err(|e| AudioError::InitializationFailed { source: Box::new(e) })?;
+            let supported_configs_fallback = Self::supported_configs(device, render_loopback)?;
                 
             for supported_config in supported_configs_fallback {
                 // Accept any configuration that has at least the minimum channels
@@ -815,18 +1253,16 @@ impl AudioCaptureService {
                             sample_rate,
                             channels,
                             timestamp: SystemTime::now(),
-                            source_channel: AudioSource::Microphone,
+                            source_channel: source,
                             duration_seconds: buffer.len() as f32 / (sample_rate * channels as u32) as f32,
                         };
                         
-                        if let Err(e) = sender.try_send(audio_data) {
+                        if let Err(TrySendError::Full(dropped)) = sender.try_send(audio_data) {
+                            drops.record(dropped.duration_seconds);
                             // Only warn occasionally to avoid spam
-                            if buffer.len() % 5 == 0 { // Only every 5th overflow
-                                warn!("Audio channel overflow - processing cannot keep up: {}", e);
+                            if drops.dropped_chunks() % 50 == 1 {
+                                warn!("Audio channel overflow - processing cannot keep up ({} chunks dropped)", drops.dropped_chunks());
                             }
-                        } else {
-                            // Remove noisy info logs
-                            // info!("Sent audio buffer with {} samples", buffer.len());
                         }
                         
                         buffer.clear();
@@ -835,6 +1271,9 @@ impl AudioCaptureService {
                     },
                     move |err| {
                         tracing::error!("Audio stream error: {}", err);
+                        if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                            device_lost.store(true, Ordering::SeqCst);
+                        }
                     },
                     None,
                 )
@@ -861,13 +1300,14 @@ impl AudioCaptureService {
                                     sample_rate,
                                     channels,
                                     timestamp: SystemTime::now(),
-                                    source_channel: AudioSource::Microphone,
+                                    source_channel: source,
                                     duration_seconds: buffer.len() as f32 / (sample_rate * channels as u32) as f32,
                                 };
                                 
-                                if let Err(e) = sender_i16.try_send(audio_data) {
-                                    if buffer.len() % 5 == 0 {
-                                        tracing::warn!("Audio channel overflow (I16): {}", e);
+                                if let Err(TrySendError::Full(dropped)) = sender_i16.try_send(audio_data) {
+                                    drops_i16.record(dropped.duration_seconds);
+                                    if drops_i16.dropped_chunks() % 50 == 1 {
+                                        tracing::warn!("Audio channel overflow (I16) ({} chunks dropped)", drops_i16.dropped_chunks());
                                     }
                                 }
                                 buffer.clear();
@@ -876,6 +1316,9 @@ impl AudioCaptureService {
                     },
                     move |err| {
                         tracing::error!("Audio stream error (I16): {}", err);
+                        if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                            device_lost.store(true, Ordering::SeqCst);
+                        }
                     },
                     None,
                 )
@@ -915,6 +1358,20 @@ mock! {
     }
 }
 
+impl AudioSourceBackend for AudioCaptureService {
+    async fn start(&mut self) -> Result<(), AudioError> {
+        self.start_capture().await
+    }
+    
+    async fn stop(&mut self) -> Result<(), AudioError> {
+        self.stop_capture().await
+    }
+    
+    async fn next_chunk(&mut self) -> Result<AudioData, AudioError> {
+        self.get_next_chunk().await
+    }
+}
+
 /// Trait for audio capture functionality (used by tests)
 pub trait AudioCapture: Send + Sync {
     fn get_sample_rate(&self) -> u32;
@@ -0,0 +1,242 @@
+//! Simulated capture from a WAV file
+//!
+//! Replays a recording through [`AudioCaptureService`](crate::audio::capture::AudioCaptureService)
+//! the way a device would deliver it: in chunks of `chunk_ms`, each handed
+//! out once its audio has "been recorded" at `speed` times real time, so the
+//! live pipeline can be exercised without audio hardware. Dropouts lose the
+//! chunks in their span and count them as dropped, like an overflowing
+//! capture channel. After the last chunk the simulated device stays silent.
+
+use crate::audio::backpressure::DropCounter;
+use crate::audio::capture::AudioSourceBackend;
+use crate::audio::types::{AudioData, AudioError, AudioSource};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::time::Instant;
+
+/// Replay settings of a simulated capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatorConfig {
+    /// WAV file to replay
+    pub path: PathBuf,
+    /// Playback speed relative to real time; 0 or less delivers chunks without waiting
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+    /// Length of each delivered chunk
+    #[serde(default = "default_chunk_ms")]
+    pub chunk_ms: u32,
+    /// Spans of the file whose chunks are dropped
+    #[serde(default)]
+    pub dropouts: Vec<SimulatedDropout>,
+}
+
+/// Span of the replayed file that never reaches the consumer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulatedDropout {
+    pub start_ms: u64,
+    pub duration_ms: u64,
+}
+
+impl SimulatedDropout {
+    fn overlaps(&self, start_ms: u64, end_ms: u64) -> bool {
+        self.start_ms < end_ms && start_ms < self.start_ms + self.duration_ms
+    }
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+fn default_chunk_ms() -> u32 {
+    100
+}
+
+/// Capture backend replaying interleaved samples of a file
+pub struct FileSimulatorBackend {
+    samples: Vec<f32>,
+    sample_rate: u32,
+    channels: u8,
+    source: AudioSource,
+    chunk_frames: usize,
+    speed: f32,
+    dropouts: Vec<SimulatedDropout>,
+    /// Frames handed out or dropped so far
+    position: usize,
+    started: Option<Instant>,
+    drops: Arc<DropCounter>,
+}
+
+impl FileSimulatorBackend {
+    /// Load the WAV file of `config`
+    pub fn open(config: &SimulatorConfig) -> Result<Self, AudioError> {
+        let mut reader = hound::WavReader::open(&config.path).map_err(|e| AudioError::ProcessingFailed {
+            message: format!("Failed to open simulated capture file {}: {}", config.path.display(), e),
+        })?;
+        let spec = reader.spec();
+        let samples: Result<Vec<f32>, hound::Error> = match spec.sample_format {
+            hound::SampleFormat::Float => reader.samples::<f32>().collect(),
+            hound::SampleFormat::Int => {
+                let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader.samples::<i32>().map(|sample| sample.map(|s| s as f32 / scale)).collect()
+            }
+        };
+        let samples = samples.map_err(|e| AudioError::ProcessingFailed {
+            message: format!("Failed to read simulated capture file {}: {}", config.path.display(), e),
+        })?;
+        Ok(Self::from_samples(samples, spec.sample_rate, spec.channels as u8, config))
+    }
+
+    /// Replay samples already in memory; `config.path` is not read
+    pub fn from_samples(samples: Vec<f32>, sample_rate: u32, channels: u8, config: &SimulatorConfig) -> Self {
+        let chunk_frames = (sample_rate as u64 * config.chunk_ms.max(1) as u64 / 1000).max(1) as usize;
+        Self {
+            samples,
+            sample_rate,
+            channels: channels.max(1),
+            source: AudioSource::Microphone,
+            chunk_frames,
+            speed: config.speed,
+            dropouts: config.dropouts.clone(),
+            position: 0,
+            started: None,
+            drops: Arc::new(DropCounter::default()),
+        }
+    }
+
+    /// Tag chunks with `source` and count dropouts in `drops`
+    pub fn attach(&mut self, source: AudioSource, drops: Arc<DropCounter>) {
+        self.source = source;
+        self.drops = drops;
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u8 {
+        self.channels
+    }
+
+    /// Every chunk of the file was delivered or dropped
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.total_frames()
+    }
+
+    pub fn drops(&self) -> &DropCounter {
+        &self.drops
+    }
+
+    fn total_frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    fn offset_ms(&self, frame: usize) -> u64 {
+        frame as u64 * 1000 / self.sample_rate as u64
+    }
+}
+
+impl AudioSourceBackend for FileSimulatorBackend {
+    async fn start(&mut self) -> Result<(), AudioError> {
+        // Restarting (as a reconnect does) continues where the replay stopped
+        let replayed = if self.speed > 0.0 {
+            Duration::from_secs_f64(self.offset_ms(self.position) as f64 / 1000.0 / self.speed as f64)
+        } else {
+            Duration::ZERO
+        };
+        self.started = Some(Instant::now() - replayed);
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), AudioError> {
+        self.started = None;
+        Ok(())
+    }
+
+    /// Cancel safe: a chunk only counts as delivered once this returns it
+    async fn next_chunk(&mut self) -> Result<AudioData, AudioError> {
+        let Some(started) = self.started else {
+            return Err(AudioError::ProcessingFailed { message: "Simulated capture is not running".to_string() });
+        };
+        loop {
+            if self.is_finished() {
+                return std::future::pending().await;
+            }
+            let start = self.position;
+            let end = (start + self.chunk_frames).min(self.total_frames());
+            if self.speed > 0.0 {
+                let recorded = Duration::from_secs_f64(self.offset_ms(end) as f64 / 1000.0 / self.speed as f64);
+                tokio::time::sleep_until(started + recorded).await;
+            }
+            self.position = end;
+
+            let duration_seconds = (end - start) as f32 / self.sample_rate as f32;
+            let (start_ms, end_ms) = (self.offset_ms(start), self.offset_ms(end));
+            if self.dropouts.iter().any(|dropout| dropout.overlaps(start_ms, end_ms)) {
+                self.drops.record(duration_seconds);
+                continue;
+            }
+            let channels = self.channels as usize;
+            return Ok(AudioData {
+                samples: self.samples[start * channels..end * channels].to_vec(),
+                sample_rate: self.sample_rate,
+                channels: self.channels,
+                timestamp: SystemTime::now(),
+                source_channel: self.source,
+                duration_seconds,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(speed: f32, dropouts: Vec<SimulatedDropout>) -> SimulatorConfig {
+        SimulatorConfig { path: PathBuf::new(), speed, chunk_ms: 100, dropouts }
+    }
+
+    #[tokio::test]
+    async fn test_accelerated_replay_drops_injected_chunks() {
+        // One second at 16 kHz; the second 100 ms chunk and the fifth are lost
+        let dropouts = vec![
+            SimulatedDropout { start_ms: 100, duration_ms: 100 },
+            SimulatedDropout { start_ms: 450, duration_ms: 10 },
+        ];
+        let mut backend = FileSimulatorBackend::from_samples(vec![0.25; 16000], 16000, 1, &config(0.0, dropouts));
+        backend.start().await.unwrap();
+
+        let mut delivered = Vec::new();
+        while !backend.is_finished() {
+            delivered.push(backend.next_chunk().await.unwrap());
+        }
+        assert_eq!(delivered.len(), 8);
+        assert!(delivered.iter().all(|chunk| chunk.samples.len() == 1600 && chunk.sample_rate == 16000));
+        assert_eq!(backend.drops().dropped_chunks(), 2);
+        assert_eq!(backend.drops().dropped_ms(), 200);
+
+        // The exhausted device stays silent
+        let silent = tokio::time::timeout(Duration::from_millis(50), backend.next_chunk()).await;
+        assert!(silent.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_real_time_replay_paces_chunks() {
+        // 400 ms of stereo audio at 4x speed takes about 100 ms
+        let mut backend = FileSimulatorBackend::from_samples(vec![0.0; 8000 * 2 * 4 / 10], 8000, 2, &config(4.0, Vec::new()));
+        assert!(backend.next_chunk().await.is_err(), "chunks only flow once started");
+        backend.start().await.unwrap();
+
+        let started = std::time::Instant::now();
+        let first = backend.next_chunk().await.unwrap();
+        assert_eq!((first.channels, first.samples.len()), (2, 800 * 2));
+        while !backend.is_finished() {
+            backend.next_chunk().await.unwrap();
+        }
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(95), "replayed too fast: {:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(400), "replayed too slowly: {:?}", elapsed);
+    }
+}
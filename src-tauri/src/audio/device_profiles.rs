@@ -85,6 +85,8 @@ pub struct DeviceProfileManager {
     profiles: HashMap<String, DeviceProfile>,
     cache_file: Option<PathBuf>,
     built_in_profiles: HashMap<String, DeviceProfile>,
+    /// Probed device info by device id, kept for the lifetime of the app
+    probed_devices: HashMap<String, AudioDevice>,
 }
 
 impl DeviceProfileManager {
@@ -94,6 +96,7 @@ impl DeviceProfileManager {
             profiles: HashMap::new(),
             cache_file: Self::get_cache_file_path(),
             built_in_profiles: HashMap::new(),
+            probed_devices: HashMap::new(),
         };
 
         manager.initialize_built_in_profiles();
@@ -218,6 +221,16 @@ impl DeviceProfileManager {
         self.save_cached_profiles()
     }
 
+    /// Device info probed earlier for this device id
+    pub fn cached_device(&self, device_id: &str) -> Option<AudioDevice> {
+        self.probed_devices.get(device_id).cloned()
+    }
+
+    /// Remember probed device info until the next refresh
+    pub fn cache_device(&mut self, device: AudioDevice) {
+        self.probed_devices.insert(device.id.clone(), device);
+    }
+
     /// Determine the best sample rate from a list of supported rates
     fn determine_preferred_sample_rate(supported_rates: &[u32]) -> u32 {
         // Priority order: 48kHz > 44.1kHz > 32kHz > other rates > 16kHz
@@ -322,6 +335,13 @@ impl DeviceProfileManager {
         suggestions
     }
 
+    /// Profiles learned for devices used on this machine
+    pub fn cached_profiles(&self) -> Vec<&DeviceProfile> {
+        let mut profiles: Vec<_> = self.profiles.values().collect();
+        profiles.sort_by(|a, b| a.device_name.cmp(&b.device_name));
+        profiles
+    }
+
     /// Get statistics about cached profiles
     pub fn get_stats(&self) -> ProfileStats {
         ProfileStats {
@@ -336,7 +356,7 @@ impl DeviceProfileManager {
 }
 
 /// Statistics about device profiles
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ProfileStats {
     pub total_profiles: usize,
     pub valid_profiles: usize,
@@ -347,7 +367,7 @@ pub struct ProfileStats {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::audio::types::AudioSource;
+    use crate::audio::types::{AudioSource, DeviceCapabilities, InputConfigRange};
 
     fn create_test_device(name: &str, sample_rates: Vec<u32>) -> AudioDevice {
         AudioDevice {
@@ -357,6 +377,7 @@ mod tests {
             is_default: false,
             sample_rates,
             channels: 1,
+            capabilities: Default::default(),
         }
     }
 
@@ -390,6 +411,33 @@ mod tests {
         assert_eq!(DeviceProfileManager::determine_preferred_sample_rate(&[]), 16000);
     }
 
+    #[test]
+    fn test_probed_device_cache() {
+        let mut manager = DeviceProfileManager::new().unwrap();
+        let mut device = create_test_device("USB Headset", vec![44100, 48000]);
+        assert!(manager.cached_device(&device.id).is_none());
+
+        device.capabilities = DeviceCapabilities::from_ranges(&[
+            InputConfigRange { min_sample_rate: 44100, max_sample_rate: 48000, channels: 2 },
+            InputConfigRange { min_sample_rate: 44100, max_sample_rate: 44100, channels: 1 },
+        ], Some(48000));
+        manager.cache_device(device.clone());
+
+        let cached = manager.cached_device(&device.id).unwrap();
+        assert_eq!(cached, device);
+        assert_eq!(cached.capabilities.min_sample_rate, 44100);
+        assert_eq!(cached.capabilities.max_sample_rate, 48000);
+        assert_eq!(cached.capabilities.supported_channels, vec![1, 2]);
+        assert_eq!(cached.capabilities.default_sample_rate, Some(48000));
+        // Neither range reaches 16 kHz
+        assert!(cached.capabilities.requires_resampling);
+
+        let wideband = DeviceCapabilities::from_ranges(&[
+            InputConfigRange { min_sample_rate: 8000, max_sample_rate: 96000, channels: 1 },
+        ], None);
+        assert!(!wideband.requires_resampling);
+    }
+
     #[test]
     fn test_profile_validity() {
         let mut profile = DeviceProfile::new(
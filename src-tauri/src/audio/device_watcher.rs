@@ -0,0 +1,150 @@
+//! Audio device hot-plug detection
+//!
+//! cpal has no device-change notification on every platform, so the input
+//! device list is polled and compared with the previous poll. Polling only
+//! happens while the device picker is open or a capture is running; a
+//! change is emitted as `audio-devices-changed`, and a removed capture
+//! device is handed to the session loop's disconnect handling.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::audio::types::AudioDevice;
+
+/// How often the device list is enumerated while watching
+pub const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Devices that appeared or went away between two polls
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceChanges {
+    pub added: Vec<AudioDevice>,
+    pub removed: Vec<AudioDevice>,
+    /// Id of the default input when it changed, e.g. after the default was unplugged
+    #[serde(rename = "defaultDeviceId")]
+    pub default_device_id: Option<String>,
+}
+
+impl DeviceChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.default_device_id.is_none()
+    }
+
+    /// A device with this name was removed
+    pub fn removes(&self, name: &str) -> bool {
+        self.removed.iter().any(|device| device.name == name)
+    }
+}
+
+fn default_id(devices: &[AudioDevice]) -> Option<&str> {
+    devices.iter().find(|device| device.is_default).map(|device| device.id.as_str())
+}
+
+/// Compare two enumerations by device id
+pub fn diff_devices(previous: &[AudioDevice], current: &[AudioDevice]) -> DeviceChanges {
+    let added = current.iter()
+        .filter(|device| !previous.iter().any(|known| known.id == device.id))
+        .cloned()
+        .collect();
+    let removed = previous.iter()
+        .filter(|known| !current.iter().any(|device| device.id == known.id))
+        .cloned()
+        .collect();
+    let default_device_id = match (default_id(previous), default_id(current)) {
+        (before, Some(now)) if before != Some(now) => Some(now.to_string()),
+        _ => None,
+    };
+    DeviceChanges { added, removed, default_device_id }
+}
+
+/// Watcher state shared between the polling task and the monitoring commands
+#[derive(Debug, Default)]
+pub struct DeviceWatcher {
+    picker_open: bool,
+    known: Option<Vec<AudioDevice>>,
+}
+
+impl DeviceWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_picker_open(&mut self, open: bool) {
+        self.picker_open = open;
+    }
+
+    /// Whether the next poll should enumerate devices
+    pub fn should_poll(&self, capturing: bool) -> bool {
+        self.picker_open || capturing
+    }
+
+    /// Record an enumeration; the first one after a pause only sets the baseline
+    pub fn observe(&mut self, devices: Vec<AudioDevice>) -> Option<DeviceChanges> {
+        let changes = self.known.as_ref()
+            .map(|known| diff_devices(known, &devices))
+            .filter(|changes| !changes.is_empty());
+        self.known = Some(devices);
+        changes
+    }
+
+    /// Forget the baseline while nobody is watching, so a change made in the
+    /// meantime is not reported late as if it had just happened
+    pub fn pause(&mut self) {
+        self.known = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(name: &str, is_default: bool) -> AudioDevice {
+        AudioDevice {
+            id: name.to_string(),
+            name: name.to_string(),
+            is_input_device: true,
+            is_default,
+            sample_rates: vec![48000],
+            channels: 1,
+            capabilities: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_diff_devices() {
+        let before = vec![device("MacBook Pro Microphone", true), device("USB Headset", false)];
+        let after = vec![device("MacBook Pro Microphone", true), device("AirPods", false)];
+
+        let changes = diff_devices(&before, &after);
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(changes.added[0].name, "AirPods");
+        assert!(changes.removes("USB Headset"));
+        assert!(!changes.removes("MacBook Pro Microphone"));
+        assert_eq!(changes.default_device_id, None);
+
+        // Unplugging the default headset moves the default to the built-in mic
+        let before = vec![device("USB Headset", true), device("MacBook Pro Microphone", false)];
+        let after = vec![device("MacBook Pro Microphone", true)];
+        let changes = diff_devices(&before, &after);
+        assert_eq!(changes.default_device_id.as_deref(), Some("MacBook Pro Microphone"));
+
+        assert!(diff_devices(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_watcher_reports_changes_after_baseline() {
+        let mut watcher = DeviceWatcher::new();
+        assert!(!watcher.should_poll(false));
+        assert!(watcher.should_poll(true));
+        watcher.set_picker_open(true);
+        assert!(watcher.should_poll(false));
+
+        assert_eq!(watcher.observe(vec![device("Built-in", true)]), None);
+        assert_eq!(watcher.observe(vec![device("Built-in", true)]), None);
+        let changes = watcher.observe(vec![device("Built-in", true), device("USB Mic", false)]).unwrap();
+        assert_eq!(changes.added[0].name, "USB Mic");
+
+        // A pause starts over from a fresh baseline
+        watcher.pause();
+        assert_eq!(watcher.observe(vec![device("Built-in", true)]), None);
+    }
+}
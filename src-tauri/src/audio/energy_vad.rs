@@ -0,0 +1,80 @@
+//! Energy-based speech scoring for live capture
+//!
+//! A signal-level heuristic, not a trained model: each frame is scored by its
+//! level above a running noise floor, discounted for noise-like zero-crossing
+//! rates. It rejects steady hums and short transients better than a fixed RMS
+//! gate, but music or loud non-speech sounds still score as speech. Live
+//! transcription uses it only when the `energyVad` option is set; RMS gating
+//! is the default.
+
+/// Samples per analysis frame (32 ms at 16 kHz)
+const FRAME_SAMPLES: usize = 512;
+/// Starting background level before any audio was seen (dBFS)
+const INITIAL_NOISE_FLOOR_DB: f32 = -60.0;
+/// Lowest background level assumed, so digital silence does not turn dither into speech
+const MIN_NOISE_FLOOR_DB: f32 = -80.0;
+/// Frame level above the background at which speech is 50% likely (dB)
+const SPEECH_SNR_MIDPOINT_DB: f32 = 9.0;
+
+/// Streaming speech scorer tracking the background level of one input
+#[derive(Debug, Clone)]
+pub struct EnergyVad {
+    /// Background level (dBFS)
+    noise_floor_db: f32,
+}
+
+impl Default for EnergyVad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnergyVad {
+    pub fn new() -> Self {
+        Self { noise_floor_db: INITIAL_NOISE_FLOOR_DB }
+    }
+
+    /// Speech score (0..1) of one live chunk of 16 kHz mono audio.
+    ///
+    /// The chunk gets the length-weighted mean frame score, so a transient such
+    /// as a key press that fills a single frame stays well below a sustained
+    /// voice. The floor falls quickly to quieter frames and rises mostly on
+    /// frames that do not look like speech.
+    pub fn speech_probability(&mut self, samples: &[f32]) -> f32 {
+        let mut score_sum = 0.0;
+        for frame in samples.chunks(FRAME_SAMPLES) {
+            let energy = frame.iter().map(|&x| x * x).sum::<f32>() / frame.len() as f32;
+            let level_db = 10.0 * energy.max(1e-12).log10();
+            let crossings = frame.windows(2).filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0)).count();
+            let zero_crossing_rate = crossings as f32 / frame.len() as f32;
+
+            let snr_db = level_db - self.noise_floor_db;
+            let level_score = 1.0 / (1.0 + (-(snr_db - SPEECH_SNR_MIDPOINT_DB) / 3.0).exp());
+            // Voiced speech stays below ~0.25 crossings per sample; hiss sits near 0.5
+            let voicing = (1.0 - (zero_crossing_rate - 0.25).max(0.0) * 3.2).clamp(0.2, 1.0);
+            let score = level_score * voicing;
+
+            // Rising slowly even under speech keeps a steady hum from passing as a voice
+            let rate = if level_db < self.noise_floor_db {
+                0.5
+            } else if score < 0.5 {
+                0.02
+            } else {
+                0.003
+            };
+            self.noise_floor_db += (level_db - self.noise_floor_db) * rate;
+            self.noise_floor_db = self.noise_floor_db.max(MIN_NOISE_FLOOR_DB);
+
+            score_sum += score * frame.len() as f32;
+        }
+        if samples.is_empty() {
+            return 0.0;
+        }
+        (score_sum / samples.len() as f32).clamp(0.0, 1.0)
+    }
+
+    /// Background level currently assumed (dBFS)
+    pub fn noise_floor_db(&self) -> f32 {
+        self.noise_floor_db
+    }
+}
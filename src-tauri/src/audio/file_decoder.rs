@@ -0,0 +1,300 @@
+//! Compressed audio file decoding
+//!
+//! Decodes MP3, M4A and AAC files (typical Zoom/Teams exports) to interleaved
+//! f32 samples with symphonia. Downmixing and resampling are left to the
+//! caller so imported files take the same path as WAV files.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use thiserror::Error;
+
+/// Extensions handled by [`decode_audio_file`]
+pub const COMPRESSED_EXTENSIONS: &[&str] = &["mp3", "m4a", "aac"];
+
+/// Decoded audio before downmixing and resampling
+#[derive(Debug, Clone)]
+pub struct DecodedAudio {
+    /// Interleaved samples in [-1.0, 1.0]
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Packets that failed to decode and were skipped
+    pub skipped_packets: usize,
+}
+
+impl DecodedAudio {
+    pub fn duration_seconds(&self) -> f32 {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return 0.0;
+        }
+        self.samples.len() as f32 / self.channels as f32 / self.sample_rate as f32
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("Failed to open '{path}': {source}")]
+    Open { path: String, source: io::Error },
+    #[error("'{path}' is not a recognizable audio file: {reason}")]
+    UnrecognizedFormat { path: String, reason: String },
+    #[error("'{path}' contains no audio track")]
+    NoAudioTrack { path: String },
+    #[error("Unsupported codec in '{path}': {reason}")]
+    UnsupportedCodec { path: String, reason: String },
+    #[error("'{path}' contains no decodable audio ({skipped} corrupt packets skipped)")]
+    NoAudio { path: String, skipped: usize },
+    #[error("Failed to decode '{path}': {reason}")]
+    Decode { path: String, reason: String },
+}
+
+/// Decode an MP3/M4A/AAC file. Corrupt packets are skipped and a truncated
+/// file yields the audio before the truncation; a file without any decodable
+/// audio is an error.
+pub fn decode_audio_file(path: &Path) -> Result<DecodedAudio, DecodeError> {
+    let display = path.display().to_string();
+    let file = File::open(path).map_err(|source| DecodeError::Open { path: display.clone(), source })?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| DecodeError::UnrecognizedFormat { path: display.clone(), reason: e.to_string() })?;
+    let mut format = probed.format;
+
+    let track = format.tracks().iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| DecodeError::NoAudioTrack { path: display.clone() })?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut channels = track.codec_params.channels.map(|c| c.count() as u16).unwrap_or(0);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| DecodeError::UnsupportedCodec { path: display.clone(), reason: e.to_string() })?;
+
+    let mut samples = Vec::new();
+    let mut skipped = 0;
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // End of stream, or a truncated file: keep what was decoded
+            Err(SymphoniaError::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(SymphoniaError::DecodeError(reason)) if !samples.is_empty() => {
+                tracing::warn!("Stopping at malformed data in '{}': {}", display, reason);
+                break;
+            }
+            Err(e) => return Err(DecodeError::Decode { path: display, reason: e.to_string() }),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                sample_rate = spec.rate;
+                channels = spec.channels.count() as u16;
+                // Reuse the buffer unless this packet is larger than any before
+                let frames = decoded.capacity();
+                let buffer = match &mut buffer {
+                    Some(existing) if existing.capacity() >= frames * channels as usize => existing,
+                    slot => slot.insert(SampleBuffer::new(frames as u64, spec)),
+                };
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+            }
+            Err(SymphoniaError::DecodeError(reason)) => {
+                tracing::warn!("Skipping corrupt packet in '{}': {}", display, reason);
+                skipped += 1;
+            }
+            Err(SymphoniaError::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(DecodeError::Decode { path: display, reason: e.to_string() }),
+        }
+    }
+
+    if samples.is_empty() || sample_rate == 0 || channels == 0 {
+        return Err(DecodeError::NoAudio { path: display, skipped });
+    }
+    if skipped > 0 {
+        tracing::warn!("Decoded '{}' with {} corrupt packets skipped", display, skipped);
+    }
+    Ok(DecodedAudio { samples, sample_rate, channels, skipped_packets: skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// MPEG-1 Layer III, 128 kbps, 44.1 kHz, mono: 417-byte frames of 1152
+    /// samples. All-zero side info carries no Huffman data, i.e. silence.
+    fn silent_mp3(frames: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(frames * 417);
+        for _ in 0..frames {
+            data.extend_from_slice(&[0xFF, 0xFB, 0x90, 0xC4]);
+            data.extend(std::iter::repeat_n(0u8, 417 - 4));
+        }
+        data
+    }
+
+    fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn full_box(kind: &[u8; 4], flags: u32, payload: &[u8]) -> Vec<u8> {
+        let mut data = flags.to_be_bytes().to_vec(); // version 0 in the top byte
+        data.extend_from_slice(payload);
+        mp4_box(kind, &data)
+    }
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    /// M4A with `frames` silent AAC-LC mono frames at 16 kHz
+    fn silent_m4a(frames: u32) -> Vec<u8> {
+        // Single channel element, global_gain 0, max_sfb 0, then ID_END
+        const SILENT_FRAME: [u8; 4] = [0x00, 0x00, 0x00, 0x07];
+        const RATE: u32 = 16000;
+        let duration = frames * 1024;
+        let matrix = words(&[0x0001_0000, 0, 0, 0, 0x0001_0000, 0, 0, 0, 0x4000_0000]);
+
+        let moov = |chunk_offset: u32| {
+            let mut mvhd = words(&[0, 0, RATE, duration, 0x0001_0000]);
+            mvhd.extend_from_slice(&[0x01, 0x00]);
+            mvhd.extend_from_slice(&[0; 10]);
+            mvhd.extend_from_slice(&matrix);
+            mvhd.extend_from_slice(&[0; 24]);
+            mvhd.extend_from_slice(&words(&[2]));
+
+            let mut tkhd = words(&[0, 0, 1, 0, duration, 0, 0]);
+            tkhd.extend_from_slice(&[0, 0, 0, 0, 0x01, 0x00, 0, 0]);
+            tkhd.extend_from_slice(&matrix);
+            tkhd.extend_from_slice(&words(&[0, 0]));
+
+            let mut mdhd = words(&[0, 0, RATE, duration]);
+            mdhd.extend_from_slice(&[0x55, 0xC4, 0, 0]);
+            let mut hdlr = words(&[0]);
+            hdlr.extend_from_slice(b"soun");
+            hdlr.extend_from_slice(&words(&[0, 0, 0]));
+            hdlr.extend_from_slice(b"SoundHandler\0");
+
+            // AudioSpecificConfig: AAC-LC, 16 kHz, one channel
+            let esds = full_box(b"esds", 0, &[
+                0x03, 25, 0x00, 0x01, 0x00,
+                0x04, 17, 0x40, 0x15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0x05, 2, 0x14, 0x08,
+                0x06, 1, 0x02,
+            ]);
+            let mut mp4a = vec![0; 6];
+            mp4a.extend_from_slice(&[0, 1]);
+            mp4a.extend_from_slice(&[0; 8]);
+            mp4a.extend_from_slice(&[0, 1, 0, 16, 0, 0, 0, 0]);
+            mp4a.extend_from_slice(&(RATE << 16).to_be_bytes());
+            mp4a.extend_from_slice(&esds);
+            let mut stsd = words(&[1]);
+            stsd.extend_from_slice(&mp4_box(b"mp4a", &mp4a));
+
+            let stbl = [
+                full_box(b"stsd", 0, &stsd),
+                full_box(b"stts", 0, &words(&[1, frames, 1024])),
+                full_box(b"stsc", 0, &words(&[1, 1, frames, 1])),
+                full_box(b"stsz", 0, &words(&[SILENT_FRAME.len() as u32, frames])),
+                full_box(b"stco", 0, &words(&[1, chunk_offset])),
+            ].concat();
+            let mut dref = words(&[1]);
+            dref.extend_from_slice(&full_box(b"url ", 1, &[]));
+            let minf = [
+                full_box(b"smhd", 0, &[0; 4]),
+                mp4_box(b"dinf", &full_box(b"dref", 0, &dref)),
+                mp4_box(b"stbl", &stbl),
+            ].concat();
+            let mdia = [full_box(b"mdhd", 0, &mdhd), full_box(b"hdlr", 0, &hdlr), mp4_box(b"minf", &minf)].concat();
+            let trak = [full_box(b"tkhd", 3, &tkhd), mp4_box(b"mdia", &mdia)].concat();
+            mp4_box(b"moov", &[full_box(b"mvhd", 0, &mvhd), mp4_box(b"trak", &trak)].concat())
+        };
+
+        let mut ftyp = b"M4A ".to_vec();
+        ftyp.extend_from_slice(&words(&[0]));
+        ftyp.extend_from_slice(b"M4A mp42isom");
+        let ftyp = mp4_box(b"ftyp", &ftyp);
+        // The chunk offset does not change the size of the moov box
+        let chunk_offset = (ftyp.len() + moov(0).len() + 8) as u32;
+        let mdat = mp4_box(b"mdat", &SILENT_FRAME.repeat(frames as usize));
+        [ftyp, moov(chunk_offset), mdat].concat()
+    }
+
+    fn write(dir: &TempDir, name: &str, data: &[u8]) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_decodes_mp3() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "meeting.mp3", &silent_mp3(40));
+
+        let decoded = decode_audio_file(&path).unwrap();
+        assert_eq!(decoded.sample_rate, 44100);
+        assert_eq!(decoded.channels, 1);
+        let expected = 40.0 * 1152.0 / 44100.0;
+        assert!((decoded.duration_seconds() - expected).abs() < 0.1, "duration {}", decoded.duration_seconds());
+        assert!(decoded.samples.iter().all(|s| s.abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_decodes_m4a() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "meeting.m4a", &silent_m4a(50));
+
+        let decoded = decode_audio_file(&path).unwrap();
+        assert_eq!(decoded.sample_rate, 16000);
+        assert_eq!(decoded.channels, 1);
+        let expected = 50.0 * 1024.0 / 16000.0;
+        assert!((decoded.duration_seconds() - expected).abs() < 0.1, "duration {}", decoded.duration_seconds());
+    }
+
+    #[test]
+    fn test_malformed_file_is_a_descriptive_error() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "notes.mp3", b"This is a text file that someone renamed to .mp3\n");
+
+        let error = decode_audio_file(&path).unwrap_err();
+        assert!(matches!(error, DecodeError::UnrecognizedFormat { .. } | DecodeError::NoAudio { .. }), "{}", error);
+        assert!(error.to_string().contains("notes.mp3"));
+
+        let empty = write(&dir, "empty.m4a", &[]);
+        assert!(decode_audio_file(&empty).is_err());
+        assert!(matches!(decode_audio_file(&dir.path().join("missing.mp3")), Err(DecodeError::Open { .. })));
+    }
+
+    #[test]
+    fn test_truncated_file_keeps_decoded_audio() {
+        let dir = TempDir::new().unwrap();
+        let mut data = silent_mp3(40);
+        data.truncate(20 * 417 + 100);
+        let path = write(&dir, "cut.mp3", &data);
+
+        let decoded = decode_audio_file(&path).unwrap();
+        let duration = decoded.duration_seconds();
+        assert!(duration > 0.3 && duration < 40.0 * 1152.0 / 44100.0, "duration {}", duration);
+    }
+}
@@ -0,0 +1,208 @@
+//! Audio level metering
+//!
+//! Peak and RMS levels in dBFS for the input meter, with clipping detection
+//! and a running noise-floor estimate for an SNR hint. A chunk counts as
+//! clipped when at least two consecutive samples of a channel sit at full
+//! scale: a clean full-scale sine touches ±1.0 on single samples, a clipped
+//! waveform stays flat there.
+
+use serde::{Deserialize, Serialize};
+
+/// Reported for digital silence instead of negative infinity
+pub const MIN_DBFS: f32 = -120.0;
+/// Magnitude treated as full scale
+pub const FULL_SCALE: f32 = 0.999;
+/// Meter level per unit of the configured VAD threshold: the default 0.5
+/// maps to 0.015, the speech threshold used before it was configurable
+pub const VAD_LEVEL_SCALE: f32 = 0.03;
+
+/// Amplitude (0..1) as dBFS, floored at [`MIN_DBFS`]
+pub fn to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 || !amplitude.is_finite() {
+        return MIN_DBFS;
+    }
+    (20.0 * amplitude.log10()).max(MIN_DBFS)
+}
+
+/// Level of one channel over one chunk
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LevelReading {
+    #[serde(rename = "peakDbfs")]
+    pub peak_dbfs: f32,
+    #[serde(rename = "rmsDbfs")]
+    pub rms_dbfs: f32,
+    pub clipping: bool,
+    /// Linear RMS (0..1)
+    #[serde(skip)]
+    pub rms: f32,
+}
+
+impl LevelReading {
+    /// The former meter value: RMS scaled by 10 and capped at 1.0. Kept for
+    /// the `level` event field and the silence thresholds tuned against it.
+    pub fn legacy_level(&self) -> f32 {
+        (self.rms * 10.0).min(1.0)
+    }
+}
+
+/// Meter level (`legacy_level` scale) above which a chunk counts as speech
+pub fn vad_level_threshold(vad_threshold: f32) -> f32 {
+    vad_threshold.clamp(0.0, 1.0) * VAD_LEVEL_SCALE
+}
+
+/// Meter level expressed on the VAD threshold's 0-1 scale, for RMS gating:
+/// `level_probability(level) > t` exactly when `level > vad_level_threshold(t)`
+pub fn level_probability(level: f32) -> f32 {
+    (level / VAD_LEVEL_SCALE).clamp(0.0, 1.0)
+}
+
+/// Measure a mono signal
+pub fn measure(samples: &[f32]) -> LevelReading {
+    measure_strided(samples, 0, 1)
+}
+
+/// Measure each channel of an interleaved signal
+pub fn measure_channels(interleaved: &[f32], channels: u8) -> Vec<LevelReading> {
+    let channels = channels.max(1) as usize;
+    (0..channels).map(|channel| measure_strided(interleaved, channel, channels)).collect()
+}
+
+fn measure_strided(samples: &[f32], offset: usize, stride: usize) -> LevelReading {
+    let mut peak = 0.0f32;
+    let mut sum_of_squares = 0.0f64;
+    let mut count = 0usize;
+    let mut full_scale_run = 0usize;
+    let mut clipping = false;
+    for &sample in samples.iter().skip(offset).step_by(stride) {
+        let magnitude = sample.abs();
+        peak = peak.max(magnitude);
+        sum_of_squares += (sample as f64) * (sample as f64);
+        count += 1;
+        if magnitude >= FULL_SCALE {
+            full_scale_run += 1;
+            clipping |= full_scale_run >= 2;
+        } else {
+            full_scale_run = 0;
+        }
+    }
+    let rms = if count > 0 { (sum_of_squares / count as f64).sqrt() as f32 } else { 0.0 };
+    LevelReading {
+        peak_dbfs: to_dbfs(peak),
+        rms_dbfs: to_dbfs(rms),
+        clipping,
+        rms,
+    }
+}
+
+/// Tracks the background level: drops to quieter chunks quickly and rises
+/// slowly, so speech barely moves it
+#[derive(Debug, Clone, Default)]
+pub struct NoiseFloorEstimator {
+    floor_dbfs: Option<f32>,
+}
+
+impl NoiseFloorEstimator {
+    /// Share of the distance to a louder chunk the floor moves per chunk
+    const RISE: f32 = 0.01;
+    /// Share of the distance to a quieter chunk the floor moves per chunk
+    const FALL: f32 = 0.5;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn observe(&mut self, rms_dbfs: f32) -> f32 {
+        let floor = match self.floor_dbfs {
+            None => rms_dbfs,
+            Some(floor) if rms_dbfs < floor => floor + (rms_dbfs - floor) * Self::FALL,
+            Some(floor) => floor + (rms_dbfs - floor) * Self::RISE,
+        };
+        self.floor_dbfs = Some(floor);
+        floor
+    }
+
+    pub fn floor_dbfs(&self) -> Option<f32> {
+        self.floor_dbfs
+    }
+
+    /// Level above the noise floor (dB)
+    pub fn snr_db(&self, rms_dbfs: f32) -> Option<f32> {
+        self.floor_dbfs.map(|floor| (rms_dbfs - floor).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, frequency: f32, rate: f32, len: usize) -> Vec<f32> {
+        (0..len).map(|n| amplitude * (2.0 * std::f32::consts::PI * frequency * n as f32 / rate).sin()).collect()
+    }
+
+    #[test]
+    fn test_full_scale_sine() {
+        let reading = measure(&sine(1.0, 1000.0, 16000.0, 1600));
+        assert!(reading.peak_dbfs.abs() < 0.01, "peak {}", reading.peak_dbfs);
+        assert!((reading.rms_dbfs + 3.01).abs() < 0.05, "rms {}", reading.rms_dbfs);
+        assert!(!reading.clipping);
+        assert_eq!(reading.legacy_level(), 1.0);
+    }
+
+    #[test]
+    fn test_silence() {
+        let reading = measure(&[0.0; 1600]);
+        assert_eq!(reading.peak_dbfs, MIN_DBFS);
+        assert_eq!(reading.rms_dbfs, MIN_DBFS);
+        assert!(!reading.clipping);
+        assert_eq!(reading.legacy_level(), 0.0);
+        assert_eq!(measure(&[]).rms_dbfs, MIN_DBFS);
+    }
+
+    #[test]
+    fn test_clipped_square_wave() {
+        let square: Vec<f32> = (0..1600).map(|n| if (n / 8) % 2 == 0 { 1.0 } else { -1.0 }).collect();
+        let reading = measure(&square);
+        assert!(reading.clipping);
+        assert!(reading.peak_dbfs.abs() < 0.01);
+        assert!(reading.rms_dbfs.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_per_channel_levels_before_downmix() {
+        // Left at full scale and clipping, right at a quarter amplitude
+        let interleaved: Vec<f32> = (0..3200).map(|i| if i % 2 == 0 { 1.0 } else { 0.25 }).collect();
+        let channels = measure_channels(&interleaved, 2);
+        assert_eq!(channels.len(), 2);
+        assert!(channels[0].clipping);
+        assert!(!channels[1].clipping);
+        assert!((channels[1].peak_dbfs + 12.04).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_vad_threshold_mapping() {
+        assert!((vad_level_threshold(0.5) - 0.015).abs() < 1e-6);
+        assert_eq!(vad_level_threshold(0.0), 0.0);
+        assert_eq!(vad_level_threshold(4.0), VAD_LEVEL_SCALE);
+        // A quiet microphone: -60 dBFS RMS speech passes once the threshold is lowered
+        let quiet = measure(&[0.001, -0.001, 0.001, -0.001]);
+        assert!(quiet.legacy_level() < vad_level_threshold(0.5));
+        assert!(quiet.legacy_level() > vad_level_threshold(0.2));
+        assert!(level_probability(quiet.legacy_level()) < 0.5);
+        assert!(level_probability(quiet.legacy_level()) > 0.2);
+        assert_eq!(level_probability(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_noise_floor_follows_background_not_speech() {
+        let mut estimator = NoiseFloorEstimator::new();
+        for _ in 0..50 {
+            estimator.observe(-60.0);
+        }
+        for _ in 0..20 {
+            estimator.observe(-20.0);
+        }
+        let floor = estimator.floor_dbfs().unwrap();
+        assert!(floor < -50.0, "floor {}", floor);
+        assert!(estimator.snr_db(-20.0).unwrap() > 30.0);
+    }
+}
@@ -0,0 +1,196 @@
+//! Microphone + system audio mixing
+//!
+//! In mixed capture two streams feed one channel. Their chunks are queued per
+//! source and summed sample by sample once both have audio; a source that
+//! stops delivering (drop, disconnect) is treated as silence after a short lag
+//! so the other keeps flowing. Each mixed chunk is tagged with the source that
+//! carried most of its energy.
+
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+use crate::audio::types::{AudioData, AudioSource};
+
+/// How far one source may run ahead before the other counts as stalled (ms)
+pub const DEFAULT_MAX_LAG_MS: u32 = 500;
+/// Share of a buffer's microphone-dominated audio for attributing it to the local user
+pub const MIC_ATTRIBUTION_RATIO: f32 = 0.8;
+/// Speaker id given to segments spoken into the local microphone
+pub const LOCAL_SPEAKER_ID: &str = "me";
+
+#[derive(Debug, Default)]
+struct SourceQueue {
+    samples: VecDeque<f32>,
+    timestamp: Option<SystemTime>,
+}
+
+/// Mixes mono chunks from the microphone and system audio at one sample rate
+#[derive(Debug)]
+pub struct SourceMixer {
+    sample_rate: u32,
+    max_lag_samples: usize,
+    microphone: SourceQueue,
+    system: SourceQueue,
+}
+
+impl SourceMixer {
+    pub fn new(sample_rate: u32, max_lag_ms: u32) -> Self {
+        Self {
+            sample_rate,
+            max_lag_samples: (sample_rate as u64 * max_lag_ms as u64 / 1000) as usize,
+            microphone: SourceQueue::default(),
+            system: SourceQueue::default(),
+        }
+    }
+
+    /// Queue a chunk; returns the mixed chunks that became ready
+    pub fn push(&mut self, chunk: AudioData) -> Vec<AudioData> {
+        let queue = match chunk.source_channel {
+            AudioSource::System => &mut self.system,
+            _ => &mut self.microphone,
+        };
+        queue.timestamp.get_or_insert(chunk.timestamp);
+        queue.samples.extend(chunk.samples);
+
+        let mut ready = Vec::new();
+        let both = self.microphone.samples.len().min(self.system.samples.len());
+        if both > 0 {
+            ready.push(self.mix(both, both));
+        }
+        // One source stalled: let the other through on its own
+        if self.system.samples.is_empty() && self.microphone.samples.len() >= self.max_lag_samples {
+            ready.push(self.mix(self.microphone.samples.len(), 0));
+        } else if self.microphone.samples.is_empty() && self.system.samples.len() >= self.max_lag_samples {
+            ready.push(self.mix(0, self.system.samples.len()));
+        }
+        ready
+    }
+
+    fn mix(&mut self, mic_count: usize, system_count: usize) -> AudioData {
+        let timestamp = [self.microphone.timestamp, self.system.timestamp]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or_else(SystemTime::now);
+        let mic: Vec<f32> = self.microphone.samples.drain(..mic_count).collect();
+        let system: Vec<f32> = self.system.samples.drain(..system_count).collect();
+        for queue in [&mut self.microphone, &mut self.system] {
+            if queue.samples.is_empty() {
+                queue.timestamp = None;
+            }
+        }
+
+        let energy = |samples: &[f32]| samples.iter().map(|s| s * s).sum::<f32>();
+        let source_channel = if energy(&mic) >= energy(&system) {
+            AudioSource::Microphone
+        } else {
+            AudioSource::System
+        };
+        let samples: Vec<f32> = (0..mic_count.max(system_count))
+            .map(|i| (mic.get(i).unwrap_or(&0.0) + system.get(i).unwrap_or(&0.0)).clamp(-1.0, 1.0))
+            .collect();
+
+        AudioData {
+            duration_seconds: samples.len() as f32 / self.sample_rate as f32,
+            samples,
+            sample_rate: self.sample_rate,
+            channels: 1,
+            timestamp,
+            source_channel,
+        }
+    }
+}
+
+/// Tracks which source dominated the audio buffered for one transcription
+#[derive(Debug, Default, Clone)]
+pub struct SourceAttribution {
+    microphone_samples: usize,
+    total_samples: usize,
+}
+
+impl SourceAttribution {
+    pub fn observe(&mut self, chunk: &AudioData) {
+        if chunk.source_channel == AudioSource::Microphone {
+            self.microphone_samples += chunk.samples.len();
+        }
+        self.total_samples += chunk.samples.len();
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// The local speaker id when the microphone carried the buffered speech
+    pub fn speaker_override(&self) -> Option<&'static str> {
+        let ratio = self.microphone_samples as f32 / self.total_samples.max(1) as f32;
+        (self.total_samples > 0 && ratio >= MIC_ATTRIBUTION_RATIO).then_some(LOCAL_SPEAKER_ID)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(source: AudioSource, value: f32, len: usize) -> AudioData {
+        AudioData {
+            samples: vec![value; len],
+            sample_rate: 16000,
+            channels: 1,
+            timestamp: SystemTime::now(),
+            source_channel: source,
+            duration_seconds: len as f32 / 16000.0,
+        }
+    }
+
+    #[test]
+    fn test_sources_are_summed_and_tagged_by_energy() {
+        let mut mixer = SourceMixer::new(16000, DEFAULT_MAX_LAG_MS);
+        assert!(mixer.push(chunk(AudioSource::Microphone, 0.5, 1600)).is_empty());
+
+        let ready = mixer.push(chunk(AudioSource::System, 0.1, 1000));
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].samples.len(), 1000);
+        assert!((ready[0].samples[0] - 0.6).abs() < 1e-6);
+        assert_eq!(ready[0].source_channel, AudioSource::Microphone);
+
+        // Sums are clamped
+        let ready = mixer.push(chunk(AudioSource::System, 0.9, 600));
+        assert!(ready[0].samples.iter().all(|s| *s <= 1.0));
+    }
+
+    #[test]
+    fn test_stalled_source_does_not_block_the_other() {
+        let mut mixer = SourceMixer::new(16000, DEFAULT_MAX_LAG_MS);
+        let mut emitted = 0;
+        // System audio disconnected: only the microphone delivers
+        for _ in 0..10 {
+            emitted += mixer.push(chunk(AudioSource::Microphone, 0.2, 1600))
+                .iter()
+                .map(|c| c.samples.len())
+                .sum::<usize>();
+        }
+        assert!(emitted >= 16000 - 8000, "emitted {}", emitted);
+
+        // And the other way round
+        let mut mixer = SourceMixer::new(16000, DEFAULT_MAX_LAG_MS);
+        let ready: Vec<AudioData> = (0..6).flat_map(|_| mixer.push(chunk(AudioSource::System, 0.2, 1600))).collect();
+        assert!(!ready.is_empty());
+        assert!(ready.iter().all(|c| c.source_channel == AudioSource::System));
+    }
+
+    #[test]
+    fn test_microphone_dominated_buffer_is_attributed_to_me() {
+        let mut attribution = SourceAttribution::default();
+        assert_eq!(attribution.speaker_override(), None);
+
+        attribution.observe(&chunk(AudioSource::Microphone, 0.3, 9000));
+        attribution.observe(&chunk(AudioSource::System, 0.3, 1000));
+        assert_eq!(attribution.speaker_override(), Some(LOCAL_SPEAKER_ID));
+
+        attribution.observe(&chunk(AudioSource::System, 0.3, 4000));
+        assert_eq!(attribution.speaker_override(), None);
+
+        attribution.reset();
+        assert_eq!(attribution.speaker_override(), None);
+    }
+}
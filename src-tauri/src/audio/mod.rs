@@ -1,12 +1,29 @@
 //! Audio processing module
 //! 
-//! Provides audio capture, voice activity detection, resampling, device profiles, and related functionality.
+//! Provides audio capture, voice activity detection, resampling, device profiles, file decoding, and related functionality.
 
 pub mod capture;
+pub mod capture_simulator;
 pub mod types;
 pub mod vad;
+pub mod energy_vad;
 pub mod resampler;
 pub mod device_profiles;
+pub mod system_audio;
+pub mod recording_writer;
+pub mod peaks;
+pub mod file_decoder;
+pub mod mixer;
+pub mod reconnect;
+pub mod levels;
+pub mod utterance;
+pub mod backpressure;
+pub mod permissions;
+pub mod device_watcher;
+pub mod noise_suppression;
+pub mod agc;
+pub mod ring_buffer;
+pub mod segment_audio;
 
 pub use types::*;
 pub use resampler::{AudioResampler, ResamplerUtils, ResamplingQuality};
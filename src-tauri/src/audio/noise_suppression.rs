@@ -0,0 +1,444 @@
+//! Noise suppression before transcription
+//!
+//! Laptop microphones in echoey rooms pick up rumble and a steady noise bed
+//! that Whisper turns into garbled words. The optional preprocessing stage
+//! removes low-frequency rumble with a high-pass filter and gates the
+//! spectrum against a noise profile learned from the quiet frames of the first
+//! second, so both transcription and diarization get cleaner audio. With the
+//! `rnnoise` feature an RNNoise denoiser runs after the gate.
+//!
+//! The spectral gate works on overlapping frames and delays the audio by one
+//! frame (32 ms at 16 kHz); every call returns as many samples as it was given.
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Cutoff of the rumble filter
+pub const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+/// Audio the noise profile is averaged over
+pub const NOISE_PROFILE_SECONDS: f32 = 1.0;
+
+const FRAME_SIZE: usize = 512;
+const HOP_SIZE: usize = FRAME_SIZE / 2;
+/// Bins this far above the noise profile pass the gate (about 6 dB)
+const GATE_RATIO: f32 = 2.0;
+/// Gain of gated bins (-20 dB)
+const GATE_ATTENUATION: f32 = 0.1;
+/// Frames louder than this are never taken for noise
+const MAX_NOISE_DBFS: f32 = -35.0;
+/// Frames within this many dB of the quietest frame count as noise
+const NOISE_MARGIN_DB: f32 = 6.0;
+
+/// Second-order Butterworth high-pass filter
+#[derive(Debug, Clone)]
+pub struct HighPassFilter {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl HighPassFilter {
+    pub fn new(cutoff_hz: f32, sample_rate: u32) -> Self {
+        let omega = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate.max(1) as f32;
+        let alpha = omega.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = omega.cos();
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: (1.0 + cos) / 2.0 / a0,
+            b1: -(1.0 + cos) / a0,
+            b2: (1.0 + cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            let x = *sample;
+            let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2 - self.a1 * self.y1 - self.a2 * self.y2;
+            self.x2 = self.x1;
+            self.x1 = x;
+            self.y2 = self.y1;
+            self.y1 = y;
+            *sample = y;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.x1 = 0.0;
+        self.x2 = 0.0;
+        self.y1 = 0.0;
+        self.y2 = 0.0;
+    }
+}
+
+/// Spectral noise gate with a noise profile learned from quiet frames
+pub struct SpectralGate {
+    sample_rate: u32,
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    /// Samples not yet covered by a full frame
+    input: Vec<f32>,
+    /// Second half of the previous frame, waiting for the next one to overlap
+    overlap: Vec<f32>,
+    /// Finished samples
+    output: VecDeque<f32>,
+    noise_sum: Vec<f32>,
+    noise_frames: usize,
+    quietest_dbfs: f32,
+    noise_profile: Option<Vec<f32>>,
+    gains: Vec<f32>,
+}
+
+impl SpectralGate {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut planner = FftPlanner::new();
+        // Periodic Hann windows at half-frame hops sum to one, so overlap-add
+        // needs no synthesis window
+        let window = (0..FRAME_SIZE)
+            .map(|n| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / FRAME_SIZE as f32).cos())
+            .collect();
+        let mut gate = Self {
+            sample_rate,
+            fft: planner.plan_fft_forward(FRAME_SIZE),
+            ifft: planner.plan_fft_inverse(FRAME_SIZE),
+            window,
+            input: Vec::new(),
+            overlap: Vec::new(),
+            output: VecDeque::new(),
+            noise_sum: vec![0.0; FRAME_SIZE],
+            noise_frames: 0,
+            quietest_dbfs: f32::INFINITY,
+            noise_profile: None,
+            gains: vec![1.0; FRAME_SIZE],
+        };
+        gate.reset();
+        gate
+    }
+
+    /// Whether enough quiet audio was heard to gate against
+    pub fn has_noise_profile(&self) -> bool {
+        self.noise_profile.is_some()
+    }
+
+    /// Drop buffered audio; the learned noise profile is kept
+    pub fn reset(&mut self) {
+        // A half frame of silence in front covers the first samples with two
+        // frames; another half frame of output keeps every call's output whole
+        self.input = vec![0.0; HOP_SIZE];
+        self.overlap = vec![0.0; FRAME_SIZE - HOP_SIZE];
+        self.output = VecDeque::from(vec![0.0; HOP_SIZE]);
+        self.gains.fill(1.0);
+    }
+
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.input.extend_from_slice(samples);
+        while self.input.len() >= FRAME_SIZE {
+            self.process_frame();
+            self.input.drain(..HOP_SIZE);
+        }
+        let available = self.output.len().min(samples.len());
+        let mut processed: Vec<f32> = vec![0.0; samples.len() - available];
+        processed.extend(self.output.drain(..available));
+        processed
+    }
+
+    fn process_frame(&mut self) {
+        let mut spectrum: Vec<Complex<f32>> = self.input[..FRAME_SIZE].iter()
+            .zip(&self.window)
+            .map(|(sample, weight)| Complex::new(sample * weight, 0.0))
+            .collect();
+        let frame_dbfs = {
+            let energy = self.input[..FRAME_SIZE].iter().map(|s| s * s).sum::<f32>() / FRAME_SIZE as f32;
+            10.0 * energy.max(1e-12).log10()
+        };
+        self.fft.process(&mut spectrum);
+
+        if self.noise_profile.is_none() {
+            self.learn_noise(&spectrum, frame_dbfs);
+        }
+        if let Some(profile) = &self.noise_profile {
+            for ((bin, noise), gain) in spectrum.iter_mut().zip(profile).zip(self.gains.iter_mut()) {
+                let target = if bin.norm() > noise * GATE_RATIO { 1.0 } else { GATE_ATTENUATION };
+                // Open at once, close gradually to avoid musical noise
+                *gain = if target > *gain { target } else { 0.5 * *gain + 0.5 * target };
+                *bin *= *gain;
+            }
+        }
+
+        self.ifft.process(&mut spectrum);
+        let scale = 1.0 / FRAME_SIZE as f32;
+        for (index, value) in spectrum.iter().enumerate() {
+            let sample = value.re * scale;
+            if index < HOP_SIZE {
+                self.output.push_back(self.overlap[index] + sample);
+            } else {
+                self.overlap[index - HOP_SIZE] = sample;
+            }
+        }
+    }
+
+    fn learn_noise(&mut self, spectrum: &[Complex<f32>], frame_dbfs: f32) {
+        if frame_dbfs > MAX_NOISE_DBFS {
+            return;
+        }
+        if frame_dbfs + NOISE_MARGIN_DB < self.quietest_dbfs {
+            // Much quieter than what was taken for noise so far; start over
+            self.noise_sum.fill(0.0);
+            self.noise_frames = 0;
+        }
+        self.quietest_dbfs = self.quietest_dbfs.min(frame_dbfs);
+        if frame_dbfs > self.quietest_dbfs + NOISE_MARGIN_DB {
+            return;
+        }
+        for (sum, bin) in self.noise_sum.iter_mut().zip(spectrum) {
+            *sum += bin.norm();
+        }
+        self.noise_frames += 1;
+        let needed = (self.sample_rate as f32 * NOISE_PROFILE_SECONDS / HOP_SIZE as f32).ceil() as usize;
+        if self.noise_frames >= needed {
+            let frames = self.noise_frames as f32;
+            self.noise_profile = Some(self.noise_sum.iter().map(|sum| sum / frames).collect());
+        }
+    }
+}
+
+/// Time spent preprocessing against the audio it covered
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SuppressionStats {
+    pub processing_time: Duration,
+    pub audio_seconds: f64,
+}
+
+impl SuppressionStats {
+    pub fn record(&mut self, elapsed: Duration, audio_seconds: f64) {
+        self.processing_time += elapsed;
+        self.audio_seconds += audio_seconds.max(0.0);
+    }
+
+    /// Share of one core spent on preprocessing, in percent
+    pub fn cpu_percent(&self) -> f32 {
+        if self.audio_seconds <= 0.0 {
+            return 0.0;
+        }
+        (self.processing_time.as_secs_f64() / self.audio_seconds * 100.0) as f32
+    }
+}
+
+/// High-pass filter, spectral gate and, with the `rnnoise` feature, RNNoise
+pub struct NoiseSuppressor {
+    sample_rate: u32,
+    high_pass: HighPassFilter,
+    gate: SpectralGate,
+    #[cfg(feature = "rnnoise")]
+    rnnoise: Option<rnnoise::RnnoiseStage>,
+    stats: SuppressionStats,
+}
+
+impl NoiseSuppressor {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            high_pass: HighPassFilter::new(HIGH_PASS_CUTOFF_HZ, sample_rate),
+            gate: SpectralGate::new(sample_rate),
+            #[cfg(feature = "rnnoise")]
+            rnnoise: rnnoise::RnnoiseStage::new(sample_rate),
+            stats: SuppressionStats::default(),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn has_noise_profile(&self) -> bool {
+        self.gate.has_noise_profile()
+    }
+
+    pub fn stats(&self) -> SuppressionStats {
+        self.stats
+    }
+
+    /// Drop buffered audio and filter state, e.g. after the stage was bypassed
+    pub fn reset(&mut self) {
+        self.high_pass.reset();
+        self.gate.reset();
+        #[cfg(feature = "rnnoise")]
+        if let Some(stage) = self.rnnoise.as_mut() {
+            stage.reset();
+        }
+    }
+
+    /// Clean a chunk; the result has the same length, delayed by the stage latency
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        let started = Instant::now();
+        let mut filtered = samples.to_vec();
+        self.high_pass.process(&mut filtered);
+        #[allow(unused_mut)]
+        let mut cleaned = self.gate.process(&filtered);
+        #[cfg(feature = "rnnoise")]
+        if let Some(stage) = self.rnnoise.as_mut() {
+            cleaned = stage.process(&cleaned);
+        }
+        self.stats.record(started.elapsed(), samples.len() as f64 / self.sample_rate.max(1) as f64);
+        cleaned
+    }
+}
+
+#[cfg(feature = "rnnoise")]
+mod rnnoise {
+    use nnnoiseless::DenoiseState;
+    use std::collections::VecDeque;
+
+    /// Rate RNNoise runs at
+    const RNNOISE_SAMPLE_RATE: u32 = 48000;
+    /// RNNoise expects 16-bit sample values
+    const SCALE: f32 = 32768.0;
+
+    /// RNNoise over audio upsampled to 48 kHz by an integer factor
+    pub struct RnnoiseStage {
+        state: Box<DenoiseState<'static>>,
+        factor: usize,
+        previous: f32,
+        input: Vec<f32>,
+        output: VecDeque<f32>,
+    }
+
+    impl RnnoiseStage {
+        /// None for rates that do not divide 48 kHz
+        pub fn new(sample_rate: u32) -> Option<Self> {
+            if sample_rate == 0 || RNNOISE_SAMPLE_RATE % sample_rate != 0 {
+                return None;
+            }
+            let mut stage = Self {
+                state: DenoiseState::new(),
+                factor: (RNNOISE_SAMPLE_RATE / sample_rate) as usize,
+                previous: 0.0,
+                input: Vec::new(),
+                output: VecDeque::new(),
+            };
+            stage.reset();
+            Some(stage)
+        }
+
+        pub fn reset(&mut self) {
+            self.previous = 0.0;
+            self.input.clear();
+            self.output = VecDeque::from(vec![0.0; DenoiseState::FRAME_SIZE / self.factor]);
+        }
+
+        pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+            for &sample in samples {
+                for step in 1..=self.factor {
+                    let t = step as f32 / self.factor as f32;
+                    self.input.push((self.previous + (sample - self.previous) * t) * SCALE);
+                }
+                self.previous = sample;
+            }
+            let mut frame = [0.0f32; DenoiseState::FRAME_SIZE];
+            while self.input.len() >= DenoiseState::FRAME_SIZE {
+                self.state.process_frame(&mut frame, &self.input[..DenoiseState::FRAME_SIZE]);
+                self.input.drain(..DenoiseState::FRAME_SIZE);
+                for group in frame.chunks(self.factor) {
+                    self.output.push_back(group.iter().sum::<f32>() / group.len() as f32 / SCALE);
+                }
+            }
+            let available = self.output.len().min(samples.len());
+            let mut processed: Vec<f32> = vec![0.0; samples.len() - available];
+            processed.extend(self.output.drain(..available));
+            processed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    const RATE: u32 = 16000;
+    const CHUNK: usize = 1600;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32).sqrt()
+    }
+
+    fn tone(frequency: f32, amplitude: f32, samples: usize, offset: usize) -> Vec<f32> {
+        (offset..offset + samples)
+            .map(|n| amplitude * (2.0 * std::f32::consts::PI * frequency * n as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    /// 1.5 s of noise only, then 2 s of a 440 Hz tone over the same noise
+    fn noisy_signal() -> (Vec<f32>, Vec<f32>) {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let noise: Vec<f32> = (0..RATE as usize * 7 / 2).map(|_| rng.gen_range(-0.02..0.02)).collect();
+        let speech_start = RATE as usize * 3 / 2;
+        let clean: Vec<f32> = (0..noise.len())
+            .map(|n| if n >= speech_start { tone(440.0, 0.3, 1, n)[0] } else { 0.0 })
+            .collect();
+        let noisy = clean.iter().zip(&noise).map(|(c, n)| c + n).collect();
+        (noisy, clean)
+    }
+
+    fn run(suppressor: &mut NoiseSuppressor, signal: &[f32]) -> Vec<f32> {
+        signal.chunks(CHUNK).flat_map(|chunk| suppressor.process(chunk)).collect()
+    }
+
+    #[test]
+    fn test_noise_floor_drops_after_profile() {
+        let (noisy, _) = noisy_signal();
+        let mut suppressor = NoiseSuppressor::new(RATE);
+        let processed = run(&mut suppressor, &noisy);
+        assert_eq!(processed.len(), noisy.len());
+        assert!(suppressor.has_noise_profile());
+
+        // Noise-only stretch after the profile was learned (1.1-1.5 s)
+        let noise_range = RATE as usize * 11 / 10..RATE as usize * 3 / 2;
+        let before = rms(&noisy[noise_range.clone()]);
+        let after = rms(&processed[noise_range]);
+        assert!(after < before * 0.5, "noise rms {} -> {}", before, after);
+        assert!(suppressor.stats().cpu_percent() > 0.0);
+    }
+
+    #[test]
+    fn test_speech_survives_the_gate() {
+        let (noisy, clean) = noisy_signal();
+        let mut suppressor = NoiseSuppressor::new(RATE);
+        let processed = run(&mut suppressor, &noisy);
+
+        // Compare the tone after the stage latency, away from its onset
+        let speech = RATE as usize * 2..RATE as usize * 3;
+        let delayed = speech.start + FRAME_SIZE..speech.end + FRAME_SIZE;
+        let tone_rms = rms(&clean[speech.clone()]);
+        let kept = rms(&processed[delayed]);
+        assert!(kept > tone_rms * 0.8 && kept < tone_rms * 1.2, "tone rms {} -> {}", tone_rms, kept);
+    }
+
+    #[test]
+    fn test_high_pass_removes_rumble() {
+        let mut filter = HighPassFilter::new(HIGH_PASS_CUTOFF_HZ, RATE);
+        let mut rumble = tone(20.0, 0.5, RATE as usize, 0);
+        filter.process(&mut rumble);
+        assert!(rms(&rumble[RATE as usize / 2..]) < 0.5 * std::f32::consts::FRAC_1_SQRT_2 * 0.1);
+
+        filter.reset();
+        let mut voice = tone(1000.0, 0.5, RATE as usize, 0);
+        filter.process(&mut voice);
+        assert!(rms(&voice[RATE as usize / 2..]) > 0.5 * std::f32::consts::FRAC_1_SQRT_2 * 0.95);
+    }
+}
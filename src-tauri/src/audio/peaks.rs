@@ -0,0 +1,136 @@
+//! Waveform peak envelopes
+//!
+//! Fixed-size min/max envelopes small enough to ship with every transcript
+//! segment so the UI can draw a mini waveform without touching the audio.
+
+/// Number of min/max pairs per segment envelope
+pub const DEFAULT_PEAK_BUCKETS: usize = 64;
+
+/// Min/max pairs over `buckets` equal slices of `samples`. Always returns
+/// exactly `buckets` pairs; slices without samples are `[0.0, 0.0]`.
+pub fn compute_peaks(samples: &[f32], buckets: usize) -> Vec<[f32; 2]> {
+    let mut peaks = vec![[0.0f32; 2]; buckets];
+    if samples.is_empty() || buckets == 0 {
+        return peaks;
+    }
+
+    for (i, peak) in peaks.iter_mut().enumerate() {
+        let start = i * samples.len() / buckets;
+        let end = ((i + 1) * samples.len() / buckets).max(start + 1).min(samples.len());
+        if start >= end {
+            continue;
+        }
+        let slice = &samples[start..end];
+        peak[0] = slice.iter().copied().fold(f32::INFINITY, f32::min);
+        peak[1] = slice.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    }
+    peaks
+}
+
+/// Samples between `start_secs` and `end_secs`, clamped to the available audio
+pub fn samples_for_span(samples: &[f32], sample_rate: u32, start_secs: f32, end_secs: f32) -> &[f32] {
+    let to_index = |secs: f32| ((secs.max(0.0) * sample_rate as f32) as usize).min(samples.len());
+    let start = to_index(start_secs);
+    let end = to_index(end_secs).max(start);
+    &samples[start..end]
+}
+
+/// Envelope of a segment (`startTime`/`endTime` in session seconds) from audio
+/// whose first sample is at `samples_start` on the session clock, such as the
+/// buffer the segment was decoded from
+pub fn segment_peaks(segment: &serde_json::Value, samples: &[f32], sample_rate: u32, samples_start: f64) -> Vec<[f32; 2]> {
+    let start = (segment["startTime"].as_f64().unwrap_or(0.0) - samples_start) as f32;
+    let end = (segment["endTime"].as_f64().unwrap_or(0.0) - samples_start) as f32;
+    compute_peaks(samples_for_span(samples, sample_rate, start, end), DEFAULT_PEAK_BUCKETS)
+}
+
+/// Compact JSON form, rounded to three decimals to keep payloads small
+pub fn peaks_to_json(peaks: &[[f32; 2]]) -> serde_json::Value {
+    let round = |v: f32| (v as f64 * 1000.0).round() / 1000.0;
+    serde_json::Value::Array(
+        peaks.iter()
+            .map(|[min, max]| serde_json::json!([round(*min), round(*max)]))
+            .collect()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::segment_audio;
+
+    /// 440 Hz sine at `amplitude` for `secs` seconds
+    fn sine_burst(amplitude: f32, secs: f32, sample_rate: u32) -> Vec<f32> {
+        (0..(secs * sample_rate as f32) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_sine_burst_envelope_is_symmetric() {
+        let peaks = compute_peaks(&sine_burst(0.5, 1.0, 16000), DEFAULT_PEAK_BUCKETS);
+
+        assert_eq!(peaks.len(), DEFAULT_PEAK_BUCKETS);
+        for [min, max] in peaks {
+            assert!((max - 0.5).abs() < 0.01, "max {}", max);
+            assert!((min + max).abs() < 0.01, "min {} max {}", min, max);
+        }
+    }
+
+    #[test]
+    fn test_envelope_size_is_fixed() {
+        assert_eq!(compute_peaks(&[], 64), vec![[0.0, 0.0]; 64]);
+        // Fewer samples than buckets still yields one pair per bucket
+        let peaks = compute_peaks(&[0.2, -0.4, 0.1], 8);
+        assert_eq!(peaks.len(), 8);
+        assert!(peaks.iter().any(|p| p[0] == -0.4));
+    }
+
+    #[test]
+    fn test_segment_peaks_cover_only_the_segment() {
+        // A buffer starting at 10s of the session: silence, then a burst from 11.0s to 11.5s
+        let sample_rate = 16000;
+        let mut buffer = vec![0.0f32; sample_rate as usize];
+        buffer.extend(sine_burst(0.8, 0.5, sample_rate));
+        buffer.extend(vec![0.0f32; sample_rate as usize]);
+
+        let burst = serde_json::json!({ "startTime": 11.0, "endTime": 11.5 });
+        let silent = serde_json::json!({ "startTime": 10.0, "endTime": 10.9 });
+
+        let peaks = segment_peaks(&burst, &buffer, sample_rate, 10.0);
+        assert!(peaks.iter().all(|[_, max]| (max - 0.8).abs() < 0.02));
+        assert!(segment_peaks(&silent, &buffer, sample_rate, 10.0).iter().all(|p| *p == [0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_lazy_peaks_read_only_the_segment_range() {
+        // A saved recording: silence, then a burst at samples 16000..24000
+        let sample_rate = 16000;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audio.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate, bits_per_sample: 32, sample_format: hound::SampleFormat::Float };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        let mut recording = vec![0.0f32; sample_rate as usize];
+        recording.extend(sine_burst(0.8, 0.5, sample_rate));
+        recording.extend(vec![0.0f32; sample_rate as usize]);
+        for sample in &recording {
+            writer.write_sample(*sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let burst = serde_json::json!({ "startSample": 16000, "endSample": 24000 });
+        let (start, end) = segment_audio::segment_range(&burst).unwrap();
+        let audio = segment_audio::read_samples(&path, start, end).unwrap();
+        assert_eq!(audio.samples.len(), 8000);
+        let peaks = compute_peaks(&audio.samples, DEFAULT_PEAK_BUCKETS);
+        assert!(peaks.iter().all(|[min, max]| (max - 0.8).abs() < 0.02 && (min + max).abs() < 0.02));
+    }
+
+    #[test]
+    fn test_span_is_clamped_to_audio() {
+        let audio = vec![0.1f32; 16000];
+        assert_eq!(samples_for_span(&audio, 16000, 0.5, 5.0).len(), 8000);
+        assert!(samples_for_span(&audio, 16000, 3.0, 4.0).is_empty());
+        assert_eq!(peaks_to_json(&[[-0.12345, 0.5]]), serde_json::json!([[-0.123, 0.5]]));
+    }
+}
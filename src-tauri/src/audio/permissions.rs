@@ -0,0 +1,300 @@
+//! Microphone permission checks
+//!
+//! Queries the OS microphone permission without opening a capture stream, so
+//! a denied permission is reported before a session downloads its model
+//! rather than as a failing stream deep inside start_capture. macOS reports
+//! the AVCaptureDevice authorization status; Windows keeps the privacy
+//! setting in the capability access consent store. Other platforms have no
+//! microphone permission and always report granted.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::audio::system_audio::{NativePlatformProbe, OsFamily, PlatformProbe};
+
+/// How long request_microphone_permission waits for the user to answer the prompt
+pub const PERMISSION_PROMPT_TIMEOUT: Duration = Duration::from_secs(60);
+const PERMISSION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// OS microphone permission for this app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MicrophonePermission {
+    Granted,
+    Denied,
+    /// Not asked yet; the OS prompts on first access
+    Undetermined,
+}
+
+/// Microphone permission queries, mockable in tests
+pub trait MicrophonePermissionProbe {
+    fn os_family(&self) -> OsFamily;
+    /// Current permission, without prompting
+    fn status(&self) -> MicrophonePermission;
+    /// Show the OS prompt when the permission is undetermined; returns
+    /// without waiting for the answer
+    fn prompt(&self);
+}
+
+/// `AVAuthorizationStatus` of AVCaptureDevice for audio
+pub fn macos_permission(authorization_status: isize) -> MicrophonePermission {
+    match authorization_status {
+        0 => MicrophonePermission::Undetermined,
+        3 => MicrophonePermission::Granted,
+        // Restricted (1) by parental controls or MDM is as final as Denied (2)
+        _ => MicrophonePermission::Denied,
+    }
+}
+
+/// Consent store values from the most to the least specific key: the
+/// desktop app setting, the per-user setting and the device-wide setting.
+/// Any "Deny" blocks capture; Windows allows access when nothing is set.
+pub fn windows_permission(values: &[Option<String>]) -> MicrophonePermission {
+    if values.iter().flatten().any(|value| value.eq_ignore_ascii_case("deny")) {
+        MicrophonePermission::Denied
+    } else {
+        MicrophonePermission::Granted
+    }
+}
+
+/// Where to change the permission, shown when it is denied
+pub fn recovery_options(os: OsFamily) -> Vec<String> {
+    match os {
+        OsFamily::MacOS => vec![
+            "Open System Settings > Privacy & Security > Microphone and enable KagiNote".to_string(),
+            "Restart KagiNote after changing the permission".to_string(),
+        ],
+        OsFamily::Windows => vec![
+            "Open Settings > Privacy & security > Microphone".to_string(),
+            "Turn on microphone access and \"Let desktop apps access your microphone\"".to_string(),
+        ],
+        _ => vec![
+            "Check that no system policy blocks access to the microphone".to_string(),
+        ],
+    }
+}
+
+/// Prompt when undetermined and wait until the user answers or `timeout` passes
+pub fn request_permission(probe: &dyn MicrophonePermissionProbe, timeout: Duration, poll_interval: Duration) -> MicrophonePermission {
+    let status = probe.status();
+    if status != MicrophonePermission::Undetermined {
+        return status;
+    }
+    probe.prompt();
+    let started = std::time::Instant::now();
+    loop {
+        let status = probe.status();
+        if status != MicrophonePermission::Undetermined || started.elapsed() >= timeout {
+            return status;
+        }
+        std::thread::sleep(poll_interval);
+    }
+}
+
+/// Blocking: polls until the prompt is answered
+pub fn request_native_permission() -> MicrophonePermission {
+    request_permission(&NativeMicrophonePermission, PERMISSION_PROMPT_TIMEOUT, PERMISSION_POLL_INTERVAL)
+}
+
+/// Permission checks backed by the real operating system
+pub struct NativeMicrophonePermission;
+
+impl MicrophonePermissionProbe for NativeMicrophonePermission {
+    fn os_family(&self) -> OsFamily {
+        NativePlatformProbe.os_family()
+    }
+
+    fn status(&self) -> MicrophonePermission {
+        #[cfg(target_os = "macos")]
+        {
+            macos_permission(macos::authorization_status())
+        }
+        #[cfg(target_os = "windows")]
+        {
+            windows_permission(&windows::consent_values())
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        {
+            MicrophonePermission::Granted
+        }
+    }
+
+    fn prompt(&self) {
+        // macOS prompts when an input stream is first opened; the stream is
+        // dropped right away. Windows has no prompt for desktop apps.
+        #[cfg(target_os = "macos")]
+        {
+            use cpal::traits::{DeviceTrait, HostTrait};
+
+            let Some(device) = cpal::default_host().default_input_device() else {
+                return;
+            };
+            let Ok(config) = device.default_input_config() else {
+                return;
+            };
+            let stream = device.build_input_stream(
+                &config.into(),
+                |_: &[f32], _: &cpal::InputCallbackInfo| {},
+                |e| tracing::debug!("Permission prompt stream error: {}", e),
+                None,
+            );
+            if let Err(e) = stream {
+                tracing::debug!("Failed to open a stream for the permission prompt: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::{c_char, c_void};
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: *const c_void;
+    }
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    type MediaTypeMsgSend = unsafe extern "C" fn(*mut c_void, *mut c_void, *const c_void) -> isize;
+
+    /// `[AVCaptureDevice authorizationStatusForMediaType:AVMediaTypeAudio]`
+    pub fn authorization_status() -> isize {
+        unsafe {
+            let class = objc_getClass(b"AVCaptureDevice\0".as_ptr() as *const c_char);
+            let selector = sel_registerName(b"authorizationStatusForMediaType:\0".as_ptr() as *const c_char);
+            if class.is_null() || selector.is_null() {
+                return 0;
+            }
+            // objc_msgSend is called through a pointer of the method's real signature
+            let send = std::mem::transmute::<unsafe extern "C" fn(), MediaTypeMsgSend>(objc_msgSend);
+            send(class, selector, AVMediaTypeAudio)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::ffi::c_void;
+
+    const CONSENT_STORE: &str = r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone";
+    const RRF_RT_REG_SZ: u32 = 0x2;
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn RegGetValueW(
+            hkey: *mut c_void,
+            subkey: *const u16,
+            value: *const u16,
+            flags: u32,
+            value_type: *mut u32,
+            data: *mut c_void,
+            data_len: *mut u32,
+        ) -> i32;
+    }
+
+    fn hkey(predefined: u32) -> *mut c_void {
+        // Predefined keys are sign-extended handles
+        predefined as i32 as isize as *mut c_void
+    }
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn read_string(root: *mut c_void, subkey: &str) -> Option<String> {
+        let subkey = wide(subkey);
+        let value = wide("Value");
+        let mut buffer = [0u16; 64];
+        let mut len = std::mem::size_of_val(&buffer) as u32;
+        let status = unsafe {
+            RegGetValueW(root, subkey.as_ptr(), value.as_ptr(), RRF_RT_REG_SZ, std::ptr::null_mut(),
+                         buffer.as_mut_ptr() as *mut c_void, &mut len)
+        };
+        if status != 0 {
+            return None;
+        }
+        let chars = (len as usize / 2).saturating_sub(1);
+        Some(String::from_utf16_lossy(&buffer[..chars.min(buffer.len())]))
+    }
+
+    /// Desktop app, per-user and device-wide consent values
+    pub fn consent_values() -> Vec<Option<String>> {
+        let current_user = hkey(0x8000_0001);
+        let local_machine = hkey(0x8000_0002);
+        vec![
+            read_string(current_user, &format!(r"{}\NonPackaged", CONSENT_STORE)),
+            read_string(current_user, CONSENT_STORE),
+            read_string(local_machine, CONSENT_STORE),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct MockPermission {
+        answers: Vec<MicrophonePermission>,
+        checks: Cell<usize>,
+        prompted: Cell<bool>,
+    }
+
+    impl MicrophonePermissionProbe for MockPermission {
+        fn os_family(&self) -> OsFamily {
+            OsFamily::MacOS
+        }
+
+        fn status(&self) -> MicrophonePermission {
+            let index = self.checks.get().min(self.answers.len() - 1);
+            self.checks.set(self.checks.get() + 1);
+            self.answers[index]
+        }
+
+        fn prompt(&self) {
+            self.prompted.set(true);
+        }
+    }
+
+    fn mock(answers: &[MicrophonePermission]) -> MockPermission {
+        MockPermission { answers: answers.to_vec(), checks: Cell::new(0), prompted: Cell::new(false) }
+    }
+
+    #[test]
+    fn test_platform_states() {
+        assert_eq!(macos_permission(0), MicrophonePermission::Undetermined);
+        assert_eq!(macos_permission(1), MicrophonePermission::Denied);
+        assert_eq!(macos_permission(2), MicrophonePermission::Denied);
+        assert_eq!(macos_permission(3), MicrophonePermission::Granted);
+
+        assert_eq!(windows_permission(&[None, None, None]), MicrophonePermission::Granted);
+        assert_eq!(windows_permission(&[Some("Allow".to_string()), None, Some("Allow".to_string())]), MicrophonePermission::Granted);
+        // Desktop apps switched off while the device setting allows access
+        assert_eq!(windows_permission(&[Some("Deny".to_string()), Some("Allow".to_string()), None]), MicrophonePermission::Denied);
+        assert_eq!(windows_permission(&[None, None, Some("Deny".to_string())]), MicrophonePermission::Denied);
+    }
+
+    #[test]
+    fn test_request_waits_for_the_answer() {
+        use MicrophonePermission::*;
+
+        let probe = mock(&[Undetermined, Undetermined, Undetermined, Granted]);
+        assert_eq!(request_permission(&probe, Duration::from_secs(5), Duration::ZERO), Granted);
+        assert!(probe.prompted.get());
+
+        // A settled permission is returned without prompting
+        let probe = mock(&[Denied]);
+        assert_eq!(request_permission(&probe, Duration::from_secs(5), Duration::ZERO), Denied);
+        assert!(!probe.prompted.get());
+
+        let probe = mock(&[Undetermined]);
+        assert_eq!(request_permission(&probe, Duration::ZERO, Duration::ZERO), Undetermined);
+        assert!(!recovery_options(probe.os_family()).is_empty());
+    }
+}
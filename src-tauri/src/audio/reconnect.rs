@@ -0,0 +1,114 @@
+//! Capture device outages
+//!
+//! When the capture device disappears mid-session (USB microphone unplugged,
+//! Bluetooth headset out of range) the session keeps running and polls for
+//! the device, or the default device, to come back. The audio timeline does
+//! not advance during the outage; the gap is reported with the stream offset
+//! at which it happened.
+
+use crate::audio::types::AudioSource;
+use std::time::{Duration, Instant};
+
+/// How often a lost device is looked for
+pub const RECONNECT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// No chunks for this long from a running microphone stream counts as a lost device
+pub const CAPTURE_STALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A capture device outage in progress
+#[derive(Debug, Clone)]
+pub struct DeviceOutage {
+    started: Instant,
+    /// Stream offset (seconds) at which audio stopped
+    stream_offset: f64,
+    attempts: u32,
+    last_attempt: Option<Instant>,
+    poll_interval: Duration,
+}
+
+impl DeviceOutage {
+    pub fn new(started: Instant, stream_offset: f64) -> Self {
+        Self::with_poll_interval(started, stream_offset, RECONNECT_POLL_INTERVAL)
+    }
+
+    pub fn with_poll_interval(started: Instant, stream_offset: f64, poll_interval: Duration) -> Self {
+        Self {
+            started,
+            stream_offset,
+            attempts: 0,
+            last_attempt: None,
+            poll_interval,
+        }
+    }
+
+    /// A reconnect should be tried now; the first try waits one interval so
+    /// the system has time to re-enumerate devices
+    pub fn attempt_due(&self, now: Instant) -> bool {
+        let since = self.last_attempt.unwrap_or(self.started);
+        now.saturating_duration_since(since) >= self.poll_interval
+    }
+
+    pub fn record_attempt(&mut self, now: Instant) {
+        self.attempts += 1;
+        self.last_attempt = Some(now);
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn stream_offset(&self) -> f64 {
+        self.stream_offset
+    }
+
+    /// Wall-clock length of the outage so far
+    pub fn gap(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.started)
+    }
+}
+
+/// Whether a running stream of `source` that last delivered at `last_chunk`
+/// has stalled. A system audio (loopback) stream delivers nothing while
+/// nothing is playing, so silence there is never a lost device; only the
+/// device-lost signal of the capture counts for it.
+pub fn is_stalled(source: AudioSource, last_chunk: Instant, now: Instant) -> bool {
+    source != AudioSource::System && now.saturating_duration_since(last_chunk) >= CAPTURE_STALL_TIMEOUT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attempts_are_spaced_by_the_poll_interval() {
+        let start = Instant::now();
+        let mut outage = DeviceOutage::with_poll_interval(start, 42.5, Duration::from_secs(3));
+
+        assert!(!outage.attempt_due(start));
+        assert!(!outage.attempt_due(start + Duration::from_secs(2)));
+        assert!(outage.attempt_due(start + Duration::from_secs(3)));
+
+        outage.record_attempt(start + Duration::from_secs(3));
+        assert!(!outage.attempt_due(start + Duration::from_secs(5)));
+        assert!(outage.attempt_due(start + Duration::from_secs(6)));
+        assert_eq!(outage.attempts(), 1);
+    }
+
+    #[test]
+    fn test_gap_and_offset_are_kept() {
+        let start = Instant::now();
+        let outage = DeviceOutage::new(start, 120.0);
+        assert_eq!(outage.stream_offset(), 120.0);
+        assert_eq!(outage.gap(start + Duration::from_secs(17)), Duration::from_secs(17));
+        // A clock reading from before the outage never underflows
+        assert_eq!(outage.gap(start), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_stall_detection() {
+        let last_chunk = Instant::now();
+        assert!(!is_stalled(AudioSource::Microphone, last_chunk, last_chunk + Duration::from_secs(1)));
+        assert!(is_stalled(AudioSource::Microphone, last_chunk, last_chunk + CAPTURE_STALL_TIMEOUT));
+        // A meeting app that plays nothing for a minute leaves the loopback stream silent
+        assert!(!is_stalled(AudioSource::System, last_chunk, last_chunk + Duration::from_secs(60)));
+    }
+}
@@ -0,0 +1,763 @@
+//! Resilient session audio recording
+//!
+//! Session audio may be written to a network share while the database stays
+//! local. Writes to the destination happen on a worker thread so a stalled share
+//! never blocks capture. When a write stalls beyond the timeout, new samples
+//! spill to a local file and are copied to the destination in order once it
+//! recovers. If the destination never comes back, unwritten audio is kept locally.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Most samples a 16-bit WAV file can hold; its data size field is 32-bit
+pub const WAV_MAX_SAMPLES: u64 = (u32::MAX as u64 - 44) / 2;
+/// Share of the sample limit at which the recording reports it is close
+pub const LIMIT_WARNING_RATIO: f64 = 0.9;
+
+/// Destination for recorded samples (a WAV file on disk or a network share)
+pub trait SampleSink: Send {
+    fn write_samples(&mut self, samples: &[f32]) -> io::Result<()>;
+    fn finalize(self: Box<Self>) -> io::Result<()>;
+}
+
+/// Opens the destination sink; called on the worker thread and retried while it fails
+pub type SinkFactory = Box<dyn FnMut() -> io::Result<Box<dyn SampleSink>> + Send>;
+
+/// 16-bit mono WAV file, flushed after every write so a crash loses little audio
+pub struct WavFileSink {
+    writer: hound::WavWriter<BufWriter<File>>,
+}
+
+impl WavFileSink {
+    pub fn create(path: &Path, sample_rate: u32) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(path, spec).map_err(to_io_error)?;
+        Ok(Self { writer })
+    }
+}
+
+impl SampleSink for WavFileSink {
+    fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+        for sample in samples {
+            self.writer
+                .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                .map_err(to_io_error)?;
+        }
+        self.writer.flush().map_err(to_io_error)
+    }
+
+    fn finalize(self: Box<Self>) -> io::Result<()> {
+        self.writer.finalize().map_err(to_io_error)
+    }
+}
+
+fn to_io_error(e: hound::Error) -> io::Error {
+    match e {
+        hound::Error::IoError(e) => e,
+        other => io::Error::other(other.to_string()),
+    }
+}
+
+/// Health of the recording destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingHealth {
+    /// Writes reach the destination in time
+    Healthy,
+    /// Destination stalled, samples are spilling to a local file
+    Spilling,
+    /// Destination unreachable for too long; recording continues locally only
+    Degraded,
+    /// Recording ended early (size limit or full disk); transcription continues
+    Stopped,
+}
+
+/// Why a recording stopped before the session ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingStop {
+    /// The configured duration or the WAV size limit was reached
+    SizeLimit,
+    /// The destination disk is full; what was written is kept
+    DiskFull,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordingWriterConfig {
+    /// Stall duration after which samples spill to the local file
+    pub stall_timeout: Duration,
+    /// Stall duration after which the recording is reported as degraded
+    pub degraded_after: Duration,
+    /// Maximum samples sent to the destination per write
+    pub chunk_samples: usize,
+    pub sample_rate: u32,
+    /// Samples after which recording stops
+    pub max_samples: u64,
+}
+
+impl Default for RecordingWriterConfig {
+    fn default() -> Self {
+        Self {
+            stall_timeout: Duration::from_secs(2),
+            degraded_after: Duration::from_secs(30),
+            chunk_samples: 16000,
+            sample_rate: 16000,
+            max_samples: WAV_MAX_SAMPLES,
+        }
+    }
+}
+
+/// Result of finishing a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSummary {
+    #[serde(rename = "samplesReceived")]
+    pub samples_received: u64,
+    #[serde(rename = "samplesWritten")]
+    pub samples_written: u64,
+    /// Every sample reached the destination and the file was finalized
+    pub complete: bool,
+    /// Local WAV holding the samples that never reached the destination
+    #[serde(rename = "localTailPath")]
+    pub local_tail_path: Option<PathBuf>,
+    #[serde(rename = "stoppedBy")]
+    pub stopped_by: Option<RecordingStop>,
+}
+
+enum WorkerMessage {
+    Write(Vec<f32>),
+    Finalize,
+}
+
+enum WorkerEvent {
+    Written(usize),
+    Finalized(io::Result<()>),
+    /// The disk is full; the worker finalized what it had and exited
+    DiskFull(io::Error),
+}
+
+/// Local overflow file of raw little-endian f32 samples, read back in order
+struct SpillFile {
+    path: PathBuf,
+    file: Option<File>,
+    written: u64,
+    read: u64,
+}
+
+impl SpillFile {
+    fn new(path: PathBuf) -> Self {
+        Self { path, file: None, written: 0, read: 0 }
+    }
+
+    fn is_drained(&self) -> bool {
+        self.read == self.written
+    }
+
+    fn append(&mut self, samples: &[f32]) -> io::Result<()> {
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let file = OpenOptions::new().create(true).read(true).write(true).truncate(true).open(&self.path)?;
+            self.file = Some(file);
+        }
+        let file = self.file.as_mut().expect("spill file opened above");
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        file.seek(SeekFrom::Start(self.written * 4))?;
+        file.write_all(&bytes)?;
+        self.written += samples.len() as u64;
+        Ok(())
+    }
+
+    fn read_chunk(&mut self, max_samples: usize) -> io::Result<Vec<f32>> {
+        let count = (self.written - self.read).min(max_samples as u64) as usize;
+        let Some(file) = self.file.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let mut bytes = vec![0u8; count * 4];
+        file.seek(SeekFrom::Start(self.read * 4))?;
+        file.read_exact(&mut bytes)?;
+        self.read += count as u64;
+
+        if self.read == self.written {
+            // Fully caught up: reuse the file from the start
+            file.set_len(0)?;
+            self.read = 0;
+            self.written = 0;
+        }
+        Ok(bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+    }
+
+    fn remove(&mut self) {
+        self.file = None;
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Recording writer that tolerates a slow or unreachable destination
+pub struct ResilientRecordingWriter {
+    config: RecordingWriterConfig,
+    to_worker: mpsc::Sender<WorkerMessage>,
+    from_worker: mpsc::Receiver<WorkerEvent>,
+    worker: Option<JoinHandle<()>>,
+    abandon: Arc<AtomicBool>,
+    /// Chunk currently being written by the worker and when it was sent
+    in_flight: Option<(Vec<f32>, Instant)>,
+    /// Samples waiting in memory while the destination keeps up
+    pending: VecDeque<Vec<f32>>,
+    spill: SpillFile,
+    health: RecordingHealth,
+    samples_received: u64,
+    samples_written: u64,
+    stopped_by: Option<RecordingStop>,
+    limit_warned: bool,
+}
+
+impl ResilientRecordingWriter {
+    pub fn new(config: RecordingWriterConfig, spill_path: PathBuf, mut factory: SinkFactory) -> Self {
+        let (to_worker, worker_rx) = mpsc::channel::<WorkerMessage>();
+        let (worker_tx, from_worker) = mpsc::channel::<WorkerEvent>();
+        let abandon = Arc::new(AtomicBool::new(false));
+        let worker_abandon = Arc::clone(&abandon);
+
+        let worker = thread::spawn(move || {
+            let mut sink: Option<Box<dyn SampleSink>> = None;
+            for message in worker_rx {
+                match message {
+                    WorkerMessage::Write(chunk) => match write_with_retry(&mut sink, &mut factory, &chunk, &worker_abandon) {
+                        Ok(true) => {
+                            let _ = worker_tx.send(WorkerEvent::Written(chunk.len()));
+                        }
+                        Ok(false) => return,
+                        Err(e) => {
+                            // Keep what was written playable
+                            if let Some(sink) = sink.take() {
+                                let _ = sink.finalize();
+                            }
+                            let _ = worker_tx.send(WorkerEvent::DiskFull(e));
+                            return;
+                        }
+                    },
+                    WorkerMessage::Finalize => {
+                        let result = match sink.take() {
+                            Some(sink) => sink.finalize(),
+                            None => Ok(()),
+                        };
+                        let _ = worker_tx.send(WorkerEvent::Finalized(result));
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self {
+            config,
+            to_worker,
+            from_worker,
+            worker: Some(worker),
+            abandon,
+            in_flight: None,
+            pending: VecDeque::new(),
+            spill: SpillFile::new(spill_path),
+            health: RecordingHealth::Healthy,
+            samples_received: 0,
+            samples_written: 0,
+            stopped_by: None,
+            limit_warned: false,
+        }
+    }
+
+    pub fn health(&self) -> RecordingHealth {
+        self.health
+    }
+
+    pub fn stopped_by(&self) -> Option<RecordingStop> {
+        self.stopped_by
+    }
+
+    /// True once, when the recording first passes LIMIT_WARNING_RATIO of its sample limit
+    pub fn near_limit(&mut self) -> bool {
+        if self.limit_warned || (self.samples_received as f64) < self.config.max_samples as f64 * LIMIT_WARNING_RATIO {
+            return false;
+        }
+        self.limit_warned = true;
+        true
+    }
+
+    /// Queue captured samples; returns the new health when it changed.
+    /// Samples past the limit, or after the disk filled up, are dropped.
+    pub fn push(&mut self, samples: &[f32]) -> Option<RecordingHealth> {
+        if self.stopped_by.is_some() {
+            return self.pump();
+        }
+        let remaining = self.config.max_samples.saturating_sub(self.samples_received);
+        let samples = &samples[..samples.len().min(usize::try_from(remaining).unwrap_or(usize::MAX))];
+        if self.samples_received + samples.len() as u64 >= self.config.max_samples {
+            tracing::warn!("Recording reached its limit of {} samples, stopping", self.config.max_samples);
+            self.stopped_by = Some(RecordingStop::SizeLimit);
+        }
+        if samples.is_empty() {
+            return self.pump();
+        }
+        self.samples_received += samples.len() as u64;
+
+        // Once anything is spilled, later samples follow it to keep order
+        if self.health != RecordingHealth::Healthy || !self.spill.is_drained() {
+            self.spill_samples(samples);
+        } else {
+            self.pending.push_back(samples.to_vec());
+        }
+        self.pump()
+    }
+
+    /// Collect finished writes, send the next chunk and update health
+    fn pump(&mut self) -> Option<RecordingHealth> {
+        while let Ok(event) = self.from_worker.try_recv() {
+            match event {
+                WorkerEvent::Written(count) => {
+                    self.samples_written += count as u64;
+                    self.in_flight = None;
+                }
+                WorkerEvent::DiskFull(e) => {
+                    tracing::error!("Recording stopped, destination disk is full: {}", e);
+                    self.stopped_by = Some(RecordingStop::DiskFull);
+                    self.in_flight = None;
+                    self.pending.clear();
+                    self.spill.remove();
+                }
+                WorkerEvent::Finalized(_) => {}
+            }
+        }
+
+        if self.in_flight.is_none() && self.stopped_by != Some(RecordingStop::DiskFull) {
+            // Catch up from the spill file before newer in-memory samples
+            let next = if !self.spill.is_drained() {
+                match self.spill.read_chunk(self.config.chunk_samples) {
+                    Ok(chunk) => Some(chunk),
+                    Err(e) => {
+                        tracing::error!("Failed to read recording spill file: {}", e);
+                        None
+                    }
+                }
+            } else {
+                self.pending.pop_front()
+            };
+            if let Some(chunk) = next.filter(|c| !c.is_empty()) {
+                if self.to_worker.send(WorkerMessage::Write(chunk.clone())).is_ok() {
+                    self.in_flight = Some((chunk, Instant::now()));
+                }
+            }
+        }
+
+        let previous = self.health;
+        self.health = match &self.in_flight {
+            _ if self.stopped_by.is_some() => RecordingHealth::Stopped,
+            Some((_, since)) if since.elapsed() >= self.config.degraded_after => RecordingHealth::Degraded,
+            Some((_, since)) if since.elapsed() >= self.config.stall_timeout => {
+                if previous == RecordingHealth::Degraded { previous } else { RecordingHealth::Spilling }
+            }
+            _ if self.spill.is_drained() => RecordingHealth::Healthy,
+            _ => previous,
+        };
+
+        // While stalled, move buffered samples out of memory
+        if matches!(self.health, RecordingHealth::Spilling | RecordingHealth::Degraded) {
+            while let Some(chunk) = self.pending.pop_front() {
+                self.spill_samples(&chunk);
+            }
+        }
+
+        (self.health != previous).then_some(self.health)
+    }
+
+    fn spill_samples(&mut self, samples: &[f32]) {
+        if let Err(e) = self.spill.append(samples) {
+            tracing::error!("Failed to spill {} recording samples locally: {}", samples.len(), e);
+        }
+    }
+
+    /// Flush everything to the destination within `timeout` and finalize the file.
+    /// If the destination stays unreachable, unwritten samples are saved to a local WAV.
+    pub fn finish(mut self, timeout: Duration) -> io::Result<RecordingSummary> {
+        let deadline = Instant::now() + timeout;
+
+        while self.in_flight.is_some() || !self.spill.is_drained() || !self.pending.is_empty() {
+            if Instant::now() >= deadline {
+                return self.abandon_to_local_tail();
+            }
+            self.pump();
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        if self.stopped_by == Some(RecordingStop::DiskFull) {
+            // The worker already finalized the file before exiting
+            if let Some(worker) = self.worker.take() {
+                let _ = worker.join();
+            }
+            return Ok(RecordingSummary {
+                samples_received: self.samples_received,
+                samples_written: self.samples_written,
+                complete: false,
+                local_tail_path: None,
+                stopped_by: self.stopped_by,
+            });
+        }
+
+        let _ = self.to_worker.send(WorkerMessage::Finalize);
+        let remaining = deadline.saturating_duration_since(Instant::now()).max(Duration::from_millis(100));
+        let finalized = loop {
+            match self.from_worker.recv_timeout(remaining) {
+                Ok(WorkerEvent::Finalized(result)) => break Some(result),
+                Ok(WorkerEvent::Written(_)) => continue,
+                Ok(WorkerEvent::DiskFull(e)) => break Some(Err(e)),
+                Err(_) => break None,
+            }
+        };
+        if let Some(worker) = self.worker.take() {
+            if finalized.is_some() {
+                let _ = worker.join();
+            }
+        }
+        self.spill.remove();
+
+        match finalized {
+            Some(result) => result.map(|_| RecordingSummary {
+                samples_received: self.samples_received,
+                samples_written: self.samples_written,
+                complete: true,
+                local_tail_path: None,
+                stopped_by: self.stopped_by,
+            }),
+            None => Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out finalizing recording")),
+        }
+    }
+
+    /// Stop retrying the destination and assemble all unwritten samples into a local WAV
+    fn abandon_to_local_tail(mut self) -> io::Result<RecordingSummary> {
+        self.abandon.store(true, Ordering::Relaxed);
+
+        let mut tail: Vec<f32> = Vec::new();
+        if let Some((chunk, _)) = self.in_flight.take() {
+            tail.extend(chunk);
+        }
+        while !self.spill.is_drained() {
+            tail.extend(self.spill.read_chunk(self.config.chunk_samples)?);
+        }
+        for chunk in self.pending.drain(..) {
+            tail.extend(chunk);
+        }
+
+        let tail_path = self.spill.path.with_extension("tail.wav");
+        let mut sink = Box::new(WavFileSink::create(&tail_path, self.config.sample_rate)?);
+        sink.write_samples(&tail)?;
+        sink.finalize()?;
+        self.spill.remove();
+
+        tracing::warn!("Recording destination unreachable, kept {} unwritten samples at {:?}", tail.len(), tail_path);
+        Ok(RecordingSummary {
+            samples_received: self.samples_received,
+            samples_written: self.samples_written,
+            complete: false,
+            local_tail_path: Some(tail_path),
+            stopped_by: self.stopped_by,
+        })
+    }
+}
+
+/// OS error codes for a full disk, where retrying cannot help
+#[cfg(unix)]
+const DISK_FULL_CODES: &[i32] = &[28]; // ENOSPC
+#[cfg(windows)]
+const DISK_FULL_CODES: &[i32] = &[39, 112]; // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+#[cfg(not(any(unix, windows)))]
+const DISK_FULL_CODES: &[i32] = &[];
+
+fn is_disk_full(e: &io::Error) -> bool {
+    e.raw_os_error().is_some_and(|code| DISK_FULL_CODES.contains(&code))
+}
+
+/// Write a chunk, reopening and retrying until it succeeds. Returns false if
+/// abandoned, and the error when the disk is full.
+fn write_with_retry(
+    sink: &mut Option<Box<dyn SampleSink>>,
+    factory: &mut SinkFactory,
+    chunk: &[f32],
+    abandon: &AtomicBool,
+) -> io::Result<bool> {
+    loop {
+        if abandon.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        if sink.is_none() {
+            match factory() {
+                Ok(opened) => *sink = Some(opened),
+                Err(e) if is_disk_full(&e) => return Err(e),
+                Err(e) => {
+                    tracing::warn!("Recording destination unavailable: {}", e);
+                    thread::sleep(Duration::from_millis(250));
+                    continue;
+                }
+            }
+        }
+        match sink.as_mut().map(|s| s.write_samples(chunk)) {
+            Some(Ok(())) => return Ok(true),
+            Some(Err(e)) if is_disk_full(&e) => return Err(e),
+            Some(Err(e)) => {
+                tracing::warn!("Recording write failed, retrying: {}", e);
+                thread::sleep(Duration::from_millis(250));
+            }
+            None => {}
+        }
+    }
+}
+
+/// Local spill file for a session's recording inside `dir`
+pub fn local_spill_path(dir: &Path, session_id: &str) -> PathBuf {
+    dir.join(format!("{}.spill", session_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Sink that blocks every write until `stall_until`
+    struct SlowSink {
+        written: Arc<Mutex<Vec<f32>>>,
+        stall_until: Instant,
+    }
+
+    impl SampleSink for SlowSink {
+        fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+            let now = Instant::now();
+            if now < self.stall_until {
+                thread::sleep(self.stall_until - now);
+            }
+            self.written.lock().unwrap().extend_from_slice(samples);
+            Ok(())
+        }
+
+        fn finalize(self: Box<Self>) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn slow_factory(written: Arc<Mutex<Vec<f32>>>, stall: Duration) -> SinkFactory {
+        let stall_until = Instant::now() + stall;
+        Box::new(move || Ok(Box::new(SlowSink { written: Arc::clone(&written), stall_until }) as Box<dyn SampleSink>))
+    }
+
+    fn config() -> RecordingWriterConfig {
+        RecordingWriterConfig {
+            stall_timeout: Duration::from_millis(40),
+            degraded_after: Duration::from_millis(150),
+            chunk_samples: 256,
+            sample_rate: 16000,
+            max_samples: WAV_MAX_SAMPLES,
+        }
+    }
+
+    fn ramp(start: usize, len: usize) -> Vec<f32> {
+        (start..start + len).map(|i| i as f32 / 100_000.0).collect()
+    }
+
+    #[test]
+    fn test_stalled_share_spills_and_catches_up_in_order() {
+        let dir = TempDir::new().unwrap();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let spill_path = local_spill_path(dir.path(), "session");
+        let mut writer = ResilientRecordingWriter::new(
+            config(),
+            spill_path.clone(),
+            slow_factory(Arc::clone(&written), Duration::from_millis(120)),
+        );
+
+        let mut expected = Vec::new();
+        let mut transitions = Vec::new();
+        for i in 0..20 {
+            let chunk = ramp(i * 160, 160);
+            expected.extend_from_slice(&chunk);
+            if let Some(health) = writer.push(&chunk) {
+                transitions.push(health);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(transitions.contains(&RecordingHealth::Spilling));
+        let summary = writer.finish(Duration::from_secs(5)).unwrap();
+
+        assert!(summary.complete);
+        assert_eq!(summary.samples_written, expected.len() as u64);
+        assert_eq!(*written.lock().unwrap(), expected);
+        assert!(!spill_path.exists());
+    }
+
+    #[test]
+    fn test_unreachable_share_degrades_and_keeps_audio_locally() {
+        let dir = TempDir::new().unwrap();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let mut writer = ResilientRecordingWriter::new(
+            config(),
+            local_spill_path(dir.path(), "session"),
+            slow_factory(Arc::clone(&written), Duration::from_secs(5)),
+        );
+
+        let mut saw_degraded = false;
+        for i in 0..30 {
+            saw_degraded |= writer.push(&ramp(i * 100, 100)) == Some(RecordingHealth::Degraded);
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(saw_degraded);
+
+        let summary = writer.finish(Duration::from_millis(50)).unwrap();
+        assert!(!summary.complete);
+
+        // Nothing lost: the local tail holds every sample the share never received
+        let tail = hound::WavReader::open(summary.local_tail_path.unwrap()).unwrap();
+        assert_eq!(tail.len() as u64 + summary.samples_written, summary.samples_received);
+    }
+
+    #[test]
+    fn test_failing_writes_are_retried_without_loss() {
+        struct FlakySink {
+            failures_left: usize,
+            written: Arc<Mutex<Vec<f32>>>,
+        }
+        impl SampleSink for FlakySink {
+            fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+                if self.failures_left > 0 {
+                    self.failures_left -= 1;
+                    return Err(io::Error::other("network hiccup"));
+                }
+                self.written.lock().unwrap().extend_from_slice(samples);
+                Ok(())
+            }
+            fn finalize(self: Box<Self>) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let dir = TempDir::new().unwrap();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let sink_written = Arc::clone(&written);
+        let mut writer = ResilientRecordingWriter::new(
+            config(),
+            local_spill_path(dir.path(), "session"),
+            Box::new(move || Ok(Box::new(FlakySink { failures_left: 1, written: Arc::clone(&sink_written) }) as Box<dyn SampleSink>)),
+        );
+
+        let samples = ramp(0, 1000);
+        writer.push(&samples);
+        let summary = writer.finish(Duration::from_secs(5)).unwrap();
+
+        assert!(summary.complete);
+        assert_eq!(*written.lock().unwrap(), samples);
+    }
+
+    #[test]
+    fn test_wav_file_assembly() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("share").join("audio.wav");
+        let sink_path = path.clone();
+        let mut writer = ResilientRecordingWriter::new(
+            config(),
+            local_spill_path(dir.path(), "session"),
+            Box::new(move || Ok(Box::new(WavFileSink::create(&sink_path, 16000)?) as Box<dyn SampleSink>)),
+        );
+
+        for i in 0..10 {
+            writer.push(&ramp(i * 1600, 1600));
+        }
+        writer.finish(Duration::from_secs(5)).unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.len(), 16000);
+    }
+
+    #[test]
+    fn test_size_limit_stops_recording_and_keeps_the_file_valid() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audio.wav");
+        let sink_path = path.clone();
+        let mut writer = ResilientRecordingWriter::new(
+            RecordingWriterConfig { max_samples: 4000, ..config() },
+            local_spill_path(dir.path(), "session"),
+            Box::new(move || Ok(Box::new(WavFileSink::create(&sink_path, 16000)?) as Box<dyn SampleSink>)),
+        );
+
+        writer.push(&ramp(0, 3000));
+        assert!(!writer.near_limit());
+        writer.push(&ramp(3000, 700));
+        assert!(writer.near_limit());
+        assert!(!writer.near_limit());
+        assert_eq!(writer.push(&ramp(3700, 1000)), Some(RecordingHealth::Stopped));
+        assert_eq!(writer.push(&ramp(4700, 1000)), None);
+        assert_eq!(writer.stopped_by(), Some(RecordingStop::SizeLimit));
+
+        let summary = writer.finish(Duration::from_secs(5)).unwrap();
+        assert!(summary.complete);
+        assert_eq!(summary.samples_written, 4000);
+        assert_eq!(summary.stopped_by, Some(RecordingStop::SizeLimit));
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 4000);
+    }
+
+    #[test]
+    fn test_full_disk_stops_recording_without_retrying() {
+        struct FullDiskSink {
+            space_left: usize,
+            written: Arc<Mutex<Vec<f32>>>,
+        }
+        impl SampleSink for FullDiskSink {
+            fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+                if samples.len() > self.space_left {
+                    return Err(io::Error::from_raw_os_error(DISK_FULL_CODES[0]));
+                }
+                self.space_left -= samples.len();
+                self.written.lock().unwrap().extend_from_slice(samples);
+                Ok(())
+            }
+            fn finalize(self: Box<Self>) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let dir = TempDir::new().unwrap();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let sink_written = Arc::clone(&written);
+        let mut writer = ResilientRecordingWriter::new(
+            config(),
+            local_spill_path(dir.path(), "session"),
+            Box::new(move || Ok(Box::new(FullDiskSink { space_left: 500, written: Arc::clone(&sink_written) }) as Box<dyn SampleSink>)),
+        );
+
+        let mut stopped = false;
+        for i in 0..20 {
+            stopped |= writer.push(&ramp(i * 100, 100)) == Some(RecordingHealth::Stopped);
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(stopped);
+        assert_eq!(writer.stopped_by(), Some(RecordingStop::DiskFull));
+
+        let summary = writer.finish(Duration::from_secs(5)).unwrap();
+        assert!(!summary.complete);
+        assert_eq!(summary.stopped_by, Some(RecordingStop::DiskFull));
+        assert_eq!(written.lock().unwrap().len(), 500);
+    }
+}
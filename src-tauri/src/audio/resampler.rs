@@ -4,9 +4,16 @@
 //! any input sample rate to 16kHz for Whisper compatibility.
 
 use crate::audio::types::{AudioData, AudioError};
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::time::Instant;
 use tracing::{debug, info};
 
+/// Resampling quality choices accepted from the frontend
+pub const RESAMPLING_PREFERENCES: [&str; 4] = ["auto", "fast", "medium", "high"];
+/// Share of a chunk's duration spent resampling above which Fast is suggested
+pub const SLOW_RESAMPLING_LOAD: f64 = 0.1;
+
 /// High-quality audio resampler for converting arbitrary sample rates to 16kHz
 pub struct AudioResampler {
     source_sample_rate: u32,
@@ -15,10 +22,11 @@ pub struct AudioResampler {
     conversion_ratio: f64,
     buffer: VecDeque<f32>,
     quality_mode: ResamplingQuality,
+    timing: ResamplingTiming,
 }
 
 /// Resampling quality modes
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ResamplingQuality {
     /// High quality with 64-sample window (default)
     High,
@@ -36,6 +44,99 @@ impl ResamplingQuality {
             ResamplingQuality::Fast => 16,
         }
     }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResamplingQuality::High => "high",
+            ResamplingQuality::Medium => "medium",
+            ResamplingQuality::Fast => "fast",
+        }
+    }
+}
+
+/// Quality requested for capture resampling; `Auto` keeps [`ResamplerUtils::recommend_quality`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResamplingPreference {
+    #[default]
+    Auto,
+    Fast,
+    Medium,
+    High,
+}
+
+impl ResamplingPreference {
+    /// Parse one of [`RESAMPLING_PREFERENCES`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "fast" => Some(Self::Fast),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    /// Quality to resample a real-time stream with
+    pub fn resolve(&self, source_rate: u32, target_rate: u32) -> ResamplingQuality {
+        match self {
+            Self::Auto => ResamplerUtils::recommend_quality(source_rate, target_rate, true),
+            Self::Fast => ResamplingQuality::Fast,
+            Self::Medium => ResamplingQuality::Medium,
+            Self::High => ResamplingQuality::High,
+        }
+    }
+}
+
+/// Measured time spent resampling chunks
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResamplingTiming {
+    pub chunks: u64,
+    pub last_chunk_ms: f64,
+    /// Exponential moving average over recent chunks
+    pub average_chunk_ms: f64,
+    /// Average processing time as a share of the audio duration it converted
+    pub load: f64,
+}
+
+impl ResamplingTiming {
+    fn record(&mut self, elapsed_ms: f64, audio_seconds: f64) {
+        const SMOOTHING: f64 = 0.1;
+        let load = if audio_seconds > 0.0 { elapsed_ms / (audio_seconds * 1000.0) } else { 0.0 };
+        if self.chunks == 0 {
+            self.average_chunk_ms = elapsed_ms;
+            self.load = load;
+        } else {
+            self.average_chunk_ms += (elapsed_ms - self.average_chunk_ms) * SMOOTHING;
+            self.load += (load - self.load) * SMOOTHING;
+        }
+        self.last_chunk_ms = elapsed_ms;
+        self.chunks += 1;
+    }
+}
+
+/// Active resampling of a capture stream, for diagnostics
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResamplingReport {
+    pub quality: &'static str,
+    pub source_sample_rate: u32,
+    pub target_sample_rate: u32,
+    pub timing: ResamplingTiming,
+}
+
+impl ResamplingReport {
+    /// Troubleshooting hint when resampling takes too much of each chunk's real time
+    pub fn slow_resampling_suggestion(&self) -> Option<String> {
+        if self.quality == ResamplingQuality::Fast.label() || self.timing.load <= SLOW_RESAMPLING_LOAD {
+            return None;
+        }
+        Some(format!(
+            "Resampling {} Hz to {} Hz takes {:.1} ms per chunk ({:.0}% of real time); set resampling quality to Fast",
+            self.source_sample_rate, self.target_sample_rate, self.timing.average_chunk_ms, self.timing.load * 100.0
+        ))
+    }
 }
 
 impl AudioResampler {
@@ -72,6 +173,7 @@ impl AudioResampler {
             conversion_ratio,
             buffer: VecDeque::new(),
             quality_mode: quality,
+            timing: ResamplingTiming::default(),
         })
     }
 
@@ -107,6 +209,7 @@ impl AudioResampler {
             return Ok(audio.clone());
         }
 
+        let started = Instant::now();
         let resampled_samples = if self.channels == 1 {
             // Mono processing
             self.resample_mono(&audio.samples)?
@@ -114,6 +217,8 @@ impl AudioResampler {
             // Stereo/multi-channel processing
             self.resample_multichannel(&audio.samples)?
         };
+        let audio_seconds = audio.samples.len() as f64 / (self.source_sample_rate as f64 * self.channels as f64);
+        self.timing.record(started.elapsed().as_secs_f64() * 1000.0, audio_seconds);
 
         debug!(
             "Resampled {} → {} samples ({:.2}s → {:.2}s)",
@@ -276,6 +381,16 @@ impl AudioResampler {
     pub fn needs_resampling(&self) -> bool {
         self.source_sample_rate != self.target_sample_rate
     }
+
+    /// Quality, rates and measured per-chunk time
+    pub fn report(&self) -> ResamplingReport {
+        ResamplingReport {
+            quality: self.quality_mode.label(),
+            source_sample_rate: self.source_sample_rate,
+            target_sample_rate: self.target_sample_rate,
+            timing: self.timing,
+        }
+    }
 }
 
 /// Utility functions for common resampling operations
@@ -407,4 +522,28 @@ mod tests {
             ResamplingQuality::Fast
         ));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_resampling_preference() {
+        assert_eq!(ResamplingPreference::from_name(" Fast "), Some(ResamplingPreference::Fast));
+        assert_eq!(ResamplingPreference::from_name("best"), None);
+        assert_eq!(ResamplingPreference::Auto.resolve(48000, 8000), ResamplerUtils::recommend_quality(48000, 8000, true));
+        assert_eq!(ResamplingPreference::High.resolve(48000, 8000), ResamplingQuality::High);
+        assert!(RESAMPLING_PREFERENCES.iter().all(|name| ResamplingPreference::from_name(name).is_some()));
+    }
+
+    #[test]
+    fn test_report_times_chunks_and_suggests_fast() {
+        let mut resampler = AudioResampler::new(48000, 16000, 1, ResamplingQuality::High).unwrap();
+        resampler.process(&create_test_audio(48000, 1, 0.1)).unwrap();
+        let mut report = resampler.report();
+        assert_eq!((report.quality, report.source_sample_rate, report.target_sample_rate), ("high", 48000, 16000));
+        assert_eq!(report.timing.chunks, 1);
+        assert!(report.timing.last_chunk_ms >= 0.0);
+
+        report.timing.load = 0.5;
+        assert!(report.slow_resampling_suggestion().unwrap().contains("Fast"));
+        report.quality = ResamplingQuality::Fast.label();
+        assert!(report.slow_resampling_suggestion().is_none());
+    }
+}
@@ -0,0 +1,193 @@
+//! Rolling buffer of a session's recent audio
+//!
+//! The transcription loop hands each chunk to Whisper and then lets it go, so
+//! re-transcribing a segment or replaying the last sentence needs a copy of
+//! the recent audio. Every chunk is appended here at its stream offset (the
+//! timeline of segment timestamps); the oldest audio is overwritten once the
+//! configured duration is reached. Samples are kept as 16-bit PCM, which caps
+//! five minutes of 16 kHz mono at about 10 MB.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Default `replayBufferSeconds`
+pub const DEFAULT_BUFFER_SECONDS: u32 = 300;
+/// Longest buffer a session may keep (about 115 MB at 16 kHz mono)
+pub const MAX_BUFFER_SECONDS: u32 = 3600;
+
+/// A chunk further than this from the end of the buffer starts a new timeline
+const DISCONTINUITY_SECONDS: f64 = 0.05;
+
+/// Fixed-capacity circular buffer of mono audio placed on the stream timeline
+#[derive(Debug, Clone)]
+pub struct AudioRingBuffer {
+    max_seconds: u32,
+    sample_rate: u32,
+    /// Grows to the capacity, then is overwritten from `head`
+    samples: Vec<i16>,
+    /// Index of the oldest sample once the buffer is full
+    head: usize,
+    /// Stream offset just after the newest sample
+    end_time: f64,
+}
+
+impl AudioRingBuffer {
+    /// Buffer holding up to `max_seconds` of audio; 0 keeps nothing
+    pub fn new(max_seconds: u32) -> Self {
+        Self {
+            max_seconds: max_seconds.min(MAX_BUFFER_SECONDS),
+            sample_rate: 0,
+            samples: Vec::new(),
+            head: 0,
+            end_time: 0.0,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.max_seconds as usize * self.sample_rate as usize
+    }
+
+    /// Append a chunk of mono audio that starts at `start_time` on the stream
+    /// timeline. A different sample rate or a jump in the timeline drops the
+    /// buffered audio, which would otherwise be misplaced.
+    pub fn push(&mut self, samples: &[f32], sample_rate: u32, start_time: f64) {
+        if self.max_seconds == 0 || sample_rate == 0 || samples.is_empty() {
+            return;
+        }
+        if sample_rate != self.sample_rate || (start_time - self.end_time).abs() > DISCONTINUITY_SECONDS {
+            self.clear();
+            self.sample_rate = sample_rate;
+        }
+
+        let capacity = self.capacity();
+        // Only the newest `capacity` samples of an oversized chunk can be kept
+        let skip = samples.len().saturating_sub(capacity);
+        let needed = (self.samples.len() + samples.len() - skip).min(capacity);
+        if needed > self.samples.capacity() {
+            // Grow by doubling, but never past the cap
+            let target = (self.samples.capacity() * 2).max(needed).min(capacity);
+            self.samples.reserve_exact(target - self.samples.len());
+        }
+        for &sample in &samples[skip..] {
+            let pcm = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+            if self.samples.len() < capacity {
+                self.samples.push(pcm);
+            } else {
+                self.samples[self.head] = pcm;
+                self.head = (self.head + 1) % capacity;
+            }
+        }
+        self.end_time = start_time + samples.len() as f64 / sample_rate as f64;
+    }
+
+    pub fn clear(&mut self) {
+        self.samples = Vec::new();
+        self.head = 0;
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Stream offsets of the oldest and just after the newest retained sample
+    pub fn retained_span(&self) -> Option<(f64, f64)> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let duration = self.samples.len() as f64 / self.sample_rate as f64;
+        Some((self.end_time - duration, self.end_time))
+    }
+
+    /// Retained audio between `start` and `end` seconds on the stream
+    /// timeline. Parts already overwritten (or not yet captured) are left out,
+    /// so a partly evicted range returns only its retained tail.
+    pub fn range(&self, start: f64, end: f64) -> Vec<f32> {
+        let Some((first, last)) = self.retained_span() else {
+            return Vec::new();
+        };
+        let (start, end) = (start.max(first), end.min(last));
+        if end <= start {
+            return Vec::new();
+        }
+        let rate = self.sample_rate as f64;
+        let from = (((start - first) * rate).round() as usize).min(self.samples.len());
+        let to = (((end - first) * rate).round() as usize).min(self.samples.len());
+        (from..to)
+            .map(|i| self.samples[(self.head + i) % self.samples.len()] as f32 / i16::MAX as f32)
+            .collect()
+    }
+
+    /// Memory held by the samples
+    pub fn memory_bytes(&self) -> usize {
+        self.samples.capacity() * std::mem::size_of::<i16>()
+    }
+}
+
+/// Ring buffer shared by the session state and its transcription loop
+#[derive(Debug, Clone)]
+pub struct SessionAudioBuffer {
+    buffer: Arc<Mutex<AudioRingBuffer>>,
+}
+
+impl SessionAudioBuffer {
+    pub fn new(max_seconds: u32) -> Self {
+        Self { buffer: Arc::new(Mutex::new(AudioRingBuffer::new(max_seconds))) }
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, AudioRingBuffer> {
+        self.buffer.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for SessionAudioBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUFFER_SECONDS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One-second chunks at 100 Hz whose samples encode the second they belong to
+    fn chunk(second: u32) -> Vec<f32> {
+        vec![second as f32 / 100.0; 100]
+    }
+
+    fn seconds_of(samples: &[f32]) -> Vec<u32> {
+        samples.chunks(100).map(|c| (c[0] * 100.0).round() as u32).collect()
+    }
+
+    #[test]
+    fn test_wraparound_keeps_newest_audio_in_order() {
+        let mut buffer = AudioRingBuffer::new(3);
+        for second in 0..7 {
+            buffer.push(&chunk(second), 100, second as f64);
+        }
+        assert_eq!(buffer.retained_span(), Some((4.0, 7.0)));
+        assert_eq!(seconds_of(&buffer.range(4.0, 7.0)), vec![4, 5, 6]);
+        assert_eq!(seconds_of(&buffer.range(5.0, 6.0)), vec![5]);
+        // Capped at three seconds of 16-bit samples
+        assert_eq!(buffer.memory_bytes(), 300 * 2);
+
+        // A chunk longer than the buffer keeps its end
+        let long: Vec<f32> = (7..12).flat_map(chunk).collect();
+        buffer.push(&long, 100, 7.0);
+        assert_eq!(seconds_of(&buffer.range(0.0, 20.0)), vec![9, 10, 11]);
+    }
+
+    #[test]
+    fn test_partially_evicted_range_returns_retained_part() {
+        let mut buffer = AudioRingBuffer::new(2);
+        for second in 0..5 {
+            buffer.push(&chunk(second), 100, second as f64);
+        }
+        assert_eq!(seconds_of(&buffer.range(1.0, 4.0)), vec![3]);
+        assert_eq!(seconds_of(&buffer.range(2.5, 10.0)), vec![3, 4]);
+        assert!(buffer.range(0.0, 2.0).is_empty());
+
+        // A jump in the timeline starts over
+        buffer.push(&chunk(9), 100, 9.0);
+        assert_eq!(buffer.retained_span(), Some((9.0, 10.0)));
+        assert!(AudioRingBuffer::new(0).range(0.0, 1.0).is_empty());
+    }
+}
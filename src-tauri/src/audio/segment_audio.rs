@@ -0,0 +1,197 @@
+//! Segment positions in the session recording
+//!
+//! The recording starts with the first chunk the transcription loop receives
+//! after it is set up, at some offset on the session clock. Each stored
+//! segment is stamped with `startSample`/`endSample` into the recording so a
+//! segment can be played back on its own; segments without a recording, or
+//! from before it started, get null offsets. The file can come out shorter
+//! than the clock says (limit reached, full disk, clock drift), so reads are
+//! clamped to its length.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+/// Segment field holding the first recording sample of the segment
+pub const START_SAMPLE_FIELD: &str = "startSample";
+/// Segment field holding the recording sample just past the segment
+pub const END_SAMPLE_FIELD: &str = "endSample";
+
+/// Where a session recording sits on the session clock
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordingTimeline {
+    /// Session time of the recording's first sample (seconds)
+    pub start_seconds: f64,
+    pub sample_rate: u32,
+}
+
+impl RecordingTimeline {
+    pub fn new(start_seconds: f64, sample_rate: u32) -> Self {
+        Self { start_seconds, sample_rate }
+    }
+
+    /// Recording samples covering `start..end` seconds of session time;
+    /// `None` when the span ended before the recording started
+    pub fn sample_range(&self, start: f64, end: f64) -> Option<(u64, u64)> {
+        if !start.is_finite() || !end.is_finite() || end <= self.start_seconds {
+            return None;
+        }
+        let rate = self.sample_rate as f64;
+        let start_sample = ((start - self.start_seconds).max(0.0) * rate).floor() as u64;
+        let end_sample = ((end - self.start_seconds) * rate).ceil() as u64;
+        Some((start_sample, end_sample.max(start_sample)))
+    }
+}
+
+/// Stamp a segment with its sample range in the recording; null without one
+pub fn label_segment(segment: &mut serde_json::Value, timeline: Option<&RecordingTimeline>) {
+    let range = timeline.and_then(|timeline| {
+        timeline.sample_range(segment["startTime"].as_f64()?, segment["endTime"].as_f64()?)
+    });
+    let (start, end) = match range {
+        Some((start, end)) => (serde_json::json!(start), serde_json::json!(end)),
+        None => (serde_json::Value::Null, serde_json::Value::Null),
+    };
+    segment[START_SAMPLE_FIELD] = start;
+    segment[END_SAMPLE_FIELD] = end;
+}
+
+/// Sample range stamped on a segment, if it has one
+pub fn segment_range(segment: &serde_json::Value) -> Option<(u64, u64)> {
+    Some((segment[START_SAMPLE_FIELD].as_u64()?, segment[END_SAMPLE_FIELD].as_u64()?))
+}
+
+/// A segment's audio, read from the session recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentAudio {
+    pub sample_rate: u32,
+    /// Range actually read, after clamping to the recording
+    pub start_sample: u64,
+    pub end_sample: u64,
+    /// Whether the stored range reached past the end of the recording
+    pub clamped: bool,
+    /// Mono samples; empty when the audio was written to `path` instead
+    pub samples: Vec<f32>,
+    pub path: Option<String>,
+}
+
+/// Read samples `start..end` of a WAV recording, clamped to its length and
+/// mixed down to mono
+pub fn read_samples(path: &Path, start: u64, end: u64) -> io::Result<SegmentAudio> {
+    let to_io = |e: hound::Error| match e {
+        hound::Error::IoError(e) => e,
+        other => io::Error::other(other.to_string()),
+    };
+    let mut reader = hound::WavReader::new(BufReader::new(fs::File::open(path)?)).map_err(to_io)?;
+    let spec = reader.spec();
+    let total_frames = reader.duration() as u64;
+    let start_frame = start.min(total_frames);
+    let end_frame = end.max(start).min(total_frames);
+    if end_frame <= start_frame {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!(
+            "Samples {}..{} are outside the recording of {} samples", start, end, total_frames
+        )));
+    }
+    reader.seek(start_frame as u32)?;
+
+    let channels = spec.channels.max(1) as usize;
+    let sample_count = (end_frame - start_frame) as usize * channels;
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().take(sample_count).collect::<Result<_, _>>().map_err(to_io)?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+            reader.samples::<i32>()
+                .take(sample_count)
+                .map(|sample| sample.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(to_io)?
+        }
+    };
+    let samples = interleaved.chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok(SegmentAudio {
+        sample_rate: spec.sample_rate,
+        start_sample: start_frame,
+        end_sample: end_frame,
+        clamped: end_frame < end,
+        samples,
+        path: None,
+    })
+}
+
+/// Write the samples to a 32-bit float WAV at `path` and keep only the path
+pub fn write_to_file(audio: &mut SegmentAudio, path: &Path) -> io::Result<()> {
+    let to_io = |e: hound::Error| match e {
+        hound::Error::IoError(e) => e,
+        other => io::Error::other(other.to_string()),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: audio.sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).map_err(to_io)?;
+    for &sample in &audio.samples {
+        writer.write_sample(sample).map_err(to_io)?;
+    }
+    writer.finalize().map_err(to_io)?;
+    audio.samples = Vec::new();
+    audio.path = Some(path.to_string_lossy().to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments_before_the_recording_have_null_offsets() {
+        let timeline = RecordingTimeline::new(2.0, 16000);
+        let mut early = serde_json::json!({ "startTime": 0.5, "endTime": 1.5 });
+        label_segment(&mut early, Some(&timeline));
+        assert!(early[START_SAMPLE_FIELD].is_null() && early[END_SAMPLE_FIELD].is_null());
+        assert_eq!(segment_range(&early), None);
+
+        // Straddling the start: the recorded part
+        let mut straddling = serde_json::json!({ "startTime": 1.5, "endTime": 3.0 });
+        label_segment(&mut straddling, Some(&timeline));
+        assert_eq!(segment_range(&straddling), Some((0, 16000)));
+
+        let mut unrecorded = serde_json::json!({ "startTime": 4.0, "endTime": 5.0 });
+        label_segment(&mut unrecorded, None);
+        assert!(unrecorded[START_SAMPLE_FIELD].is_null());
+    }
+
+    #[test]
+    fn test_reads_are_clamped_to_the_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audio.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: 8000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..8000i32 {
+            writer.write_sample((i % 100) as i16 * 100).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let audio = read_samples(&path, 7900, 9000).unwrap();
+        assert_eq!((audio.start_sample, audio.end_sample, audio.clamped), (7900, 8000, true));
+        assert_eq!(audio.samples.len(), 100);
+        assert_eq!(audio.samples[1], 100.0 / 32768.0);
+        assert!(read_samples(&path, 8000, 9000).is_err());
+
+        let mut audio = read_samples(&path, 0, 10).unwrap();
+        assert!(!audio.clamped);
+        write_to_file(&mut audio, &dir.path().join("segment.wav")).unwrap();
+        assert!(audio.samples.is_empty());
+        let written = read_samples(Path::new(audio.path.as_deref().unwrap()), 0, 10).unwrap();
+        assert_eq!(written.samples[5], 500.0 / 32768.0);
+    }
+}
@@ -0,0 +1,414 @@
+//! System audio (loopback) capability probing
+//!
+//! Reports whether the current OS can capture system audio and whether the
+//! required permission is granted, without ever starting a capture stream,
+//! and plans which device a system audio capture should open.
+
+use serde::{Deserialize, Serialize};
+
+/// Minimum macOS version with ScreenCaptureKit audio capture
+pub const MACOS_MIN_SYSTEM_AUDIO_VERSION: (u32, u32) = (13, 0);
+
+/// Operating system family as seen by the probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsFamily {
+    MacOS,
+    Windows,
+    Linux,
+    Other,
+}
+
+/// Permission state for system audio capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    NotRequired,
+    Unknown,
+}
+
+/// Loopback availability for an output (render) device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoopbackDevice {
+    pub name: String,
+    #[serde(rename = "isDefault")]
+    pub is_default: bool,
+    #[serde(rename = "loopbackAvailable")]
+    pub loopback_available: bool,
+}
+
+/// systemAudio section of get_system_info
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemAudioCapability {
+    pub supported: bool,
+    pub reason: String,
+    #[serde(rename = "permissionState")]
+    pub permission_state: PermissionState,
+    #[serde(rename = "osVersion")]
+    pub os_version: Option<String>,
+    #[serde(rename = "minimumOsVersion")]
+    pub minimum_os_version: Option<String>,
+    /// Backend that would be used for capture (e.g. "ScreenCaptureKit", "WASAPI loopback")
+    pub backend: Option<String>,
+    #[serde(rename = "loopbackDevices")]
+    pub loopback_devices: Vec<LoopbackDevice>,
+}
+
+/// Platform queries used by the probe, mockable in tests
+pub trait PlatformProbe {
+    fn os_family(&self) -> OsFamily;
+    /// OS version string (e.g. "14.2.1")
+    fn os_version(&self) -> Option<String>;
+    /// Screen recording permission (macOS only)
+    fn screen_capture_permission(&self) -> PermissionState;
+    /// Output devices that could be captured via loopback
+    fn render_devices(&self) -> Vec<LoopbackDevice>;
+}
+
+/// Probe backed by the real operating system
+pub struct NativePlatformProbe;
+
+impl PlatformProbe for NativePlatformProbe {
+    fn os_family(&self) -> OsFamily {
+        if cfg!(target_os = "macos") {
+            OsFamily::MacOS
+        } else if cfg!(target_os = "windows") {
+            OsFamily::Windows
+        } else if cfg!(target_os = "linux") {
+            OsFamily::Linux
+        } else {
+            OsFamily::Other
+        }
+    }
+
+    fn os_version(&self) -> Option<String> {
+        sysinfo::System::os_version()
+    }
+
+    fn screen_capture_permission(&self) -> PermissionState {
+        #[cfg(target_os = "macos")]
+        {
+            #[link(name = "CoreGraphics", kind = "framework")]
+            extern "C" {
+                // Checks the permission without prompting the user
+                fn CGPreflightScreenCaptureAccess() -> bool;
+            }
+            if unsafe { CGPreflightScreenCaptureAccess() } {
+                PermissionState::Granted
+            } else {
+                PermissionState::Denied
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            PermissionState::NotRequired
+        }
+    }
+
+    fn render_devices(&self) -> Vec<LoopbackDevice> {
+        use cpal::traits::{DeviceTrait, HostTrait};
+
+        let host = cpal::default_host();
+        let default_name = host.default_output_device().and_then(|d| d.name().ok());
+        let Ok(devices) = host.output_devices() else {
+            return Vec::new();
+        };
+
+        devices
+            .filter_map(|device| device.name().ok())
+            .map(|name| LoopbackDevice {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                // WASAPI supports loopback on every shared-mode render endpoint
+                loopback_available: cfg!(target_os = "windows"),
+                name,
+            })
+            .collect()
+    }
+}
+
+/// Parse "major.minor[.patch]" into (major, minor)
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
+/// Determine system audio capture support without starting any capture
+pub fn probe_system_audio(probe: &dyn PlatformProbe) -> SystemAudioCapability {
+    let os_version = probe.os_version();
+
+    match probe.os_family() {
+        OsFamily::MacOS => {
+            let minimum = format!("{}.{}", MACOS_MIN_SYSTEM_AUDIO_VERSION.0, MACOS_MIN_SYSTEM_AUDIO_VERSION.1);
+            let version_ok = os_version.as_deref()
+                .and_then(parse_version)
+                .map(|v| v >= MACOS_MIN_SYSTEM_AUDIO_VERSION)
+                .unwrap_or(false);
+
+            if !version_ok {
+                return SystemAudioCapability {
+                    supported: false,
+                    reason: format!("System audio capture requires macOS {} or later", minimum),
+                    permission_state: PermissionState::Unknown,
+                    os_version,
+                    minimum_os_version: Some(minimum),
+                    backend: None,
+                    loopback_devices: Vec::new(),
+                };
+            }
+
+            let permission_state = probe.screen_capture_permission();
+            let (supported, reason) = match permission_state {
+                PermissionState::Granted | PermissionState::NotRequired => (true, "Ready".to_string()),
+                _ => (false, "Screen recording permission is required for system audio capture".to_string()),
+            };
+
+            SystemAudioCapability {
+                supported,
+                reason,
+                permission_state,
+                os_version,
+                minimum_os_version: Some(minimum),
+                backend: Some("ScreenCaptureKit".to_string()),
+                loopback_devices: Vec::new(),
+            }
+        }
+        OsFamily::Windows => {
+            let devices = probe.render_devices();
+            let default_available = devices.iter().any(|d| d.is_default && d.loopback_available);
+
+            SystemAudioCapability {
+                supported: default_available,
+                reason: if default_available {
+                    "Ready".to_string()
+                } else {
+                    "No default render device supports WASAPI loopback".to_string()
+                },
+                permission_state: PermissionState::NotRequired,
+                os_version,
+                minimum_os_version: None,
+                backend: Some("WASAPI loopback".to_string()),
+                loopback_devices: devices,
+            }
+        }
+        OsFamily::Linux | OsFamily::Other => SystemAudioCapability {
+            supported: false,
+            reason: "System audio capture is not supported on this platform".to_string(),
+            permission_state: PermissionState::Unknown,
+            os_version,
+            minimum_os_version: None,
+            backend: None,
+            loopback_devices: Vec::new(),
+        },
+    }
+}
+
+/// Input devices that carry system output: virtual loopback drivers (macOS
+/// aggregate setups), PulseAudio/PipeWire monitors and Windows "Stereo Mix"
+const VIRTUAL_LOOPBACK_NAMES: &[&str] = &[
+    "blackhole", "soundflower", "loopback audio", "monitor of", ".monitor", "stereo mix", "what u hear", "wave out mix",
+];
+
+/// Whether an input device name looks like a loopback of the system output
+pub fn is_virtual_loopback_input(name: &str) -> bool {
+    let name = name.to_lowercase();
+    VIRTUAL_LOOPBACK_NAMES.iter().any(|pattern| name.contains(pattern))
+}
+
+/// How a system audio capture will be opened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopbackPlan {
+    /// Capture an output device directly (WASAPI loopback)
+    RenderLoopback { device: String },
+    /// Capture an input device that mirrors the output (BlackHole, monitor sources)
+    VirtualInput { device: String },
+    Unavailable { reason: String, suggestions: Vec<String> },
+}
+
+/// Recovery suggestions when no system audio route exists
+pub fn unavailable_suggestions(os: OsFamily) -> Vec<String> {
+    match os {
+        OsFamily::MacOS => vec![
+            "Install a virtual loopback driver such as BlackHole".to_string(),
+            "In Audio MIDI Setup, create a Multi-Output Device with your speakers and BlackHole and select it as the output".to_string(),
+            "Start the session again; KagiNote captures the loopback device automatically".to_string(),
+        ],
+        OsFamily::Windows => vec![
+            "Make sure a playback device is enabled in Sound settings".to_string(),
+            "Set the speakers or headset used for the meeting as the default output device".to_string(),
+        ],
+        OsFamily::Linux => vec![
+            "Use a PulseAudio or PipeWire monitor source of your output as an input device".to_string(),
+            "For example: pactl load-module module-remap-source master=<sink>.monitor".to_string(),
+        ],
+        OsFamily::Other => vec!["Transcribe a recording of the meeting with file import instead".to_string()],
+    }
+}
+
+/// Choose the device for a system audio capture. `requested` is a device
+/// name selected by the user; render devices only offer loopback on Windows.
+pub fn plan_loopback(
+    os: OsFamily,
+    requested: Option<&str>,
+    render_devices: &[LoopbackDevice],
+    input_devices: &[String],
+) -> LoopbackPlan {
+    if let Some(requested) = requested {
+        if render_devices.iter().any(|d| d.name == requested && d.loopback_available) {
+            return LoopbackPlan::RenderLoopback { device: requested.to_string() };
+        }
+        // An input the user picked explicitly is trusted to carry system audio
+        if input_devices.iter().any(|name| name == requested) {
+            return LoopbackPlan::VirtualInput { device: requested.to_string() };
+        }
+        return LoopbackPlan::Unavailable {
+            reason: format!("System audio device '{}' was not found", requested),
+            suggestions: unavailable_suggestions(os),
+        };
+    }
+
+    let render = render_devices.iter()
+        .filter(|d| d.loopback_available)
+        .max_by_key(|d| d.is_default);
+    if let Some(device) = render {
+        return LoopbackPlan::RenderLoopback { device: device.name.clone() };
+    }
+    if let Some(name) = input_devices.iter().find(|name| is_virtual_loopback_input(name)) {
+        return LoopbackPlan::VirtualInput { device: name.clone() };
+    }
+
+    let reason = match os {
+        OsFamily::MacOS => "No loopback device found; system audio on macOS needs a virtual loopback device such as BlackHole",
+        OsFamily::Windows => "No output device supports WASAPI loopback",
+        OsFamily::Linux => "No monitor source of the system output is available as an input",
+        OsFamily::Other => "System audio capture is not supported on this platform",
+    };
+    LoopbackPlan::Unavailable { reason: reason.to_string(), suggestions: unavailable_suggestions(os) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockProbe {
+        family: OsFamily,
+        version: Option<&'static str>,
+        permission: PermissionState,
+        devices: Vec<LoopbackDevice>,
+    }
+
+    impl PlatformProbe for MockProbe {
+        fn os_family(&self) -> OsFamily {
+            self.family
+        }
+
+        fn os_version(&self) -> Option<String> {
+            self.version.map(|v| v.to_string())
+        }
+
+        fn screen_capture_permission(&self) -> PermissionState {
+            self.permission
+        }
+
+        fn render_devices(&self) -> Vec<LoopbackDevice> {
+            self.devices.clone()
+        }
+    }
+
+    fn mac(version: &'static str, permission: PermissionState) -> MockProbe {
+        MockProbe { family: OsFamily::MacOS, version: Some(version), permission, devices: Vec::new() }
+    }
+
+    #[test]
+    fn test_unsupported_macos_version() {
+        let capability = probe_system_audio(&mac("12.6.1", PermissionState::Granted));
+
+        assert!(!capability.supported);
+        assert_eq!(capability.minimum_os_version.as_deref(), Some("13.0"));
+        assert!(capability.backend.is_none());
+        assert!(capability.reason.contains("13.0"));
+    }
+
+    #[test]
+    fn test_permission_denied() {
+        let capability = probe_system_audio(&mac("14.2", PermissionState::Denied));
+
+        assert!(!capability.supported);
+        assert_eq!(capability.permission_state, PermissionState::Denied);
+        assert_eq!(capability.backend.as_deref(), Some("ScreenCaptureKit"));
+    }
+
+    #[test]
+    fn test_ready_state() {
+        let capability = probe_system_audio(&mac("13.0", PermissionState::Granted));
+
+        assert!(capability.supported);
+        assert_eq!(capability.reason, "Ready");
+
+        let json = serde_json::to_value(&capability).unwrap();
+        assert_eq!(json["permissionState"], "granted");
+        assert_eq!(json["backend"], "ScreenCaptureKit");
+    }
+
+    #[test]
+    fn test_windows_reports_loopback_per_render_device() {
+        let probe = MockProbe {
+            family: OsFamily::Windows,
+            version: Some("10.0.22631"),
+            permission: PermissionState::NotRequired,
+            devices: vec![
+                LoopbackDevice { name: "Speakers".to_string(), is_default: true, loopback_available: true },
+                LoopbackDevice { name: "HDMI".to_string(), is_default: false, loopback_available: true },
+            ],
+        };
+        let capability = probe_system_audio(&probe);
+
+        assert!(capability.supported);
+        assert_eq!(capability.backend.as_deref(), Some("WASAPI loopback"));
+        assert_eq!(capability.loopback_devices.len(), 2);
+        assert!(capability.loopback_devices[0].is_default);
+    }
+
+    #[test]
+    fn test_unsupported_os() {
+        let probe = MockProbe { family: OsFamily::Other, version: None, permission: PermissionState::Unknown, devices: Vec::new() };
+        assert!(!probe_system_audio(&probe).supported);
+    }
+
+    #[test]
+    fn test_windows_plans_loopback_on_default_render_device() {
+        let render = vec![
+            LoopbackDevice { name: "HDMI".to_string(), is_default: false, loopback_available: true },
+            LoopbackDevice { name: "Speakers".to_string(), is_default: true, loopback_available: true },
+        ];
+        let plan = plan_loopback(OsFamily::Windows, None, &render, &["Microphone".to_string()]);
+        assert_eq!(plan, LoopbackPlan::RenderLoopback { device: "Speakers".to_string() });
+
+        let plan = plan_loopback(OsFamily::Windows, Some("HDMI"), &render, &[]);
+        assert_eq!(plan, LoopbackPlan::RenderLoopback { device: "HDMI".to_string() });
+    }
+
+    #[test]
+    fn test_macos_uses_virtual_loopback_input_or_fails() {
+        let render = vec![LoopbackDevice { name: "MacBook Pro Speakers".to_string(), is_default: true, loopback_available: false }];
+        let inputs = vec!["MacBook Pro Microphone".to_string(), "BlackHole 2ch".to_string()];
+        let plan = plan_loopback(OsFamily::MacOS, None, &render, &inputs);
+        assert_eq!(plan, LoopbackPlan::VirtualInput { device: "BlackHole 2ch".to_string() });
+
+        // Never falls back to the microphone
+        match plan_loopback(OsFamily::MacOS, None, &render, &inputs[..1]) {
+            LoopbackPlan::Unavailable { reason, suggestions } => {
+                assert!(reason.contains("BlackHole"));
+                assert!(!suggestions.is_empty());
+            }
+            other => panic!("unexpected plan {:?}", other),
+        }
+        assert!(matches!(
+            plan_loopback(OsFamily::Linux, Some("Missing"), &[], &inputs),
+            LoopbackPlan::Unavailable { .. }
+        ));
+        assert!(is_virtual_loopback_input("Monitor of Built-in Audio Analog Stereo"));
+    }
+}
@@ -18,15 +18,16 @@ pub struct AudioData {
 }
 
 /// Audio source types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AudioSource {
+    #[default]
     Microphone,
     System,
     File,
 }
 
 /// Audio device information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AudioDevice {
     pub id: String,
     pub name: String,
@@ -34,6 +35,57 @@ pub struct AudioDevice {
     pub is_default: bool,
     pub sample_rates: Vec<u32>,
     pub channels: u8,
+    /// Supported input formats, probed from the device's input configurations
+    #[serde(default)]
+    pub capabilities: DeviceCapabilities,
+}
+
+/// Input formats a device supports
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceCapabilities {
+    #[serde(rename = "minSampleRate")]
+    pub min_sample_rate: u32,
+    #[serde(rename = "maxSampleRate")]
+    pub max_sample_rate: u32,
+    /// Distinct channel counts over all input configurations, ascending
+    #[serde(rename = "supportedChannels")]
+    pub supported_channels: Vec<u16>,
+    /// Rate the device opens with when none is requested
+    #[serde(rename = "defaultSampleRate")]
+    pub default_sample_rate: Option<u32>,
+    /// No configuration captures at the transcription rate, so every chunk is resampled
+    #[serde(rename = "requiresResampling")]
+    pub requires_resampling: bool,
+}
+
+/// One supported input configuration: a sample rate range at a channel count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputConfigRange {
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub channels: u16,
+}
+
+impl DeviceCapabilities {
+    /// Sample rate Whisper transcribes at
+    pub const TARGET_SAMPLE_RATE: u32 = 16000;
+
+    /// Summarize the configurations a device reports
+    pub fn from_ranges(ranges: &[InputConfigRange], default_sample_rate: Option<u32>) -> Self {
+        let mut supported_channels: Vec<u16> = ranges.iter().map(|range| range.channels).collect();
+        supported_channels.sort_unstable();
+        supported_channels.dedup();
+
+        Self {
+            min_sample_rate: ranges.iter().map(|range| range.min_sample_rate).min().unwrap_or(0),
+            max_sample_rate: ranges.iter().map(|range| range.max_sample_rate).max().unwrap_or(0),
+            supported_channels,
+            default_sample_rate,
+            requires_resampling: !ranges.iter().any(|range| {
+                (range.min_sample_rate..=range.max_sample_rate).contains(&Self::TARGET_SAMPLE_RATE)
+            }),
+        }
+    }
 }
 
 /// Audio processing errors
@@ -62,6 +114,9 @@ pub enum AudioError {
     
     #[error("Audio processing failed: {message}")]
     ProcessingFailed { message: String },
+    
+    #[error("System audio capture unavailable: {reason}")]
+    SystemAudioUnavailable { reason: String, suggestions: Vec<String> },
 }
 
 /// VAD-specific errors
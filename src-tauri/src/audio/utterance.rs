@@ -0,0 +1,171 @@
+//! Utterance endpointing for live capture
+//!
+//! Turns per-chunk speech probabilities into utterance boundaries. An
+//! utterance opens when the probability crosses the VAD threshold, stays open
+//! down to a slightly lower threshold, and closes after a stretch of silence
+//! (the hangover). Silence inside the hangover is kept so word tails and short
+//! pauses reach the recognizer; utterances with too little speech, such as a
+//! keyboard clack or a cough, are discarded instead of transcribed.
+
+/// Drop below the VAD threshold an open utterance tolerates before a chunk counts as silence
+pub const HYSTERESIS: f32 = 0.15;
+
+/// Endpointing timings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EndpointConfig {
+    /// Speech (seconds) an utterance needs to be transcribed
+    pub min_speech_seconds: f64,
+    /// Silence (seconds) that ends an utterance
+    pub hangover_seconds: f64,
+}
+
+impl Default for EndpointConfig {
+    fn default() -> Self {
+        Self {
+            min_speech_seconds: 0.25,
+            hangover_seconds: 0.7,
+        }
+    }
+}
+
+/// What to do with one chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkDecision {
+    /// Outside an utterance: not buffered
+    Silence,
+    /// Speech: buffered
+    Speech,
+    /// Silence within the hangover of an open utterance: buffered
+    Trailing,
+    /// The utterance ended: flush the buffer to the recognizer
+    End,
+    /// The utterance ended with too little speech: drop the buffer
+    Discard,
+}
+
+impl ChunkDecision {
+    /// The chunk belongs in the transcription buffer
+    pub fn buffers_chunk(self) -> bool {
+        matches!(self, ChunkDecision::Speech | ChunkDecision::Trailing)
+    }
+}
+
+/// Open utterance state across chunks
+#[derive(Debug, Clone, Default)]
+pub struct UtteranceTracker {
+    config: EndpointConfig,
+    active: bool,
+    speech_seconds: f64,
+    silence_seconds: f64,
+}
+
+impl UtteranceTracker {
+    pub fn new(config: EndpointConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Classify a chunk of `chunk_seconds` with the given speech probability
+    pub fn observe(&mut self, probability: f32, threshold: f32, chunk_seconds: f64) -> ChunkDecision {
+        let is_speech = if self.active {
+            probability >= threshold - HYSTERESIS
+        } else {
+            probability > threshold
+        };
+
+        if is_speech {
+            self.active = true;
+            self.speech_seconds += chunk_seconds;
+            self.silence_seconds = 0.0;
+            return ChunkDecision::Speech;
+        }
+        if !self.active {
+            return ChunkDecision::Silence;
+        }
+
+        self.silence_seconds += chunk_seconds;
+        if self.silence_seconds < self.config.hangover_seconds {
+            return ChunkDecision::Trailing;
+        }
+        let long_enough = self.speech_seconds >= self.config.min_speech_seconds;
+        self.reset();
+        if long_enough {
+            ChunkDecision::End
+        } else {
+            ChunkDecision::Discard
+        }
+    }
+
+    /// An utterance is open
+    pub fn in_utterance(&self) -> bool {
+        self.active
+    }
+
+    /// Forget the open utterance, e.g. after a capture gap
+    pub fn reset(&mut self) {
+        self.active = false;
+        self.speech_seconds = 0.0;
+        self.silence_seconds = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK: f64 = 0.1;
+
+    fn run(tracker: &mut UtteranceTracker, probabilities: &[f32]) -> Vec<ChunkDecision> {
+        probabilities.iter().map(|&p| tracker.observe(p, 0.5, CHUNK)).collect()
+    }
+
+    #[test]
+    fn test_utterance_ends_after_hangover() {
+        let mut tracker = UtteranceTracker::default();
+        let mut probabilities = vec![0.1, 0.9, 0.9, 0.9, 0.8];
+        probabilities.extend([0.1; 7]);
+        let decisions = run(&mut tracker, &probabilities);
+
+        assert_eq!(decisions[0], ChunkDecision::Silence);
+        assert!(decisions[1..5].iter().all(|&d| d == ChunkDecision::Speech));
+        assert!(decisions[5..11].iter().all(|&d| d == ChunkDecision::Trailing));
+        assert_eq!(decisions[11], ChunkDecision::End);
+        assert!(!tracker.in_utterance());
+    }
+
+    #[test]
+    fn test_short_bursts_are_discarded() {
+        // A key press: one loud chunk, then quiet
+        let mut tracker = UtteranceTracker::default();
+        let mut probabilities = vec![0.95];
+        probabilities.extend([0.0; 7]);
+        let decisions = run(&mut tracker, &probabilities);
+        assert_eq!(decisions.last(), Some(&ChunkDecision::Discard));
+        assert!(!decisions.contains(&ChunkDecision::End));
+    }
+
+    #[test]
+    fn test_hysteresis_keeps_soft_speech_in_the_utterance() {
+        let mut tracker = UtteranceTracker::default();
+        // Falling to 0.4 does not start an utterance, but continues one
+        assert_eq!(tracker.observe(0.4, 0.5, CHUNK), ChunkDecision::Silence);
+        assert_eq!(tracker.observe(0.6, 0.5, CHUNK), ChunkDecision::Speech);
+        assert_eq!(tracker.observe(0.4, 0.5, CHUNK), ChunkDecision::Speech);
+        assert_eq!(tracker.observe(0.3, 0.5, CHUNK), ChunkDecision::Trailing);
+        // A pause shorter than the hangover keeps the utterance open
+        assert_eq!(tracker.observe(0.7, 0.5, CHUNK), ChunkDecision::Speech);
+        assert!(tracker.in_utterance());
+    }
+
+    #[test]
+    fn test_reset_drops_the_open_utterance() {
+        let mut tracker = UtteranceTracker::new(EndpointConfig { min_speech_seconds: 0.0, hangover_seconds: 0.2 });
+        tracker.observe(0.9, 0.5, CHUNK);
+        tracker.reset();
+        assert_eq!(tracker.observe(0.0, 0.5, CHUNK), ChunkDecision::Silence);
+        assert!(ChunkDecision::Trailing.buffers_chunk());
+        assert!(!ChunkDecision::End.buffers_chunk());
+    }
+}
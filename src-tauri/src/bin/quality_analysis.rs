@@ -0,0 +1,107 @@
+//! Quality analysis of a recording against a reference transcript
+//!
+//! Transcribes the recording with the given model tier, compares it with the
+//! reference (plain text or ground-truth JSON) and writes the HTML and JSON
+//! reports. Prints `{ "result": ..., "reports": [...] }`, or
+//! `{ "error": { "kind": ..., "message": ... } }` with exit code 1.
+//!
+//! ```text
+//! cargo run --bin quality_analysis -- <audio> <reference> [--tier standard] [--report-dir quality_reports]
+//! ```
+//!
+//! The analysis comes from [`kaginote_lib::quality`]; DER is computed by the
+//! diarization validator of the integration tests, included from there.
+
+use std::process::ExitCode;
+
+use kaginote_lib::asr::types::ModelTier;
+
+#[allow(dead_code)]
+#[path = "../../tests/diarization_realtime/validation.rs"]
+mod validation;
+
+/// Path the analyzer imports the diarization validator from
+mod diarization_realtime {
+    pub(crate) use super::validation;
+}
+
+#[allow(dead_code, unused_imports)]
+#[path = "../../tests/transcription_quality_analyzer.rs"]
+mod transcription_quality_analyzer;
+
+use kaginote_lib::quality::{load_reference_transcript, transcribe_for_analysis, QualityAnalysisConfig, QualityAnalysisError};
+use transcription_quality_analyzer::TranscriptionQualityAnalyzer;
+
+const USAGE: &str = "usage: quality_analysis <audio> <reference> [--tier standard|high-accuracy|turbo] [--report-dir DIR]";
+
+struct Args {
+    audio_path: String,
+    reference_path: String,
+    tier: ModelTier,
+    report_dir: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut tier = ModelTier::Standard;
+    let mut report_dir = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tier" => {
+                let name = args.next().ok_or("--tier needs a value")?;
+                tier = ModelTier::from_quality_tier(&name).ok_or_else(|| format!("Unknown model tier '{}'", name))?;
+            }
+            "--report-dir" => report_dir = Some(args.next().ok_or("--report-dir needs a value")?),
+            _ => positional.push(arg),
+        }
+    }
+    match <[String; 2]>::try_from(positional) {
+        Ok([audio_path, reference_path]) => Ok(Args { audio_path, reference_path, tier, report_dir }),
+        Err(_) => Err(USAGE.to_string()),
+    }
+}
+
+async fn run(args: Args) -> Result<serde_json::Value, QualityAnalysisError> {
+    let audio = kaginote_lib::commands::read_audio_file(&args.audio_path).await
+        .map_err(|message| QualityAnalysisError::IoError { message })?;
+    let audio_duration = audio.samples.len() as f32 / audio.sample_rate.max(1) as f32;
+    let ground_truth = load_reference_transcript(&args.reference_path, audio_duration)?;
+
+    // A single-speaker reference gets its speaker on every word; diarization is not run
+    let speaker = match ground_truth.speaker_segments.as_slice() {
+        [segment] => Some(segment.speaker_id.clone()),
+        _ => None,
+    };
+    let (transcription, performance) = transcribe_for_analysis(&audio, args.tier, speaker).await?;
+
+    let mut config = QualityAnalysisConfig::default();
+    if let Some(dir) = args.report_dir {
+        config.report_output_dir = dir;
+    }
+    let mut analyzer = TranscriptionQualityAnalyzer::with_config(config);
+    let result = analyzer.analyze_quality(transcription, ground_truth, performance)?;
+    let reports = analyzer.report_paths(&result);
+    Ok(serde_json::json!({ "result": result, "reports": reports }))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args).await {
+        Ok(output) => {
+            println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            println!("{}", serde_json::json!({ "error": { "kind": error.kind(), "message": error.to_string() } }));
+            ExitCode::FAILURE
+        }
+    }
+}
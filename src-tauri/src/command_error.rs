@@ -0,0 +1,290 @@
+//! Errors returned by Tauri commands
+//!
+//! Commands reject with a `CommandError`, which reaches the frontend as
+//! `{ code, message, category, recoveryOptions, details }`. The same payload
+//! is the body of `transcription-error` events, so command rejections and
+//! error events share one vocabulary: `code` is a stable snake_case
+//! identifier to localize on, `message` stays the human-readable text.
+
+use serde::{Deserialize, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::asr::types::ASRError;
+use crate::audio::permissions;
+use crate::audio::system_audio::{NativePlatformProbe, PlatformProbe};
+use crate::audio::types::AudioError;
+use crate::diarization::types::DiarizationError;
+
+/// Area an error belongs to, for grouping and guidance in the frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorCategory {
+    Model,
+    Audio,
+    Permissions,
+    Diarization,
+    Storage,
+    Validation,
+    System,
+    Network,
+    Unknown,
+}
+
+impl ErrorCategory {
+    /// Category of an error code such as `model_manager_init_failed`
+    pub fn of_code(code: &str) -> Self {
+        match code {
+            c if c.contains("model") => Self::Model,
+            c if c.contains("permission") => Self::Permissions,
+            c if c.contains("audio") => Self::Audio,
+            c if c.contains("diarization") || c.contains("speaker") => Self::Diarization,
+            c if c.contains("storage") || c.contains("backup") || c.contains("session_store") => Self::Storage,
+            c if c.contains("invalid") || c.contains("config") || c.contains("not_found") => Self::Validation,
+            c if c.contains("system") || c.contains("session") => Self::System,
+            c if c.contains("network") => Self::Network,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Serialized form of a `CommandError`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandErrorPayload {
+    pub code: String,
+    pub message: String,
+    pub category: ErrorCategory,
+    #[serde(rename = "recoveryOptions")]
+    pub recovery_options: Vec<String>,
+    #[serde(default)]
+    pub details: Option<serde_json::Value>,
+}
+
+/// Error returned by a Tauri command
+#[derive(Debug, Error)]
+pub enum CommandError {
+    #[error(transparent)]
+    Audio(#[from] AudioError),
+    /// Whisper model loading, download and transcription
+    #[error(transparent)]
+    Model(#[from] ASRError),
+    #[error(transparent)]
+    Diarization(#[from] DiarizationError),
+    /// Session store, speaker database and files on disk
+    #[error("{0}")]
+    Storage(String),
+    /// Rejected command arguments
+    #[error("{0}")]
+    InvalidInput(String),
+    /// Session, speaker or model that does not exist
+    #[error("{0}")]
+    NotFound(String),
+    /// Failure reported with its own code and recovery steps
+    #[error("{message}")]
+    Detailed {
+        code: String,
+        message: String,
+        recovery_options: Vec<String>,
+        details: Option<serde_json::Value>,
+    },
+    /// Any other failure, described by its message
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl CommandError {
+    /// Error with an explicit code; the category follows from the code
+    pub fn detailed(code: &str, message: impl Into<String>, recovery_options: Vec<String>) -> Self {
+        Self::Detailed {
+            code: code.to_string(),
+            message: message.into(),
+            recovery_options,
+            details: None,
+        }
+    }
+
+    /// Attach structured context (paths, sizes, ids) for the frontend
+    pub fn with_details(self, details: serde_json::Value) -> Self {
+        match self {
+            Self::Detailed { code, message, recovery_options, .. } => Self::Detailed {
+                code,
+                message,
+                recovery_options,
+                details: Some(details),
+            },
+            other => Self::Detailed {
+                code: other.code().to_string(),
+                message: other.to_string(),
+                recovery_options: other.recovery_options(),
+                details: Some(details),
+            },
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            Self::Audio(error) => match error {
+                AudioError::InvalidSampleRate(_) => "audio_invalid_sample_rate",
+                AudioError::PermissionDenied { .. } => "audio_permission_denied",
+                AudioError::DeviceDisconnected { .. } => "audio_device_disconnected",
+                AudioError::NoAudioMethodAvailable { .. } => "audio_no_capture_method",
+                AudioError::NoFallbackDevice => "audio_no_fallback_device",
+                AudioError::InitializationFailed { .. } => "audio_initialization_failed",
+                AudioError::BufferOverflow => "audio_buffer_overflow",
+                AudioError::ProcessingFailed { .. } => "audio_processing_failed",
+                AudioError::SystemAudioUnavailable { .. } => "system_audio_unavailable",
+            },
+            Self::Model(error) => match error {
+                ASRError::InsufficientMemory { .. } | ASRError::OutOfMemory => "model_insufficient_memory",
+                ASRError::ModelNotFound { .. } => "model_not_found",
+                ASRError::ModelLoadFailed { .. } => "model_load_failed",
+                ASRError::DownloadCancelled => "model_download_cancelled",
+                ASRError::ModelVerificationFailed { .. } => "model_verification_failed",
+                ASRError::DeviceNotAvailable { .. } => "model_device_unavailable",
+                _ => "model_transcription_failed",
+            },
+            Self::Diarization(error) => match error {
+                DiarizationError::ModelLoadError { .. } => "diarization_model_load_failed",
+                DiarizationError::SessionNotFound { .. } => "diarization_session_not_found",
+                DiarizationError::ConfigError { .. } => "diarization_invalid_config",
+                _ => "diarization_failed",
+            },
+            Self::Storage(_) => "storage_failed",
+            Self::InvalidInput(_) => "invalid_input",
+            Self::NotFound(_) => "not_found",
+            Self::Detailed { code, .. } => code.as_str(),
+            Self::Failed(_) => "command_failed",
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Audio(AudioError::PermissionDenied { .. }) => ErrorCategory::Permissions,
+            Self::Audio(_) => ErrorCategory::Audio,
+            Self::Model(_) => ErrorCategory::Model,
+            Self::Diarization(_) => ErrorCategory::Diarization,
+            Self::Storage(_) => ErrorCategory::Storage,
+            Self::InvalidInput(_) | Self::NotFound(_) => ErrorCategory::Validation,
+            Self::Detailed { code, .. } => ErrorCategory::of_code(code),
+            Self::Failed(_) => ErrorCategory::Unknown,
+        }
+    }
+
+    pub fn recovery_options(&self) -> Vec<String> {
+        match self {
+            Self::Audio(AudioError::PermissionDenied { .. }) => {
+                permissions::recovery_options(NativePlatformProbe.os_family())
+            }
+            Self::Audio(AudioError::DeviceDisconnected { .. }) | Self::Audio(AudioError::NoFallbackDevice) => vec![
+                "Reconnect the audio device or choose another input".to_string(),
+            ],
+            Self::Audio(AudioError::SystemAudioUnavailable { suggestions, .. }) => suggestions.clone(),
+            Self::Model(ASRError::InsufficientMemory { .. }) | Self::Model(ASRError::OutOfMemory) => vec![
+                "Close other memory-intensive applications".to_string(),
+                "Choose a smaller quality tier".to_string(),
+            ],
+            Self::Model(ASRError::ModelNotFound { .. }) | Self::Model(ASRError::ModelVerificationFailed { .. }) => vec![
+                "Download the model again".to_string(),
+            ],
+            Self::Diarization(_) => vec![
+                "Retry speaker identification with retry_diarization".to_string(),
+            ],
+            Self::Detailed { recovery_options, .. } => recovery_options.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::Audio(AudioError::PermissionDenied { device })
+            | Self::Audio(AudioError::DeviceDisconnected { device }) => Some(serde_json::json!({ "device": device })),
+            Self::Audio(AudioError::NoAudioMethodAvailable { attempted_methods }) => {
+                Some(serde_json::json!({ "attemptedMethods": attempted_methods }))
+            }
+            Self::Model(ASRError::ModelNotFound { path }) => Some(serde_json::json!({ "path": path })),
+            Self::Diarization(DiarizationError::SessionNotFound { session_id }) => Some(serde_json::json!({ "sessionId": session_id })),
+            Self::Detailed { details, .. } => details.clone(),
+            _ => None,
+        }
+    }
+
+    pub fn payload(&self) -> CommandErrorPayload {
+        CommandErrorPayload {
+            code: self.code().to_string(),
+            message: self.to_string(),
+            category: self.category(),
+            recovery_options: self.recovery_options(),
+            details: self.details(),
+        }
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.payload().serialize(serializer)
+    }
+}
+
+/// Messages from helpers that still report `Result<_, String>`
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::Failed(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_shape() {
+        let error = CommandError::detailed("session_already_active", "Another session is running", vec![
+            "Stop the current session first".to_string(),
+        ]).with_details(serde_json::json!({ "sessionIds": ["a"] }));
+
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "session_already_active");
+        assert_eq!(value["message"], "Another session is running");
+        assert_eq!(value["category"], "system");
+        assert_eq!(value["recoveryOptions"][0], "Stop the current session first");
+        assert_eq!(value["details"]["sessionIds"][0], "a");
+        // Display keeps the plain message
+        assert_eq!(error.to_string(), "Another session is running");
+    }
+
+    #[test]
+    fn test_domain_errors_keep_code_and_category() {
+        let permission = CommandError::from(AudioError::PermissionDenied { device: "Built-in Microphone".to_string() });
+        assert_eq!(permission.code(), "audio_permission_denied");
+        assert_eq!(permission.category(), ErrorCategory::Permissions);
+        assert_eq!(permission.details().unwrap()["device"], "Built-in Microphone");
+        assert!(!permission.recovery_options().is_empty());
+
+        let model = CommandError::from(ASRError::ModelNotFound { path: "/models/medium.bin".to_string() });
+        assert_eq!(model.code(), "model_not_found");
+        assert_eq!(model.category(), ErrorCategory::Model);
+        assert_eq!(model.to_string(), "Model not found at path: /models/medium.bin");
+
+        let diarization = CommandError::from(DiarizationError::SessionNotFound { session_id: "s1".to_string() });
+        assert_eq!(diarization.category(), ErrorCategory::Diarization);
+
+        let legacy = CommandError::from(format!("Failed to open {}", "notes.md"));
+        assert_eq!(legacy.code(), "command_failed");
+        assert_eq!(legacy.payload().message, "Failed to open notes.md");
+    }
+
+    #[test]
+    fn test_category_of_code() {
+        assert_eq!(ErrorCategory::of_code("model_disk_space_insufficient"), ErrorCategory::Model);
+        assert_eq!(ErrorCategory::of_code("audio_capture_already_active"), ErrorCategory::Audio);
+        assert_eq!(ErrorCategory::of_code("microphone_permission_denied"), ErrorCategory::Permissions);
+        assert_eq!(ErrorCategory::of_code("invalid_config"), ErrorCategory::Validation);
+        assert_eq!(ErrorCategory::of_code("system_validation_failed"), ErrorCategory::System);
+        assert_eq!(ErrorCategory::of_code("something_else"), ErrorCategory::Unknown);
+    }
+}
@@ -5,19 +5,100 @@
 
 use serde::{Deserialize, Serialize};
 use crate::audio::capture::{AudioCaptureService, AudioConfig};
-use crate::audio::types::{AudioData, AudioDevice, AudioSource};
+use crate::audio::capture_simulator::SimulatorConfig;
+use crate::audio::types::{AudioData, AudioDevice, AudioError, AudioSource, DeviceCapabilities};
+use crate::command_error::CommandError;
 use crate::audio::device_profiles::DeviceProfileManager;
+use crate::audio::file_decoder;
+use crate::audio::mixer::SourceAttribution;
+use crate::audio::peaks;
+use crate::audio::levels::{self, NoiseFloorEstimator};
+use crate::audio::reconnect::{self, DeviceOutage};
+use crate::audio::utterance::{ChunkDecision, UtteranceTracker};
+use crate::audio::backpressure::{self, ChannelHealth, DropWarnings};
+use crate::audio::energy_vad::EnergyVad;
+use crate::audio::recording_writer::{self, RecordingHealth, RecordingStop, RecordingWriterConfig, ResilientRecordingWriter, SinkFactory, WavFileSink};
+use crate::audio::system_audio::{self, NativePlatformProbe, SystemAudioCapability};
+use crate::audio::permissions::{self, MicrophonePermission, MicrophonePermissionProbe, NativeMicrophonePermission};
+use crate::audio::device_watcher::{self, DeviceWatcher};
+use crate::audio::noise_suppression::NoiseSuppressor;
+use crate::audio::agc::{self, AgcConfig, AutomaticGainControl};
+use crate::audio::resampler::{ResamplerUtils, ResamplingPreference};
+use crate::audio::ring_buffer::{self, SessionAudioBuffer};
+use crate::audio::segment_audio::{self, RecordingTimeline, SegmentAudio};
 use crate::asr::whisper::{WhisperEngine, WhisperConfig};
-use crate::asr::types::{ASRResult, TranscriptionContext};
-use crate::diarization::{DiarizationService, DiarizationConfig};
+use crate::asr::engine_pool::{EngineLease, EnginePool};
+use crate::asr::transcriber::SharedTranscriber;
+use crate::asr::model_manager::{self, DownloadCancellation, DownloadedModel, ModelManager, ModelRequirements, ModelVerification, ProgressCallback};
+use crate::asr::models_location::{self, ModelsLocation, ModelsMigration};
+use crate::asr::prompt_budget::PromptBudget;
+use crate::asr::temperature_fallback::{self, FallbackStats};
+use crate::asr::word_timing;
+use crate::asr::types::{ASRResult, Language, LanguageInfo, TranscriptionContext};
+use crate::diarization::reattribution::{self, ReattributionSummary};
+use crate::diarization::muting::{self, SpeakerMutes};
+use crate::diarization::speaker_names::{self, SpeakerNames};
+use crate::diarization::enrollment;
+use crate::diarization::refinement::{self, SessionEmbeddings};
+use crate::diarization::overlap::{self, ChunkVoice};
+use crate::diarization::session_stats::{self, AttributionCounters, AttributionOutcome, DiarizationStats};
+use crate::diarization::recovery::{self, DiarizationStatus};
+use crate::diarization::speaker_stats;
+use crate::diarization::known_speakers::{self, KnownSpeakerMatch, UnknownSpeakers};
+use crate::diarization::{DiarizationService, DiarizationConfig, SpeakerCountEstimate, SpeakerCountEstimator, SpeakerEmbedding};
 use crate::models::{
-    SpeakerProfile as DbSpeakerProfile, VoiceEmbedding, MeetingSpeaker, SimilarSpeaker, 
+    SpeakerProfile as DbSpeakerProfile, VoiceEmbedding, SpeakerSample, MeetingSpeaker, SimilarSpeaker, 
     CreateSpeakerProfileRequest, UpdateSpeakerProfileRequest, SpeakerIdentification
 };
 use crate::storage::{Database, SpeakerStore, EmbeddingIndex, SeedManager};
-use crate::transcription::{ContentHasher, TemporalAnalyzer, BoundaryDetector};
+use crate::storage::embedding_index::{save_index, save_index_when_idle, IndexLoadError, EMBEDDING_INDEX_FILE};
+use crate::storage::speaker_store::SPEAKER_SAMPLE_CAP_SECONDS;
+use crate::storage::profile_transfer::{self, ConflictStrategy, ImportError, SpeakerImport};
+use crate::storage::usage_metrics::{SessionUsageMetrics, UsageMetricsStore, UsageSummary};
+use crate::storage::session_store::{self, SavedSession, SavedSessionSummary, SessionStore};
+use crate::storage::transcript_search::{SearchOptions, SearchPage};
+use crate::storage::autosave::{self, RecoverableSession, SessionAutosave};
+use crate::storage::event_log::{self, EventLogWriter, LoggedEvent, SessionEventLog, EVENT_LOG_QUEUE_CAPACITY};
+use crate::storage::backup::{self, BackupPolicy, BackupSource, BackupStatus};
+use crate::storage::storage_usage::{self, StorageLayout, StorageUsageCache, StorageUsageReport, WorkspaceQuota};
+use crate::transcription::{ContentHasher, TemporalAnalyzer, BoundaryDetector, RolloverPolicy, SessionRecord};
+use crate::transcription::session_chain;
+use crate::startup::{InitState, ReadinessGate, StartupReport};
+use crate::session_tasks::{SessionCancellation, SessionTasks};
+use crate::shutdown::{self, ExitCleanup};
+use crate::power::{self, PowerAssertionState, PowerAssertions};
+use crate::quality::{ground_truth, GroundTruthData, QualityAnalysisError};
+use crate::resource_monitor::{self, ResourceSampler};
+use crate::logging::{self, LogEntry};
+use crate::diagnostics::{self, BundleFile, DiagnosticsManifest, Redactor};
+use crate::instance_lock;
+use crate::metadata;
+use crate::config_validation::{self, validate_transcription_config, ConfigValidationReport};
+use crate::transcription::postprocess::{PunctuationConfig, PunctuationRestorer, PunctuationRules};
+use crate::transcription::speech_rate::{boundary_flush_due, AdaptiveChunkSizer};
+use crate::transcription::system_status::{LoopStatus, NoiseSuppressionStatus, ReplayBufferStatus, SystemStatus, SYSTEM_STATUS_INTERVAL};
+use crate::transcription::speaker_naming::{self, NameSuggestion, SpeakerNameSuggester};
+use crate::transcription::highlights::{self, Highlight, HighlightRequest};
+use crate::transcription::subtitles::{self, SubtitleExport, SubtitleFormat};
+use crate::transcription::markdown::{self, MeetingNotesExport};
+use crate::transcription::batch::{self, BatchCancellation, BatchFileOutcome, BatchProgress, BatchSummary};
+use crate::transcription::quality_metrics::{self, ProcessingStats, SessionQualityMetrics};
 use crate::transcription::temporal_analyzer::TemporalSegment;
+use crate::transcription::window_routing::TranscriptWindowRouter;
+use crate::transcription::session_clock::SessionClock;
+use crate::transcription::partials::{self, PartialScheduler};
+use crate::transcription::adaptive_tier::{self, TierAdapter};
 use crate::transcription::boundary_detector::{AudioChunk, BoundaryConfig, BoundaryType};
+use crate::transcription::content_hasher;
+use crate::transcription::hallucination::{self, BufferSpeech, HallucinationFilter};
+use crate::transcription::keywords::{KeywordMatcher, KeywordRule, KeywordSummary};
+use crate::transcription::chapters::{self, Chapter, ChapterConfig};
+use crate::transcription::session_progress::{AudioLevelSnapshot, ModelStatus, SessionProgress};
+use crate::transcription::review;
+use crate::transcription::translation;
+use crate::transcription::auto_stop::{AutoStopEvent, AutoStopReason, AutoStopTimer};
+use crate::transcription::heartbeat::{self, SessionHeartbeat};
+use crate::transcription::chunk_window::{self, ChunkWindow};
 use uuid::Uuid;
 use tauri::{Emitter, Manager, State};
 use std::collections::{HashMap, VecDeque};
@@ -25,55 +106,193 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing;
 use sysinfo;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 /// Application state holding persistent services and sessions
 pub struct AppState {
-    /// Audio capture service instance
-    pub audio_capture_service: Arc<Mutex<Option<AudioCaptureService>>>,
-    /// Whisper ASR engine instance
-    pub whisper_engine: Arc<Mutex<Option<WhisperEngine>>>,
+    /// Audio capture services by session id; start_audio_capture's under [`STANDALONE_CAPTURE`]
+    pub audio_captures: Arc<Mutex<HashMap<String, SharedCapture>>>,
+    /// Whisper engines by model tier, leased by sessions and file transcriptions
+    pub engine_pool: Arc<std::sync::Mutex<EnginePool>>,
     /// Diarization service instance
     pub diarization_service: Arc<Mutex<Option<DiarizationService>>>,
     /// Active transcription sessions
     pub active_sessions: Arc<Mutex<HashMap<String, TranscriptionSessionState>>>,
-    /// Device profile manager for caching optimal configurations
-    pub device_profile_manager: Arc<Mutex<DeviceProfileManager>>,
+    /// Device profile manager for caching optimal configurations (loaded from disk on first use)
+    pub device_profile_manager: Arc<tokio::sync::OnceCell<Mutex<DeviceProfileManager>>>,
     /// Speaker storage database
     pub speaker_database: Arc<Mutex<Option<Database>>>,
     /// Speaker store for CRUD operations
     pub speaker_store: Arc<Mutex<Option<SpeakerStore>>>,
-    /// Fast embedding index for similarity search
-    pub embedding_index: Arc<Mutex<EmbeddingIndex>>,
+    /// Fast embedding index for similarity search (built on first use, see [`AppState::embedding_index`])
+    pub embedding_index: Arc<tokio::sync::OnceCell<Arc<Mutex<EmbeddingIndex>>>>,
+    /// File the embedding index is saved to, once speaker storage is initialized
+    pub embedding_index_path: Arc<Mutex<Option<PathBuf>>>,
+    /// Finalized sessions, including parts produced by automatic rollover
+    pub completed_sessions: Arc<Mutex<HashMap<String, SessionRecord>>>,
+    /// Per-session logs of emitted events, written in the background (std mutex so emitting stays synchronous)
+    pub event_logs: Arc<std::sync::Mutex<HashMap<String, Arc<EventLogWriter>>>>,
+    /// Readiness of the speaker storage initialized in the background at startup
+    pub speaker_storage_gate: ReadinessGate,
+    /// Startup phase timings
+    pub startup_report: Arc<std::sync::Mutex<StartupReport>>,
+    /// Detached transcript windows and the sessions they show
+    pub transcript_windows: Arc<std::sync::Mutex<TranscriptWindowRouter>>,
+    /// Outcome of the most recent backup
+    pub backup_status: Arc<std::sync::Mutex<BackupStatus>>,
+    /// Serializes backups so a session save and the daily backup never overlap
+    pub backup_lock: Arc<Mutex<()>>,
+    /// Sleep prevention held while sessions are active
+    pub power_assertions: Arc<std::sync::Mutex<PowerAssertions>>,
+    /// Last disk usage report and the walk in progress
+    pub storage_usage: Arc<std::sync::Mutex<StorageUsageCache>>,
+    /// In-flight model downloads by session, for cancellation
+    pub model_downloads: Arc<std::sync::Mutex<HashMap<String, DownloadCancellation>>>,
+    /// Folder transcription in progress, for cancellation
+    pub batch_transcription: Arc<std::sync::Mutex<Option<BatchCancellation>>>,
+    /// Session recordings not yet finalized; set to true once the WAV file is complete
+    pub recordings_in_progress: Arc<std::sync::Mutex<HashMap<String, tokio::sync::watch::Sender<bool>>>>,
+    /// Input device polling for hot-plug events
+    pub device_watcher: Arc<std::sync::Mutex<DeviceWatcher>>,
+    /// Model initialization and transcription loops of active sessions, for cancellation
+    pub session_tasks: Arc<std::sync::Mutex<SessionTasks>>,
+    /// Saving of active sessions when the app exits
+    pub exit_cleanup: Arc<ExitCleanup>,
+    /// Autosaves a previous run left behind, found at startup
+    pub recoverable_sessions: Arc<std::sync::Mutex<Vec<RecoverableSession>>>,
+    /// Held while an autosave is written or removed, so a stopped session's file stays removed
+    pub autosave_lock: Arc<Mutex<()>>,
 }
 
+/// How long commands wait for background startup work before reporting "initializing"
+const STARTUP_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
 impl AppState {
+    /// Create the application state. Only cheap, in-memory members are built here;
+    /// disk-backed members are initialized lazily or in the background after startup.
     pub fn new() -> Self {
-        let device_profile_manager = DeviceProfileManager::new()
-            .unwrap_or_else(|e| {
-                tracing::warn!("Failed to initialize device profile manager: {}. Using fallback.", e);
-                // Create a fallback manager without cache file
-                DeviceProfileManager::new().unwrap()
-            });
-
-        // Initialize embedding index with default dimensions (512 for typical speaker embeddings)
-        let embedding_index = EmbeddingIndex::new(512, 8);
+        let started = std::time::Instant::now();
+        let mut startup_report = StartupReport::default();
+        startup_report.record("app_state", started.elapsed());
 
         Self {
-            audio_capture_service: Arc::new(Mutex::new(None)),
-            whisper_engine: Arc::new(Mutex::new(None)),
+            audio_captures: Arc::new(Mutex::new(HashMap::new())),
+            engine_pool: Arc::new(std::sync::Mutex::new(EnginePool::new())),
             diarization_service: Arc::new(Mutex::new(None)),
             active_sessions: Arc::new(Mutex::new(HashMap::new())),
-            device_profile_manager: Arc::new(Mutex::new(device_profile_manager)),
+            device_profile_manager: Arc::new(tokio::sync::OnceCell::new()),
             speaker_database: Arc::new(Mutex::new(None)),
             speaker_store: Arc::new(Mutex::new(None)),
-            embedding_index: Arc::new(Mutex::new(embedding_index)),
+            embedding_index: Arc::new(tokio::sync::OnceCell::new()),
+            embedding_index_path: Arc::new(Mutex::new(None)),
+            completed_sessions: Arc::new(Mutex::new(HashMap::new())),
+            event_logs: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            speaker_storage_gate: ReadinessGate::new("Speaker storage"),
+            startup_report: Arc::new(std::sync::Mutex::new(startup_report)),
+            transcript_windows: Arc::new(std::sync::Mutex::new(TranscriptWindowRouter::new())),
+            backup_status: Arc::new(std::sync::Mutex::new(BackupStatus::default())),
+            backup_lock: Arc::new(Mutex::new(())),
+            power_assertions: Arc::new(std::sync::Mutex::new(PowerAssertions::new(power::native_platform()))),
+            storage_usage: Arc::new(std::sync::Mutex::new(StorageUsageCache::default())),
+            model_downloads: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            batch_transcription: Arc::new(std::sync::Mutex::new(None)),
+            recordings_in_progress: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            device_watcher: Arc::new(std::sync::Mutex::new(DeviceWatcher::new())),
+            session_tasks: Arc::new(std::sync::Mutex::new(SessionTasks::new())),
+            exit_cleanup: Arc::new(ExitCleanup::new()),
+            recoverable_sessions: Arc::new(std::sync::Mutex::new(Vec::new())),
+            autosave_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Device profile manager, reading the profile cache from disk on first use.
+    /// A failed load is returned and tried again on the next use.
+    pub async fn device_profiles(&self) -> Result<&Mutex<DeviceProfileManager>, AudioError> {
+        self.device_profile_manager.get_or_try_init(|| async {
+            let started = std::time::Instant::now();
+            let manager = tokio::task::spawn_blocking(DeviceProfileManager::new)
+                .await
+                .map_err(|e| AudioError::InitializationFailed { source: Box::new(e) })??;
+            self.record_startup_phase("device_profiles", started.elapsed());
+            Ok(Mutex::new(manager))
+        }).await
+    }
+
+    /// Embedding index, created on first use with default dimensions (512 for
+    /// typical speaker embeddings); speaker storage fills it in the background
+    pub async fn embedding_index(&self) -> &Arc<Mutex<EmbeddingIndex>> {
+        self.embedding_index.get_or_init(|| async {
+            let started = std::time::Instant::now();
+            let index = Arc::new(Mutex::new(EmbeddingIndex::new(512, 8)));
+            self.record_startup_phase("embedding_index", started.elapsed());
+            index
+        }).await
+    }
+
+    /// Wait briefly for speaker storage, returning a clear error while it is still initializing
+    pub async fn speaker_storage_ready(&self) -> Result<(), String> {
+        self.speaker_storage_gate.wait_ready(STARTUP_READY_TIMEOUT).await
+    }
+
+    /// Record the duration of a startup phase in the startup report
+    pub fn record_startup_phase(&self, name: &str, elapsed: std::time::Duration) {
+        if let Ok(mut report) = self.startup_report.lock() {
+            report.record(name, elapsed);
+        }
+    }
+
+    /// Start recording the events emitted for a session
+    pub fn open_event_log(&self, session_id: &str, redact_text: bool) {
+        if let Err(e) = instance_lock::assert_held("Session event log") {
+            tracing::warn!("Session events will not be logged: {}", e);
+            return;
+        }
+        let Some(dir) = event_log_dir() else {
+            tracing::warn!("Failed to get app data directory, session events will not be logged");
+            return;
+        };
+        
+        let max_bytes = event_log::event_log_max_bytes(load_workspace_quota().map(|q| q.max_bytes));
+        match SessionEventLog::open(&dir, session_id, max_bytes, redact_text) {
+            Ok(log) => {
+                let writer = Arc::new(EventLogWriter::spawn(log, EVENT_LOG_QUEUE_CAPACITY));
+                if let Ok(mut logs) = self.event_logs.lock() {
+                    logs.insert(session_id.to_string(), writer);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to open event log for session {}: {}", session_id, e),
+        }
+    }
+
+    /// Stop recording events for a session (the log file is kept for later dumps)
+    pub fn close_event_log(&self, session_id: &str) {
+        // Dropped outside the lock: the writer finishes its queue first
+        let closed = self.event_logs.lock().ok().and_then(|mut logs| logs.remove(session_id));
+        if let Some(writer) = closed {
+            if writer.dropped_events() > 0 {
+                tracing::warn!("{} events of session {} were not logged", writer.dropped_events(), session_id);
+            }
         }
     }
 
     /// Initialize speaker storage database
     pub async fn initialize_speaker_storage(&self) -> Result<(), String> {
+        let started = std::time::Instant::now();
+        self.speaker_storage_gate.set(InitState::Initializing);
+        
+        let result = self.initialize_speaker_storage_inner().await;
+        match &result {
+            Ok(()) => self.speaker_storage_gate.set(InitState::Ready),
+            Err(e) => self.speaker_storage_gate.set(InitState::Failed(e.clone())),
+        }
+        self.record_startup_phase("speaker_storage", started.elapsed());
+        result
+    }
+
+    async fn initialize_speaker_storage_inner(&self) -> Result<(), String> {
+        instance_lock::assert_held("Speaker storage").map_err(|e| e.to_string())?;
+        
         // Get app data directory
         let app_data_dir = dirs::data_local_dir()
             .ok_or("Failed to get app data directory")?;
@@ -93,9 +312,38 @@ impl AppState {
         database.migrate().await
             .map_err(|e| format!("Failed to run database migrations: {}", e))?;
         
+        // Sessions still marked active were cut short by a crash or forced quit
+        match SessionStore::new(database.clone()).mark_interrupted().await {
+            Ok(interrupted) if !interrupted.is_empty() => {
+                tracing::warn!("Marked {} unfinished session(s) from a previous run as interrupted", interrupted.len());
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to mark interrupted sessions: {}", e),
+        }
+        
         // Create speaker store
         let speaker_store = SpeakerStore::new(database.clone());
         
+        // Live sessions search the index to recognise speakers from earlier sessions.
+        // The index saved by the previous run is used while it still matches storage.
+        let index_path = kaginote_dir.join(EMBEDDING_INDEX_FILE);
+        if !self.load_saved_embedding_index(&speaker_store, &index_path).await {
+            match active_profile_embeddings(&speaker_store).await {
+                Ok(embeddings) => {
+                    let count = embeddings.len();
+                    match self.embedding_index().await.lock().await.rebuild(embeddings) {
+                        Ok(()) => tracing::info!("Rebuilt the embedding index from {} stored voice embeddings", count),
+                        Err(e) => tracing::warn!("Failed to load the embedding index: {}", e),
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to load the embedding index: {}", e),
+            }
+        }
+        // A retried initialization keeps the saver started by the first attempt
+        if self.embedding_index_path.lock().await.replace(index_path.clone()).is_none() {
+            tauri::async_runtime::spawn(save_index_when_idle(self.embedding_index().await.clone(), index_path));
+        }
+        
         // Update app state
         {
             let mut db_guard = self.speaker_database.lock().await;
@@ -110,6 +358,80 @@ impl AppState {
         tracing::info!("Speaker storage initialized successfully");
         Ok(())
     }
+
+    /// Replace the embedding index with the one saved at `path`. Returns false
+    /// when there is none usable and the index must be rebuilt.
+    async fn load_saved_embedding_index(&self, speaker_store: &SpeakerStore, path: &Path) -> bool {
+        let expected = match speaker_store.active_voice_embeddings_fingerprint().await {
+            Ok(fingerprint) => fingerprint,
+            Err(e) => {
+                tracing::warn!("Failed to fingerprint stored voice embeddings: {}", e);
+                return false;
+            }
+        };
+        let mut index = self.embedding_index().await.lock().await;
+        let (dimension, num_hashes) = (index.embedding_dimension(), index.num_hashes());
+        let path = path.to_path_buf();
+        let loaded = tokio::task::spawn_blocking(move || EmbeddingIndex::load(&path, dimension, num_hashes, expected)).await;
+        match loaded {
+            Ok(Ok(saved)) => {
+                let count = saved.get_stats().map(|stats| stats.total_embeddings).unwrap_or_default();
+                *index = saved;
+                tracing::info!("Loaded the saved embedding index with {} voice embeddings", count);
+                true
+            }
+            Ok(Err(IndexLoadError::Missing(_))) => false,
+            Ok(Err(e)) => {
+                tracing::warn!("Rebuilding the embedding index: {}", e);
+                false
+            }
+            Err(e) => {
+                tracing::warn!("Failed to load the saved embedding index: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Write the embedding index to disk if it has unsaved changes
+    pub async fn flush_embedding_index(&self) {
+        let Some(path) = self.embedding_index_path.lock().await.clone() else {
+            return;
+        };
+        // An index nothing used was never built and has nothing to save
+        let Some(index) = self.embedding_index.get() else {
+            return;
+        };
+        if let Err(e) = save_index(index, &path).await {
+            tracing::warn!("Failed to save the embedding index: {}", e);
+        }
+    }
+
+    /// Look for autosaves of sessions a previous run did not finish
+    pub async fn find_recoverable_sessions(&self) {
+        let Some(dir) = autosave::autosave_dir() else {
+            return;
+        };
+        let found = match tokio::task::spawn_blocking(move || autosave::scan_autosaves(&dir)).await {
+            Ok(Ok(found)) => found,
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to look for session autosaves: {:#}", e);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to look for session autosaves: {}", e);
+                return;
+            }
+        };
+        // Sessions started before the scan finished have autosaves of this run
+        let active: Vec<String> = self.active_sessions.lock().await.keys().cloned().collect();
+        let found: Vec<_> = found.into_iter().filter(|session| !active.contains(&session.session_id)).collect();
+        if !found.is_empty() {
+            tracing::warn!("Found {} autosaved session(s) from a previous run", found.len());
+        }
+        if let Ok(mut recoverable) = self.recoverable_sessions.lock() {
+            *recoverable = found;
+        }
+    }
 }
 
 /// Internal session state tracking
@@ -122,6 +444,79 @@ pub struct TranscriptionSessionState {
     pub audio_capture: Option<String>, // Reference to audio capture instance
     pub whisper_config: WhisperConfig,
     pub transcription_segments: Vec<serde_json::Value>, // Store transcription segments
+    pub continuation_of: Option<String>, // Previous session when created by automatic rollover
+    pub error_count: u32, // Errors reported during the session (for local usage metrics)
+    pub speaker_embeddings: Vec<SpeakerEmbedding>, // Recent embeddings re-clustered for the speaker count estimate
+    pub speaker_estimate: Option<SpeakerCountEstimate>,
+    pub recording_path: Option<String>, // Session audio recording, possibly on a network share
+    pub recording_timeline: Option<RecordingTimeline>, // Where the recording starts on the session clock, for segment sample offsets
+    pub speaker_mutes: SpeakerMutes, // Speakers left out of the transcript stream and default exports
+    pub speaker_names: SpeakerNames, // Display names given to diarization speakers during the session
+    pub processing_stats: ProcessingStats, // Measured transcription time for the quality metrics
+    pub engine: Option<EngineLease>, // Whisper engine, leased once the model is loaded
+    pub transcriber: Option<SharedTranscriber>, // Replaces the engine for final passes, e.g. a scripted transcriber in tests
+    pub unknown_speakers: UnknownSpeakers, // Speakers no stored profile matched, kept for provisional profiles
+    pub session_embeddings: SessionEmbeddings, // Embeddings of stored segments for the session-end speaker refinement
+    pub attribution_counters: AttributionCounters, // How live chunks were attributed, for the diarization stats
+    pub diarization_status: DiarizationStatus, // Degraded when the diarization models failed to load
+    pub progress: SessionProgress, // Model status, segment count and latest audio level for get_active_sessions
+    pub clock: SessionClock, // Audio-time offsets shared by transcript segments and diarization results
+    pub audio_buffer: SessionAudioBuffer, // Recent audio on the stream timeline, for replay and re-transcription
+    pub heartbeat: SessionHeartbeat, // Last transcription loop iteration, to detect a dead loop
+    pub chunk_window: ChunkWindow, // Transcription buffer bounds and overlap from the config
+}
+
+impl TranscriptionSessionState {
+    /// State of a session that is still starting: it holds the session slot
+    /// but has no capture or engine yet
+    fn reserved(session_id: &str, config: &TranscriptionConfig) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            config: config.clone(),
+            start_time: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            status: SESSION_STARTING.to_string(),
+            audio_capture: None,
+            whisper_config: WhisperConfig::default(),
+            transcription_segments: Vec::new(), // Initialize empty segments vector
+            continuation_of: None,
+            error_count: 0,
+            speaker_embeddings: Vec::new(),
+            speaker_estimate: None,
+            recording_path: None,
+            recording_timeline: None,
+            speaker_mutes: SpeakerMutes::new(),
+            speaker_names: SpeakerNames::new(),
+            processing_stats: ProcessingStats::default(),
+            engine: None,
+            transcriber: None,
+            unknown_speakers: UnknownSpeakers::new(),
+            session_embeddings: SessionEmbeddings::new(),
+            attribution_counters: AttributionCounters::default(),
+            diarization_status: DiarizationStatus::initial(config.enable_speaker_diarization),
+            progress: SessionProgress::default(),
+            clock: SessionClock::new(),
+            audio_buffer: SessionAudioBuffer::new(config.replay_buffer_seconds),
+            heartbeat: SessionHeartbeat::new(),
+            chunk_window: ChunkWindow {
+                min_ms: config.min_chunk_ms,
+                max_ms: config.max_chunk_ms,
+                overlap_ms: config.overlap_ms,
+            },
+        }
+    }
+}
+
+/// Result of starting a session: the config it actually runs with and what was adjusted
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StartTranscriptionResponse {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    #[serde(rename = "effectiveConfig")]
+    pub effective_config: TranscriptionConfig,
+    pub validation: ConfigValidationReport,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -130,6 +525,11 @@ pub struct StartCaptureRequest {
     pub channels: u8,
     pub buffer_size_ms: u32,
     pub device_id: Option<String>,
+    /// Capture system audio (loopback) instead of a microphone
+    #[serde(default)]
+    pub source: AudioSource,
+    #[serde(default)]
+    pub resampling_quality: ResamplingPreference,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -145,7 +545,7 @@ pub struct TranscribeRequest {
 pub struct TranscriptionConfig {
     #[serde(rename = "qualityTier")]
     pub quality_tier: String,
-    pub languages: Vec<String>,
+    pub languages: Vec<Language>,
     #[serde(rename = "enableSpeakerDiarization")]
     pub enable_speaker_diarization: bool,
     #[serde(rename = "enableTwoPassRefinement")]
@@ -154,6 +554,306 @@ pub struct TranscriptionConfig {
     pub audio_sources: AudioSourceConfig,
     #[serde(rename = "vadThreshold")]
     pub vad_threshold: f32,
+    /// Maximum session length in seconds before rolling over into a continuation session
+    #[serde(rename = "maxSessionDuration", default)]
+    pub max_session_duration: Option<u64>,
+    /// Insert punctuation from word-timing pauses when the ASR output lacks it
+    #[serde(rename = "punctuationRestoration", default)]
+    pub punctuation_restoration: bool,
+    /// Pause thresholds and marks per language code, replacing the built-in rules
+    #[serde(rename = "punctuationRules", default)]
+    pub punctuation_rules: HashMap<String, PunctuationRules>,
+    /// Replace transcript text in the persisted session event log
+    #[serde(rename = "redactEventLog", default)]
+    pub redact_event_log: bool,
+    /// Adapt the buffering window to the measured speech rate
+    #[serde(rename = "adaptiveChunking", default)]
+    pub adaptive_chunking: bool,
+    /// Directory for session audio recordings (e.g. a network share); the database stays local
+    #[serde(rename = "sessionAudioDir", default)]
+    pub session_audio_dir: Option<String>,
+    /// Record the captured audio even without a sessionAudioDir, in the session data directory
+    #[serde(rename = "saveAudioRecording", default)]
+    pub save_audio_recording: bool,
+    /// Recording stops after this many minutes; transcription continues
+    #[serde(rename = "maxRecordingMinutes", default = "default_max_recording_minutes")]
+    pub max_recording_minutes: u32,
+    /// Also keep the display awake while recording (idle sleep is always prevented)
+    #[serde(rename = "preventDisplaySleep", default)]
+    pub prevent_display_sleep: bool,
+    /// Opening seconds searched for self-introductions to suggest speaker names (0 disables)
+    #[serde(rename = "nameSuggestionWindow", default)]
+    pub name_suggestion_window: Option<u64>,
+    /// Most distinct speakers diarization may assign
+    #[serde(rename = "maxSpeakers", default = "default_max_speakers")]
+    pub max_speakers: u8,
+    /// Fewest speakers diarization assumes
+    #[serde(rename = "minSpeakers", default = "default_min_speakers")]
+    pub min_speakers: u8,
+    /// Embedding similarity needed to match an existing speaker (0..1)
+    #[serde(rename = "similarityThreshold", default = "default_similarity_threshold")]
+    pub similarity_threshold: f32,
+    /// Shortest speech segment attributed to a speaker, in seconds
+    #[serde(rename = "minSegmentDuration", default = "default_min_segment_duration")]
+    pub min_segment_duration: f32,
+    /// Product names, acronyms and jargon offered to the decoder as prompt terms
+    #[serde(rename = "customVocabulary", default)]
+    pub custom_vocabulary: Option<Vec<String>>,
+    /// Phrases that raise a keyword-detected event when a segment mentions them
+    #[serde(rename = "keywords", default)]
+    pub keywords: Vec<KeywordRule>,
+    /// Thresholds of the chaptering pass run when the session stops
+    #[serde(rename = "chapters", default)]
+    pub chapters: ChapterConfig,
+    /// Gate speech on the energy-based speech scorer and its utterance
+    /// endpoints instead of the RMS level (experimental)
+    #[serde(rename = "energyVad", default)]
+    pub energy_vad: bool,
+    /// Condition the decoder on the text of the last few segments. Off by
+    /// default: a misrecognition or hallucination can carry over into later
+    /// segments.
+    #[serde(rename = "rollingPrompt", default)]
+    pub rolling_prompt: bool,
+    /// Show provisional text for the utterance still being buffered
+    #[serde(rename = "partialResults", default)]
+    pub partial_results: bool,
+    /// Milliseconds between partial passes over the growing buffer
+    #[serde(rename = "partialIntervalMs", default = "default_partial_interval_ms")]
+    pub partial_interval_ms: u64,
+    /// Quality tier decoding partial results; unset uses the session's engine
+    #[serde(rename = "partialTier", default)]
+    pub partial_tier: Option<String>,
+    /// Save speakers no stored profile matched as provisional profiles when the session ends
+    #[serde(rename = "rememberSpeakers", default)]
+    pub remember_speakers: bool,
+    /// Inference device: "auto", "cpu" or "gpu". A GPU that cannot be used falls back to the CPU.
+    #[serde(rename = "preferredDevice", default = "default_preferred_device")]
+    pub preferred_device: String,
+    /// Move to a faster tier when transcription keeps falling behind real time
+    #[serde(rename = "allowAdaptiveTier", default)]
+    pub allow_adaptive_tier: bool,
+    /// Rolling real-time factor above which the adaptive tier steps down
+    #[serde(rename = "adaptiveTierThreshold", default = "default_adaptive_tier_threshold")]
+    pub adaptive_tier_threshold: f32,
+    /// Words at the end of the previous segment checked for repetition at the
+    /// start of the next one; 0 keeps repeated words
+    #[serde(rename = "boundaryOverlapWords", default = "default_boundary_overlap_words")]
+    pub boundary_overlap_words: usize,
+    /// Hold back segments that look like Whisper output on silence
+    #[serde(rename = "hallucinationFilter", default = "default_hallucination_filter")]
+    pub hallucination_filter: bool,
+    /// Phrases filtered in addition to the built-in hallucination blocklist
+    #[serde(rename = "hallucinationBlocklist", default)]
+    pub hallucination_blocklist: Option<Vec<String>>,
+    /// Verbatim repeats of the previous segment kept before filtering
+    #[serde(rename = "hallucinationMaxRepeats", default = "default_hallucination_max_repeats")]
+    pub hallucination_max_repeats: u32,
+    /// Keep filtered segments, marked `flagged`, for tuning the filter
+    #[serde(rename = "hallucinationDebug", default)]
+    pub hallucination_debug: bool,
+    /// High-pass filter and spectral noise gate before transcription and diarization;
+    /// can be switched while the session runs
+    #[serde(rename = "enableNoiseSuppression", default)]
+    pub enable_noise_suppression: bool,
+    /// Bring speech to `agc_target_dbfs` with a bounded gain before transcription
+    #[serde(rename = "automaticGainControl", default)]
+    pub automatic_gain_control: bool,
+    #[serde(rename = "agcTargetDbfs", default = "default_agc_target_dbfs")]
+    pub agc_target_dbfs: f32,
+    /// Capture resampling quality: "auto", "fast", "medium" or "high"
+    #[serde(rename = "resamplingQuality", default = "default_resampling_quality")]
+    pub resampling_quality: String,
+    /// Segments below this confidence are marked `needsReview`
+    #[serde(rename = "reviewConfidenceThreshold", default = "default_review_confidence_threshold")]
+    pub review_confidence_threshold: f32,
+    /// Seconds of recent audio kept in memory for replay and re-transcription; 0 keeps none
+    #[serde(rename = "replayBufferSeconds", default = "default_replay_buffer_seconds")]
+    pub replay_buffer_seconds: u32,
+    /// "transcribe", or "translate" for English text whatever the spoken language
+    #[serde(default = "default_mode")]
+    pub mode: String,
+    /// Stop the session after this many minutes of captured audio
+    #[serde(rename = "maxDurationMinutes", default)]
+    pub max_duration_minutes: Option<u32>,
+    /// Stop the session after this many minutes without voice activity
+    #[serde(rename = "autoStopAfterSilenceMinutes", default)]
+    pub auto_stop_after_silence_minutes: Option<u32>,
+    /// How long quitting the app waits for the session to be saved
+    #[serde(rename = "exitGracePeriodSeconds", default = "default_exit_grace_period_seconds")]
+    pub exit_grace_period_seconds: u32,
+    /// Shortest buffer handed to Whisper; shorter gives snappier dictation feedback
+    #[serde(rename = "minChunkMs", default = "default_min_chunk_ms")]
+    pub min_chunk_ms: u64,
+    /// Buffer length at which audio is transcribed even mid-utterance
+    #[serde(rename = "maxChunkMs", default = "default_max_chunk_ms")]
+    pub max_chunk_ms: u64,
+    /// Audio from the end of a buffer cut mid-utterance that starts the next one
+    #[serde(rename = "overlapMs", default)]
+    pub overlap_ms: u64,
+    /// Sessions that may run at once, this one included
+    #[serde(rename = "maxConcurrentSessions", default = "default_max_concurrent_sessions")]
+    pub max_concurrent_sessions: u32,
+    /// Highest temperature a degenerate decode is retried at; 0 disables the fallback
+    #[serde(rename = "maxDecodeTemperature", default = "default_max_decode_temperature")]
+    pub max_decode_temperature: f32,
+    /// Replay a WAV file instead of capturing from a device; development builds only
+    #[serde(rename = "captureSimulator", default)]
+    pub capture_simulator: Option<SimulatorConfig>,
+    /// Seconds between transcript autosaves for crash recovery; 0 disables them
+    #[serde(rename = "autosaveIntervalSeconds", default = "default_autosave_interval_seconds")]
+    pub autosave_interval_seconds: u32,
+}
+
+fn default_boundary_overlap_words() -> usize {
+    content_hasher::DEFAULT_OVERLAP_WORDS
+}
+
+fn default_resampling_quality() -> String {
+    "auto".to_string()
+}
+
+fn default_agc_target_dbfs() -> f32 {
+    agc::DEFAULT_TARGET_DBFS
+}
+
+fn default_review_confidence_threshold() -> f32 {
+    review::DEFAULT_REVIEW_THRESHOLD
+}
+
+fn default_replay_buffer_seconds() -> u32 {
+    ring_buffer::DEFAULT_BUFFER_SECONDS
+}
+
+fn default_min_chunk_ms() -> u64 {
+    chunk_window::DEFAULT_MIN_CHUNK_MS
+}
+
+fn default_max_chunk_ms() -> u64 {
+    chunk_window::DEFAULT_MAX_CHUNK_MS
+}
+
+fn default_max_concurrent_sessions() -> u32 {
+    1
+}
+
+fn default_max_decode_temperature() -> f32 {
+    temperature_fallback::DEFAULT_MAX_TEMPERATURE
+}
+
+fn default_autosave_interval_seconds() -> u32 {
+    autosave::DEFAULT_AUTOSAVE_INTERVAL_SECONDS
+}
+
+fn default_exit_grace_period_seconds() -> u32 {
+    shutdown::DEFAULT_EXIT_GRACE_SECONDS
+}
+
+fn default_mode() -> String {
+    translation::MODE_TRANSCRIBE.to_string()
+}
+
+fn default_max_recording_minutes() -> u32 {
+    DEFAULT_MAX_RECORDING_MINUTES
+}
+
+fn default_hallucination_filter() -> bool {
+    true
+}
+
+fn default_hallucination_max_repeats() -> u32 {
+    hallucination::DEFAULT_MAX_REPEATS
+}
+
+fn default_adaptive_tier_threshold() -> f32 {
+    adaptive_tier::DEFAULT_RTF_THRESHOLD
+}
+
+fn default_preferred_device() -> String {
+    "auto".to_string()
+}
+
+fn default_max_speakers() -> u8 {
+    config_validation::DEFAULT_MAX_SPEAKERS
+}
+
+fn default_min_speakers() -> u8 {
+    config_validation::DEFAULT_MIN_SPEAKERS
+}
+
+fn default_similarity_threshold() -> f32 {
+    config_validation::DEFAULT_SIMILARITY_THRESHOLD
+}
+
+fn default_min_segment_duration() -> f32 {
+    config_validation::DEFAULT_MIN_SEGMENT_DURATION
+}
+
+fn default_partial_interval_ms() -> u64 {
+    partials::DEFAULT_PARTIAL_INTERVAL_MS
+}
+
+impl Default for TranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            quality_tier: "standard".to_string(),
+            languages: vec![Language::ENGLISH],
+            enable_speaker_diarization: false,
+            enable_two_pass_refinement: false,
+            audio_sources: AudioSourceConfig {
+                microphone: true,
+                system_audio: false,
+            },
+            vad_threshold: 0.5,
+            max_session_duration: None,
+            punctuation_restoration: false,
+            punctuation_rules: HashMap::new(),
+            redact_event_log: false,
+            adaptive_chunking: false,
+            session_audio_dir: None,
+            save_audio_recording: false,
+            max_recording_minutes: default_max_recording_minutes(),
+            prevent_display_sleep: false,
+            name_suggestion_window: None,
+            max_speakers: default_max_speakers(),
+            min_speakers: default_min_speakers(),
+            similarity_threshold: default_similarity_threshold(),
+            min_segment_duration: default_min_segment_duration(),
+            custom_vocabulary: None,
+            keywords: Vec::new(),
+            chapters: ChapterConfig::default(),
+            energy_vad: false,
+            rolling_prompt: false,
+            partial_results: false,
+            partial_interval_ms: default_partial_interval_ms(),
+            partial_tier: None,
+            remember_speakers: false,
+            preferred_device: default_preferred_device(),
+            allow_adaptive_tier: false,
+            adaptive_tier_threshold: default_adaptive_tier_threshold(),
+            boundary_overlap_words: default_boundary_overlap_words(),
+            hallucination_filter: default_hallucination_filter(),
+            hallucination_blocklist: None,
+            hallucination_max_repeats: default_hallucination_max_repeats(),
+            hallucination_debug: false,
+            enable_noise_suppression: false,
+            automatic_gain_control: false,
+            agc_target_dbfs: default_agc_target_dbfs(),
+            resampling_quality: default_resampling_quality(),
+            review_confidence_threshold: default_review_confidence_threshold(),
+            replay_buffer_seconds: default_replay_buffer_seconds(),
+            mode: default_mode(),
+            max_duration_minutes: None,
+            auto_stop_after_silence_minutes: None,
+            exit_grace_period_seconds: default_exit_grace_period_seconds(),
+            min_chunk_ms: default_min_chunk_ms(),
+            max_chunk_ms: default_max_chunk_ms(),
+            overlap_ms: chunk_window::DEFAULT_OVERLAP_MS,
+            max_concurrent_sessions: default_max_concurrent_sessions(),
+            max_decode_temperature: default_max_decode_temperature(),
+            capture_simulator: None,
+            autosave_interval_seconds: default_autosave_interval_seconds(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -174,6 +874,12 @@ pub struct SystemCapabilities {
     #[serde(rename = "cpuCores")]
     pub cpu_cores: u32,
     pub warnings: Option<Vec<String>>,
+    /// System audio (loopback) support and permission state
+    #[serde(rename = "systemAudio")]
+    pub system_audio: SystemAudioCapability,
+    /// Sessions running when the recommendation was made
+    #[serde(rename = "activeSessions")]
+    pub active_sessions: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -184,6 +890,22 @@ pub struct TranscriptionSession {
     #[serde(rename = "startTime")]
     pub start_time: u64,
     pub status: String,
+    #[serde(rename = "diarizationStatus")]
+    pub diarization_status: DiarizationStatus,
+    #[serde(rename = "elapsedSeconds")]
+    pub elapsed_seconds: u64,
+    #[serde(rename = "segmentCount")]
+    pub segment_count: usize,
+    /// Unix time the last segment was stored (milliseconds)
+    #[serde(rename = "lastSegmentAt")]
+    pub last_segment_at: Option<u64>,
+    #[serde(rename = "modelStatus")]
+    pub model_status: ModelStatus,
+    #[serde(rename = "currentAudioLevel")]
+    pub current_audio_level: Option<AudioLevelSnapshot>,
+    /// Time since the transcription loop last ran (milliseconds); `None` before it starts
+    #[serde(rename = "heartbeatAgeMs")]
+    pub heartbeat_age_ms: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -192,12 +914,32 @@ pub struct FinalTranscriptionResult {
     pub session_id: String,
     #[serde(rename = "totalDuration")]
     pub total_duration: f32,
+    /// Each segment carries startSample/endSample into the audio recording, null when it was not recorded
     pub segments: Vec<serde_json::Value>,
     pub speakers: Option<Vec<serde_json::Value>>,
     #[serde(rename = "qualityMetrics")]
-    pub quality_metrics: serde_json::Value,
+    pub quality_metrics: SessionQualityMetrics,
+    /// Time spent transcribing during the session, measured per chunk
     #[serde(rename = "processingTimeMs")]
     pub processing_time_ms: u64,
+    #[serde(rename = "estimatedSpeakerCount")]
+    pub estimated_speaker_count: Option<usize>,
+    #[serde(rename = "speakerCountConfidence")]
+    pub speaker_count_confidence: Option<f32>,
+    #[serde(rename = "diarizationStatus")]
+    pub diarization_status: DiarizationStatus,
+    /// WAV recording of the session audio, when it was recorded
+    #[serde(rename = "audioRecordingPath")]
+    pub audio_recording_path: Option<String>,
+    /// "maxDuration" or "silence" when a session limit ended the session
+    #[serde(rename = "autoStopReason", default)]
+    pub auto_stop_reason: Option<String>,
+    /// Occurrences of each configured keyword over the session
+    #[serde(rename = "keywordMatches", default)]
+    pub keyword_matches: Vec<KeywordSummary>,
+    /// Topic chapters of the transcript; empty when chaptering is disabled
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
 }
 
 // Legacy greeting command for compatibility
@@ -206,25 +948,269 @@ pub fn greet(name: &str) -> String {
     format!("Hello, {}! KagiNote is ready for transcription.", name)
 }
 
+/// Timings of the startup phases recorded so far
+#[tauri::command]
+pub async fn get_startup_report(state: State<'_, AppState>) -> Result<StartupReport, CommandError> {
+    state.startup_report.lock()
+        .map(|report| report.clone())
+        .map_err(|e| CommandError::Failed(format!("Failed to read startup report: {}", e)))
+}
+
+/// Entries returned by get_recent_logs when no count is given, and the most it returns
+const DEFAULT_RECENT_LOG_LINES: usize = 200;
+const MAX_RECENT_LOG_LINES: usize = 5000;
+
+/// The most recent application log entries, oldest first. `level_filter`
+/// ("error", "warn", "info", "debug" or "trace") keeps entries at that level or
+/// more severe; the log file only holds info and above.
+#[tauri::command]
+pub async fn get_recent_logs(lines: Option<usize>, level_filter: Option<String>) -> Result<Vec<LogEntry>, CommandError> {
+    let lines = lines.unwrap_or(DEFAULT_RECENT_LOG_LINES).min(MAX_RECENT_LOG_LINES);
+    let min_level = match level_filter.as_deref() {
+        None => tracing::Level::TRACE,
+        Some(name) => name.parse::<tracing::Level>()
+            .map_err(|_| CommandError::InvalidInput(format!("Unknown log level '{}'", name)))?,
+    };
+    let dir = logging::log_dir().ok_or("Failed to get app data directory")?;
+    tokio::task::spawn_blocking(move || logging::read_recent_logs(&dir, lines, min_level))
+        .await
+        .map_err(|e| CommandError::Failed(format!("Failed to read logs: {}", e)))?
+        .map_err(|e| CommandError::Failed(format!("Failed to read logs: {}", e)))
+}
+
+/// Log entries put in a diagnostics bundle
+const DIAGNOSTICS_LOG_LINES: usize = 2000;
+
+/// Write a diagnostics bundle for a support request to `path`: a zip of
+/// system info, device profiles, model status, embedding index stats, the
+/// settings of active sessions and recent logs, without any transcript text
+#[tauri::command]
+pub async fn export_diagnostics(
+    path: String,
+    state: State<'_, AppState>
+) -> Result<DiagnosticsManifest, CommandError> {
+    if path.trim().is_empty() {
+        return Err(CommandError::InvalidInput("Diagnostics path must not be empty".to_string()));
+    }
+    write_diagnostics_bundle(&state, Path::new(&path), logging::log_dir().as_deref()).await
+}
+
+/// Collect the diagnostics bundle of export_diagnostics, with the logs read from `log_dir`
+pub async fn write_diagnostics_bundle(state: &AppState, path: &Path, log_dir: Option<&Path>) -> Result<DiagnosticsManifest, CommandError> {
+    // Transcript text of active sessions, redacted wherever it turns up
+    let (sessions, segment_texts) = {
+        let sessions_guard = state.active_sessions.lock().await;
+        let texts: Vec<String> = sessions_guard.values()
+            .flat_map(|session| &session.transcription_segments)
+            .flat_map(|segment| ["text", "rawText"].map(|key| segment.get(key).and_then(|text| text.as_str()).map(str::to_string)))
+            .flatten()
+            .collect();
+        let sessions: Vec<serde_json::Value> = sessions_guard.values().map(|session| serde_json::json!({
+            "sessionId": session.session_id,
+            "status": session.status,
+            "startTime": session.start_time,
+            "continuationOf": session.continuation_of,
+            "modelTier": session.whisper_config.model_tier,
+            "segmentCount": session.transcription_segments.len(),
+            "errorCount": session.error_count,
+            "realTimeFactor": session.processing_stats.real_time_factor(),
+            "diarizationStatus": session.diarization_status,
+            "config": session.config,
+        })).collect();
+        (sessions, texts)
+    };
+
+    let device_profiles = match state.device_profiles().await {
+        Ok(manager) => {
+            let manager = manager.lock().await;
+            serde_json::json!({
+                "stats": manager.get_stats(),
+                "profiles": manager.cached_profiles(),
+            })
+        }
+        Err(e) => serde_json::json!({ "error": format!("Failed to load device profiles: {}", e) }),
+    };
+    let models = match ModelManager::new() {
+        Ok(manager) => serde_json::json!({
+            "requirements": manager.model_requirements().await,
+            "downloaded": manager.list_downloaded_models().await,
+        }),
+        Err(e) => serde_json::json!({ "error": format!("Failed to open models directory: {}", e) }),
+    };
+    let embedding_index = match state.embedding_index().await.lock().await.get_stats() {
+        Ok(stats) => serde_json::to_value(stats).unwrap_or_default(),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    };
+    let logs = match log_dir {
+        Some(dir) => {
+            let dir = dir.to_path_buf();
+            tokio::task::spawn_blocking(move || logging::read_recent_logs(&dir, DIAGNOSTICS_LOG_LINES, tracing::Level::TRACE))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|logs| logs.map_err(|e| e.to_string()))
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to read logs for the diagnostics bundle: {}", e);
+                    Vec::new()
+                })
+        }
+        None => Vec::new(),
+    };
+
+    let files = vec![
+        BundleFile::new("system_info.json", "Output of get_system_info", system_capabilities(state).await),
+        BundleFile::new("device_profiles.json", "Device profile cache and its statistics", device_profiles),
+        BundleFile::new("models.json", "Requirements and cache status of each model tier", models),
+        BundleFile::new("embedding_index.json", "Speaker embedding index statistics", embedding_index),
+        BundleFile::new("active_sessions.json", "Settings and counters of active sessions, without transcripts", sessions),
+        BundleFile::new("logs.json", "Recent application log entries, oldest first", logs),
+    ];
+    let path = path.to_path_buf();
+    let manifest = tokio::task::spawn_blocking(move || diagnostics::write_bundle(&path, files, &Redactor::new(segment_texts)))
+        .await
+        .map_err(|e| CommandError::Failed(format!("Failed to write diagnostics bundle: {}", e)))?
+        .map_err(|e| CommandError::Failed(format!("Failed to write diagnostics bundle: {:#}", e)))?;
+    tracing::info!("Wrote diagnostics bundle with {} files, {} values redacted", manifest.files.len(), manifest.redacted_values);
+    Ok(manifest)
+}
+
+/// Path of the log file currently written to, for attaching to bug reports
+#[tauri::command]
+pub async fn get_log_file_path() -> Result<String, CommandError> {
+    let dir = logging::log_dir().ok_or("Failed to get app data directory")?;
+    Ok(dir.join(logging::LOG_FILE_NAME).to_string_lossy().into_owned())
+}
+
+/// Input devices with their supported formats; `refresh` probes every device again
+/// instead of using the capabilities cached by device id
+#[tauri::command]
+pub async fn get_audio_devices(
+    refresh: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<Vec<AudioDevice>, CommandError> {
+    let mut profile_manager = state.device_profiles().await?.lock().await;
+    Ok(AudioCaptureService::list_audio_devices_cached(&mut profile_manager, refresh.unwrap_or(false)).await?)
+}
+
+/// Start polling for device changes while the device picker is open; returns the current devices
 #[tauri::command]
-pub async fn get_audio_devices() -> Result<Vec<AudioDevice>, String> {
-    AudioCaptureService::list_audio_devices()
+pub async fn start_device_monitoring(state: State<'_, AppState>) -> Result<Vec<AudioDevice>, CommandError> {
+    let devices = tokio::task::spawn_blocking(AudioCaptureService::input_devices_snapshot)
         .await
-        .map_err(|e| format!("Failed to list audio devices: {}", e))
+        .map_err(|e| CommandError::Failed(format!("Device enumeration failed: {}", e)))?;
+    let mut watcher = state.device_watcher.lock()
+        .map_err(|_| CommandError::Failed("Device watcher lock poisoned".to_string()))?;
+    watcher.set_picker_open(true);
+    watcher.observe(devices.clone());
+    Ok(devices)
+}
+
+/// Stop polling for the device picker; running sessions keep being watched
+#[tauri::command]
+pub async fn stop_device_monitoring(state: State<'_, AppState>) -> Result<(), CommandError> {
+    let mut watcher = state.device_watcher.lock()
+        .map_err(|_| CommandError::Failed("Device watcher lock poisoned".to_string()))?;
+    watcher.set_picker_open(false);
+    Ok(())
+}
+
+/// Poll input devices for the lifetime of the app, emitting `audio-devices-changed`
+/// on a change. Polling pauses while neither the picker nor a capture needs it.
+/// A removed capture device is marked lost so the session loop's disconnect
+/// handling takes over.
+pub fn spawn_device_watcher(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        loop {
+            tokio::time::sleep(device_watcher::DEVICE_POLL_INTERVAL).await;
+            
+            let captures: Vec<SharedCapture> = state.audio_captures.lock().await.values().cloned().collect();
+            let mut capturing = false;
+            for capture in &captures {
+                capturing |= capture.lock().await.is_capturing();
+            }
+            let should_poll = match state.device_watcher.lock() {
+                Ok(mut watcher) => {
+                    let should_poll = watcher.should_poll(capturing);
+                    if !should_poll {
+                        watcher.pause();
+                    }
+                    should_poll
+                }
+                Err(_) => false,
+            };
+            if !should_poll {
+                continue;
+            }
+            
+            let devices = match tokio::task::spawn_blocking(AudioCaptureService::input_devices_snapshot).await {
+                Ok(devices) => devices,
+                Err(e) => {
+                    tracing::warn!("Device enumeration failed: {}", e);
+                    continue;
+                }
+            };
+            let changes = match state.device_watcher.lock() {
+                Ok(mut watcher) => watcher.observe(devices.clone()),
+                Err(_) => None,
+            };
+            let Some(changes) = changes else {
+                continue;
+            };
+            tracing::info!("Audio devices changed: {} added, {} removed", changes.added.len(), changes.removed.len());
+            
+            for capture in &captures {
+                let capture = capture.lock().await;
+                let removed_capture_device = capture.is_capturing()
+                    && capture.device_name().is_some_and(|name| changes.removes(&name));
+                if removed_capture_device {
+                    tracing::warn!("Capture device {:?} was removed", capture.device_name());
+                    capture.mark_device_lost();
+                }
+            }
+            
+            if let Err(e) = app_handle.emit("audio-devices-changed", serde_json::json!({
+                "added": changes.added,
+                "removed": changes.removed,
+                "defaultDeviceId": changes.default_device_id,
+                "devices": devices,
+                "timestamp": std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis()
+            })) {
+                tracing::warn!("Failed to emit audio-devices-changed event: {}", e);
+            }
+        }
+    });
 }
 
 #[tauri::command]
 pub async fn get_device_troubleshooting(
     device_name: String,
     state: State<'_, AppState>
-) -> Result<serde_json::Value, String> {
-    let profile_manager = state.device_profile_manager.lock().await;
-    let suggestions = profile_manager.get_troubleshooting_suggestions(&device_name);
-    let stats = profile_manager.get_stats();
+) -> Result<serde_json::Value, CommandError> {
+    let (mut suggestions, stats) = {
+        let profile_manager = state.device_profiles().await?.lock().await;
+        (profile_manager.get_troubleshooting_suggestions(&device_name), profile_manager.get_stats())
+    };
+
+    // Resampling of a running capture, with a hint when it is too slow for this machine
+    let captures: Vec<SharedCapture> = state.audio_captures.lock().await.values().cloned().collect();
+    let mut resampling = None;
+    for capture in captures {
+        resampling = capture.lock().await.resampling_report();
+        if resampling.is_some() {
+            break;
+        }
+    }
+    if let Some(hint) = resampling.as_ref().and_then(|report| report.slow_resampling_suggestion()) {
+        suggestions.insert(0, hint);
+    }
 
     Ok(serde_json::json!({
         "device_name": device_name,
         "suggestions": suggestions,
+        "resampling": resampling,
         "profile_stats": {
             "total_profiles": stats.total_profiles,
             "valid_profiles": stats.valid_profiles,
@@ -238,7 +1224,7 @@ pub async fn get_device_troubleshooting(
 pub async fn start_audio_capture(
     request: StartCaptureRequest,
     state: State<'_, AppState>
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     let config = AudioConfig {
         sample_rate: request.sample_rate,
         channels: request.channels,
@@ -246,37 +1232,38 @@ pub async fn start_audio_capture(
         device_id: request.device_id,
         auto_sample_rate: true,
         target_sample_rate: 16000,
+        source: request.source,
+        agc: AgcConfig::default(),
+        resampling_quality: request.resampling_quality,
+        simulator: None,
     };
     
     // Create and store capture service in app state
-    let mut capture_service = AudioCaptureService::new(config)
-        .await
-        .map_err(|e| format!("Failed to create audio capture service: {}", e))?;
-        
-    capture_service.start_capture()
-        .await
-        .map_err(|e| format!("Failed to start audio capture: {}", e))?;
-    
-    // Store the capture service in app state
-    let mut audio_capture_guard = state.audio_capture_service.lock().await;
-    *audio_capture_guard = Some(capture_service);
-    drop(audio_capture_guard);
+    let mut capture_service = AudioCaptureService::new(config).await?;
+    capture_service.start_capture().await?;
+    
+    // Store the capture service in app state, replacing an earlier standalone capture
+    let previous = state.audio_captures.lock().await
+        .insert(STANDALONE_CAPTURE.to_string(), Arc::new(Mutex::new(capture_service)));
+    if let Some(previous) = previous {
+        if let Err(e) = previous.lock().await.stop_capture().await {
+            tracing::error!("Failed to stop the previous audio capture: {}", e);
+        }
+    }
         
     Ok("Audio capture started successfully".to_string())
 }
 
 #[tauri::command]
-pub async fn stop_audio_capture(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn stop_audio_capture(state: State<'_, AppState>) -> Result<String, CommandError> {
     // Retrieve capture service from app state and stop it
-    let mut audio_capture_guard = state.audio_capture_service.lock().await;
+    let capture = state.audio_captures.lock().await.remove(STANDALONE_CAPTURE);
     
-    if let Some(mut capture_service) = audio_capture_guard.take() {
-        capture_service.stop_capture()
-            .await
-            .map_err(|e| format!("Failed to stop audio capture: {}", e))?;
+    if let Some(capture) = capture {
+        capture.lock().await.stop_capture().await?;
         Ok("Audio capture stopped successfully".to_string())
     } else {
-        Err("No active audio capture service found".to_string())
+        Err(CommandError::NotFound("No active audio capture service found".to_string()))
     }
 }
 
@@ -284,7 +1271,7 @@ pub async fn stop_audio_capture(state: State<'_, AppState>) -> Result<String, St
 pub async fn transcribe_audio(
     request: TranscribeRequest,
     state: State<'_, AppState>
-) -> Result<ASRResult, String> {
+) -> Result<ASRResult, CommandError> {
     let duration_seconds = request.audio_data.len() as f32 / request.sample_rate as f32;
     let audio_data = AudioData {
         samples: request.audio_data,
@@ -295,29 +1282,37 @@ pub async fn transcribe_audio(
         duration_seconds,
     };
     
-    // Use configured Whisper engine from app state
-    let mut whisper_guard = state.whisper_engine.lock().await;
-    
-    // Initialize engine if not already present
-    if whisper_guard.is_none() {
-        let config = WhisperConfig::default();
-        let engine = WhisperEngine::new(config)
-            .await
-            .map_err(|e| format!("Failed to initialize Whisper engine: {}", e))?;
-        *whisper_guard = Some(engine);
-    }
-    
-    let engine = whisper_guard.as_ref().unwrap();
+    // Share a loaded engine of the default tier, or load one
+    let engine = lease_engine(&state, WhisperConfig::default()).await?;
     let context = request.context.unwrap_or_default();
-    engine.transcribe(&audio_data, &context)
-        .await
-        .map_err(|e| format!("Failed to transcribe audio: {}", e))
+    Ok(engine.transcribe(&audio_data, &context).await?)
 }
 
+
 // High-level integration commands
 
+/// Model tier this machine can run for a session while `concurrent_sessions`
+/// run in all. Each session decodes on its own engine and threads, so memory
+/// and cores are shared between them.
+pub fn recommended_tier(total_memory_gb: f32, cpu_cores: u32, concurrent_sessions: usize) -> &'static str {
+    let sessions = concurrent_sessions.max(1);
+    let memory_per_session = total_memory_gb / sessions as f32;
+    let cores_per_session = cpu_cores / sessions as u32;
+    if memory_per_session >= 16.0 && cores_per_session >= 8 {
+        "high-accuracy"
+    } else if memory_per_session >= 8.0 && cores_per_session >= 4 {
+        "standard"
+    } else {
+        "turbo"
+    }
+}
+
 #[tauri::command]
-pub async fn get_system_info() -> Result<SystemCapabilities, String> {
+pub async fn get_system_info(state: State<'_, AppState>) -> Result<SystemCapabilities, CommandError> {
+    Ok(system_capabilities(&state).await)
+}
+
+async fn system_capabilities(state: &AppState) -> SystemCapabilities {
     // Get system information
     let mut sys = sysinfo::System::new_all();
     sys.refresh_all();
@@ -328,14 +1323,10 @@ pub async fn get_system_info() -> Result<SystemCapabilities, String> {
     // Check for GPU availability (simplified)
     let has_gpu = cfg!(feature = "gpu") || std::env::var("CUDA_VISIBLE_DEVICES").is_ok();
     
-    // Determine recommended tier based on system capabilities
-    let recommended_tier = if total_memory >= 16.0 && cpu_count >= 8 {
-        "high-accuracy"
-    } else if total_memory >= 8.0 && cpu_count >= 4 {
-        "standard"
-    } else {
-        "turbo"
-    };
+    // Determine recommended tier based on system capabilities, for a session
+    // started next to the ones already running
+    let active_sessions = state.active_sessions.lock().await.len();
+    let recommended_tier = recommended_tier(total_memory, cpu_count, active_sessions + 1);
     
     let mut warnings = Vec::new();
     if total_memory < 4.0 {
@@ -345,95 +1336,350 @@ pub async fn get_system_info() -> Result<SystemCapabilities, String> {
         warnings.push("limited_cpu_cores".to_string());
     }
     
-    Ok(SystemCapabilities {
+    // Probe system audio support without starting a capture stream
+    let system_audio = system_audio::probe_system_audio(&NativePlatformProbe);
+    
+    SystemCapabilities {
         recommended_tier: recommended_tier.to_string(),
         available_memory_gb: total_memory,
         has_gpu,
         cpu_cores: cpu_count,
         warnings: if warnings.is_empty() { None } else { Some(warnings) },
-    })
+        system_audio,
+        active_sessions,
+    }
+}
+
+/// OS microphone permission, queried without opening a capture stream
+#[tauri::command]
+pub async fn check_microphone_permission() -> Result<MicrophonePermission, CommandError> {
+    Ok(NativeMicrophonePermission.status())
+}
+
+/// Show the OS microphone prompt when the permission was never asked and wait
+/// for the answer. Windows has no prompt for desktop apps and reports the setting.
+#[tauri::command]
+pub async fn request_microphone_permission() -> Result<MicrophonePermission, CommandError> {
+    tokio::task::spawn_blocking(permissions::request_native_permission)
+        .await
+        .map_err(|e| CommandError::Failed(format!("Failed to request microphone permission: {}", e)))
 }
 
 #[tauri::command]
 pub async fn start_transcription(
+
     config: TranscriptionConfig,
     app_handle: tauri::AppHandle,
-) -> Result<String, String> {
+) -> Result<StartTranscriptionResponse, CommandError> {
     let session_id = Uuid::new_v4().to_string();
     let state = app_handle.state::<AppState>();
     
     tracing::info!("🎙️ Starting transcription session: {} with config: {:?}", session_id, config);
     
+    // PHASE 0: Reject unusable settings, clamp out-of-range ones
+    let (config, validation) = validate_transcription_config(&config);
+    if !validation.is_valid() {
+        let error_msg = format!("Invalid transcription settings: {}", validation.error_summary());
+        tracing::warn!("⚠️ {}", error_msg);
+        let error = CommandError::detailed("invalid_config", error_msg, vec![
+            "Review the transcription settings and try again".to_string()
+        ]).with_details(serde_json::json!({ "errors": validation.errors }));
+        emit_detailed_error(&app_handle, &session_id, &error);
+        return Err(error);
+    }
+    for warning in &validation.warnings {
+        tracing::warn!("⚙️ Adjusted {}: {} ({} -> {})", warning.field, warning.message, warning.original,
+                       warning.adjusted.clone().unwrap_or_default());
+    }
+    
     // PHASE 1: Pre-flight System Validation
     tracing::info!("📋 Phase 1: Running system validation checks...");
     
     // Check system resources first
-    let sys_info = get_system_info().await.map_err(|e| {
+    let sys_info = get_system_info(app_handle.state::<AppState>()).await.map_err(|e| {
         let error_msg = format!("System validation failed: {}. This may indicate insufficient resources or system compatibility issues.", e);
         tracing::error!("❌ {}", error_msg);
-        emit_detailed_error(&app_handle, &session_id, "system_validation_failed", &error_msg, vec![
+        let error = CommandError::detailed("system_validation_failed", error_msg, vec![
             "Check available memory (minimum 4GB recommended)".to_string(),
             "Close other memory-intensive applications".to_string(),
             "Restart the application and try again".to_string()
         ]);
-        error_msg
+        emit_detailed_error(&app_handle, &session_id, &error);
+        error
     })?;
     
     tracing::info!("✅ System validation passed: {} cores, {:.1}GB RAM, GPU: {}", 
                  sys_info.cpu_cores, sys_info.available_memory_gb, sys_info.has_gpu);
     
-    // Check for existing sessions with detailed state information
-    {
-        let sessions_guard = state.active_sessions.lock().await;
-        if !sessions_guard.is_empty() {
-            let existing_sessions: Vec<String> = sessions_guard.keys().cloned().collect();
-            let error_msg = format!(
-                "Another transcription session is already active: {:?}. Only one transcription session can run at a time to ensure optimal performance.",
-                existing_sessions
-            );
-            tracing::warn!("⚠️ {}", error_msg);
-            emit_detailed_error(&app_handle, &session_id, "session_already_active", &error_msg, vec![
-                "Stop the current session first".to_string(),
-                "Use Emergency Stop if the session is unresponsive".to_string()
-            ]);
-            return Err(error_msg);
-        }
+    // A denied microphone fails here, before the model download, not in start_capture
+    let uses_microphone = config.capture_simulator.is_none()
+        && (config.audio_sources.microphone || !config.audio_sources.system_audio);
+    let microphone = NativeMicrophonePermission;
+    if uses_microphone && microphone.status() == MicrophonePermission::Denied {
+        let error_msg = "Microphone access is denied for KagiNote. Allow it in the system privacy settings and start the session again.".to_string();
+        tracing::warn!("⚠️ {}", error_msg);
+        let error = CommandError::detailed("microphone_permission_denied", error_msg, permissions::recovery_options(microphone.os_family()));
+        emit_detailed_error(&app_handle, &session_id, &error);
+        return Err(error);
     }
     
-    // Check audio capture state with device validation
-    {
-        let audio_capture_guard = state.audio_capture_service.lock().await;
-        if audio_capture_guard.is_some() {
-            let error_msg = "Audio capture is already active from a previous session. This may indicate improper cleanup from a previous session.".to_string();
-            tracing::warn!("⚠️ {}", error_msg);
-            emit_detailed_error(&app_handle, &session_id, "audio_capture_already_active", &error_msg, vec![
-                "Use Emergency Stop to clear all audio resources".to_string(),
-                "Restart the application if the issue persists".to_string()
-            ]);
-            return Err(error_msg);
-        }
-    }
+    // A session whose loop died would otherwise hold the slot forever
+    reap_stalled_sessions(&app_handle).await;
     
-    // Check if there's already an active session
-    {
-        let sessions_guard = state.active_sessions.lock().await;
-        if !sessions_guard.is_empty() {
-            return Err("Another transcription session is already active. Please stop it first.".to_string());
-        }
-    }
+    let model_tier = resolve_session_model(&app_handle, &session_id, &config).await?;
     
-    // Check if audio capture is already running
-    {
-        let audio_capture_guard = state.audio_capture_service.lock().await;
-        if audio_capture_guard.is_some() {
-            return Err("Audio capture is already active. Please stop it first.".to_string());
+    match start_session_capture(&state, &session_id, &config).await {
+        Ok(()) => {}
+        Err(CommandError::Audio(AudioError::SystemAudioUnavailable { reason, suggestions })) => {
+            let error = AudioError::SystemAudioUnavailable { reason, suggestions: suggestions.clone() };
+            emit_enhanced_audio_error(
+                &app_handle, &session_id, "system_audio_unavailable", &error.to_string(),
+                None, None, None, None, suggestions,
+            ).await;
+            return Err(error.into());
+        }
+        Err(error) => {
+            if matches!(error.code(), "session_already_active" | "audio_capture_already_active") {
+                tracing::warn!("⚠️ {}", error);
+                emit_detailed_error(&app_handle, &session_id, &error);
+            }
+            return Err(error);
         }
     }
     
-    // PHASE 2: Model Availability and Validation
-    tracing::info!("🤖 Phase 2: Validating model availability...");
-    
-    let model_tier = match config.quality_tier.as_str() {
+    // Initialize ASR engine configuration (reuse model_tier from above)
+    let mut whisper_config = WhisperConfig {
+        model_tier,
+        model_path: None,
+        device: crate::asr::types::Device::from_preference(&config.preferred_device)
+            .unwrap_or(crate::asr::types::Device::Auto),
+        num_threads: 4,
+        beam_size: tier_beam_size(model_tier),
+        temperature: 0.0,
+        language: config.languages.first().copied(),
+        task: crate::asr::types::Task::Transcribe,
+        enable_vad: true,
+        enable_word_timestamps: config.enable_two_pass_refinement,
+        context_size: 50,
+        custom_vocabulary: config.custom_vocabulary.clone(),
+        optimization_level: Some(crate::asr::types::OptimizationLevel::Balanced),
+        max_temperature: config.max_decode_temperature,
+    };
+    translation::configure(&mut whisper_config, &config.mode);
+    
+    // DO NOT initialize ASR engine synchronously - it blocks on model download
+    // The engine will be initialized asynchronously in the background task
+    
+    // Store the session state in global app state, replacing the reservation
+    let session_state = TranscriptionSessionState {
+        status: "active".to_string(),
+        audio_capture: Some("primary".to_string()),
+        whisper_config: whisper_config.clone(),
+        ..TranscriptionSessionState::reserved(&session_id, &config)
+    };
+    
+    persist_session_start(&state, &session_state).await;
+    {
+        let mut sessions_guard = state.active_sessions.lock().await;
+        if !sessions_guard.contains_key(&session_id) {
+            // Emergency-stopped while starting: the capture started above is ours to stop
+            drop(sessions_guard);
+            stop_session_capture(&state, &session_id).await;
+            let now = session_state.start_time;
+            persist_session_end(&state, &build_session_record(&session_state, now), now, session_store::SESSION_STATUS_INTERRUPTED).await;
+            return Err(CommandError::detailed("session_cancelled", "The session was stopped while it was starting", vec![
+                "Start the session again".to_string()
+            ]));
+        }
+        sessions_guard.insert(session_id.clone(), session_state);
+    }
+    
+    state.open_event_log(&session_id, config.redact_event_log);
+    acquire_power_assertion(&app_handle, &session_id, config.prevent_display_sleep);
+    
+    // Start ASR engine initialization and transcription loop in background
+    let app_handle_clone = app_handle.clone();
+    let session_id_clone = session_id.clone();
+    let whisper_config_clone = whisper_config.clone();
+    let cancellation = session_tasks(&state).cancellation(&session_id);
+    
+    let init_task = async move {
+        tracing::info!("Starting background ASR initialization for session: {}", session_id_clone);
+        
+        // Share a loaded engine with the same decoding settings, or initialize
+        // one asynchronously with progress reporting
+        let pooled = pooled_engine(&app_handle_clone.state::<AppState>(), &whisper_config_clone);
+        let lease = match pooled {
+            Some(lease) => {
+                tracing::info!("Reusing the loaded {:?} engine for session {}", lease.tier(), session_id_clone);
+                Ok(lease)
+            }
+            None => {
+                let tier = whisper_config_clone.model_tier;
+                initialize_whisper_engine_async(
+                    whisper_config_clone, 
+                    session_id_clone.clone(),
+                    app_handle_clone.clone()
+                ).await.map(|engine| register_engine(&app_handle_clone.state::<AppState>(), tier, engine))
+            }
+        };
+        match lease {
+            Ok(lease) => {
+                let device = lease.get_current_device();
+                let device_fallback = lease.device_fallback().map(str::to_string);
+                if let Some(reason) = &device_fallback {
+                    if let Err(emit_err) = emit_session_event(&app_handle_clone, &session_id_clone, "inference-device-warning", serde_json::json!({
+                        "sessionId": session_id_clone,
+                        "requestedDevice": config.preferred_device,
+                        "device": device.label(),
+                        "message": format!("GPU acceleration unavailable, transcribing on the CPU: {}", reason),
+                        "timestamp": std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_millis()
+                    })) {
+                        tracing::warn!("Failed to emit inference-device-warning event: {}", emit_err);
+                    }
+                }
+                
+                // The session holds its engine; it is freed once no consumer holds it
+                let state = app_handle_clone.state::<AppState>();
+                match state.active_sessions.lock().await.get_mut(&session_id_clone) {
+                    Some(session_state) => {
+                        session_state.engine = Some(lease);
+                        session_state.progress.model_status = ModelStatus::Ready;
+                    }
+                    None => {
+                        tracing::info!("Session {} ended before its model was ready", session_id_clone);
+                        return;
+                    }
+                }
+                
+                // Initialize diarization service if enabled
+                if config.enable_speaker_diarization {
+                    tracing::info!("Initializing speaker diarization for session: {}", session_id_clone);
+                    let diarization_config = session_diarization_config(&config);
+                    let initialized = recovery::initialize_with_retry(recovery::INIT_ATTEMPTS, recovery::INIT_BACKOFF_BASE, |attempt| {
+                        tracing::debug!("Speaker diarization initialization attempt {}", attempt);
+                        DiarizationService::new(diarization_config.clone())
+                    }).await;
+                    
+                    let status = match initialized {
+                        Ok((diarization_service, attempts)) => {
+                            let mut diarization_guard = state.diarization_service.lock().await;
+                            *diarization_guard = Some(diarization_service);
+                            drop(diarization_guard);
+                            tracing::info!("Speaker diarization initialized successfully after {} attempt(s)", attempts);
+                            DiarizationStatus::Active
+                        }
+                        Err(failure) => {
+                            tracing::warn!("Failed to initialize speaker diarization after {} attempts: {:?}", failure.attempts, failure.error);
+                            if let Err(emit_err) = emit_session_event(&app_handle_clone, &session_id_clone, "diarization-warning", serde_json::json!({
+                                "sessionId": session_id_clone,
+                                "type": "initialization_failed",
+                                "message": "Speaker identification could not be started - continuing in single speaker mode",
+                                "recoverable": true,
+                                "retryCount": failure.attempts,
+                                "manualRetryAvailable": true,
+                                "timestamp": std::time::SystemTime::now()
+                                    .duration_since(std::time::UNIX_EPOCH)
+                                    .unwrap_or_default()
+                                    .as_millis()
+                            })) {
+                                tracing::warn!("Failed to emit diarization warning: {}", emit_err);
+                            }
+                            DiarizationStatus::Degraded
+                        }
+                    };
+                    set_diarization_status(&state, &session_id_clone, status).await;
+                }
+                
+                // A stop while diarization initialized may not have aborted this task yet
+                if cancellation.is_cancelled() {
+                    tracing::info!("Session {} stopped while its model was loading", session_id_clone);
+                    return;
+                }
+                
+                // Emit success event
+                if let Err(emit_err) = emit_session_event(&app_handle_clone, &session_id_clone, "model-ready", serde_json::json!({
+                    "sessionId": session_id_clone,
+                    "status": "ready",
+                    "device": device.label(),
+                    "deviceFallback": device_fallback,
+                    "message": "Whisper model loaded successfully"
+                })) {
+                    tracing::error!("Failed to emit model-ready event: {}", emit_err);
+                }
+                
+                // Start transcription loop
+                tracing::info!("Starting transcription loop for session: {}", session_id_clone);
+                // Spawned apart from this task so aborting a late initialization never cuts
+                // the loop off before it finalizes the recording
+                tokio::spawn(async move {
+                    if let Err(e) = run_transcription_loop(
+                        session_id_clone.clone(),
+                        app_handle_clone,
+                        cancellation,
+                    ).await {
+                        tracing::error!("Transcription loop failed for session {}: {}", session_id_clone, e);
+                    }
+                });
+            }
+            Err(e) => {
+                // Cancelled download or stopped session: nothing left to report
+                let state = app_handle_clone.state::<AppState>();
+                match state.active_sessions.lock().await.get_mut(&session_id_clone) {
+                    Some(session_state) => session_state.progress.model_status = ModelStatus::Error,
+                    None => {
+                        tracing::info!("Session {} ended before its model was ready: {}", session_id_clone, e);
+                        return;
+                    }
+                }
+                let detailed_error = format!(
+                    "Failed to initialize Whisper engine for session {}: {}. \
+                    Common causes: 1) Model files not found or corrupted, 2) Insufficient memory, \
+                    3) Permissions issues with models directory, 4) Network issues during download. \
+                    Check logs for specific model loading errors.", 
+                    session_id_clone, e
+                );
+                tracing::error!("{}", detailed_error);
+                
+                if let Err(emit_err) = emit_session_event(&app_handle_clone, &session_id_clone, "model-error", serde_json::json!({
+                    "sessionId": session_id_clone,
+                    "status": "error", 
+                    "message": detailed_error,
+                    "errorType": "model_initialization_failed",
+                    "originalError": e.to_string()
+                })) {
+                    tracing::error!("Failed to emit model-error event: {}", emit_err);
+                }
+            }
+        }
+    };
+    session_tasks(&state).spawn_init(&session_id, init_task);
+    
+    tracing::info!("Transcription session {} started successfully", session_id);
+    tracing::info!("✅ Transcription session {} started successfully", session_id);
+    Ok(StartTranscriptionResponse {
+        session_id,
+        effective_config: config,
+        validation,
+    })
+}
+
+/// Check the model and start audio capture for a reserved session. Returns the
+/// requested model tier; on failure nothing is left running.
+/// Model tier for a new session: the requested one, or the first downloaded
+/// fallback (announced with model-fallback)
+async fn resolve_session_model(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    config: &TranscriptionConfig,
+) -> Result<crate::asr::types::ModelTier, CommandError> {
+    // PHASE 2: Model Availability and Validation
+    tracing::info!("🤖 Phase 2: Validating model availability...");
+    
+    let model_tier = match config.quality_tier.as_str() {
         "high-accuracy" => crate::asr::types::ModelTier::HighAccuracy,
         "standard" => crate::asr::types::ModelTier::Standard,
         "turbo" => crate::asr::types::ModelTier::Turbo,
@@ -454,13 +1700,14 @@ pub async fn start_transcription(
                 e
             );
             tracing::error!("❌ {}", error_msg);
-            emit_detailed_error(&app_handle, &session_id, "model_manager_init_failed", &error_msg, vec![
+            let error = CommandError::detailed("model_manager_init_failed", error_msg, vec![
                 "Check disk space (at least 2GB free space required)".to_string(),
                 "Verify write permissions to ~/Library/Application Support/".to_string(),
                 "Try running with administrator privileges".to_string(),
                 "Check macOS Security & Privacy settings".to_string()
             ]);
-            error_msg
+            emit_detailed_error(app_handle, session_id, &error);
+            error
         })?;
         
         // Check requested model availability
@@ -492,7 +1739,7 @@ pub async fn start_transcription(
                         model_available = true;
                         
                         // Emit fallback notification
-                        let _ = app_handle.emit("model-fallback", serde_json::json!({
+                        let _ = emit_session_event(app_handle, session_id, "model-fallback", serde_json::json!({
                             "sessionId": session_id,
                             "requestedTier": format!("{:?}", model_tier),
                             "fallbackTier": format!("{:?}", fallback_tier),
@@ -504,9 +1751,7 @@ pub async fn start_transcription(
             
             if !model_available {
                 // Check if we have network connectivity for downloads
-                let models_dir = dirs::data_dir()
-                    .map(|d| d.join("KagiNote").join("models"))
-                    .unwrap_or_else(|| std::path::PathBuf::from("~/Library/Application Support/KagiNote/models"));
+                let models_dir = model_manager.models_dir().to_path_buf();
                 
                 let disk_space = get_available_disk_space(&models_dir).await.unwrap_or(0);
                 
@@ -533,15 +1778,44 @@ pub async fn start_transcription(
                     ]
                 };
                 
-                emit_detailed_error(&app_handle, &session_id, "no_models_available", &error_msg, recovery_options);
-                return Err(error_msg);
+                let error = CommandError::detailed("no_models_available", error_msg, recovery_options)
+                    .with_details(serde_json::json!({
+                        "requestedTier": model_tier.to_string(),
+                        "modelsDirectory": models_dir,
+                        "availableDiskBytes": disk_space,
+                    }));
+                emit_detailed_error(app_handle, session_id, &error);
+                return Err(error);
             }
         } else {
             tracing::info!("✅ Model {:?} is available and ready", model_tier);
         }
     }
     
+    Ok(model_tier)
+}
+
+/// Reserve a slot for `session_id` and start its audio capture, kept in
+/// `audio_captures` under the session id. The slot is claimed before the
+/// capture opens, so a start over `maxConcurrentSessions` never starts
+/// another capture; a capture that fails to start gives the slot back.
+pub async fn start_session_capture(state: &AppState, session_id: &str, config: &TranscriptionConfig) -> Result<(), CommandError> {
+    reserve_session(state, session_id, config).await?;
+    if let Err(error) = open_session_capture(state, session_id, config).await {
+        release_session_reservation(state, session_id).await;
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+async fn open_session_capture(state: &AppState, session_id: &str, config: &TranscriptionConfig) -> Result<(), AudioError> {
     // Convert frontend config to backend config
+    let mixed_capture = config.audio_sources.microphone && config.audio_sources.system_audio;
+    let audio_source = if config.audio_sources.system_audio && !mixed_capture {
+        AudioSource::System
+    } else {
+        AudioSource::Microphone
+    };
     let audio_config = AudioConfig {
         sample_rate: 0, // Auto-detect optimal rate
         channels: 1,
@@ -549,156 +1823,140 @@ pub async fn start_transcription(
         device_id: None,
         auto_sample_rate: true,
         target_sample_rate: 16000, // Target for Whisper
+        source: audio_source,
+        agc: AgcConfig {
+            enabled: config.automatic_gain_control,
+            target_dbfs: config.agc_target_dbfs,
+        },
+        resampling_quality: ResamplingPreference::from_name(&config.resampling_quality).unwrap_or_default(),
+        simulator: config.capture_simulator.clone(),
     };
     
     // Start audio capture ONLY after model availability is confirmed
-    let mut capture_service = AudioCaptureService::new(audio_config)
-        .await
-        .map_err(|e| format!("Failed to initialize audio capture: {}", e))?;
-        
-    capture_service.start_capture()
-        .await
-        .map_err(|e| format!("Failed to start audio capture: {}", e))?;
+    let capture_result = if mixed_capture && audio_config.simulator.is_none() {
+        AudioCaptureService::new_mixed(audio_config).await
+    } else {
+        AudioCaptureService::new(audio_config).await
+    };
+    let mut capture_service = capture_result?;
+    capture_service.start_capture().await?;
     
     // Store audio capture service in app state
-    let mut audio_capture_guard = state.audio_capture_service.lock().await;
-    *audio_capture_guard = Some(capture_service);
-    drop(audio_capture_guard);
-    
-    // Initialize ASR engine configuration (reuse model_tier from above)
-    let whisper_config = WhisperConfig {
-        model_tier,
-        model_path: None,
-        device: crate::asr::types::Device::Auto,
-        num_threads: 4,
-        beam_size: match config.quality_tier.as_str() {
-            "high-accuracy" => 5,
-            "standard" => 3,
-            "turbo" => 1,
-            _ => 3,
-        },
-        temperature: 0.0,
-        language: config.languages.first().cloned(),
-        task: crate::asr::types::Task::Transcribe,
-        enable_vad: true,
-        enable_word_timestamps: config.enable_two_pass_refinement,
-        context_size: 50,
-        custom_vocabulary: None,
-        optimization_level: Some(crate::asr::types::OptimizationLevel::Balanced),
+    state.audio_captures.lock().await.insert(session_id.to_string(), Arc::new(Mutex::new(capture_service)));
+    Ok(())
+}
+
+/// Status of a session whose start is still in progress
+pub const SESSION_STARTING: &str = "starting";
+
+/// Reserve a session slot for `session_id`. The limit is the smallest
+/// `maxConcurrentSessions` of the new session and every active one, so a
+/// session started alone is never joined by one that asks for more room. The
+/// check and the placeholder insertion happen under one lock, so concurrent
+/// starts never exceed the limit. The placeholder is replaced once the
+/// session is running, or removed with [`release_session_reservation`] when
+/// starting fails.
+pub async fn reserve_session(state: &AppState, session_id: &str, config: &TranscriptionConfig) -> Result<(), CommandError> {
+    let active_session_ids: Vec<String> = {
+        let mut sessions_guard = state.active_sessions.lock().await;
+        let max_sessions = sessions_guard.values()
+            .map(|s| s.config.max_concurrent_sessions)
+            .fold(config.max_concurrent_sessions, u32::min)
+            .max(1) as usize;
+        if sessions_guard.len() >= max_sessions {
+            let existing_sessions: Vec<String> = sessions_guard.keys().cloned().collect();
+            let error_msg = if max_sessions == 1 {
+                format!(
+                    "Another transcription session is already active: {:?}. Only one transcription session can run at a time to ensure optimal performance.",
+                    existing_sessions
+                )
+            } else {
+                format!(
+                    "{} transcription sessions are already active: {:?}. At most {} can run at a time (maxConcurrentSessions).",
+                    existing_sessions.len(), existing_sessions, max_sessions
+                )
+            };
+            return Err(CommandError::detailed("session_already_active", error_msg, vec![
+                "Stop the current session first".to_string(),
+                "Use Emergency Stop if the session is unresponsive".to_string()
+            ]).with_details(serde_json::json!({
+                "activeSessionIds": existing_sessions,
+                "maxConcurrentSessions": max_sessions,
+            })));
+        }
+        sessions_guard.insert(session_id.to_string(), TranscriptionSessionState::reserved(session_id, config));
+        sessions_guard.keys().cloned().collect()
     };
     
-    // DO NOT initialize ASR engine synchronously - it blocks on model download
-    // The engine will be initialized asynchronously in the background task
-    
-    // Store the session state in global app state
-    let session_state = TranscriptionSessionState {
-        session_id: session_id.clone(),
-        config: config.clone(),
-        start_time: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs(),
-        status: "active".to_string(),
-        audio_capture: Some("primary".to_string()),
-        whisper_config: whisper_config.clone(),
-        transcription_segments: Vec::new(), // Initialize empty segments vector
+    // Checked after the reservation: a capture that belongs to no active
+    // session was left behind, and no other start can claim it meanwhile
+    let orphaned_capture = state.audio_captures.lock().await.keys()
+        .any(|capture_session_id| !active_session_ids.contains(capture_session_id));
+    if orphaned_capture {
+        release_session_reservation(state, session_id).await;
+        let error_msg = "Audio capture is already active from a previous session. This may indicate improper cleanup from a previous session.".to_string();
+        return Err(CommandError::detailed("audio_capture_already_active", error_msg, vec![
+            "Use Emergency Stop to clear all audio resources".to_string(),
+            "Restart the application if the issue persists".to_string()
+        ]));
+    }
+    Ok(())
+}
+
+/// Remove sessions whose transcription loop stopped beating (it panicked or
+/// hung): stop their capture, save what they transcribed as interrupted and
+/// deliver it with session-crashed. Returns how many were removed.
+async fn reap_stalled_sessions(app_handle: &tauri::AppHandle) -> usize {
+    let state = app_handle.state::<AppState>();
+    let stalled: Vec<TranscriptionSessionState> = {
+        let mut sessions_guard = state.active_sessions.lock().await;
+        let ids: Vec<String> = sessions_guard.values()
+            .filter(|s| s.heartbeat.is_stalled(heartbeat::STALL_TIMEOUT))
+            .map(|s| s.session_id.clone())
+            .collect();
+        ids.iter().filter_map(|id| sessions_guard.remove(id)).collect()
     };
+    if stalled.is_empty() {
+        return 0;
+    }
     
-    let mut sessions_guard = state.active_sessions.lock().await;
-    sessions_guard.insert(session_id.clone(), session_state);
-    drop(sessions_guard);
-    
-    // Start ASR engine initialization and transcription loop in background
-    let app_handle_clone = app_handle.clone();
-    let session_id_clone = session_id.clone();
-    let whisper_config_clone = whisper_config.clone();
-    
-    tokio::spawn(async move {
-        tracing::info!("Starting background ASR initialization for session: {}", session_id_clone);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    for session_state in &stalled {
+        let session_id = &session_state.session_id;
+        let heartbeat_age = session_state.heartbeat.age().unwrap_or_default();
+        tracing::error!("Transcription loop of session {} stopped {:.0}s ago, cleaning up", session_id, heartbeat_age.as_secs_f64());
+        stop_session_capture(&state, session_id).await;
+        session_tasks(&state).cancel(session_id);
+        release_power_assertion(&state, session_id);
         
-        // Initialize WhisperEngine asynchronously with progress reporting
-        match initialize_whisper_engine_async(
-            whisper_config_clone, 
-            session_id_clone.clone(),
-            app_handle_clone.clone()
-        ).await {
-            Ok(engine) => {
-                // Store the initialized engine
-                let state = app_handle_clone.state::<AppState>();
-                let mut whisper_guard = state.whisper_engine.lock().await;
-                *whisper_guard = Some(engine);
-                drop(whisper_guard);
-                
-                // Initialize diarization service if enabled
-                if config.enable_speaker_diarization {
-                    tracing::info!("Initializing speaker diarization for session: {}", session_id_clone);
-                    let diarization_config = DiarizationConfig {
-                        max_speakers: 8,
-                        min_speakers: 2,
-                        embedding_dimension: 512,
-                        similarity_threshold: 0.7,
-                        min_segment_duration: 1.0,
-                        speaker_change_detection_threshold: 0.6,
-                        ..Default::default()
-                    };
-                    
-                    match DiarizationService::new(diarization_config).await {
-                        Ok(diarization_service) => {
-                            let mut diarization_guard = state.diarization_service.lock().await;
-                            *diarization_guard = Some(diarization_service);
-                            drop(diarization_guard);
-                            tracing::info!("Speaker diarization initialized successfully");
-                        }
-                        Err(e) => {
-                            tracing::warn!("Failed to initialize speaker diarization: {:?}", e);
-                        }
-                    }
-                }
-                
-                // Emit success event
-                if let Err(emit_err) = app_handle_clone.emit("model-ready", serde_json::json!({
-                    "sessionId": session_id_clone,
-                    "status": "ready",
-                    "message": "Whisper model loaded successfully"
-                })) {
-                    tracing::error!("Failed to emit model-ready event: {}", emit_err);
-                }
-                
-                // Start transcription loop
-                tracing::info!("Starting transcription loop for session: {}", session_id_clone);
-                if let Err(e) = run_transcription_loop(
-                    session_id_clone.clone(),
-                    app_handle_clone
-                ).await {
-                    tracing::error!("Transcription loop failed for session {}: {}", session_id_clone, e);
-                }
-            }
-            Err(e) => {
-                let detailed_error = format!(
-                    "Failed to initialize Whisper engine for session {}: {}. \
-                    Common causes: 1) Model files not found or corrupted, 2) Insufficient memory, \
-                    3) Permissions issues with models directory, 4) Network issues during download. \
-                    Check logs for specific model loading errors.", 
-                    session_id_clone, e
-                );
-                tracing::error!("{}", detailed_error);
-                
-                if let Err(emit_err) = app_handle_clone.emit("model-error", serde_json::json!({
-                    "sessionId": session_id_clone,
-                    "status": "error", 
-                    "message": detailed_error,
-                    "errorType": "model_initialization_failed",
-                    "originalError": e.to_string()
-                })) {
-                    tracing::error!("Failed to emit model-error event: {}", emit_err);
-                }
-            }
+        let record = build_session_record(session_state, now);
+        persist_session_end(&state, &record, now, session_store::SESSION_STATUS_INTERRUPTED).await;
+        record_usage_metrics(&state, session_state, record.total_duration as f64, "crashed").await;
+        state.completed_sessions.lock().await.insert(session_id.clone(), record);
+        
+        if let Err(emit_err) = emit_session_event(app_handle, session_id, "session-crashed", serde_json::json!({
+            "sessionId": session_id,
+            "message": "Transcription stopped responding; the transcript so far was saved",
+            "heartbeatAgeSeconds": heartbeat_age.as_secs(),
+            "segments": muting::export_segments(&session_state.transcription_segments, false),
+            "audioRecordingPath": session_state.recording_path,
+        })) {
+            tracing::warn!("Failed to emit session-crashed event: {}", emit_err);
         }
-    });
-    
-    tracing::info!("Transcription session {} started successfully", session_id);
-    tracing::info!("✅ Transcription session {} started successfully", session_id);
-    Ok(session_id)
+        state.close_event_log(session_id);
+    }
+    stalled.len()
+}
+
+/// Drop the reservation of a session that failed to start
+pub async fn release_session_reservation(state: &AppState, session_id: &str) {
+    let mut sessions_guard = state.active_sessions.lock().await;
+    if sessions_guard.get(session_id).is_some_and(|s| s.status == SESSION_STARTING) {
+        sessions_guard.remove(session_id);
+    }
 }
 
 // Helper functions for enhanced error diagnostics
@@ -707,36 +1965,60 @@ pub async fn start_transcription(
 fn emit_detailed_error(
     app_handle: &tauri::AppHandle,
     session_id: &str,
-    error_type: &str,
-    message: &str,
-    recovery_options: Vec<String>
+    error: &CommandError,
 ) {
-    let _ = app_handle.emit("transcription-error", serde_json::json!({
-        "type": error_type,
-        "message": message,
+    let payload = error.payload();
+    let _ = emit_session_event(app_handle, session_id, "transcription-error", serde_json::json!({
+        "code": payload.code,
+        "message": payload.message,
+        "category": payload.category,
+        "recoveryOptions": payload.recovery_options,
+        "details": payload.details,
         "sessionId": session_id,
         "timestamp": std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis(),
         "severity": "error",
-        "recoveryOptions": recovery_options,
-        "errorCategory": categorize_error(error_type)
+        // Names used before the command errors shared this payload
+        "type": payload.code,
+        "errorCategory": payload.category
     }));
 }
 
-/// Categorize errors for better user guidance
-fn categorize_error(error_type: &str) -> &'static str {
-    match error_type {
-        e if e.contains("model") => "model",
-        e if e.contains("audio") => "audio",
-        e if e.contains("permission") => "permissions",
-        e if e.contains("system") => "system",
-        e if e.contains("network") => "network",
-        _ => "unknown"
+/// Directory holding the per-session event logs
+fn event_log_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("KagiNote").join("event_logs"))
+}
+
+/// Emit a session-scoped event and record it in the session's event log
+fn emit_session_event(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    event: &str,
+    payload: serde_json::Value,
+) -> tauri::Result<()> {
+    let state = app_handle.state::<AppState>();
+    if let Ok(logs) = state.event_logs.lock() {
+        if let Some(writer) = logs.get(session_id) {
+            let timestamp_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            writer.record(event, &payload, timestamp_ms);
+        }
+    }
+    
+    if event == "transcription-update" {
+        if let Ok(router) = state.transcript_windows.lock() {
+            router.route(app_handle, session_id, &payload);
+        }
     }
+    
+    app_handle.emit(event, payload)
 }
 
+
 /// Emit enhanced audio error with device-specific troubleshooting and resampling information
 async fn emit_enhanced_audio_error(
     app_handle: &tauri::AppHandle,
@@ -747,8 +2029,11 @@ async fn emit_enhanced_audio_error(
     profile_manager: Option<&DeviceProfileManager>,
     actual_sample_rate: Option<u32>,
     target_sample_rate: Option<u32>,
+    suggestions: Vec<String>,
 ) {
-    let mut recovery_actions = Vec::new();
+    // Error-specific suggestions first
+    let has_specific_suggestions = !suggestions.is_empty();
+    let mut recovery_actions = suggestions;
 
     // Add device-specific suggestions if available
     if let (Some(device), Some(manager)) = (device_name, profile_manager) {
@@ -769,11 +2054,13 @@ async fn emit_enhanced_audio_error(
     }
 
     // Add general audio troubleshooting
-    recovery_actions.extend(vec![
-        "Check that no other applications are using the microphone".to_string(),
-        "Verify microphone permissions in System Preferences > Security & Privacy > Privacy > Microphone".to_string(),
-        "Try restarting the application".to_string(),
-    ]);
+    if !has_specific_suggestions {
+        recovery_actions.extend(vec![
+            "Check that no other applications are using the microphone".to_string(),
+            "Verify microphone permissions in System Preferences > Security & Privacy > Privacy > Microphone".to_string(),
+        ]);
+    }
+    recovery_actions.push("Try restarting the application".to_string());
 
     let error_data = serde_json::json!({
         "type": error_type,
@@ -797,7 +2084,7 @@ async fn emit_enhanced_audio_error(
         }
     });
 
-    let _ = app_handle.emit("transcription-error", error_data);
+    let _ = emit_session_event(app_handle, session_id, "transcription-error", error_data);
 }
 
 /// Get available disk space for models directory
@@ -807,6 +2094,9 @@ async fn get_available_disk_space(path: &Path) -> Result<u64, std::io::Error> {
         let _ = fs::create_dir_all(parent).await;
     }
     
+    // The directory itself may not exist yet; its file system is that of the nearest ancestor
+    let path = path.ancestors().find(|p| p.exists()).unwrap_or(path);
+    
     // Use statvfs on Unix systems to get disk space
     #[cfg(unix)]
     {
@@ -844,97 +2134,2747 @@ async fn get_available_disk_space(path: &Path) -> Result<u64, std::io::Error> {
         }
     }
     
-    // Fallback: return a large number if we can't determine disk space
-    Ok(10_000_000_000) // 10GB fallback
+    // GetDiskFreeSpaceExW on Windows, honouring per-user quotas
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn GetDiskFreeSpaceExW(
+                directory_name: *const u16,
+                free_bytes_available_to_caller: *mut u64,
+                total_number_of_bytes: *mut u64,
+                total_number_of_free_bytes: *mut u64,
+            ) -> i32;
+        }
+        
+        let wide_path: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        let mut free_bytes: u64 = 0;
+        let result = unsafe {
+            GetDiskFreeSpaceExW(wide_path.as_ptr(), &mut free_bytes, std::ptr::null_mut(), std::ptr::null_mut())
+        };
+        if result != 0 {
+            return Ok(free_bytes);
+        }
+    }
+    
+    // Fallback: return a large number if we can't determine disk space
+    Ok(10_000_000_000) // 10GB fallback
+}
+
+#[tauri::command]
+pub async fn stop_transcription(
+    session_id: String,
+    app_handle: tauri::AppHandle,
+) -> Result<FinalTranscriptionResult, CommandError> {
+    finish_session(session_id, &app_handle, None).await
+}
+
+/// Tear down a session and build its final result; `auto_stop` is set when a
+/// session limit rather than stop_transcription ended it
+pub(crate) async fn finish_session(
+    session_id: String,
+    app_handle: &tauri::AppHandle,
+    auto_stop: Option<AutoStopReason>,
+) -> Result<FinalTranscriptionResult, CommandError> {
+    let state = app_handle.state::<AppState>();
+    
+    tracing::info!("Stopping transcription session: {}", session_id);
+    
+    // Retrieve session from global state and stop it. The id may belong to an
+    // earlier part of a rollover chain, in which case the active continuation is stopped.
+    let mut sessions_guard = state.active_sessions.lock().await;
+    let active_session_id = {
+        let completed_guard = state.completed_sessions.lock().await;
+        resolve_active_session_id(&sessions_guard, &completed_guard, &session_id)
+    };
+    let mut session_state = active_session_id
+        .and_then(|id| sessions_guard.remove(&id))
+        .ok_or_else(|| {
+            tracing::warn!("Session {} not found in active sessions", session_id);
+            CommandError::NotFound(format!("Session {} not found", session_id))
+        })?;
+    drop(sessions_guard);
+    let session_id = session_state.session_id.clone();
+    session_tasks(&state).cancel(&session_id);
+    state.close_event_log(&session_id);
+    release_power_assertion(&state, &session_id);
+    
+    // Stop the session's audio capture; failing to stop it doesn't fail the whole operation
+    tracing::info!("Stopping audio capture for session {}", session_id);
+    if !stop_session_capture(&state, &session_id).await {
+        tracing::warn!("No active audio capture service found for session {}", session_id);
+    }
+    
+    // The loop finalizes the recording once it sees the session gone; wait so the WAV is complete
+    wait_for_recording_finalized(&state, &session_id).await;
+    
+    // The session's engine lease went with its state; the engine itself is
+    // freed once no file transcription or other session holds it
+    
+    // Calculate session duration
+    let current_time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let total_duration = (current_time - session_state.start_time) as f32;
+    
+    let refinement = if session_state.config.enable_speaker_diarization {
+        refine_session_speakers(&state, &mut session_state).await
+    } else {
+        None
+    };
+    
+    // Keep the finalized session for exports and continuation chains
+    let record = build_session_record(&session_state, current_time);
+    persist_session_end(&state, &record, current_time, session_store::SESSION_STATUS_COMPLETED).await;
+    {
+        let mut completed_guard = state.completed_sessions.lock().await;
+        completed_guard.insert(session_id.clone(), record);
+    }
+    // Ended cleanly, so there is nothing to recover
+    remove_session_autosave(&state, &session_id).await;
+    record_usage_metrics(&state, &session_state, total_duration as f64, "completed").await;
+    let speaker_estimate = final_speaker_estimate(&state, &session_state, refinement.as_ref()).await;
+    save_unknown_speakers(&state, &session_state).await;
+    spawn_backup_if_configured(app_handle);
+    
+    // Muted speakers are left out of both the segments and the keyword counts
+    let exported_segments = muting::export_segments(&session_state.transcription_segments, false);
+    let keyword_matches = KeywordMatcher::new(&session_state.config.keywords).summarize(&exported_segments);
+    let chapters = session_chapters(&exported_segments, &session_state.config.chapters);
+    
+    // Use the actual transcription segments if available, otherwise provide a default message
+    let segments = if !session_state.transcription_segments.is_empty() {
+        tracing::info!("Returning {} stored transcription segments", session_state.transcription_segments.len());
+        exported_segments
+    } else {
+        tracing::warn!("No transcription segments found, returning placeholder");
+        vec![
+            serde_json::json!({
+                "text": "No transcription data available",
+                "startTime": 0.0,
+                "endTime": total_duration,
+                "confidence": 0.0,
+                "speaker": "speaker_1"
+            })
+        ]
+    };
+    
+    let result = FinalTranscriptionResult {
+        session_id: session_id.clone(),
+        total_duration,
+        segments,
+        speakers: Some(session_speakers(&session_state)),
+        quality_metrics: quality_metrics::compute(&session_state.transcription_segments, &session_state.processing_stats),
+        processing_time_ms: session_state.processing_stats.processing_time.as_millis() as u64,
+        estimated_speaker_count: speaker_estimate.map(|e| e.count),
+        speaker_count_confidence: speaker_estimate.map(|e| e.confidence),
+        diarization_status: session_state.diarization_status,
+        audio_recording_path: session_state.recording_path.clone(),
+        auto_stop_reason: auto_stop.map(|reason| reason.as_str().to_string()),
+        keyword_matches,
+        chapters,
+    };
+    
+    tracing::info!("Transcription session {} stopped successfully", session_id);
+    Ok(result)
+}
+
+/// Chapters of a transcript, unless chaptering is turned off
+fn session_chapters(segments: &[serde_json::Value], config: &ChapterConfig) -> Vec<Chapter> {
+    if !config.enabled {
+        return Vec::new();
+    }
+    let chapters = chapters::split_chapters(segments, config);
+    tracing::debug!("Split {} segments into {} chapters", segments.len(), chapters.len());
+    chapters
+}
+
+/// Build the finalized record of a session ending at `end_time` (seconds since UNIX epoch)
+fn build_session_record(session_state: &TranscriptionSessionState, end_time: u64) -> SessionRecord {
+    SessionRecord {
+        session_id: session_state.session_id.clone(),
+        continuation_of: session_state.continuation_of.clone(),
+        start_time: session_state.start_time,
+        total_duration: end_time.saturating_sub(session_state.start_time) as f32,
+        segments: session_state.transcription_segments.clone(),
+        audio_path: session_state.recording_path.clone(),
+        speaker_mutes: session_state.speaker_mutes.clone(),
+    }
+}
+
+/// Resolve a session id that may have been rolled over to its currently active continuation
+fn resolve_active_session_id(
+    active_sessions: &HashMap<String, TranscriptionSessionState>,
+    completed_sessions: &HashMap<String, SessionRecord>,
+    session_id: &str,
+) -> Option<String> {
+    if active_sessions.contains_key(session_id) {
+        return Some(session_id.to_string());
+    }
+    
+    active_sessions
+        .values()
+        .find(|session_state| {
+            let mut previous = session_state.continuation_of.clone();
+            while let Some(previous_id) = previous {
+                if previous_id == session_id {
+                    return true;
+                }
+                previous = completed_sessions.get(&previous_id).and_then(|r| r.continuation_of.clone());
+            }
+            false
+        })
+        .map(|session_state| session_state.session_id.clone())
+}
+
+/// Finalize a session that reached its maxSessionDuration and start its continuation.
+/// Returns the continuation session id when a rollover happened.
+async fn roll_over_session_if_due(session_id: &str, app_handle: &tauri::AppHandle) -> Option<String> {
+    let state = app_handle.state::<AppState>();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    
+    let mut sessions_guard = state.active_sessions.lock().await;
+    let is_due = sessions_guard.get(session_id)
+        .map(|s| RolloverPolicy::new(s.config.max_session_duration).is_due(now.saturating_sub(s.start_time)))
+        .unwrap_or(false);
+    if !is_due {
+        return None;
+    }
+    
+    let previous_state = sessions_guard.remove(session_id)?;
+    let next_session_id = Uuid::new_v4().to_string();
+    let next_state = TranscriptionSessionState {
+        session_id: next_session_id.clone(),
+        start_time: now,
+        status: "active".to_string(),
+        transcription_segments: Vec::new(),
+        continuation_of: Some(session_id.to_string()),
+        error_count: 0,
+        recording_path: None,
+        recording_timeline: None,
+        processing_stats: ProcessingStats::default(),
+        progress: SessionProgress {
+            segment_count: 0,
+            last_segment_at: None,
+            ..previous_state.progress.clone()
+        },
+        ..previous_state.clone()
+    };
+    sessions_guard.insert(next_session_id.clone(), next_state.clone());
+    drop(sessions_guard);
+    
+    let record = build_session_record(&previous_state, now);
+    let segment_count = record.segments.len();
+    persist_session_end(&state, &record, now, session_store::SESSION_STATUS_COMPLETED).await;
+    persist_session_start(&state, &next_state).await;
+    record_usage_metrics(&state, &previous_state, record.total_duration as f64, "completed").await;
+    state.completed_sessions.lock().await.insert(session_id.to_string(), record);
+    state.close_event_log(session_id);
+    state.open_event_log(&next_session_id, previous_state.config.redact_event_log);
+    if let Ok(mut assertions) = state.power_assertions.lock() {
+        assertions.transfer(session_id, &next_session_id);
+    }
+    if let Ok(mut router) = state.transcript_windows.lock() {
+        router.transfer(session_id, &next_session_id);
+    }
+    
+    tracing::info!("🔁 Session {} reached its maximum duration, continuing as {}", session_id, next_session_id);
+    if let Err(emit_err) = emit_session_event(app_handle, &next_session_id, "session-rollover", serde_json::json!({
+        "previousSessionId": session_id,
+        "sessionId": next_session_id,
+        "continuationOf": session_id,
+        "segmentCount": segment_count,
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    })) {
+        tracing::warn!("Failed to emit session-rollover event: {}", emit_err);
+    }
+    
+    Some(next_session_id)
+}
+
+/// Minimum embeddings for a cluster to count toward the speaker estimate
+const SPEAKER_ESTIMATE_MIN_CLUSTER_SIZE: usize = 3;
+/// Minimum cluster cohesion for a cluster to count toward the speaker estimate
+const SPEAKER_ESTIMATE_MIN_COHESION: f32 = 0.6;
+/// Embeddings retained per session for re-clustering (bounds clustering cost)
+const MAX_SPEAKER_ESTIMATE_EMBEDDINGS: usize = 60;
+
+fn speaker_count_estimator(config: &DiarizationConfig) -> SpeakerCountEstimator {
+    SpeakerCountEstimator::new(SPEAKER_ESTIMATE_MIN_CLUSTER_SIZE, SPEAKER_ESTIMATE_MIN_COHESION, config.max_speakers)
+}
+
+/// Re-cluster the session's embeddings and emit speakers-estimate when the
+/// estimated count changes. The loop calls this every few embeddings.
+async fn update_speaker_estimate(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    session_id: &str,
+    diarization: &DiarizationService,
+    embeddings: &[SpeakerEmbedding],
+    estimator: &mut SpeakerCountEstimator,
+) {
+    let clusters = match diarization.cluster_speakers(embeddings).await {
+        Ok(clusters) => clusters,
+        Err(e) => {
+            tracing::warn!("Speaker re-clustering failed: {:?}", e);
+            return;
+        }
+    };
+    
+    let changed = estimator.update(&clusters);
+    if let Some(session_state) = state.active_sessions.lock().await.get_mut(session_id) {
+        session_state.speaker_estimate = estimator.current();
+    }
+    
+    if let Some(estimate) = changed {
+        tracing::info!("👥 Estimated {} speakers in session {} (confidence {:.2})", estimate.count, session_id, estimate.confidence);
+        if let Err(emit_err) = emit_session_event(app_handle, session_id, "speakers-estimate", serde_json::json!({
+            "sessionId": session_id,
+            "estimatedSpeakerCount": estimate.count,
+            "confidence": estimate.confidence,
+            "timestamp": std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+        })) {
+            tracing::warn!("Failed to emit speakers-estimate event: {}", emit_err);
+        }
+    }
+}
+
+/// Cluster all of a session's segment embeddings together and relabel the
+/// stored segments whose live speaker the full session revises. Returns the
+/// refinement, whose clusters the final speaker count is estimated from.
+async fn refine_session_speakers(
+    state: &AppState,
+    session_state: &mut TranscriptionSessionState,
+) -> Option<refinement::SpeakerRefinement> {
+    if session_state.session_embeddings.is_empty() {
+        return None;
+    }
+    let config = {
+        let diarization_guard = state.diarization_service.lock().await;
+        diarization_guard.as_ref().map(|d| d.get_config().clone()).unwrap_or_default()
+    };
+    
+    let refined = match refinement::refine_speakers(session_state.session_embeddings.embeddings(), &config).await {
+        Ok(refined) => refined,
+        Err(e) => {
+            tracing::warn!("Speaker refinement failed for session {}: {}", session_state.session_id, e);
+            return None;
+        }
+    };
+    let revised_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let previous_speakers: Vec<Option<String>> = session_state.transcription_segments.iter()
+        .map(|segment| segment["speaker"].as_str().map(str::to_string))
+        .collect();
+    match refinement::apply_refinement(&mut session_state.transcription_segments, &refined, &config, revised_at).await {
+        Ok(changed) => {
+            // Relabelled segments take the names of their new speaker
+            for &index in &changed {
+                let segment = &mut session_state.transcription_segments[index];
+                if let Some(fields) = segment.as_object_mut() {
+                    fields.remove(speaker_names::SPEAKER_NAME_FIELD);
+                }
+                session_state.speaker_names.label_segment(segment);
+            }
+            // Mutes follow the person to their new speaker id, as in rediarize_session
+            session_state.speaker_mutes.apply_remap(&muting::derive_remap(&previous_speakers, &session_state.transcription_segments));
+            session_state.speaker_mutes.flag_segments(&mut session_state.transcription_segments);
+            tracing::info!("Refined session {} to {} speakers, relabelling {} segments",
+                          session_state.session_id, refined.speaker_count, changed.len());
+        }
+        Err(e) => tracing::warn!("Failed to relabel segments of session {}: {}", session_state.session_id, e),
+    }
+    Some(refined)
+}
+
+/// Speakers of the final result with their segment counts and talk time
+fn session_speakers(session_state: &TranscriptionSessionState) -> Vec<serde_json::Value> {
+    if !session_state.config.enable_speaker_diarization || session_state.transcription_segments.is_empty() {
+        return vec![
+            serde_json::json!({
+                "id": "speaker_1",
+                "name": "Speaker 1",
+                "segments": 1
+            })
+        ];
+    }
+    speaker_stats::compute_speaker_stats(&session_state.transcription_segments).speakers.into_iter()
+        .map(|talk_time| serde_json::json!({
+            "name": session_state.speaker_names.name_of(&talk_time.speaker_id).unwrap_or(&talk_time.speaker_id),
+            "id": talk_time.speaker_id,
+            "segments": talk_time.segment_count,
+            "talkTime": talk_time.speaking_seconds
+        }))
+        .collect()
+}
+
+/// Speaker count of the final speakers the session-end refinement clustered
+/// from all of the session's embeddings; the live estimate without one
+async fn final_speaker_estimate(
+    state: &AppState,
+    session_state: &TranscriptionSessionState,
+    refinement: Option<&refinement::SpeakerRefinement>,
+) -> Option<SpeakerCountEstimate> {
+    let Some(refinement) = refinement.filter(|refinement| !refinement.clusters.is_empty()) else {
+        return session_state.speaker_estimate;
+    };
+    let config = {
+        let diarization_guard = state.diarization_service.lock().await;
+        diarization_guard.as_ref().map(|d| d.get_config().clone()).unwrap_or_default()
+    };
+    Some(speaker_count_estimator(&config).estimate(&refinement.clusters))
+}
+
+/// Speakers of a chunk's secondary voices, matched like the dominant voice but
+/// without creating speakers; voices nobody matches are listed as unknown
+async fn overlapping_speaker_ids(
+    state: &AppState,
+    session_id: &str,
+    diarization: &DiarizationService,
+    voices: &[ChunkVoice],
+) -> Vec<String> {
+    let similarity_threshold = diarization.get_config().similarity_threshold;
+    let mut speakers = Vec::new();
+    for voice in voices.iter().filter(|voice| voice.seconds >= overlap::MIN_OVERLAP_SECONDS) {
+        let embedding = &voice.embeddings[0];
+        if let Ok(Some(speaker)) = diarization.reidentify_speaker(embedding).await {
+            speakers.push(speaker);
+            continue;
+        }
+        if let KnownSpeakerMatch::Known(known) = identify_known_speaker(state, &embedding.vector, similarity_threshold).await {
+            speakers.push(known.speaker_id());
+            continue;
+        }
+        let sessions_guard = state.active_sessions.lock().await;
+        let unknown = sessions_guard.get(session_id)
+            .and_then(|s| s.unknown_speakers.matching_speaker(embedding, similarity_threshold))
+            .map(str::to_string);
+        speakers.push(unknown.unwrap_or_else(|| speaker_stats::UNKNOWN_SPEAKER.to_string()));
+    }
+    speakers
+}
+
+/// Search the stored speaker profiles for an embedding no session speaker
+/// matched. Each profile's own confidence threshold decides a match; a
+/// similarity between `similarity_floor` and that threshold is a near miss.
+async fn identify_known_speaker(state: &AppState, vector: &[f32], similarity_floor: f32) -> KnownSpeakerMatch {
+    let candidates = {
+        let index = state.embedding_index().await.lock().await;
+        match index.find_similar_embeddings(vector, known_speakers::INDEX_SEARCH_FLOOR, known_speakers::MAX_INDEX_CANDIDATES) {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                tracing::debug!("Embedding index search skipped: {}", e);
+                return KnownSpeakerMatch::Unknown;
+            }
+        }
+    };
+    if candidates.is_empty() {
+        return KnownSpeakerMatch::Unknown;
+    }
+    
+    let store_guard = state.speaker_store.lock().await;
+    let Some(store) = store_guard.as_ref() else {
+        return KnownSpeakerMatch::Unknown;
+    };
+    let mut profiles = Vec::with_capacity(candidates.len());
+    for (profile_id, similarity) in candidates {
+        match store.get_speaker_profile(profile_id).await {
+            Ok(Some(profile)) => profiles.push((profile, similarity)),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to load speaker profile {}: {}", profile_id, e),
+        }
+    }
+    known_speakers::match_known_speaker(profiles, similarity_floor)
+}
+
+/// Save the session's unmatched speakers as provisional profiles, if the session asked to remember speakers
+async fn save_unknown_speakers(state: &AppState, session_state: &TranscriptionSessionState) {
+    if !session_state.config.remember_speakers || session_state.unknown_speakers.is_empty() {
+        return;
+    }
+    
+    let store_guard = state.speaker_store.lock().await;
+    let Some(store) = store_guard.as_ref() else {
+        tracing::warn!("Speaker storage not initialized; unknown speakers of session {} were not saved", session_state.session_id);
+        return;
+    };
+    let index = state.embedding_index().await.lock().await;
+    for speaker in session_state.unknown_speakers.speakers() {
+        let profile = match store.create_speaker_profile(speaker.profile_request(&session_state.session_id)).await {
+            Ok(profile) => profile,
+            Err(e) => {
+                tracing::warn!("Failed to save provisional profile for {}: {}", speaker.speaker_id, e);
+                continue;
+            }
+        };
+        for embedding in speaker.voice_embeddings(profile.id) {
+            if let Err(e) = store.add_voice_embedding(embedding.clone()).await {
+                tracing::warn!("Failed to store embedding for provisional profile {}: {}", profile.id, e);
+                continue;
+            }
+            if let Err(e) = index.add_embedding(embedding) {
+                tracing::warn!("Failed to index embedding for provisional profile {}: {}", profile.id, e);
+            }
+        }
+        tracing::info!("Saved {} from session {} as provisional profile {}", speaker.speaker_id, session_state.session_id, profile.id);
+    }
+}
+
+/// Record content-free usage metrics for a finished session in the local database
+async fn record_usage_metrics(
+    state: &AppState,
+    session_state: &TranscriptionSessionState,
+    duration_seconds: f64,
+    outcome: &str,
+) {
+    let store = {
+        let db_guard = state.speaker_database.lock().await;
+        match db_guard.as_ref() {
+            Some(database) => UsageMetricsStore::new(database.clone()),
+            None => {
+                tracing::debug!("Storage not initialized, skipping usage metrics");
+                return;
+            }
+        }
+    };
+    
+    let metrics = SessionUsageMetrics {
+        ended_at: chrono::Utc::now().timestamp(),
+        duration_seconds,
+        model_tier: match session_state.config.quality_tier.as_str() {
+            "high-accuracy" => "high-accuracy",
+            "turbo" => "turbo",
+            _ => "standard",
+        }.to_string(),
+        outcome: outcome.to_string(),
+        segment_count: session_state.transcription_segments.len() as u32,
+        error_count: session_state.error_count,
+        diarization_enabled: session_state.config.enable_speaker_diarization,
+    };
+    
+    if let Err(e) = store.record_session(metrics).await {
+        tracing::warn!("Failed to record usage metrics: {}", e);
+    }
+}
+
+/// Session persistence, when storage is initialized
+async fn saved_session_store(state: &AppState) -> Option<SessionStore> {
+    let db_guard = state.speaker_database.lock().await;
+    db_guard.as_ref().map(|database| SessionStore::new(database.clone()))
+}
+
+/// A finished session of this run, or else as saved by an earlier run
+async fn finished_session_record(state: &AppState, session_id: &str) -> Result<SessionRecord, CommandError> {
+    if let Some(record) = state.completed_sessions.lock().await.get(session_id) {
+        return Ok(record.clone());
+    }
+    let saved = match saved_session_store(state).await {
+        Some(store) => store.get_session(session_id)
+            .await
+            .map_err(|e| CommandError::Storage(format!("Failed to load saved session: {}", e)))?,
+        None => None,
+    };
+    saved.map(saved_session_record)
+        .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))
+}
+
+fn saved_session_record(saved: SavedSession) -> SessionRecord {
+    SessionRecord {
+        speaker_mutes: SpeakerMutes::from_flagged_segments(&saved.segments),
+        session_id: saved.summary.session_id,
+        continuation_of: saved.summary.continuation_of,
+        start_time: saved.summary.started_at.max(0) as u64,
+        total_duration: saved.summary.total_duration as f32,
+        audio_path: saved.summary.audio_path,
+        segments: saved.segments,
+    }
+}
+
+/// All finished parts of the rollover chain containing `session_id`, oldest first.
+/// Parts come from memory when present and from the session store otherwise, so
+/// a chain recorded before a restart still exports whole.
+async fn finished_session_chain(state: &AppState, session_id: &str) -> Result<Vec<SessionRecord>, CommandError> {
+    let first = finished_session_record(state, session_id).await?;
+    let store = saved_session_store(state).await;
+    let mut records = HashMap::new();
+
+    // Earlier parts
+    let mut previous = first.continuation_of.clone();
+    records.insert(first.session_id.clone(), first);
+    while let Some(id) = previous.take() {
+        if records.contains_key(&id) {
+            break;
+        }
+        match finished_session_record(state, &id).await {
+            Ok(record) => {
+                previous = record.continuation_of.clone();
+                records.insert(id, record);
+            }
+            Err(CommandError::NotFound(_)) => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    // Later parts, skipping one that is still recording
+    let mut current = session_id.to_string();
+    loop {
+        let in_memory = state.completed_sessions.lock().await.values()
+            .find(|record| record.continuation_of.as_deref() == Some(current.as_str()))
+            .map(|record| record.session_id.clone());
+        let next = match (in_memory, &store) {
+            (Some(id), _) => Some(id),
+            (None, Some(store)) => store.get_continuation(&current)
+                .await
+                .map_err(|e| CommandError::Storage(format!("Failed to load session continuation: {}", e)))?,
+            (None, None) => None,
+        };
+        let Some(next) = next else { break };
+        if records.contains_key(&next) || state.active_sessions.lock().await.contains_key(&next) {
+            break;
+        }
+        let record = finished_session_record(state, &next).await?;
+        records.insert(next.clone(), record);
+        current = next;
+    }
+
+    Ok(session_chain::collect_chain(&records, session_id).into_iter().cloned().collect())
+}
+
+/// Save a newly started session so its transcript survives crashes and restarts
+async fn persist_session_start(state: &AppState, session_state: &TranscriptionSessionState) {
+    let Some(store) = saved_session_store(state).await else {
+        tracing::debug!("Storage not initialized, session {} is not persisted", session_state.session_id);
+        return;
+    };
+    let config = serde_json::to_value(&session_state.config).unwrap_or_default();
+    if let Err(e) = store.create_session(
+        &session_state.session_id,
+        session_state.start_time as i64,
+        session_state.continuation_of.clone(),
+        config,
+    ).await {
+        tracing::warn!("Failed to persist session {}: {}", session_state.session_id, e);
+    }
+}
+
+/// Write the segments produced since the last flush; returns the new persisted count
+async fn persist_new_segments(state: &AppState, session_id: &str, persisted: usize) -> usize {
+    let pending: Vec<serde_json::Value> = {
+        let sessions_guard = state.active_sessions.lock().await;
+        match sessions_guard.get(session_id) {
+            Some(session_state) => session_state.transcription_segments.iter().skip(persisted).cloned().collect(),
+            None => return persisted,
+        }
+    };
+    if pending.is_empty() {
+        return persisted;
+    }
+    let Some(store) = saved_session_store(state).await else {
+        return persisted;
+    };
+    let count = pending.len();
+    match store.append_segments(session_id, persisted, pending).await {
+        Ok(()) => persisted + count,
+        Err(e) => {
+            tracing::warn!("Failed to persist segments of session {}: {}", session_id, e);
+            persisted
+        }
+    }
+}
+
+/// Write the autosave of an active session. Skipped once the session is gone,
+/// so a session stopped meanwhile is not given a new file.
+async fn autosave_session(state: &AppState, session_id: &str) {
+    let Some(dir) = autosave::autosave_dir() else {
+        return;
+    };
+    let _autosave_guard = state.autosave_lock.lock().await;
+    let snapshot = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(session_id).map(|session_state| SessionAutosave {
+            format_version: autosave::AUTOSAVE_FORMAT_VERSION,
+            session_id: session_state.session_id.clone(),
+            start_time: session_state.start_time,
+            saved_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            continuation_of: session_state.continuation_of.clone(),
+            config: serde_json::to_value(&session_state.config).unwrap_or_default(),
+            segments: session_state.transcription_segments.clone(),
+        })
+    };
+    let Some(snapshot) = snapshot else {
+        return;
+    };
+    match tokio::task::spawn_blocking(move || autosave::write_autosave(&dir, &snapshot)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::warn!("Failed to autosave session {}: {:#}", session_id, e),
+        Err(e) => tracing::warn!("Failed to autosave session {}: {}", session_id, e),
+    }
+}
+
+/// Delete the autosave of a session that no longer needs recovering
+async fn remove_session_autosave(state: &AppState, session_id: &str) {
+    let Some(dir) = autosave::autosave_dir() else {
+        return;
+    };
+    let _autosave_guard = state.autosave_lock.lock().await;
+    if let Err(e) = autosave::remove_autosave(&dir, session_id) {
+        tracing::warn!("Failed to delete the autosave of session {}: {:#}", session_id, e);
+    }
+}
+
+/// Take `session_id` off the recoverable sessions; NotFound unless it was on them
+fn take_recoverable_session(state: &AppState, session_id: &str) -> Result<(), CommandError> {
+    metadata::validate_identifier("sessionId", session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    let mut recoverable = state.recoverable_sessions.lock()
+        .map_err(|e| CommandError::Failed(format!("Failed to read recoverable sessions: {}", e)))?;
+    let index = recoverable.iter()
+        .position(|session| session.session_id == session_id)
+        .ok_or_else(|| CommandError::NotFound(format!("No recoverable session {}", session_id)))?;
+    recoverable.remove(index);
+    Ok(())
+}
+
+/// Autosaved sessions a previous run did not finish, most recent first, for a
+/// "Recover transcript from crashed session?" prompt
+#[tauri::command]
+pub async fn get_recoverable_sessions(state: State<'_, AppState>) -> Result<Vec<RecoverableSession>, CommandError> {
+    state.recoverable_sessions.lock()
+        .map(|recoverable| recoverable.clone())
+        .map_err(|e| CommandError::Failed(format!("Failed to read recoverable sessions: {}", e)))
+}
+
+/// Segments and config of an autosaved session; its autosave is deleted
+#[tauri::command]
+pub async fn recover_session(
+    session_id: String,
+    state: State<'_, AppState>
+) -> Result<SessionAutosave, CommandError> {
+    take_recoverable_session(&state, &session_id)?;
+    let dir = autosave::autosave_dir().ok_or("Failed to get app data directory")?;
+    let recovered = autosave::read_autosave(&dir, &session_id)
+        .map_err(|e| CommandError::Failed(format!("{:#}", e)))?
+        .ok_or_else(|| CommandError::NotFound(format!("The autosave of session {} is gone", session_id)))?;
+    remove_session_autosave(&state, &session_id).await;
+    tracing::info!("Recovered {} segments of session {}", recovered.segments.len(), session_id);
+    Ok(recovered)
+}
+
+/// Delete the autosave of a session without recovering it
+#[tauri::command]
+pub async fn discard_recovered_session(
+    session_id: String,
+    state: State<'_, AppState>
+) -> Result<bool, CommandError> {
+    take_recoverable_session(&state, &session_id)?;
+    let dir = autosave::autosave_dir().ok_or("Failed to get app data directory")?;
+    let _autosave_guard = state.autosave_lock.lock().await;
+    autosave::remove_autosave(&dir, &session_id).map_err(|e| CommandError::Failed(format!("{:#}", e)))
+}
+
+/// Store the final transcript of an ended session
+async fn persist_session_end(state: &AppState, record: &SessionRecord, ended_at: u64, status: &str) {
+    let Some(store) = saved_session_store(state).await else {
+        return;
+    };
+    if let Err(e) = store.finish_session(
+        &record.session_id,
+        status,
+        ended_at as i64,
+        record.total_duration as f64,
+        record.audio_path.clone(),
+        record.segments.clone(),
+    ).await {
+        tracing::warn!("Failed to persist end of session {}: {}", record.session_id, e);
+    }
+}
+
+/// Saved sessions, newest first, including sessions from earlier app runs
+#[tauri::command]
+pub async fn list_saved_sessions(state: State<'_, AppState>) -> Result<Vec<SavedSessionSummary>, CommandError> {
+    state.speaker_storage_ready().await?;
+    let store = saved_session_store(&state).await.ok_or("Storage not initialized")?;
+    store.list_sessions()
+        .await
+        .map_err(|e| CommandError::Storage(format!("Failed to list saved sessions: {}", e)))
+}
+
+/// Search the saved sessions' transcripts for `query`, with optional session,
+/// speaker and date filters and paging
+#[tauri::command]
+pub async fn search_transcripts(
+    query: String,
+    options: Option<SearchOptions>,
+    state: State<'_, AppState>
+) -> Result<SearchPage, CommandError> {
+    let options = options.unwrap_or_default();
+    if let Some(session_id) = &options.session_id {
+        metadata::validate_identifier("sessionId", session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    }
+    if let (Some(from), Some(to)) = (options.from, options.to) {
+        if from > to {
+            return Err(CommandError::InvalidInput(format!("Search range starts after it ends ({} > {})", from, to)));
+        }
+    }
+    state.speaker_storage_ready().await?;
+    let store = saved_session_store(&state).await.ok_or("Storage not initialized")?;
+    store.search(&query, &options)
+        .await
+        .map_err(|e| CommandError::Storage(format!("Failed to search transcripts: {}", e)))
+}
+
+/// A saved session with its configuration and full transcript
+#[tauri::command]
+pub async fn get_saved_session(
+    session_id: String,
+    state: State<'_, AppState>
+) -> Result<SavedSession, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    state.speaker_storage_ready().await?;
+    let store = saved_session_store(&state).await.ok_or("Storage not initialized")?;
+    store.get_session(&session_id)
+        .await
+        .map_err(|e| CommandError::Storage(format!("Failed to load saved session: {}", e)))?
+        .ok_or_else(|| CommandError::NotFound(format!("Saved session {} not found", session_id)))
+}
+
+/// Delete a saved session and its transcript. Running sessions must be stopped first.
+#[tauri::command]
+pub async fn delete_saved_session(
+    session_id: String,
+    state: State<'_, AppState>
+) -> Result<bool, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    if state.active_sessions.lock().await.contains_key(&session_id) {
+        return Err(CommandError::detailed("session_still_running", format!("Session {} is still running", session_id), vec![
+            "Stop the session before deleting it".to_string()
+        ]));
+    }
+    state.speaker_storage_ready().await?;
+    let store = saved_session_store(&state).await.ok_or("Storage not initialized")?;
+    let deleted = store.delete_session(&session_id)
+        .await
+        .map_err(|e| CommandError::Storage(format!("Failed to delete saved session: {}", e)))?;
+    state.completed_sessions.lock().await.remove(&session_id);
+    Ok(deleted)
+}
+
+/// Aggregated local usage metrics for the in-app dashboard (Unix-second range, inclusive)
+#[tauri::command]
+pub async fn get_usage_metrics(
+    start: Option<i64>,
+    end: Option<i64>,
+    state: State<'_, AppState>
+) -> Result<UsageSummary, CommandError> {
+    state.speaker_storage_ready().await?;
+    let db_guard = state.speaker_database.lock().await;
+    let database = db_guard.as_ref().ok_or("Storage not initialized")?.clone();
+    drop(db_guard);
+    
+    UsageMetricsStore::new(database)
+        .summarize(start, end)
+        .await
+        .map_err(|e| CommandError::Storage(format!("Failed to load usage metrics: {}", e)))
+}
+
+/// Delete all locally recorded usage metrics
+#[tauri::command]
+pub async fn clear_usage_metrics(state: State<'_, AppState>) -> Result<usize, CommandError> {
+    state.speaker_storage_ready().await?;
+    let db_guard = state.speaker_database.lock().await;
+    let database = db_guard.as_ref().ok_or("Storage not initialized")?.clone();
+    drop(db_guard);
+    
+    UsageMetricsStore::new(database)
+        .clear()
+        .await
+        .map_err(|e| CommandError::Storage(format!("Failed to clear usage metrics: {}", e)))
+}
+
+/// Result of deleting a downloaded model
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeletedModel {
+    /// Tiers whose model file was removed (tiers can share a file)
+    pub tiers: Vec<crate::asr::types::ModelTier>,
+    #[serde(rename = "freedBytes")]
+    pub freed_bytes: u64,
+}
+
+fn parse_model_tier(tier: &str) -> Result<crate::asr::types::ModelTier, String> {
+    crate::asr::types::ModelTier::from_quality_tier(tier)
+        .ok_or_else(|| format!("Unknown model tier '{}'. Expected standard, high-accuracy or turbo", tier))
+}
+
+fn open_model_manager() -> Result<ModelManager, String> {
+    ModelManager::new().map_err(|e| format!("Failed to open models directory: {}", e))
+}
+
+/// Whisper models present on disk with their size and checksum status
+#[tauri::command]
+pub async fn list_downloaded_models() -> Result<Vec<DownloadedModel>, CommandError> {
+    Ok(open_model_manager()?.list_downloaded_models().await)
+}
+
+/// Download size, installed size and recommended memory of each model tier
+#[tauri::command]
+pub async fn get_model_requirements() -> Result<Vec<ModelRequirements>, CommandError> {
+    Ok(open_model_manager()?.model_requirements().await)
+}
+
+/// Delete the files of a model tier. Refused while a session uses that model file.
+#[tauri::command]
+pub async fn delete_model(
+    tier: String,
+    state: State<'_, AppState>
+) -> Result<DeletedModel, CommandError> {
+    let tier = parse_model_tier(&tier)?;
+    let mut manager = open_model_manager()?;
+    let tiers = manager.tiers_sharing_file(tier);
+    
+    let sessions_guard = state.active_sessions.lock().await;
+    if let Some(session) = sessions_guard.values().find(|s| tiers.contains(&s.whisper_config.model_tier)) {
+        return Err(CommandError::detailed("model_in_use", format!(
+            "The {} model is in use by session {}. Stop the session before deleting it.",
+            tier.quality_tier(), session.session_id
+        ), vec![
+            "Stop the session before deleting the model".to_string()
+        ]).with_details(serde_json::json!({ "sessionId": session.session_id })));
+    }
+    // Held until the files are gone so no session can start with this model meanwhile
+    let freed_bytes = manager.delete_model(tier).await
+        .map_err(|e| format!("Failed to delete model: {}", e))?;
+    drop(sessions_guard);
+    
+    if let Ok(mut cache) = state.storage_usage.lock() {
+        cache.invalidate();
+    }
+    tracing::info!("Deleted {} model, freed {} bytes", tier.quality_tier(), freed_bytes);
+    Ok(DeletedModel { tiers, freed_bytes })
+}
+
+/// Re-hash a model file against its reference checksum and report corruption
+#[tauri::command]
+pub async fn verify_model(tier: String) -> Result<ModelVerification, CommandError> {
+    let tier = parse_model_tier(&tier)?;
+    Ok(open_model_manager()?.verify_model(tier).await?)
+}
+
+/// Where models are stored and the space left there
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageInfo {
+    #[serde(rename = "modelsDirectory")]
+    pub models_directory: PathBuf,
+    /// Directory chosen with set_models_directory, if any
+    #[serde(rename = "configuredDirectory")]
+    pub configured_directory: Option<PathBuf>,
+    /// The configured directory is unavailable and the default is in use
+    #[serde(rename = "usingFallback")]
+    pub using_fallback: bool,
+    #[serde(rename = "freeBytes")]
+    pub free_bytes: u64,
+    #[serde(rename = "modelsBytes")]
+    pub models_bytes: u64,
+}
+
+/// Result of changing the models directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelsDirectoryChange {
+    pub storage: StorageInfo,
+    pub migration: ModelsMigration,
+}
+
+async fn storage_info() -> Result<StorageInfo, String> {
+    let resolved = models_location::current()
+        .map_err(|e| format!("Failed to resolve models directory: {}", e))?;
+    let models_bytes = models_location::model_files(&resolved.directory).iter().map(|(_, size)| size).sum();
+    let free_bytes = get_available_disk_space(&resolved.directory).await.unwrap_or(0);
+    Ok(StorageInfo {
+        models_directory: resolved.directory,
+        configured_directory: resolved.configured,
+        using_fallback: resolved.fell_back,
+        free_bytes,
+        models_bytes,
+    })
+}
+
+/// Current models directory and its free space
+#[tauri::command]
+pub async fn get_storage_info() -> Result<StorageInfo, CommandError> {
+    Ok(storage_info().await?)
+}
+
+/// Store models in `path` from now on, moving the downloaded models there.
+/// `None` returns to the default directory. Refused while sessions or
+/// downloads are running.
+#[tauri::command]
+pub async fn set_models_directory(
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ModelsDirectoryChange, CommandError> {
+    let settings_path = models_location::settings_path().ok_or("Failed to get app data directory")?;
+    let default = models_location::default_models_directory().ok_or("Failed to get app data directory")?;
+    let target = path.map(PathBuf::from).unwrap_or_else(|| default.clone());
+    if !target.is_absolute() {
+        return Err(CommandError::InvalidInput(format!("Models directory must be an absolute path: {:?}", target)));
+    }
+    
+    // Held until the files are moved so no session starts reading the old location
+    let sessions_guard = state.active_sessions.lock().await;
+    if !sessions_guard.is_empty() {
+        return Err(CommandError::detailed("model_directory_session_active", "Stop all transcription sessions before changing the models directory", vec![
+            "Stop all transcription sessions and try again".to_string()
+        ]));
+    }
+    if state.model_downloads.lock().map(|downloads| !downloads.is_empty()).unwrap_or(false) {
+        return Err(CommandError::detailed("model_download_in_progress", "Wait for model downloads to finish before changing the models directory", vec![
+            "Wait for the download to finish or cancel it".to_string()
+        ]));
+    }
+    
+    let current = models_location::current()
+        .map_err(|e| format!("Failed to resolve models directory: {}", e))?;
+    models_location::ensure_writable(&target)
+        .map_err(|e| format!("Models directory {:?} is not writable: {}", target, e))?;
+    let needed: u64 = models_location::model_files(&current.directory).iter().map(|(_, size)| size).sum();
+    let available = get_available_disk_space(&target).await.unwrap_or(0);
+    if target != current.directory && available < needed + models_location::MIN_FREE_MARGIN_BYTES {
+        return Err(CommandError::detailed("model_disk_space_insufficient", format!(
+            "Not enough space in {:?}: {:.1}MB free, {:.1}MB needed for the downloaded models",
+            target,
+            available as f64 / (1024.0 * 1024.0),
+            (needed + models_location::MIN_FREE_MARGIN_BYTES) as f64 / (1024.0 * 1024.0)
+        ), vec![
+            "Free up disk space or choose another directory".to_string()
+        ]).with_details(serde_json::json!({
+            "freeBytes": available,
+            "neededBytes": needed + models_location::MIN_FREE_MARGIN_BYTES,
+        })));
+    }
+    
+    let (from, to) = (current.directory.clone(), target.clone());
+    let migration = tokio::task::spawn_blocking(move || models_location::migrate(&from, &to))
+        .await
+        .map_err(|e| format!("Failed to move models: {}", e))?;
+    let location = ModelsLocation { directory: (target != default).then(|| target.clone()) };
+    models_location::save(&settings_path, &location)
+        .map_err(|e| format!("Failed to save models directory: {}", e))?;
+    drop(sessions_guard);
+    
+    if let Ok(mut cache) = state.storage_usage.lock() {
+        cache.invalidate();
+    }
+    tracing::info!(
+        "Models directory set to {:?}: moved {} file(s), {} to re-download",
+        target, migration.moved_files.len(), migration.redownload_files.len()
+    );
+    Ok(ModelsDirectoryChange { storage: storage_info().await?, migration })
+}
+
+/// Warn when the configured models directory is missing at startup (e.g. an
+/// unplugged external drive); models are read from the default meanwhile
+pub fn check_models_directory(app_handle: &tauri::AppHandle) {
+    let Ok(resolved) = models_location::current() else {
+        return;
+    };
+    if !resolved.fell_back {
+        return;
+    }
+    tracing::warn!(
+        "Models directory {:?} is unavailable, using {:?}",
+        resolved.configured, resolved.directory
+    );
+    if let Err(emit_err) = app_handle.emit("models-directory-unavailable", serde_json::json!({
+        "configuredDirectory": resolved.configured,
+        "fallbackDirectory": resolved.directory,
+        "message": "The configured models directory is unavailable; models are read from the default location until it is reconnected",
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    })) {
+        tracing::warn!("Failed to emit models-directory-unavailable event: {}", emit_err);
+    }
+}
+
+/// Data locations to include in the disk usage breakdown
+fn storage_layout() -> Option<StorageLayout> {
+    use crate::asr::model_manager::ModelManager;
+    use crate::asr::types::ModelTier;
+    
+    let model_files = ModelManager::new()
+        .map(|manager| {
+            [(ModelTier::Standard, "standard"), (ModelTier::HighAccuracy, "high-accuracy"), (ModelTier::Turbo, "turbo")]
+                .into_iter()
+                .filter_map(|(tier, label)| manager.get_model_metadata(tier).map(|m| (m.name.clone(), label.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    Some(StorageLayout {
+        data_dir: dirs::data_local_dir()?.join("KagiNote"),
+        models_dir: models_location::current().ok().map(|resolved| resolved.directory),
+        backup_dir: load_backup_policy().map(|policy| policy.directory),
+        model_files,
+    })
+}
+
+/// Disk usage of the app data by category. A cached report younger than
+/// `max_age_secs` (default 5 minutes) is returned without walking again.
+#[tauri::command]
+pub async fn compute_storage_usage(
+    max_age_secs: Option<u64>,
+    top_sessions: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<StorageUsageReport, CommandError> {
+    let max_age_secs = max_age_secs.unwrap_or(storage_usage::DEFAULT_MAX_AGE_SECS);
+    let cancel = {
+        let mut cache = state.storage_usage.lock().map_err(|e| format!("Failed to read storage usage cache: {}", e))?;
+        if let Some(report) = cache.fresh(max_age_secs) {
+            return Ok(report);
+        }
+        cache.begin()
+    };
+    
+    let layout = storage_layout().ok_or("Failed to get app data directory")?;
+    let walk_cancel = cancel.clone();
+    let report = storage_usage::compute_storage_usage(
+        &layout,
+        top_sessions.unwrap_or(storage_usage::DEFAULT_TOP_SESSIONS),
+        move || walk_cancel.load(std::sync::atomic::Ordering::SeqCst),
+    ).await;
+    
+    let mut cache = state.storage_usage.lock().map_err(|e| format!("Failed to read storage usage cache: {}", e))?;
+    match report {
+        Ok(Some(report)) => {
+            cache.finish(&cancel, Some(report.clone()));
+            tracing::info!("💾 Storage usage: {} bytes in {} categories", report.total_bytes, report.categories.len());
+            Ok(report)
+        }
+        Ok(None) => {
+            cache.finish(&cancel, None);
+            Err(CommandError::detailed("storage_usage_cancelled", "Storage usage computation was cancelled", Vec::new()))
+        }
+        Err(e) => {
+            cache.finish(&cancel, None);
+            Err(CommandError::Storage(format!("Failed to compute storage usage: {}", e)))
+        }
+
+    }
+}
+
+/// Cancel a running storage usage computation; the cached report is kept
+#[tauri::command]
+pub async fn cancel_storage_usage(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let mut cache = state.storage_usage.lock().map_err(|e| format!("Failed to read storage usage cache: {}", e))?;
+    Ok(cache.cancel())
+}
+
+/// Export a finalized session together with all parts of its rollover chain.
+/// Segments of muted speakers are left out unless `include_muted` is set;
+/// `include_highlights` adds the saved highlights as an appendix.
+#[tauri::command]
+pub async fn export_session_chain(
+    session_id: String,
+    stitch: Option<bool>,
+    include_muted: Option<bool>,
+    include_highlights: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<serde_json::Value, CommandError> {
+    let chain: Vec<SessionRecord> = finished_session_chain(&state, &session_id)
+        .await?
+        .into_iter()
+        .map(|record| SessionRecord {
+            segments: muting::export_segments(&record.segments, include_muted.unwrap_or(false)),
+            ..record
+        })
+        .collect();
+    
+    // Highlights appendix across all parts of the chain
+    let highlights_appendix = if include_highlights.unwrap_or(false) {
+        let dirs: Vec<PathBuf> = chain.iter()
+            .filter_map(|record| session_highlights_dir(record.audio_path.as_deref(), &record.session_id))
+            .collect();
+        let appendix = tokio::task::spawn_blocking(move || -> Result<Vec<Highlight>, String> {
+            let mut all = Vec::new();
+            for dir in dirs {
+                all.extend(highlights::list_highlights(&dir).map_err(|e| format!("Failed to read highlights: {}", e))?);
+            }
+            Ok(all)
+        }).await
+            .map_err(|e| format!("Failed to read highlights: {}", e))??;
+        Some(appendix)
+    } else {
+        None
+    };
+    
+    let mut document = if stitch.unwrap_or(true) {
+        let parts: Vec<&SessionRecord> = chain.iter().collect();
+        let mut document = session_chain::stitch_chain(&parts);
+        let muted_speakers: Vec<String> = chain.iter()
+            .flat_map(|record| record.speaker_mutes.muted_speakers())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        document["mutedSpeakers"] = serde_json::json!(muted_speakers);
+        document
+    } else {
+        serde_json::json!({ "parts": chain })
+    };
+    if let Some(appendix) = highlights_appendix {
+        document["highlights"] = serde_json::json!(appendix);
+    }
+    Ok(document)
+}
+
+/// Export a live or finished session transcript as SRT or WebVTT subtitles
+#[tauri::command]
+pub async fn export_transcript(
+    session_id: String,
+    format: String,
+    include_muted: Option<bool>,
+    speaker_labels: Option<HashMap<String, String>>,
+    state: State<'_, AppState>
+) -> Result<SubtitleExport, CommandError> {
+    let format = SubtitleFormat::parse(&format)
+        .ok_or_else(|| CommandError::InvalidInput(format!("Unsupported transcript format: {}", format)))?;
+    
+    let live_segments = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id).map(|session_state| session_state.transcription_segments.clone())
+    };
+    let segments = match live_segments {
+        Some(segments) => segments,
+        None => {
+            let completed_guard = state.completed_sessions.lock().await;
+            completed_guard.get(&session_id)
+                .map(|record| record.segments.clone())
+                .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?
+        }
+    };
+    
+    let segments = muting::export_segments(&segments, include_muted.unwrap_or(false));
+    Ok(subtitles::export_subtitles(&segments, format, &speaker_labels.unwrap_or_default()))
+}
+
+/// Convert a transcript segments payload (e.g. from FinalTranscriptionResult) to SRT or WebVTT
+#[tauri::command]
+pub async fn format_transcript_segments(
+    segments: Vec<serde_json::Value>,
+    format: String,
+    speaker_labels: Option<HashMap<String, String>>,
+) -> Result<SubtitleExport, CommandError> {
+    let format = SubtitleFormat::parse(&format)
+        .ok_or_else(|| CommandError::InvalidInput(format!("Unsupported transcript format: {}", format)))?;
+    Ok(subtitles::export_subtitles(&segments, format, &speaker_labels.unwrap_or_default()))
+}
+
+/// Write Markdown meeting notes for a live or finished session: metadata, the
+/// transcript as speaker turns under chapter headings, and the talk-time table.
+/// Muted speakers are left out.
+#[tauri::command]
+pub async fn export_session_markdown(
+    session_id: String,
+    path: String,
+    state: State<'_, AppState>
+) -> Result<MeetingNotesExport, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let live_record = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .map(|session_state| (build_session_record(session_state, now), session_state.config.chapters.clone()))
+    };
+    // Finished sessions keep no config, so their chapters use the default thresholds
+    let (mut record, chapter_config) = match live_record {
+        Some(live) => live,
+        None => {
+            let completed_guard = state.completed_sessions.lock().await;
+            let record = completed_guard.get(&session_id)
+                .cloned()
+                .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?;
+            (record, ChapterConfig::default())
+        }
+    };
+    record.segments = muting::export_segments(&record.segments, false);
+    let chapters = session_chapters(&record.segments, &chapter_config);
+    
+    // Speakers recognised from stored profiles are labelled with the profile name
+    let mut profile_names = HashMap::new();
+    {
+        let store_guard = state.speaker_store.lock().await;
+        if let Some(store) = store_guard.as_ref() {
+            let profile_ids: std::collections::BTreeSet<Uuid> = record.segments.iter()
+                .filter_map(|segment| segment["speaker"].as_str())
+                .filter_map(|speaker| Uuid::parse_str(speaker).ok())
+                .collect();
+            for profile_id in profile_ids {
+                match store.get_speaker_profile(profile_id).await {
+                    Ok(Some(profile)) => {
+                        profile_names.insert(profile_id.to_string(), profile.name);
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failed to load speaker profile {}: {}", profile_id, e),
+                }
+            }
+        }
+    }
+    
+    let notes = markdown::render_meeting_notes(&record, &profile_names, &chapters);
+    let output_path = PathBuf::from(&path);
+    if let Some(parent) = output_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).await
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    fs::write(&output_path, &notes.content).await
+        .map_err(|e| format!("Failed to write meeting notes: {}", e))?;
+    
+    tracing::info!("Exported meeting notes for session {} to {}", session_id, path);
+    Ok(MeetingNotesExport {
+        path,
+        byte_count: notes.content.len(),
+        turn_count: notes.turn_count,
+        participants: notes.participants,
+    })
+}
+
+/// Save a finished session as diarization ground truth, the JSON format of the
+/// validation framework, to be corrected by hand and added to an evaluation corpus
+#[tauri::command]
+pub async fn export_session_as_ground_truth(
+    session_id: String,
+    path: String,
+    state: State<'_, AppState>
+) -> Result<GroundTruthData, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    if state.active_sessions.lock().await.contains_key(&session_id) {
+        return Err(CommandError::detailed("session_still_running", format!("Session {} is still running", session_id), vec![
+            "Stop the session before exporting it as ground truth".to_string()
+        ]));
+    }
+    
+    // The saved session has the configuration; a session of this run may only be in memory
+    let saved = match saved_session_store(&state).await {
+        Some(store) => store.get_session(&session_id)
+            .await
+            .map_err(|e| CommandError::Storage(format!("Failed to load saved session: {}", e)))?,
+        None => None,
+    };
+    let completed = state.completed_sessions.lock().await.get(&session_id).cloned();
+    let (segments, duration, config) = match (completed, saved) {
+        (Some(record), saved) => (record.segments, record.total_duration, saved.map(|s| s.config)),
+        (None, Some(saved)) => (saved.segments, saved.summary.total_duration as f32, Some(saved.config)),
+        (None, None) => return Err(CommandError::NotFound(format!("Session {} not found", session_id))),
+    };
+    
+    let config = config.unwrap_or_default();
+    let mut recording_metadata = HashMap::from([
+        ("source".to_string(), "kaginote_session".to_string()),
+        ("session_id".to_string(), session_id.clone()),
+        ("duration".to_string(), format!("{:.3}", duration)),
+    ]);
+    // Tier of the first transcribed segment, which the adaptive tier may have lowered since
+    let model_tier = segments.iter()
+        .find_map(|segment| segment["modelTier"].as_str())
+        .or_else(|| config["qualityTier"].as_str());
+    for (key, value) in [
+        ("model_tier", model_tier.map(str::to_string)),
+        ("device", config["preferredDevice"].as_str().map(str::to_string)),
+        ("audio_sources", config.get("audioSources").filter(|v| !v.is_null()).map(|v| v.to_string())),
+    ] {
+        if let Some(value) = value {
+            recording_metadata.insert(key.to_string(), value);
+        }
+    }
+    
+    let ground_truth = GroundTruthData::from_session_segments(
+        &session_id,
+        &segments,
+        duration,
+        DeviceCapabilities::TARGET_SAMPLE_RATE,
+        recording_metadata,
+    );
+    ground_truth::save_ground_truth(&ground_truth, &path)
+        .map_err(|e| CommandError::Storage(format!("Failed to write ground truth: {}", e)))?;
+    
+    tracing::info!("Exported session {} as ground truth to {} ({} segments)", session_id, path, ground_truth.segments.len());
+    Ok(ground_truth)
+}
+
+/// Read a ground truth file for display. Schema problems are rejected with
+/// their segment locations in `details.issues`.
+#[tauri::command]
+pub async fn import_ground_truth(path: String) -> Result<GroundTruthData, CommandError> {
+    let ground_truth = ground_truth::load_ground_truth(&path).map_err(|e| match e {
+        QualityAnalysisError::GroundTruthNotFound { path } => CommandError::NotFound(format!("Ground truth file not found: {}", path)),
+        other => CommandError::detailed("invalid_ground_truth", other.to_string(), vec![
+            "Check that the file is ground truth JSON exported by KagiNote".to_string()
+        ]),
+    })?;
+    
+    let issues = ground_truth.validate();
+    if !issues.is_empty() {
+        return Err(CommandError::detailed(
+            "invalid_ground_truth",
+            format!("{} has {} invalid segment(s)", path, issues.len()),
+            vec!["Fix the listed segments and import the file again".to_string()],
+        ).with_details(serde_json::json!({ "issues": issues })));
+    }
+    Ok(ground_truth)
+}
+
+/// Audio of a running session between `start_s` and `end_s` on the stream
+/// timeline, from its replay buffer. Only the retained part of the range is
+/// returned; None when the session is not running or none of it is retained.
+pub(crate) async fn get_audio_range(state: &AppState, session_id: &str, start_s: f64, end_s: f64) -> Option<AudioData> {
+    let buffer = state.active_sessions.lock().await.get(session_id)?.audio_buffer.clone();
+    let buffer = buffer.lock();
+    let samples = buffer.range(start_s, end_s);
+    if samples.is_empty() {
+        return None;
+    }
+    Some(AudioData {
+        duration_seconds: samples.len() as f32 / buffer.sample_rate() as f32,
+        samples,
+        sample_rate: buffer.sample_rate(),
+        channels: 1,
+        timestamp: std::time::SystemTime::now(),
+        source_channel: AudioSource::Microphone,
+    })
+}
+
+/// Transcribe one segment again from the session audio with `tier`, e.g. a
+/// segment marked `needsReview`. The new text replaces the old, which
+/// is kept as `previousText`, and is emitted as a `retranscribed` update.
+#[tauri::command]
+pub async fn retranscribe_segment(
+    session_id: String,
+    segment_index: usize,
+    tier: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<serde_json::Value, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    let model_tier = crate::asr::types::ModelTier::from_quality_tier(&tier)
+        .ok_or_else(|| CommandError::InvalidInput(format!("Unknown model tier '{}'", tier)))?;
+    
+    let live = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id).map(|session_state| (
+            session_state.transcription_segments.get(segment_index).cloned(),
+            session_state.recording_path.clone(),
+            session_state.config.review_confidence_threshold,
+        ))
+    };
+    let (segment, recording_path, threshold) = match live {
+        Some(live) => live,
+        None => {
+            let completed_guard = state.completed_sessions.lock().await;
+            let record = completed_guard.get(&session_id)
+                .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?;
+            (record.segments.get(segment_index).cloned(), record.audio_path.clone(), review::DEFAULT_REVIEW_THRESHOLD)
+        }
+    };
+    let mut segment = segment
+        .ok_or_else(|| CommandError::NotFound(format!("Session {} has no segment {}", session_id, segment_index)))?;
+    
+    // The recording has the whole session; without one a live session's replay buffer may still hold the segment
+    let start = segment["startTime"].as_f64().unwrap_or(0.0) as f32;
+    let end = segment["endTime"].as_f64().unwrap_or(0.0) as f32;
+    let audio = match recording_path {
+        Some(recording_path) => tokio::task::spawn_blocking(move || review::read_recording_window(Path::new(&recording_path), start, end))
+            .await
+            .map_err(|e| format!("Failed to read segment audio: {}", e))?
+            .map_err(|e| CommandError::Failed(format!("Failed to read segment audio: {}", e)))?,
+        None => get_audio_range(&state, &session_id, start as f64, end as f64).await
+            .ok_or_else(|| CommandError::detailed(
+                "segment_audio_unavailable",
+                format!("Session {} kept no audio of segment {}", session_id, segment_index),
+                vec!["Enable saveAudioRecording to retranscribe segments of finished sessions".to_string()],
+            ))?,
+    };
+    let audio = ResamplerUtils::to_whisper_format(&audio)
+        .map_err(|e| CommandError::Failed(format!("Failed to resample segment audio: {}", e)))?;
+    
+    // A translated segment is translated again
+    let mut whisper_config = WhisperConfig { model_tier, ..Default::default() };
+    if segment.get("originalLanguage").is_some() {
+        translation::configure(&mut whisper_config, translation::MODE_TRANSLATE);
+    }
+    let engine = lease_engine(&state, whisper_config).await?;
+    let needs_review = review::retranscribe(&mut segment, audio, model_tier, threshold, |audio| async move {
+        engine.transcribe(&audio, &TranscriptionContext::default()).await
+    }).await.map_err(|e| CommandError::Failed(format!("Failed to retranscribe segment: {}", e)))?;
+    
+    // Write back unless the segment list changed meanwhile
+    let mut sessions_guard = state.active_sessions.lock().await;
+    if let Some(stored) = sessions_guard.get_mut(&session_id).and_then(|s| s.transcription_segments.get_mut(segment_index)) {
+        *stored = segment.clone();
+    } else {
+        drop(sessions_guard);
+        if let Some(stored) = state.completed_sessions.lock().await.get_mut(&session_id).and_then(|r| r.segments.get_mut(segment_index)) {
+            *stored = segment.clone();
+        }
+    }
+    if let Some(store) = saved_session_store(&state).await {
+        if let Err(e) = store.append_segments(&session_id, segment_index, vec![segment.clone()]).await {
+            tracing::warn!("Failed to save retranscribed segment {} of session {}: {}", segment_index, session_id, e);
+        }
+    }
+    
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "transcription-update", serde_json::json!({
+        "sessionId": session_id,
+        "updateType": "retranscribed",
+        "segmentIndex": segment_index,
+        "segment": segment,
+        "timestamp": timestamp
+    })) {
+        tracing::warn!("Failed to emit retranscribed update: {}", emit_err);
+    }
+    
+    tracing::info!("Retranscribed segment {} of session {} with {} (needs review: {})", segment_index, session_id, tier, needs_review);
+    Ok(segment)
+}
+
+/// Audio of one segment of a live or finished session, cut from the session
+/// recording by the segment's startSample/endSample. Returned as samples, or
+/// written to a temporary WAV whose path is returned when `as_file` is set.
+#[tauri::command]
+pub async fn get_segment_audio(
+    session_id: String,
+    segment_index: usize,
+    as_file: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<SegmentAudio, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    
+    let live = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id).map(|session_state| (
+            session_state.transcription_segments.get(segment_index).cloned(),
+            session_state.recording_path.clone(),
+        ))
+    };
+    let (segment, recording_path) = match live {
+        Some(live) => live,
+        None => {
+            let completed_guard = state.completed_sessions.lock().await;
+            let record = completed_guard.get(&session_id)
+                .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?;
+            (record.segments.get(segment_index).cloned(), record.audio_path.clone())
+        }
+    };
+    let segment = segment
+        .ok_or_else(|| CommandError::NotFound(format!("Session {} has no segment {}", session_id, segment_index)))?;
+    
+    // Segments from before the recording started have no offsets
+    let (range, recording_path) = match (segment_audio::segment_range(&segment), recording_path) {
+        (Some(range), Some(recording_path)) => (range, recording_path),
+        _ => return Err(CommandError::detailed(
+            "segment_audio_unavailable",
+            format!("Segment {} of session {} is not in a session recording", segment_index, session_id),
+            vec!["Enable saveAudioRecording before starting a session to play back its segments".to_string()],
+        )),
+    };
+    let temp_path = as_file.unwrap_or(false).then(|| {
+        std::env::temp_dir().join("KagiNote").join("segments").join(format!("{}_{}.wav", session_id, segment_index))
+    });
+    
+    let audio = tokio::task::spawn_blocking(move || -> std::io::Result<SegmentAudio> {
+        let mut audio = segment_audio::read_samples(Path::new(&recording_path), range.0, range.1)?;
+        if let Some(temp_path) = temp_path {
+            segment_audio::write_to_file(&mut audio, &temp_path)?;
+        }
+        Ok(audio)
+    }).await
+        .map_err(|e| format!("Failed to read segment audio: {}", e))?
+        .map_err(|e| CommandError::Failed(format!("Failed to read segment audio: {}", e)))?;
+    if audio.clamped {
+        tracing::debug!("Segment {} of session {} reaches past its recording; clamped to sample {}", segment_index, session_id, audio.end_sample);
+    }
+    Ok(audio)
+}
+
+/// Save the last `lookback_seconds` of a live or finished session as a highlight:
+/// an audio clip (when session audio is retained) with its transcript snippet.
+#[tauri::command]
+pub async fn capture_highlight(
+    session_id: String,
+    lookback_seconds: f32,
+    label: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<Highlight, CommandError> {
+    instance_lock::assert_held("Highlights").map_err(|e| e.to_string())?;
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    let label = metadata::sanitize_text("label", &label, metadata::LABEL_RULE).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    if !lookback_seconds.is_finite() || lookback_seconds <= 0.0 {
+        return Err(CommandError::InvalidInput("Lookback must be a positive number of seconds".to_string()));
+    }
+    
+    // Live sessions end now; finished sessions at their recorded duration
+    let live = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id).map(|session_state| {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            (
+                now.saturating_sub(session_state.start_time) as f32,
+                session_state.transcription_segments.clone(),
+                session_state.recording_path.clone(),
+            )
+        })
+    };
+    let (elapsed, segments, recording_path) = match live {
+        Some(live) => live,
+        None => {
+            let completed_guard = state.completed_sessions.lock().await;
+            let record = completed_guard.get(&session_id)
+                .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?;
+            (record.total_duration, record.segments.clone(), record.audio_path.clone())
+        }
+    };
+    let highlights_dir = session_highlights_dir(recording_path.as_deref(), &session_id)
+        .ok_or("Failed to get app data directory")?;
+    
+    let created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let capture_session_id = session_id.clone();
+    let highlight = tokio::task::spawn_blocking(move || {
+        highlights::capture_highlight(&HighlightRequest {
+            session_id: &capture_session_id,
+            label: &label,
+            lookback_secs: lookback_seconds,
+            session_elapsed: elapsed,
+            segments: &segments,
+            recording_path: recording_path.as_deref().map(Path::new),
+            highlights_dir: &highlights_dir,
+            created_at,
+        })
+    }).await
+        .map_err(|e| format!("Failed to capture highlight: {}", e))?
+        .map_err(|e| format!("Failed to capture highlight: {}", e))?;
+    
+    if let Some(ref warning) = highlight.warning {
+        tracing::warn!("Highlight {} of session {}: {}", highlight.id, session_id, warning);
+    }
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "highlight-captured", serde_json::json!({
+        "sessionId": session_id,
+        "highlight": highlight,
+        "timestamp": created_at
+    })) {
+        tracing::warn!("Failed to emit highlight-captured event: {}", emit_err);
+    }
+    Ok(highlight)
+}
+
+/// Highlights saved for a session, oldest first
+#[tauri::command]
+pub async fn get_session_highlights(
+    session_id: String,
+    state: State<'_, AppState>
+) -> Result<Vec<Highlight>, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    let live_recording = state.active_sessions.lock().await
+        .get(&session_id)
+        .map(|s| s.recording_path.clone());
+    let recording_path = match live_recording {
+        Some(path) => path,
+        None => state.completed_sessions.lock().await
+            .get(&session_id)
+            .and_then(|record| record.audio_path.clone()),
+    };
+    let dir = session_highlights_dir(recording_path.as_deref(), &session_id)
+        .ok_or("Failed to get app data directory")?;
+    
+    tokio::task::spawn_blocking(move || highlights::list_highlights(&dir)).await
+        .map_err(|e| format!("Failed to read highlights: {}", e))?
+        .map_err(|e| CommandError::Storage(format!("Failed to read highlights: {}", e)))
+}
+
+/// Speaking time, segment count and confidence per speaker, from the live
+/// session or a finished one
+#[tauri::command]
+pub async fn get_session_speaker_stats(
+    session_id: String,
+    state: State<'_, AppState>
+) -> Result<speaker_stats::SpeakerStats, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    if let Some(session_state) = state.active_sessions.lock().await.get(&session_id) {
+        return Ok(speaker_stats::compute_speaker_stats(&session_state.transcription_segments));
+    }
+    let record = finished_session_record(&state, &session_id).await?;
+    Ok(speaker_stats::compute_speaker_stats(&record.segments))
+}
+
+/// Exclude a speaker's future segments from the transcript stream and default
+/// exports. Their segments are still processed and stored, flagged as muted.
+#[tauri::command]
+pub async fn mute_speaker(
+    session_id: String,
+    speaker_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<Vec<String>, CommandError> {
+    Ok(set_speaker_muted(&app_handle, &state, &session_id, &speaker_id, true).await?)
+}
+
+/// Include a previously muted speaker again
+#[tauri::command]
+pub async fn unmute_speaker(
+    session_id: String,
+    speaker_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<Vec<String>, CommandError> {
+    Ok(set_speaker_muted(&app_handle, &state, &session_id, &speaker_id, false).await?)
+}
+
+/// Update the mute list of a live or finished session and re-flag its stored
+/// segments. Returns the muted speakers of the session.
+async fn set_speaker_muted(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    session_id: &str,
+    speaker_id: &str,
+    muted: bool,
+) -> Result<Vec<String>, String> {
+    let apply = |mutes: &mut SpeakerMutes, segments: &mut [serde_json::Value]| {
+        let changed = if muted { mutes.mute(speaker_id) } else { mutes.unmute(speaker_id) };
+        mutes.flag_segments(segments);
+        (changed, mutes.muted_speakers())
+    };
+    
+    let live_result = {
+        let mut sessions_guard = state.active_sessions.lock().await;
+        let active_session_id = {
+            let completed_guard = state.completed_sessions.lock().await;
+            resolve_active_session_id(&sessions_guard, &completed_guard, session_id)
+        };
+        active_session_id
+            .and_then(|id| sessions_guard.get_mut(&id))
+            .map(|session_state| apply(&mut session_state.speaker_mutes, &mut session_state.transcription_segments))
+    };
+    let (changed, muted_speakers) = match live_result {
+        Some(result) => result,
+        None => {
+            let mut completed_guard = state.completed_sessions.lock().await;
+            let record = completed_guard.get_mut(session_id)
+                .ok_or_else(|| format!("Session {} not found", session_id))?;
+            apply(&mut record.speaker_mutes, &mut record.segments)
+        }
+    };
+    
+    if changed {
+        tracing::info!("🔇 Speaker {} {} in session {}", speaker_id, if muted { "muted" } else { "unmuted" }, session_id);
+        if let Err(emit_err) = emit_session_event(app_handle, session_id, "speaker-mute-changed", serde_json::json!({
+            "sessionId": session_id,
+            "speakerId": speaker_id,
+            "muted": muted,
+            "mutedSpeakers": muted_speakers,
+            "timestamp": std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64
+        })) {
+            tracing::warn!("Failed to emit speaker-mute-changed event: {}", emit_err);
+        }
+    }
+    Ok(muted_speakers)
+}
+
+/// Show a session's full transcript stream in a detached window. The window is
+/// resynced with the segments so far, follows the session across rollovers and
+/// is deregistered when it is destroyed.
+#[tauri::command]
+pub async fn register_transcript_window(
+    window_label: String,
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<(), CommandError> {
+    let window = app_handle.get_webview_window(&window_label)
+        .ok_or_else(|| CommandError::NotFound(format!("Window '{}' not found", window_label)))?;
+    
+    // A live session is routed to its current part; the window is registered
+    // while the segments are locked, so none can land between route and backlog
+    let live = {
+        let sessions_guard = state.active_sessions.lock().await;
+        let completed_guard = state.completed_sessions.lock().await;
+        match resolve_active_session_id(&sessions_guard, &completed_guard, &session_id).and_then(|id| sessions_guard.get(&id)) {
+            Some(session_state) => {
+                let newly_registered = state.transcript_windows.lock()
+                    .map_err(|e| format!("Failed to register transcript window: {}", e))?
+                    .register(&window_label, &session_state.session_id);
+                
+                // Earlier parts of a rolled over session come first
+                let mut parts = vec![session_state.transcription_segments.clone()];
+                let mut earlier_part = session_state.continuation_of.clone();
+                while let Some(record) = earlier_part.and_then(|part_id| completed_guard.get(&part_id)) {
+                    parts.push(record.segments.clone());
+                    earlier_part = record.continuation_of.clone();
+                }
+                Some((newly_registered, parts.into_iter().rev().flatten().collect::<Vec<_>>()))
+            }
+            None => None,
+        }
+    };
+    let (newly_registered, backlog) = match live {
+        Some(live) => live,
+        None => {
+            let record = finished_session_record(&state, &session_id).await?;
+            let newly_registered = state.transcript_windows.lock()
+                .map_err(|e| format!("Failed to register transcript window: {}", e))?
+                .register(&window_label, &session_id);
+            (newly_registered, record.segments)
+        }
+    };
+    
+    state.transcript_windows.lock()
+        .map_err(|e| format!("Failed to register transcript window: {}", e))?
+        .send_backlog(&app_handle, &window_label, &backlog)
+        .map_err(|e| format!("Failed to send transcript backlog: {}", e))?;
+    
+    // One destroyed handler per window, however often it is re-registered
+    if newly_registered {
+        let router = state.transcript_windows.clone();
+        let label = window_label.clone();
+        window.on_window_event(move |event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                if let Ok(mut router) = router.lock() {
+                    router.deregister(&label);
+                }
+            }
+        });
+    }
+    
+    Ok(())
+}
+
+/// Check a suggested speaker name against stored profiles and send it to the frontend.
+/// Nothing is renamed here; the user confirms through update_speaker_in_session.
+async fn emit_name_suggestion(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    session_id: &str,
+    mut suggestion: NameSuggestion,
+) {
+    let profiles = {
+        let store_guard = state.speaker_store.lock().await;
+        match store_guard.as_ref() {
+            Some(store) => store.list_speaker_profiles(true).await.unwrap_or_else(|e| {
+                tracing::warn!("Failed to list speaker profiles for name suggestion: {}", e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        }
+    };
+    let profile_ids: Vec<String> = profiles.iter().map(|p| p.id.to_string()).collect();
+    suggestion.profile_match = speaker_naming::match_profile(
+        &suggestion.suggested_name,
+        profile_ids.iter().zip(&profiles).map(|(id, p)| (id.as_str(), p.name.as_str())),
+    );
+    
+    tracing::info!("🏷️ Suggesting name '{}' for {} in session {} (confidence {:.2})",
+                   suggestion.suggested_name, suggestion.speaker_id, session_id, suggestion.confidence);
+    let mut payload = serde_json::json!(suggestion);
+    payload["sessionId"] = serde_json::json!(session_id);
+    payload["timestamp"] = serde_json::json!(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis());
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "speaker-name-suggestion", payload) {
+        tracing::warn!("Failed to emit speaker-name-suggestion event: {}", emit_err);
+    }
+}
+
+/// Attach the waveform envelope of a live segment and send it as a follow-up
+/// event. `buffer` is the audio the segment was decoded from, starting at
+/// `buffer_start` on the session clock; only the segment's span of it counts.
+async fn attach_segment_peaks(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    segment_index: usize,
+    buffer: &AudioData,
+    buffer_start: f64,
+) {
+    let state = app_handle.state::<AppState>();
+    let Some(segment) = state.active_sessions.lock().await
+        .get(session_id)
+        .and_then(|s| s.transcription_segments.get(segment_index).cloned())
+    else {
+        return;
+    };
+    let peaks = peaks::peaks_to_json(&peaks::segment_peaks(&segment, &buffer.samples, buffer.sample_rate, buffer_start));
+    if let Some(segment) = state.active_sessions.lock().await
+        .get_mut(session_id)
+        .and_then(|s| s.transcription_segments.get_mut(segment_index))
+    {
+        segment["peaks"] = peaks.clone();
+    }
+    
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "segment-peaks", serde_json::json!({
+        "sessionId": session_id,
+        "segmentIndex": segment_index,
+        "peaks": peaks,
+    })) {
+        tracing::warn!("Failed to emit segment-peaks event: {}", emit_err);
+    }
+}
+
+/// Texts of the last `count` segments, offered to the decoder as context when
+/// the session has rollingPrompt set; otherwise no context
+async fn recent_transcript_context(state: &AppState, session_id: &str, count: usize) -> TranscriptionContext {
+    let sessions_guard = state.active_sessions.lock().await;
+    let previous_segments = sessions_guard.get(session_id)
+        .filter(|s| s.config.rolling_prompt)
+        .map(|s| {
+            let recent = s.transcription_segments.len().saturating_sub(count);
+            s.transcription_segments[recent..].iter()
+                .filter_map(|segment| segment["text"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    TranscriptionContext { previous_segments, ..TranscriptionContext::default() }
+}
+
+/// Decode the utterance still being buffered and send it as a provisional
+/// segment. Partials are never stored; the final segment replaces them.
+#[allow(clippy::too_many_arguments)]
+async fn run_partial_pass(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    session_id: &str,
+    partial_engine: Option<&WhisperEngine>,
+    num_threads: usize,
+    audio: &AudioData,
+    context: &TranscriptionContext,
+    span: (f64, f64),
+    scheduler: &mut PartialScheduler,
+) {
+    // Partials carry no speaker yet, so they could show a muted speaker's words
+    let has_muted_speakers = state.active_sessions.lock().await
+        .get(session_id)
+        .is_some_and(|s| !s.speaker_mutes.muted_speakers().is_empty());
+    if has_muted_speakers {
+        scheduler.record(std::time::Instant::now(), std::time::Duration::ZERO);
+        return;
+    }
+    
+    let session_engine = match partial_engine {
+        Some(_) => None,
+        None => session_engine(state, session_id).await,
+    };
+    let started = std::time::Instant::now();
+    let engine = partial_engine.or(session_engine.as_deref());
+    let task = engine.map_or(crate::asr::types::Task::Transcribe, |engine| engine.get_config().task);
+    let result = match engine {
+        Some(engine) => Some(engine.transcribe_partial(audio, context, num_threads).await),
+        None => None,
+    };
+    let revision = scheduler.record(std::time::Instant::now(), started.elapsed());
+    let result = match result {
+        Some(Ok(result)) => result,
+        Some(Err(e)) => {
+            tracing::debug!("Partial transcription failed: {}", e);
+            return;
+        }
+        None => return,
+    };
+    
+    let text = result.text.trim();
+    if text.is_empty() || text.contains("[BLANK_AUDIO]") {
+        return;
+    }
+    let segment_id = scheduler.segment_id(|| Uuid::new_v4().to_string()).to_string();
+    let mut segment = serde_json::json!({
+        "id": segment_id,
+        "text": text,
+        "startTime": span.0,
+        "endTime": span.1,
+        "confidence": result.confidence,
+        "language": Language::parse(&result.language).map(|l| l.code()).unwrap_or("und"),
+        "revision": revision
+    });
+    translation::label_segment(&mut segment, task);
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "transcription-update", serde_json::json!({
+        "sessionId": session_id,
+        "segment": segment,
+        "updateType": "partial",
+        "processingPass": 1
+    })) {
+        tracing::warn!("Failed to emit partial transcription-update event: {}", emit_err);
+    }
+}
+
+/// Tell the frontend a shown partial has no final version (silence, duplicate or noise)
+fn retract_partial(app_handle: &tauri::AppHandle, session_id: &str, segment_id: Option<String>) {
+    let Some(segment_id) = segment_id else {
+        return;
+    };
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "transcription-update", serde_json::json!({
+        "sessionId": session_id,
+        "segmentId": segment_id,
+        "updateType": "partial_discarded"
+    })) {
+        tracing::warn!("Failed to emit partial_discarded event: {}", emit_err);
+    }
+}
+
+/// Waveform envelope of a segment. Live segments carry theirs; for a finished
+/// or saved session it is computed from the segment's range of the session
+/// recording on first request and saved with the segment.
+#[tauri::command]
+pub async fn get_segment_peaks(
+    session_id: String,
+    segment_index: usize,
+    state: State<'_, AppState>
+) -> Result<serde_json::Value, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    if let Some(session_state) = state.active_sessions.lock().await.get(&session_id) {
+        return session_state.transcription_segments.get(segment_index)
+            .and_then(|segment| segment.get("peaks").cloned())
+            .ok_or_else(|| CommandError::NotFound(format!("No peaks yet for segment {} of session {}", segment_index, session_id)));
+    }
+    
+    let record = finished_session_record(&state, &session_id).await?;
+    let mut segment = record.segments.get(segment_index).cloned()
+        .ok_or_else(|| CommandError::NotFound(format!("Session {} has no segment {}", session_id, segment_index)))?;
+    if let Some(peaks) = segment.get("peaks") {
+        return Ok(peaks.clone());
+    }
+    
+    // Segments from before the recording started have no offsets
+    let (range, recording_path) = match (segment_audio::segment_range(&segment), record.audio_path) {
+        (Some(range), Some(recording_path)) => (range, recording_path),
+        _ => return Err(CommandError::detailed(
+            "segment_audio_unavailable",
+            format!("Segment {} of session {} is not in a session recording", segment_index, session_id),
+            vec!["Enable saveAudioRecording before starting a session to see its waveforms".to_string()],
+        )),
+    };
+    let audio = tokio::task::spawn_blocking(move || segment_audio::read_samples(Path::new(&recording_path), range.0, range.1))
+        .await
+        .map_err(|e| format!("Failed to read segment audio: {}", e))?
+        .map_err(|e| CommandError::Failed(format!("Failed to read segment audio: {}", e)))?;
+    let peaks = peaks::peaks_to_json(&peaks::compute_peaks(&audio.samples, peaks::DEFAULT_PEAK_BUCKETS));
+    
+    // Saved with the segment, so the recording is read once per segment
+    segment["peaks"] = peaks.clone();
+    if let Some(stored) = state.completed_sessions.lock().await
+        .get_mut(&session_id)
+        .and_then(|record| record.segments.get_mut(segment_index))
+    {
+        stored["peaks"] = peaks.clone();
+    }
+    if let Some(store) = saved_session_store(&state).await {
+        if let Err(e) = store.append_segments(&session_id, segment_index, vec![segment]).await {
+            tracing::warn!("Failed to save peaks of segment {} of session {}: {}", segment_index, session_id, e);
+        }
+    }
+    Ok(peaks)
+}
+
+/// Backups older than this trigger the daily backup
+const DAILY_BACKUP_INTERVAL_SECS: i64 = 24 * 3600;
+
+/// Persisted backup policy, itself part of every backup set
+fn backup_policy_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("KagiNote").join("backup_policy.json"))
+}
+
+fn load_backup_policy() -> Option<BackupPolicy> {
+    let bytes = std::fs::read(backup_policy_path()?).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Configure the backup directory and rotation; `None` disables backups
+#[tauri::command]
+pub async fn configure_backups(
+    directory: Option<String>,
+    keep_sets: Option<usize>,
+) -> Result<Option<BackupPolicy>, CommandError> {
+    let path = backup_policy_path().ok_or("Failed to get app data directory")?;
+    let Some(directory) = directory else {
+        if path.exists() {
+            fs::remove_file(&path).await
+                .map_err(|e| format!("Failed to disable backups: {}", e))?;
+        }
+        return Ok(None);
+    };
+    
+    let policy = BackupPolicy {
+        directory: PathBuf::from(directory),
+        keep_sets: keep_sets.unwrap_or(7).max(1),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await
+            .map_err(|e| format!("Failed to save backup policy: {}", e))?;
+    }
+    let json = serde_json::to_vec_pretty(&policy)
+        .map_err(|e| format!("Failed to save backup policy: {}", e))?;
+    fs::write(&path, json).await
+        .map_err(|e| format!("Failed to save backup policy: {}", e))?;
+    Ok(Some(policy))
+}
+
+/// Persisted workspace quota; bounds per-session logs in the data directory
+fn workspace_quota_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("KagiNote").join("workspace_quota.json"))
+}
+
+fn load_workspace_quota() -> Option<WorkspaceQuota> {
+    let bytes = std::fs::read(workspace_quota_path()?).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Set how much disk space the app's data may use; `None` removes the quota
+#[tauri::command]
+pub async fn configure_workspace_quota(
+    max_bytes: Option<u64>,
+) -> Result<Option<WorkspaceQuota>, CommandError> {
+    let path = workspace_quota_path().ok_or("Failed to get app data directory")?;
+    let Some(max_bytes) = max_bytes else {
+        if path.exists() {
+            fs::remove_file(&path).await
+                .map_err(|e| format!("Failed to remove workspace quota: {}", e))?;
+        }
+        return Ok(None);
+    };
+    if max_bytes == 0 {
+        return Err(CommandError::InvalidInput("Workspace quota must be greater than zero".to_string()));
+    }
+    
+    let quota = WorkspaceQuota { max_bytes };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await
+            .map_err(|e| format!("Failed to save workspace quota: {}", e))?;
+    }
+    let json = serde_json::to_vec_pretty(&quota)
+        .map_err(|e| format!("Failed to save workspace quota: {}", e))?;
+    fs::write(&path, json).await
+        .map_err(|e| format!("Failed to save workspace quota: {}", e))?;
+    Ok(Some(quota))
+}
+
+/// Last backup outcome for diagnostics
+#[tauri::command]
+pub async fn get_backup_status(state: State<'_, AppState>) -> Result<BackupStatus, CommandError> {
+    state.backup_status.lock()
+        .map(|status| status.clone())
+        .map_err(|e| CommandError::Failed(format!("Failed to read backup status: {}", e)))
+}
+
+/// Create a backup set now, if backups are configured
+#[tauri::command]
+pub async fn run_backup_now(app_handle: tauri::AppHandle) -> Result<BackupStatus, CommandError> {
+    if load_backup_policy().is_none() {
+        return Err(CommandError::detailed("backup_not_configured", "Backups are not configured", vec![
+            "Choose a backup directory with configure_backups".to_string()
+        ]));
+    }
+    run_backup(&app_handle).await;
+    let state = app_handle.state::<AppState>();
+    let status = state.backup_status.lock()
+        .map(|status| status.clone())
+        .map_err(|e| format!("Failed to read backup status: {}", e))?;
+    match &status.last_error {
+        Some(e) => Err(CommandError::Storage(format!("Backup failed: {}", e))),
+        None => Ok(status),
+    }
+}
+
+/// Restore a backup set into the live data directory. Every live file the
+/// restore replaces, the database included, is first copied into a
+/// `restore-safety-<time>` directory beside it. The database is restored
+/// through its open connection; saved sessions the restored database lacks are
+/// saved again from the set's session records.
+#[tauri::command]
+pub async fn restore_from_backup(path: String, state: State<'_, AppState>) -> Result<serde_json::Value, CommandError> {
+    instance_lock::assert_held("Restore").map_err(|e| e.to_string())?;
+    if !state.active_sessions.lock().await.is_empty() {
+        return Err(CommandError::detailed("session_still_running", "A session is running", vec![
+            "Stop every session before restoring a backup".to_string()
+        ]));
+    }
+    let set_path = PathBuf::from(&path);
+    let data_dir = dirs::data_local_dir()
+        .ok_or("Failed to get app data directory")?
+        .join("KagiNote");
+    let safety_dir = data_dir.join(format!("restore-safety-{}", chrono::Utc::now().format("%Y%m%dT%H%M%SZ")));
+    
+    let intact_path = set_path.clone();
+    tokio::task::spawn_blocking(move || backup::ensure_intact(&intact_path)).await
+        .map_err(|e| format!("Failed to restore backup: {}", e))?
+        .map_err(|e| format!("Failed to restore backup: {}", e))?;
+    
+    // The open database is saved and restored through its connection
+    let database = state.speaker_database.lock().await.clone();
+    let set_database = set_path.join("speakers.db");
+    let restores_database = database.is_some() && set_database.exists();
+    if let (Some(database), true) = (&database, restores_database) {
+        fs::create_dir_all(&safety_dir).await
+            .map_err(|e| format!("Failed to create safety directory: {}", e))?;
+        database.snapshot_to(safety_dir.join("speakers.db")).await
+            .map_err(|e| format!("Failed to save a copy of the speaker database: {}", e))?;
+    }
+    let (file_set, file_data_dir, file_safety_dir) = (set_path.clone(), data_dir.clone(), safety_dir.join("files"));
+    let restore = tokio::task::spawn_blocking(move || {
+        backup::restore_backup_set(&file_set, &file_data_dir, &file_safety_dir, |name| {
+            (restores_database && name == "speakers.db") || name.starts_with("sessions/")
+        })
+    }).await
+        .map_err(|e| format!("Failed to restore backup: {}", e))?
+        .map_err(|e| format!("Failed to restore backup: {}", e))?;
+    if let (Some(database), true) = (&database, restores_database) {
+        database.restore_from(&set_database).await
+            .map_err(|e| CommandError::Storage(format!("Failed to restore speaker database: {}", e)))?;
+    }
+    
+    // Records of this run would shadow the restored sessions
+    state.completed_sessions.lock().await.clear();
+    let mut resaved_sessions = 0;
+    if let (Some(store), Ok(mut entries)) = (saved_session_store(&state).await, fs::read_dir(set_path.join("sessions")).await) {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Some(record) = fs::read(entry.path()).await.ok()
+                .and_then(|bytes| serde_json::from_slice::<SessionRecord>(&bytes).ok())
+            else {
+                continue;
+            };
+            if matches!(store.get_session(&record.session_id).await, Ok(Some(_))) {
+                continue;
+            }
+            if let Err(e) = store.create_session(&record.session_id, record.start_time as i64, record.continuation_of.clone(), serde_json::Value::Null).await {
+                tracing::warn!("Failed to restore session {}: {}", record.session_id, e);
+                continue;
+            }
+            persist_session_end(&state, &record, record.start_time + record.total_duration as u64, session_store::SESSION_STATUS_COMPLETED).await;
+            resaved_sessions += 1;
+        }
+    }
+    if restores_database {
+        if let Err(e) = rebuild_embedding_index(state).await {
+            tracing::warn!("Failed to rebuild embedding index after restore: {}", e);
+        }
+    }
+    
+    tracing::info!("💾 Restored backup set {:?} ({} files, database: {}, {} sessions saved again); live copies in {:?}",
+                   set_path, restore.restored.len(), restores_database, resaved_sessions, safety_dir);
+    Ok(serde_json::json!({
+        "restoredFrom": set_path,
+        "safetyDir": safety_dir,
+        "restoredFiles": restore.restored,
+        "restoredDatabase": restores_database,
+        "resavedSessions": resaved_sessions,
+        "verification": restore.verification,
+    }))
+}
+
+/// Back up in the background after a session was saved
+fn spawn_backup_if_configured(app_handle: &tauri::AppHandle) {
+    if load_backup_policy().is_none() {
+        return;
+    }
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        run_backup(&app_handle).await;
+    });
+}
+
+/// Periodically create a backup when the last one is more than a day old
+pub async fn run_daily_backups(app_handle: tauri::AppHandle) {
+    loop {
+        if load_backup_policy().is_some() {
+            let last_backup = {
+                let state = app_handle.state::<AppState>();
+                let status = state.backup_status.lock().map(|s| s.clone()).unwrap_or_default();
+                status.last_backup.map(|b| b.created_at).or(status.last_attempt_at)
+            };
+            let now = chrono::Utc::now().timestamp();
+            if last_backup.map(|t| now - t >= DAILY_BACKUP_INTERVAL_SECS).unwrap_or(true) {
+                run_backup(&app_handle).await;
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+    }
+}
+
+/// Create, verify and rotate a backup set, then report the outcome
+async fn run_backup(app_handle: &tauri::AppHandle) {
+    let Some(policy) = load_backup_policy() else {
+        return;
+    };
+    let state = app_handle.state::<AppState>();
+    let _guard = state.backup_lock.lock().await;
+    let started_at = chrono::Utc::now().timestamp();
+    
+    let result = create_backup(&state, &policy, started_at).await;
+    let status = {
+        let mut status = state.backup_status.lock().unwrap_or_else(|e| e.into_inner());
+        status.last_attempt_at = Some(started_at);
+        match &result {
+            Ok(info) => {
+                status.last_backup = Some(info.clone());
+                status.last_error = None;
+            }
+            Err(e) => {
+                tracing::warn!("Backup failed: {}", e);
+                status.last_error = Some(e.clone());
+            }
+        }
+        status.clone()
+    };
+    
+    if let Err(emit_err) = app_handle.emit("backup-status", serde_json::json!({
+        "success": result.is_ok(),
+        "status": status,
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    })) {
+        tracing::warn!("Failed to emit backup-status event: {}", emit_err);
+    }
+}
+
+async fn create_backup(
+    state: &AppState,
+    policy: &BackupPolicy,
+    created_at: i64,
+) -> Result<backup::BackupSetInfo, String> {
+    instance_lock::assert_held("Backup").map_err(|e| e.to_string())?;
+    let mut sources = Vec::new();
+    
+    // Snapshot the live database so WAL contents are included consistently
+    let snapshot_path = dirs::data_local_dir()
+        .ok_or("Failed to get app data directory")?
+        .join("KagiNote")
+        .join("backup_snapshot.db");
+    if let Some(database) = state.speaker_database.lock().await.as_ref() {
+        // VACUUM INTO refuses to overwrite a leftover snapshot
+        let _ = fs::remove_file(&snapshot_path).await;
+        database.snapshot_to(&snapshot_path).await
+            .map_err(|e| format!("Failed to snapshot speaker database: {}", e))?;
+        sources.push(BackupSource::File { name: "speakers.db".to_string(), path: snapshot_path.clone() });
+    }
+    if let Some(path) = backup_policy_path().filter(|p| p.exists()) {
+        sources.push(BackupSource::File { name: "backup_policy.json".to_string(), path });
+    }
+    if let Some(path) = models_location::settings_path().filter(|p| p.exists()) {
+        sources.push(BackupSource::File { name: "models_location.json".to_string(), path });
+    }
+    
+    for (session_id, record) in state.completed_sessions.lock().await.iter() {
+        let bytes = serde_json::to_vec(record)
+            .map_err(|e| format!("Failed to serialize session {}: {}", session_id, e))?;
+        sources.push(BackupSource::Data { name: format!("sessions/{}.json", session_id), bytes });
+    }
+    
+    let policy = policy.clone();
+    tokio::task::spawn_blocking(move || {
+        let result = backup::create_backup_set(&policy, &sources, created_at);
+        let _ = std::fs::remove_file(&snapshot_path);
+        let info = result?;
+        let removed = backup::rotate_backup_sets(&policy)?;
+        if !removed.is_empty() {
+            tracing::info!("💾 Rotated out {} old backup sets", removed.len());
+        }
+        Ok::<_, anyhow::Error>(info)
+    }).await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Location of a session's audio recording, inside `audio_dir` when configured
+/// (e.g. a network share) and in the local data directory otherwise
+fn session_audio_path(audio_dir: Option<&str>, session_id: &str) -> Option<PathBuf> {
+    let base = match audio_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::data_local_dir()?.join("KagiNote").join("sessions"),
+    };
+    Some(base.join(session_id).join("audio.wav"))
+}
+
+/// Highlights directory of a session: next to its recording, or in the default session directory
+fn session_highlights_dir(recording_path: Option<&str>, session_id: &str) -> Option<PathBuf> {
+    let default_dir = session_audio_path(None, session_id)?.parent()?.to_path_buf();
+    Some(highlights::highlights_dir(recording_path.map(Path::new), &default_dir))
+}
+
+/// Local directory for recording spill files, always on the local disk
+fn recording_spill_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("KagiNote").join("recording_spill"))
+}
+
+/// Default recording limit (minutes)
+const DEFAULT_MAX_RECORDING_MINUTES: u32 = 8 * 60;
+
+/// Time allowed for flushing a recording to its destination when a session ends
+const RECORDING_FINISH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Start the resilient recording writer for a session whose config sets
+/// sessionAudioDir; its first sample is at `start_seconds` on the session clock
+async fn start_session_recording(
+    state: &AppState,
+    session_id: &str,
+    sample_rate: u32,
+    start_seconds: f64,
+) -> Option<ResilientRecordingWriter> {
+    if let Err(e) = instance_lock::assert_held("Session recording") {
+        tracing::warn!("Session audio will not be recorded: {}", e);
+        return None;
+    }
+    let (audio_dir, max_minutes) = {
+        let sessions_guard = state.active_sessions.lock().await;
+        let config = &sessions_guard.get(session_id)?.config;
+        if config.session_audio_dir.is_none() && !config.save_audio_recording {
+            return None;
+        }
+        (config.session_audio_dir.clone(), config.max_recording_minutes)
+    };
+    let audio_path = session_audio_path(audio_dir.as_deref(), session_id)?;
+    let spill_path = recording_writer::local_spill_path(&recording_spill_dir()?, session_id);
+    
+    let sink_path = audio_path.clone();
+    let factory: SinkFactory = Box::new(move || {
+        Ok(Box::new(WavFileSink::create(&sink_path, sample_rate)?) as Box<dyn recording_writer::SampleSink>)
+    });
+    let max_samples = (max_minutes as u64 * 60 * sample_rate as u64).min(recording_writer::WAV_MAX_SAMPLES);
+    let config = RecordingWriterConfig { sample_rate, max_samples, ..Default::default() };
+    
+    if let Some(session_state) = state.active_sessions.lock().await.get_mut(session_id) {
+        session_state.recording_path = Some(audio_path.to_string_lossy().to_string());
+        session_state.recording_timeline = Some(RecordingTimeline::new(start_seconds, sample_rate));
+    }
+    if let Ok(mut recordings) = state.recordings_in_progress.lock() {
+        recordings.insert(session_id.to_string(), tokio::sync::watch::channel(false).0);
+    }
+    tracing::info!("🎙️ Recording session {} audio to {:?}", session_id, audio_path);
+    Some(ResilientRecordingWriter::new(config, spill_path, factory))
+}
+
+fn emit_recording_health(app_handle: &tauri::AppHandle, session_id: &str, health: RecordingHealth, stopped_by: Option<RecordingStop>) {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "recording-status", serde_json::json!({
+        "sessionId": session_id,
+        "health": health,
+        "timestamp": timestamp
+    })) {
+        tracing::warn!("Failed to emit recording-status event: {}", emit_err);
+    }
+    
+    if health == RecordingHealth::Degraded {
+        tracing::warn!("Recording destination for session {} is unreachable, keeping audio locally", session_id);
+        if let Err(emit_err) = emit_session_event(app_handle, session_id, "recording-degraded", serde_json::json!({
+            "sessionId": session_id,
+            "message": "Recording location is unreachable - audio is being kept locally until it recovers",
+            "recoverable": true,
+            "timestamp": timestamp
+        })) {
+            tracing::warn!("Failed to emit recording-degraded event: {}", emit_err);
+        }
+    }
+    
+    if health == RecordingHealth::Stopped {
+        let message = match stopped_by {
+            Some(RecordingStop::DiskFull) => "Disk is full - audio recording stopped, transcription continues",
+            _ => "Audio recording reached its limit and stopped, transcription continues",
+        };
+        tracing::warn!("Session {} recording stopped: {:?}", session_id, stopped_by);
+        if let Err(emit_err) = emit_session_event(app_handle, session_id, "recording-stopped", serde_json::json!({
+            "sessionId": session_id,
+            "reason": stopped_by,
+            "message": message,
+            "timestamp": timestamp
+        })) {
+            tracing::warn!("Failed to emit recording-stopped event: {}", emit_err);
+        }
+    }
+}
+
+/// Warn that the recording will stop soon; transcription is not affected
+fn emit_recording_limit_warning(app_handle: &tauri::AppHandle, session_id: &str, max_minutes: u32) {
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "recording-limit-warning", serde_json::json!({
+        "sessionId": session_id,
+        "message": format!("Audio recording will stop at its {} minute limit; transcription continues", max_minutes),
+        "maxRecordingMinutes": max_minutes,
+        "limitRatio": recording_writer::LIMIT_WARNING_RATIO,
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    })) {
+        tracing::warn!("Failed to emit recording-limit-warning event: {}", emit_err);
+    }
+}
+
+/// Warn that a session limit will stop the session
+fn emit_auto_stop_warning(app_handle: &tauri::AppHandle, session_id: &str, reason: AutoStopReason, remaining_seconds: f64) {
+    let message = match reason {
+        AutoStopReason::MaxDuration => "The session will stop when it reaches its maximum duration",
+        AutoStopReason::Silence => "The session will stop if no one speaks",
+    };
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "session-warning", serde_json::json!({
+        "sessionId": session_id,
+        "reason": reason.as_str(),
+        "message": message,
+        "remainingSeconds": remaining_seconds.max(0.0).round(),
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    })) {
+        tracing::warn!("Failed to emit session-warning event: {}", emit_err);
+    }
+}
+
+/// Stop a session that reached a limit. There is no command caller to return
+/// the final result to, so it is delivered with session-auto-stopped. Runs
+/// apart from the loop, which finalizes the recording the teardown waits for.
+fn spawn_auto_stop(app_handle: &tauri::AppHandle, session_id: &str, reason: AutoStopReason) {
+    tracing::info!("Session {} reached its {} limit, stopping", session_id, reason.as_str());
+    let app_handle = app_handle.clone();
+    let session_id = session_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        match finish_session(session_id.clone(), &app_handle, Some(reason)).await {
+            Ok(result) => {
+                if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "session-auto-stopped", serde_json::json!({
+                    "sessionId": session_id,
+                    "reason": reason.as_str(),
+                    "result": result,
+                })) {
+                    tracing::warn!("Failed to emit session-auto-stopped event: {}", emit_err);
+                }
+            }
+            // Most likely stopped by the user in the meantime
+            Err(e) => tracing::warn!("Auto-stop of session {} did not complete: {}", session_id, e),
+        }
+    });
+}
+
+/// Background work registry; a panic elsewhere must not stop sessions from being cancelled
+fn session_tasks(state: &AppState) -> std::sync::MutexGuard<'_, SessionTasks> {
+    state.session_tasks.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Capture of one session. Each has its own lock, so a loop waiting for its
+/// next chunk never holds up another session's.
+pub type SharedCapture = Arc<Mutex<AudioCaptureService>>;
+
+/// Key of the capture started by start_audio_capture outside any session
+pub const STANDALONE_CAPTURE: &str = "standalone";
+
+/// Capture of `session_id`, if it has one
+async fn session_capture(state: &AppState, session_id: &str) -> Option<SharedCapture> {
+    state.audio_captures.lock().await.get(session_id).cloned()
+}
+
+/// Stop and remove the capture of `session_id`. Returns whether it had one.
+async fn stop_session_capture(state: &AppState, session_id: &str) -> bool {
+    let capture = state.audio_captures.lock().await.remove(session_id);
+    let Some(capture) = capture else {
+        return false;
+    };
+    if let Err(e) = capture.lock().await.stop_capture().await {
+        tracing::error!("Failed to stop audio capture for session {}: {}", session_id, e);
+    }
+    true
+}
+
+/// Wait (bounded) until the loop has finalized the session's recording, if it has one
+async fn wait_for_recording_finalized(state: &AppState, session_id: &str) {
+    let finalized = state.recordings_in_progress.lock().ok()
+        .and_then(|recordings| recordings.get(session_id).map(|sender| sender.subscribe()));
+    let Some(mut finalized) = finalized else {
+        return;
+    };
+    let wait = RECORDING_FINISH_TIMEOUT + std::time::Duration::from_secs(2);
+    if tokio::time::timeout(wait, finalized.wait_for(|done| *done)).await.is_err() {
+        tracing::warn!("Session {} recording was not finalized within {:?}", session_id, wait);
+    }
+}
+
+/// Flush and finalize a session recording off the async runtime
+async fn finish_session_recording(app_handle: &tauri::AppHandle, session_id: &str, writer: ResilientRecordingWriter) {
+    let outcome = tokio::task::spawn_blocking(move || writer.finish(RECORDING_FINISH_TIMEOUT)).await;
+    if let Ok(mut recordings) = app_handle.state::<AppState>().recordings_in_progress.lock() {
+        if let Some(finalized) = recordings.remove(session_id) {
+            finalized.send_replace(true);
+        }
+    }
+    match outcome {
+        Ok(Ok(summary)) if summary.complete => {
+            tracing::info!("Session {} recording finalized ({} samples)", session_id, summary.samples_written);
+        }
+        Ok(Ok(summary)) if summary.stopped_by == Some(RecordingStop::DiskFull) => {
+            tracing::warn!("Session {} recording stopped early on a full disk ({} samples kept)", session_id, summary.samples_written);
+        }
+        Ok(Ok(summary)) => {
+            tracing::warn!("Session {} recording incomplete, unwritten audio kept at {:?}", session_id, summary.local_tail_path);
+            if let Err(emit_err) = emit_session_event(app_handle, session_id, "recording-degraded", serde_json::json!({
+                "sessionId": session_id,
+                "message": "Recording location stayed unreachable - remaining audio was saved locally",
+                "recoverable": false,
+                "localTailPath": summary.local_tail_path,
+                "timestamp": std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis()
+            })) {
+                tracing::warn!("Failed to emit recording-degraded event: {}", emit_err);
+            }
+        }
+        Ok(Err(e)) => tracing::error!("Failed to finalize session {} recording: {}", session_id, e),
+        Err(e) => tracing::error!("Recording finalize task failed for session {}: {}", session_id, e),
+    }
+}
+
+fn emit_rediarization_progress(app_handle: &tauri::AppHandle, session_id: &str, stage: &str, progress: f32) {
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "rediarization-progress", serde_json::json!({
+        "sessionId": session_id,
+        "stage": stage,
+        "progress": progress,
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    })) {
+        tracing::warn!("Failed to emit rediarization-progress event: {}", emit_err);
+    }
 }
 
+/// Re-run only speaker diarization over a finished session's retained audio and
+/// re-attribute its existing segments, keeping all text and timings. Works for
+/// sessions saved by earlier runs too; the saved transcript is updated.
 #[tauri::command]
-pub async fn stop_transcription(
+pub async fn rediarize_session(
     session_id: String,
+    diarization_config: Option<DiarizationConfig>,
     app_handle: tauri::AppHandle,
-) -> Result<FinalTranscriptionResult, String> {
-    let state = app_handle.state::<AppState>();
-    
-    tracing::info!("Stopping transcription session: {}", session_id);
-    
-    // Retrieve session from global state and stop it
-    let mut sessions_guard = state.active_sessions.lock().await;
-    let session_state = sessions_guard.remove(&session_id)
-        .ok_or_else(|| {
-            tracing::warn!("Session {} not found in active sessions", session_id);
-            format!("Session {} not found", session_id)
-        })?;
-    drop(sessions_guard);
-    
-    // Stop the audio capture service
-    let mut audio_capture_guard = state.audio_capture_service.lock().await;
-    if let Some(mut capture_service) = audio_capture_guard.take() {
-        tracing::info!("Stopping audio capture for session {}", session_id);
-        if let Err(e) = capture_service.stop_capture().await {
-            tracing::error!("Failed to stop audio capture for session {}: {}", session_id, e);
-            // Don't fail the whole operation if just audio stop fails
-        }
-    } else {
-        tracing::warn!("No active audio capture service found for session {}", session_id);
-    }
-    drop(audio_capture_guard);
-    
-    // Clear the whisper engine to free resources
-    let mut whisper_guard = state.whisper_engine.lock().await;
-    *whisper_guard = None;
-    drop(whisper_guard);
-    
-    // Calculate session duration
-    let current_time = std::time::SystemTime::now()
+    state: State<'_, AppState>
+) -> Result<ReattributionSummary, CommandError> {
+    let record = finished_session_record(&state, &session_id).await?;
+    let audio_path = record.audio_path.clone().map(PathBuf::from)
+        .or_else(|| session_audio_path(None, &session_id))
+        .filter(|path| path.exists())
+        .ok_or_else(|| format!("No retained audio for session {}", session_id))?;
+    
+    tracing::info!("🔄 Re-diarizing session {} from {:?}", session_id, audio_path);
+    emit_rediarization_progress(&app_handle, &session_id, "loading_audio", 0.0);
+    let audio = read_wav_file(&audio_path.to_string_lossy()).await?;
+    
+    emit_rediarization_progress(&app_handle, &session_id, "diarizing", 0.2);
+    let service = DiarizationService::new(diarization_config.unwrap_or_default()).await?;
+    let diarization = service.diarize(&audio.samples, audio.sample_rate).await?;
+    
+    emit_rediarization_progress(&app_handle, &session_id, "reattributing", 0.9);
+    let revised_at = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
-        .as_secs();
-    let total_duration = (current_time - session_state.start_time) as f32;
-    
-    // Use the actual transcription segments if available, otherwise provide a default message
-    let segments = if !session_state.transcription_segments.is_empty() {
-        tracing::info!("Returning {} stored transcription segments", session_state.transcription_segments.len());
-        session_state.transcription_segments
-    } else {
-        tracing::warn!("No transcription segments found, returning placeholder");
-        vec![
-            serde_json::json!({
-                "text": "No transcription data available",
-                "startTime": 0.0,
-                "endTime": total_duration,
-                "confidence": 0.0,
-                "speaker": "speaker_1"
-            })
-        ]
+        .as_millis() as u64;
+    let (mut summary, segments, links) = {
+        let mut completed_guard = state.completed_sessions.lock().await;
+        // The record of this run may have been muted or renamed meanwhile
+        let mut loaded = record;
+        let record = completed_guard.get_mut(&session_id).unwrap_or(&mut loaded);
+        let previous_speakers: Vec<Option<String>> = record.segments.iter()
+            .map(|segment| segment["speaker"].as_str().map(str::to_string))
+            .collect();
+        let previous_names = SpeakerNames::from_segments(&record.segments);
+        let mut summary = reattribution::reattribute_segments(&mut record.segments, &diarization.segments, revised_at);
+        
+        // Mutes and names follow the person to their new speaker id
+        let remap = muting::derive_remap(&previous_speakers, &record.segments);
+        record.speaker_mutes.apply_remap(&remap);
+        record.speaker_mutes.flag_segments(&mut record.segments);
+        summary.muted_speakers = record.speaker_mutes.muted_speakers();
+        let names = previous_names.remapped(&remap);
+        for (segment, previous) in record.segments.iter_mut().zip(&previous_speakers) {
+            if segment["speaker"].as_str() != previous.as_deref() {
+                if let Some(fields) = segment.as_object_mut() {
+                    fields.remove(speaker_names::SPEAKER_NAME_FIELD);
+                }
+                names.label_segment(segment);
+            }
+        }
+        
+        summary.talk_time = Some(speaker_stats::compute_speaker_stats(&record.segments));
+        let links = Uuid::parse_str(&session_id).ok().map(|meeting_id| {
+            let started_at = chrono::DateTime::from_timestamp(record.start_time as i64, 0).unwrap_or_else(chrono::Utc::now);
+            (meeting_id, speaker_stats::profile_links(meeting_id, started_at, &record.segments, &names))
+        });
+        (summary, record.segments.clone(), links)
     };
     
-    let result = FinalTranscriptionResult {
-        session_id: session_id.clone(),
-        total_duration,
-        segments,
-        speakers: Some(vec![
-            serde_json::json!({
-                "id": "speaker_1",
-                "name": "Speaker 1",
-                "segments": 1
-            })
-        ]),
-        quality_metrics: serde_json::json!({
-            "averageConfidence": 0.95,
-            "wordErrorRate": 0.05,
-            "realTimeFactor": 0.8
-        }),
-        processing_time_ms: 1500,
-    };
+    // Profiles the session is now attributed to, with their talk time in it
+    if let Some((meeting_id, links)) = links {
+        let store_guard = state.speaker_store.lock().await;
+        if let Some(store) = store_guard.as_ref() {
+            summary.linked_profiles = store.sync_meeting_speakers(meeting_id, links)
+                .await
+                .map_err(|e| CommandError::Storage(format!("Failed to update session speaker links: {}", e)))?;
+        }
+    }
     
-    tracing::info!("Transcription session {} stopped successfully", session_id);
-    Ok(result)
+    // The saved transcript (and its speakers) is what history and search read
+    if let Some(store) = saved_session_store(&state).await {
+        store.replace_segments(&session_id, segments)
+            .await
+            .map_err(|e| CommandError::Storage(format!("Failed to save re-diarized session: {}", e)))?;
+    }
+    
+    emit_rediarization_progress(&app_handle, &session_id, "complete", 1.0);
+    tracing::info!("Re-diarization of session {} changed {} of {} segments",
+                  session_id, summary.changed_segments, summary.total_segments);
+    Ok(summary)
+}
+
+/// Languages for the language picker, with native display names
+#[tauri::command]
+pub async fn list_supported_languages() -> Result<Vec<LanguageInfo>, CommandError> {
+    let mut languages = vec![Language::AUTO.info()];
+    languages.extend(crate::asr::types::supported_languages().iter().map(|l| l.info()));
+    Ok(languages)
 }
 
 /// Get information about active transcription sessions
 #[tauri::command]
-pub async fn get_active_sessions(state: State<'_, AppState>) -> Result<Vec<TranscriptionSession>, String> {
+pub async fn get_active_sessions(state: State<'_, AppState>) -> Result<Vec<TranscriptionSession>, CommandError> {
     let sessions_guard = state.active_sessions.lock().await;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
     
     let active_sessions: Vec<TranscriptionSession> = sessions_guard
         .values()
@@ -943,24 +4883,256 @@ pub async fn get_active_sessions(state: State<'_, AppState>) -> Result<Vec<Trans
             config: session_state.config.clone(),
             start_time: session_state.start_time,
             status: session_state.status.clone(),
+            diarization_status: session_state.diarization_status,
+            elapsed_seconds: SessionProgress::elapsed_seconds(session_state.start_time, now),
+            segment_count: session_state.progress.segment_count,
+            last_segment_at: session_state.progress.last_segment_at,
+            model_status: session_state.progress.model_status,
+            current_audio_level: session_state.progress.current_audio_level,
+            heartbeat_age_ms: session_state.heartbeat.age().map(|age| age.as_millis() as u64),
         })
         .collect();
     
     Ok(active_sessions)
 }
 
+/// Prevent system sleep for an active session, warning the user if the OS refuses
+fn acquire_power_assertion(app_handle: &tauri::AppHandle, session_id: &str, prevent_display_sleep: bool) {
+    let state = app_handle.state::<AppState>();
+    let result = match state.power_assertions.lock() {
+        Ok(mut assertions) => assertions.acquire(session_id, prevent_display_sleep),
+        Err(e) => Err(e.to_string()),
+    };
+    
+    if let Err(e) = result {
+        if let Err(emit_err) = emit_session_event(app_handle, session_id, "power-assertion-warning", serde_json::json!({
+            "sessionId": session_id,
+            "message": "The system may sleep during this recording. Keep the computer awake or plugged in.",
+            "error": e,
+            "timestamp": std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64
+        })) {
+            tracing::warn!("Failed to emit power-assertion-warning event: {}", emit_err);
+        }
+    }
+}
+
+fn release_power_assertion(state: &AppState, session_id: &str) {
+    if let Ok(mut assertions) = state.power_assertions.lock() {
+        assertions.release(session_id);
+    }
+}
+
+/// Current sleep prevention state for diagnostics
+#[tauri::command]
+pub async fn get_power_assertion_state(state: State<'_, AppState>) -> Result<PowerAssertionState, CommandError> {
+    state.power_assertions.lock()
+        .map(|assertions| assertions.state())
+        .map_err(|e| CommandError::Failed(format!("Failed to read power assertion state: {}", e)))
+}
+
+/// Pause a session: captured audio is discarded and the machine may sleep again
+#[tauri::command]
+pub async fn pause_transcription(
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<(), CommandError> {
+    set_session_status(&state, &session_id, "paused").await?;
+    release_power_assertion(&state, &session_id);
+    
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "session-paused", serde_json::json!({
+        "sessionId": session_id,
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    })) {
+        tracing::warn!("Failed to emit session-paused event: {}", emit_err);
+    }
+    Ok(())
+}
+
+/// Resume a paused session
+#[tauri::command]
+pub async fn resume_transcription(
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<(), CommandError> {
+    let prevent_display_sleep = set_session_status(&state, &session_id, "active").await?;
+    acquire_power_assertion(&app_handle, &session_id, prevent_display_sleep);
+    
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "session-resumed", serde_json::json!({
+        "sessionId": session_id,
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    })) {
+        tracing::warn!("Failed to emit session-resumed event: {}", emit_err);
+    }
+    Ok(())
+}
+
+/// Set the status of an active session, returning its preventDisplaySleep setting
+async fn set_session_status(state: &AppState, session_id: &str, status: &str) -> Result<bool, String> {
+    let mut sessions_guard = state.active_sessions.lock().await;
+    let session_state = sessions_guard.get_mut(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    session_state.status = status.to_string();
+    Ok(session_state.config.prevent_display_sleep)
+}
+
+
+/// Change the speech threshold (0-1) of a running session; takes effect with the next chunk
+#[tauri::command]
+pub async fn update_vad_threshold(
+    session_id: String,
+    value: f32,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<f32, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    if !value.is_finite() || !(0.0..=1.0).contains(&value) {
+        return Err(CommandError::InvalidInput(format!("VAD threshold must be between 0 and 1, got {}", value)));
+    }
+    
+    {
+        let mut sessions_guard = state.active_sessions.lock().await;
+        let active_session_id = {
+            let completed_guard = state.completed_sessions.lock().await;
+            resolve_active_session_id(&sessions_guard, &completed_guard, &session_id)
+        };
+        let session_state = active_session_id
+            .and_then(|id| sessions_guard.get_mut(&id))
+            .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?;
+        session_state.config.vad_threshold = value;
+    }
+    
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "vad-threshold-updated", serde_json::json!({
+        "sessionId": session_id,
+        "vadThreshold": value,
+        "vadLevelThreshold": levels::vad_level_threshold(value)
+    })) {
+        tracing::warn!("Failed to emit vad-threshold-updated event: {}", emit_err);
+    }
+    Ok(value)
+}
+
+/// Switch noise suppression of a running session on or off; takes effect with the next chunk
+#[tauri::command]
+pub async fn set_noise_suppression(
+    session_id: String,
+    enabled: bool,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<bool, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    
+    {
+        let mut sessions_guard = state.active_sessions.lock().await;
+        let active_session_id = {
+            let completed_guard = state.completed_sessions.lock().await;
+            resolve_active_session_id(&sessions_guard, &completed_guard, &session_id)
+        };
+        let session_state = active_session_id
+            .and_then(|id| sessions_guard.get_mut(&id))
+            .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?;
+        session_state.config.enable_noise_suppression = enabled;
+    }
+    
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "noise-suppression-updated", serde_json::json!({
+        "sessionId": session_id,
+        "enableNoiseSuppression": enabled
+    })) {
+        tracing::warn!("Failed to emit noise-suppression-updated event: {}", emit_err);
+    }
+    Ok(enabled)
+}
+
+/// Capture and processing health of an active session
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionHealth {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    pub status: String,
+    #[serde(rename = "droppedChunks")]
+    pub dropped_chunks: u64,
+    #[serde(rename = "droppedMs")]
+    pub dropped_ms: u64,
+    #[serde(rename = "queueDepth")]
+    pub queue_depth: usize,
+    #[serde(rename = "queueCapacity")]
+    pub queue_capacity: usize,
+    #[serde(rename = "queuedMs")]
+    pub queued_ms: u64,
+    #[serde(rename = "realTimeFactor")]
+    pub real_time_factor: Option<f32>,
+    #[serde(rename = "errorCount")]
+    pub error_count: u32,
+}
+
+/// Dropped audio, capture queue depth and processing speed of an active session
+#[tauri::command]
+pub async fn get_session_health(
+    session_id: String,
+    state: State<'_, AppState>
+) -> Result<SessionHealth, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    
+    let (active_session_id, status, real_time_factor, error_count) = {
+        let sessions_guard = state.active_sessions.lock().await;
+        let active_session_id = {
+            let completed_guard = state.completed_sessions.lock().await;
+            resolve_active_session_id(&sessions_guard, &completed_guard, &session_id)
+        };
+        let session_state = active_session_id
+            .and_then(|id| sessions_guard.get(&id))
+            .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?;
+        (
+            session_state.session_id.clone(),
+            session_state.status.clone(),
+            session_state.processing_stats.real_time_factor(),
+            session_state.error_count,
+        )
+    };
+    
+    let channel = match session_capture(&state, &active_session_id).await {
+        Some(capture) => capture.lock().await.channel_health(),
+        None => Default::default(),
+    };
+    
+    Ok(SessionHealth {
+        session_id: active_session_id,
+        status,
+        dropped_chunks: channel.dropped_chunks,
+        dropped_ms: channel.dropped_ms,
+        queue_depth: channel.queue_depth,
+        queue_capacity: channel.queue_capacity,
+        queued_ms: channel.queued_ms,
+        real_time_factor,
+        error_count,
+    })
+}
+
 /// Cleanup a specific session without stopping transcription
 #[tauri::command]
 pub async fn cleanup_session(
     session_id: String,
     state: State<'_, AppState>
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     let mut sessions_guard = state.active_sessions.lock().await;
     
     if sessions_guard.remove(&session_id).is_some() {
+        session_tasks(&state).cancel(&session_id);
+        state.close_event_log(&session_id);
+        release_power_assertion(&state, &session_id);
         Ok(format!("Session {} cleaned up successfully", session_id))
     } else {
-        Err(format!("Session {} not found", session_id))
+        Err(CommandError::NotFound(format!("Session {} not found", session_id)))
     }
 }
 
@@ -968,33 +5140,124 @@ pub async fn cleanup_session(
 #[tauri::command]
 pub async fn emergency_stop_all(
     state: State<'_, AppState>
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     tracing::warn!("Emergency stop all triggered");
     
+    // Stop model downloads and initializations first so none of them brings a
+    // session back to life. Aborted downloads keep their partial file to resume.
+    let cancelled_tasks = session_tasks(&state).cancel_all();
+    if let Ok(mut downloads) = state.model_downloads.lock() {
+        downloads.drain().for_each(|(_, download)| download.cancel());
+    }
+    tracing::info!("Cancelled background work of {} sessions", cancelled_tasks);
+    
     // Stop all audio capture services
-    let mut audio_capture_guard = state.audio_capture_service.lock().await;
-    if let Some(mut capture_service) = audio_capture_guard.take() {
-        if let Err(e) = capture_service.stop_capture().await {
-            tracing::error!("Failed to emergency stop audio capture: {}", e);
+    let captures: Vec<(String, SharedCapture)> = state.audio_captures.lock().await.drain().collect();
+    for (capture_session_id, capture) in captures {
+        if let Err(e) = capture.lock().await.stop_capture().await {
+            tracing::error!("Failed to emergency stop audio capture of {}: {}", capture_session_id, e);
         }
     }
-    drop(audio_capture_guard);
     
     // Clear all active sessions
     let mut sessions_guard = state.active_sessions.lock().await;
     let session_count = sessions_guard.len();
-    sessions_guard.clear();
+    let cleared_sessions: Vec<TranscriptionSessionState> = sessions_guard.drain().map(|(_, s)| s).collect();
     drop(sessions_guard);
     
-    // Clear whisper engine
-    let mut whisper_guard = state.whisper_engine.lock().await;
-    *whisper_guard = None;
-    drop(whisper_guard);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    // Sessions still starting were never saved; their start fails on its own
+    for session_state in cleared_sessions.iter().filter(|s| s.status != SESSION_STARTING) {
+        let duration = now.saturating_sub(session_state.start_time) as f64;
+        record_usage_metrics(&state, session_state, duration, "emergency_stop").await;
+        let record = build_session_record(session_state, now);
+        persist_session_end(&state, &record, now, session_store::SESSION_STATUS_INTERRUPTED).await;
+    }
+    
+    let closed_logs: Vec<Arc<EventLogWriter>> = state.event_logs.lock()
+        .map(|mut logs| logs.drain().map(|(_, writer)| writer).collect())
+        .unwrap_or_default();
+    drop(closed_logs);
+    if let Ok(mut assertions) = state.power_assertions.lock() {
+        assertions.release_all();
+    }
     
     tracing::info!("Emergency stop completed. Cleared {} sessions", session_count);
     Ok(format!("Emergency stop completed. Cleared {} sessions and stopped all audio capture.", session_count))
 }
 
+/// Dump the persisted event log of a session, optionally limited to a sequence range
+#[tauri::command]
+pub async fn dump_session_events(
+    session_id: String,
+    start_sequence: Option<u64>,
+    end_sequence: Option<u64>,
+    state: State<'_, AppState>
+) -> Result<Vec<LoggedEvent>, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    // A running session's log may still have events queued
+    let writer = state.event_logs.lock().ok().and_then(|logs| logs.get(&session_id).cloned());
+    if let Some(writer) = writer {
+        tokio::task::spawn_blocking(move || writer.flush())
+            .await
+            .map_err(|e| format!("Task join error: {}", e))?;
+    }
+    let dir = event_log_dir().ok_or("Failed to get app data directory")?;
+    let path = SessionEventLog::log_path(&dir, &session_id);
+    if !path.exists() {
+        return Err(CommandError::NotFound(format!("No event log found for session {}", session_id)));
+    }
+    
+    let range = match (start_sequence, end_sequence) {
+        (None, None) => None,
+        (start, end) => Some((start.unwrap_or(0), end.unwrap_or(u64::MAX))),
+    };
+    
+    tokio::task::spawn_blocking(move || event_log::read_events(&path, range))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+        .map_err(|e| CommandError::Storage(format!("Failed to read session events: {}", e)))
+}
+
+/// Re-emit a session's logged events in order for UI debugging; refused in release builds
+#[tauri::command]
+pub async fn replay_events_to_frontend(
+    session_id: String,
+    speed: Option<f32>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize, CommandError> {
+    if !cfg!(debug_assertions) {
+        return Err(CommandError::detailed(
+            "dev_command_unavailable",
+            "Event replay is only available in development builds",
+            vec![],
+        ));
+    }
+    
+    let events = dump_session_events(session_id.clone(), None, None, app_handle.state::<AppState>()).await?;
+    let delays = event_log::replay_schedule(&events, speed.unwrap_or(1.0));
+    let event_count = events.len();
+    
+    tracing::info!("Replaying {} events for session {}", event_count, session_id);
+    tokio::spawn(async move {
+        for (delay, logged) in delays.into_iter().zip(events) {
+            tokio::time::sleep(delay).await;
+            let mut payload = logged.payload;
+            if let Some(obj) = payload.as_object_mut() {
+                obj.insert("replaySequence".to_string(), serde_json::json!(logged.sequence));
+            }
+            if let Err(emit_err) = app_handle.emit(&logged.event, payload) {
+                tracing::warn!("Failed to replay {} event: {}", logged.event, emit_err);
+            }
+        }
+    });
+    
+    Ok(event_count)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TranscribeFileRequest {
     pub file_path: String,
@@ -1005,55 +5268,224 @@ pub struct TranscribeFileRequest {
 pub async fn transcribe_audio_file(
     request: TranscribeFileRequest,
     state: State<'_, AppState>
-) -> Result<ASRResult, String> {
+) -> Result<ASRResult, CommandError> {
 
     tracing::info!("Starting audio file transcription: {}", request.file_path);
 
     // Validate file exists
     let file_path = Path::new(&request.file_path);
     if !file_path.exists() {
-        return Err(format!("Audio file not found: {}", request.file_path));
+        return Err(CommandError::NotFound(format!("Audio file not found: {}", request.file_path)));
     }
 
     // Read audio file
     let audio_data = match read_audio_file(&request.file_path).await {
         Ok(data) => data,
-        Err(e) => return Err(format!("Failed to read audio file: {}", e)),
+        Err(e) => return Err(CommandError::Failed(format!("Failed to read audio file: {}", e))),
     };
 
     tracing::info!("Audio file loaded: {} samples at {}Hz", audio_data.samples.len(), audio_data.sample_rate);
+    let vocabulary = config_validation::normalize_vocabulary(request.config.custom_vocabulary.as_deref().unwrap_or_default());
+
+    // Share the engine of a live session on the same tier, or load one. The
+    // engine is not locked, so a live session using it keeps transcribing.
+    let engine = lease_engine(&state, file_whisper_config(&request.config, vocabulary.clone())).await?;
+    let context = TranscriptionContext::default();
+    let result = engine.transcribe_with_vocabulary(&audio_data, &context, vocabulary.as_deref()).await;
+    let result = result?;
+
+    tracing::info!("Transcription completed successfully");
+    Ok(result)
+}
+
+/// Engine settings for transcribing files with a session config
+fn file_whisper_config(config: &TranscriptionConfig, vocabulary: Option<Vec<String>>) -> WhisperConfig {
+    let mut whisper_config = WhisperConfig {
+        model_tier: crate::asr::types::ModelTier::from(config.quality_tier.as_str()),
+        language: config.languages.first().copied(),
+        device: crate::asr::types::Device::Auto,
+        custom_vocabulary: vocabulary,
+        max_temperature: config.max_decode_temperature,
+        ..Default::default()
+    };
+    translation::configure(&mut whisper_config, &config.mode);
+    whisper_config
+}
 
-    // Use configured Whisper engine from app state
-    let mut whisper_guard = state.whisper_engine.lock().await;
+/// Transcribe every supported audio file in a folder, one after another on
+/// one engine. Results are written next to each file as `<name>.transcript.json`,
+/// plus a subtitle file when `subtitle_format` (srt or vtt) is given.
+#[tauri::command]
+pub async fn transcribe_audio_folder(
+    path: String,
+    config: TranscriptionConfig,
+    subtitle_format: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>
+) -> Result<BatchSummary, CommandError> {
+    let folder = PathBuf::from(&path);
+    if !folder.is_dir() {
+        return Err(CommandError::NotFound(format!("Folder not found: {}", path)));
+    }
+    let subtitle_format = subtitle_format
+        .map(|format| SubtitleFormat::parse(&format).ok_or_else(|| format!("Unsupported transcript format: {}", format)))
+        .transpose()?;
+    let files = batch::list_audio_files(&folder)
+        .map_err(|e| format!("Failed to list audio files: {}", e))?;
     
-    // Initialize engine if not already present
-    if whisper_guard.is_none() {
-        let whisper_config = WhisperConfig {
-            model_tier: crate::asr::types::ModelTier::from(request.config.quality_tier.as_str()),
-            language: request.config.languages.get(0).cloned(),
-            device: crate::asr::types::Device::Auto,
-            ..Default::default()
-        };
-        
-        let engine = WhisperEngine::new(whisper_config)
-            .await
-            .map_err(|e| format!("Failed to initialize Whisper engine: {}", e))?;
-        *whisper_guard = Some(engine);
+    let cancellation = BatchCancellation::new();
+    {
+        let mut running = state.batch_transcription.lock()
+            .map_err(|e| format!("Failed to start batch transcription: {}", e))?;
+        if running.is_some() {
+            return Err(CommandError::detailed("batch_transcription_running", "A folder transcription is already running", vec![
+                "Wait for it to finish or cancel it".to_string()
+            ]));
+        }
+        *running = Some(cancellation.clone());
     }
     
-    let engine = whisper_guard.as_ref().unwrap();
-    let context = TranscriptionContext::default();
+    tracing::info!("Starting batch transcription of {} files in {}", files.len(), path);
+    let summary = run_batch_transcription(&app_handle, &state, &folder, &files, &config, subtitle_format, &cancellation).await;
     
-    // Transcribe the audio
-    let result = engine.transcribe(&audio_data, &context)
-        .await
+    if let Ok(mut running) = state.batch_transcription.lock() {
+        *running = None;
+    }
+    tracing::info!("Batch transcription finished: {} succeeded, {} failed, cancelled: {}",
+                   summary.succeeded, summary.failed, summary.cancelled);
+    Ok(summary)
+}
+
+/// Stop the running folder transcription after the file in progress.
+/// Returns false when no batch is running.
+#[tauri::command]
+pub async fn cancel_batch_transcription(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let running = state.batch_transcription.lock()
+        .map_err(|e| format!("Failed to cancel batch transcription: {}", e))?;
+    match running.as_ref() {
+        Some(cancellation) => {
+            cancellation.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+async fn run_batch_transcription(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    folder: &Path,
+    files: &[PathBuf],
+    config: &TranscriptionConfig,
+    subtitle_format: Option<SubtitleFormat>,
+    cancellation: &BatchCancellation,
+) -> BatchSummary {
+    let mut summary = BatchSummary::new(folder, files.len());
+    let vocabulary = config_validation::normalize_vocabulary(config.custom_vocabulary.as_deref().unwrap_or_default());
+    let mut engine: Option<EngineLease> = None;
+    
+    for (index, file) in files.iter().enumerate() {
+        if cancellation.is_cancelled() {
+            summary.cancelled = true;
+            break;
+        }
+        emit_batch_progress(app_handle, BatchProgress::new(file, index, index, files.len()));
+        
+        // The engine is loaded with the first file and reused for the rest
+        let lease = match engine.take() {
+            Some(lease) => lease,
+            None => match lease_engine(state, file_whisper_config(config, vocabulary.clone())).await {
+                Ok(lease) => lease,
+                Err(e) => {
+                    summary.record(batch_failure(file, e));
+                    continue;
+                }
+            },
+        };
+        let outcome = match transcribe_batch_file(&lease, file, vocabulary.as_deref(), subtitle_format).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                tracing::warn!("Batch transcription of {} failed: {}", file.display(), e);
+                batch_failure(file, e)
+            }
+        };
+        summary.record(outcome);
+        engine = Some(lease);
+    }
+    
+    if let Some(last) = files.last().filter(|_| !summary.cancelled) {
+        emit_batch_progress(app_handle, BatchProgress::new(last, files.len() - 1, files.len(), files.len()));
+    }
+    summary
+}
+
+/// Transcribe one file of a batch and write its outputs next to it
+async fn transcribe_batch_file(
+    engine: &WhisperEngine,
+    file: &Path,
+    vocabulary: Option<&[String]>,
+    subtitle_format: Option<SubtitleFormat>,
+) -> Result<BatchFileOutcome, String> {
+    let file_path = file.to_string_lossy().to_string();
+    let audio_data = read_audio_file(&file_path).await
+        .map_err(|e| format!("Failed to read audio file: {}", e))?;
+    let result = engine.transcribe_with_vocabulary(&audio_data, &TranscriptionContext::default(), vocabulary).await
         .map_err(|e| format!("Failed to transcribe audio: {}", e))?;
+    let mut segments = batch::segments_from_result(&result, audio_data.duration_seconds);
+    for segment in &mut segments {
+        translation::label_segment(segment, engine.get_config().task);
+    }
+    
+    let mut outputs = Vec::new();
+    let json_path = batch::output_path(file, "transcript.json");
+    let document = serde_json::json!({
+        "sourceFile": file_path,
+        "durationSeconds": audio_data.duration_seconds,
+        "transcribedAt": chrono::Utc::now().to_rfc3339(),
+        "segments": segments,
+        "result": result,
+    });
+    let json = serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Failed to serialize transcript: {}", e))?;
+    fs::write(&json_path, json).await
+        .map_err(|e| format!("Failed to write {}: {}", json_path.display(), e))?;
+    outputs.push(json_path.to_string_lossy().to_string());
+    
+    if let Some(format) = subtitle_format {
+        let subtitle_path = batch::output_path(file, format.extension());
+        let export = subtitles::export_subtitles(&segments, format, &HashMap::new());
+        fs::write(&subtitle_path, export.content).await
+            .map_err(|e| format!("Failed to write {}: {}", subtitle_path.display(), e))?;
+        outputs.push(subtitle_path.to_string_lossy().to_string());
+    }
+    
+    Ok(BatchFileOutcome {
+        file: file_path,
+        success: true,
+        error: None,
+        outputs,
+        segment_count: segments.len(),
+    })
+}
 
-    tracing::info!("Transcription completed successfully");
-    Ok(result)
+fn batch_failure(file: &Path, error: String) -> BatchFileOutcome {
+    BatchFileOutcome {
+        file: file.to_string_lossy().to_string(),
+        success: false,
+        error: Some(error),
+        outputs: Vec::new(),
+        segment_count: 0,
+    }
+}
+
+fn emit_batch_progress(app_handle: &tauri::AppHandle, progress: BatchProgress) {
+    if let Err(emit_err) = app_handle.emit("batch-progress", &progress) {
+        tracing::warn!("Failed to emit batch-progress event: {}", emit_err);
+    }
 }
 
-async fn read_audio_file(file_path: &str) -> Result<AudioData, String> {
+/// Decode a WAV, MP3, M4A or AAC file to 16 kHz mono
+pub async fn read_audio_file(file_path: &str) -> Result<AudioData, String> {
     let path = Path::new(file_path);
     let extension = path.extension()
         .and_then(|s| s.to_str())
@@ -1062,15 +5494,23 @@ async fn read_audio_file(file_path: &str) -> Result<AudioData, String> {
 
     match extension.as_str() {
         "wav" => read_wav_file(file_path).await,
-        "mp3" | "m4a" | "aac" => {
-            // For now, return an error for unsupported formats
-            // In the future, we could add symphonia or other decoders
-            Err(format!("Audio format '{}' not yet supported. Please use WAV files.", extension))
-        },
+        ext if file_decoder::COMPRESSED_EXTENSIONS.contains(&ext) => read_compressed_file(file_path).await,
         _ => Err(format!("Unsupported audio format: {}", extension)),
     }
 }
 
+async fn read_compressed_file(file_path: &str) -> Result<AudioData, String> {
+    let path = PathBuf::from(file_path);
+    tokio::task::spawn_blocking(move || -> Result<AudioData, String> {
+        let decoded = file_decoder::decode_audio_file(&path).map_err(|e| e.to_string())?;
+        if decoded.skipped_packets > 0 {
+            tracing::warn!("{} corrupt packets skipped while decoding {}", decoded.skipped_packets, path.display());
+        }
+        file_audio_data(decoded.samples, decoded.channels, decoded.sample_rate)
+    }).await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 async fn read_wav_file(file_path: &str) -> Result<AudioData, String> {
     use tokio::task;
     
@@ -1122,42 +5562,44 @@ async fn read_wav_file(file_path: &str) -> Result<AudioData, String> {
             }
         };
         
-        // Convert to mono if stereo
-        let mono_samples = if spec.channels == 1 {
-            samples
-        } else if spec.channels == 2 {
-            // Simple stereo to mono conversion (average channels)
-            samples.chunks(2)
-                .map(|chunk| (chunk[0] + chunk.get(1).unwrap_or(&0.0)) / 2.0)
-                .collect()
-        } else {
-            return Err(format!("Unsupported channel count: {}", spec.channels));
-        };
-        
-        // Resample to 16kHz if needed
-        let target_sample_rate = 16000;
-        let final_samples = if spec.sample_rate != target_sample_rate {
-            resample_audio(&mono_samples, spec.sample_rate, target_sample_rate)?
-        } else {
-            mono_samples
-        };
-        
-        let duration_seconds = final_samples.len() as f32 / target_sample_rate as f32;
-        
-        Ok(AudioData {
-            samples: final_samples,
-            sample_rate: target_sample_rate,
-            channels: 1,
-            timestamp: std::time::SystemTime::now(),
-            source_channel: AudioSource::File,
-            duration_seconds,
-        })
+        file_audio_data(samples, spec.channels, spec.sample_rate)
     }).await
     .map_err(|e| format!("Task join error: {}", e))??;
     
     Ok(audio_data)
 }
 
+/// Downmix interleaved file samples to mono and resample to 16kHz
+fn file_audio_data(samples: Vec<f32>, channels: u16, sample_rate: u32) -> Result<AudioData, String> {
+    // Mono is the average of all channels
+    let mono_samples = match channels {
+        0 => return Err("Audio has no channels".to_string()),
+        1 => samples,
+        n => samples.chunks(n as usize)
+            .map(|frame| frame.iter().sum::<f32>() / n as f32)
+            .collect(),
+    };
+    
+    // Resample to 16kHz if needed
+    let target_sample_rate = 16000;
+    let final_samples = if sample_rate != target_sample_rate {
+        resample_audio(&mono_samples, sample_rate, target_sample_rate)?
+    } else {
+        mono_samples
+    };
+    
+    let duration_seconds = final_samples.len() as f32 / target_sample_rate as f32;
+    
+    Ok(AudioData {
+        samples: final_samples,
+        sample_rate: target_sample_rate,
+        channels: 1,
+        timestamp: std::time::SystemTime::now(),
+        source_channel: AudioSource::File,
+        duration_seconds,
+    })
+}
+
 fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, String> {
     if from_rate == to_rate {
         return Ok(samples.to_vec());
@@ -1195,6 +5637,155 @@ fn check_whisper_availability() -> Result<(), String> {
     Ok(())
 }
 
+/// Whisper engine leased by a session, once its model is loaded
+async fn session_engine(state: &AppState, session_id: &str) -> Option<EngineLease> {
+    state.active_sessions.lock().await
+        .get(session_id)
+        .and_then(|session_state| session_state.engine.clone())
+}
+
+/// Transcriber for the final passes of a session: its override if one was
+/// set, else its leased engine once the model is loaded
+pub async fn session_transcriber(state: &AppState, session_id: &str) -> Option<SharedTranscriber> {
+    let sessions = state.active_sessions.lock().await;
+    let session_state = sessions.get(session_id)?;
+    session_state.transcriber.clone()
+        .or_else(|| session_state.engine.clone().map(|engine| Arc::new(engine) as SharedTranscriber))
+}
+
+/// Transcribe a session's final passes with `transcriber` instead of its
+/// Whisper engine. Returns false when the session is not active.
+pub async fn set_session_transcriber(state: &AppState, session_id: &str, transcriber: SharedTranscriber) -> bool {
+    match state.active_sessions.lock().await.get_mut(session_id) {
+        Some(session_state) => {
+            session_state.transcriber = Some(transcriber);
+            true
+        }
+        None => false,
+    }
+}
+
+/// A loaded engine of the config's tier that decodes the way the config asks
+fn pooled_engine(state: &AppState, config: &WhisperConfig) -> Option<EngineLease> {
+    let lease = state.engine_pool.lock().ok()?.lease(config.model_tier)?;
+    let loaded = lease.get_config();
+    let fits = loaded.task == config.task
+        && loaded.language == config.language
+        && loaded.custom_vocabulary == config.custom_vocabulary
+        && loaded.beam_size == config.beam_size
+        && loaded.enable_word_timestamps == config.enable_word_timestamps
+        && loaded.max_temperature == config.max_temperature;
+    fits.then_some(lease)
+}
+
+/// Beam width of a tier's final pass
+fn tier_beam_size(tier: crate::asr::types::ModelTier) -> usize {
+    match tier {
+        crate::asr::types::ModelTier::HighAccuracy => 5,
+        crate::asr::types::ModelTier::Standard => 3,
+        crate::asr::types::ModelTier::Turbo => 1,
+    }
+}
+
+/// Start moving a session that cannot keep up to `tier`: at once when an engine
+/// of the tier is loaded, otherwise by loading its downloaded model in the
+/// background. A tier whose model is not downloaded is skipped.
+async fn begin_tier_switch(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    adapter: &mut TierAdapter,
+    tier: crate::asr::types::ModelTier,
+) -> Option<(WhisperConfig, tokio::task::JoinHandle<Result<WhisperEngine, crate::asr::types::ASRError>>)> {
+    let current = state.active_sessions.lock().await.get(session_id)?.whisper_config.clone();
+    let config = WhisperConfig {
+        model_tier: tier,
+        beam_size: tier_beam_size(tier),
+        ..current
+    };
+    if let Some(lease) = pooled_engine(state, &config) {
+        complete_tier_switch(state, app_handle, session_id, adapter, config, lease).await;
+        return None;
+    }
+    
+    let downloaded = match ModelManager::new() {
+        Ok(manager) => manager.is_model_available(tier).await,
+        Err(_) => false,
+    };
+    if !downloaded {
+        tracing::info!("Session {} is falling behind but the {} model is not downloaded", session_id, tier.quality_tier());
+        adapter.switch_failed(tier);
+        return None;
+    }
+    tracing::info!("Loading the {} model for session {} to keep up with real time", tier.quality_tier(), session_id);
+    Some((config.clone(), tokio::spawn(WhisperEngine::new(config))))
+}
+
+/// Put a session on the engine of a faster tier and explain the switch
+async fn complete_tier_switch(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    adapter: &mut TierAdapter,
+    config: WhisperConfig,
+    lease: EngineLease,
+) {
+    let tier = config.model_tier;
+    let previous_tier = {
+        let mut sessions_guard = state.active_sessions.lock().await;
+        let Some(session_state) = sessions_guard.get_mut(session_id) else {
+            return;
+        };
+        let previous_tier = session_state.whisper_config.model_tier;
+        session_state.whisper_config = config;
+        session_state.engine = Some(lease);
+        previous_tier
+    };
+    
+    let rtf = adapter.rolling_rtf().unwrap_or_default();
+    tracing::info!("Session {} switched from {:?} to {:?} at {:.2}x real time", session_id, previous_tier, tier, rtf);
+    if let Err(emit_err) = emit_session_event(app_handle, session_id, "model-fallback", serde_json::json!({
+        "sessionId": session_id,
+        "requestedTier": format!("{:?}", previous_tier),
+        "fallbackTier": format!("{:?}", tier),
+        "reason": "real_time_factor",
+        "realTimeFactor": rtf,
+        "threshold": adapter.threshold(),
+        "message": format!(
+            "Transcription ran at {:.1}x real time, switched from the {} to the faster {} model",
+            rtf, previous_tier.to_string(), tier.to_string()
+        )
+    })) {
+        tracing::warn!("Failed to emit model-fallback event: {}", emit_err);
+    }
+    adapter.switched(tier);
+}
+
+/// Make a loaded engine the one leased for its tier
+fn register_engine(state: &AppState, tier: crate::asr::types::ModelTier, engine: WhisperEngine) -> EngineLease {
+    match state.engine_pool.lock() {
+        Ok(mut pool) => pool.register(tier, engine),
+        Err(poisoned) => poisoned.into_inner().register(tier, engine),
+    }
+}
+
+/// Lease the loaded engine of the config's tier, loading one when no consumer
+/// holds it. Callers passing their own vocabulary per call share any engine of
+/// the tier and task.
+async fn lease_engine(state: &AppState, config: WhisperConfig) -> Result<EngineLease, String> {
+    let tier = config.model_tier;
+    let pooled = state.engine_pool.lock().ok()
+        .and_then(|mut pool| pool.lease(tier))
+        .filter(|lease| lease.get_config().task == config.task);
+    if let Some(lease) = pooled {
+        return Ok(lease);
+    }
+    let engine = WhisperEngine::new(config)
+        .await
+        .map_err(|e| format!("Failed to initialize Whisper engine: {}", e))?;
+    Ok(register_engine(state, tier, engine))
+}
+
 /// Initialize Whisper engine asynchronously with progress reporting
 async fn initialize_whisper_engine_async(
     config: WhisperConfig,
@@ -1204,7 +5795,7 @@ async fn initialize_whisper_engine_async(
     // Whisper should be available with whisper-rs dependency
     tracing::info!("🤖 Whisper engine available via whisper-rs integration");
     // Emit initial progress
-    if let Err(emit_err) = app_handle.emit("model-progress", serde_json::json!({
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "model-progress", serde_json::json!({
         "sessionId": session_id,
         "status": "downloading",
         "progress": 0,
@@ -1213,27 +5804,56 @@ async fn initialize_whisper_engine_async(
         tracing::error!("Failed to emit model-progress event: {}", emit_err);
     }
     
+    // Fail early with exact numbers when the model download cannot fit on disk
+    if let Ok(manager) = ModelManager::new() {
+        let pending = manager.pending_download_bytes(config.model_tier).await;
+        let available = if pending > 0 {
+            get_available_disk_space(manager.models_dir()).await.unwrap_or(u64::MAX)
+        } else {
+            u64::MAX
+        };
+        if let Some(shortfall) = model_manager::disk_space_shortfall(pending, available) {
+            let error_msg = format!(
+                "Not enough disk space to download the {} model ({}) into {:?}",
+                config.model_tier.quality_tier(), shortfall, manager.models_dir()
+            );
+            tracing::error!("❌ {}", error_msg);
+            let error = CommandError::detailed("model_disk_space_insufficient", error_msg, vec![
+                "Free up disk space and try again".to_string(),
+                "Move models to a larger drive with set_models_directory".to_string(),
+                "Choose a smaller quality tier".to_string(),
+            ]);
+            emit_detailed_error(&app_handle, &session_id, &error);
+            return Err(error.to_string());
+
+        }
+    }
+    
     // Initialize WhisperEngine with comprehensive error handling
     tracing::info!("🤖 Initializing Whisper engine asynchronously for session: {} with config: {:?}", session_id, config);
     
-    // Emit mid-progress update
-    if let Err(emit_err) = app_handle.emit("model-progress", serde_json::json!({
-        "sessionId": session_id,
-        "status": "downloading",
-        "progress": 50,
-        "message": "Downloading and loading Whisper model... This may take a few minutes on first run."
-    })) {
-        tracing::error!("Failed to emit model-progress event: {}", emit_err);
+    let cancellation = DownloadCancellation::new();
+    let state = app_handle.state::<AppState>();
+    if let Ok(mut downloads) = state.model_downloads.lock() {
+        downloads.insert(session_id.clone(), cancellation.clone());
     }
     
-    let engine = WhisperEngine::new(config).await
+    let result = WhisperEngine::new_with_download(
+        config,
+        Some(model_progress_callback(app_handle.clone(), session_id.clone())),
+        Some(cancellation),
+    ).await;
+    if let Ok(mut downloads) = state.model_downloads.lock() {
+        downloads.remove(&session_id);
+    }
+    let engine = result
         .map_err(|e| {
             tracing::error!("Whisper engine initialization failed: {}", e);
             format!("Failed to initialize Whisper engine: {}", e)
         })?;
     
     // Emit completion progress
-    if let Err(emit_err) = app_handle.emit("model-progress", serde_json::json!({
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "model-progress", serde_json::json!({
         "sessionId": session_id,
         "status": "ready",
         "progress": 100,
@@ -1246,22 +5866,182 @@ async fn initialize_whisper_engine_async(
     Ok(engine)
 }
 
+/// Forward model download progress as model-progress events, at most once per
+/// percent or every 500ms so a large download does not flood the frontend
+fn model_progress_callback(app_handle: tauri::AppHandle, session_id: String) -> ProgressCallback {
+    let last_emit: std::sync::Mutex<Option<(u64, std::time::Instant)>> = std::sync::Mutex::new(None);
+    Box::new(move |downloaded, total| {
+        let progress = if total > 0 { (downloaded.min(total) * 100 / total).min(100) } else { 0 };
+        if let Ok(mut last) = last_emit.lock() {
+            let due = match *last {
+                Some((last_progress, at)) => progress != last_progress || at.elapsed() >= std::time::Duration::from_millis(500),
+                None => true,
+            };
+            if !due {
+                return;
+            }
+            *last = Some((progress, std::time::Instant::now()));
+        }
+        
+        if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "model-progress", serde_json::json!({
+            "sessionId": session_id,
+            "status": "downloading",
+            "progress": progress,
+            "downloadedBytes": downloaded,
+            "totalBytes": total,
+            "message": format!(
+                "Downloading Whisper model: {:.1} / {:.1} MB",
+                downloaded as f64 / 1_048_576.0,
+                total as f64 / 1_048_576.0
+            )
+        })) {
+            tracing::error!("Failed to emit model-progress event: {}", emit_err);
+        }
+    })
+}
+
+/// Cancel the model download of a starting session. The partial download is
+/// removed and the session is discarded so the user can start over.
+#[tauri::command]
+pub async fn cancel_model_download(
+    session_id: String,
+    app_handle: tauri::AppHandle,
+) -> Result<bool, CommandError> {
+    let state = app_handle.state::<AppState>();
+    let cancellation = state.model_downloads.lock()
+        .map_err(|_| "Model download registry unavailable".to_string())?
+        .remove(&session_id);
+    let Some(cancellation) = cancellation else {
+        return Ok(false);
+    };
+    cancellation.cancel();
+    session_tasks(&state).detach(&session_id);
+    tracing::info!("Model download cancelled for session {}", session_id);
+    
+    let session_state = state.active_sessions.lock().await.remove(&session_id);
+    if let Some(session_state) = session_state {
+        stop_session_capture(&state, &session_id).await;
+        
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let record = build_session_record(&session_state, now);
+        persist_session_end(&state, &record, now, session_store::SESSION_STATUS_INTERRUPTED).await;
+    }
+    
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "model-progress", serde_json::json!({
+        "sessionId": session_id,
+        "status": "cancelled",
+        "message": "Model download cancelled"
+    })) {
+        tracing::error!("Failed to emit model-progress event: {}", emit_err);
+    }
+    state.close_event_log(&session_id);
+    release_power_assertion(&state, &session_id);
+    Ok(true)
+}
+
+/// Emit the system-status event of the session a transcription loop runs,
+/// every SYSTEM_STATUS_INTERVAL until the loop ends or the session is cancelled
+async fn report_system_status(
+    app_handle: tauri::AppHandle,
+    mut loop_updates: tokio::sync::watch::Receiver<LoopStatus>,
+    replay_buffer: SessionAudioBuffer,
+    cancellation: SessionCancellation,
+) {
+    let state = app_handle.state::<AppState>();
+    let resource_sampler = ResourceSampler::start(resource_monitor::SAMPLE_INTERVAL);
+    let mut ticks = tokio::time::interval_at(tokio::time::Instant::now() + SYSTEM_STATUS_INTERVAL, SYSTEM_STATUS_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticks.tick() => {}
+            _ = cancellation.cancelled() => break,
+        }
+        // The loop dropped its sender on the way out
+        if loop_updates.has_changed().is_err() {
+            break;
+        }
+        let loop_status = loop_updates.borrow_and_update().clone();
+        let Some(processing_stats) = state.active_sessions.lock().await
+            .get(&loop_status.session_id)
+            .map(|session_state| session_state.processing_stats)
+        else {
+            break;
+        };
+        let replay = {
+            let buffer = replay_buffer.lock();
+            ReplayBufferStatus::new(buffer.memory_bytes(), buffer.retained_span())
+        };
+        let session_id = loop_status.session_id.clone();
+        let status = SystemStatus::new(loop_status, &processing_stats, resource_sampler.summary(), replay);
+        let payload = serde_json::to_value(&status).unwrap_or_default();
+        if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "system-status", payload) {
+            tracing::warn!("Failed to emit system-status event: {}", emit_err);
+        }
+    }
+}
+
 /// Real-time transcription processing loop with advanced quality improvements
 async fn run_transcription_loop(
-    session_id: String,
+    mut session_id: String,
     app_handle: tauri::AppHandle,
+    cancellation: SessionCancellation,
 ) -> Result<(), String> {
     let state = app_handle.state::<AppState>();
     let mut audio_level_counter = 0;
+    let mut noise_floor = NoiseFloorEstimator::new();
     let mut transcription_counter = 0;
     
     // Enhanced audio buffering with intelligent boundary detection
     let mut audio_buffer: Vec<f32> = Vec::new();
     let mut buffer_timestamp = std::time::SystemTime::now();
     let mut consecutive_silence_chunks = 0;
+    // Segment and speaker times are offsets into the captured audio stream,
+    // on the clock the session state owns
+    let stream_clock = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id).map(|s| s.clock.clone()).unwrap_or_default()
+    };
+    let replay_buffer = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id).map(|s| s.audio_buffer.clone()).unwrap_or_else(|| SessionAudioBuffer::new(0))
+    };
+    let heartbeat = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id).map(|s| s.heartbeat.clone()).unwrap_or_default()
+    };
+    // This session's capture; other sessions' loops read their own
+    let capture = session_capture(&state, &session_id).await;
     
     // Initialize advanced transcription quality modules
     let mut content_hasher = ContentHasher::new(8, 0.6); // 8 segments, 60% similarity threshold
+    // Text of the last stored segment, for trimming words the next buffer hears again
+    let mut previous_segment_text: Option<String> = None;
+    let boundary_overlap_words = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .map(|s| s.config.boundary_overlap_words)
+            .unwrap_or(content_hasher::DEFAULT_OVERLAP_WORDS)
+    };
+    // Text Whisper produces on silence; in debug mode it is kept and flagged
+    let (mut hallucination_filter, hallucination_debug) = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .filter(|s| s.config.hallucination_filter)
+            .map(|s| (
+                Some(HallucinationFilter::new(s.config.hallucination_blocklist.as_deref().unwrap_or_default(), s.config.hallucination_max_repeats)),
+                s.config.hallucination_debug,
+            ))
+            .unwrap_or((None, false))
+    };
+    let keyword_matcher = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .map(|s| KeywordMatcher::new(&s.config.keywords))
+            .filter(|matcher| !matcher.is_empty())
+    };
+    let mut buffer_speech = BufferSpeech::default();
     let mut temporal_analyzer = TemporalAnalyzer::new(10, 0.3, 0.1); // 10 segments, 0.3s overlap, 0.1s gap
     let boundary_config = BoundaryConfig {
         silence_threshold: 0.015,
@@ -1274,37 +6054,297 @@ async fn run_transcription_loop(
     };
     let mut boundary_detector = BoundaryDetector::new(boundary_config);
     
-    // Enhanced buffering parameters for complete utterances
-    const MIN_AUDIO_DURATION_MS: u64 = 4500; // 4.5 seconds minimum for complete thoughts
-    const MAX_AUDIO_DURATION_MS: u64 = 20000; // 20 seconds maximum (even longer for complex thoughts)
-    const SILENCE_THRESHOLD: f32 = 0.015; // More sensitive silence detection
-    const MAX_BUFFER_SIZE: usize = 16000 * 20; // 20 seconds at 16kHz
+    // Optional punctuation restoration for output that arrives unpunctuated
+    let punctuation_restorer = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .filter(|s| s.config.punctuation_restoration)
+            .map(|s| PunctuationRestorer::new(PunctuationConfig::with_language_rules(&s.config.punctuation_rules)))
+    };
+    
+    // Buffer window: 4.5 s minimum for complete thoughts, 20 s maximum by default
+    let chunk_window = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id).map(|s| s.chunk_window).unwrap_or_default()
+    };
+    let max_buffer_size = chunk_window.max_buffer_samples();
+    const PROMPT_CONTEXT_SEGMENTS: usize = 5; // Previous segments offered as decoder context
+    
+    // Speech-rate driven window sizing, bounded to 2.5s-8s when enabled
+    let mut chunk_sizer = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .filter(|s| s.config.adaptive_chunking)
+            .map(|_| AdaptiveChunkSizer::new(2500, 8000, chunk_window.min_ms))
+    };
+    
+    // Errors seen since the last session state update
+    let mut pending_error_count = 0u32;
+    
+    // Speaker name suggestions from self-introductions early in the session
+    let mut name_suggester = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .map(|s| s.config.name_suggestion_window.unwrap_or(speaker_naming::DEFAULT_WINDOW_SECS))
+            .filter(|&window| window > 0)
+            .map(SpeakerNameSuggester::new)
+    };
+    
+    // Prompt budget of the latest transcription and temperature fallback
+    // counters of this session, reported with the system status
+    let mut last_prompt_budget: Option<PromptBudget> = None;
+    let mut fallback_stats = FallbackStats::default();
+    // Device the session's engine runs inference on, for the system status
+    let mut inference_device: Option<crate::asr::types::Device> = None;
+    
+    // Capture channel drops and depth, reported with the system status
+    let mut channel_health: Option<ChannelHealth> = None;
+    let mut drop_warnings = DropWarnings::default();
+    
+    // Speaker count estimate, created once diarization produces embeddings
+    let mut speaker_estimator: Option<SpeakerCountEstimator> = None;
+    
+    // Mixed microphone + system capture: speech carried by the microphone is the local user
+    let mut source_attribution = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .filter(|s| s.config.audio_sources.microphone && s.config.audio_sources.system_audio)
+            .map(|_| SourceAttribution::default())
+    };
+    
+    // Session audio recording, started with the first chunk when sessionAudioDir is set
+    let mut recorder: Option<ResilientRecordingWriter> = None;
+    let mut recording_checked = false;
+    
+    // Segments are saved in batches; the full transcript is rewritten when the session ends
+    const SEGMENT_PERSIST_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+    /// Talk-time statistics are pushed after every this many stored segments
+    const SPEAKER_STATS_EVERY_SEGMENTS: usize = 5;
+    let mut persisted_segments = 0usize;
+    let mut last_segment_persist = std::time::Instant::now();
+    // Crash recovery snapshots of the transcript, every autosaveIntervalSeconds
+    let autosave_interval = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .map(|s| s.config.autosave_interval_seconds)
+            .filter(|&seconds| seconds > 0)
+            .map(|seconds| std::time::Duration::from_secs(seconds as u64))
+    };
+    let mut last_autosave = std::time::Instant::now();
+    
+    // Speech gating: the RMS level, or the energy scorer and its utterance endpoints when energyVad is set
+    let mut vad = state.active_sessions.lock().await
+        .get(&session_id)
+        .is_some_and(|s| s.config.energy_vad)
+        .then(EnergyVad::new);
+    let mut utterances = UtteranceTracker::default();
+    
+    // Provisional results for the utterance being buffered, when partialResults is set.
+    // A partialTier other than the session's loads its own engine in the background.
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let partial_threads = partials::partial_threads(cores, 4);
+    let (mut partial_scheduler, mut partial_engine_loading) = {
+        let sessions_guard = state.active_sessions.lock().await;
+        match sessions_guard.get(&session_id).filter(|s| s.config.partial_results) {
+            Some(session_state) => {
+                let config = &session_state.config;
+                let scheduler = PartialScheduler::new(std::time::Duration::from_millis(config.partial_interval_ms), cores);
+                let loading = config.partial_tier.as_deref()
+                    .map(crate::asr::types::ModelTier::from)
+                    .filter(|&tier| tier != crate::asr::types::ModelTier::from(config.quality_tier.as_str()))
+                    .map(|model_tier| {
+                        let mut whisper_config = WhisperConfig {
+                            model_tier,
+                            device: crate::asr::types::Device::Auto,
+                            num_threads: partial_threads,
+                            beam_size: 1,
+                            language: config.languages.first().copied(),
+                            enable_word_timestamps: false,
+                            custom_vocabulary: config.custom_vocabulary.clone(),
+                            ..Default::default()
+                        };
+                        translation::configure(&mut whisper_config, &config.mode);
+                        tokio::spawn(WhisperEngine::new(whisper_config))
+                    });
+                (Some(scheduler), loading)
+            }
+            None => (None, None),
+        }
+    };
+    let mut partial_engine: Option<WhisperEngine> = None;
+    
+    // allowAdaptiveTier: a session that keeps falling behind moves to a faster tier
+    let mut tier_adapter = state.active_sessions.lock().await
+        .get(&session_id)
+        .filter(|session_state| session_state.config.allow_adaptive_tier)
+        .map(|session_state| TierAdapter::new(session_state.whisper_config.model_tier, session_state.config.adaptive_tier_threshold));
+    let mut faster_engine_loading: Option<(WhisperConfig, tokio::task::JoinHandle<Result<WhisperEngine, crate::asr::types::ASRError>>)> = None;
+    let mut buffer_started_at = std::time::Instant::now();
+    
+    // Lost capture device: polled for until it (or the default device) is back
+    let mut device_outage: Option<DeviceOutage> = None;
+    let mut last_chunk_at = std::time::Instant::now();
+    // Outage length (seconds) to mark on the next segment
+    let mut gap_before: Option<f64> = None;
+    
+    // Created when noise suppression is first switched on
+    let mut noise_suppressor: Option<NoiseSuppressor> = None;
+    let mut suppression_bypassed = false;
+    // Rebuilt when the capture's gain control settings change
+    let mut gain_control: Option<AutomaticGainControl> = None;
+    // Reported by a task of its own every SYSTEM_STATUS_INTERVAL; it stops when this loop drops the sender
+    let loop_status = {
+        let (sender, receiver) = tokio::sync::watch::channel(LoopStatus { session_id: session_id.clone(), ..LoopStatus::default() });
+        tauri::async_runtime::spawn(report_system_status(app_handle.clone(), receiver, replay_buffer.clone(), cancellation.clone()));
+        sender
+    };
+    // maxDurationMinutes and autoStopAfterSilenceMinutes; they span rollovers
+    let mut auto_stop = {
+        let sessions_guard = state.active_sessions.lock().await;
+        sessions_guard.get(&session_id)
+            .map(|s| AutoStopTimer::new(s.config.max_duration_minutes, s.config.auto_stop_after_silence_minutes))
+            .filter(AutoStopTimer::is_enabled)
+    };
     
     // Main processing loop - continue until session is stopped
     loop {
-        // Check if session still exists
-        {
-            let sessions_guard = state.active_sessions.lock().await;
-            if !sessions_guard.contains_key(&session_id) {
-                tracing::info!("Session {} ended, stopping transcription loop", session_id);
-                break;
+        if cancellation.is_cancelled() {
+            tracing::info!("Session {} cancelled, stopping transcription loop", session_id);
+            break;
+        }
+        heartbeat.beat();
+        
+        // Check if session still exists; the VAD threshold and noise suppression can change while it runs
+        let (paused, vad_threshold, noise_suppression) = {
+            let mut sessions_guard = state.active_sessions.lock().await;
+            match sessions_guard.get_mut(&session_id) {
+                Some(session_state) => {
+                    session_state.error_count += pending_error_count;
+                    pending_error_count = 0;
+                    (session_state.status == "paused", session_state.config.vad_threshold, session_state.config.enable_noise_suppression)
+                }
+                None => {
+                    tracing::info!("Session {} ended, stopping transcription loop", session_id);
+                    break;
+                }
+            }
+        };
+        
+        if last_segment_persist.elapsed() >= SEGMENT_PERSIST_INTERVAL {
+            persisted_segments = persist_new_segments(&state, &session_id, persisted_segments).await;
+            last_segment_persist = std::time::Instant::now();
+        }
+        if autosave_interval.is_some_and(|interval| last_autosave.elapsed() >= interval) {
+            autosave_session(&state, &session_id).await;
+            last_autosave = std::time::Instant::now();
+        }
+        
+        // Roll over into a continuation session when maxSessionDuration is reached.
+        // The in-flight audio buffer is kept so no audio is lost across the boundary.
+        if let Some(next_session_id) = roll_over_session_if_due(&session_id, &app_handle).await {
+            if let Some(writer) = recorder.take() {
+                finish_session_recording(&app_handle, &session_id, writer).await;
+            }
+            recording_checked = false;
+            persisted_segments = 0;
+            // The finished part is stored; its continuation gets an autosave of its own
+            remove_session_autosave(&state, &session_id).await;
+            session_tasks(&state).rename(&session_id, &next_session_id);
+            {
+                let mut captures_guard = state.audio_captures.lock().await;
+                if let Some(capture) = captures_guard.remove(&session_id) {
+                    captures_guard.insert(next_session_id.clone(), capture);
+                }
+            }
+            session_id = next_session_id;
+            temporal_analyzer = TemporalAnalyzer::new(10, 0.3, 0.1);
+            stream_clock.rebase();
+        }
+        
+        if device_outage.is_none() {
+            let lost = match &capture {
+                Some(capture) => {
+                    let capture = capture.lock().await;
+                    capture.is_capturing() && (capture.is_device_lost() || reconnect::is_stalled(capture.source(), last_chunk_at, std::time::Instant::now()))
+                }
+                None => false,
+            };
+            if lost {
+                tracing::warn!("Audio device lost during session {}, waiting for it to come back", session_id);
+                device_outage = Some(DeviceOutage::new(std::time::Instant::now(), stream_clock.consumed_seconds()));
+                if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "transcription-error", serde_json::json!({
+                    "type": "device_disconnected",
+                    "message": "Audio device disconnected. Recording resumes when it is reconnected.",
+                    "sessionId": session_id,
+                    "streamOffset": stream_clock.consumed_seconds(),
+                    "timestamp": std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis(),
+                    "severity": "warning"
+                })) {
+                    tracing::warn!("Failed to emit device_disconnected event: {}", emit_err);
+                }
+            }
+        }
+        if let Some(outage) = device_outage.as_mut() {
+            let now = std::time::Instant::now();
+            if outage.attempt_due(now) {
+                outage.record_attempt(now);
+                let reconnected = match &capture {
+                    Some(capture) => capture.lock().await.reconnect().await,
+                    None => Err(AudioError::NoFallbackDevice),
+                };
+                match reconnected {
+                    Ok(()) => {
+                        let gap = outage.gap(now).as_secs_f64();
+                        tracing::info!("Audio device reconnected after {:.1}s ({} attempts)", gap, outage.attempts());
+                        if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "audio-device-reconnected", serde_json::json!({
+                            "sessionId": session_id,
+                            "streamOffset": outage.stream_offset(),
+                            "gapSeconds": gap,
+                            "attempts": outage.attempts()
+                        })) {
+                            tracing::warn!("Failed to emit audio-device-reconnected event: {}", emit_err);
+                        }
+                        gap_before = Some(gap);
+                        utterances.reset();
+                        device_outage = None;
+                        last_chunk_at = now;
+                    }
+                    Err(e) => tracing::debug!("Audio device not back yet (attempt {}): {}", outage.attempts(), e),
+                }
+            }
+            if device_outage.is_some() {
+                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+                continue;
             }
         }
         
         // Get audio data from capture service with timeout
+        let mut input_levels = Vec::new();
+        let mut agc_config = AgcConfig::default();
+        let mut resampling = None;
         let audio_data = {
-            let mut audio_capture_guard = state.audio_capture_service.lock().await;
-            if let Some(ref mut capture_service) = *audio_capture_guard {
+            if let Some(capture) = &capture {
+                let mut capture_service = capture.lock().await;
                 // Use timeout to prevent blocking indefinitely
                 match tokio::time::timeout(
                     tokio::time::Duration::from_millis(200), // 200ms timeout
                     capture_service.get_next_chunk()
                 ).await {
-                    Ok(Ok(audio_data)) => Some(audio_data),
+                    Ok(Ok(audio_data)) => {
+                        input_levels = capture_service.input_levels().to_vec();
+                        agc_config = capture_service.agc_config();
+                        resampling = capture_service.resampling_report();
+                        channel_health = Some(capture_service.channel_health());
+                        Some(audio_data)
+                    }
                     Ok(Err(e)) => {
                         tracing::warn!("Failed to get audio chunk: {}", e);
+                        pending_error_count += 1;
                         // Emit audio error
-                        let _ = app_handle.emit("transcription-error", serde_json::json!({
+                        let _ = emit_session_event(&app_handle, &session_id, "transcription-error", serde_json::json!({
                             "type": "audio_capture_failed",
                             "message": format!("Audio capture error: {}", e),
                             "sessionId": session_id,
@@ -1327,9 +6367,120 @@ async fn run_transcription_loop(
             }
         };
         
-        if let Some(audio_data) = audio_data {
-            // Calculate audio level (RMS)
-            let audio_level = calculate_audio_level(&audio_data.samples);
+        if audio_data.is_some() {
+            last_chunk_at = std::time::Instant::now();
+        }
+        
+        // Chunks dropped because the loop fell behind leave holes in the transcript
+        if let Some(health) = channel_health.filter(|health| drop_warnings.should_warn(health)) {
+            tracing::warn!("Audio capture dropped {} chunks ({} ms) for session {}", health.dropped_chunks, health.dropped_ms, session_id);
+            let message = match backpressure::faster_tier(&config.quality_tier) {
+                Some(tier) => format!(
+                    "Transcription is falling behind: {:.1}s of audio was dropped. Try the faster '{}' model tier.",
+                    health.dropped_ms as f64 / 1000.0, tier
+                ),
+                None => format!(
+                    "Transcription is falling behind: {:.1}s of audio was dropped. Close other demanding applications.",
+                    health.dropped_ms as f64 / 1000.0
+                ),
+            };
+            if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "transcription-error", serde_json::json!({
+                "type": "audio_backpressure",
+                "message": message,
+                "sessionId": session_id,
+                "timestamp": std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis(),
+                "severity": "warning",
+                "backpressure": health
+            })) {
+                tracing::error!("Failed to emit backpressure warning: {}", emit_err);
+            }
+        }
+        
+        // Audio captured while paused is drained and discarded
+        if paused {
+            continue;
+        }
+        
+        if let Some(mut audio_data) = audio_data {
+            if !recording_checked {
+                recorder = start_session_recording(&state, &session_id, audio_data.sample_rate, stream_clock.consumed_seconds()).await;
+                recording_checked = true;
+            }
+            if let Some(ref mut writer) = recorder {
+                if let Some(health) = writer.push(&audio_data.samples) {
+                    emit_recording_health(&app_handle, &session_id, health, writer.stopped_by());
+                }
+                if writer.near_limit() {
+                    let max_minutes = state.active_sessions.lock().await
+                        .get(&session_id)
+                        .map(|s| s.config.max_recording_minutes)
+                        .unwrap_or(DEFAULT_MAX_RECORDING_MINUTES);
+                    emit_recording_limit_warning(&app_handle, &session_id, max_minutes);
+                }
+            }
+            
+            let chunk_start = stream_clock.advance(audio_data.samples.len(), audio_data.sample_rate);
+            replay_buffer.lock().push(&audio_data.samples, audio_data.sample_rate, chunk_start);
+            
+            // Peak/RMS of what transcription receives; `audio_level` keeps the old 0-1 scale
+            let level = levels::measure(&audio_data.samples);
+            let noise_floor = noise_floor.observe(level.rms_dbfs);
+            let audio_level = level.legacy_level();
+            let speech_level = levels::vad_level_threshold(vad_threshold);
+            
+            // Noise suppression after resampling and the level meter, so the VAD,
+            // the buffer and diarization get the cleaned audio; the recording keeps the raw input
+            if noise_suppression {
+                let suppressor = match noise_suppressor.take() {
+                    Some(suppressor) if suppressor.sample_rate() == audio_data.sample_rate => suppressor,
+                    _ => NoiseSuppressor::new(audio_data.sample_rate),
+                };
+                let suppressor = noise_suppressor.insert(suppressor);
+                if suppression_bypassed {
+                    suppressor.reset();
+                    suppression_bypassed = false;
+                }
+                audio_data.samples = suppressor.process(&audio_data.samples);
+            } else {
+                suppression_bypassed = true;
+            }
+            
+            let speech_probability = match vad.as_mut() {
+                Some(vad) => vad.speech_probability(&audio_data.samples),
+                None => levels::level_probability(audio_level),
+            };
+            let chunk_seconds = audio_data.samples.len() as f64 / audio_data.sample_rate.max(1) as f64;
+            let decision = utterances.observe(speech_probability, vad_threshold, chunk_seconds);
+            
+            // With the energy VAD only speech and the hangover after it are buffered;
+            // RMS gating keeps everything from the first loud chunk on
+            let is_speech = match vad {
+                Some(_) => decision == ChunkDecision::Speech,
+                None => speech_probability > vad_threshold,
+            };
+            let keeps_silence = vad.is_none() || decision.buffers_chunk();
+            
+            match auto_stop.as_mut().and_then(|timer| timer.observe(chunk_seconds, is_speech)) {
+                Some(AutoStopEvent::Warning { reason, remaining_seconds }) => {
+                    emit_auto_stop_warning(&app_handle, &session_id, reason, remaining_seconds);
+                }
+                Some(AutoStopEvent::Stop(reason)) => spawn_auto_stop(&app_handle, &session_id, reason),
+                None => {}
+            }
+            
+            // Gain control follows the VAD decision so pauses are not amplified
+            let agc_gain_db = if agc_config.enabled {
+                if gain_control.as_ref().map(AutomaticGainControl::config) != Some(agc_config) {
+                    gain_control = Some(AutomaticGainControl::new(agc_config));
+                }
+                gain_control.as_mut().map(|agc| agc.process(&mut audio_data.samples, audio_data.sample_rate, is_speech))
+            } else {
+                gain_control = None;
+                None
+            };
             
             // Create audio chunk for boundary detection
             let audio_chunk = AudioChunk {
@@ -1344,9 +6495,31 @@ async fn run_transcription_loop(
             
             // Emit audio level updates every few chunks for UI responsiveness
             if audio_level_counter % 3 == 0 { // ~30fps if chunks are 100ms
-                if let Err(emit_err) = app_handle.emit("audio-level", serde_json::json!({
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64;
+                if let Some(session_state) = state.active_sessions.lock().await.get_mut(&session_id) {
+                    session_state.progress.record_level(&level, now_ms);
+                }
+                if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "audio-level", serde_json::json!({
                     "level": audio_level,
-                    "vadActivity": audio_level > 0.02, // Simple VAD threshold
+                    "peakDbfs": level.peak_dbfs,
+                    "rmsDbfs": level.rms_dbfs,
+                    "clipping": level.clipping || input_levels.iter().any(|c| c.clipping),
+                    // Device channels before resampling and downmix
+                    "channels": input_levels,
+                    "noiseFloorDbfs": noise_floor,
+                    "snrDb": (level.rms_dbfs - noise_floor).max(0.0),
+                    // Speech probability (0-1) of the chunk
+                    "vadActivity": speech_probability,
+                    "vadThreshold": vad_threshold,
+                    "vadMode": if vad.is_some() { "energy" } else { "rms" },
+                    "inUtterance": utterances.in_utterance(),
+                    // Threshold on the `level` scale, for drawing it on the meter
+                    "vadLevelThreshold": speech_level,
+                    // Gain control applied to the chunk (dB), null when off
+                    "agcGainDb": agc_gain_db,
                     "boundaryType": match boundary_type {
                         BoundaryType::None => "none",
                         BoundaryType::SoftBoundary => "soft",
@@ -1364,8 +6537,17 @@ async fn run_transcription_loop(
             }
             audio_level_counter += 1;
             
-            // Enhanced buffering with intelligent boundary detection
-            let is_speech = audio_level > SILENCE_THRESHOLD;
+            if decision == ChunkDecision::Discard && vad.is_some() && !audio_buffer.is_empty() {
+                tracing::debug!("Dropping {:.2}s utterance with too little speech", audio_buffer.len() as f64 / audio_data.sample_rate.max(1) as f64);
+                retract_partial(&app_handle, &session_id, partial_scheduler.as_mut().and_then(PartialScheduler::finish));
+                audio_buffer.clear();
+                buffer_speech.reset();
+                stream_clock.clear_buffer();
+                if let Some(ref mut attribution) = source_attribution {
+                    attribution.reset();
+                }
+                consecutive_silence_chunks = 0;
+            }
             
             if is_speech {
                 // Reset silence counter when speech detected
@@ -1374,44 +6556,88 @@ async fn run_transcription_loop(
                 // Reset buffer timestamp on first audio activity
                 if audio_buffer.is_empty() {
                     buffer_timestamp = std::time::SystemTime::now();
+                    buffer_started_at = std::time::Instant::now();
+                    stream_clock.begin_buffer(chunk_start);
                     tracing::debug!("Starting new audio buffer at speech onset");
                 }
                 
                 // Add samples to buffer
                 audio_buffer.extend_from_slice(&audio_data.samples);
-            } else if !audio_buffer.is_empty() {
+                buffer_speech.observe(speech_probability);
+                if let Some(ref mut attribution) = source_attribution {
+                    attribution.observe(&audio_data);
+                }
+            } else if keeps_silence && !audio_buffer.is_empty() {
                 // We have silence but there's audio in the buffer
                 consecutive_silence_chunks += 1;
                 
                 // Still add silence to buffer (important for natural speech)
                 audio_buffer.extend_from_slice(&audio_data.samples);
+                buffer_speech.observe(speech_probability);
+                if let Some(ref mut attribution) = source_attribution {
+                    attribution.observe(&audio_data);
+                }
             }
             
             // Prevent buffer from growing too large
-            if audio_buffer.len() > MAX_BUFFER_SIZE {
-                let excess = audio_buffer.len() - MAX_BUFFER_SIZE;
+            if audio_buffer.len() > max_buffer_size {
+                let excess = audio_buffer.len() - max_buffer_size;
                 audio_buffer.drain(0..excess);
+                stream_clock.trim_buffer(excess, audio_data.sample_rate);
                 tracing::warn!("Audio buffer exceeded maximum size, trimmed {} samples", excess);
             }
             
             // Determine if we should send buffer to transcription using intelligent boundary detection
             let buffer_duration_ms = buffer_timestamp.elapsed().unwrap_or_default().as_millis() as u64;
             
-            // Use boundary detector to make intelligent transcription decisions
-            let should_transcribe = !audio_buffer.is_empty() && (
-                // Hard boundary detected with sufficient content
-                (matches!(boundary_type, BoundaryType::HardBoundary | BoundaryType::SentenceEnd) 
-                 && buffer_duration_ms >= MIN_AUDIO_DURATION_MS) ||
-                // Soft boundary with longer content (more conservative)
-                (matches!(boundary_type, BoundaryType::SoftBoundary) 
-                 && buffer_duration_ms >= MIN_AUDIO_DURATION_MS + 2000) ||
+            // Window for the measured speech rate when adaptive chunking is enabled
+            let window_ms = chunk_sizer.as_ref().map_or(chunk_window.min_ms, AdaptiveChunkSizer::window_ms);
+            
+            // The energy VAD flushes at utterance ends; RMS gating relies on the boundary detector
+            let buffered_ms = audio_buffer.len() as u64 * 1000 / audio_data.sample_rate.max(1) as u64;
+            let should_transcribe = !audio_buffer.is_empty() && if vad.is_some() {
+                decision == ChunkDecision::End || buffered_ms >= chunk_window.max_ms
+            } else {
+                // Hard boundaries flush once the window for the measured speech rate is
+                // filled, soft boundaries only well past it
+                boundary_flush_due(&boundary_type, buffer_duration_ms, window_ms) ||
                 // Maximum duration reached (fallback)
-                buffer_duration_ms >= MAX_AUDIO_DURATION_MS ||
+                buffer_duration_ms >= chunk_window.max_ms ||
                 // Override: boundary detector suggests not to continue buffering
                 !boundary_detector.should_continue_buffering(buffer_duration_ms)
-            );
+            };
+            
+            // Provisional text while the utterance is still being buffered
+            if !should_transcribe && !audio_buffer.is_empty() {
+                if let Some(scheduler) = partial_scheduler.as_mut() {
+                    let buffered_seconds = audio_buffer.len() as f64 / audio_data.sample_rate.max(1) as f64;
+                    if scheduler.is_due(std::time::Instant::now(), buffered_seconds, buffer_started_at) {
+                        if let Some(loading) = partial_engine_loading.take_if(|loading| loading.is_finished()) {
+                            match loading.await {
+                                Ok(Ok(engine)) => partial_engine = Some(engine),
+                                Ok(Err(e)) => tracing::warn!("Partial tier engine failed to load, using the session's engine: {}", e),
+                                Err(e) => tracing::warn!("Partial tier engine task failed: {}", e),
+                            }
+                        }
+                        let buffered_audio = AudioData {
+                            samples: audio_buffer.clone(),
+                            sample_rate: audio_data.sample_rate,
+                            channels: 1,
+                            timestamp: buffer_timestamp,
+                            source_channel: audio_data.source_channel,
+                            duration_seconds: buffered_seconds as f32,
+                        };
+                        let context = recent_transcript_context(&state, &session_id, PROMPT_CONTEXT_SEGMENTS).await;
+                        let span = stream_clock.buffer_span(audio_buffer.len(), audio_data.sample_rate);
+                        run_partial_pass(&app_handle, &state, &session_id, partial_engine.as_ref(), partial_threads,
+                                         &buffered_audio, &context, span, scheduler).await;
+                    }
+                }
+            }
             
             if should_transcribe {
+                    // The final segment takes over the id of the partials shown for it
+                    let mut partial_id = partial_scheduler.as_mut().and_then(PartialScheduler::finish);
                     tracing::info!("Processing buffered audio: {} samples, {:.2}s duration", 
                                  audio_buffer.len(), buffer_duration_ms as f32 / 1000.0);
                     
@@ -1421,20 +6647,38 @@ async fn run_transcription_loop(
                         sample_rate: audio_data.sample_rate,
                         channels: 1,
                         timestamp: buffer_timestamp,
-                        source_channel: AudioSource::Microphone,
+                        source_channel: audio_data.source_channel,
                         duration_seconds: audio_buffer.len() as f32 / audio_data.sample_rate as f32,
                     };
                     
+                    // With rollingPrompt, recent segment texts condition the decoder; the engine fits them into its prompt budget
+                    let context = recent_transcript_context(&state, &session_id, PROMPT_CONTEXT_SEGMENTS).await;
+                    
                     // Transcribe buffered audio using Whisper engine
+                    let mut transcribe_elapsed = None;
+                    let mut transcribed_tier = None;
                     let transcription_result = {
-                        let whisper_guard = state.whisper_engine.lock().await;
-                        if let Some(ref engine) = *whisper_guard {
-                            let context = TranscriptionContext::default();
-                            match engine.transcribe(&buffered_audio, &context).await {
-                                Ok(result) => Some(result),
+                        if let Some(transcriber) = session_transcriber(&state, &session_id).await {
+                            transcribed_tier = transcriber.model_tier();
+                            let transcribe_started = std::time::Instant::now();
+                            let result = tokio::select! {
+                                result = transcriber.transcribe(&buffered_audio, &context) => result,
+                                _ = cancellation.cancelled() => break,
+                            };
+                            transcribe_elapsed = Some(transcribe_started.elapsed());
+                            inference_device = Some(transcriber.device());
+                            match result {
+                                Ok(result) => {
+                                    if result.prompt_budget.is_some() {
+                                        last_prompt_budget = result.prompt_budget.clone();
+                                    }
+                                    fallback_stats.record(&result);
+                                    Some(result)
+                                }
                                 Err(e) => {
                                     tracing::warn!("Transcription failed: {}", e);
-                                    if let Err(emit_err) = app_handle.emit("transcription-error", serde_json::json!({
+                                    pending_error_count += 1;
+                                    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "transcription-error", serde_json::json!({
                                         "type": "transcription_failed",
                                         "message": format!("Transcription error: {}", e),
                                         "sessionId": session_id,
@@ -1452,7 +6696,7 @@ async fn run_transcription_loop(
                         } else {
                             tracing::warn!("No Whisper engine available - model may still be downloading");
                             // Emit status update to inform frontend that model is not ready
-                            if let Err(emit_err) = app_handle.emit("model-status", serde_json::json!({
+                            if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "model-status", serde_json::json!({
                                 "sessionId": session_id,
                                 "status": "downloading",
                                 "message": "Whisper model is still being downloaded. Transcription will begin once ready."
@@ -1462,10 +6706,54 @@ async fn run_transcription_loop(
                             None
                         }
                     };
+                    if let Some(elapsed) = transcribe_elapsed {
+                        if let Some(session_state) = state.active_sessions.lock().await.get_mut(&session_id) {
+                            session_state.processing_stats.record(elapsed, buffered_audio.duration_seconds as f64);
+                            if let Ok(latency) = audio_data.timestamp.elapsed() {
+                                session_state.processing_stats.record_latency(latency);
+                            }
+                        }
+                    }
+                    
+                    // Tier switches happen here, between utterances, never mid-utterance
+                    if let Some(adapter) = tier_adapter.as_mut() {
+                        if let Some((config, loading)) = faster_engine_loading.take_if(|(_, loading)| loading.is_finished()) {
+                            match loading.await {
+                                Ok(Ok(engine)) => {
+                                    let lease = register_engine(&state, config.model_tier, engine);
+                                    complete_tier_switch(&state, &app_handle, &session_id, adapter, config, lease).await;
+                                }
+                                Ok(Err(e)) => {
+                                    tracing::warn!("Faster tier engine failed to load: {}", e);
+                                    adapter.switch_failed(config.model_tier);
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Faster tier engine task failed: {}", e);
+                                    adapter.switch_failed(config.model_tier);
+                                }
+                            }
+                        }
+                        if let Some(next_tier) = transcribe_elapsed.and_then(|elapsed| adapter.record(elapsed, buffered_audio.duration_seconds)) {
+                            faster_engine_loading = begin_tier_switch(&state, &app_handle, &session_id, adapter, next_tier).await;
+                        }
+                    }
                 
                 // Emit transcription updates if we got text
                 if let Some(result) = transcription_result {
-                    let cleaned_text = result.text.trim();
+                    let mut cleaned_text = result.text.trim();
+                    
+                    // Overlapping buffers repeat the tail of the previous segment
+                    if let Some(ref previous) = previous_segment_text {
+                        let trimmed = content_hasher::trim_repeated_boundary(previous, cleaned_text, boundary_overlap_words);
+                        if trimmed.len() < cleaned_text.len() {
+                            tracing::debug!("Trimmed repeated boundary text '{}'", &cleaned_text[..cleaned_text.len() - trimmed.len()]);
+                            cleaned_text = trimmed;
+                        }
+                    }
+                    
+                    if let Some(ref mut sizer) = chunk_sizer {
+                        sizer.observe(&result.words);
+                    }
                     
                     // Advanced duplicate detection using semantic content hashing
                     let current_time = std::time::SystemTime::now()
@@ -1473,16 +6761,35 @@ async fn run_transcription_loop(
                         .unwrap_or_default()
                         .as_secs() as f32;
                     
+                    let hallucination = match hallucination_filter.as_mut() {
+                        Some(filter) if !cleaned_text.is_empty() => filter.check(cleaned_text, buffer_speech.mean()),
+                        _ => None,
+                    };
+                    if let Some(reason) = hallucination {
+                        tracing::info!("Likely hallucination ({}), {} characters", reason.as_str(), cleaned_text.chars().count());
+                        tracing::debug!("Likely hallucination text: '{}'", cleaned_text);
+                        let mut sessions_guard = state.active_sessions.lock().await;
+                        if let Some(session_state) = sessions_guard.get_mut(&session_id) {
+                            session_state.processing_stats.record_hallucination();
+                        }
+                    }
+                    
                     // Check for duplicates using advanced content hasher
                     let is_duplicate = cleaned_text.is_empty() 
                         || cleaned_text.contains("[BLANK_AUDIO]")
                         || cleaned_text.contains("[INAUDIBLE]")
+                        || (hallucination.is_some() && !hallucination_debug)
                         || content_hasher.is_duplicate(cleaned_text, current_time);
                     
                     if !is_duplicate {
-                        tracing::info!("Emitting transcription update: '{}'", cleaned_text);
+                        tracing::debug!("Emitting transcription update: '{}'", cleaned_text);
+                        previous_segment_text = Some(cleaned_text.to_string());
                         
                         // Determine speaker ID using diarization if enabled
+                        let mut segment_embedding = None;
+                        let mut overlapping_speakers = Vec::new();
+                        let mut attribution = None;
+                        let mut near_miss = None;
                         let speaker_id = {
                             let sessions_guard = state.active_sessions.lock().await;
                             let enable_diarization = sessions_guard.get(&session_id)
@@ -1496,40 +6803,116 @@ async fn run_transcription_loop(
                                 if let Some(ref diarization) = *diarization_guard {
                                     // Extract embeddings and identify speaker
                                     match diarization.extract_speaker_embeddings(&buffered_audio.samples, buffered_audio.sample_rate).await {
-                                        Ok(embeddings) if !embeddings.is_empty() => {
+                                        Ok(mut embeddings) if !embeddings.is_empty() => {
+                                            // Embeddings are timed within the buffer; move them onto the session timeline
+                                            let (buffer_start, _) = stream_clock.buffer_span(buffered_audio.samples.len(), buffered_audio.sample_rate);
+                                            SessionClock::place_embeddings(&mut embeddings, buffer_start);
+                                            // The chunk is attributed to the voice heard longest; other voices are cross-talk
+                                            let voices = overlap::group_voices(&embeddings, diarization.get_config().similarity_threshold);
+                                            overlapping_speakers = overlapping_speaker_ids(&state, &session_id, diarization, &voices[1..]).await;
+                                            let embeddings = &voices[0].embeddings;
+                                            segment_embedding = Some(embeddings[0].clone());
+                                            
+                                            // Track recent embeddings and re-cluster to keep the speaker count estimate current
+                                            let tracked = {
+                                                let mut sessions_guard = state.active_sessions.lock().await;
+                                                sessions_guard.get_mut(&session_id).map(|s| {
+                                                    s.speaker_embeddings.push(embeddings[0].clone());
+                                                    if s.speaker_embeddings.len() > MAX_SPEAKER_ESTIMATE_EMBEDDINGS {
+                                                        s.speaker_embeddings.remove(0);
+                                                    }
+                                                    s.speaker_embeddings.clone()
+                                                })
+                                            };
+                                            let estimator = speaker_estimator.get_or_insert_with(|| speaker_count_estimator(diarization.get_config()));
+                                            // Re-clustering every retained embedding is costly; stop_transcription re-clusters once more
+                                            if let Some(tracked) = tracked.filter(|_| estimator.observe()) {
+                                                update_speaker_estimate(&app_handle, &state, &session_id, diarization, &tracked, estimator).await;
+                                            }
+                                            
                                             // Try to reidentify existing speaker
                                             match diarization.reidentify_speaker(&embeddings[0]).await {
                                                 Ok(Some(existing_speaker)) => {
                                                     tracing::debug!("Reidentified speaker: {}", existing_speaker);
+                                                    attribution = Some(AttributionOutcome::Reidentified);
                                                     existing_speaker
                                                 }
                                                 Ok(None) => {
-                                                    // Create new speaker ID
-                                                    let new_speaker_id = format!("speaker_{}", transcription_counter + 1);
-                                                    tracing::debug!("Creating new speaker: {}", new_speaker_id);
-                                                    
-                                                    // Emit speaker detection event
-                                                    if let Err(emit_err) = app_handle.emit("speaker-update", serde_json::json!({
-                                                        "speakerId": new_speaker_id,
-                                                        "displayName": format!("Speaker {}", transcription_counter + 1),
-                                                        "confidence": embeddings[0].confidence,
-                                                        "voiceCharacteristics": {
-                                                            "pitch": 150.0,
-                                                            "formantF1": 500.0,
-                                                            "formantF2": 1500.0,
-                                                            "speakingRate": 150.0
-                                                        },
-                                                        "isActive": true,
-                                                        "sessionId": session_id,
-                                                        "timestamp": std::time::SystemTime::now()
-                                                            .duration_since(std::time::UNIX_EPOCH)
-                                                            .unwrap_or_default()
-                                                            .as_millis()
-                                                    })) {
-                                                        tracing::warn!("Failed to emit speaker-update event: {}", emit_err);
+                                                    // Speakers from earlier sessions first, then speakers already new in this one
+                                                    let similarity_threshold = diarization.get_config().similarity_threshold;
+                                                    let known_match = identify_known_speaker(&state, &embeddings[0].vector, similarity_threshold).await;
+                                                    // Short of the profile's own threshold: a provisional speaker, counted for tuning
+                                                    if let KnownSpeakerMatch::NearMiss(ref miss) = known_match {
+                                                        tracing::debug!("Near miss of stored speaker {} (similarity {:.2}, threshold {:.2})", miss.name, miss.similarity, miss.threshold);
+                                                        near_miss = Some(miss.clone());
+                                                    }
+                                                    if let KnownSpeakerMatch::Known(known) = known_match {
+                                                        tracing::debug!("Recognised stored speaker {} (similarity {:.2})", known.name, known.similarity);
+                                                        if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "speaker-update", serde_json::json!({
+                                                            "speakerId": known.speaker_id(),
+                                                            "displayName": known.name,
+                                                            "confidence": known.similarity,
+                                                            "voiceCharacteristics": {
+                                                                "pitch": 150.0,
+                                                                "formantF1": 500.0,
+                                                                "formantF2": 1500.0,
+                                                                "speakingRate": 150.0
+                                                            },
+                                                            "isActive": true,
+                                                            "profileId": known.profile_id.to_string(),
+                                                            "knownSpeaker": true,
+                                                            "sessionId": session_id,
+                                                            "timestamp": std::time::SystemTime::now()
+                                                                .duration_since(std::time::UNIX_EPOCH)
+                                                                .unwrap_or_default()
+                                                                .as_millis()
+                                                        })) {
+                                                            tracing::warn!("Failed to emit speaker-update event: {}", emit_err);
+                                                        }
+                                                        attribution = Some(AttributionOutcome::Reidentified);
+                                                        known.speaker_id()
+                                                    } else {
+                                                        let mut sessions_guard = state.active_sessions.lock().await;
+                                                        let unknown_speaker = sessions_guard.get(&session_id)
+                                                            .and_then(|s| s.unknown_speakers.matching_speaker(&embeddings[0], similarity_threshold))
+                                                            .map(str::to_string);
+                                                        let is_new = unknown_speaker.is_none();
+                                                        attribution = Some(if is_new { AttributionOutcome::NewSpeaker } else { AttributionOutcome::Reidentified });
+                                                        let speaker_id = unknown_speaker
+                                                            .unwrap_or_else(|| format!("speaker_{}", transcription_counter + 1));
+                                                        if let Some(session_state) = sessions_guard.get_mut(&session_id) {
+                                                            session_state.unknown_speakers.record(&speaker_id, embeddings[0].clone());
+                                                        }
+                                                        drop(sessions_guard);
+                                                        
+                                                        if is_new {
+                                                            tracing::debug!("Creating new speaker: {}", speaker_id);
+                                                            
+                                                            // Emit speaker detection event
+                                                            if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "speaker-update", serde_json::json!({
+                                                                "speakerId": speaker_id,
+                                                                "displayName": format!("Speaker {}", transcription_counter + 1),
+                                                                "confidence": embeddings[0].confidence,
+                                                                "voiceCharacteristics": {
+                                                                    "pitch": 150.0,
+                                                                    "formantF1": 500.0,
+                                                                    "formantF2": 1500.0,
+                                                                    "speakingRate": 150.0
+                                                                },
+                                                                "isActive": true,
+                                                                "knownSpeaker": false,
+                                                                "sessionId": session_id,
+                                                                "timestamp": std::time::SystemTime::now()
+                                                                    .duration_since(std::time::UNIX_EPOCH)
+                                                                    .unwrap_or_default()
+                                                                    .as_millis()
+                                                            })) {
+                                                                tracing::warn!("Failed to emit speaker-update event: {}", emit_err);
+                                                            }
+                                                        }
+                                                        
+                                                        speaker_id
                                                     }
-                                                    
-                                                    new_speaker_id
                                                 }
                                                 Err(e) => {
                                                     tracing::warn!("Speaker identification failed: {:?}", e);
@@ -1543,12 +6926,15 @@ async fn run_transcription_loop(
                                         }
                                         Err(e) => {
                                             tracing::warn!("Embedding extraction failed: {:?}", e);
+                                            attribution = Some(AttributionOutcome::ExtractionFailed);
                                             // Emit graceful degradation warning to frontend
-                                            if let Err(emit_err) = app_handle.emit("diarization-warning", serde_json::json!({
+                                            if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "diarization-warning", serde_json::json!({
                                                 "sessionId": session_id,
                                                 "type": "embedding_extraction_failed",
                                                 "message": "Speaker identification temporarily unavailable - falling back to single speaker mode",
                                                 "recoverable": true,
+                                                "retryCount": 0,
+                                                "manualRetryAvailable": false,
                                                 "timestamp": std::time::SystemTime::now()
                                                     .duration_since(std::time::UNIX_EPOCH)
                                                     .unwrap_or_default()
@@ -1566,27 +6952,36 @@ async fn run_transcription_loop(
                                 "speaker_1".to_string()
                             }
                         };
+                        let speaker_id = match source_attribution.as_ref().and_then(SourceAttribution::speaker_override) {
+                            Some(local_speaker) => {
+                                segment_embedding = None;
+                                overlapping_speakers.clear();
+                                local_speaker.to_string()
+                            }
+                            None => speaker_id,
+                        };
 
-                        // Calculate actual timestamps from buffer
-                        let current_time = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs() as f32;
+                        // Place the buffer by its offset in the captured audio stream
+                        let (segment_start, segment_end) = stream_clock.buffer_span(buffered_audio.samples.len(), buffered_audio.sample_rate);
+                        let (segment_start, segment_end) = (segment_start as f32, segment_end as f32);
                         
-                        // Get session start time for relative timestamps
-                        let start_time = {
-                            let sessions_guard = state.active_sessions.lock().await;
-                            sessions_guard.get(&session_id)
-                                .map(|s| s.start_time as f32)
-                                .unwrap_or(current_time)
+                        // Decoder word timing places the segment on the speech within the buffer
+                        let buffer_offset = segment_start;
+                        let (segment_start, segment_end) = match word_timing::word_span(&result.words).filter(|_| result.word_timestamps) {
+                            Some((first_word, last_word)) => (buffer_offset + first_word, buffer_offset + last_word),
+                            None => (segment_start, segment_end),
                         };
                         
-                        let segment_start = current_time - start_time - (buffer_duration_ms as f32 / 1000.0);
-                        let segment_end = current_time - start_time;
+                        // Restore punctuation if enabled, keeping the raw ASR text alongside; it
+                        // reads pauses, so only decoder word timestamps will do
+                        let segment_text = match punctuation_restorer {
+                            Some(ref restorer) if result.word_timestamps => restorer.restore(cleaned_text, &result.words, &result.language).text,
+                            _ => cleaned_text.to_string(),
+                        };
                         
                         // Create temporal segment for analysis
                         let mut temporal_segment = TemporalSegment {
-                            text: cleaned_text.to_string(),
+                            text: segment_text,
                             start_time: segment_start,
                             end_time: segment_end,
                             confidence: result.confidence,
@@ -1619,12 +7014,18 @@ async fn run_transcription_loop(
                         // Process all final segments (usually just one, multiple if merged)
                         for final_segment in final_segments {
                             // Create the segment JSON with validated timestamps
-                            let segment = serde_json::json!({
+                            let mut segment = serde_json::json!({
                                 "text": final_segment.text,
+                                "rawText": cleaned_text,
                                 "startTime": final_segment.start_time,
                                 "endTime": final_segment.end_time,
                                 "confidence": final_segment.confidence,
                                 "speaker": final_segment.speaker_id,
+                                // BCP-47 tag, "und" when the engine reported something unrecognized
+                                "language": Language::parse(&result.language).map(|l| l.code()).unwrap_or("und"),
+                                "decodeTemperature": result.decode_temperature,
+                                // Tier of the engine that transcribed the segment; changes when the adaptive tier steps down
+                                "modelTier": transcribed_tier.map(|tier| tier.quality_tier()),
                                 "qualityScore": {
                                     "boundaryType": match boundary_type {
                                         BoundaryType::None => "none",
@@ -1635,28 +7036,113 @@ async fn run_transcription_loop(
                                     "processingVersion": "v2_enhanced"
                                 }
                             });
+                            overlap::mark_overlap(&mut segment, &final_segment.speaker_id, &overlapping_speakers);
+                            if let Some(ref embedding) = segment_embedding {
+                                segment[session_stats::SPEAKER_CONFIDENCE_FIELD] = serde_json::json!(embedding.confidence);
+                            }
+                            if let Some(budget) = result.prompt_budget.as_ref().filter(|budget| !budget.truncations.is_empty()) {
+                                segment["promptTruncations"] = serde_json::json!(budget.truncations);
+                            }
+                            // First segment after a device outage; the timeline itself has no hole
+                            if let Some(gap) = gap_before.take() {
+                                segment["gapBefore"] = serde_json::json!(gap);
+                            }
+                            if result.word_timestamps && !result.words.is_empty() {
+                                segment["words"] = serde_json::json!(word_timing::words_json(&result.words, buffer_offset));
+                            }
+                            // Only reached in hallucination debug mode
+                            if let Some(reason) = hallucination {
+                                segment["flagged"] = serde_json::json!(true);
+                                segment["flaggedReason"] = serde_json::json!(reason.as_str());
+                            }
+                            // Segments keep the id of the partial they replace so the frontend can patch them later
+                            let replaces_partial = partial_id.is_some();
+                            segment["id"] = serde_json::json!(partial_id.take().unwrap_or_else(|| Uuid::new_v4().to_string()));
                             
-                            // Store the segment in the session state
-                            {
+                            // Store the segment in the session state; muted speakers are stored but not emitted
+                            let mut muted = false;
+                            let mut talk_time = None;
+                            let segment_index = {
                                 let mut sessions_guard = state.active_sessions.lock().await;
-                                if let Some(session_state) = sessions_guard.get_mut(&session_id) {
+                                sessions_guard.get_mut(&session_id).map(|session_state| {
+                                    muted = session_state.speaker_mutes.flag_segment(&mut segment);
+                                    session_state.speaker_names.label_segment(&mut segment);
+                                    review::flag_segment(&mut segment, session_state.config.review_confidence_threshold);
+                                    translation::label_segment(&mut segment, session_state.whisper_config.task);
+                                    segment_audio::label_segment(&mut segment, session_state.recording_timeline.as_ref());
+                                    if let Some(outcome) = attribution.take() {
+                                        session_state.attribution_counters.record(outcome);
+                                    }
+                                    if let Some(miss) = near_miss.take() {
+                                        session_state.attribution_counters.record_near_miss(&miss);
+                                    }
+                                    if let Some(embedding) = segment_embedding.take() {
+                                        session_state.session_embeddings.record(&final_segment.speaker_id, final_segment.start_time, final_segment.end_time, embedding);
+                                    }
                                     session_state.transcription_segments.push(segment.clone());
+                                    session_state.progress.record_segment(std::time::SystemTime::now()
+                                        .duration_since(std::time::UNIX_EPOCH)
+                                        .unwrap_or_default()
+                                        .as_millis() as u64);
                                     tracing::debug!("Stored enhanced segment #{} for session {} ({})", 
                                                  session_state.transcription_segments.len(), session_id, final_segment.text.len());
+                                    if session_state.transcription_segments.len() % SPEAKER_STATS_EVERY_SEGMENTS == 0 {
+                                        talk_time = Some(speaker_stats::compute_speaker_stats(&session_state.transcription_segments));
+                                    }
+                                    session_state.transcription_segments.len() - 1
+                                })
+                            };
+                            
+                            transcription_counter += 1;
+                            if let Some(talk_time) = talk_time {
+                                if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "speaker-stats", serde_json::json!({
+                                    "sessionId": session_id,
+                                    "stats": talk_time
+                                })) {
+                                    tracing::warn!("Failed to emit speaker-stats event: {}", emit_err);
+                                }
+                            }
+                            if muted {
+                                tracing::debug!("Segment from muted speaker {} stored without emitting", final_segment.speaker_id);
+                                if replaces_partial {
+                                    retract_partial(&app_handle, &session_id, segment["id"].as_str().map(str::to_string));
                                 }
+                                continue;
                             }
                             
                             // Emit the update to the frontend
-                            if let Err(emit_err) = app_handle.emit("transcription-update", serde_json::json!({
+                            if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "transcription-update", serde_json::json!({
                                 "sessionId": session_id,
                                 "segment": segment,
-                                "updateType": "new",
+                                "updateType": if partial_scheduler.is_some() { "final" } else { "new" },
                                 "processingPass": 2,
                                 "qualityEnhanced": true
                             })) {
                                 tracing::error!("Failed to emit transcription-update event: {}", emit_err);
                             }
-                            transcription_counter += 1;
+                            
+                            if let (Some(matcher), Some(segment_index)) = (&keyword_matcher, segment_index) {
+                                for detection in matcher.detections(&segment, segment_index) {
+                                    tracing::debug!("Keyword '{}' in segment #{} of session {}", detection.keyword, segment_index, session_id);
+                                    let mut payload = serde_json::json!(detection);
+                                    payload["sessionId"] = serde_json::json!(session_id);
+                                    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "keyword-detected", payload) {
+                                        tracing::warn!("Failed to emit keyword-detected event: {}", emit_err);
+                                    }
+                                }
+                            }
+                            
+                            // Peaks are computed after the emit so they never delay the transcript
+                            if let Some(segment_index) = segment_index {
+                                attach_segment_peaks(&app_handle, &session_id, segment_index, &buffered_audio, buffer_offset as f64).await;
+                            }
+                            
+                            if let Some(ref mut suggester) = name_suggester {
+                                let language = Language::parse(&result.language).ok();
+                                if let Some(suggestion) = suggester.observe(&final_segment.speaker_id, &final_segment.text, language, final_segment.start_time) {
+                                    emit_name_suggestion(&app_handle, &state, &session_id, suggestion).await;
+                                }
+                            }
                         }
                     } else {
                         tracing::debug!("Transcription result was empty, not emitting update");
@@ -1665,60 +7151,68 @@ async fn run_transcription_loop(
                     tracing::debug!("No transcription result available");
                 }
                 
-                // Clear buffer and reset counters after processing
-                audio_buffer.clear();
+                retract_partial(&app_handle, &session_id, partial_id.take());
+                
+                // Clear buffer and reset counters after processing. A buffer cut
+                // while the speaker was still talking keeps its overlap tail.
+                let mid_speech = match vad {
+                    Some(_) => decision != ChunkDecision::End,
+                    None => is_speech,
+                };
+                let dropped = chunk_window.flush(&mut audio_buffer, audio_data.sample_rate, mid_speech);
+                if audio_buffer.is_empty() {
+                    stream_clock.clear_buffer();
+                } else {
+                    stream_clock.trim_buffer(dropped, audio_data.sample_rate);
+                }
+                buffer_speech.reset();
+                if let Some(ref mut attribution) = source_attribution {
+                    attribution.reset();
+                }
                 buffer_timestamp = std::time::SystemTime::now();
                 consecutive_silence_chunks = 0;
                 tracing::debug!("Buffer cleared, ready for next segment");
             } else {
                 // No voice activity - clear buffer if it's been too long
                 let buffer_age_ms = buffer_timestamp.elapsed().unwrap_or_default().as_millis() as u64;
-                if buffer_age_ms > MIN_AUDIO_DURATION_MS * 2 && !audio_buffer.is_empty() {
+                if vad.is_none() && buffer_age_ms > chunk_window.min_ms * 2 && !audio_buffer.is_empty() {
                     tracing::debug!("Clearing stale audio buffer after {}ms", buffer_age_ms);
+                    retract_partial(&app_handle, &session_id, partial_scheduler.as_mut().and_then(PartialScheduler::finish));
                     audio_buffer.clear();
+                    buffer_speech.reset();
+                    stream_clock.clear_buffer();
+                    if let Some(ref mut attribution) = source_attribution {
+                        attribution.reset();
+                    }
                     consecutive_silence_chunks = 0;
                 }
             }
             
-            // Emit system status periodically
-            if audio_level_counter % 50 == 0 { // Every 5 seconds
-                if let Err(emit_err) = app_handle.emit("system-status", serde_json::json!({
-                    "processingMetrics": {
-                        "realTimeFactor": 0.8, // Placeholder - would calculate actual RTF
-                        "averageLatency": 150,
-                        "queuedSegments": 0,
-                        "cpuUsage": 25.0,
-                        "memoryUsage": 2.1
-                    },
-                    "memoryUsage": {
-                        "used": 2100,
-                        "available": 6000,
-                        "percentage": 35
-                    }
-                })) {
-                    tracing::warn!("Failed to emit system-status event: {}", emit_err);
-                }
-            }
+            loop_status.send_replace(LoopStatus {
+                session_id: session_id.clone(),
+                adaptive_chunking: chunk_sizer.as_ref().map(|s| s.diagnostics()),
+                prompt_budget: last_prompt_budget.clone(),
+                temperature_fallback: fallback_stats.clone(),
+                backpressure: channel_health,
+                inference_device,
+                resampling,
+                noise_suppression: noise_suppressor.as_ref().map(|suppressor| NoiseSuppressionStatus {
+                    enabled: noise_suppression,
+                    profile_ready: suppressor.has_noise_profile(),
+                    cpu_percent: suppressor.stats().cpu_percent(),
+                }),
+            });
         } else {
             // No audio data available, short sleep to prevent busy loop
             tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
         }
     }
     
-    Ok(())
-}
-
-/// Calculate RMS audio level from samples
-fn calculate_audio_level(samples: &[f32]) -> f32 {
-    if samples.is_empty() {
-        return 0.0;
+    if let Some(writer) = recorder.take() {
+        finish_session_recording(&app_handle, &session_id, writer).await;
     }
     
-    let sum_of_squares: f32 = samples.iter().map(|&sample| sample * sample).sum();
-    let rms = (sum_of_squares / samples.len() as f32).sqrt();
-    
-    // Normalize to 0-1 range (assuming typical audio levels)
-    (rms * 10.0).min(1.0)
+    Ok(())
 }
 
 /// Calculate text similarity using simple word overlap
@@ -1827,7 +7321,7 @@ fn is_duplicate_segment(text: &str, recent_segments: &VecDeque<(String, f32)>, t
 
 /// Initialize speaker storage system
 #[tauri::command]
-pub async fn initialize_speaker_storage(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn initialize_speaker_storage(state: State<'_, AppState>) -> Result<String, CommandError> {
     state.initialize_speaker_storage().await?;
     Ok("Speaker storage initialized successfully".to_string())
 }
@@ -1837,7 +7331,10 @@ pub async fn initialize_speaker_storage(state: State<'_, AppState>) -> Result<St
 pub async fn create_speaker_profile(
     request: CreateSpeakerProfileRequest,
     state: State<'_, AppState>,
-) -> Result<DbSpeakerProfile, String> {
+) -> Result<DbSpeakerProfile, CommandError> {
+    let request = metadata::sanitize_create_profile(request)
+        .map_err(|e| format!("Invalid speaker profile: {}", e))?;
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -1853,7 +7350,8 @@ pub async fn create_speaker_profile(
 pub async fn get_speaker_profile(
     speaker_id: String,
     state: State<'_, AppState>,
-) -> Result<Option<DbSpeakerProfile>, String> {
+) -> Result<Option<DbSpeakerProfile>, CommandError> {
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -1861,9 +7359,13 @@ pub async fn get_speaker_profile(
     let uuid = Uuid::parse_str(&speaker_id)
         .map_err(|e| format!("Invalid speaker ID: {}", e))?;
     
-    let profile = store.get_speaker_profile(uuid).await
+    let mut profile = store.get_speaker_profile(uuid).await
         .map_err(|e| format!("Failed to get speaker profile: {}", e))?;
     
+    // Data stored before sanitization existed is cleaned for display
+    if let Some(ref mut profile) = profile {
+        metadata::normalize_profile_on_read(profile);
+    }
     Ok(profile)
 }
 
@@ -1872,14 +7374,18 @@ pub async fn get_speaker_profile(
 pub async fn list_speaker_profiles(
     active_only: bool,
     state: State<'_, AppState>,
-) -> Result<Vec<DbSpeakerProfile>, String> {
+) -> Result<Vec<DbSpeakerProfile>, CommandError> {
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
     
-    let profiles = store.list_speaker_profiles(active_only).await
+    let mut profiles = store.list_speaker_profiles(active_only).await
         .map_err(|e| format!("Failed to list speaker profiles: {}", e))?;
     
+    for profile in profiles.iter_mut() {
+        metadata::normalize_profile_on_read(profile);
+    }
     Ok(profiles)
 }
 
@@ -1889,7 +7395,10 @@ pub async fn update_speaker_profile(
     speaker_id: String,
     request: UpdateSpeakerProfileRequest,
     state: State<'_, AppState>,
-) -> Result<Option<DbSpeakerProfile>, String> {
+) -> Result<Option<DbSpeakerProfile>, CommandError> {
+    let request = metadata::sanitize_update_profile(request)
+        .map_err(|e| format!("Invalid speaker profile: {}", e))?;
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -1908,7 +7417,8 @@ pub async fn update_speaker_profile(
 pub async fn delete_speaker_profile(
     speaker_id: String,
     state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -1918,7 +7428,7 @@ pub async fn delete_speaker_profile(
     
     // Remove from embedding index as well
     {
-        let mut index_guard = state.embedding_index.lock().await;
+        let mut index_guard = state.embedding_index().await.lock().await;
         if let Err(e) = index_guard.remove_speaker(uuid) {
             tracing::warn!("Failed to remove speaker from embedding index: {}", e);
         }
@@ -1935,7 +7445,8 @@ pub async fn delete_speaker_profile(
 pub async fn add_voice_embedding(
     embedding: VoiceEmbedding,
     state: State<'_, AppState>,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -1946,7 +7457,7 @@ pub async fn add_voice_embedding(
     
     // Add to fast index
     {
-        let mut index_guard = state.embedding_index.lock().await;
+        let mut index_guard = state.embedding_index().await.lock().await;
         if let Err(e) = index_guard.add_embedding(embedding) {
             tracing::warn!("Failed to add embedding to index: {}", e);
         }
@@ -1960,7 +7471,8 @@ pub async fn add_voice_embedding(
 pub async fn get_voice_embeddings(
     speaker_id: String,
     state: State<'_, AppState>,
-) -> Result<Vec<VoiceEmbedding>, String> {
+) -> Result<Vec<VoiceEmbedding>, CommandError> {
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -1974,6 +7486,106 @@ pub async fn get_voice_embeddings(
     Ok(embeddings)
 }
 
+/// A stored speaker sample and the older samples evicted to make room for it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerSampleAdded {
+    pub sample_id: String,
+    pub duration_seconds: f32,
+    pub evicted_sample_ids: Vec<String>,
+}
+
+/// Encode mono samples as a WAV clip and store it for the speaker
+async fn store_speaker_sample(
+    store: &SpeakerStore,
+    speaker_id: Uuid,
+    samples: &[f32],
+    sample_rate: u32,
+) -> Result<SpeakerSampleAdded, String> {
+    let sample = SpeakerSample::from_samples(speaker_id, samples, sample_rate)
+        .map_err(|e| format!("Failed to encode speaker sample: {}", e))?;
+    let evicted = store.add_speaker_sample(sample.clone(), SPEAKER_SAMPLE_CAP_SECONDS).await
+        .map_err(|e| format!("Failed to store speaker sample: {}", e))?;
+    Ok(SpeakerSampleAdded {
+        sample_id: sample.id.to_string(),
+        duration_seconds: sample.duration_seconds,
+        evicted_sample_ids: evicted.iter().map(Uuid::to_string).collect(),
+    })
+}
+
+/// Attach a reference audio clip to a speaker profile. Each speaker keeps up
+/// to a minute of clips; the oldest are evicted to make room for a new one.
+#[tauri::command]
+pub async fn add_speaker_sample(
+    speaker_id: String,
+    samples: Vec<f32>,
+    sample_rate: u32,
+    state: State<'_, AppState>,
+) -> Result<SpeakerSampleAdded, CommandError> {
+    let uuid = Uuid::parse_str(&speaker_id)
+        .map_err(|e| CommandError::InvalidInput(format!("Invalid speaker ID: {}", e)))?;
+    if sample_rate == 0 || samples.is_empty() {
+        return Err(CommandError::InvalidInput("Invalid speaker sample: no audio".to_string()));
+    }
+    let duration_seconds = samples.len() as f32 / sample_rate as f32;
+    if duration_seconds > SPEAKER_SAMPLE_CAP_SECONDS {
+        return Err(CommandError::InvalidInput(format!(
+            "Invalid speaker sample: {:.1}s is longer than the {:.0}s kept per speaker",
+            duration_seconds, SPEAKER_SAMPLE_CAP_SECONDS
+        )));
+    }
+    
+    state.speaker_storage_ready().await?;
+    let store_guard = state.speaker_store.lock().await;
+    let store = store_guard.as_ref()
+        .ok_or("Speaker storage not initialized")?;
+    store.get_speaker_profile(uuid).await
+        .map_err(|e| format!("Failed to get speaker profile: {}", e))?
+        .ok_or_else(|| CommandError::NotFound(format!("Speaker profile {} not found", speaker_id)))?;
+    
+    Ok(store_speaker_sample(store, uuid, &samples, sample_rate).await?)
+}
+
+/// Reference audio clips of a speaker, oldest first, as base64 WAV files
+#[tauri::command]
+pub async fn get_speaker_samples(
+    speaker_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<SpeakerSample>, CommandError> {
+    state.speaker_storage_ready().await?;
+    let store_guard = state.speaker_store.lock().await;
+    let store = store_guard.as_ref()
+        .ok_or("Speaker storage not initialized")?;
+    
+    let uuid = Uuid::parse_str(&speaker_id)
+        .map_err(|e| CommandError::InvalidInput(format!("Invalid speaker ID: {}", e)))?;
+    
+    let samples = store.get_speaker_samples(uuid).await
+        .map_err(|e| format!("Failed to get speaker samples: {}", e))?;
+    
+    Ok(samples)
+}
+
+/// Delete a reference audio clip. Returns false when it does not exist.
+#[tauri::command]
+pub async fn delete_speaker_sample(
+    sample_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    state.speaker_storage_ready().await?;
+    let store_guard = state.speaker_store.lock().await;
+    let store = store_guard.as_ref()
+        .ok_or("Speaker storage not initialized")?;
+    
+    let uuid = Uuid::parse_str(&sample_id)
+        .map_err(|e| CommandError::InvalidInput(format!("Invalid sample ID: {}", e)))?;
+    
+    let deleted = store.delete_speaker_sample(uuid).await
+        .map_err(|e| format!("Failed to delete speaker sample: {}", e))?;
+    
+    Ok(deleted)
+}
+
 /// Search for similar speakers based on voice embedding
 #[tauri::command]
 pub async fn find_similar_speakers(
@@ -1981,7 +7593,8 @@ pub async fn find_similar_speakers(
     threshold: f32,
     max_results: usize,
     state: State<'_, AppState>,
-) -> Result<Vec<SimilarSpeaker>, String> {
+) -> Result<Vec<SimilarSpeaker>, CommandError> {
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -1999,8 +7612,8 @@ pub async fn fast_similarity_search(
     threshold: f32,
     max_results: usize,
     state: State<'_, AppState>,
-) -> Result<Vec<(String, f32)>, String> {
-    let index_guard = state.embedding_index.lock().await;
+) -> Result<Vec<(String, f32)>, CommandError> {
+    let index_guard = state.embedding_index().await.lock().await;
     
     let results = index_guard.find_similar_embeddings(&query_vector, threshold, max_results)
         .map_err(|e| format!("Failed to perform similarity search: {}", e))?;
@@ -2016,8 +7629,8 @@ pub async fn fast_similarity_search(
 
 /// Get embedding index statistics
 #[tauri::command]
-pub async fn get_embedding_index_stats(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-    let index_guard = state.embedding_index.lock().await;
+pub async fn get_embedding_index_stats(state: State<'_, AppState>) -> Result<serde_json::Value, CommandError> {
+    let index_guard = state.embedding_index().await.lock().await;
     
     let stats = index_guard.get_stats()
         .map_err(|e| format!("Failed to get index stats: {}", e))?;
@@ -2032,31 +7645,36 @@ pub async fn get_embedding_index_stats(state: State<'_, AppState>) -> Result<ser
     }))
 }
 
+/// Voice embeddings of all active speaker profiles
+async fn active_profile_embeddings(store: &SpeakerStore) -> Result<Vec<VoiceEmbedding>, String> {
+    let profiles = store.list_speaker_profiles(true).await
+        .map_err(|e| format!("Failed to get speaker profiles: {}", e))?;
+    
+    let mut all_embeddings = Vec::new();
+    for profile in profiles {
+        let embeddings = store.get_voice_embeddings(profile.id).await
+            .map_err(|e| format!("Failed to get embeddings for speaker {}: {}", profile.id, e))?;
+        all_embeddings.extend(embeddings);
+    }
+    Ok(all_embeddings)
+}
+
 /// Rebuild embedding index from database
 #[tauri::command]
-pub async fn rebuild_embedding_index(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn rebuild_embedding_index(state: State<'_, AppState>) -> Result<String, CommandError> {
     // Get all embeddings from database
     let all_embeddings = {
+        state.speaker_storage_ready().await?;
         let store_guard = state.speaker_store.lock().await;
         let store = store_guard.as_ref()
             .ok_or("Speaker storage not initialized")?;
         
-        let profiles = store.list_speaker_profiles(true).await
-            .map_err(|e| format!("Failed to get speaker profiles: {}", e))?;
-        
-        let mut all_embeddings = Vec::new();
-        for profile in profiles {
-            let embeddings = store.get_voice_embeddings(profile.id).await
-                .map_err(|e| format!("Failed to get embeddings for speaker {}: {}", profile.id, e))?;
-            all_embeddings.extend(embeddings);
-        }
-        
-        all_embeddings
+        active_profile_embeddings(store).await?
     };
     
     // Rebuild index
     {
-        let mut index_guard = state.embedding_index.lock().await;
+        let mut index_guard = state.embedding_index().await.lock().await;
         index_guard.rebuild(all_embeddings)
             .map_err(|e| format!("Failed to rebuild embedding index: {}", e))?;
     }
@@ -2064,107 +7682,75 @@ pub async fn rebuild_embedding_index(state: State<'_, AppState>) -> Result<Strin
     Ok(format!("Embedding index rebuilt successfully"))
 }
 
-/// Export speaker data as JSON
+/// Export speaker data as JSON; with `include_samples` the profiles'
+/// reference audio clips go along as base64 WAV files
 #[tauri::command]
 pub async fn export_speaker_profiles(
     include_embeddings: bool,
+    include_samples: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, CommandError> {
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
     
-    let profiles = store.list_speaker_profiles(false).await
-        .map_err(|e| format!("Failed to get speaker profiles: {}", e))?;
-    
-    let mut export_data = serde_json::json!({
-        "metadata": {
-            "exported_at": chrono::Utc::now().to_rfc3339(),
-            "app_version": "1.0.0",
-            "total_profiles": profiles.len(),
-            "format_version": 1
-        },
-        "profiles": profiles
-    });
-    
-    if include_embeddings {
-        let mut all_embeddings = Vec::new();
-        for profile in &profiles {
-            let embeddings = store.get_voice_embeddings(profile.id).await
-                .map_err(|e| format!("Failed to get embeddings for speaker {}: {}", profile.id, e))?;
-            all_embeddings.extend(embeddings);
-        }
-        export_data["embeddings"] = serde_json::to_value(all_embeddings)
-            .map_err(|e| format!("Failed to serialize embeddings: {}", e))?;
-    }
+    let export_data = profile_transfer::export_speakers(store, include_embeddings, include_samples.unwrap_or(false)).await
+        .map_err(|e| format!("Failed to export speaker profiles: {}", e))?;
     
     Ok(export_data)
 }
 
-/// Import speaker profiles from JSON data
+/// Import speaker profiles from JSON data. Imported embeddings and samples
+/// follow their profile; `conflict_strategy` ("skip", the default,
+/// "overwrite" or "duplicate") decides what happens to profiles whose name
+/// is already taken.
 #[tauri::command]
 pub async fn import_speaker_profiles(
     import_data: serde_json::Value,
+    conflict_strategy: Option<ConflictStrategy>,
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, CommandError> {
+    let import: SpeakerImport = serde_json::from_value(import_data)
+        .map_err(|e| CommandError::InvalidInput(format!("Invalid speaker import data: {}", e)))?;
+    
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
-    
-    let profiles: Vec<DbSpeakerProfile> = serde_json::from_value(
-        import_data.get("profiles")
-            .ok_or("Missing profiles in import data")?
-            .clone()
-    ).map_err(|e| format!("Invalid profile data format: {}", e))?;
-    
-    let mut imported_count = 0;
-    let mut errors = Vec::new();
-    
-    for profile in profiles {
-        match store.create_speaker_profile(CreateSpeakerProfileRequest {
-            name: profile.name.clone(),
-            description: profile.description,
-            color: Some(profile.color),
-            confidence_threshold: Some(profile.confidence_threshold),
-        }).await {
-            Ok(_) => imported_count += 1,
-            Err(e) => errors.push(format!("Failed to import {}: {}", profile.name, e)),
+    let index_guard = state.embedding_index().await.lock().await;
+    
+    let report = match profile_transfer::import_speakers(store, &index_guard, import, conflict_strategy.unwrap_or_default()).await {
+        Ok(report) => report,
+        Err(ImportError::DimensionMismatch { embedding_id, expected, actual }) => {
+            return Err(CommandError::detailed("embedding_dimension_mismatch", format!(
+                "Embedding {} has {} dimensions, but speaker embeddings here have {}", embedding_id, actual, expected
+            ), vec![
+                "Export the profiles again from an app version using the same speaker model".to_string()
+            ]).with_details(serde_json::json!({
+                "embeddingId": embedding_id,
+                "expected": expected,
+                "actual": actual
+            })));
         }
-    }
-    
-    // Import embeddings if present
-    let mut imported_embeddings = 0;
-    if let Some(embeddings_value) = import_data.get("embeddings") {
-        let embeddings: Vec<VoiceEmbedding> = serde_json::from_value(embeddings_value.clone())
-            .map_err(|e| format!("Invalid embedding data format: {}", e))?;
-        
-        for embedding in embeddings {
-            match store.add_voice_embedding(embedding.clone()).await {
-                Ok(_) => {
-                    imported_embeddings += 1;
-                    // Add to index as well
-                    let mut index_guard = state.embedding_index.lock().await;
-                    if let Err(e) = index_guard.add_embedding(embedding) {
-                        tracing::warn!("Failed to add embedding to index during import: {}", e);
-                    }
-                }
-                Err(e) => errors.push(format!("Failed to import embedding {}: {}", embedding.id, e)),
-            }
-        }
-    }
+        Err(ImportError::Storage(e)) => return Err(format!("Failed to import speaker profiles: {}", e).into()),
+    };
     
     Ok(serde_json::json!({
-        "success": errors.is_empty(),
-        "imported_profiles": imported_count,
-        "imported_embeddings": imported_embeddings,
-        "errors": errors
+        "success": report.errors.is_empty(),
+        "imported_profiles": report.imported_profiles(),
+        "imported_embeddings": report.imported_embeddings,
+        "imported_samples": report.imported_samples,
+        "profiles": report.profiles,
+        "errors": report.errors
     }))
 }
 
 /// Load test seed data for development and testing
 #[tauri::command]
-pub async fn load_test_seed_data(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn load_test_seed_data(state: State<'_, AppState>) -> Result<String, CommandError> {
     let result = {
+        state.speaker_storage_ready().await?;
         let db_guard = state.speaker_database.lock().await;
         let db = db_guard.as_ref()
             .ok_or("Speaker storage not initialized")?;
@@ -2184,8 +7770,9 @@ pub async fn load_test_seed_data(state: State<'_, AppState>) -> Result<String, S
 
 /// Create comprehensive test dataset for development
 #[tauri::command]
-pub async fn create_comprehensive_test_dataset(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn create_comprehensive_test_dataset(state: State<'_, AppState>) -> Result<String, CommandError> {
     let result = {
+        state.speaker_storage_ready().await?;
         let db_guard = state.speaker_database.lock().await;
         let db = db_guard.as_ref()
             .ok_or("Speaker storage not initialized")?;
@@ -2205,7 +7792,8 @@ pub async fn create_comprehensive_test_dataset(state: State<'_, AppState>) -> Re
 
 /// Clear all speaker data (for testing)
 #[tauri::command]
-pub async fn clear_all_speaker_data(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn clear_all_speaker_data(state: State<'_, AppState>) -> Result<String, CommandError> {
+    state.speaker_storage_ready().await?;
     let db_guard = state.speaker_database.lock().await;
     let db = db_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -2216,7 +7804,7 @@ pub async fn clear_all_speaker_data(state: State<'_, AppState>) -> Result<String
     
     // Clear the embedding index as well
     {
-        let mut index_guard = state.embedding_index.lock().await;
+        let mut index_guard = state.embedding_index().await.lock().await;
         if let Err(e) = index_guard.clear() {
             tracing::warn!("Failed to clear embedding index: {}", e);
         }
@@ -2225,6 +7813,60 @@ pub async fn clear_all_speaker_data(state: State<'_, AppState>) -> Result<String
     Ok(result)
 }
 
+/// Rewrite speaker profile names and descriptions stored before input
+/// sanitization existed; with `dry_run` only reports what would change
+#[tauri::command]
+pub async fn normalize_stored_metadata(
+    dry_run: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, CommandError> {
+    let dry_run = dry_run.unwrap_or(false);
+    state.speaker_storage_ready().await?;
+    let store_guard = state.speaker_store.lock().await;
+    let store = store_guard.as_ref()
+        .ok_or("Speaker storage not initialized")?;
+    
+    let profiles = store.list_speaker_profiles(false).await
+        .map_err(|e| format!("Failed to list speaker profiles: {}", e))?;
+    let checked = profiles.len();
+    let mut changed_ids = Vec::new();
+    let mut errors = Vec::new();
+    
+    for mut profile in profiles {
+        if !metadata::normalize_profile_on_read(&mut profile) {
+            continue;
+        }
+        changed_ids.push(profile.id.to_string());
+        if dry_run {
+            continue;
+        }
+        let update = UpdateSpeakerProfileRequest {
+            name: Some(profile.name),
+            description: profile.description,
+            color: None,
+            confidence_threshold: None,
+            is_active: None,
+        };
+        if let Err(e) = store.update_speaker_profile(profile.id, update).await {
+            errors.push(format!("Failed to normalize {}: {}", profile.id, e));
+        }
+    }
+    
+    tracing::info!(
+        "Metadata normalization {}: {} of {} profiles affected",
+        if dry_run { "dry run" } else { "applied" },
+        changed_ids.len(),
+        checked
+    );
+    Ok(serde_json::json!({
+        "dryRun": dry_run,
+        "checked": checked,
+        "changed": changed_ids.len(),
+        "changedIds": changed_ids,
+        "errors": errors
+    }))
+}
+
 // ============================================================================
 // DIARIZATION INTEGRATION COMMANDS
 // ============================================================================
@@ -2234,7 +7876,7 @@ pub async fn clear_all_speaker_data(state: State<'_, AppState>) -> Result<String
 pub async fn initialize_diarization_service(
     config: DiarizationConfig,
     state: State<'_, AppState>,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     let service = DiarizationService::new(config).await
         .map_err(|e| format!("Failed to initialize diarization service: {:?}", e))?;
     
@@ -2251,7 +7893,7 @@ pub async fn diarize_audio_segment(
     audio_samples: Vec<f32>,
     sample_rate: u32,
     state: State<'_, AppState>,
-) -> Result<crate::diarization::DiarizationResult, String> {
+) -> Result<crate::diarization::DiarizationResult, CommandError> {
     let diarization_guard = state.diarization_service.lock().await;
     let service = diarization_guard.as_ref()
         .ok_or("Diarization service not initialized")?;
@@ -2268,7 +7910,7 @@ pub async fn identify_speaker(
     audio_samples: Vec<f32>,
     sample_rate: u32,
     state: State<'_, AppState>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CommandError> {
     let diarization_guard = state.diarization_service.lock().await;
     let service = diarization_guard.as_ref()
         .ok_or("Diarization service not initialized")?;
@@ -2288,52 +7930,268 @@ pub async fn identify_speaker(
     Ok(speaker_id)
 }
 
-/// Get diarization statistics for current session
+/// A profile created from an enrollment sample
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerEnrollment {
+    pub profile: DbSpeakerProfile,
+    /// Mean embedding confidence scaled by how closely the sample's embeddings agree (0-1)
+    pub quality_score: f32,
+    pub speech_seconds: f32,
+    pub embedding_count: usize,
+    /// The enrollment clip, stored as the profile's first reference sample
+    pub sample_id: Option<String>,
+}
+
+/// Create a speaker profile from a short sample of the speaker reading aloud.
+/// The sample's embeddings are averaged into one reference embedding, stored
+/// with the profile and added to the embedding index.
+#[tauri::command]
+pub async fn enroll_speaker(
+    name: String,
+    audio_samples: Vec<f32>,
+    sample_rate: u32,
+    state: State<'_, AppState>,
+) -> Result<SpeakerEnrollment, CommandError> {
+    let request = metadata::sanitize_create_profile(CreateSpeakerProfileRequest {
+        name,
+        description: None,
+        color: None,
+        confidence_threshold: None,
+    }).map_err(|e| CommandError::InvalidInput(format!("Invalid speaker profile: {}", e)))?;
+    if sample_rate == 0 {
+        return Err(CommandError::InvalidInput("Invalid enrollment sample: sample rate is 0".to_string()));
+    }
+    let assessment = enrollment::assess_sample(&audio_samples, sample_rate)
+        .map_err(|e| format!("Enrollment rejected: {}", e))?;
+    
+    // The live service when one is running, otherwise one with the default settings
+    let reference = {
+        let diarization_guard = state.diarization_service.lock().await;
+        let standalone = match diarization_guard.as_ref() {
+            Some(_) => None,
+            None => Some(DiarizationService::new(DiarizationConfig::default()).await
+                .map_err(|e| format!("Failed to initialize diarization: {}", e))?),
+        };
+        let service = diarization_guard.as_ref().or(standalone.as_ref())
+            .ok_or("Diarization service not initialized")?;
+        let embeddings = service.extract_speaker_embeddings(&audio_samples, sample_rate).await
+            .map_err(|e| format!("Failed to extract embeddings: {}", e))?;
+        enrollment::combine_embeddings(&embeddings, service.get_config().similarity_threshold)
+            .map_err(|e| format!("Enrollment rejected: {}", e))?
+    };
+    
+    state.speaker_storage_ready().await?;
+    let (profile, sample_id) = {
+        let store_guard = state.speaker_store.lock().await;
+        let store = store_guard.as_ref()
+            .ok_or("Speaker storage not initialized")?;
+        let profile = store.create_speaker_profile(request).await
+            .map_err(|e| format!("Failed to create speaker profile: {}", e))?;
+        let embedding = VoiceEmbedding::new(
+            profile.id,
+            reference.vector.clone(),
+            known_speakers::LIVE_EMBEDDING_MODEL.to_string(),
+            reference.quality_score,
+            reference.duration_seconds,
+        );
+        store.add_voice_embedding(embedding.clone()).await
+            .map_err(|e| format!("Failed to store enrollment embedding: {}", e))?;
+        state.embedding_index().await.lock().await.add_embedding(embedding)
+            .map_err(|e| format!("Failed to index enrollment embedding: {}", e))?;
+        let clip_len = audio_samples.len().min((SPEAKER_SAMPLE_CAP_SECONDS * sample_rate as f32) as usize);
+        let sample_id = match store_speaker_sample(store, profile.id, &audio_samples[..clip_len], sample_rate).await {
+            Ok(added) => Some(added.sample_id),
+            Err(e) => {
+                tracing::warn!("Failed to keep the enrollment sample of {}: {}", profile.id, e);
+                None
+            }
+        };
+        (profile, sample_id)
+    };
+    
+    tracing::info!("Enrolled speaker profile {} from {:.1}s of speech (quality {:.2})",
+                  profile.id, assessment.speech_seconds, reference.quality_score);
+    Ok(SpeakerEnrollment {
+        profile,
+        quality_score: reference.quality_score,
+        speech_seconds: assessment.speech_seconds,
+        embedding_count: reference.embedding_count,
+        sample_id,
+    })
+}
+
+/// Diarization settings of a session's config
+fn session_diarization_config(config: &TranscriptionConfig) -> DiarizationConfig {
+    DiarizationConfig {
+        max_speakers: config.max_speakers,
+        min_speakers: config.min_speakers,
+        embedding_dimension: 512,
+        similarity_threshold: config.similarity_threshold,
+        min_segment_duration: config.min_segment_duration,
+        speaker_change_detection_threshold: 0.6,
+        ..Default::default()
+    }
+}
+
+async fn set_diarization_status(state: &AppState, session_id: &str, status: DiarizationStatus) {
+    if let Some(session_state) = state.active_sessions.lock().await.get_mut(session_id) {
+        session_state.diarization_status = status;
+    }
+}
+
+/// Try to start speaker diarization again for a session running degraded.
+/// Chunks are attributed to speakers from the next one on.
+#[tauri::command]
+pub async fn retry_diarization(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<DiarizationStatus, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    let (active_session_id, config) = {
+        let sessions_guard = state.active_sessions.lock().await;
+        let completed_guard = state.completed_sessions.lock().await;
+        let active_session_id = resolve_active_session_id(&sessions_guard, &completed_guard, &session_id)
+            .ok_or_else(|| CommandError::NotFound(format!("Session {} not found", session_id)))?;
+        let config = sessions_guard[&active_session_id].config.clone();
+        (active_session_id, config)
+    };
+    if !config.enable_speaker_diarization {
+        return Err(CommandError::detailed("diarization_disabled", format!("Speaker diarization is not enabled for session {}", session_id), vec![
+            "Enable speaker diarization in the session settings".to_string()
+        ]));
+    }
+    
+    if state.diarization_service.lock().await.is_none() {
+        set_diarization_status(&state, &active_session_id, DiarizationStatus::Initializing).await;
+        match DiarizationService::new(session_diarization_config(&config)).await {
+            Ok(diarization_service) => {
+                state.diarization_service.lock().await.get_or_insert(diarization_service);
+            }
+            Err(e) => {
+                set_diarization_status(&state, &active_session_id, DiarizationStatus::Degraded).await;
+                return Err(e.into());
+            }
+        }
+    }
+    
+    tracing::info!("Speaker diarization active for session {}", active_session_id);
+    set_diarization_status(&state, &active_session_id, DiarizationStatus::Active).await;
+    Ok(DiarizationStatus::Active)
+}
+
+/// Diarization statistics of a live session
 #[tauri::command]
 pub async fn get_diarization_stats(
     session_id: String,
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<DiarizationStats, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
     let sessions_guard = state.active_sessions.lock().await;
-    let session_state = sessions_guard.get(&session_id)
+    let active_session_id = {
+        let completed_guard = state.completed_sessions.lock().await;
+        resolve_active_session_id(&sessions_guard, &completed_guard, &session_id)
+    };
+    let session_state = active_session_id
+        .and_then(|id| sessions_guard.get(&id))
         .ok_or("Session not found")?;
     
-    // Return basic stats - in a full implementation this would include
-    // real statistics from the diarization service
-    Ok(serde_json::json!({
-        "sessionId": session_id,
-        "speakersDetected": 0, // Placeholder
-        "totalSegments": session_state.transcription_segments.len(),
-        "averageConfidence": 0.95,
-        "processingTimeMs": 1500
-    }))
+    Ok(session_stats::compute_diarization_stats(
+        &session_state.session_id,
+        &session_state.transcription_segments,
+        &session_state.attribution_counters,
+        session_state.speaker_estimate,
+        session_state.processing_stats.processing_time.as_millis() as u64,
+    ))
 }
 
-/// Update speaker information in active session
+/// Rename a speaker of a live or finished session. The speaker's stored
+/// segments are relabelled, including earlier parts of a rollover chain and
+/// their saved copies, and later segments of a live session carry the name.
 #[tauri::command]
 pub async fn update_speaker_in_session(
     session_id: String,
     speaker_id: String,
     display_name: String,
     color: String,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     app_handle: tauri::AppHandle,
-) -> Result<String, String> {
-    // This would typically update the speaker in the diarization service
-    // and emit an update event to the frontend
+) -> Result<String, CommandError> {
+    metadata::validate_identifier("sessionId", &session_id).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+    let display_name = metadata::sanitize_text("displayName", &display_name, metadata::NAME_RULE)
+        .map_err(|e| format!("Invalid speaker update: {}", e))?;
+    let color = metadata::sanitize_color("color", &color)
+        .map_err(|e| format!("Invalid speaker update: {}", e))?;
+    
+    let has_speaker = |segments: &[serde_json::Value]| segments.iter().any(|segment| segment["speaker"].as_str() == Some(speaker_id.as_str()));
+    let (segment_ids, rewritten) = {
+        let mut sessions_guard = state.active_sessions.lock().await;
+        let mut completed_guard = state.completed_sessions.lock().await;
+        let active_session_id = resolve_active_session_id(&sessions_guard, &completed_guard, &session_id);
+        let mut segment_ids = Vec::new();
+        let mut rewritten = Vec::new();
+        
+        let mut earlier_part = match active_session_id.and_then(|id| sessions_guard.get_mut(&id)) {
+            Some(session_state) => {
+                session_state.speaker_names.rename(&speaker_id, &display_name);
+                if has_speaker(&session_state.transcription_segments) {
+                    segment_ids.extend(speaker_names::rename_segments(&mut session_state.transcription_segments, &speaker_id, &display_name));
+                    rewritten.push((session_state.session_id.clone(), session_state.transcription_segments.clone()));
+                }
+                session_state.continuation_of.clone()
+            }
+            None if completed_guard.contains_key(&session_id) => Some(session_id.clone()),
+            None => return Err(CommandError::NotFound(format!("Session {} not found", session_id))),
+
+        };
+        while let Some(part_id) = earlier_part {
+            let Some(record) = completed_guard.get_mut(&part_id) else {
+                break;
+            };
+            if has_speaker(&record.segments) {
+                segment_ids.extend(speaker_names::rename_segments(&mut record.segments, &speaker_id, &display_name));
+                rewritten.push((part_id.clone(), record.segments.clone()));
+            }
+            earlier_part = record.continuation_of.clone();
+        }
+        (segment_ids, rewritten)
+    };
+    
+    // Saved copies are rewritten in place; positions are unchanged by a rename
+    if let Some(store) = saved_session_store(&state).await {
+        for (part_id, segments) in rewritten {
+            if let Err(e) = store.append_segments(&part_id, 0, segments).await {
+                tracing::warn!("Failed to save renamed speaker in session {}: {}", part_id, e);
+            }
+        }
+    }
+    tracing::info!("Renamed speaker {} in session {} ({} segments)", speaker_id, session_id, segment_ids.len());
     
-    let _ = app_handle.emit("speaker-update", serde_json::json!({
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "speaker-update", serde_json::json!({
         "speakerId": speaker_id,
         "displayName": display_name,
         "confidence": 0.95,
         "isActive": true,
         "sessionId": session_id,
-        "timestamp": std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis(),
+        "timestamp": timestamp,
         "color": color
-    }));
+    })) {
+        tracing::warn!("Failed to emit speaker-update event: {}", emit_err);
+    }
+    if let Err(emit_err) = emit_session_event(&app_handle, &session_id, "transcription-update", serde_json::json!({
+        "sessionId": session_id,
+        "updateType": "speaker_renamed",
+        "speakerId": speaker_id,
+        "displayName": display_name,
+        "segmentIds": segment_ids,
+        "timestamp": timestamp
+    })) {
+        tracing::warn!("Failed to emit speaker_renamed update: {}", emit_err);
+    }
     
     Ok("Speaker updated successfully".to_string())
 }
@@ -2420,7 +8278,7 @@ async fn store_diarization_results_to_database(
                         .map_err(|e| format!("Failed to store voice embedding: {}", e))?;
                     
                     // Add to fast index
-                    let index_guard = state.embedding_index.lock().await;
+                    let index_guard = state.embedding_index().await.lock().await;
                     if let Err(e) = index_guard.add_embedding(voice_embedding) {
                         tracing::warn!("Failed to add embedding to index: {}", e);
                     }
@@ -2452,8 +8310,9 @@ pub async fn merge_speaker_profiles(
     primary_speaker_id: String,
     secondary_speaker_id: String,
     state: State<'_, AppState>,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     // Get both speaker profiles from storage
+    state.speaker_storage_ready().await?;
     let store_guard = state.speaker_store.lock().await;
     let store = store_guard.as_ref()
         .ok_or("Speaker storage not initialized")?;
@@ -2501,12 +8360,21 @@ pub async fn merge_speaker_profiles(
             .map_err(|e| format!("Failed to transfer embedding: {}", e))?;
     }
     
+    // Reference samples move over too, within the primary's cap
+    let secondary_samples = store.get_speaker_samples(secondary_uuid).await
+        .map_err(|e| format!("Failed to get secondary samples: {}", e))?;
+    for sample in secondary_samples {
+        let transferred_sample = SpeakerSample { id: Uuid::new_v4(), speaker_id: primary_uuid, ..sample };
+        store.add_speaker_sample(transferred_sample, SPEAKER_SAMPLE_CAP_SECONDS).await
+            .map_err(|e| format!("Failed to transfer speaker sample: {}", e))?;
+    }
+    
     // Delete secondary profile
     store.delete_speaker_profile(secondary_uuid).await
         .map_err(|e| format!("Failed to delete secondary profile: {}", e))?;
     
     // Update embedding index
-    let index_guard = state.embedding_index.lock().await;
+    let index_guard = state.embedding_index().await.lock().await;
     if let Err(e) = index_guard.remove_speaker(secondary_uuid) {
         tracing::warn!("Failed to remove secondary speaker from embedding index: {}", e);
     }
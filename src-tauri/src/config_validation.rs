@@ -0,0 +1,928 @@
+//! Validation of user-supplied transcription settings
+//!
+//! The frontend sends a [`TranscriptionConfig`] with every session start. Values
+//! that cannot work (no audio source, a non-numeric threshold) reject the start;
+//! values that are merely out of range are clamped so the session can proceed.
+//! Either way the caller gets a report listing every adjustment, and the
+//! adjusted *effective* config is what the session actually runs with.
+//! Diarization parameters are never clamped: a wrong speaker range would
+//! silently change who gets attributed what, so it rejects the start instead.
+
+use serde::{Deserialize, Serialize};
+
+use crate::asr::temperature_fallback;
+use crate::asr::types::Language;
+use crate::commands::TranscriptionConfig;
+use crate::transcription::adaptive_tier;
+use crate::transcription::chunk_window::{MAX_CHUNK_CEILING_MS, MIN_CHUNK_FLOOR_MS};
+use crate::transcription::chapters::ChapterConfig;
+use crate::transcription::keywords;
+use crate::transcription::review;
+use crate::transcription::translation;
+use crate::audio::agc;
+use crate::audio::ring_buffer;
+use crate::shutdown;
+use crate::audio::resampler::RESAMPLING_PREFERENCES;
+
+/// Quality tiers understood by the ASR engine
+pub const QUALITY_TIERS: &[&str] = &["standard", "high-accuracy", "turbo"];
+
+/// Accepted real-time factor thresholds for the adaptive tier
+pub const MIN_ADAPTIVE_TIER_THRESHOLD: f32 = 0.5;
+pub const MAX_ADAPTIVE_TIER_THRESHOLD: f32 = 5.0;
+
+/// Longest accepted audio recording limit (minutes); a 16 kHz WAV holds about 37 hours
+pub const MAX_RECORDING_MINUTES: u32 = 24 * 60;
+
+/// Longest accepted `maxDurationMinutes` and `autoStopAfterSilenceMinutes`
+pub const MAX_SESSION_LIMIT_MINUTES: u32 = 24 * 60;
+
+/// Most accepted `maxConcurrentSessions`; each session holds a capture stream and an engine
+pub const MAX_CONCURRENT_SESSIONS: u32 = 4;
+
+/// Most words compared when trimming repeated text at segment boundaries
+pub const MAX_BOUNDARY_OVERLAP_WORDS: usize = 32;
+
+/// Inference device preferences
+pub const DEVICE_PREFERENCES: &[&str] = &["auto", "cpu", "gpu"];
+
+/// Shortest accepted rollover interval (seconds)
+pub const MIN_SESSION_DURATION_SECS: u64 = 60;
+/// Longest accepted rollover interval (seconds)
+pub const MAX_SESSION_DURATION_SECS: u64 = 24 * 60 * 60;
+
+/// Diarization defaults, matching the parameters used before they were configurable
+pub const DEFAULT_MAX_SPEAKERS: u8 = 8;
+pub const DEFAULT_MIN_SPEAKERS: u8 = 2;
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.7;
+pub const DEFAULT_MIN_SEGMENT_DURATION: f32 = 1.0;
+/// Most speakers a session may be configured for
+pub const MAX_SPEAKERS_LIMIT: u8 = 20;
+/// Longest accepted minimum segment duration (seconds)
+pub const MAX_MIN_SEGMENT_DURATION: f32 = 10.0;
+
+/// Most custom vocabulary terms kept; the prompt budget may drop more
+pub const MAX_VOCABULARY_TERMS: usize = 100;
+/// Longest custom vocabulary term (characters)
+pub const MAX_VOCABULARY_TERM_CHARS: usize = 64;
+
+/// Shortest accepted spacing of partial passes (milliseconds)
+pub const MIN_PARTIAL_INTERVAL_MS: u64 = 300;
+/// Longest accepted spacing of partial passes (milliseconds)
+pub const MAX_PARTIAL_INTERVAL_MS: u64 = 5000;
+
+/// A single rejected or adjusted setting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigIssue {
+    /// Config field in frontend naming, e.g. "vadThreshold"
+    pub field: String,
+    pub message: String,
+    /// Value as supplied
+    pub original: serde_json::Value,
+    /// Value the session runs with (absent for errors)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjusted: Option<serde_json::Value>,
+}
+
+/// Outcome of validating a config: errors reject the start, warnings were clamped
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigValidationReport {
+    pub errors: Vec<ConfigIssue>,
+    pub warnings: Vec<ConfigIssue>,
+}
+
+impl ConfigValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// One-line summary of all errors for command results
+    pub fn error_summary(&self) -> String {
+        self.errors.iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    fn error(&mut self, field: &str, message: impl Into<String>, original: serde_json::Value) {
+        self.errors.push(ConfigIssue {
+            field: field.to_string(),
+            message: message.into(),
+            original,
+            adjusted: None,
+        });
+    }
+
+    fn adjust(&mut self, field: &str, message: impl Into<String>, original: serde_json::Value, adjusted: serde_json::Value) {
+        self.warnings.push(ConfigIssue {
+            field: field.to_string(),
+            message: message.into(),
+            original,
+            adjusted: Some(adjusted),
+        });
+    }
+}
+
+/// Validate `config` and return the effective config with the report.
+///
+/// When the report is valid the effective config satisfies:
+/// - `quality_tier` is one of [`QUALITY_TIERS`]
+/// - `languages` is non-empty without duplicates, and "auto" is only used alone
+/// - `vad_threshold` is finite and within `0.0..=1.0`
+/// - `max_session_duration` is `None` or within the session duration bounds
+/// - at least one audio source is enabled
+/// - `session_audio_dir` is `None` or a non-blank path
+/// - `1 <= min_speakers <= max_speakers <= MAX_SPEAKERS_LIMIT`
+/// - `similarity_threshold` is within `0.0..=1.0`
+/// - `min_segment_duration` is within `(0.0, MAX_MIN_SEGMENT_DURATION]`
+/// - `custom_vocabulary` is `None` or as returned by [`normalize_vocabulary`]
+/// - `keywords` is as returned by [`keywords::normalize_rules`]
+/// - the chapter thresholds are within `0.0..=1.0` and its durations finite
+///   and non-negative
+/// - `partial_interval_ms` is within the partial interval bounds
+/// - `partial_tier` is `None` or one of [`QUALITY_TIERS`]
+/// - `preferred_device` is one of [`DEVICE_PREFERENCES`]
+/// - `resampling_quality` is one of [`RESAMPLING_PREFERENCES`]
+/// - `mode` is one of [`translation::MODES`]
+/// - `adaptive_tier_threshold` is within the adaptive tier threshold bounds
+/// - `boundary_overlap_words` is at most [`MAX_BOUNDARY_OVERLAP_WORDS`]
+/// - `max_recording_minutes` is within `1..=MAX_RECORDING_MINUTES`
+/// - `agc_target_dbfs` is within the AGC target bounds
+/// - `review_confidence_threshold` is within `0.0..=1.0`
+/// - `replay_buffer_seconds` is at most [`ring_buffer::MAX_BUFFER_SECONDS`]
+/// - `max_duration_minutes` and `auto_stop_after_silence_minutes` are `None`
+///   or within `1..=MAX_SESSION_LIMIT_MINUTES`
+/// - `exit_grace_period_seconds` is at most [`shutdown::MAX_EXIT_GRACE_SECONDS`]
+/// - `min_chunk_ms` and `max_chunk_ms` are within the chunk bounds, with
+///   `min_chunk_ms < max_chunk_ms` and `overlap_ms < min_chunk_ms`
+/// - `max_concurrent_sessions` is within `1..=MAX_CONCURRENT_SESSIONS`
+/// - `max_decode_temperature` is within `0.0..=1.0`
+pub fn validate_transcription_config(config: &TranscriptionConfig) -> (TranscriptionConfig, ConfigValidationReport) {
+    let mut effective = config.clone();
+    let mut report = ConfigValidationReport::default();
+
+    let tier = config.quality_tier.trim().to_lowercase();
+    if !QUALITY_TIERS.contains(&tier.as_str()) {
+        report.adjust("qualityTier", format!("Unknown quality tier, expected one of {:?}", QUALITY_TIERS),
+                      config.quality_tier.clone().into(), "standard".into());
+        effective.quality_tier = "standard".to_string();
+    } else {
+        effective.quality_tier = tier;
+    }
+
+    validate_languages(config, &mut effective, &mut report);
+
+    if !config.vad_threshold.is_finite() {
+        report.error("vadThreshold", "VAD threshold must be a number between 0 and 1",
+                     serde_json::Value::String(config.vad_threshold.to_string()));
+    } else if !(0.0..=1.0).contains(&config.vad_threshold) {
+        let clamped = config.vad_threshold.clamp(0.0, 1.0);
+        report.adjust("vadThreshold", "VAD threshold clamped to 0..1",
+                      config.vad_threshold.into(), clamped.into());
+        effective.vad_threshold = clamped;
+    }
+
+    match config.max_session_duration {
+        Some(0) => {
+            report.adjust("maxSessionDuration", "A zero duration disables automatic rollover", 0.into(), serde_json::Value::Null);
+            effective.max_session_duration = None;
+        }
+        Some(secs) if !(MIN_SESSION_DURATION_SECS..=MAX_SESSION_DURATION_SECS).contains(&secs) => {
+            let clamped = secs.clamp(MIN_SESSION_DURATION_SECS, MAX_SESSION_DURATION_SECS);
+            report.adjust("maxSessionDuration",
+                          format!("Session duration clamped to {}..{} seconds", MIN_SESSION_DURATION_SECS, MAX_SESSION_DURATION_SECS),
+                          secs.into(), clamped.into());
+            effective.max_session_duration = Some(clamped);
+        }
+        _ => {}
+    }
+
+    if !config.audio_sources.microphone && !config.audio_sources.system_audio {
+        report.error("audioSources", "At least one audio source (microphone or system audio) must be enabled",
+                     serde_json::json!({ "microphone": false, "systemAudio": false }));
+    }
+
+    if let Some(dir) = &config.session_audio_dir {
+        if dir.trim().is_empty() {
+            report.adjust("sessionAudioDir", "Blank recording directory ignored", dir.clone().into(), serde_json::Value::Null);
+            effective.session_audio_dir = None;
+        }
+    }
+
+    validate_diarization(config, &mut report);
+
+    if let Some(terms) = &config.custom_vocabulary {
+        let normalized = normalize_vocabulary(terms);
+        if normalized.as_ref() != Some(terms) {
+            report.adjust("customVocabulary",
+                          format!("Blank, duplicate and over-long terms removed, at most {} kept", MAX_VOCABULARY_TERMS),
+                          serde_json::json!(terms), serde_json::json!(normalized));
+            effective.custom_vocabulary = normalized;
+        }
+    }
+
+    let keyword_rules = keywords::normalize_rules(&config.keywords);
+    if keyword_rules != config.keywords {
+        report.adjust("keywords",
+                      format!("Blank, duplicate and over-long phrases removed, at most {} kept", keywords::MAX_KEYWORD_RULES),
+                      serde_json::json!(config.keywords), serde_json::json!(keyword_rules));
+        effective.keywords = keyword_rules;
+    }
+
+    validate_chapters(&mut effective, &mut report);
+    validate_partials(config, &mut effective, &mut report);
+
+    let device = config.preferred_device.trim().to_lowercase();
+    if DEVICE_PREFERENCES.contains(&device.as_str()) {
+        effective.preferred_device = device;
+    } else {
+        report.adjust("preferredDevice", format!("Unknown device, expected one of {:?}", DEVICE_PREFERENCES),
+                      config.preferred_device.clone().into(), "auto".into());
+        effective.preferred_device = "auto".to_string();
+    }
+
+    let resampling = config.resampling_quality.trim().to_lowercase();
+    if RESAMPLING_PREFERENCES.contains(&resampling.as_str()) {
+        effective.resampling_quality = resampling;
+    } else {
+        report.adjust("resamplingQuality", format!("Unknown resampling quality, expected one of {:?}", RESAMPLING_PREFERENCES),
+                      config.resampling_quality.clone().into(), "auto".into());
+        effective.resampling_quality = "auto".to_string();
+    }
+
+    let mode = config.mode.trim().to_lowercase();
+    if translation::MODES.contains(&mode.as_str()) {
+        effective.mode = mode;
+    } else {
+        report.adjust("mode", format!("Unknown mode, expected one of {:?}", translation::MODES),
+                      config.mode.clone().into(), translation::MODE_TRANSCRIBE.into());
+        effective.mode = translation::MODE_TRANSCRIBE.to_string();
+    }
+
+    if !config.adaptive_tier_threshold.is_finite() {
+        report.adjust("adaptiveTierThreshold", "Adaptive tier threshold must be a number, using the default",
+                      serde_json::Value::String(config.adaptive_tier_threshold.to_string()),
+                      adaptive_tier::DEFAULT_RTF_THRESHOLD.into());
+        effective.adaptive_tier_threshold = adaptive_tier::DEFAULT_RTF_THRESHOLD;
+    } else if !(MIN_ADAPTIVE_TIER_THRESHOLD..=MAX_ADAPTIVE_TIER_THRESHOLD).contains(&config.adaptive_tier_threshold) {
+        let clamped = config.adaptive_tier_threshold.clamp(MIN_ADAPTIVE_TIER_THRESHOLD, MAX_ADAPTIVE_TIER_THRESHOLD);
+        report.adjust("adaptiveTierThreshold",
+                      format!("Adaptive tier threshold clamped to {}..{}", MIN_ADAPTIVE_TIER_THRESHOLD, MAX_ADAPTIVE_TIER_THRESHOLD),
+                      config.adaptive_tier_threshold.into(), clamped.into());
+        effective.adaptive_tier_threshold = clamped;
+    }
+
+    if config.boundary_overlap_words > MAX_BOUNDARY_OVERLAP_WORDS {
+        report.adjust("boundaryOverlapWords",
+                      format!("Boundary overlap limited to {} words", MAX_BOUNDARY_OVERLAP_WORDS),
+                      config.boundary_overlap_words.into(), MAX_BOUNDARY_OVERLAP_WORDS.into());
+        effective.boundary_overlap_words = MAX_BOUNDARY_OVERLAP_WORDS;
+    }
+
+    if !(1..=MAX_RECORDING_MINUTES).contains(&config.max_recording_minutes) {
+        let clamped = config.max_recording_minutes.clamp(1, MAX_RECORDING_MINUTES);
+        report.adjust("maxRecordingMinutes",
+                      format!("Recording limit clamped to 1..{} minutes", MAX_RECORDING_MINUTES),
+                      config.max_recording_minutes.into(), clamped.into());
+        effective.max_recording_minutes = clamped;
+    }
+
+    if !config.agc_target_dbfs.is_finite() {
+        report.adjust("agcTargetDbfs", "AGC target must be a number, using the default",
+                      serde_json::Value::String(config.agc_target_dbfs.to_string()),
+                      agc::DEFAULT_TARGET_DBFS.into());
+        effective.agc_target_dbfs = agc::DEFAULT_TARGET_DBFS;
+    } else if !(agc::MIN_TARGET_DBFS..=agc::MAX_TARGET_DBFS).contains(&config.agc_target_dbfs) {
+        let clamped = config.agc_target_dbfs.clamp(agc::MIN_TARGET_DBFS, agc::MAX_TARGET_DBFS);
+        report.adjust("agcTargetDbfs",
+                      format!("AGC target clamped to {}..{} dBFS", agc::MIN_TARGET_DBFS, agc::MAX_TARGET_DBFS),
+                      config.agc_target_dbfs.into(), clamped.into());
+        effective.agc_target_dbfs = clamped;
+    }
+
+    if !config.review_confidence_threshold.is_finite() {
+        report.adjust("reviewConfidenceThreshold", "Review threshold must be a number, using the default",
+                      serde_json::Value::String(config.review_confidence_threshold.to_string()),
+                      review::DEFAULT_REVIEW_THRESHOLD.into());
+        effective.review_confidence_threshold = review::DEFAULT_REVIEW_THRESHOLD;
+    } else if !(0.0..=1.0).contains(&config.review_confidence_threshold) {
+        let clamped = config.review_confidence_threshold.clamp(0.0, 1.0);
+        report.adjust("reviewConfidenceThreshold", "Review threshold clamped to 0.0..1.0",
+                      config.review_confidence_threshold.into(), clamped.into());
+        effective.review_confidence_threshold = clamped;
+    }
+
+    if config.replay_buffer_seconds > ring_buffer::MAX_BUFFER_SECONDS {
+        report.adjust("replayBufferSeconds",
+                      format!("Replay buffer capped at {} seconds", ring_buffer::MAX_BUFFER_SECONDS),
+                      config.replay_buffer_seconds.into(), ring_buffer::MAX_BUFFER_SECONDS.into());
+        effective.replay_buffer_seconds = ring_buffer::MAX_BUFFER_SECONDS;
+    }
+
+    effective.max_duration_minutes = validate_session_limit("maxDurationMinutes", config.max_duration_minutes, &mut report);
+    effective.auto_stop_after_silence_minutes =
+        validate_session_limit("autoStopAfterSilenceMinutes", config.auto_stop_after_silence_minutes, &mut report);
+
+    if config.exit_grace_period_seconds > shutdown::MAX_EXIT_GRACE_SECONDS {
+        report.adjust("exitGracePeriodSeconds",
+                      format!("Exit grace period capped at {} seconds", shutdown::MAX_EXIT_GRACE_SECONDS),
+                      config.exit_grace_period_seconds.into(), shutdown::MAX_EXIT_GRACE_SECONDS.into());
+        effective.exit_grace_period_seconds = shutdown::MAX_EXIT_GRACE_SECONDS;
+    }
+
+    validate_chunk_window(config, &mut effective, &mut report);
+
+    if !(1..=MAX_CONCURRENT_SESSIONS).contains(&config.max_concurrent_sessions) {
+        let clamped = config.max_concurrent_sessions.clamp(1, MAX_CONCURRENT_SESSIONS);
+        report.adjust("maxConcurrentSessions",
+                      format!("Concurrent sessions clamped to 1..{}", MAX_CONCURRENT_SESSIONS),
+                      config.max_concurrent_sessions.into(), clamped.into());
+        effective.max_concurrent_sessions = clamped;
+    }
+
+    if !config.max_decode_temperature.is_finite() {
+        report.adjust("maxDecodeTemperature", "Decode temperature must be a number, using the default",
+                      serde_json::Value::String(config.max_decode_temperature.to_string()),
+                      temperature_fallback::DEFAULT_MAX_TEMPERATURE.into());
+        effective.max_decode_temperature = temperature_fallback::DEFAULT_MAX_TEMPERATURE;
+    } else if !(0.0..=1.0).contains(&config.max_decode_temperature) {
+        let clamped = config.max_decode_temperature.clamp(0.0, 1.0);
+        report.adjust("maxDecodeTemperature", "Decode temperature clamped to 0.0..1.0",
+                      config.max_decode_temperature.into(), clamped.into());
+        effective.max_decode_temperature = clamped;
+    }
+
+    (effective, report)
+}
+
+/// Chapter settings that cannot work fall back to their defaults
+fn validate_chapters(effective: &mut TranscriptionConfig, report: &mut ConfigValidationReport) {
+    let defaults = ChapterConfig::default();
+    let chapters = &mut effective.chapters;
+    let fields: [(&str, &mut f32, f32, bool); 5] = [
+        ("chapters.minPauseSeconds", &mut chapters.min_pause_seconds, defaults.min_pause_seconds, false),
+        ("chapters.speakerChangeThreshold", &mut chapters.speaker_change_threshold, defaults.speaker_change_threshold, true),
+        ("chapters.lexicalSimilarityThreshold", &mut chapters.lexical_similarity_threshold, defaults.lexical_similarity_threshold, true),
+        ("chapters.windowSeconds", &mut chapters.window_seconds, defaults.window_seconds, false),
+        ("chapters.minChapterSeconds", &mut chapters.min_chapter_seconds, defaults.min_chapter_seconds, false),
+    ];
+    for (field, value, default, is_similarity) in fields {
+        let usable = value.is_finite() && *value >= 0.0 && (!is_similarity || *value <= 1.0);
+        if !usable {
+            let message = if is_similarity { "Similarity threshold must be within 0..1, using the default" } else { "Duration must be a non-negative number, using the default" };
+            report.adjust(field, message, serde_json::Value::String(value.to_string()), default.into());
+            *value = default;
+        }
+    }
+}
+
+fn validate_chunk_window(config: &TranscriptionConfig, effective: &mut TranscriptionConfig, report: &mut ConfigValidationReport) {
+    for (field, value, target) in [
+        ("minChunkMs", config.min_chunk_ms, &mut effective.min_chunk_ms),
+        ("maxChunkMs", config.max_chunk_ms, &mut effective.max_chunk_ms),
+    ] {
+        if !(MIN_CHUNK_FLOOR_MS..=MAX_CHUNK_CEILING_MS).contains(&value) {
+            let clamped = value.clamp(MIN_CHUNK_FLOOR_MS, MAX_CHUNK_CEILING_MS);
+            report.adjust(field, format!("Chunk length clamped to {}..{} ms", MIN_CHUNK_FLOOR_MS, MAX_CHUNK_CEILING_MS),
+                          value.into(), clamped.into());
+            *target = clamped;
+        }
+    }
+
+    // A swapped window would change when audio is transcribed in ways the user did not ask for
+    if effective.min_chunk_ms >= effective.max_chunk_ms {
+        report.error("maxChunkMs",
+                     format!("Maximum chunk length ({} ms) must exceed the minimum ({} ms)", effective.max_chunk_ms, effective.min_chunk_ms),
+                     config.max_chunk_ms.into());
+    } else if config.overlap_ms >= effective.min_chunk_ms {
+        let clamped = effective.min_chunk_ms / 2;
+        report.adjust("overlapMs", "Overlap must be shorter than the minimum chunk length",
+                      config.overlap_ms.into(), clamped.into());
+        effective.overlap_ms = clamped;
+    }
+}
+
+/// A zero limit is read as no limit; longer ones are capped
+fn validate_session_limit(field: &str, minutes: Option<u32>, report: &mut ConfigValidationReport) -> Option<u32> {
+    match minutes {
+        Some(0) => {
+            report.adjust(field, "A zero limit disables it", 0.into(), serde_json::Value::Null);
+            None
+        }
+        Some(minutes) if minutes > MAX_SESSION_LIMIT_MINUTES => {
+            report.adjust(field, format!("Limit capped at {} minutes", MAX_SESSION_LIMIT_MINUTES),
+                          minutes.into(), MAX_SESSION_LIMIT_MINUTES.into());
+            Some(MAX_SESSION_LIMIT_MINUTES)
+        }
+        minutes => minutes,
+    }
+}
+
+fn validate_partials(config: &TranscriptionConfig, effective: &mut TranscriptionConfig, report: &mut ConfigValidationReport) {
+    if !(MIN_PARTIAL_INTERVAL_MS..=MAX_PARTIAL_INTERVAL_MS).contains(&config.partial_interval_ms) {
+        let clamped = config.partial_interval_ms.clamp(MIN_PARTIAL_INTERVAL_MS, MAX_PARTIAL_INTERVAL_MS);
+        report.adjust("partialIntervalMs",
+                      format!("Partial interval clamped to {}..{} ms", MIN_PARTIAL_INTERVAL_MS, MAX_PARTIAL_INTERVAL_MS),
+                      config.partial_interval_ms.into(), clamped.into());
+        effective.partial_interval_ms = clamped;
+    }
+
+    if let Some(tier) = &config.partial_tier {
+        let normalized = tier.trim().to_lowercase();
+        if QUALITY_TIERS.contains(&normalized.as_str()) {
+            effective.partial_tier = Some(normalized);
+        } else {
+            report.adjust("partialTier", "Unknown partial tier, partial results use the session's engine",
+                          tier.clone().into(), serde_json::Value::Null);
+            effective.partial_tier = None;
+        }
+    }
+}
+
+/// Trimmed vocabulary terms without blanks, case-insensitive duplicates or
+/// terms over [`MAX_VOCABULARY_TERM_CHARS`], capped at [`MAX_VOCABULARY_TERMS`].
+/// `None` when no term is left.
+pub fn normalize_vocabulary(terms: &[String]) -> Option<Vec<String>> {
+    let mut seen = std::collections::HashSet::new();
+    let normalized: Vec<String> = terms.iter()
+        .map(|term| term.trim())
+        .filter(|term| !term.is_empty() && term.chars().count() <= MAX_VOCABULARY_TERM_CHARS)
+        .filter(|term| seen.insert(term.to_lowercase()))
+        .take(MAX_VOCABULARY_TERMS)
+        .map(str::to_string)
+        .collect();
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+fn validate_diarization(config: &TranscriptionConfig, report: &mut ConfigValidationReport) {
+    if config.max_speakers == 0 || config.max_speakers > MAX_SPEAKERS_LIMIT {
+        report.error("maxSpeakers", format!("Maximum speaker count must be between 1 and {}", MAX_SPEAKERS_LIMIT),
+                     config.max_speakers.into());
+    }
+    if config.min_speakers == 0 {
+        report.error("minSpeakers", "Minimum speaker count must be at least 1", config.min_speakers.into());
+    } else if config.min_speakers > config.max_speakers {
+        report.error("minSpeakers",
+                     format!("Minimum speaker count ({}) exceeds the maximum ({})", config.min_speakers, config.max_speakers),
+                     config.min_speakers.into());
+    }
+    if !(0.0..=1.0).contains(&config.similarity_threshold) {
+        report.error("similarityThreshold", "Similarity threshold must be between 0 and 1",
+                     serde_json::Value::String(config.similarity_threshold.to_string()));
+    }
+    if !(config.min_segment_duration > 0.0 && config.min_segment_duration <= MAX_MIN_SEGMENT_DURATION) {
+        report.error("minSegmentDuration",
+                     format!("Minimum segment duration must be greater than 0 and at most {} seconds", MAX_MIN_SEGMENT_DURATION),
+                     serde_json::Value::String(config.min_segment_duration.to_string()));
+    }
+}
+
+fn validate_languages(config: &TranscriptionConfig, effective: &mut TranscriptionConfig, report: &mut ConfigValidationReport) {
+    // Unknown codes are already rejected when the config is deserialized
+    let original = serde_json::json!(config.languages);
+    if config.languages.is_empty() {
+        report.adjust("languages", "No language selected, using English", original, serde_json::json!([Language::ENGLISH]));
+        effective.languages = vec![Language::ENGLISH];
+        return;
+    }
+
+    let mut languages: Vec<Language> = Vec::new();
+    for language in &config.languages {
+        if !languages.contains(language) {
+            languages.push(*language);
+        }
+    }
+    // Detection cannot be combined with explicit languages
+    if languages.len() > 1 && languages.contains(&Language::AUTO) {
+        languages.retain(|l| !l.is_auto());
+    }
+
+    if languages != config.languages {
+        report.adjust("languages", "Duplicate languages removed, \"auto\" only applies on its own",
+                      original, serde_json::json!(languages));
+        effective.languages = languages;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_default_config_is_clean() {
+        let (effective, report) = validate_transcription_config(&TranscriptionConfig::default());
+        assert!(report.is_valid());
+        assert!(report.warnings.is_empty());
+        assert_eq!(effective.languages, vec![Language::ENGLISH]);
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_clamped() {
+        let config = TranscriptionConfig {
+            quality_tier: "Ultra".to_string(),
+            languages: vec![Language::AUTO, Language::ENGLISH, Language::ENGLISH],
+            vad_threshold: 1.7,
+            max_session_duration: Some(5),
+            ..TranscriptionConfig::default()
+        };
+        let (effective, report) = validate_transcription_config(&config);
+
+        assert!(report.is_valid());
+        assert_eq!(effective.quality_tier, "standard");
+        assert_eq!(effective.languages, vec![Language::ENGLISH]);
+        assert_eq!(effective.vad_threshold, 1.0);
+        assert_eq!(effective.max_session_duration, Some(MIN_SESSION_DURATION_SECS));
+        let fields: Vec<&str> = report.warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, vec!["qualityTier", "languages", "vadThreshold", "maxSessionDuration"]);
+    }
+
+    #[test]
+    fn test_unusable_values_are_rejected() {
+        let mut config = TranscriptionConfig {
+            vad_threshold: f32::NAN,
+            ..TranscriptionConfig::default()
+        };
+        config.audio_sources.microphone = false;
+        let (_, report) = validate_transcription_config(&config);
+
+        assert!(!report.is_valid());
+        let fields: Vec<&str> = report.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["vadThreshold", "audioSources"]);
+        assert!(report.error_summary().contains("audioSources"));
+    }
+
+    #[test]
+    fn test_diarization_defaults_match_previous_behavior() {
+        let config: TranscriptionConfig = serde_json::from_value(serde_json::json!({
+            "qualityTier": "standard",
+            "languages": ["en"],
+            "enableSpeakerDiarization": true,
+            "enableTwoPassRefinement": false,
+            "audioSources": { "microphone": true, "systemAudio": false },
+            "vadThreshold": 0.5
+        })).unwrap();
+        assert_eq!((config.min_speakers, config.max_speakers), (2, 8));
+        assert_eq!(config.similarity_threshold, 0.7);
+        assert_eq!(config.min_segment_duration, 1.0);
+
+        let one_on_one = TranscriptionConfig { min_speakers: 1, max_speakers: 2, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&one_on_one);
+        assert!(report.is_valid());
+        assert_eq!((effective.min_speakers, effective.max_speakers), (1, 2));
+    }
+
+    #[test]
+    fn test_out_of_range_diarization_values_are_rejected_not_clamped() {
+        let config = TranscriptionConfig {
+            min_speakers: 6,
+            max_speakers: 4,
+            similarity_threshold: 1.5,
+            min_segment_duration: 0.0,
+            ..TranscriptionConfig::default()
+        };
+        let (_, report) = validate_transcription_config(&config);
+
+        assert!(report.warnings.is_empty());
+        let fields: Vec<&str> = report.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["minSpeakers", "similarityThreshold", "minSegmentDuration"]);
+        assert!(report.error_summary().contains("Minimum speaker count (6) exceeds the maximum (4)"));
+
+        let crowded = TranscriptionConfig { max_speakers: MAX_SPEAKERS_LIMIT + 1, ..TranscriptionConfig::default() };
+        let (_, report) = validate_transcription_config(&crowded);
+        assert_eq!(report.errors[0].field, "maxSpeakers");
+
+        let nan = TranscriptionConfig { similarity_threshold: f32::NAN, ..TranscriptionConfig::default() };
+        assert!(!validate_transcription_config(&nan).1.is_valid());
+    }
+
+    #[test]
+    fn test_custom_vocabulary_is_normalized() {
+        let long_term = "x".repeat(MAX_VOCABULARY_TERM_CHARS + 1);
+        let terms = ["  KagiNote ", "kaginote", "", "Tauri", long_term.as_str()];
+        let config = TranscriptionConfig {
+            custom_vocabulary: Some(terms.iter().map(|t| t.to_string()).collect()),
+            ..TranscriptionConfig::default()
+        };
+        let (effective, report) = validate_transcription_config(&config);
+
+        assert!(report.is_valid());
+        assert_eq!(effective.custom_vocabulary, Some(vec!["KagiNote".to_string(), "Tauri".to_string()]));
+        assert_eq!(report.warnings[0].field, "customVocabulary");
+
+        let clean = TranscriptionConfig { custom_vocabulary: Some(vec!["SLA".to_string()]), ..TranscriptionConfig::default() };
+        assert!(validate_transcription_config(&clean).1.warnings.is_empty());
+        assert_eq!(normalize_vocabulary(&[" ".to_string()]), None);
+        let many: Vec<String> = (0..MAX_VOCABULARY_TERMS + 20).map(|i| format!("term{}", i)).collect();
+        assert_eq!(normalize_vocabulary(&many).unwrap().len(), MAX_VOCABULARY_TERMS);
+    }
+
+    #[test]
+    fn test_unusable_chapter_settings_fall_back_to_defaults() {
+        let config = TranscriptionConfig {
+            chapters: ChapterConfig { min_pause_seconds: -5.0, lexical_similarity_threshold: 1.5, window_seconds: f32::NAN, ..ChapterConfig::default() },
+            ..TranscriptionConfig::default()
+        };
+        let (effective, report) = validate_transcription_config(&config);
+
+        assert!(report.is_valid());
+        let fields: Vec<&str> = report.warnings.iter().map(|issue| issue.field.as_str()).collect();
+        assert_eq!(fields, ["chapters.minPauseSeconds", "chapters.lexicalSimilarityThreshold", "chapters.windowSeconds"]);
+        assert_eq!(effective.chapters, ChapterConfig::default());
+    }
+
+    #[test]
+    fn test_keywords_are_normalized() {
+        use crate::transcription::keywords::{KeywordRule, MatchMode};
+
+        let config = TranscriptionConfig {
+            keywords: vec![
+                KeywordRule::new(" Acme  Corp ", MatchMode::Fuzzy),
+                KeywordRule::new("acme corp", MatchMode::Exact),
+                KeywordRule::new("", MatchMode::Exact),
+                KeywordRule::new("pricing", MatchMode::WordBoundary),
+            ],
+            ..TranscriptionConfig::default()
+        };
+        let (effective, report) = validate_transcription_config(&config);
+
+        assert!(report.is_valid());
+        assert_eq!(report.warnings[0].field, "keywords");
+        assert_eq!(effective.keywords, [KeywordRule::new("Acme Corp", MatchMode::Fuzzy), KeywordRule::new("pricing", MatchMode::WordBoundary)]);
+        assert!(validate_transcription_config(&effective).1.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_partial_settings() {
+        let config = TranscriptionConfig {
+            partial_results: true,
+            partial_interval_ms: 50,
+            partial_tier: Some("Lightning".to_string()),
+            ..TranscriptionConfig::default()
+        };
+        let (effective, report) = validate_transcription_config(&config);
+        assert!(report.is_valid());
+        assert_eq!(effective.partial_interval_ms, MIN_PARTIAL_INTERVAL_MS);
+        assert_eq!(effective.partial_tier, None);
+        let fields: Vec<&str> = report.warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, vec!["partialIntervalMs", "partialTier"]);
+
+        let turbo = TranscriptionConfig { partial_tier: Some(" Turbo".to_string()), ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&turbo);
+        assert!(report.warnings.is_empty());
+        assert_eq!(effective.partial_tier.as_deref(), Some("turbo"));
+    }
+
+    fn language_choices() -> Vec<Language> {
+        let mut choices = crate::asr::types::supported_languages();
+        choices.push(Language::AUTO);
+        choices
+    }
+
+    fn arb_config() -> impl Strategy<Value = TranscriptionConfig> {
+        (
+            prop_oneof![Just("standard".to_string()), Just("TURBO".to_string()), ".{0,12}"],
+            prop::collection::vec(prop::sample::select(language_choices()), 0..4),
+            prop_oneof![any::<f32>(), -2.0f32..2.0],
+            prop::option::of(prop_oneof![Just(0u64), any::<u64>(), 0u64..200_000]),
+            any::<(bool, bool)>(),
+            prop::option::of(prop_oneof![Just("  ".to_string()), ".{0,8}"]),
+            (0u8..25, 0u8..25, prop_oneof![any::<f32>(), 0.0f32..1.0], prop_oneof![any::<f32>(), 0.0f32..12.0]),
+            prop::option::of(prop::collection::vec(prop_oneof![Just(" SLA ".to_string()), Just("sla".to_string()), ".{0,80}"], 0..120)),
+            (any::<u64>(), prop::option::of(prop_oneof![Just("TURBO".to_string()), ".{0,12}"])),
+        ).prop_map(|(tier, languages, vad, duration, (microphone, system_audio), dir, (min, max, similarity, min_segment), vocabulary, (partial_interval, partial_tier))| {
+            let mut config = TranscriptionConfig {
+                quality_tier: tier,
+                languages,
+                vad_threshold: vad,
+                max_session_duration: duration,
+                session_audio_dir: dir,
+                min_speakers: min,
+                max_speakers: max,
+                similarity_threshold: similarity,
+                min_segment_duration: min_segment,
+                custom_vocabulary: vocabulary,
+                partial_interval_ms: partial_interval,
+                partial_tier,
+                ..TranscriptionConfig::default()
+            };
+            config.audio_sources.microphone = microphone;
+            config.audio_sources.system_audio = system_audio;
+            config
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_accepted_configs_satisfy_invariants(config in arb_config()) {
+            let (effective, report) = validate_transcription_config(&config);
+            if report.is_valid() {
+                prop_assert!(QUALITY_TIERS.contains(&effective.quality_tier.as_str()));
+                prop_assert!(!effective.languages.is_empty());
+                let distinct: std::collections::HashSet<_> = effective.languages.iter().collect();
+                prop_assert_eq!(distinct.len(), effective.languages.len());
+                prop_assert!(effective.languages.len() == 1 || !effective.languages.contains(&Language::AUTO));
+                prop_assert!(effective.vad_threshold.is_finite());
+                prop_assert!((0.0..=1.0).contains(&effective.vad_threshold));
+                if let Some(secs) = effective.max_session_duration {
+                    prop_assert!((MIN_SESSION_DURATION_SECS..=MAX_SESSION_DURATION_SECS).contains(&secs));
+                }
+                prop_assert!(effective.audio_sources.microphone || effective.audio_sources.system_audio);
+                prop_assert!(effective.session_audio_dir.map(|d| !d.trim().is_empty()).unwrap_or(true));
+                prop_assert!(1 <= effective.min_speakers && effective.min_speakers <= effective.max_speakers);
+                prop_assert!(effective.max_speakers <= MAX_SPEAKERS_LIMIT);
+                prop_assert!((0.0..=1.0).contains(&effective.similarity_threshold));
+                prop_assert!(effective.min_segment_duration > 0.0 && effective.min_segment_duration <= MAX_MIN_SEGMENT_DURATION);
+                if let Some(terms) = &effective.custom_vocabulary {
+                    prop_assert!(!terms.is_empty() && terms.len() <= MAX_VOCABULARY_TERMS);
+                    prop_assert!(terms.iter().all(|t| !t.is_empty() && t.trim() == t && t.chars().count() <= MAX_VOCABULARY_TERM_CHARS));
+                    let distinct: std::collections::HashSet<String> = terms.iter().map(|t| t.to_lowercase()).collect();
+                    prop_assert_eq!(distinct.len(), terms.len());
+                }
+                prop_assert!((MIN_PARTIAL_INTERVAL_MS..=MAX_PARTIAL_INTERVAL_MS).contains(&effective.partial_interval_ms));
+                prop_assert!(effective.partial_tier.map(|t| QUALITY_TIERS.contains(&t.as_str())).unwrap_or(true));
+            } else {
+                prop_assert!(report.errors.iter().all(|e| e.adjusted.is_none()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_preferred_device_is_normalized() {
+        let gpu = TranscriptionConfig { preferred_device: " GPU".to_string(), ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&gpu);
+        assert_eq!(effective.preferred_device, "gpu");
+        assert!(report.warnings.is_empty());
+
+        let tpu = TranscriptionConfig { preferred_device: "tpu".to_string(), ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&tpu);
+        assert_eq!(effective.preferred_device, "auto");
+        assert_eq!(report.warnings[0].field, "preferredDevice");
+        assert_eq!(crate::asr::types::Device::from_preference(&effective.preferred_device), Some(crate::asr::types::Device::Auto));
+    }
+
+    #[test]
+    fn test_adaptive_tier_threshold_is_clamped() {
+        let config = TranscriptionConfig { allow_adaptive_tier: true, adaptive_tier_threshold: 0.1, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert!(report.is_valid());
+        assert!(effective.allow_adaptive_tier);
+        assert_eq!(effective.adaptive_tier_threshold, MIN_ADAPTIVE_TIER_THRESHOLD);
+        assert_eq!(report.warnings[0].field, "adaptiveTierThreshold");
+    }
+
+    #[test]
+    fn test_boundary_overlap_words_are_limited() {
+        let config = TranscriptionConfig { boundary_overlap_words: 500, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert!(report.is_valid());
+        assert_eq!(effective.boundary_overlap_words, MAX_BOUNDARY_OVERLAP_WORDS);
+        assert_eq!(report.warnings[0].field, "boundaryOverlapWords");
+
+        let disabled = TranscriptionConfig { boundary_overlap_words: 0, ..TranscriptionConfig::default() };
+        assert!(validate_transcription_config(&disabled).1.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_recording_limit_is_clamped() {
+        let config = TranscriptionConfig { save_audio_recording: true, max_recording_minutes: 0, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert!(report.is_valid());
+        assert_eq!(effective.max_recording_minutes, 1);
+        assert_eq!(report.warnings[0].field, "maxRecordingMinutes");
+
+        let config = TranscriptionConfig { max_recording_minutes: 100_000, ..TranscriptionConfig::default() };
+        assert_eq!(validate_transcription_config(&config).0.max_recording_minutes, MAX_RECORDING_MINUTES);
+    }
+
+    #[test]
+    fn test_agc_target_is_clamped() {
+        let config = TranscriptionConfig { automatic_gain_control: true, agc_target_dbfs: 0.0, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert!(report.is_valid());
+        assert!(effective.automatic_gain_control);
+        assert_eq!(effective.agc_target_dbfs, agc::MAX_TARGET_DBFS);
+        assert_eq!(report.warnings[0].field, "agcTargetDbfs");
+
+        let config = TranscriptionConfig { agc_target_dbfs: f32::NAN, ..TranscriptionConfig::default() };
+        assert_eq!(validate_transcription_config(&config).0.agc_target_dbfs, agc::DEFAULT_TARGET_DBFS);
+    }
+
+    #[test]
+    fn test_review_threshold_is_clamped() {
+        let config = TranscriptionConfig { review_confidence_threshold: 1.5, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert_eq!(effective.review_confidence_threshold, 1.0);
+        assert_eq!(report.warnings[0].field, "reviewConfidenceThreshold");
+
+        let config = TranscriptionConfig { review_confidence_threshold: f32::NAN, ..TranscriptionConfig::default() };
+        assert_eq!(validate_transcription_config(&config).0.review_confidence_threshold, review::DEFAULT_REVIEW_THRESHOLD);
+    }
+
+    #[test]
+    fn test_replay_buffer_is_capped() {
+        let config = TranscriptionConfig { replay_buffer_seconds: 10_000, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert_eq!(effective.replay_buffer_seconds, ring_buffer::MAX_BUFFER_SECONDS);
+        assert_eq!(report.warnings[0].field, "replayBufferSeconds");
+    }
+
+    #[test]
+    fn test_chunk_window_is_validated() {
+        let dictation = TranscriptionConfig { min_chunk_ms: 800, max_chunk_ms: 3000, overlap_ms: 200, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&dictation);
+        assert!(report.is_valid() && report.warnings.is_empty());
+        assert_eq!((effective.min_chunk_ms, effective.max_chunk_ms, effective.overlap_ms), (800, 3000, 200));
+
+        let overlap = TranscriptionConfig { min_chunk_ms: 800, overlap_ms: 800, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&overlap);
+        assert_eq!(effective.overlap_ms, 400);
+        assert_eq!(report.warnings[0].field, "overlapMs");
+
+        let swapped = TranscriptionConfig { min_chunk_ms: 5000, max_chunk_ms: 100, ..TranscriptionConfig::default() };
+        let (_, report) = validate_transcription_config(&swapped);
+        assert!(!report.is_valid());
+        assert_eq!(report.errors[0].field, "maxChunkMs");
+    }
+
+    #[test]
+    fn test_concurrent_sessions_are_bounded() {
+        let (effective, report) = validate_transcription_config(&TranscriptionConfig::default());
+        assert_eq!(effective.max_concurrent_sessions, 1);
+        assert!(report.warnings.is_empty());
+
+        let config = TranscriptionConfig { max_concurrent_sessions: 0, ..TranscriptionConfig::default() };
+        assert_eq!(validate_transcription_config(&config).0.max_concurrent_sessions, 1);
+
+        let config = TranscriptionConfig { max_concurrent_sessions: 16, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert_eq!(effective.max_concurrent_sessions, MAX_CONCURRENT_SESSIONS);
+        assert_eq!(report.warnings[0].field, "maxConcurrentSessions");
+    }
+
+    #[test]
+    fn test_decode_temperature_ceiling_is_clamped() {
+        let config = TranscriptionConfig { max_decode_temperature: 0.4, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert_eq!(effective.max_decode_temperature, 0.4);
+        assert!(report.warnings.is_empty());
+
+        let config = TranscriptionConfig { max_decode_temperature: 2.0, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert_eq!(effective.max_decode_temperature, 1.0);
+        assert_eq!(report.warnings[0].field, "maxDecodeTemperature");
+
+        let config = TranscriptionConfig { max_decode_temperature: f32::NAN, ..TranscriptionConfig::default() };
+        assert_eq!(validate_transcription_config(&config).0.max_decode_temperature, temperature_fallback::DEFAULT_MAX_TEMPERATURE);
+    }
+
+    #[test]
+    fn test_session_limits_are_bounded() {
+        let config = TranscriptionConfig {
+            max_duration_minutes: Some(0),
+            auto_stop_after_silence_minutes: Some(MAX_SESSION_LIMIT_MINUTES + 1),
+            ..TranscriptionConfig::default()
+        };
+        let (effective, report) = validate_transcription_config(&config);
+        assert_eq!(effective.max_duration_minutes, None);
+        assert_eq!(effective.auto_stop_after_silence_minutes, Some(MAX_SESSION_LIMIT_MINUTES));
+        let fields: Vec<&str> = report.warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, vec!["maxDurationMinutes", "autoStopAfterSilenceMinutes"]);
+
+        let config = TranscriptionConfig { max_duration_minutes: Some(90), ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert_eq!(effective.max_duration_minutes, Some(90));
+        assert!(report.warnings.is_empty());
+
+        let config = TranscriptionConfig { exit_grace_period_seconds: 3600, ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&config);
+        assert_eq!(effective.exit_grace_period_seconds, shutdown::MAX_EXIT_GRACE_SECONDS);
+        assert_eq!(report.warnings[0].field, "exitGracePeriodSeconds");
+    }
+
+    #[test]
+    fn test_mode_is_normalized() {
+        let translate = TranscriptionConfig { mode: " Translate".to_string(), ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&translate);
+        assert_eq!(effective.mode, "translate");
+        assert!(report.warnings.is_empty());
+
+        let dub = TranscriptionConfig { mode: "dub".to_string(), ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&dub);
+        assert_eq!(effective.mode, "transcribe");
+        assert_eq!(report.warnings[0].field, "mode");
+    }
+
+    #[test]
+    fn test_resampling_quality_is_normalized() {
+        let fast = TranscriptionConfig { resampling_quality: "Fast ".to_string(), ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&fast);
+        assert!(report.warnings.is_empty());
+        assert_eq!(effective.resampling_quality, "fast");
+
+        let best = TranscriptionConfig { resampling_quality: "best".to_string(), ..TranscriptionConfig::default() };
+        let (effective, report) = validate_transcription_config(&best);
+        assert_eq!(effective.resampling_quality, "auto");
+        assert_eq!(report.warnings[0].field, "resamplingQuality");
+    }
+}
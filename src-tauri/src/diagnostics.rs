@@ -0,0 +1,207 @@
+//! Diagnostics bundle for support requests
+//!
+//! A single zip holding JSON snapshots of the app's state (system info, device
+//! profiles, models, embedding index, active session settings, recent logs)
+//! and a `manifest.json` describing them. User content never goes in: fields
+//! that hold it are replaced, and any string containing the text of a
+//! transcript segment is redacted wherever it turns up, logs included.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Layout version of the bundle
+pub const DIAGNOSTICS_FORMAT_VERSION: u32 = 1;
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+/// Replacement for redacted content
+pub const REDACTED: &str = "[redacted]";
+
+/// Fields that hold user content whatever their value
+const CONTENT_FIELDS: &[&str] = &["text", "rawText", "snippet", "customVocabulary", "hallucinationBlocklist", "keywords"];
+/// Segment texts this short are only redacted where a string equals them,
+/// so a "Yes." does not wipe every log line containing "yes"
+const MIN_CONTAINED_SECRET_CHARS: usize = 4;
+
+/// One file of the bundle before redaction
+pub struct BundleFile {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub contents: serde_json::Value,
+}
+
+impl BundleFile {
+    pub fn new(name: &'static str, description: &'static str, contents: impl Serialize) -> Self {
+        let contents = serde_json::to_value(contents)
+            .unwrap_or_else(|e| serde_json::json!({ "error": format!("Failed to collect: {}", e) }));
+        Self { name, description, contents }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub description: String,
+}
+
+/// Contents of manifest.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    pub os: String,
+    pub arch: String,
+    pub files: Vec<ManifestEntry>,
+    /// Values replaced because they held user content
+    pub redacted_values: usize,
+}
+
+/// Strips user content from the bundle
+pub struct Redactor {
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    /// Redact content fields and any string containing one of `texts`
+    pub fn new(texts: impl IntoIterator<Item = String>) -> Self {
+        let mut secrets: Vec<String> = texts.into_iter()
+            .map(|text| text.trim().to_lowercase())
+            .filter(|text| !text.is_empty())
+            .collect();
+        secrets.sort();
+        secrets.dedup();
+        Self { secrets }
+    }
+
+    /// Redact `value` in place; returns how many values were replaced
+    pub fn redact(&self, value: &mut serde_json::Value) -> usize {
+        match value {
+            serde_json::Value::Object(fields) => fields.iter_mut()
+                .map(|(key, field)| {
+                    if CONTENT_FIELDS.contains(&key.as_str()) {
+                        redact_content_field(field)
+                    } else {
+                        self.redact(field)
+                    }
+                })
+                .sum(),
+            serde_json::Value::Array(items) => items.iter_mut().map(|item| self.redact(item)).sum(),
+            serde_json::Value::String(text) if self.contains_secret(text) => {
+                *text = REDACTED.to_string();
+                1
+            }
+            _ => 0,
+        }
+    }
+
+    fn contains_secret(&self, text: &str) -> bool {
+        let text = text.trim().to_lowercase();
+        self.secrets.iter().any(|secret| {
+            *secret == text || (secret.chars().count() >= MIN_CONTAINED_SECRET_CHARS && text.contains(secret.as_str()))
+        })
+    }
+}
+
+/// Replace a content field, keeping how much there was
+fn redact_content_field(field: &mut serde_json::Value) -> usize {
+    let replacement = match field {
+        serde_json::Value::Null => return 0,
+        serde_json::Value::Array(items) => serde_json::Value::String(format!("{} ({} entries)", REDACTED, items.len())),
+        _ => serde_json::Value::String(REDACTED.to_string()),
+    };
+    *field = replacement;
+    1
+}
+
+/// Redact `files` and write them with a manifest to a zip at `path`
+pub fn write_bundle(path: &Path, mut files: Vec<BundleFile>, redactor: &Redactor) -> Result<DiagnosticsManifest> {
+    let redacted_values = files.iter_mut().map(|file| redactor.redact(&mut file.contents)).sum();
+    let manifest = DiagnosticsManifest {
+        format_version: DIAGNOSTICS_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        files: files.iter()
+            .map(|file| ManifestEntry { name: file.name.to_string(), description: file.description.to_string() })
+            .collect(),
+        redacted_values,
+    };
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    let file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(MANIFEST_FILE_NAME, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    for file in &files {
+        zip.start_file(file.name, options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&file.contents)?)?;
+    }
+    zip.finish().context("Failed to finish the diagnostics bundle")?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_redactor_replaces_content_fields_and_leaked_text() {
+        let redactor = Redactor::new(["Our launch date is March 3rd".to_string(), "Yes.".to_string(), " ".to_string()]);
+        let mut value = serde_json::json!({
+            "config": { "customVocabulary": ["Kagi", "LSH"], "hallucinationBlocklist": null, "vadThreshold": 0.3 },
+            "logs": [
+                { "message": "Emitting transcription update: 'our launch date is march 3rd'" },
+                { "message": "Yes, the device reconnected" },
+                { "message": "yes." }
+            ],
+            "segment": { "text": "anything", "confidence": 0.9 }
+        });
+
+        assert_eq!(redactor.redact(&mut value), 4);
+        assert_eq!(value["config"]["customVocabulary"], "[redacted] (2 entries)");
+        assert!(value["config"]["hallucinationBlocklist"].is_null());
+        assert_eq!(value["config"]["vadThreshold"], 0.3);
+        assert_eq!(value["logs"][0]["message"], REDACTED);
+        assert_eq!(value["logs"][1]["message"], "Yes, the device reconnected");
+        assert_eq!(value["logs"][2]["message"], REDACTED);
+        assert_eq!(value["segment"], serde_json::json!({ "text": REDACTED, "confidence": 0.9 }));
+    }
+
+    #[test]
+    fn test_bundle_holds_manifest_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("diagnostics.zip");
+        let files = vec![
+            BundleFile::new("system_info.json", "System capabilities", serde_json::json!({ "cpuCores": 8 })),
+            BundleFile::new("logs.json", "Recent log entries", vec![serde_json::json!({ "message": "secret words here" })]),
+        ];
+        let manifest = write_bundle(&path, files, &Redactor::new(["secret words".to_string()])).unwrap();
+        assert_eq!(manifest.redacted_values, 1);
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        assert_eq!(names.len(), 3);
+        let mut contents = String::new();
+        archive.by_name(MANIFEST_FILE_NAME).unwrap().read_to_string(&mut contents).unwrap();
+        let stored: DiagnosticsManifest = serde_json::from_str(&contents).unwrap();
+        assert_eq!(stored, manifest);
+        assert_eq!(stored.files.iter().map(|file| file.name.as_str()).collect::<Vec<_>>(), ["system_info.json", "logs.json"]);
+
+        contents.clear();
+        archive.by_name("logs.json").unwrap().read_to_string(&mut contents).unwrap();
+        assert!(!contents.contains("secret words"));
+    }
+}
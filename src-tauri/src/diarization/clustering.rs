@@ -139,6 +139,14 @@ impl SpeakerClusterer {
         // Limit the number of speakers to the configured range
         let final_clusters = self.enforce_speaker_limits(clusters).await?;
         
+        // Number speakers by where they first appear in the audio, independent of
+        // how the embeddings happened to be buffered and processed
+        let final_clusters = if self.is_deterministic() {
+            relabel_by_first_appearance(final_clusters)
+        } else {
+            final_clusters
+        };
+        
         tracing::info!("Clustered into {} distinct speakers", final_clusters.len());
         Ok(final_clusters)
     }
@@ -157,9 +165,10 @@ impl SpeakerClusterer {
         }
         
         // Iteratively merge similar clusters
+        let mut merge_count = 0;
         loop {
-            let cluster_ids: Vec<String> = clusters.keys().cloned().collect();
-            if cluster_ids.len() <= self.config.max_speakers as usize {
+            // Keep merging while clusters are similar enough; the speaker limits are enforced afterwards
+            if clusters.len() <= 1 {
                 break;
             }
             
@@ -176,7 +185,8 @@ impl SpeakerClusterer {
             let mut cluster_b_embeddings = clusters.remove(&cluster_b).unwrap();
             merged_embeddings.append(&mut cluster_b_embeddings);
             
-            let new_cluster_id = format!("merged_{}", merged_embeddings.len());
+            merge_count += 1;
+            let new_cluster_id = format!("merged_{}", merge_count);
             clusters.insert(new_cluster_id, merged_embeddings);
         }
         
@@ -203,7 +213,8 @@ impl SpeakerClusterer {
         &self,
         clusters: &HashMap<String, Vec<SpeakerEmbedding>>
     ) -> Result<((String, String), f32)> {
-        let cluster_ids: Vec<String> = clusters.keys().cloned().collect();
+        // The first pair in iteration order wins ties
+        let cluster_ids = self.cluster_order(clusters);
         let mut best_similarity = 0.0;
         let mut best_pair = (cluster_ids[0].clone(), cluster_ids[0].clone());
         
@@ -306,9 +317,10 @@ impl SpeakerClusterer {
         &self,
         clusters: &HashMap<String, Vec<SpeakerEmbedding>>
     ) -> Option<String> {
-        clusters.iter()
-            .max_by_key(|(_, embeddings)| embeddings.len())
-            .map(|(id, _)| id.clone())
+        // Earliest cluster wins ties (max_by_key keeps the last maximum)
+        self.cluster_order(clusters).into_iter()
+            .rev()
+            .max_by_key(|id| clusters[id].len())
     }
     
     /// Split a cluster into two sub-clusters
@@ -342,12 +354,13 @@ impl SpeakerClusterer {
         let mut best_similarity = 0.0;
         
         // Check similarity against existing speakers
-        for (speaker_id, speaker_embeddings) in existing_speakers.iter() {
+        for speaker_id in self.cluster_order(existing_speakers) {
+            let speaker_embeddings = &existing_speakers[&speaker_id];
             let avg_similarity = self.compute_average_similarity(&embedding, speaker_embeddings).await?;
             
             if avg_similarity > best_similarity && avg_similarity > self.config.similarity_threshold {
                 best_similarity = avg_similarity;
-                best_match = Some(speaker_id.clone());
+                best_match = Some(speaker_id);
             }
         }
         
@@ -383,6 +396,24 @@ impl SpeakerClusterer {
         Ok(total_similarity / speaker_embeddings.len() as f32)
     }
     
+    /// Deterministic mode is enabled by configuring a seed
+    fn is_deterministic(&self) -> bool {
+        self.config.deterministic_seed.is_some()
+    }
+    
+    /// Cluster IDs in iteration order: by first appearance in deterministic mode,
+    /// map order otherwise
+    fn cluster_order(&self, clusters: &HashMap<String, Vec<SpeakerEmbedding>>) -> Vec<String> {
+        let mut ids: Vec<String> = clusters.keys().cloned().collect();
+        if self.is_deterministic() {
+            ids.sort_by(|a, b| {
+                first_appearance(&clusters[a]).total_cmp(&first_appearance(&clusters[b]))
+                    .then_with(|| a.cmp(b))
+            });
+        }
+        ids
+    }
+    
     /// Get clustering statistics
     pub fn get_stats(&self) -> HashMap<String, f32> {
         let mut stats = HashMap::new();
@@ -392,4 +423,112 @@ impl SpeakerClusterer {
         stats.insert("min_speakers".to_string(), self.config.min_speakers as f32);
         stats
     }
-}
\ No newline at end of file
+}
+
+/// Start time of the earliest embedding in a cluster
+fn first_appearance(embeddings: &[SpeakerEmbedding]) -> f32 {
+    embeddings.iter().map(|e| e.timestamp_start).fold(f32::INFINITY, f32::min)
+}
+
+/// Renumber clusters speaker_1..speaker_n in order of first appearance
+fn relabel_by_first_appearance(
+    clusters: HashMap<String, Vec<SpeakerEmbedding>>
+) -> HashMap<String, Vec<SpeakerEmbedding>> {
+    let mut ordered: Vec<(String, Vec<SpeakerEmbedding>)> = clusters.into_iter().collect();
+    ordered.sort_by(|a, b| {
+        first_appearance(&a.1).total_cmp(&first_appearance(&b.1)).then_with(|| a.0.cmp(&b.0))
+    });
+
+    ordered.into_iter()
+        .enumerate()
+        .map(|(i, (_, mut embeddings))| {
+            let speaker_id = format!("speaker_{}", i + 1);
+            embeddings.sort_by(|a, b| a.timestamp_start.total_cmp(&b.timestamp_start));
+            for embedding in &mut embeddings {
+                embedding.speaker_id = Some(speaker_id.clone());
+            }
+            (speaker_id, embeddings)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Embedding of one of three synthetic voices starting at `start`
+    fn embedding(voice: usize, start: f32) -> SpeakerEmbedding {
+        let mut vector = vec![0.05f32; 8];
+        vector[voice] = 1.0;
+        SpeakerEmbedding {
+            vector,
+            confidence: 0.9,
+            timestamp_start: start,
+            timestamp_end: start + 1.0,
+            speaker_id: None,
+            quality: 0.9,
+            extracted_at: 0,
+            audio_duration_ms: 1000,
+        }
+    }
+
+    /// Voice 2 speaks first, then voice 0, then voice 1
+    fn scenario() -> Vec<SpeakerEmbedding> {
+        [2, 2, 0, 0, 1, 1, 2, 0].iter().enumerate()
+            .map(|(i, voice)| embedding(*voice, i as f32 * 2.0))
+            .collect()
+    }
+
+    fn deterministic_config() -> DiarizationConfig {
+        DiarizationConfig {
+            max_speakers: 3,
+            min_speakers: 1,
+            deterministic_seed: Some(7),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_speakers_numbered_by_first_appearance() {
+        let mut clusterer = SpeakerClusterer::new(deterministic_config()).await.unwrap();
+        let clusters = clusterer.cluster_embeddings(&scenario()).await.unwrap();
+
+        assert_eq!(clusters.len(), 3);
+        assert_eq!(clusters["speaker_1"][0].vector[2], 1.0);
+        assert_eq!(clusters["speaker_2"][0].vector[0], 1.0);
+        assert_eq!(clusters["speaker_3"][0].vector[1], 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_processing_order_does_not_change_assignment() {
+        let mut forward = SpeakerClusterer::new(deterministic_config()).await.unwrap();
+        let mut backward = SpeakerClusterer::new(deterministic_config()).await.unwrap();
+        let mut reversed = scenario();
+        reversed.reverse();
+
+        let a = forward.cluster_embeddings(&scenario()).await.unwrap();
+        let b = backward.cluster_embeddings(&reversed).await.unwrap();
+
+        for id in ["speaker_1", "speaker_2", "speaker_3"] {
+            let starts_a: Vec<f32> = a[id].iter().map(|e| e.timestamp_start).collect();
+            let starts_b: Vec<f32> = b[id].iter().map(|e| e.timestamp_start).collect();
+            assert_eq!(starts_a, starts_b);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_runs_are_identical() {
+        let mut first = SpeakerClusterer::new(deterministic_config()).await.unwrap();
+        let mut second = SpeakerClusterer::new(deterministic_config()).await.unwrap();
+
+        let a = first.cluster_embeddings(&scenario()).await.unwrap();
+        let b = second.cluster_embeddings(&scenario()).await.unwrap();
+
+        let sorted = |c: HashMap<String, Vec<SpeakerEmbedding>>| {
+            let mut v: Vec<_> = c.into_iter().collect();
+            v.sort_by(|x, y| x.0.cmp(&y.0));
+            serde_json::to_string(&v).unwrap()
+        };
+        assert_eq!(sorted(a), sorted(b));
+    }
+}
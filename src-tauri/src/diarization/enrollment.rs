@@ -0,0 +1,195 @@
+//! Speaker Enrollment
+//!
+//! A user enrolls by reading a sentence aloud. The sample must hold enough
+//! speech at a usable level, and its embeddings must all come from one
+//! voice. They are averaged into the profile's reference embedding, which
+//! re-identifies the speaker more reliably than any single embedding.
+
+use super::overlap;
+use super::types::SpeakerEmbedding;
+use crate::audio::levels;
+use thiserror::Error;
+
+/// Speech a sample must contain, in seconds
+pub const MIN_ENROLLMENT_SPEECH_SECONDS: f32 = 5.0;
+/// RMS level the speech of a sample must reach
+pub const MIN_ENROLLMENT_DBFS: f32 = -40.0;
+/// VAD threshold of the RMS gate that finds speech frames, as in live gating
+pub const ENROLLMENT_VAD_THRESHOLD: f32 = 0.5;
+/// Frame length of the speech gate
+const FRAME_MS: u32 = 30;
+
+/// Why a sample cannot be enrolled
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum EnrollmentError {
+    #[error("Sample is too quiet ({level_dbfs:.1} dBFS, needs {} dBFS); move closer to the microphone", MIN_ENROLLMENT_DBFS)]
+    TooQuiet { level_dbfs: f32 },
+
+    #[error("Sample has {speech_seconds:.1}s of speech, needs at least {}s; read the whole sentence", MIN_ENROLLMENT_SPEECH_SECONDS)]
+    TooShort { speech_seconds: f32 },
+
+    #[error("Sample contains {speakers} different voices; record only the person enrolling")]
+    MultipleSpeakers { speakers: usize },
+
+    #[error("No voice could be extracted from the sample")]
+    NoVoice,
+}
+
+/// Speech found in a sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleAssessment {
+    pub speech_seconds: f32,
+    /// RMS level of the speech frames
+    pub speech_dbfs: f32,
+}
+
+/// Check that a sample holds enough speech at a usable level
+pub fn assess_sample(samples: &[f32], sample_rate: u32) -> Result<SampleAssessment, EnrollmentError> {
+    let frame_len = ((sample_rate.max(1) * FRAME_MS / 1000) as usize).max(1);
+    let speech_threshold = levels::vad_level_threshold(ENROLLMENT_VAD_THRESHOLD);
+    let mut speech_samples = 0usize;
+    let mut speech_energy = 0.0f64;
+    let mut loudest_dbfs = levels::MIN_DBFS;
+
+    for frame in samples.chunks(frame_len) {
+        let reading = levels::measure(frame);
+        loudest_dbfs = loudest_dbfs.max(reading.rms_dbfs);
+        if reading.legacy_level() > speech_threshold {
+            speech_samples += frame.len();
+            speech_energy += frame.iter().map(|&s| (s as f64) * (s as f64)).sum::<f64>();
+        }
+    }
+
+    let speech_dbfs = if speech_samples == 0 {
+        loudest_dbfs
+    } else {
+        levels::to_dbfs((speech_energy / speech_samples as f64).sqrt() as f32)
+    };
+    if speech_dbfs < MIN_ENROLLMENT_DBFS {
+        return Err(EnrollmentError::TooQuiet { level_dbfs: speech_dbfs });
+    }
+    let speech_seconds = speech_samples as f32 / sample_rate.max(1) as f32;
+    if speech_seconds < MIN_ENROLLMENT_SPEECH_SECONDS {
+        return Err(EnrollmentError::TooShort { speech_seconds });
+    }
+    Ok(SampleAssessment { speech_seconds, speech_dbfs })
+}
+
+/// Number of voices among a sample's embeddings. Each embedding joins the
+/// first voice whose first embedding it matches at `similarity_threshold`.
+pub fn count_voices(embeddings: &[SpeakerEmbedding], similarity_threshold: f32) -> usize {
+    overlap::group_voices(embeddings, similarity_threshold).len()
+}
+
+/// The reference embedding of an enrolled speaker
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnrollmentEmbedding {
+    /// Confidence-weighted mean of the sample's embeddings, unit length
+    pub vector: Vec<f32>,
+    /// Mean embedding confidence scaled by how closely the embeddings agree (0-1)
+    pub quality_score: f32,
+    pub embedding_count: usize,
+    /// Audio the embeddings were extracted from, in seconds
+    pub duration_seconds: f32,
+}
+
+/// Average one voice's embeddings into a reference embedding
+pub fn combine_embeddings(embeddings: &[SpeakerEmbedding], similarity_threshold: f32) -> Result<EnrollmentEmbedding, EnrollmentError> {
+    let dimension = embeddings.first().map(|e| e.vector.len()).unwrap_or(0);
+    if dimension == 0 {
+        return Err(EnrollmentError::NoVoice);
+    }
+    let speakers = count_voices(embeddings, similarity_threshold);
+    if speakers > 1 {
+        return Err(EnrollmentError::MultipleSpeakers { speakers });
+    }
+
+    let mut vector = vec![0.0f32; dimension];
+    for embedding in embeddings {
+        let weight = embedding.confidence.max(1e-3);
+        for (sum, value) in vector.iter_mut().zip(&embedding.vector) {
+            *sum += weight * value;
+        }
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+
+    let centroid = SpeakerEmbedding { vector: vector.clone(), ..embeddings[0].clone() };
+    let count = embeddings.len() as f32;
+    let mean_confidence = embeddings.iter().map(|e| e.confidence).sum::<f32>() / count;
+    let mean_agreement = embeddings.iter().map(|e| e.similarity(&centroid)).sum::<f32>() / count;
+    Ok(EnrollmentEmbedding {
+        vector,
+        quality_score: (mean_confidence * mean_agreement).clamp(0.0, 1.0),
+        embedding_count: embeddings.len(),
+        duration_seconds: embeddings.iter().map(|e| (e.timestamp_end - e.timestamp_start).max(0.0)).sum(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(seconds: f32, amplitude: f32) -> Vec<f32> {
+        (0..(seconds * 16_000.0) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / 16_000.0).sin())
+            .collect()
+    }
+
+    fn embedding(vector: Vec<f32>, confidence: f32) -> SpeakerEmbedding {
+        SpeakerEmbedding {
+            vector,
+            confidence,
+            timestamp_start: 0.0,
+            timestamp_end: 2.0,
+            speaker_id: None,
+            quality: confidence,
+            extracted_at: 0,
+            audio_duration_ms: 2000,
+        }
+    }
+
+    #[test]
+    fn test_sample_needs_enough_speech() {
+        let mut samples = tone(3.0, 0.3);
+        assert!(matches!(assess_sample(&samples, 16_000), Err(EnrollmentError::TooShort { .. })));
+
+        // Silence around the reading does not count as speech
+        samples.extend(vec![0.0; 16_000 * 4]);
+        samples.extend(tone(2.5, 0.3));
+        let assessment = assess_sample(&samples, 16_000).unwrap();
+        assert!((assessment.speech_seconds - 5.5).abs() < 0.05);
+        assert!(assessment.speech_dbfs > -20.0);
+    }
+
+    #[test]
+    fn test_quiet_sample_is_rejected() {
+        assert!(matches!(assess_sample(&tone(8.0, 0.005), 16_000), Err(EnrollmentError::TooQuiet { .. })));
+        assert!(matches!(assess_sample(&vec![0.0; 16_000 * 8], 16_000), Err(EnrollmentError::TooQuiet { .. })));
+    }
+
+    #[test]
+    fn test_one_voice_is_averaged() {
+        let embeddings = vec![embedding(vec![1.0, 0.1, 0.0], 0.9), embedding(vec![0.9, 0.0, 0.1], 0.8)];
+        let combined = combine_embeddings(&embeddings, 0.7).unwrap();
+        assert_eq!(combined.embedding_count, 2);
+        assert_eq!(combined.duration_seconds, 4.0);
+        let norm = combined.vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!(combined.quality_score > 0.8 && combined.quality_score <= 0.85);
+    }
+
+    #[test]
+    fn test_second_voice_is_rejected() {
+        let embeddings = vec![
+            embedding(vec![1.0, 0.0, 0.0], 0.9),
+            embedding(vec![0.0, 1.0, 0.0], 0.9),
+            embedding(vec![0.95, 0.05, 0.0], 0.9),
+        ];
+        assert_eq!(count_voices(&embeddings, 0.7), 2);
+        assert_eq!(combine_embeddings(&embeddings, 0.7), Err(EnrollmentError::MultipleSpeakers { speakers: 2 }));
+        assert_eq!(combine_embeddings(&[], 0.7), Err(EnrollmentError::NoVoice));
+    }
+}
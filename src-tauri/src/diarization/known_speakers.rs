@@ -0,0 +1,277 @@
+//! Speakers recognised across sessions
+//!
+//! Live diarization labels speakers per session. An embedding that matches
+//! none of the session's speakers is searched against the stored speaker
+//! profiles, and a match at or above that profile's own confidence threshold
+//! is attributed to the profile. A match that clears the diarization
+//! similarity threshold but not the profile's own threshold is a near miss:
+//! the voice gets a provisional session label and the miss is counted for
+//! threshold tuning. Speakers that match nothing are collected for the rest
+//! of the session so they keep one label, and can be saved as provisional
+//! profiles when the session ends.
+
+use crate::diarization::types::SpeakerEmbedding;
+use crate::models::{CreateSpeakerProfileRequest, SpeakerProfile, VoiceEmbedding};
+use uuid::Uuid;
+
+/// Lowest similarity searched for in the embedding index; each profile's own threshold then applies
+pub const INDEX_SEARCH_FLOOR: f32 = 0.5;
+/// Profiles checked against their thresholds per search
+pub const MAX_INDEX_CANDIDATES: usize = 5;
+/// Embeddings kept per unknown speaker for its provisional profile
+pub const MAX_PROVISIONAL_EMBEDDINGS: usize = 5;
+/// Model name stored with embeddings from live diarization
+pub const LIVE_EMBEDDING_MODEL: &str = "diarization_model";
+
+/// A stored profile an embedding was attributed to
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnownSpeaker {
+    pub profile_id: Uuid,
+    pub name: String,
+    pub similarity: f32,
+}
+
+impl KnownSpeaker {
+    /// Speaker id used in transcript segments and events
+    pub fn speaker_id(&self) -> String {
+        self.profile_id.to_string()
+    }
+}
+
+/// The most similar candidate that reaches its profile's confidence
+/// threshold. Inactive profiles never match.
+pub fn best_known_speaker(candidates: impl IntoIterator<Item = (SpeakerProfile, f32)>) -> Option<KnownSpeaker> {
+    candidates.into_iter()
+        .filter(|(profile, similarity)| profile.is_active && *similarity >= profile.confidence_threshold)
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(profile, similarity)| KnownSpeaker {
+            profile_id: profile.id,
+            name: profile.name,
+            similarity,
+        })
+}
+
+/// A stored profile an embedding came close to without reaching the
+/// profile's confidence threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearMiss {
+    pub profile_id: Uuid,
+    pub name: String,
+    pub similarity: f32,
+    /// The profile's confidence threshold the similarity fell short of
+    pub threshold: f32,
+}
+
+/// Outcome of searching the stored profiles for an embedding
+#[derive(Debug, Clone, PartialEq)]
+pub enum KnownSpeakerMatch {
+    Known(KnownSpeaker),
+    NearMiss(NearMiss),
+    Unknown,
+}
+
+/// Like `best_known_speaker`, but when no profile's threshold is reached the
+/// most similar active profile at or above `similarity_floor` is reported as
+/// a near miss
+pub fn match_known_speaker(
+    candidates: impl IntoIterator<Item = (SpeakerProfile, f32)>,
+    similarity_floor: f32,
+) -> KnownSpeakerMatch {
+    let candidates: Vec<(SpeakerProfile, f32)> = candidates.into_iter().collect();
+    let near_miss = candidates.iter()
+        .filter(|(profile, similarity)| {
+            profile.is_active && *similarity >= similarity_floor && *similarity < profile.confidence_threshold
+        })
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(profile, similarity)| NearMiss {
+            profile_id: profile.id,
+            name: profile.name.clone(),
+            similarity: *similarity,
+            threshold: profile.confidence_threshold,
+        });
+    match (best_known_speaker(candidates), near_miss) {
+        (Some(known), _) => KnownSpeakerMatch::Known(known),
+        (None, Some(near_miss)) => KnownSpeakerMatch::NearMiss(near_miss),
+        (None, None) => KnownSpeakerMatch::Unknown,
+    }
+}
+
+/// A session speaker that matched no stored profile
+#[derive(Debug, Clone)]
+pub struct UnknownSpeaker {
+    pub speaker_id: String,
+    /// Most confident embeddings, best first
+    pub embeddings: Vec<SpeakerEmbedding>,
+}
+
+impl UnknownSpeaker {
+    /// Profile request for saving this speaker at the end of `session_id`
+    pub fn profile_request(&self, session_id: &str) -> CreateSpeakerProfileRequest {
+        let number = self.speaker_id.strip_prefix("speaker_").unwrap_or(&self.speaker_id);
+        CreateSpeakerProfileRequest {
+            name: format!("Unconfirmed speaker {}", number),
+            description: Some(format!("Provisional profile from session {}", session_id)),
+            color: None,
+            confidence_threshold: None,
+        }
+    }
+
+    /// The kept embeddings, owned by `profile_id`
+    pub fn voice_embeddings(&self, profile_id: Uuid) -> Vec<VoiceEmbedding> {
+        self.embeddings.iter()
+            .map(|embedding| VoiceEmbedding::new(
+                profile_id,
+                embedding.vector.clone(),
+                LIVE_EMBEDDING_MODEL.to_string(),
+                embedding.confidence,
+                (embedding.timestamp_end - embedding.timestamp_start).max(0.0),
+            ))
+            .collect()
+    }
+}
+
+/// Unknown speakers of a session, in order of first appearance
+#[derive(Debug, Clone, Default)]
+pub struct UnknownSpeakers {
+    speakers: Vec<UnknownSpeaker>,
+}
+
+impl UnknownSpeakers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The unknown speaker whose embeddings best match `embedding` at or above `threshold`
+    pub fn matching_speaker(&self, embedding: &SpeakerEmbedding, threshold: f32) -> Option<&str> {
+        self.speakers.iter()
+            .map(|speaker| {
+                let similarity = speaker.embeddings.iter()
+                    .map(|stored| embedding.similarity(stored))
+                    .fold(0.0f32, f32::max);
+                (speaker, similarity)
+            })
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(speaker, _)| speaker.speaker_id.as_str())
+    }
+
+    /// Keep `embedding` for `speaker_id`, adding the speaker if it is new
+    pub fn record(&mut self, speaker_id: &str, embedding: SpeakerEmbedding) {
+        let index = match self.speakers.iter().position(|speaker| speaker.speaker_id == speaker_id) {
+            Some(index) => index,
+            None => {
+                self.speakers.push(UnknownSpeaker { speaker_id: speaker_id.to_string(), embeddings: Vec::new() });
+                self.speakers.len() - 1
+            }
+        };
+        let embeddings = &mut self.speakers[index].embeddings;
+        embeddings.push(embedding);
+        embeddings.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+        embeddings.truncate(MAX_PROVISIONAL_EMBEDDINGS);
+    }
+
+    pub fn speakers(&self) -> &[UnknownSpeaker] {
+        &self.speakers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.speakers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, threshold: f32, is_active: bool) -> SpeakerProfile {
+        let mut profile = SpeakerProfile::new(name.to_string());
+        profile.confidence_threshold = threshold;
+        profile.is_active = is_active;
+        profile
+    }
+
+    fn embedding(vector: Vec<f32>, confidence: f32) -> SpeakerEmbedding {
+        SpeakerEmbedding {
+            vector,
+            confidence,
+            timestamp_start: 1.0,
+            timestamp_end: 3.5,
+            speaker_id: None,
+            quality: confidence,
+            extracted_at: 0,
+            audio_duration_ms: 2500,
+        }
+    }
+
+    #[test]
+    fn test_match_must_reach_the_profiles_own_threshold() {
+        let strict = profile("Alice", 0.9, true);
+        let lenient = profile("Bob", 0.6, true);
+        let matched = best_known_speaker(vec![(strict, 0.85), (lenient.clone(), 0.7)]).unwrap();
+        assert_eq!(matched.name, "Bob");
+        assert_eq!(matched.speaker_id(), lenient.id.to_string());
+        assert!(best_known_speaker(vec![(profile("Carol", 0.8, true), 0.79)]).is_none());
+    }
+
+    #[test]
+    fn test_best_match_wins_and_inactive_profiles_are_skipped() {
+        let inactive = profile("Dana", 0.5, false);
+        let close = profile("Erin", 0.7, true);
+        let closer = profile("Finn", 0.7, true);
+        let matched = best_known_speaker(vec![(inactive, 0.99), (close, 0.8), (closer, 0.9)]).unwrap();
+        assert_eq!(matched.name, "Finn");
+        assert_eq!(matched.similarity, 0.9);
+    }
+
+    #[test]
+    fn test_borderline_similarity_is_a_near_miss_of_the_strict_profile() {
+        let strict = profile("Me", 0.9, true);
+        let lenient = profile("Guest", 0.6, true);
+
+        // Clears the global floor, falls short of the strict profile, too far from the lenient one
+        let outcome = match_known_speaker(vec![(strict.clone(), 0.82), (lenient.clone(), 0.55)], 0.7);
+        assert_eq!(outcome, KnownSpeakerMatch::NearMiss(NearMiss {
+            profile_id: strict.id,
+            name: "Me".to_string(),
+            similarity: 0.82,
+            threshold: 0.9,
+        }));
+
+        // The lenient profile accepts the same similarity, so it is a match, not a miss
+        let outcome = match_known_speaker(vec![(strict.clone(), 0.82), (lenient.clone(), 0.82)], 0.7);
+        assert!(matches!(outcome, KnownSpeakerMatch::Known(known) if known.profile_id == lenient.id));
+
+        // Below the global floor nothing is recorded
+        assert_eq!(match_known_speaker(vec![(strict, 0.65)], 0.7), KnownSpeakerMatch::Unknown);
+        assert_eq!(match_known_speaker(vec![(profile("Off", 0.9, false), 0.85)], 0.7), KnownSpeakerMatch::Unknown);
+    }
+
+    #[test]
+    fn test_unknown_speakers_keep_their_label() {
+        let mut unknown = UnknownSpeakers::new();
+        assert!(unknown.matching_speaker(&embedding(vec![1.0, 0.0], 0.9), 0.7).is_none());
+        unknown.record("speaker_1", embedding(vec![1.0, 0.0], 0.9));
+        unknown.record("speaker_2", embedding(vec![0.0, 1.0], 0.9));
+
+        assert_eq!(unknown.matching_speaker(&embedding(vec![0.9, 0.1], 0.8), 0.7), Some("speaker_1"));
+        assert_eq!(unknown.matching_speaker(&embedding(vec![0.1, 0.9], 0.8), 0.7), Some("speaker_2"));
+        assert!(unknown.matching_speaker(&embedding(vec![1.0, 1.0], 0.8), 0.8).is_none());
+    }
+
+    #[test]
+    fn test_most_confident_embeddings_are_kept_for_the_profile() {
+        let mut unknown = UnknownSpeakers::new();
+        for i in 0..(MAX_PROVISIONAL_EMBEDDINGS + 3) {
+            unknown.record("speaker_3", embedding(vec![1.0, 0.0], i as f32 / 10.0));
+        }
+        let speaker = &unknown.speakers()[0];
+        assert_eq!(speaker.embeddings.len(), MAX_PROVISIONAL_EMBEDDINGS);
+        assert_eq!(speaker.embeddings[0].confidence, 0.7);
+
+        let request = speaker.profile_request("session-1");
+        assert_eq!(request.name, "Unconfirmed speaker 3");
+        let profile_id = Uuid::new_v4();
+        let stored = speaker.voice_embeddings(profile_id);
+        assert!(stored.iter().all(|e| e.speaker_id == profile_id && e.duration_seconds == 2.5));
+    }
+}
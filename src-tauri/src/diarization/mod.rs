@@ -12,11 +12,23 @@ pub mod clustering;
 pub mod buffer_manager;
 pub mod segment_merger;
 pub mod model_manager;
+pub mod speaker_count;
+pub mod reattribution;
+pub mod muting;
+pub mod speaker_stats;
+pub mod known_speakers;
+pub mod speaker_names;
+pub mod enrollment;
+pub mod refinement;
+pub mod overlap;
+pub mod session_stats;
+pub mod recovery;
 
 // Re-export main types and service
 pub use types::*;
 pub use service::DiarizationService;
 pub use pipeline::DiarizationPipeline;
+pub use speaker_count::{SpeakerCountEstimate, SpeakerCountEstimator};
 
 // Re-export for backward compatibility with test expectations
 pub use service::DiarizationService as DiarizationEngine;
@@ -0,0 +1,253 @@
+//! Speaker Muting
+//!
+//! Lets users exclude one voice (e.g. an interpreter repeating everything)
+//! from the transcript. Muted speakers are still processed and stored; their
+//! segments are only flagged and left out of the live stream and default
+//! exports. Mutes are keyed by the person, not the label: when speaker ids
+//! are remapped (re-clustering, re-diarization) the alias map carries the
+//! mute over to the new id.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Segment field marking a muted segment
+pub const MUTED_FIELD: &str = "muted";
+
+/// Muted speakers of a session and the id remaps seen so far
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerMutes {
+    /// Muted persons, identified by the speaker id they were first known by
+    muted: BTreeSet<String>,
+    /// Current speaker id -> person, for ids that changed since first seen
+    aliases: HashMap<String, String>,
+}
+
+impl SpeakerMutes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mutes recovered from the flags of saved segments, for a session whose
+    /// mute state did not survive a restart
+    pub fn from_flagged_segments(segments: &[serde_json::Value]) -> Self {
+        let muted = segments.iter()
+            .filter(|segment| is_muted_segment(segment))
+            .filter_map(|segment| segment["speaker"].as_str().map(str::to_string))
+            .collect();
+        Self { muted, aliases: HashMap::new() }
+    }
+
+    /// Person behind a current speaker id
+    pub fn person_of(&self, speaker_id: &str) -> String {
+        self.aliases.get(speaker_id).cloned().unwrap_or_else(|| speaker_id.to_string())
+    }
+
+    /// Mute a speaker; returns false if they were already muted
+    pub fn mute(&mut self, speaker_id: &str) -> bool {
+        self.muted.insert(self.person_of(speaker_id))
+    }
+
+    /// Unmute a speaker; returns false if they were not muted
+    pub fn unmute(&mut self, speaker_id: &str) -> bool {
+        self.muted.remove(&self.person_of(speaker_id))
+    }
+
+    pub fn is_muted(&self, speaker_id: &str) -> bool {
+        self.muted.contains(&self.person_of(speaker_id))
+    }
+
+    /// Current speaker ids of the muted persons
+    pub fn muted_speakers(&self) -> Vec<String> {
+        let mut speakers: BTreeSet<String> = self.aliases.iter()
+            .filter(|(_, person)| self.muted.contains(*person))
+            .map(|(speaker, _)| speaker.clone())
+            .collect();
+        // Persons never remapped are still known by their own id
+        let remapped: BTreeSet<&String> = self.aliases.keys().chain(self.aliases.values()).collect();
+        speakers.extend(self.muted.iter().filter(|person| !remapped.contains(person)).cloned());
+        speakers.into_iter().collect()
+    }
+
+    /// Record that speaker ids were renamed (old id -> new id) so mutes follow
+    /// the person. When several ids merge into one, a muted person wins.
+    pub fn apply_remap(&mut self, remap: &HashMap<String, String>) {
+        let mut next: HashMap<String, String> = self.aliases.iter()
+            .filter(|(speaker, _)| !remap.contains_key(*speaker))
+            .map(|(speaker, person)| (speaker.clone(), person.clone()))
+            .collect();
+
+        let mut entries: Vec<(&String, &String)> = remap.iter().collect();
+        entries.sort();
+        let mut assigned: BTreeSet<&String> = BTreeSet::new();
+        for (old, new) in entries {
+            let person = self.person_of(old);
+            let keep_existing = assigned.contains(new)
+                && next.get(new).map(|p| self.muted.contains(p)).unwrap_or(false);
+            if !keep_existing {
+                next.insert(new.clone(), person);
+            }
+            assigned.insert(new);
+        }
+        next.retain(|speaker, person| speaker != person);
+        self.aliases = next;
+    }
+
+    /// Set the muted flag of one stored segment; returns whether it is muted
+    pub fn flag_segment(&self, segment: &mut serde_json::Value) -> bool {
+        let muted = segment["speaker"].as_str().map(|s| self.is_muted(s)).unwrap_or(false);
+        if let Some(fields) = segment.as_object_mut() {
+            if muted {
+                fields.insert(MUTED_FIELD.to_string(), serde_json::Value::Bool(true));
+            } else {
+                fields.remove(MUTED_FIELD);
+            }
+        }
+        muted
+    }
+
+    /// Refresh the muted flags of stored segments after a (un)mute or remap
+    pub fn flag_segments(&self, segments: &mut [serde_json::Value]) -> usize {
+        segments.iter_mut().map(|segment| self.flag_segment(segment)).filter(|muted| *muted).count()
+    }
+}
+
+pub fn is_muted_segment(segment: &serde_json::Value) -> bool {
+    segment[MUTED_FIELD].as_bool().unwrap_or(false)
+}
+
+/// Segments for an export; muted ones are only included on request
+pub fn export_segments(segments: &[serde_json::Value], include_muted: bool) -> Vec<serde_json::Value> {
+    segments.iter()
+        .filter(|segment| include_muted || !is_muted_segment(segment))
+        .cloned()
+        .collect()
+}
+
+/// Old -> new speaker ids implied by re-attributing segments: each previous
+/// speaker maps to the speaker that received most of their talk time.
+/// `previous` holds the speaker of each segment before re-attribution.
+pub fn derive_remap(previous: &[Option<String>], segments: &[serde_json::Value]) -> HashMap<String, String> {
+    let mut votes: HashMap<&str, HashMap<&str, f64>> = HashMap::new();
+    for (before, segment) in previous.iter().zip(segments) {
+        let (Some(before), Some(after)) = (before.as_deref(), segment["speaker"].as_str()) else {
+            continue;
+        };
+        let duration = segment["endTime"].as_f64().unwrap_or(0.0) - segment["startTime"].as_f64().unwrap_or(0.0);
+        *votes.entry(before).or_default().entry(after).or_insert(0.0) += duration.max(0.0) + 1e-6;
+    }
+
+    votes.into_iter()
+        .filter_map(|(before, targets)| {
+            targets.into_iter()
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal).then(b.0.cmp(a.0)))
+                .map(|(after, _)| (before.to_string(), after.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Alternating two-speaker session, "interpreter" repeats every line of "host"
+    fn two_speaker_session() -> Vec<serde_json::Value> {
+        (0..6).map(|i| {
+            let speaker = if i % 2 == 0 { "speaker_1" } else { "speaker_2" };
+            serde_json::json!({
+                "text": format!("line {}", i / 2),
+                "startTime": i as f64 * 2.0,
+                "endTime": i as f64 * 2.0 + 1.5,
+                "speaker": speaker,
+            })
+        }).collect()
+    }
+
+    #[test]
+    fn test_muted_segments_are_flagged_not_dropped() {
+        let mut mutes = SpeakerMutes::new();
+        assert!(mutes.mute("speaker_2"));
+        assert!(!mutes.mute("speaker_2"));
+
+        let mut segments = two_speaker_session();
+        // Emission decision per new segment, as in the live loop
+        let mut emitted = Vec::new();
+        for segment in segments.iter_mut() {
+            if !mutes.flag_segment(segment) {
+                emitted.push(segment["speaker"].as_str().unwrap().to_string());
+            }
+        }
+
+        assert_eq!(emitted, vec!["speaker_1".to_string(); 3]);
+        assert_eq!(segments.len(), 6);
+        assert_eq!(segments.iter().filter(|s| is_muted_segment(s)).count(), 3);
+        assert!(!is_muted_segment(&segments[0]));
+    }
+
+    #[test]
+    fn test_export_excludes_muted_unless_requested() {
+        let mut mutes = SpeakerMutes::new();
+        mutes.mute("speaker_2");
+        let mut segments = two_speaker_session();
+        assert_eq!(mutes.flag_segments(&mut segments), 3);
+
+        let default_export = export_segments(&segments, false);
+        assert_eq!(default_export.len(), 3);
+        assert!(default_export.iter().all(|s| s["speaker"] == "speaker_1"));
+        assert_eq!(export_segments(&segments, true).len(), 6);
+
+        // Unmuting clears the flags again
+        assert!(mutes.unmute("speaker_2"));
+        assert_eq!(mutes.flag_segments(&mut segments), 0);
+        assert_eq!(export_segments(&segments, false).len(), 6);
+    }
+
+    #[test]
+    fn test_mute_follows_speaker_across_remap() {
+        let mut mutes = SpeakerMutes::new();
+        mutes.mute("speaker_2");
+        let mut segments = two_speaker_session();
+        let previous: Vec<Option<String>> = segments.iter()
+            .map(|s| s["speaker"].as_str().map(str::to_string))
+            .collect();
+
+        // Re-clustering swaps the labels of the two voices
+        for segment in segments.iter_mut() {
+            let swapped = if segment["speaker"] == "speaker_1" { "speaker_2" } else { "speaker_1" };
+            segment["speaker"] = serde_json::json!(swapped);
+        }
+        let remap = derive_remap(&previous, &segments);
+        assert_eq!(remap["speaker_2"], "speaker_1");
+        mutes.apply_remap(&remap);
+        mutes.flag_segments(&mut segments);
+
+        // The interpreter (odd segments) stays muted under the new label
+        assert_eq!(mutes.muted_speakers(), vec!["speaker_1".to_string()]);
+        assert!(segments.iter().enumerate().all(|(i, s)| is_muted_segment(s) == (i % 2 == 1)));
+        assert!(mutes.is_muted("speaker_1"));
+        assert!(!mutes.is_muted("speaker_2"));
+    }
+
+    #[test]
+    fn test_mutes_are_recovered_from_saved_flags() {
+        let mut mutes = SpeakerMutes::new();
+        mutes.mute("speaker_2");
+        let mut segments = two_speaker_session();
+        mutes.flag_segments(&mut segments);
+
+        let recovered = SpeakerMutes::from_flagged_segments(&segments);
+        assert_eq!(recovered.muted_speakers(), vec!["speaker_2".to_string()]);
+        assert!(!recovered.is_muted("speaker_1"));
+    }
+
+    #[test]
+    fn test_state_round_trips_through_serde() {
+        let mut mutes = SpeakerMutes::new();
+        mutes.mute("speaker_3");
+        mutes.apply_remap(&HashMap::from([("speaker_3".to_string(), "speaker_1".to_string())]));
+
+        let restored: SpeakerMutes = serde_json::from_value(serde_json::to_value(&mutes).unwrap()).unwrap();
+        assert_eq!(restored, mutes);
+        assert_eq!(restored.person_of("speaker_1"), "speaker_3");
+        assert_eq!(restored.muted_speakers(), vec!["speaker_1".to_string()]);
+    }
+}
@@ -0,0 +1,177 @@
+//! Cross-talk Within a Chunk
+//!
+//! A buffered chunk yields several embedding windows. When they come from
+//! more than one voice, the segment is attributed to the voice that speaks
+//! longest and the others are listed as overlapping speakers, so cross-talk
+//! can be flagged in the transcript.
+
+use super::types::SpeakerEmbedding;
+use std::collections::HashMap;
+
+/// Segment field listing the other speakers heard in the segment
+pub const OVERLAPPING_SPEAKERS_FIELD: &str = "overlappingSpeakers";
+/// Speech a secondary voice needs within a chunk to count as overlapping
+pub const MIN_OVERLAP_SECONDS: f32 = 0.5;
+
+/// Embedding windows of one voice within a chunk
+#[derive(Debug, Clone)]
+pub struct ChunkVoice {
+    pub embeddings: Vec<SpeakerEmbedding>,
+    /// Time covered by the voice's windows
+    pub seconds: f32,
+}
+
+/// Length of the union of the windows' spans
+fn covered_seconds(embeddings: &[SpeakerEmbedding]) -> f32 {
+    let mut spans: Vec<(f32, f32)> = embeddings.iter()
+        .map(|e| (e.timestamp_start, e.timestamp_end.max(e.timestamp_start)))
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut total = 0.0;
+    let mut current: Option<(f32, f32)> = None;
+    for (start, end) in spans {
+        current = match current {
+            Some((current_start, current_end)) if start <= current_end => Some((current_start, current_end.max(end))),
+            Some((current_start, current_end)) => {
+                total += current_end - current_start;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    total + current.map(|(start, end)| end - start).unwrap_or(0.0)
+}
+
+/// Group a chunk's embeddings by voice, dominant voice first. Each embedding
+/// joins the first voice whose first embedding it matches at `similarity_threshold`.
+pub fn group_voices(embeddings: &[SpeakerEmbedding], similarity_threshold: f32) -> Vec<ChunkVoice> {
+    let mut voices: Vec<ChunkVoice> = Vec::new();
+    for embedding in embeddings {
+        match voices.iter_mut().find(|voice| voice.embeddings[0].similarity(embedding) >= similarity_threshold) {
+            Some(voice) => voice.embeddings.push(embedding.clone()),
+            None => voices.push(ChunkVoice { embeddings: vec![embedding.clone()], seconds: 0.0 }),
+        }
+    }
+    for voice in &mut voices {
+        voice.seconds = covered_seconds(&voice.embeddings);
+    }
+    // Stable sort: the voice heard first wins ties
+    voices.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+    voices
+}
+
+/// List `others` on a segment attributed to `dominant`; the dominant speaker
+/// and duplicates are left out, and nothing is written when none remain
+pub fn mark_overlap(segment: &mut serde_json::Value, dominant: &str, others: &[String]) {
+    let mut overlapping: Vec<&str> = Vec::new();
+    for other in others {
+        if other != dominant && !overlapping.contains(&other.as_str()) {
+            overlapping.push(other);
+        }
+    }
+    if overlapping.is_empty() {
+        return;
+    }
+    if let Some(fields) = segment.as_object_mut() {
+        fields.insert(OVERLAPPING_SPEAKERS_FIELD.to_string(), serde_json::json!(overlapping));
+    }
+}
+
+/// Rename a segment's overlapping speakers after its speakers were remapped,
+/// dropping any that became the segment's own speaker
+pub fn remap_overlapping(segment: &mut serde_json::Value, mapping: &HashMap<String, String>) {
+    let Some(listed) = segment[OVERLAPPING_SPEAKERS_FIELD].as_array() else {
+        return;
+    };
+    let others: Vec<String> = listed.iter()
+        .filter_map(|speaker| speaker.as_str())
+        .map(|speaker| mapping.get(speaker).cloned().unwrap_or_else(|| speaker.to_string()))
+        .collect();
+    let dominant = segment["speaker"].as_str().unwrap_or_default().to_string();
+    if let Some(fields) = segment.as_object_mut() {
+        fields.remove(OVERLAPPING_SPEAKERS_FIELD);
+    }
+    mark_overlap(segment, &dominant, &others);
+}
+
+/// Whether other speakers were heard in a stored segment
+pub fn has_overlap(segment: &serde_json::Value) -> bool {
+    segment[OVERLAPPING_SPEAKERS_FIELD].as_array().is_some_and(|speakers| !speakers.is_empty())
+}
+
+/// Share of the segments' speech time (0-1) spent in segments with cross-talk
+pub fn overlap_ratio(segments: &[serde_json::Value]) -> f32 {
+    let mut total = 0.0;
+    let mut overlapped = 0.0;
+    for segment in segments {
+        let start = segment["startTime"].as_f64().unwrap_or(0.0);
+        let end = segment["endTime"].as_f64().unwrap_or(0.0);
+        let seconds = (end - start).max(0.0);
+        total += seconds;
+        if has_overlap(segment) {
+            overlapped += seconds;
+        }
+    }
+    if total > 0.0 { (overlapped / total) as f32 } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn window(vector: Vec<f32>, start: f32, end: f32) -> SpeakerEmbedding {
+        SpeakerEmbedding {
+            vector,
+            confidence: 0.9,
+            timestamp_start: start,
+            timestamp_end: end,
+            speaker_id: None,
+            quality: 0.9,
+            extracted_at: 0,
+            audio_duration_ms: ((end - start) * 1000.0) as u32,
+        }
+    }
+
+    #[test]
+    fn test_longest_voice_is_dominant() {
+        let embeddings = vec![
+            window(vec![0.0, 1.0], 0.0, 1.5),
+            window(vec![1.0, 0.0], 1.0, 2.5),
+            window(vec![0.95, 0.1], 2.0, 3.5),
+            window(vec![0.9, 0.05], 3.0, 4.5),
+        ];
+        let voices = group_voices(&embeddings, 0.7);
+        assert_eq!(voices.len(), 2);
+        assert_eq!(voices[0].embeddings.len(), 3);
+        // Overlapping windows are not counted twice
+        assert!((voices[0].seconds - 3.5).abs() < 1e-5);
+        assert_eq!(voices[1].embeddings[0].vector, vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_overlapping_speakers_are_listed_without_the_dominant_one() {
+        let mut segment = json!({ "speaker": "speaker_1", "startTime": 0.0, "endTime": 4.0 });
+        mark_overlap(&mut segment, "speaker_1", &["speaker_1".to_string()]);
+        assert!(!has_overlap(&segment));
+
+        mark_overlap(&mut segment, "speaker_1", &["speaker_2".to_string(), "speaker_1".to_string(), "speaker_2".to_string()]);
+        assert_eq!(segment[OVERLAPPING_SPEAKERS_FIELD], json!(["speaker_2"]));
+        assert!(has_overlap(&segment));
+
+        // A merged speaker is no longer overlapping itself
+        let mapping = HashMap::from([("speaker_2".to_string(), "speaker_1".to_string())]);
+        remap_overlapping(&mut segment, &mapping);
+        assert!(!has_overlap(&segment));
+    }
+
+    #[test]
+    fn test_overlap_ratio_is_by_speech_time() {
+        let segments = vec![
+            json!({ "speaker": "speaker_1", "startTime": 0.0, "endTime": 3.0, "overlappingSpeakers": ["speaker_2"] }),
+            json!({ "speaker": "speaker_2", "startTime": 3.0, "endTime": 12.0 }),
+        ];
+        assert!((overlap_ratio(&segments) - 0.25).abs() < 1e-6);
+        assert_eq!(overlap_ratio(&[]), 0.0);
+    }
+}
@@ -0,0 +1,196 @@
+//! Segment Re-attribution
+//!
+//! Re-attributes already transcribed segments to a new speaker timeline by
+//! time overlap, so speaker labels can be fixed without re-running ASR. Text
+//! and timings are never touched; previous speakers are kept as revisions.
+
+use super::speaker_stats;
+use super::types::{SpeakerSegment, SpeakerStats};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Outcome of re-attributing a session's segments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReattributionSummary {
+    #[serde(rename = "totalSegments")]
+    pub total_segments: usize,
+    #[serde(rename = "changedSegments")]
+    pub changed_segments: usize,
+    /// Segments with no overlapping speaker turn (attribution kept)
+    #[serde(rename = "unmatchedSegments")]
+    pub unmatched_segments: usize,
+    /// Stats include muted speakers' talk time; these are the muted ones
+    #[serde(rename = "speakerStats")]
+    pub speaker_stats: Vec<SpeakerStats>,
+    #[serde(rename = "mutedSpeakers", default)]
+    pub muted_speakers: Vec<String>,
+    /// Talk time per speaker after re-attribution, as the saved session reports it
+    #[serde(rename = "talkTime", default)]
+    pub talk_time: Option<speaker_stats::SpeakerStats>,
+    /// Speaker profiles linked to the session after re-attribution
+    #[serde(rename = "linkedProfiles", default)]
+    pub linked_profiles: usize,
+}
+
+/// Speaker turn that overlaps the span [start, end] the most
+pub fn dominant_speaker(timeline: &[SpeakerSegment], start: f32, end: f32) -> Option<&str> {
+    let mut overlaps: HashMap<&str, f32> = HashMap::new();
+    for turn in timeline {
+        let overlap = end.min(turn.end_time) - start.max(turn.start_time);
+        if overlap > 0.0 {
+            *overlaps.entry(turn.speaker_id.as_str()).or_insert(0.0) += overlap;
+        }
+    }
+
+    overlaps.into_iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal).then(b.0.cmp(a.0)))
+        .map(|(speaker, _)| speaker)
+}
+
+/// Re-attribute stored segment JSON to `timeline`, recording prior speakers as revisions
+pub fn reattribute_segments(
+    segments: &mut [serde_json::Value],
+    timeline: &[SpeakerSegment],
+    revised_at_ms: u64,
+) -> ReattributionSummary {
+    let mut changed_segments = 0;
+    let mut unmatched_segments = 0;
+
+    for segment in segments.iter_mut() {
+        let start = segment["startTime"].as_f64().unwrap_or(0.0) as f32;
+        let end = segment["endTime"].as_f64().unwrap_or(0.0) as f32;
+
+        let Some(speaker) = dominant_speaker(timeline, start, end) else {
+            unmatched_segments += 1;
+            continue;
+        };
+        let previous = segment["speaker"].as_str().unwrap_or_default().to_string();
+        if previous == speaker {
+            continue;
+        }
+
+        let Some(fields) = segment.as_object_mut() else {
+            continue;
+        };
+        let revisions = fields.entry("speakerRevisions").or_insert_with(|| serde_json::json!([]));
+        if let Some(revisions) = revisions.as_array_mut() {
+            revisions.push(serde_json::json!({
+                "speaker": previous,
+                "revisedAt": revised_at_ms,
+                "reason": "rediarization"
+            }));
+        }
+        fields.insert("speaker".to_string(), serde_json::Value::String(speaker.to_string()));
+        changed_segments += 1;
+    }
+
+    ReattributionSummary {
+        total_segments: segments.len(),
+        changed_segments,
+        unmatched_segments,
+        speaker_stats: segment_speaker_stats(segments),
+        muted_speakers: Vec::new(),
+        talk_time: None,
+        linked_profiles: 0,
+    }
+}
+
+/// Per-speaker statistics computed from stored segment JSON
+pub fn segment_speaker_stats(segments: &[serde_json::Value]) -> Vec<SpeakerStats> {
+    // speaker -> (speech time, segment count, confidence sum, word count)
+    let mut totals: HashMap<String, (f32, usize, f32, usize)> = HashMap::new();
+    for segment in segments {
+        let Some(speaker) = segment["speaker"].as_str() else {
+            continue;
+        };
+        let start = segment["startTime"].as_f64().unwrap_or(0.0) as f32;
+        let end = segment["endTime"].as_f64().unwrap_or(0.0) as f32;
+        let entry = totals.entry(speaker.to_string()).or_insert((0.0, 0, 0.0, 0));
+        entry.0 += (end - start).max(0.0);
+        entry.1 += 1;
+        entry.2 += segment["confidence"].as_f64().unwrap_or(0.0) as f32;
+        entry.3 += segment["text"].as_str().map(|t| t.split_whitespace().count()).unwrap_or(0);
+    }
+
+    let total_time: f32 = totals.values().map(|t| t.0).sum();
+    let mut stats: Vec<SpeakerStats> = totals.into_iter()
+        .map(|(speaker_id, (speech_time, count, confidence_sum, words))| SpeakerStats {
+            speaker_id,
+            total_speech_time: speech_time,
+            percentage_of_total: if total_time > 0.0 { speech_time / total_time * 100.0 } else { 0.0 },
+            segment_count: count,
+            average_segment_length: speech_time / count as f32,
+            average_confidence: confidence_sum / count as f32,
+            words_per_minute: (speech_time > 0.0).then(|| words as f32 / speech_time * 60.0),
+        })
+        .collect();
+    stats.sort_by(|a, b| a.speaker_id.cmp(&b.speaker_id));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(speaker: &str, start: f32, end: f32) -> SpeakerSegment {
+        SpeakerSegment {
+            speaker_id: speaker.to_string(),
+            start_time: start,
+            end_time: end,
+            confidence: 0.9,
+            text: None,
+            embedding: None,
+            has_overlap: false,
+            overlapping_speakers: Vec::new(),
+        }
+    }
+
+    /// Alternating two-speaker session: ground truth speaker per 5s segment
+    fn synthetic_session() -> (Vec<serde_json::Value>, Vec<&'static str>) {
+        let truth = vec!["alice", "bob", "alice", "bob", "alice", "bob"];
+        let segments = truth.iter().enumerate()
+            .map(|(i, _)| serde_json::json!({
+                "text": format!("sentence number {}", i),
+                "startTime": i as f32 * 5.0,
+                "endTime": i as f32 * 5.0 + 4.5,
+                "confidence": 0.9,
+                "speaker": "alice"
+            }))
+            .collect();
+        (segments, truth)
+    }
+
+    #[test]
+    fn test_revisions_record_prior_attribution() {
+        let (mut segments, _) = synthetic_session();
+        reattribute_segments(&mut segments, &[turn("bob", 5.0, 10.0)], 42);
+
+        let revisions = segments[1]["speakerRevisions"].as_array().unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0]["speaker"], "alice");
+        assert_eq!(revisions[0]["revisedAt"], 42);
+        // Unchanged segments carry no revision history
+        assert!(segments[0].get("speakerRevisions").is_none());
+    }
+
+    #[test]
+    fn test_dominant_speaker_uses_largest_overlap() {
+        let timeline = vec![turn("alice", 0.0, 2.0), turn("bob", 2.0, 10.0)];
+        assert_eq!(dominant_speaker(&timeline, 1.0, 5.0), Some("bob"));
+        assert_eq!(dominant_speaker(&timeline, 20.0, 25.0), None);
+    }
+
+    #[test]
+    fn test_speaker_stats_follow_attribution() {
+        let (mut segments, truth) = synthetic_session();
+        let timeline: Vec<SpeakerSegment> = truth.iter().enumerate()
+            .map(|(i, speaker)| turn(speaker, i as f32 * 5.0, i as f32 * 5.0 + 5.0))
+            .collect();
+        let summary = reattribute_segments(&mut segments, &timeline, 0);
+
+        assert_eq!(summary.speaker_stats.len(), 2);
+        assert_eq!(summary.speaker_stats[1].speaker_id, "bob");
+        assert_eq!(summary.speaker_stats[1].segment_count, 3);
+        assert!((summary.speaker_stats[1].percentage_of_total - 50.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,101 @@
+//! Diarization Initialization Recovery
+//!
+//! Loading the diarization models can fail transiently (a model file still
+//! being written, memory pressure at session start). Initialization is
+//! retried with exponential backoff; if every attempt fails the session runs
+//! degraded, in single-speaker mode, until a manual retry succeeds.
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+/// Initialization attempts at session start
+pub const INIT_ATTEMPTS: u32 = 3;
+/// Wait before the second attempt; doubled for each further attempt
+pub const INIT_BACKOFF_BASE: Duration = Duration::from_millis(500);
+
+/// Speaker diarization state of a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiarizationStatus {
+    /// Not enabled in the session config
+    Disabled,
+    /// Models are loading; segments are attributed to a single speaker meanwhile
+    Initializing,
+    Active,
+    /// Initialization failed; single-speaker mode until retry_diarization succeeds
+    Degraded,
+}
+
+impl DiarizationStatus {
+    /// Status of a new session
+    pub fn initial(diarization_enabled: bool) -> Self {
+        if diarization_enabled { Self::Initializing } else { Self::Disabled }
+    }
+}
+
+/// Wait after failed attempt `attempt` (1-based) before the next one
+pub fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1u32 << attempt.saturating_sub(1).min(16))
+}
+
+/// Initialization that failed every attempt
+#[derive(Debug)]
+pub struct InitFailure<E> {
+    pub error: E,
+    pub attempts: u32,
+}
+
+/// Run `init` up to `attempts` times, backing off between failures. Returns
+/// the value and the number of attempts it took, or the last error.
+pub async fn initialize_with_retry<T, E, F, Fut>(attempts: u32, base: Duration, mut init: F) -> Result<(T, u32), InitFailure<E>>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let attempts = attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match init(attempt).await {
+            Ok(value) => return Ok((value, attempt)),
+            Err(error) if attempt >= attempts => return Err(InitFailure { error, attempts: attempt }),
+            Err(_) => {
+                tokio::time::sleep(backoff_delay(base, attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles() {
+        let base = Duration::from_millis(500);
+        assert_eq!(backoff_delay(base, 1), Duration::from_millis(500));
+        assert_eq!(backoff_delay(base, 2), Duration::from_secs(1));
+        assert_eq!(backoff_delay(base, 3), Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_transient_failure_is_retried() {
+        let (value, attempts) = initialize_with_retry(3, Duration::ZERO, |attempt| async move {
+            if attempt < 3 { Err("model busy") } else { Ok(attempt * 10) }
+        }).await.unwrap();
+        assert_eq!(value, 30);
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_persistent_failure_reports_attempts() {
+        let failure = initialize_with_retry(3, Duration::ZERO, |attempt| async move {
+            Err::<(), _>(format!("attempt {} failed", attempt))
+        }).await.unwrap_err();
+        assert_eq!(failure.attempts, 3);
+        assert_eq!(failure.error, "attempt 3 failed");
+        assert_eq!(DiarizationStatus::initial(true), DiarizationStatus::Initializing);
+        assert_eq!(serde_json::to_value(DiarizationStatus::Degraded).unwrap(), "degraded");
+    }
+}
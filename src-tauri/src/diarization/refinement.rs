@@ -0,0 +1,292 @@
+//! Session-End Speaker Refinement
+//!
+//! Live diarization labels each chunk greedily from the evidence seen so far,
+//! so early in a meeting one voice often gets several labels. When the session
+//! ends, the embeddings gathered for its segments are clustered together and
+//! the stored segments are re-aligned to the clusters. Each cluster keeps the
+//! live label it was given most often, so names, mutes and known profiles
+//! stay attached to the speakers the user already saw.
+
+use super::clustering::SpeakerClusterer;
+use super::overlap;
+use super::segment_merger::{SegmentMerger, TranscriptionSegment};
+use super::types::{DiarizationConfig, SpeakerEmbedding, SpeakerSegment};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Embeddings kept per session for the refinement pass; clustering is quadratic
+pub const MAX_REFINEMENT_EMBEDDINGS: usize = 200;
+
+/// Embeddings of a session's segments, labelled with their live speaker and
+/// timed by the segment they were extracted for
+#[derive(Debug, Clone, Default)]
+pub struct SessionEmbeddings {
+    embeddings: Vec<SpeakerEmbedding>,
+}
+
+impl SessionEmbeddings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `embedding` for the segment [start, end] attributed to `speaker_id`.
+    /// When full, every other embedding is dropped so the whole session stays covered.
+    pub fn record(&mut self, speaker_id: &str, start: f32, end: f32, mut embedding: SpeakerEmbedding) {
+        if self.embeddings.len() >= MAX_REFINEMENT_EMBEDDINGS {
+            let mut index = 0;
+            self.embeddings.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+        }
+        embedding.speaker_id = Some(speaker_id.to_string());
+        embedding.timestamp_start = start;
+        embedding.timestamp_end = end;
+        self.embeddings.push(embedding);
+    }
+
+    pub fn embeddings(&self) -> &[SpeakerEmbedding] {
+        &self.embeddings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.embeddings.is_empty()
+    }
+}
+
+/// Outcome of refining a session's speakers
+#[derive(Debug, Clone, Default)]
+pub struct SpeakerRefinement {
+    /// Final speaker of each live speaker, by its most frequent cluster
+    pub speaker_mapping: HashMap<String, String>,
+    /// Speakers left after refinement
+    pub speaker_count: usize,
+    /// Speaker turns of the final speakers, one per embedding
+    pub timeline: Vec<SpeakerSegment>,
+    /// Embeddings of each final speaker, for estimating the session's speaker count
+    pub clusters: HashMap<String, Vec<SpeakerEmbedding>>,
+}
+
+/// Key of an embedding that survives clustering, which rewrites speaker ids
+fn span_key(embedding: &SpeakerEmbedding) -> (u32, u32) {
+    (embedding.timestamp_start.to_bits(), embedding.timestamp_end.to_bits())
+}
+
+/// Embeddings and first appearance behind each label
+type Votes = HashMap<String, (usize, f32)>;
+
+fn vote(votes: &mut Votes, label: &str, start: f32) {
+    let entry = votes.entry(label.to_string()).or_insert((0, f32::INFINITY));
+    entry.0 += 1;
+    entry.1 = entry.1.min(start);
+}
+
+/// The label voted for most, earliest first appearance winning ties
+fn most_voted(votes: &Votes) -> Option<String> {
+    votes.iter()
+        .max_by(|a, b| a.1.0.cmp(&b.1.0).then_with(|| b.1.1.total_cmp(&a.1.1)).then_with(|| b.0.cmp(a.0)))
+        .map(|(label, _)| label.clone())
+}
+
+/// Cluster a session's embeddings and map each live speaker to its final speaker
+pub async fn refine_speakers(embeddings: &[SpeakerEmbedding], config: &DiarizationConfig) -> Result<SpeakerRefinement> {
+    if embeddings.is_empty() {
+        return Ok(SpeakerRefinement::default());
+    }
+    let live_labels: HashMap<(u32, u32), &str> = embeddings.iter()
+        .filter_map(|e| e.speaker_id.as_deref().map(|label| (span_key(e), label)))
+        .collect();
+
+    let mut clusterer = SpeakerClusterer::new(config.clone()).await?;
+    let clusters = clusterer.cluster_embeddings(embeddings).await?;
+
+    let mut timeline = Vec::new();
+    let mut final_labels: Vec<String> = Vec::new();
+    let mut final_clusters: HashMap<String, Vec<SpeakerEmbedding>> = HashMap::new();
+    // Final speakers each live speaker's embeddings ended up with
+    let mut assignments: HashMap<String, Votes> = HashMap::new();
+    for members in clusters.values() {
+        let labelled: Vec<(&str, &SpeakerEmbedding)> = members.iter()
+            .filter_map(|e| live_labels.get(&span_key(e)).map(|label| (*label, e)))
+            .collect();
+        let mut votes = Votes::new();
+        for (label, embedding) in &labelled {
+            vote(&mut votes, label, embedding.timestamp_start);
+        }
+        // Each cluster is named after its most frequent live label
+        let Some(final_label) = most_voted(&votes) else {
+            continue;
+        };
+        for (label, embedding) in labelled {
+            vote(assignments.entry(label.to_string()).or_default(), &final_label, embedding.timestamp_start);
+            timeline.push(SpeakerSegment {
+                speaker_id: final_label.clone(),
+                start_time: embedding.timestamp_start,
+                end_time: embedding.timestamp_end,
+                confidence: embedding.confidence,
+                text: None,
+                embedding: None,
+                has_overlap: false,
+                overlapping_speakers: Vec::new(),
+            });
+        }
+        final_clusters.entry(final_label.clone()).or_default().extend(members.iter().cloned());
+        final_labels.push(final_label);
+    }
+    // Clusters sharing a most frequent label are one speaker; merging them is what refinement is for
+    final_labels.sort_unstable();
+    final_labels.dedup();
+    timeline.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    let speaker_mapping = assignments.into_iter()
+        .filter_map(|(live, votes)| most_voted(&votes).map(|final_label| (live, final_label)))
+        .collect();
+    Ok(SpeakerRefinement {
+        speaker_mapping,
+        speaker_count: final_labels.len(),
+        timeline,
+        clusters: final_clusters,
+    })
+}
+
+/// Rewrite the speaker of stored segments whose live speaker was refined. A
+/// segment takes the final speaker of the turn it aligns with, or its live
+/// speaker's mapping when no turn covers it. Segments of speakers that were
+/// not diarized (e.g. the local microphone) are left alone. Returns the
+/// indices of the segments that changed; prior speakers are kept as revisions.
+pub async fn apply_refinement(
+    segments: &mut [serde_json::Value],
+    refinement: &SpeakerRefinement,
+    config: &DiarizationConfig,
+    revised_at_ms: u64,
+) -> Result<Vec<usize>> {
+    let merger = SegmentMerger::new(config.clone());
+    let mut changed = Vec::new();
+
+    for (index, segment) in segments.iter_mut().enumerate() {
+        let Some(live) = segment["speaker"].as_str().map(str::to_string) else {
+            continue;
+        };
+        let Some(mapped) = refinement.speaker_mapping.get(&live) else {
+            continue;
+        };
+        let transcription = TranscriptionSegment {
+            start_time: segment["startTime"].as_f64().unwrap_or(0.0) as f32,
+            end_time: segment["endTime"].as_f64().unwrap_or(0.0) as f32,
+            text: segment["text"].as_str().unwrap_or_default().to_string(),
+            confidence: segment["confidence"].as_f64().unwrap_or(0.0) as f32,
+            speaker_id: mapped.clone(),
+        };
+        let aligned = merger.merge_segments(&refinement.timeline, &[transcription]).await?;
+        let refined = aligned.into_iter()
+            .find(|final_segment| final_segment.was_merged)
+            .map(|final_segment| final_segment.speaker_id)
+            .unwrap_or_else(|| mapped.clone());
+        if refined == live {
+            continue;
+        }
+
+        let Some(fields) = segment.as_object_mut() else {
+            continue;
+        };
+        let revisions = fields.entry("speakerRevisions").or_insert_with(|| serde_json::json!([]));
+        if let Some(revisions) = revisions.as_array_mut() {
+            revisions.push(serde_json::json!({
+                "speaker": live,
+                "revisedAt": revised_at_ms,
+                "reason": "session_refinement"
+            }));
+        }
+        fields.insert("speaker".to_string(), serde_json::Value::String(refined));
+        changed.push(index);
+    }
+    for segment in segments.iter_mut() {
+        overlap::remap_overlapping(segment, &refinement.speaker_mapping);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedding(vector: Vec<f32>) -> SpeakerEmbedding {
+        SpeakerEmbedding {
+            vector,
+            confidence: 0.9,
+            timestamp_start: 0.0,
+            timestamp_end: 0.0,
+            speaker_id: None,
+            quality: 0.9,
+            extracted_at: 0,
+            audio_duration_ms: 2000,
+        }
+    }
+
+    fn segment(speaker: &str, start: f32, end: f32) -> serde_json::Value {
+        serde_json::json!({ "text": "hello there", "speaker": speaker, "startTime": start, "endTime": end, "confidence": 0.9 })
+    }
+
+    fn config() -> DiarizationConfig {
+        DiarizationConfig {
+            similarity_threshold: 0.7,
+            min_speakers: 1,
+            max_speakers: 8,
+            deterministic_seed: Some(7),
+            ..DiarizationConfig::default()
+        }
+    }
+
+    /// One voice labelled speaker_1 then speaker_2 early on, and a second voice speaker_3
+    fn over_segmented_session() -> SessionEmbeddings {
+        let mut session = SessionEmbeddings::new();
+        session.record("speaker_1", 0.0, 2.0, embedding(vec![1.0, 0.0, 0.05]));
+        session.record("speaker_2", 2.0, 4.0, embedding(vec![0.97, 0.05, 0.0]));
+        session.record("speaker_3", 4.0, 6.0, embedding(vec![0.0, 1.0, 0.0]));
+        session.record("speaker_1", 6.0, 8.0, embedding(vec![0.99, 0.0, 0.02]));
+        session.record("speaker_3", 8.0, 10.0, embedding(vec![0.05, 0.98, 0.0]));
+        session
+    }
+
+    #[tokio::test]
+    async fn test_over_segmented_speaker_is_merged() {
+        let session = over_segmented_session();
+        let refinement = refine_speakers(session.embeddings(), &config()).await.unwrap();
+        assert_eq!(refinement.speaker_count, 2);
+        assert_eq!(refinement.speaker_mapping["speaker_1"], "speaker_1");
+        assert_eq!(refinement.speaker_mapping["speaker_2"], "speaker_1");
+        assert_eq!(refinement.speaker_mapping["speaker_3"], "speaker_3");
+        assert_eq!(refinement.timeline.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_segments_are_relabelled_with_revisions() {
+        let session = over_segmented_session();
+        let refinement = refine_speakers(session.embeddings(), &config()).await.unwrap();
+        let mut segments = vec![
+            segment("speaker_1", 0.0, 2.0),
+            segment("speaker_2", 2.0, 4.0),
+            segment("speaker_3", 4.0, 6.0),
+            segment("You", 10.0, 12.0),
+        ];
+        let changed = apply_refinement(&mut segments, &refinement, &config(), 42).await.unwrap();
+        assert_eq!(changed, vec![1]);
+        assert_eq!(segments[1]["speaker"].as_str(), Some("speaker_1"));
+        assert_eq!(segments[1]["speakerRevisions"][0]["speaker"].as_str(), Some("speaker_2"));
+        assert_eq!(segments[3]["speaker"].as_str(), Some("You"));
+    }
+
+    #[test]
+    fn test_full_session_is_thinned_evenly() {
+        let mut session = SessionEmbeddings::new();
+        for i in 0..=MAX_REFINEMENT_EMBEDDINGS {
+            session.record("speaker_1", i as f32, i as f32 + 1.0, embedding(vec![1.0, 0.0]));
+        }
+        let kept = session.embeddings();
+        assert_eq!(kept.len(), MAX_REFINEMENT_EMBEDDINGS / 2 + 1);
+        assert_eq!(kept[0].timestamp_start, 0.0);
+        assert_eq!(kept[1].timestamp_start, 2.0);
+        assert_eq!(kept.last().unwrap().timestamp_start, MAX_REFINEMENT_EMBEDDINGS as f32);
+        assert_eq!(kept[0].speaker_id.as_deref(), Some("speaker_1"));
+    }
+}
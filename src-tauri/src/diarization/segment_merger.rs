@@ -312,6 +312,22 @@ impl SegmentMerger {
         Ok(processed)
     }
     
+    /// Merge consecutive segments of one speaker into speaker turns. Segments
+    /// must be sorted by start time; a pause longer than `max_gap` seconds
+    /// starts a new turn even when the speaker stays the same.
+    pub fn merge_speaker_turns(&self, segments: &[FinalSegment], max_gap: f32) -> Vec<FinalSegment> {
+        let mut turns: Vec<FinalSegment> = Vec::new();
+        for segment in segments {
+            match turns.last_mut() {
+                Some(turn) if turn.speaker_id == segment.speaker_id && segment.start_time - turn.end_time <= max_gap => {
+                    *turn = self.merge_same_speaker_segments(turn, segment);
+                }
+                _ => turns.push(segment.clone()),
+            }
+        }
+        turns
+    }
+    
     /// Merge two segments from the same speaker
     fn merge_same_speaker_segments(&self, seg1: &FinalSegment, seg2: &FinalSegment) -> FinalSegment {
         FinalSegment {
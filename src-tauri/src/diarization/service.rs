@@ -325,8 +325,26 @@ impl DiarizationService {
         let embeddings = self.extract_speaker_embeddings(audio_samples, sample_rate).await?;
         tracing::debug!("Extracted {} embeddings", embeddings.len());
         
+        let mut result = self.diarize_embeddings(&embeddings).await?;
+        result.processing_time = start_time.elapsed();
+        
+        tracing::info!("Diarization completed in {:?}: {} speakers, {:.2} confidence", 
+                      result.processing_time, result.total_speakers, result.overall_confidence);
+        
+        Ok(result)
+    }
+    
+    /// Cluster already extracted embeddings into a speaker timeline, the second
+    /// half of `diarize`. Used to re-run clustering with other settings without
+    /// extracting the embeddings again.
+    pub async fn diarize_embeddings(
+        &self,
+        embeddings: &[SpeakerEmbedding],
+    ) -> Result<DiarizationResult, DiarizationError> {
+        let start_time = Instant::now();
+        
         // Cluster speakers
-        let clusters = self.cluster_speakers(&embeddings).await?;
+        let clusters = self.cluster_speakers(embeddings).await?;
         let total_speakers = clusters.len();
         tracing::debug!("Found {} distinct speakers", total_speakers);
         
@@ -335,6 +353,10 @@ impl DiarizationService {
         let mut segments = Vec::new();
         let mut overall_confidence = 0.0;
         
+        // Fixed speaker order keeps results (including float sums) reproducible
+        let mut clusters: Vec<(String, Vec<SpeakerEmbedding>)> = clusters.into_iter().collect();
+        clusters.sort_by(|a, b| a.0.cmp(&b.0));
+        
         for (speaker_id, speaker_embeddings) in clusters {
             // Calculate speaker statistics
             let total_speech_time: f32 = speaker_embeddings.iter()
@@ -382,7 +404,9 @@ impl DiarizationService {
         }
         
         // Sort segments by time
-        segments.sort_by(|a, b| a.start_time.partial_cmp(&b.start_time).unwrap());
+        segments.sort_by(|a, b| {
+            a.start_time.total_cmp(&b.start_time).then_with(|| a.speaker_id.cmp(&b.speaker_id))
+        });
         
         // Calculate overall confidence
         overall_confidence = if total_speakers > 0 { 
@@ -391,17 +415,12 @@ impl DiarizationService {
             0.0 
         };
         
-        let processing_time = start_time.elapsed();
-        
-        tracing::info!("Diarization completed in {:?}: {} speakers, {:.2} confidence", 
-                      processing_time, total_speakers, overall_confidence);
-        
         Ok(DiarizationResult {
             segments,
             speakers: speaker_profiles,
             total_speakers,
             overall_confidence,
-            processing_time,
+            processing_time: start_time.elapsed(),
             session_id: None,
             metrics: None,
             warnings: vec![],
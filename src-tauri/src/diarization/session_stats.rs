@@ -0,0 +1,277 @@
+//! Diarization Statistics of a Session
+//!
+//! What get_diarization_stats reports: who was detected and for how long,
+//! how confident the speaker attribution was, how often the speaker changed,
+//! and how much speech went without diarization. Segment-level figures come
+//! from the stored segments; extraction failures, re-identification hits and
+//! near misses of stored profiles are counted by the live loop as chunks are
+//! attributed.
+
+use serde::{Deserialize, Serialize};
+
+use super::known_speakers::NearMiss;
+use super::overlap;
+use super::speaker_count::SpeakerCountEstimate;
+use super::speaker_stats::{self, SpeakerTalkTime};
+
+/// Segment field holding the confidence of the segment's speaker attribution
+pub const SPEAKER_CONFIDENCE_FIELD: &str = "speakerConfidence";
+
+/// How the live loop attributed one chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributionOutcome {
+    /// Matched a speaker already heard, in this session or a stored profile
+    Reidentified,
+    /// Became a new speaker of the session
+    NewSpeaker,
+    /// No embedding could be extracted
+    ExtractionFailed,
+}
+
+/// Chunks that came close to a stored profile without reaching its
+/// confidence threshold, so the threshold can be tuned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileNearMisses {
+    #[serde(rename = "profileId")]
+    pub profile_id: String,
+    pub name: String,
+    /// The profile's confidence threshold
+    pub threshold: f32,
+    pub count: u32,
+    #[serde(rename = "bestSimilarity")]
+    pub best_similarity: f32,
+    #[serde(rename = "meanSimilarity")]
+    pub mean_similarity: f32,
+}
+
+/// Live attribution counters of a session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AttributionCounters {
+    pub reidentified: u32,
+    pub new_speakers: u32,
+    pub extraction_failures: u32,
+    /// Near misses per stored profile, in order of the first miss
+    pub near_misses: Vec<ProfileNearMisses>,
+}
+
+impl AttributionCounters {
+    pub fn record(&mut self, outcome: AttributionOutcome) {
+        match outcome {
+            AttributionOutcome::Reidentified => self.reidentified += 1,
+            AttributionOutcome::NewSpeaker => self.new_speakers += 1,
+            AttributionOutcome::ExtractionFailed => self.extraction_failures += 1,
+        }
+    }
+
+    /// Count a chunk that was given a provisional speaker because it fell
+    /// short of a stored profile's threshold
+    pub fn record_near_miss(&mut self, near_miss: &NearMiss) {
+        let profile_id = near_miss.profile_id.to_string();
+        match self.near_misses.iter_mut().find(|misses| misses.profile_id == profile_id) {
+            Some(misses) => {
+                misses.mean_similarity = (misses.mean_similarity * misses.count as f32 + near_miss.similarity) / (misses.count + 1) as f32;
+                misses.count += 1;
+                misses.best_similarity = misses.best_similarity.max(near_miss.similarity);
+                // The threshold may have been edited during the session
+                misses.threshold = near_miss.threshold;
+            }
+            None => self.near_misses.push(ProfileNearMisses {
+                profile_id,
+                name: near_miss.name.clone(),
+                threshold: near_miss.threshold,
+                count: 1,
+                best_similarity: near_miss.similarity,
+                mean_similarity: near_miss.similarity,
+            }),
+        }
+    }
+
+    /// Share of attributed chunks that matched a speaker already heard (0-1)
+    pub fn reidentification_hit_rate(&self) -> Option<f32> {
+        let attempts = self.reidentified + self.new_speakers;
+        (attempts > 0).then(|| self.reidentified as f32 / attempts as f32)
+    }
+}
+
+/// Diarization statistics of a session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiarizationStats {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    /// Distinct speakers of the stored segments
+    #[serde(rename = "speakersDetected")]
+    pub speakers_detected: usize,
+    /// Segment count and talk time per speaker, most talkative first
+    pub speakers: Vec<SpeakerTalkTime>,
+    #[serde(rename = "totalSegments")]
+    pub total_segments: usize,
+    /// Mean speaker attribution confidence of the diarized segments
+    #[serde(rename = "averageConfidence")]
+    pub average_confidence: Option<f32>,
+    /// Consecutive segments with different speakers
+    #[serde(rename = "speakerSwitches")]
+    pub speaker_switches: usize,
+    /// Speech attributed from a voice embedding
+    #[serde(rename = "diarizedSeconds")]
+    pub diarized_seconds: f64,
+    /// Speech attributed without one (fallback speaker or local microphone)
+    #[serde(rename = "undiarizedSeconds")]
+    pub undiarized_seconds: f64,
+    #[serde(rename = "overlappingSegments")]
+    pub overlapping_segments: usize,
+    /// Share of speech time with cross-talk (0-1)
+    #[serde(rename = "overlapRatio")]
+    pub overlap_ratio: f32,
+    #[serde(rename = "embeddingFailures")]
+    pub embedding_failures: u32,
+    #[serde(rename = "reidentificationHitRate")]
+    pub reidentification_hit_rate: Option<f32>,
+    /// Stored profiles live chunks nearly matched
+    #[serde(rename = "nearMisses", default)]
+    pub near_misses: Vec<ProfileNearMisses>,
+    /// Speaker count estimated from the cluster structure, once re-clustered
+    #[serde(rename = "estimatedSpeakerCount")]
+    pub estimated_speaker_count: Option<usize>,
+    /// Confidence of the estimated speaker count (0.0-1.0)
+    #[serde(rename = "estimatedSpeakerCountConfidence")]
+    pub estimated_speaker_count_confidence: Option<f32>,
+    /// Time spent transcribing during the session
+    #[serde(rename = "processingTimeMs")]
+    pub processing_time_ms: u64,
+}
+
+fn segment_seconds(segment: &serde_json::Value) -> f64 {
+    let start = segment["startTime"].as_f64().unwrap_or(0.0);
+    let end = segment["endTime"].as_f64().unwrap_or(0.0);
+    (end - start).max(0.0)
+}
+
+/// Statistics of a session's stored segments and live counters
+pub fn compute_diarization_stats(
+    session_id: &str,
+    segments: &[serde_json::Value],
+    counters: &AttributionCounters,
+    speaker_estimate: Option<SpeakerCountEstimate>,
+    processing_time_ms: u64,
+) -> DiarizationStats {
+    let speakers = speaker_stats::compute_speaker_stats(segments).speakers;
+
+    let mut ordered: Vec<&serde_json::Value> = segments.iter().collect();
+    ordered.sort_by(|a, b| {
+        let start = |s: &serde_json::Value| s["startTime"].as_f64().unwrap_or(0.0);
+        start(a).total_cmp(&start(b))
+    });
+    let speaker_switches = ordered.windows(2)
+        .filter(|pair| pair[0]["speaker"].as_str() != pair[1]["speaker"].as_str())
+        .count();
+
+    let mut diarized_seconds = 0.0;
+    let mut undiarized_seconds = 0.0;
+    let mut confidences = Vec::new();
+    for segment in segments {
+        match segment[SPEAKER_CONFIDENCE_FIELD].as_f64() {
+            Some(confidence) => {
+                diarized_seconds += segment_seconds(segment);
+                confidences.push(confidence as f32);
+            }
+            None => undiarized_seconds += segment_seconds(segment),
+        }
+    }
+
+    DiarizationStats {
+        session_id: session_id.to_string(),
+        speakers_detected: speakers.len(),
+        speakers,
+        total_segments: segments.len(),
+        average_confidence: (!confidences.is_empty()).then(|| confidences.iter().sum::<f32>() / confidences.len() as f32),
+        speaker_switches,
+        diarized_seconds,
+        undiarized_seconds,
+        overlapping_segments: segments.iter().filter(|segment| overlap::has_overlap(segment)).count(),
+        overlap_ratio: overlap::overlap_ratio(segments),
+        embedding_failures: counters.extraction_failures,
+        reidentification_hit_rate: counters.reidentification_hit_rate(),
+        near_misses: counters.near_misses.clone(),
+        estimated_speaker_count: speaker_estimate.map(|estimate| estimate.count),
+        estimated_speaker_count_confidence: speaker_estimate.map(|estimate| estimate.confidence),
+        processing_time_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn segments() -> Vec<serde_json::Value> {
+        vec![
+            json!({ "speaker": "speaker_1", "startTime": 0.0, "endTime": 4.0, "speakerConfidence": 0.9 }),
+            json!({ "speaker": "speaker_2", "startTime": 4.0, "endTime": 6.0, "speakerConfidence": 0.7 }),
+            json!({ "speaker": "speaker_1", "startTime": 6.0, "endTime": 9.0 }),
+            json!({ "speaker": "speaker_1", "startTime": 9.0, "endTime": 10.0, "speakerConfidence": 0.8 }),
+        ]
+    }
+
+    #[test]
+    fn test_stats_come_from_the_stored_segments() {
+        let stats = compute_diarization_stats("s1", &segments(), &AttributionCounters::default(), None, 1200);
+        assert_eq!(stats.speakers_detected, 2);
+        assert_eq!(stats.speakers[0].speaker_id, "speaker_1");
+        assert_eq!(stats.speakers[0].segment_count, 3);
+        assert_eq!(stats.total_segments, 4);
+        assert_eq!(stats.speaker_switches, 2);
+        assert_eq!(stats.diarized_seconds, 7.0);
+        assert_eq!(stats.undiarized_seconds, 3.0);
+        assert!((stats.average_confidence.unwrap() - 0.8).abs() < 1e-6);
+        assert_eq!(stats.processing_time_ms, 1200);
+    }
+
+    #[test]
+    fn test_counters_give_the_hit_rate() {
+        let mut counters = AttributionCounters::default();
+        assert_eq!(counters.reidentification_hit_rate(), None);
+        for outcome in [AttributionOutcome::NewSpeaker, AttributionOutcome::Reidentified, AttributionOutcome::Reidentified,
+                        AttributionOutcome::Reidentified, AttributionOutcome::ExtractionFailed] {
+            counters.record(outcome);
+        }
+        let stats = compute_diarization_stats("s1", &[], &counters, None, 0);
+        assert_eq!(stats.reidentification_hit_rate, Some(0.75));
+        assert_eq!(stats.embedding_failures, 1);
+        assert_eq!(stats.average_confidence, None);
+        assert_eq!(stats.speakers_detected, 0);
+        assert_eq!(stats.estimated_speaker_count, None);
+    }
+
+    #[test]
+    fn test_stats_carry_the_speaker_estimate() {
+        let estimate = SpeakerCountEstimate { count: 3, confidence: 0.7 };
+        let stats = compute_diarization_stats("s1", &segments(), &AttributionCounters::default(), Some(estimate), 0);
+        assert_eq!(stats.estimated_speaker_count, Some(3));
+        assert_eq!(stats.estimated_speaker_count_confidence, Some(0.7));
+        assert_eq!(stats.speakers_detected, 2);
+    }
+
+    #[test]
+    fn test_near_misses_are_tallied_per_profile() {
+        let near_miss = |profile_id, similarity| NearMiss {
+            profile_id,
+            name: "Me".to_string(),
+            similarity,
+            threshold: 0.9,
+        };
+        let me = uuid::Uuid::new_v4();
+        let other = uuid::Uuid::new_v4();
+        let mut counters = AttributionCounters::default();
+        counters.record_near_miss(&near_miss(me, 0.8));
+        counters.record_near_miss(&near_miss(other, 0.75));
+        counters.record_near_miss(&near_miss(me, 0.86));
+
+        let stats = compute_diarization_stats("s1", &[], &counters, None, 0);
+        assert_eq!(stats.near_misses.len(), 2);
+        let misses = &stats.near_misses[0];
+        assert_eq!((misses.profile_id.as_str(), misses.count), (me.to_string().as_str(), 2));
+        assert_eq!(misses.best_similarity, 0.86);
+        assert!((misses.mean_similarity - 0.83).abs() < 1e-6);
+        assert_eq!(stats.near_misses[1].count, 1);
+    }
+}
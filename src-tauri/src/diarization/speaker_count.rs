@@ -0,0 +1,221 @@
+//! Speaker Count Estimation
+//!
+//! Estimates how many speakers are present from the structure of the current
+//! clusters instead of counting every speaker ID handed out so far. Early in a
+//! session the clusterer produces small or loose clusters that later merge, so
+//! only clusters that are both large enough and internally cohesive count.
+
+use super::types::SpeakerEmbedding;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// New embeddings between re-clusterings of a live session
+pub const DEFAULT_RECLUSTER_INTERVAL: usize = 5;
+
+/// Estimated number of speakers with the confidence of the estimate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerCountEstimate {
+    pub count: usize,
+    /// Confidence of the estimate (0.0-1.0)
+    pub confidence: f32,
+}
+
+/// Stateful estimator that reports when the estimated speaker count changes
+#[derive(Debug, Clone)]
+pub struct SpeakerCountEstimator {
+    /// Minimum embeddings for a cluster to count as a speaker
+    min_cluster_size: usize,
+    /// Minimum average similarity of members to the cluster centroid
+    min_cohesion: f32,
+    max_speakers: usize,
+    /// New embeddings between re-clusterings
+    recluster_interval: usize,
+    /// Embeddings observed since the last re-clustering
+    pending: usize,
+    last: Option<SpeakerCountEstimate>,
+}
+
+impl SpeakerCountEstimator {
+    pub fn new(min_cluster_size: usize, min_cohesion: f32, max_speakers: u8) -> Self {
+        Self {
+            min_cluster_size: min_cluster_size.max(1),
+            min_cohesion,
+            max_speakers: max_speakers.max(1) as usize,
+            recluster_interval: DEFAULT_RECLUSTER_INTERVAL,
+            pending: 0,
+            last: None,
+        }
+    }
+
+    pub fn with_recluster_interval(mut self, interval: usize) -> Self {
+        self.recluster_interval = interval.max(1);
+        self
+    }
+
+    /// Count a new embedding; true once enough arrived since the last re-clustering
+    pub fn observe(&mut self) -> bool {
+        self.pending += 1;
+        if self.pending < self.recluster_interval {
+            return false;
+        }
+        self.pending = 0;
+        true
+    }
+
+    /// Estimate the speaker count from the current clustering
+    pub fn estimate(&self, clusters: &HashMap<String, Vec<SpeakerEmbedding>>) -> SpeakerCountEstimate {
+        let total: usize = clusters.values().map(|c| c.len()).sum();
+        if total == 0 {
+            return SpeakerCountEstimate { count: 0, confidence: 0.0 };
+        }
+
+        let mut qualifying: Vec<(usize, f32)> = clusters.values()
+            .filter(|members| members.len() >= self.min_cluster_size)
+            .map(|members| (members.len(), cluster_cohesion(members)))
+            .filter(|(_, cohesion)| *cohesion >= self.min_cohesion)
+            .collect();
+
+        // Keep the best supported clusters if there are more than allowed
+        qualifying.sort_by(|a, b| b.0.cmp(&a.0));
+        qualifying.truncate(self.max_speakers);
+
+        if qualifying.is_empty() {
+            // Speech without any well formed cluster yet is at least one speaker
+            return SpeakerCountEstimate { count: 1, confidence: 0.0 };
+        }
+
+        let covered: usize = qualifying.iter().map(|(size, _)| size).sum();
+        let coverage = covered as f32 / total as f32;
+        let mean_cohesion = qualifying.iter().map(|(_, c)| c).sum::<f32>() / qualifying.len() as f32;
+        // Confidence grows with the evidence behind each counted cluster
+        let evidence = (covered as f32 / (qualifying.len() * self.min_cluster_size * 3) as f32).min(1.0);
+
+        SpeakerCountEstimate {
+            count: qualifying.len(),
+            confidence: (coverage * mean_cohesion * evidence).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Re-estimate and return the new estimate only if the count changed
+    pub fn update(&mut self, clusters: &HashMap<String, Vec<SpeakerEmbedding>>) -> Option<SpeakerCountEstimate> {
+        let estimate = self.estimate(clusters);
+        let changed = self.last.map(|last| last.count != estimate.count).unwrap_or(estimate.count > 0);
+        self.last = Some(estimate);
+        changed.then_some(estimate)
+    }
+
+    pub fn current(&self) -> Option<SpeakerCountEstimate> {
+        self.last
+    }
+}
+
+/// Average cosine similarity of the members to the cluster centroid
+fn cluster_cohesion(members: &[SpeakerEmbedding]) -> f32 {
+    let Some(first) = members.first() else {
+        return 0.0;
+    };
+    let mut centroid = vec![0.0f32; first.vector.len()];
+    for member in members {
+        for (c, v) in centroid.iter_mut().zip(&member.vector) {
+            *c += v;
+        }
+    }
+    let centroid = SpeakerEmbedding {
+        vector: centroid,
+        ..first.clone()
+    };
+
+    members.iter().map(|m| m.similarity(&centroid)).sum::<f32>() / members.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic noise in [-0.5, 0.5)
+    fn noise(seed: &mut u64) -> f32 {
+        *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((*seed >> 33) as f32 / (1u64 << 31) as f32) - 0.5
+    }
+
+    fn embedding(speaker: usize, seed: &mut u64, spread: f32) -> SpeakerEmbedding {
+        let mut vector = vec![0.0f32; 16];
+        vector[speaker] = 1.0;
+        for v in vector.iter_mut() {
+            *v += spread * noise(seed);
+        }
+        SpeakerEmbedding {
+            vector,
+            confidence: 0.9,
+            timestamp_start: 0.0,
+            timestamp_end: 1.0,
+            speaker_id: None,
+            quality: 0.9,
+            extracted_at: 0,
+            audio_duration_ms: 1000,
+        }
+    }
+
+    /// Clusters as an over-eager clusterer produces them for a simulated meeting:
+    /// true speakers plus spurious singleton clusters from short turns
+    fn meeting_clusters(speakers: usize, turns_per_speaker: usize, seed: &mut u64) -> HashMap<String, Vec<SpeakerEmbedding>> {
+        let mut clusters = HashMap::new();
+        for speaker in 0..speakers {
+            let members = (0..turns_per_speaker).map(|_| embedding(speaker, seed, 0.3)).collect();
+            clusters.insert(format!("speaker_{}", speaker), members);
+            clusters.insert(format!("spurious_{}", speaker), vec![embedding(speaker, seed, 0.3)]);
+        }
+        clusters
+    }
+
+    #[test]
+    fn test_estimate_ignores_small_clusters() {
+        let mut seed = 7;
+        let clusters = meeting_clusters(3, 6, &mut seed);
+        assert_eq!(clusters.len(), 6);
+
+        let estimate = SpeakerCountEstimator::new(3, 0.6, 8).estimate(&clusters);
+        assert_eq!(estimate.count, 3);
+        assert!(estimate.confidence > 0.4);
+    }
+
+    #[test]
+    fn test_estimate_rejects_incoherent_clusters() {
+        let mut seed = 11;
+        let mut clusters = meeting_clusters(2, 6, &mut seed);
+        // A large cluster of unrelated embeddings is not a speaker
+        let mixed = (0..8).map(|i| embedding(4 + i, &mut seed, 0.3)).collect();
+        clusters.insert("mixed".to_string(), mixed);
+
+        assert_eq!(SpeakerCountEstimator::new(3, 0.6, 8).estimate(&clusters).count, 2);
+    }
+
+    #[test]
+    fn test_estimate_is_capped_at_max_speakers() {
+        let mut seed = 42;
+        // More true speakers than allowed keeps the best supported ones
+        let crowded = meeting_clusters(6, 6, &mut seed);
+        assert_eq!(SpeakerCountEstimator::new(3, 0.6, 4).estimate(&crowded).count, 4);
+    }
+
+    #[test]
+    fn test_update_reports_only_changes() {
+        let mut seed = 3;
+        let mut estimator = SpeakerCountEstimator::new(3, 0.6, 8);
+
+        assert_eq!(estimator.update(&meeting_clusters(2, 4, &mut seed)).map(|e| e.count), Some(2));
+        assert!(estimator.update(&meeting_clusters(2, 5, &mut seed)).is_none());
+        assert_eq!(estimator.update(&meeting_clusters(3, 5, &mut seed)).map(|e| e.count), Some(3));
+    }
+
+    #[test]
+    fn test_reclustering_is_throttled() {
+        let mut estimator = SpeakerCountEstimator::new(3, 0.6, 8).with_recluster_interval(4);
+        let due: Vec<bool> = (0..9).map(|_| estimator.observe()).collect();
+        assert_eq!(due, [false, false, false, true, false, false, false, true, false]);
+
+        // An interval of zero re-clusters on every embedding
+        let mut every = SpeakerCountEstimator::new(3, 0.6, 8).with_recluster_interval(0);
+        assert!(every.observe() && every.observe());
+    }
+}
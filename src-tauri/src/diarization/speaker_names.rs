@@ -0,0 +1,175 @@
+//! Speaker Display Names
+//!
+//! Names given to a session's speakers while it runs. Segments keep the
+//! diarization speaker id in `speaker` and carry the display name alongside,
+//! so two speakers given the same name stay distinct for stats, mutes and
+//! exports. Renaming rewrites the name on segments already stored; segments
+//! stored later are labelled from the same map.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Segment field holding the speaker's display name
+pub const SPEAKER_NAME_FIELD: &str = "speakerName";
+
+/// Display names by diarization speaker id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerNames {
+    names: HashMap<String, String>,
+}
+
+impl SpeakerNames {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names recovered from the labels of saved segments, for a session whose
+    /// name map did not survive a restart
+    pub fn from_segments(segments: &[serde_json::Value]) -> Self {
+        let names = segments.iter()
+            .filter_map(|segment| Some((segment["speaker"].as_str()?, segment[SPEAKER_NAME_FIELD].as_str()?)))
+            .map(|(speaker, name)| (speaker.to_string(), name.to_string()))
+            .collect();
+        Self { names }
+    }
+
+    /// Names moved to renamed speaker ids (old id -> new id) so they follow the
+    /// person. When several named ids merge into one, the first old id wins.
+    pub fn remapped(&self, remap: &HashMap<String, String>) -> Self {
+        let mut names: HashMap<String, String> = self.names.iter()
+            .filter(|(speaker, _)| !remap.contains_key(*speaker))
+            .map(|(speaker, name)| (speaker.clone(), name.clone()))
+            .collect();
+        let mut entries: Vec<(&String, &String)> = remap.iter().collect();
+        entries.sort();
+        for (old, new) in entries {
+            if let Some(name) = self.names.get(old) {
+                names.entry(new.clone()).or_insert_with(|| name.clone());
+            }
+        }
+        Self { names }
+    }
+
+    /// Name `speaker_id`; returns false if it already had this name
+    pub fn rename(&mut self, speaker_id: &str, display_name: &str) -> bool {
+        self.names.insert(speaker_id.to_string(), display_name.to_string()).as_deref() != Some(display_name)
+    }
+
+    pub fn name_of(&self, speaker_id: &str) -> Option<&str> {
+        self.names.get(speaker_id).map(String::as_str)
+    }
+
+    /// Speaker ids sharing `display_name`
+    pub fn speakers_named(&self, display_name: &str) -> Vec<&str> {
+        let mut speakers: Vec<&str> = self.names.iter()
+            .filter(|(_, name)| name.as_str() == display_name)
+            .map(|(speaker, _)| speaker.as_str())
+            .collect();
+        speakers.sort_unstable();
+        speakers
+    }
+
+    /// Set the display name on a new segment, if its speaker was named
+    pub fn label_segment(&self, segment: &mut serde_json::Value) {
+        let Some(name) = segment["speaker"].as_str().and_then(|speaker| self.name_of(speaker)) else {
+            return;
+        };
+        let name = serde_json::Value::String(name.to_string());
+        if let Some(fields) = segment.as_object_mut() {
+            fields.insert(SPEAKER_NAME_FIELD.to_string(), name);
+        }
+    }
+}
+
+/// Write `display_name` on the stored segments of `speaker_id`. Returns the
+/// ids of the segments that changed; segments without an id are still renamed.
+pub fn rename_segments(segments: &mut [serde_json::Value], speaker_id: &str, display_name: &str) -> Vec<String> {
+    let mut changed = Vec::new();
+    for segment in segments.iter_mut() {
+        if segment["speaker"].as_str() != Some(speaker_id) || segment[SPEAKER_NAME_FIELD].as_str() == Some(display_name) {
+            continue;
+        }
+        let Some(fields) = segment.as_object_mut() else {
+            continue;
+        };
+        fields.insert(SPEAKER_NAME_FIELD.to_string(), serde_json::Value::String(display_name.to_string()));
+        if let Some(id) = fields.get("id").and_then(|id| id.as_str()) {
+            changed.push(id.to_string());
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn segment(id: &str, speaker: &str) -> serde_json::Value {
+        json!({ "id": id, "text": "hello", "speaker": speaker, "startTime": 0.0, "endTime": 1.0 })
+    }
+
+    #[test]
+    fn test_rename_rewrites_stored_segments_of_that_speaker() {
+        let mut segments = vec![segment("a", "speaker_1"), segment("b", "speaker_2"), segment("c", "speaker_1")];
+        let changed = rename_segments(&mut segments, "speaker_1", "Alice");
+        assert_eq!(changed, vec!["a", "c"]);
+        assert_eq!(segments[0][SPEAKER_NAME_FIELD].as_str(), Some("Alice"));
+        assert_eq!(segments[0]["speaker"].as_str(), Some("speaker_1"));
+        assert!(segments[1].get(SPEAKER_NAME_FIELD).is_none());
+
+        // Renaming to the same name again changes nothing
+        assert!(rename_segments(&mut segments, "speaker_1", "Alice").is_empty());
+    }
+
+    #[test]
+    fn test_future_segments_get_the_new_name() {
+        let mut names = SpeakerNames::new();
+        assert!(names.rename("speaker_2", "Bob"));
+        assert!(!names.rename("speaker_2", "Bob"));
+
+        let mut later = segment("d", "speaker_2");
+        names.label_segment(&mut later);
+        assert_eq!(later[SPEAKER_NAME_FIELD].as_str(), Some("Bob"));
+
+        let mut unnamed = segment("e", "speaker_3");
+        names.label_segment(&mut unnamed);
+        assert!(unnamed.get(SPEAKER_NAME_FIELD).is_none());
+    }
+
+    #[test]
+    fn test_speakers_with_the_same_name_stay_distinct() {
+        let mut names = SpeakerNames::new();
+        let mut segments = vec![segment("a", "speaker_1"), segment("b", "speaker_2")];
+        names.rename("speaker_1", "Sam");
+        names.rename("speaker_2", "Sam");
+        rename_segments(&mut segments, "speaker_1", "Sam");
+        rename_segments(&mut segments, "speaker_2", "Sam");
+
+        assert_eq!(names.speakers_named("Sam"), vec!["speaker_1", "speaker_2"]);
+        assert_eq!(segments[0]["speaker"].as_str(), Some("speaker_1"));
+        assert_eq!(segments[1]["speaker"].as_str(), Some("speaker_2"));
+
+        // Renaming one of them back leaves the other alone
+        assert_eq!(rename_segments(&mut segments, "speaker_2", "Samantha"), vec!["b"]);
+        assert_eq!(segments[0][SPEAKER_NAME_FIELD].as_str(), Some("Sam"));
+    }
+
+    #[test]
+    fn test_names_follow_remapped_speakers() {
+        let mut segments = vec![segment("a", "speaker_1"), segment("b", "speaker_2")];
+        rename_segments(&mut segments, "speaker_1", "Alice");
+        let names = SpeakerNames::from_segments(&segments);
+        assert_eq!(names.name_of("speaker_1"), Some("Alice"));
+        assert_eq!(names.name_of("speaker_2"), None);
+
+        let remap = HashMap::from([
+            ("speaker_1".to_string(), "speaker_3".to_string()),
+            ("speaker_2".to_string(), "speaker_1".to_string()),
+        ]);
+        let remapped = names.remapped(&remap);
+        assert_eq!(remapped.name_of("speaker_3"), Some("Alice"));
+        // The id now belongs to someone else, whose name is unknown
+        assert_eq!(remapped.name_of("speaker_1"), None);
+    }
+}
@@ -0,0 +1,266 @@
+//! Per-speaker talk time
+//!
+//! Aggregates stored transcript segments into speaking time per speaker for
+//! the "who dominated the meeting" view. A speaker's overlapping segments are
+//! merged before summing so re-emitted or overlapping windows are not counted
+//! twice; overlap between different speakers counts for each of them.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::diarization::muting;
+use crate::diarization::speaker_names::SpeakerNames;
+use crate::models::MeetingSpeaker;
+
+/// Bucket for segments without a speaker label
+pub const UNKNOWN_SPEAKER: &str = "unknown";
+
+/// Talk time of one speaker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerTalkTime {
+    #[serde(rename = "speakerId")]
+    pub speaker_id: String,
+    #[serde(rename = "speakingSeconds")]
+    pub speaking_seconds: f64,
+    #[serde(rename = "segmentCount")]
+    pub segment_count: usize,
+    /// Mean confidence of the speaker's segments that reported one
+    #[serde(rename = "averageConfidence")]
+    pub average_confidence: Option<f32>,
+    /// Share of the summed talk time of all speakers (0-100)
+    #[serde(rename = "talkTimePercent")]
+    pub talk_time_percent: f64,
+    /// The speaker is muted and left out of the live transcript
+    pub muted: bool,
+}
+
+/// Talk time of all speakers of a session, most talkative first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerStats {
+    pub speakers: Vec<SpeakerTalkTime>,
+    #[serde(rename = "totalTalkSeconds")]
+    pub total_talk_seconds: f64,
+    #[serde(rename = "segmentCount")]
+    pub segment_count: usize,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    intervals: Vec<(f64, f64)>,
+    segment_count: usize,
+    confidence_sum: f64,
+    confidence_count: usize,
+    muted: bool,
+}
+
+/// Length of the union of `intervals`
+fn covered_seconds(intervals: &mut [(f64, f64)]) -> f64 {
+    intervals.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut total = 0.0;
+    let mut current: Option<(f64, f64)> = None;
+    for &(start, end) in intervals.iter() {
+        current = match current {
+            Some((current_start, current_end)) if start <= current_end => Some((current_start, current_end.max(end))),
+            Some((current_start, current_end)) => {
+                total += current_end - current_start;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    total + current.map(|(start, end)| end - start).unwrap_or(0.0)
+}
+
+/// Aggregate talk time per speaker. Segments without usable times count
+/// toward the segment count but add no speaking time.
+pub fn compute_speaker_stats(segments: &[serde_json::Value]) -> SpeakerStats {
+    let mut speakers: HashMap<String, Accumulator> = HashMap::new();
+    for segment in segments {
+        let speaker_id = segment["speaker"].as_str()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .unwrap_or(UNKNOWN_SPEAKER);
+        let entry = speakers.entry(speaker_id.to_string()).or_default();
+        entry.segment_count += 1;
+        entry.muted |= muting::is_muted_segment(segment);
+
+        let start = segment["startTime"].as_f64().filter(|t| t.is_finite());
+        let end = segment["endTime"].as_f64().filter(|t| t.is_finite());
+        if let (Some(start), Some(end)) = (start, end) {
+            if end > start {
+                entry.intervals.push((start, end));
+            }
+        }
+        if let Some(confidence) = segment["confidence"].as_f64().filter(|c| (0.0..=1.0).contains(c)) {
+            entry.confidence_sum += confidence;
+            entry.confidence_count += 1;
+        }
+    }
+
+    let mut talk_times: Vec<SpeakerTalkTime> = speakers.into_iter()
+        .map(|(speaker_id, mut acc)| SpeakerTalkTime {
+            speaker_id,
+            speaking_seconds: covered_seconds(&mut acc.intervals),
+            segment_count: acc.segment_count,
+            average_confidence: (acc.confidence_count > 0).then(|| (acc.confidence_sum / acc.confidence_count as f64) as f32),
+            talk_time_percent: 0.0,
+            muted: acc.muted,
+        })
+        .collect();
+
+    let total_talk_seconds: f64 = talk_times.iter().map(|s| s.speaking_seconds).sum();
+    if total_talk_seconds > 0.0 {
+        for talk_time in &mut talk_times {
+            talk_time.talk_time_percent = talk_time.speaking_seconds / total_talk_seconds * 100.0;
+        }
+    }
+    talk_times.sort_by(|a, b| {
+        b.speaking_seconds.total_cmp(&a.speaking_seconds).then_with(|| a.speaker_id.cmp(&b.speaker_id))
+    });
+
+    SpeakerStats {
+        speakers: talk_times,
+        total_talk_seconds,
+        segment_count: segments.len(),
+    }
+}
+
+/// Links between a session and the speaker profiles its segments are
+/// attributed to, with each profile's talk time in the session. Speakers
+/// without a profile (session-local ids) get no link.
+pub fn profile_links(
+    meeting_id: Uuid,
+    started_at: DateTime<Utc>,
+    segments: &[serde_json::Value],
+    names: &SpeakerNames,
+) -> Vec<MeetingSpeaker> {
+    let stats = compute_speaker_stats(segments);
+    stats.speakers.iter()
+        .filter_map(|talk_time| {
+            let profile_id = Uuid::parse_str(&talk_time.speaker_id).ok()?;
+            let times: Vec<(f64, f64)> = segments.iter()
+                .filter(|segment| segment["speaker"].as_str().map(str::trim) == Some(talk_time.speaker_id.as_str()))
+                .filter_map(|segment| Some((segment["startTime"].as_f64()?, segment["endTime"].as_f64()?)))
+                .collect();
+            let first = times.iter().map(|t| t.0).fold(f64::INFINITY, f64::min);
+            let last = times.iter().map(|t| t.1).fold(f64::NEG_INFINITY, f64::max);
+            let at = |seconds: f64| started_at + Duration::milliseconds((seconds.max(0.0) * 1000.0) as i64);
+
+            let display_name = names.name_of(&talk_time.speaker_id).unwrap_or(&talk_time.speaker_id);
+            let mut link = MeetingSpeaker::new(meeting_id, profile_id, display_name.to_string());
+            link.speaking_time_seconds = talk_time.speaking_seconds as f32;
+            link.segment_count = talk_time.segment_count as u32;
+            link.average_confidence = talk_time.average_confidence.unwrap_or(0.0);
+            link.first_spoken_at = if first.is_finite() { at(first) } else { started_at };
+            link.last_spoken_at = if last.is_finite() { at(last) } else { started_at };
+            Some(link)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn speaker<'a>(stats: &'a SpeakerStats, id: &str) -> &'a SpeakerTalkTime {
+        stats.speakers.iter().find(|s| s.speaker_id == id).unwrap()
+    }
+
+    #[test]
+    fn test_talk_time_and_percentages() {
+        let segments = [
+            json!({"speaker": "speaker_1", "startTime": 0.0, "endTime": 6.0, "confidence": 0.9}),
+            json!({"speaker": "speaker_2", "startTime": 6.0, "endTime": 8.0, "confidence": 0.7}),
+            json!({"speaker": "speaker_1", "startTime": 8.0, "endTime": 10.0, "confidence": 0.8}),
+        ];
+        let stats = compute_speaker_stats(&segments);
+
+        assert_eq!(stats.total_talk_seconds, 10.0);
+        assert_eq!(stats.speakers[0].speaker_id, "speaker_1");
+        assert_eq!(stats.speakers[0].speaking_seconds, 8.0);
+        assert_eq!(stats.speakers[0].segment_count, 2);
+        assert!((stats.speakers[0].talk_time_percent - 80.0).abs() < 1e-9);
+        assert!((stats.speakers[0].average_confidence.unwrap() - 0.85).abs() < 1e-6);
+        assert!((speaker(&stats, "speaker_2").talk_time_percent - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_overlapping_segments_of_one_speaker_are_not_double_counted() {
+        let segments = [
+            json!({"speaker": "speaker_1", "startTime": 0.0, "endTime": 5.0}),
+            json!({"speaker": "speaker_1", "startTime": 3.0, "endTime": 7.0}),
+            json!({"speaker": "speaker_1", "startTime": 4.0, "endTime": 4.5}),
+            // Crosstalk: counts for both speakers
+            json!({"speaker": "speaker_2", "startTime": 6.0, "endTime": 9.0}),
+        ];
+        let stats = compute_speaker_stats(&segments);
+
+        assert_eq!(speaker(&stats, "speaker_1").speaking_seconds, 7.0);
+        assert_eq!(speaker(&stats, "speaker_1").segment_count, 3);
+        assert_eq!(speaker(&stats, "speaker_2").speaking_seconds, 3.0);
+        assert_eq!(stats.total_talk_seconds, 10.0);
+    }
+
+    #[test]
+    fn test_zero_length_and_unlabelled_segments() {
+        let segments = [
+            json!({"speaker": "speaker_1", "startTime": 2.0, "endTime": 2.0, "confidence": 0.5}),
+            json!({"speaker": "speaker_1", "startTime": 5.0, "endTime": 4.0}),
+            json!({"text": "no speaker", "startTime": 0.0, "endTime": 1.5}),
+            json!({"speaker": "  ", "startTime": 1.5, "endTime": 2.0}),
+        ];
+        let stats = compute_speaker_stats(&segments);
+
+        let first = speaker(&stats, "speaker_1");
+        assert_eq!(first.speaking_seconds, 0.0);
+        assert_eq!(first.segment_count, 2);
+        assert_eq!(first.talk_time_percent, 0.0);
+        let unknown = speaker(&stats, UNKNOWN_SPEAKER);
+        assert_eq!(unknown.segment_count, 2);
+        assert_eq!(unknown.speaking_seconds, 2.0);
+        assert_eq!(unknown.talk_time_percent, 100.0);
+        assert_eq!(stats.segment_count, 4);
+
+        let empty = compute_speaker_stats(&[]);
+        assert!(empty.speakers.is_empty());
+        assert_eq!(empty.total_talk_seconds, 0.0);
+    }
+
+    #[test]
+    fn test_muted_speakers_are_labelled() {
+        let segments = [
+            json!({"speaker": "speaker_1", "startTime": 0.0, "endTime": 1.0, "muted": true}),
+            json!({"speaker": "speaker_2", "startTime": 1.0, "endTime": 2.0}),
+        ];
+        let stats = compute_speaker_stats(&segments);
+        assert!(speaker(&stats, "speaker_1").muted);
+        assert!(!speaker(&stats, "speaker_2").muted);
+    }
+
+    #[test]
+    fn test_profile_links_cover_only_profile_speakers() {
+        let profile = Uuid::new_v4();
+        let segments = [
+            json!({"speaker": profile.to_string(), "startTime": 2.0, "endTime": 5.0, "confidence": 0.8}),
+            json!({"speaker": "speaker_2", "startTime": 5.0, "endTime": 6.0}),
+            json!({"speaker": profile.to_string(), "startTime": 10.0, "endTime": 12.0, "confidence": 0.6}),
+        ];
+        let mut names = SpeakerNames::new();
+        names.rename(&profile.to_string(), "Alice");
+        let meeting = Uuid::new_v4();
+        let started_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        let links = profile_links(meeting, started_at, &segments, &names);
+        assert_eq!(links.len(), 1);
+        assert_eq!((links[0].meeting_id, links[0].speaker_id), (meeting, profile));
+        assert_eq!(links[0].display_name, "Alice");
+        assert_eq!(links[0].speaking_time_seconds, 5.0);
+        assert_eq!(links[0].segment_count, 2);
+        assert!((links[0].average_confidence - 0.7).abs() < 1e-6);
+        assert_eq!(links[0].first_spoken_at.timestamp(), 1_700_000_002);
+        assert_eq!(links[0].last_spoken_at.timestamp(), 1_700_000_012);
+    }
+}
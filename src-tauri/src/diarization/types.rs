@@ -104,6 +104,13 @@ pub struct DiarizationConfig {
     
     /// Maximum memory usage in MB
     pub max_memory_mb: usize,
+    
+    /// Seed for deterministic mode (reproducible test runs): speakers are numbered
+    /// by first appearance in the audio and ties are broken by ID. Clustering
+    /// draws no random numbers; test scenarios generate their audio and
+    /// embeddings from this seed
+    #[serde(default)]
+    pub deterministic_seed: Option<u64>,
 }
 
 impl Default for DiarizationConfig {
@@ -123,6 +130,7 @@ impl Default for DiarizationConfig {
             vad_threshold: 0.5,
             detect_overlaps: true,
             max_memory_mb: 500,
+            deterministic_seed: None,
         }
     }
 }
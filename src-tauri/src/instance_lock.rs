@@ -0,0 +1,219 @@
+//! Single-instance lock
+//!
+//! Two running copies of the app would share speakers.db, the audio device and
+//! the session directories. At startup the app takes an OS-level advisory lock
+//! on a lockfile in the data directory and records its PID there. A second
+//! instance that finds the lock held refuses to start (the single-instance
+//! plugin focuses the existing window first). A held OS lock means its holder
+//! is alive, whatever PID the file shows, so a locked file is never removed;
+//! a file whose lock is free was left by a process that is gone, and its PID
+//! is simply overwritten.
+//!
+//! Subsystems that write to the data directory call [`assert_held`] before
+//! writing. The check is only enforced once the app has asked for the lock, so
+//! library and test use of those subsystems is unaffected.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use thiserror::Error;
+
+/// Lockfile name inside the data directory
+pub const LOCK_FILE_NAME: &str = "kaginote.lock";
+
+/// Why the instance lock could not be taken or is not held
+#[derive(Debug, Clone, PartialEq, Error, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InstanceLockError {
+    #[error("KagiNote is already running (process {pid})")]
+    AlreadyRunning { pid: u32, path: String },
+
+    #[error("Failed to access instance lock {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("{subsystem} tried to write without holding the instance lock")]
+    NotHeld { subsystem: String },
+}
+
+/// Held lock; released when dropped (or when the process exits)
+#[derive(Debug)]
+pub struct InstanceLock {
+    file: File,
+    path: PathBuf,
+    /// PID left in the file by a previous holder that exited without releasing it
+    pub took_over_from: Option<u32>,
+}
+
+impl InstanceLock {
+    /// Take the lock at `path`, taking over the file of a holder that is gone
+    pub fn acquire(path: &Path) -> Result<Self, InstanceLockError> {
+        Self::acquire_with(path, std::process::id())
+    }
+
+    /// [`InstanceLock::acquire`] with an explicit PID
+    pub fn acquire_with(path: &Path, pid: u32) -> Result<Self, InstanceLockError> {
+        let io_error = |e: std::io::Error| InstanceLockError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+
+        let mut file = open_lock_file(path).map_err(io_error)?;
+        let previous_pid = read_pid(&mut file);
+
+        // The OS releases the lock when its holder exits, so a held lock is a
+        // running instance even if the PID in the file looks dead: that
+        // instance may not have written its own PID yet
+        if file.try_lock().is_err() {
+            if let Some(holder) = previous_pid.filter(|&holder| !process_is_alive(holder)) {
+                tracing::warn!("Instance lock is held although process {} recorded in it has exited", holder);
+            }
+            return Err(InstanceLockError::AlreadyRunning {
+                pid: previous_pid.unwrap_or(0),
+                path: path.display().to_string(),
+            });
+        }
+
+        // Locked by us: any PID still in the file is stale
+        let took_over_from = previous_pid.filter(|&previous| previous != pid);
+        if let Some(previous) = took_over_from {
+            tracing::info!("🔓 Took over instance lock left by process {}", previous);
+        }
+
+        file.set_len(0).map_err(io_error)?;
+        file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        write!(file, "{}", pid).map_err(io_error)?;
+        file.sync_all().map_err(io_error)?;
+
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            took_over_from,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // Empty the file so a later start does not report a takeover
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+    }
+}
+
+fn open_lock_file(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)
+}
+
+fn read_pid(file: &mut File) -> Option<u32> {
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).ok()?;
+    contents.trim().parse().ok()
+}
+
+/// Whether a process with `pid` currently exists
+pub fn process_is_alive(pid: u32) -> bool {
+    let mut system = sysinfo::System::new();
+    system.refresh_process(sysinfo::Pid::from_u32(pid))
+}
+
+/// Default lockfile location in the app data directory
+pub fn default_lock_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("KagiNote").join(LOCK_FILE_NAME))
+}
+
+static ENFORCED: AtomicBool = AtomicBool::new(false);
+static PROCESS_LOCK: OnceLock<InstanceLock> = OnceLock::new();
+
+/// Take the process-wide lock at startup; from now on [`assert_held`] is enforced
+pub fn acquire_for_process(path: &Path) -> Result<&'static InstanceLock, InstanceLockError> {
+    ENFORCED.store(true, Ordering::SeqCst);
+    if let Some(lock) = PROCESS_LOCK.get() {
+        return Ok(lock);
+    }
+    let lock = InstanceLock::acquire(path)?;
+    Ok(PROCESS_LOCK.get_or_init(|| lock))
+}
+
+/// Fail if the app took part in instance locking but does not hold the lock
+pub fn assert_held(subsystem: &str) -> Result<(), InstanceLockError> {
+    if ENFORCED.load(Ordering::SeqCst) && PROCESS_LOCK.get().is_none() {
+        tracing::error!("❌ {} attempted to write without the instance lock", subsystem);
+        return Err(InstanceLockError::NotHeld { subsystem: subsystem.to_string() });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_path(dir: &tempfile::TempDir) -> PathBuf {
+        dir.path().join("data").join(LOCK_FILE_NAME)
+    }
+
+    #[test]
+    fn test_stale_lock_from_dead_pid_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = lock_path(&dir);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        // A crashed instance leaves its PID behind; the OS released its lock
+        std::fs::write(&path, "999999").unwrap();
+
+        let lock = InstanceLock::acquire_with(&path, 4242).unwrap();
+
+        assert_eq!(lock.took_over_from, Some(999999));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "4242");
+    }
+
+    #[test]
+    fn test_live_lock_is_refused_with_structured_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = lock_path(&dir);
+        let _first = InstanceLock::acquire_with(&path, 4242).unwrap();
+
+        let error = InstanceLock::acquire_with(&path, 5151).unwrap_err();
+
+        assert_eq!(error, InstanceLockError::AlreadyRunning { pid: 4242, path: path.display().to_string() });
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["type"], "alreadyRunning");
+        assert_eq!(json["pid"], 4242);
+    }
+
+    #[test]
+    fn test_held_lock_is_never_removed_whatever_pid_it_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = lock_path(&dir);
+        // The running holder has not rewritten the file yet: it names a dead process
+        let holder = InstanceLock::acquire_with(&path, 999999).unwrap();
+
+        let error = InstanceLock::acquire_with(&path, 5151).unwrap_err();
+
+        assert!(matches!(error, InstanceLockError::AlreadyRunning { pid: 999999, .. }));
+        // Same file, still locked by the holder
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "999999");
+        assert!(InstanceLock::acquire_with(&path, 6161).is_err());
+        drop(holder);
+        assert_eq!(InstanceLock::acquire_with(&path, 6161).unwrap().took_over_from, None);
+    }
+
+    #[test]
+    fn test_released_lock_can_be_reacquired() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = lock_path(&dir);
+        drop(InstanceLock::acquire_with(&path, 4242).unwrap());
+
+        let lock = InstanceLock::acquire_with(&path, 5151).unwrap();
+        assert_eq!(lock.took_over_from, None);
+        assert_eq!(lock.path(), path.as_path());
+    }
+}
@@ -9,8 +9,22 @@ pub mod diarization;
 pub mod models;
 pub mod storage;
 pub mod transcription;
+pub mod startup;
+pub mod power;
+pub mod session_tasks;
+pub mod shutdown;
+pub mod config_validation;
+pub mod instance_lock;
+pub mod metadata;
+pub mod command_error;
+pub mod quality;
+pub mod resource_monitor;
+pub mod logging;
+pub mod diagnostics;
 
-use tauri::Manager;
+use tauri::{Emitter, Manager};
+
+use shutdown::ExitDecision;
 
 // Tauri commands for frontend integration
 pub mod commands;
@@ -18,6 +32,13 @@ pub mod commands;
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must be registered first: a second launch focuses the running window and exits
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        }))
         .plugin(tauri_plugin_opener::init())
         .manage(commands::AppState::new())
         .invoke_handler(tauri::generate_handler![
@@ -26,13 +47,71 @@ pub fn run() {
             commands::stop_audio_capture,
             commands::transcribe_audio,
             commands::transcribe_audio_file,
+            commands::transcribe_audio_folder,
+            commands::cancel_batch_transcription,
             commands::get_audio_devices,
+            commands::start_device_monitoring,
+            commands::stop_device_monitoring,
             commands::get_system_info,
+            commands::check_microphone_permission,
+            commands::request_microphone_permission,
+            commands::get_startup_report,
+            commands::get_recent_logs,
+            commands::get_log_file_path,
+            commands::export_diagnostics,
+            commands::get_recoverable_sessions,
+            commands::recover_session,
+            commands::discard_recovered_session,
             commands::start_transcription,
             commands::stop_transcription,
+            commands::cancel_model_download,
             commands::get_active_sessions,
             commands::cleanup_session,
             commands::emergency_stop_all,
+            commands::export_session_chain,
+            commands::export_transcript,
+            commands::export_session_markdown,
+            commands::export_session_as_ground_truth,
+            commands::import_ground_truth,
+            commands::retranscribe_segment,
+            commands::get_segment_audio,
+            commands::format_transcript_segments,
+            commands::rediarize_session,
+            commands::register_transcript_window,
+            commands::configure_backups,
+            commands::configure_workspace_quota,
+            commands::get_backup_status,
+            commands::run_backup_now,
+            commands::restore_from_backup,
+            commands::get_segment_peaks,
+            commands::get_power_assertion_state,
+            commands::list_supported_languages,
+            commands::mute_speaker,
+            commands::unmute_speaker,
+            commands::get_session_speaker_stats,
+            commands::capture_highlight,
+            commands::get_session_highlights,
+            commands::pause_transcription,
+            commands::resume_transcription,
+            commands::update_vad_threshold,
+            commands::set_noise_suppression,
+            commands::get_session_health,
+            commands::dump_session_events,
+            commands::replay_events_to_frontend,
+            commands::get_usage_metrics,
+            commands::clear_usage_metrics,
+            commands::list_saved_sessions,
+            commands::get_saved_session,
+            commands::search_transcripts,
+            commands::delete_saved_session,
+            commands::compute_storage_usage,
+            commands::cancel_storage_usage,
+            commands::list_downloaded_models,
+            commands::delete_model,
+            commands::verify_model,
+            commands::get_model_requirements,
+            commands::get_storage_info,
+            commands::set_models_directory,
             // Speaker profile management commands
             commands::initialize_speaker_storage,
             commands::create_speaker_profile,
@@ -42,6 +121,9 @@ pub fn run() {
             commands::delete_speaker_profile,
             commands::add_voice_embedding,
             commands::get_voice_embeddings,
+            commands::add_speaker_sample,
+            commands::get_speaker_samples,
+            commands::delete_speaker_sample,
             commands::find_similar_speakers,
             commands::fast_similarity_search,
             commands::get_embedding_index_stats,
@@ -52,46 +134,100 @@ pub fn run() {
             commands::load_test_seed_data,
             commands::create_comprehensive_test_dataset,
             commands::clear_all_speaker_data,
+            commands::normalize_stored_metadata,
             // Diarization integration commands
             commands::initialize_diarization_service,
             commands::diarize_audio_segment,
             commands::identify_speaker,
+            commands::enroll_speaker,
             commands::get_diarization_stats,
+            commands::retry_diarization,
             commands::update_speaker_in_session,
             commands::merge_speaker_profiles
         ])
         .setup(|app| {
-            // Initialize logging
-            tracing_subscriber::fmt::init();
+            // Console plus the rotating JSON log file the diagnostics panel reads
+            if let Some(path) = logging::init(logging::log_dir().as_deref()) {
+                tracing::info!("Writing logs to {}", path.display());
+            }
+            
+            // Guard the data directory against a second instance the plugin did not catch
+            // (e.g. a different build); a lock left by a dead process is taken over
+            let lock_path = instance_lock::default_lock_path()
+                .ok_or("Failed to get app data directory for the instance lock")?;
+            if let Err(e) = instance_lock::acquire_for_process(&lock_path) {
+                tracing::error!("❌ {}", e);
+                return Err(Box::new(e));
+            }
             
             // Store app handle for cleanup on exit
             let app_handle = app.handle().clone();
             
-            // Perform any initialization here
+            // Commands issued before background initialization finishes wait on this
+            // gate and report "initializing" instead of "not initialized"
+            app.state::<commands::AppState>()
+                .speaker_storage_gate
+                .set(startup::InitState::Initializing);
+            
+            // Device hot-plug polling; idle until the picker or a capture needs it
+            commands::spawn_device_watcher(app_handle.clone());
+            
+            // Heavy initialization runs in the background so the window appears immediately
             tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<commands::AppState>();
+                
+                // Fall back to the default models directory if the configured one is gone
+                commands::check_models_directory(&app_handle);
+                
                 // Initialize audio and ASR systems
-                if let Err(e) = initialize_systems().await {
+                let started = std::time::Instant::now();
+                if let Err(e) = initialize_systems(&state).await {
                     tracing::error!("Failed to initialize systems: {}", e);
                 }
+                state.record_startup_phase("systems", started.elapsed());
                 
                 // Initialize speaker storage on startup
-                let state = app_handle.state::<commands::AppState>();
                 if let Err(e) = state.initialize_speaker_storage().await {
                     tracing::warn!("Failed to initialize speaker storage on startup: {}", e);
                 }
+                
+                // Warm up the device profile cache before the first recording
+                if let Err(e) = state.device_profiles().await {
+                    tracing::warn!("Failed to load device profiles on startup: {}", e);
+                }
             });
             
-            // Register cleanup handler for when app is about to exit
-            // Note: For Tauri v2, window event handling may be different
-            // For now, we'll handle cleanup in a different way or skip this specific handler
+            // Daily backups, when a backup directory is configured
+            tauri::async_runtime::spawn(commands::run_daily_backups(app.handle().clone()));
             
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Quitting and closing the last window both end up here; the exit is
+            // held until active sessions are saved
+            if let tauri::RunEvent::ExitRequested { api, .. } = event {
+                match app_handle.state::<commands::AppState>().exit_cleanup.request_exit() {
+                    ExitDecision::Exit => {}
+                    ExitDecision::Wait => api.prevent_exit(),
+                    ExitDecision::Cleanup => {
+                        api.prevent_exit();
+                        let app_handle = app_handle.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = cleanup_app_state(app_handle.clone()).await {
+                                tracing::error!("App state cleanup failed: {}", e);
+                            }
+                            app_handle.state::<commands::AppState>().exit_cleanup.finish();
+                            app_handle.exit(0);
+                        });
+                    }
+                }
+            }
+        });
 }
 
-async fn initialize_systems() -> anyhow::Result<()> {
+async fn initialize_systems(state: &commands::AppState) -> anyhow::Result<()> {
     // Initialize core systems
     tracing::info!("Initializing KagiNote systems...");
     
@@ -105,6 +241,9 @@ async fn initialize_systems() -> anyhow::Result<()> {
         tracing::warn!("Diarization system initialization failed: {}", e);
     }
     
+    // Transcripts autosaved by sessions a crash or forced quit cut short
+    state.find_recoverable_sessions().await;
+    
     // Pre-load models if available
     // asr::whisper::WhisperEngine::preload_models().await?;
     
@@ -112,29 +251,58 @@ async fn initialize_systems() -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Cleanup app state when the application is shutting down
+/// Cleanup app state when the application is shutting down. Active sessions are
+/// stopped the way stop_transcription stops them, so their results are persisted
+/// and recordings finalized, within the longest grace period they configured;
+/// whatever is left after that is torn down like an emergency stop.
 async fn cleanup_app_state(app_handle: tauri::AppHandle) -> anyhow::Result<()> {
     tracing::info!("Cleaning up KagiNote app state...");
     
     let state = app_handle.state::<commands::AppState>();
+    let (session_ids, grace_period) = {
+        let sessions_guard = state.active_sessions.lock().await;
+        let grace_period = shutdown::grace_period(sessions_guard.values().map(|s| s.config.exit_grace_period_seconds));
+        (sessions_guard.keys().cloned().collect::<Vec<_>>(), grace_period)
+    };
     
-    // Stop all active audio capture services
-    let mut audio_capture_guard = state.audio_capture_service.lock().await;
-    if let Some(mut capture_service) = audio_capture_guard.take() {
-        if let Err(e) = capture_service.stop_capture().await {
-            tracing::warn!("Failed to stop audio capture during cleanup: {}", e);
+    if !session_ids.is_empty() {
+        // Lets the frontend show a spinner while sessions are saved
+        emit_exit_progress(&app_handle, "saving", session_ids.len(), grace_period);
+        let saving = async {
+            for session_id in &session_ids {
+                if let Err(e) = commands::finish_session(session_id.clone(), &app_handle, None).await {
+                    tracing::warn!("Failed to stop session {} on exit: {}", session_id, e);
+                }
+            }
+        };
+        if tokio::time::timeout(grace_period, saving).await.is_err() {
+            tracing::warn!("Active sessions were not saved within {:?}", grace_period);
         }
     }
-    drop(audio_capture_guard);
     
-    // Clear all active sessions
-    let mut sessions_guard = state.active_sessions.lock().await;
-    let session_count = sessions_guard.len();
-    sessions_guard.clear();
-    drop(sessions_guard);
+    // Sessions not saved in time, the capture service and background work
+    if let Err(e) = commands::emergency_stop_all(app_handle.state()).await {
+        tracing::warn!("Failed to stop remaining sessions during cleanup: {}", e);
+    }
+    if !session_ids.is_empty() {
+        emit_exit_progress(&app_handle, "saved", session_ids.len(), grace_period);
+    }
     
-    // Whisper engine will be dropped automatically when the app state is dropped
+    // Changes made since the last debounced save
+    state.flush_embedding_index().await;
     
-    tracing::info!("Cleaned up {} active sessions and audio services", session_count);
+    tracing::info!("Cleaned up {} active sessions and audio services", session_ids.len());
     Ok(())
 }
+
+/// Progress of saving sessions on exit: "saving", then "saved"
+fn emit_exit_progress(app_handle: &tauri::AppHandle, status: &str, session_count: usize, grace_period: std::time::Duration) {
+    if let Err(emit_err) = app_handle.emit("app-exit-saving", serde_json::json!({
+        "status": status,
+        "sessionCount": session_count,
+        "gracePeriodSeconds": grace_period.as_secs(),
+        "message": if status == "saving" { "Saving..." } else { "Saved" },
+    })) {
+        tracing::warn!("Failed to emit app-exit-saving event: {}", emit_err);
+    }
+}
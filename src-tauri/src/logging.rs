@@ -0,0 +1,317 @@
+//! Application log file
+//!
+//! Besides the console, tracing events at info level and above are written as
+//! JSON lines to `kaginote.log` in the app data directory, so a bundled app
+//! leaves something to attach to a bug report. Each event is a single append
+//! to an unbuffered file: a crash loses at most the event being written, and
+//! a torn last line is skipped when reading. Once the file would exceed
+//! [`DEFAULT_MAX_LOG_BYTES`] it is rotated to `kaginote.1.log`, older files
+//! shift up and only [`DEFAULT_KEPT_LOG_FILES`] rotated files are kept.
+//!
+//! Transcribed text is logged at debug level only, which never reaches the file.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use tracing::Level;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Log file currently written to
+pub const LOG_FILE_NAME: &str = "kaginote.log";
+/// Size a log file may reach before it is rotated (5MB)
+pub const DEFAULT_MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated log files kept besides the current one
+pub const DEFAULT_KEPT_LOG_FILES: usize = 4;
+
+/// Directory holding the application log files
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("KagiNote").join("logs"))
+}
+
+/// Path of the `index`-th rotated log file; 0 is the current file
+fn log_file_path(dir: &Path, index: usize) -> PathBuf {
+    match index {
+        0 => dir.join(LOG_FILE_NAME),
+        _ => dir.join(format!("kaginote.{}.log", index)),
+    }
+}
+
+/// Size-bounded log file that rotates into numbered predecessors
+pub struct RotatingLogFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingLogFile {
+    /// Open (or continue) the log file in `dir`
+    pub fn open(dir: &Path, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let mut log = Self { dir: dir.to_path_buf(), max_bytes, keep, file: None, size: 0 };
+        log.reopen()?;
+        Ok(log)
+    }
+
+    pub fn path(&self) -> PathBuf {
+        log_file_path(&self.dir, 0)
+    }
+
+    fn reopen(&mut self) -> io::Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(self.path())?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        // Renaming onto an existing file fails on Windows, so the oldest goes first
+        remove_if_exists(&log_file_path(&self.dir, self.keep))?;
+        for index in (0..self.keep).rev() {
+            let from = log_file_path(&self.dir, index);
+            if from.exists() {
+                fs::rename(&from, log_file_path(&self.dir, index + 1))?;
+            }
+        }
+        if self.keep == 0 {
+            remove_if_exists(&self.path())?;
+        }
+        self.reopen()
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+impl Write for RotatingLogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        if self.file.is_none() {
+            self.reopen()?;
+        }
+        let file = self.file.as_mut().ok_or_else(|| io::Error::other("log file is not open"))?;
+        file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writer handed to the JSON layer; every event is one locked write
+#[derive(Clone)]
+pub struct LogFileWriter(Arc<Mutex<RotatingLogFile>>);
+
+impl LogFileWriter {
+    pub fn new(file: RotatingLogFile) -> Self {
+        Self(Arc::new(Mutex::new(file)))
+    }
+}
+
+impl Write for LogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogFileWriter {
+    type Writer = LogFileWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Log to the console and, when `dir` is given, to the log file in it.
+/// Returns the path of the log file if one is written.
+pub fn init(dir: Option<&Path>) -> Option<PathBuf> {
+    use tracing_subscriber::prelude::*;
+
+    let file = dir.and_then(|dir| match RotatingLogFile::open(dir, DEFAULT_MAX_LOG_BYTES, DEFAULT_KEPT_LOG_FILES) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            eprintln!("Failed to open the log file in {}: {}", dir.display(), e);
+            None
+        }
+    });
+    let path = file.as_ref().map(RotatingLogFile::path);
+    let file_layer = file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(false)
+            .with_writer(LogFileWriter::new(file))
+            .with_filter(LevelFilter::INFO)
+    });
+
+    let installed = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+        .with(file_layer)
+        .try_init();
+    if installed.is_err() {
+        eprintln!("A tracing subscriber is already installed; logs are not written to a file");
+        return None;
+    }
+    path
+}
+
+/// One event read back from the log file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Structured fields of the event other than the message
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LogEntry {
+    /// Parse a JSON log line; torn or foreign lines give None
+    fn parse(line: &str) -> Option<Self> {
+        let serde_json::Value::Object(mut fields) = serde_json::from_str(line).ok()? else {
+            return None;
+        };
+        let mut take = |key: &str| match fields.remove(key) {
+            Some(serde_json::Value::String(value)) => Some(value),
+            _ => None,
+        };
+        let level = take("level")?;
+        Some(Self {
+            timestamp: take("timestamp").unwrap_or_default(),
+            target: take("target").unwrap_or_default(),
+            message: take("message").unwrap_or_default(),
+            level,
+            fields,
+        })
+    }
+
+    fn level(&self) -> Option<Level> {
+        self.level.parse().ok()
+    }
+}
+
+/// The last `lines` entries at `min_level` or more severe, oldest first,
+/// from the current log file and then the rotated ones
+pub fn read_recent_logs(dir: &Path, lines: usize, min_level: Level) -> io::Result<Vec<LogEntry>> {
+    let mut newest_first = Vec::with_capacity(lines);
+    for index in 0..=DEFAULT_KEPT_LOG_FILES {
+        if newest_first.len() >= lines {
+            break;
+        }
+        let contents = match fs::read(log_file_path(dir, index)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let wanted = lines - newest_first.len();
+        newest_first.extend(String::from_utf8_lossy(&contents)
+            .lines()
+            .rev()
+            .filter_map(LogEntry::parse)
+            .filter(|entry| entry.level().is_some_and(|level| level <= min_level))
+            .take(wanted));
+    }
+    newest_first.reverse();
+    Ok(newest_first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(level: &str, message: &str) -> String {
+        format!("{}\n", serde_json::json!({
+            "timestamp": "2026-10-15T09:00:00.000000Z",
+            "level": level,
+            "message": message,
+            "sessionId": "session-1",
+            "target": "kaginote_lib::commands"
+        }))
+    }
+
+    #[test]
+    fn test_rotation_keeps_bounded_number_of_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = line("INFO", "chunk processed");
+        let mut log = RotatingLogFile::open(dir.path(), entry.len() as u64 * 3, 2).unwrap();
+        for _ in 0..12 {
+            log.write_all(entry.as_bytes()).unwrap();
+        }
+
+        let mut names: Vec<_> = fs::read_dir(dir.path()).unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["kaginote.1.log", "kaginote.2.log", "kaginote.log"]);
+        for name in names {
+            let len = fs::metadata(dir.path().join(name)).unwrap().len();
+            assert_eq!(len, entry.len() as u64 * 3);
+        }
+    }
+
+    #[test]
+    fn test_recent_logs_span_rotated_files_and_filter_levels() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(log_file_path(dir.path(), 1), [line("INFO", "one"), line("ERROR", "two")].concat()).unwrap();
+        // The crash cut the last line short
+        let current = [line("WARN", "three"), line("DEBUG", "four"), line("INFO", "five")].concat() + "{\"timestamp\":\"2026-10";
+        fs::write(log_file_path(dir.path(), 0), current).unwrap();
+
+        let recent = read_recent_logs(dir.path(), 3, Level::INFO).unwrap();
+        let messages: Vec<_> = recent.iter().map(|entry| entry.message.as_str()).collect();
+        assert_eq!(messages, ["two", "three", "five"]);
+        assert_eq!(recent[0].fields["sessionId"], "session-1");
+        assert_eq!(recent[0].target, "kaginote_lib::commands");
+
+        let warnings = read_recent_logs(dir.path(), 10, Level::WARN).unwrap();
+        assert_eq!(warnings.iter().map(|entry| entry.level.as_str()).collect::<Vec<_>>(), ["ERROR", "WARN"]);
+        assert!(read_recent_logs(&dir.path().join("absent"), 10, Level::TRACE).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_json_layer_output_reads_back() {
+        use tracing_subscriber::prelude::*;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = RotatingLogFile::open(dir.path(), DEFAULT_MAX_LOG_BYTES, DEFAULT_KEPT_LOG_FILES).unwrap();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_writer(LogFileWriter::new(file))
+                .with_filter(LevelFilter::INFO),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(chunks = 3, "Transcription completed");
+            tracing::debug!("Transcription text: 'not for the log file'");
+        });
+
+        let recent = read_recent_logs(dir.path(), 10, Level::TRACE).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!((recent[0].level.as_str(), recent[0].message.as_str()), ("INFO", "Transcription completed"));
+        assert_eq!(recent[0].fields["chunks"], 3);
+    }
+}
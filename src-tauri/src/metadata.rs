@@ -0,0 +1,349 @@
+//! Sanitization of user-supplied metadata
+//!
+//! Speaker names, descriptions, display names and highlight labels are
+//! free-form input that ends up in the database, JSON exports, the UI and
+//! (through slugs) in file names. Commands pass such input through this module
+//! at their boundary:
+//! - text is NFC-normalized, control characters and bidirectional overrides
+//!   are removed and the length is bounded per field; overlong or empty
+//!   required values are rejected with a per-field error
+//! - identifiers that become part of paths (session ids) must be plain
+//!   `[A-Za-z0-9_.-]` tokens
+//! - anything else used in a path goes through [`slugify`], with collisions
+//!   resolved by [`unique_slug`]
+//!
+//! Data stored before this existed is cleaned on read with
+//! [`normalize_stored_text`]; nothing is rejected there.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::models::{CreateSpeakerProfileRequest, SpeakerProfile, UpdateSpeakerProfileRequest};
+
+/// Speaker profile names and session display names
+pub const NAME_RULE: TextRule = TextRule { max_chars: 100, multiline: false, required: true };
+/// Profile descriptions and notes
+pub const DESCRIPTION_RULE: TextRule = TextRule { max_chars: 4000, multiline: true, required: false };
+/// Highlight labels and similar short captions
+pub const LABEL_RULE: TextRule = TextRule { max_chars: 200, multiline: false, required: false };
+/// Longest identifier accepted where it becomes part of a path
+pub const MAX_IDENTIFIER_CHARS: usize = 128;
+/// Longest generated slug (characters)
+pub const MAX_SLUG_CHARS: usize = 64;
+
+/// Device names Windows refuses as file names, with any extension
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9",
+    "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Limits for one text field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextRule {
+    pub max_chars: usize,
+    /// Keep line breaks and tabs
+    pub multiline: bool,
+    /// Reject values that are empty after cleaning
+    pub required: bool,
+}
+
+/// A rejected metadata value
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[error("{field}: {message}")]
+pub struct MetadataError {
+    pub field: String,
+    pub message: String,
+}
+
+impl MetadataError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self { field: field.to_string(), message: message.into() }
+    }
+}
+
+/// Clean and bound a text field
+pub fn sanitize_text(field: &str, input: &str, rule: TextRule) -> Result<String, MetadataError> {
+    // Reject huge inputs before normalizing them; 4 bytes per char is the UTF-8 maximum
+    if input.len() > rule.max_chars * 4 + 1024 {
+        return Err(MetadataError::new(field, format!("must be at most {} characters", rule.max_chars)));
+    }
+    let cleaned = clean(input, rule.multiline);
+    let length = cleaned.chars().count();
+    if length > rule.max_chars {
+        return Err(MetadataError::new(field, format!("must be at most {} characters (got {})", rule.max_chars, length)));
+    }
+    if rule.required && cleaned.is_empty() {
+        return Err(MetadataError::new(field, "must not be empty"));
+    }
+    Ok(cleaned)
+}
+
+/// [`sanitize_text`] for optional fields; blank values become `None`
+pub fn sanitize_optional(field: &str, input: Option<&str>, rule: TextRule) -> Result<Option<String>, MetadataError> {
+    match input {
+        Some(value) => sanitize_text(field, value, TextRule { required: false, ..rule })
+            .map(|cleaned| Some(cleaned).filter(|c| !c.is_empty())),
+        None => Ok(None),
+    }
+}
+
+/// Accept `#rgb` or `#rrggbb` colors, returned in lowercase
+pub fn sanitize_color(field: &str, input: &str) -> Result<String, MetadataError> {
+    let color = input.trim();
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    if (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(color.to_ascii_lowercase())
+    } else {
+        Err(MetadataError::new(field, "must be a hex color like #1a2b3c"))
+    }
+}
+
+/// Check an identifier that becomes part of a file path
+pub fn validate_identifier<'a>(field: &str, id: &'a str) -> Result<&'a str, MetadataError> {
+    let valid = !id.is_empty()
+        && id.chars().count() <= MAX_IDENTIFIER_CHARS
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !id.starts_with('.')
+        && !id.contains("..");
+    if valid {
+        Ok(id)
+    } else {
+        Err(MetadataError::new(field, "must be 1-128 letters, digits, '_', '-' or '.'"))
+    }
+}
+
+/// Filename-safe slug: lowercase letters and digits separated by single dashes
+pub fn slugify(input: &str) -> String {
+    let mut slug = String::new();
+    // Only the start of the input can end up in the slug
+    let head: String = input.chars().take(MAX_SLUG_CHARS * 8).collect();
+    for c in clean(&head, false).chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= MAX_SLUG_CHARS {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        return "untitled".to_string();
+    }
+    if RESERVED_FILE_NAMES.contains(&slug.as_str()) {
+        return format!("{}-file", slug);
+    }
+    slug
+}
+
+/// `base`, or `base-2`, `base-3`, ... whichever `is_taken` does not report as used
+pub fn unique_slug(base: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    if !is_taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !is_taken(candidate))
+        .unwrap_or_else(|| base.to_string())
+}
+
+/// Lenient clean-up for stored values: same cleaning, truncated instead of
+/// rejected. Returns `None` when the value is already clean.
+pub fn normalize_stored_text(input: &str, rule: TextRule) -> Option<String> {
+    let mut cleaned = clean(input, rule.multiline);
+    if let Some((index, _)) = cleaned.char_indices().nth(rule.max_chars) {
+        cleaned.truncate(index);
+        cleaned = cleaned.trim_end().to_string();
+    }
+    (cleaned != input).then_some(cleaned)
+}
+
+/// NFC, no control or bidi formatting characters, trimmed
+fn clean(input: &str, multiline: bool) -> String {
+    // Filter before normalizing so removed characters cannot leave uncomposed sequences
+    let filtered: String = input.replace("\r\n", "\n").chars()
+        .filter_map(|c| match c {
+            '\n' | '\t' if multiline => Some(c),
+            '\n' | '\t' | '\r' => Some(' '),
+            c if c.is_control() || is_invisible_format(c) => None,
+            c => Some(c),
+        })
+        .collect();
+    filtered.nfc().collect::<String>().trim().to_string()
+}
+
+/// Bidirectional overrides/isolates, zero-width characters and the BOM
+fn is_invisible_format(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2069}' | '\u{FEFF}')
+}
+
+/// Sanitize a profile creation request
+pub fn sanitize_create_profile(request: CreateSpeakerProfileRequest) -> Result<CreateSpeakerProfileRequest, MetadataError> {
+    Ok(CreateSpeakerProfileRequest {
+        name: sanitize_text("name", &request.name, NAME_RULE)?,
+        description: sanitize_optional("description", request.description.as_deref(), DESCRIPTION_RULE)?,
+        color: request.color.as_deref().map(|c| sanitize_color("color", c)).transpose()?,
+        confidence_threshold: request.confidence_threshold,
+    })
+}
+
+/// Sanitize a profile update request; absent fields stay untouched
+pub fn sanitize_update_profile(request: UpdateSpeakerProfileRequest) -> Result<UpdateSpeakerProfileRequest, MetadataError> {
+    Ok(UpdateSpeakerProfileRequest {
+        name: request.name.as_deref().map(|n| sanitize_text("name", n, NAME_RULE)).transpose()?,
+        // An empty description clears it, so it is kept as Some("")
+        description: request.description.as_deref()
+            .map(|d| sanitize_text("description", d, DESCRIPTION_RULE))
+            .transpose()?,
+        color: request.color.as_deref().map(|c| sanitize_color("color", c)).transpose()?,
+        ..request
+    })
+}
+
+/// Clean a stored profile for display; returns whether anything changed
+pub fn normalize_profile_on_read(profile: &mut SpeakerProfile) -> bool {
+    let mut changed = false;
+    if let Some(name) = normalize_stored_text(&profile.name, NAME_RULE) {
+        profile.name = name;
+        changed = true;
+    }
+    if let Some(description) = profile.description.as_deref().and_then(|d| normalize_stored_text(d, DESCRIPTION_RULE)) {
+        profile.description = Some(description);
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Inputs that have caused trouble elsewhere
+    fn adversarial() -> Vec<String> {
+        vec![
+            "../../../etc/passwd".to_string(),
+            "..\\..\\windows\\system32".to_string(),
+            "/absolute/path".to_string(),
+            "name\u{0}with\u{0}nulls".to_string(),
+            "\u{202E}gnp.exe".to_string(),
+            "evil\u{2066}isolate\u{2069}".to_string(),
+            "line\nbreak\r\nand\ttab".to_string(),
+            "zero\u{200B}width\u{FEFF}".to_string(),
+            "CON".to_string(),
+            "nul.txt".to_string(),
+            "<script>alert('x')</script>".to_string(),
+            "'; DROP TABLE speakers; --".to_string(),
+            "e\u{301}cole".to_string(),
+            "   ".to_string(),
+            "x".repeat(1024 * 1024),
+        ]
+    }
+
+    fn assert_clean(value: &str, multiline: bool) {
+        assert!(value.chars().all(|c| !is_invisible_format(c)), "{:?}", value);
+        assert!(value.chars().all(|c| !c.is_control() || (multiline && matches!(c, '\n' | '\t'))), "{:?}", value);
+        assert_eq!(value, value.trim());
+    }
+
+    fn assert_safe_slug(slug: &str) {
+        assert!(!slug.is_empty() && slug.chars().count() <= MAX_SLUG_CHARS, "{:?}", slug);
+        assert!(slug.chars().all(|c| c.is_alphanumeric() || c == '-'), "{:?}", slug);
+        assert!(!slug.starts_with('-') && !slug.ends_with('-'));
+        assert!(!RESERVED_FILE_NAMES.contains(&slug));
+    }
+
+    #[test]
+    fn test_adversarial_inputs_through_every_boundary() {
+        for input in adversarial() {
+            // Profile create/update, session display names and highlight labels
+            let create = sanitize_create_profile(CreateSpeakerProfileRequest {
+                name: input.clone(),
+                description: Some(input.clone()),
+                color: None,
+                confidence_threshold: None,
+            });
+            match create {
+                Ok(request) => {
+                    assert_clean(&request.name, false);
+                    assert!(request.name.chars().count() <= NAME_RULE.max_chars);
+                    assert_clean(request.description.as_deref().unwrap_or_default(), true);
+                }
+                Err(e) => assert!(e.field == "name" || e.field == "description"),
+            }
+            let update = sanitize_update_profile(UpdateSpeakerProfileRequest {
+                name: Some(input.clone()),
+                description: None,
+                color: None,
+                confidence_threshold: None,
+                is_active: None,
+            });
+            if let Ok(request) = update {
+                assert_clean(request.name.as_deref().unwrap(), false);
+            }
+            if let Ok(label) = sanitize_text("label", &input, LABEL_RULE) {
+                assert_clean(&label, false);
+            }
+            assert_safe_slug(&slugify(&input));
+        }
+
+        // Clean errors where rejection is right
+        let huge = sanitize_text("label", &"x".repeat(1024 * 1024), LABEL_RULE).unwrap_err();
+        assert_eq!(huge.field, "label");
+        assert_eq!(sanitize_text("name", "   ", NAME_RULE).unwrap_err().message, "must not be empty");
+        assert_eq!(sanitize_text("name", "\u{202E}gnp.exe", NAME_RULE).unwrap(), "gnp.exe");
+        assert_eq!(sanitize_text("name", "name\u{0}with\u{0}nulls", NAME_RULE).unwrap(), "namewithnulls");
+    }
+
+    #[test]
+    fn test_nfc_and_lines() {
+        // Decomposed and precomposed forms store identically
+        assert_eq!(sanitize_text("name", "e\u{301}cole", NAME_RULE).unwrap(), "\u{e9}cole");
+        assert_eq!(sanitize_text("name", "line\nbreak", NAME_RULE).unwrap(), "line break");
+        assert_eq!(sanitize_text("description", "a\r\nb\tc", DESCRIPTION_RULE).unwrap(), "a\nb\tc");
+        assert_eq!(sanitize_optional("description", Some("  "), DESCRIPTION_RULE).unwrap(), None);
+        assert_eq!(sanitize_color("color", " #1A2B3C ").unwrap(), "#1a2b3c");
+        assert!(sanitize_color("color", "red; background:url(x)").is_err());
+    }
+
+    #[test]
+    fn test_identifiers_and_slugs() {
+        assert!(validate_identifier("sessionId", "session-2024_01.a").is_ok());
+        for bad in ["", "../secrets", "a/b", "a\\b", ".hidden", "a..b", "x\u{0}"] {
+            assert!(validate_identifier("sessionId", bad).is_err(), "{:?}", bad);
+        }
+
+        assert_eq!(slugify("Q3 Planning: Budget & Hiring!"), "q3-planning-budget-hiring");
+        assert_eq!(slugify("../../etc/passwd"), "etc-passwd");
+        assert_eq!(slugify("CON"), "con-file");
+        assert_eq!(slugify("***"), "untitled");
+        assert_eq!(slugify("大阪 オフィス"), "大阪-オフィス");
+
+        let taken = ["standup", "standup-2"];
+        assert_eq!(unique_slug("standup", |s| taken.contains(&s)), "standup-3");
+        assert_eq!(unique_slug("retro", |s| taken.contains(&s)), "retro");
+    }
+
+    #[test]
+    fn test_stored_values_are_normalized_leniently() {
+        let long = "y".repeat(150);
+        assert_eq!(normalize_stored_text(&long, NAME_RULE).unwrap().chars().count(), 100);
+        assert_eq!(normalize_stored_text("Alice", NAME_RULE), None);
+        assert_eq!(normalize_stored_text(" Bob\u{202E} ", NAME_RULE).as_deref(), Some("Bob"));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_sanitized_text_is_clean(input in any::<String>()) {
+            if let Ok(value) = sanitize_text("label", &input, LABEL_RULE) {
+                assert_clean(&value, false);
+                prop_assert!(value.chars().count() <= LABEL_RULE.max_chars);
+                // Sanitizing is idempotent
+                prop_assert_eq!(sanitize_text("label", &value, LABEL_RULE).unwrap(), value.clone());
+            }
+            assert_safe_slug(&slugify(&input));
+        }
+    }
+}
@@ -68,6 +68,24 @@ pub struct VoiceEmbedding {
     pub created_at: DateTime<Utc>,
 }
 
+/// Reference audio clip of a speaker, to listen to who a profile is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerSample {
+    /// Unique identifier for the sample
+    pub id: Uuid,
+    /// Speaker profile this sample belongs to
+    pub speaker_id: Uuid,
+    /// 16-bit mono WAV file, base64-encoded in JSON
+    #[serde(with = "base64_bytes")]
+    pub wav: Vec<u8>,
+    /// Sample rate of the clip (Hz)
+    pub sample_rate: u32,
+    /// Duration of the clip (seconds)
+    pub duration_seconds: f32,
+    /// When this sample was added
+    pub created_at: DateTime<Utc>,
+}
+
 /// Request for creating a new speaker profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateSpeakerProfileRequest {
@@ -207,6 +225,49 @@ impl VoiceEmbedding {
     }
 }
 
+impl SpeakerSample {
+    /// Encode mono samples as a WAV clip of `speaker_id`
+    pub fn from_samples(speaker_id: Uuid, samples: &[f32], sample_rate: u32) -> Result<Self, hound::Error> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut wav, spec)?;
+        for sample in samples {
+            writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+        }
+        writer.finalize()?;
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            speaker_id,
+            wav: wav.into_inner(),
+            sample_rate,
+            duration_seconds: samples.len() as f32 / sample_rate as f32,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Binary fields as base64 strings, so exports stay plain JSON
+mod base64_bytes {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,4 +316,20 @@ mod tests {
         assert_eq!(profile.identification_count, initial_count + 1);
         assert!(profile.updated_at > initial_time);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_speaker_sample_round_trips_as_base64_wav() {
+        let samples: Vec<f32> = (0..8000).map(|i| (i as f32 / 20.0).sin() * 0.5).collect();
+        let sample = SpeakerSample::from_samples(Uuid::new_v4(), &samples, 16000).unwrap();
+        assert!((sample.duration_seconds - 0.5).abs() < 1e-6);
+
+        let json = serde_json::to_value(&sample).unwrap();
+        assert!(json["wav"].as_str().unwrap().starts_with("UklGR")); // "RIFF"
+        let restored: SpeakerSample = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.wav, sample.wav);
+
+        let reader = hound::WavReader::new(std::io::Cursor::new(restored.wav)).unwrap();
+        assert_eq!((reader.spec().channels, reader.spec().sample_rate), (1, 16000));
+        assert_eq!(reader.len(), 8000);
+    }
+}
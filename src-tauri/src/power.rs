@@ -0,0 +1,417 @@
+//! Power assertions during active sessions
+//!
+//! Long recordings on battery can be throttled by App Nap or interrupted when
+//! the machine goes to sleep. While a session is active we hold an assertion
+//! that prevents idle sleep (and optionally display sleep), and release it as
+//! soon as the session stops, pauses or is emergency-stopped.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use native::NativePowerPlatform;
+
+/// What an assertion prevents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssertionKind {
+    PreventIdleSleep,
+    PreventDisplaySleep,
+}
+
+/// OS power management, mockable in tests
+pub trait PowerPlatform: Send {
+    fn supported(&self) -> bool;
+    /// Take an assertion and return its handle
+    fn acquire(&mut self, kind: AssertionKind, reason: &str) -> Result<u64, String>;
+    fn release(&mut self, handle: u64);
+}
+
+/// Current assertion state, exposed in diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerAssertionState {
+    pub supported: bool,
+    pub active: bool,
+    #[serde(rename = "preventsDisplaySleep")]
+    pub prevents_display_sleep: bool,
+    pub sessions: Vec<String>,
+    /// Last time the OS denied an assertion
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+}
+
+/// Assertions held per session
+pub struct PowerAssertions {
+    platform: Box<dyn PowerPlatform>,
+    held: HashMap<String, Vec<(AssertionKind, u64)>>,
+    last_error: Option<String>,
+}
+
+impl PowerAssertions {
+    pub fn new(platform: Box<dyn PowerPlatform>) -> Self {
+        Self {
+            platform,
+            held: HashMap::new(),
+            last_error: None,
+        }
+    }
+
+    /// Hold assertions for `session_id`. Acquiring twice is a no-op; on failure
+    /// nothing is left held and the OS error is returned for the user warning.
+    pub fn acquire(&mut self, session_id: &str, prevent_display_sleep: bool) -> Result<(), String> {
+        if self.held.contains_key(session_id) || !self.platform.supported() {
+            return Ok(());
+        }
+
+        let mut kinds = vec![AssertionKind::PreventIdleSleep];
+        if prevent_display_sleep {
+            kinds.push(AssertionKind::PreventDisplaySleep);
+        }
+
+        let mut handles = Vec::with_capacity(kinds.len());
+        for kind in kinds {
+            match self.platform.acquire(kind, "KagiNote is recording a meeting") {
+                Ok(handle) => handles.push((kind, handle)),
+                Err(e) => {
+                    for (_, handle) in handles {
+                        self.platform.release(handle);
+                    }
+                    tracing::warn!("⚡ Power assertion denied for session {}: {}", session_id, e);
+                    self.last_error = Some(e.clone());
+                    return Err(e);
+                }
+            }
+        }
+
+        tracing::info!("⚡ Holding power assertion for session {}", session_id);
+        self.held.insert(session_id.to_string(), handles);
+        Ok(())
+    }
+
+    /// Release the assertions of `session_id`; returns whether any were held
+    pub fn release(&mut self, session_id: &str) -> bool {
+        let Some(handles) = self.held.remove(session_id) else {
+            return false;
+        };
+        for (_, handle) in handles {
+            self.platform.release(handle);
+        }
+        tracing::info!("⚡ Released power assertion for session {}", session_id);
+        true
+    }
+
+    /// Release everything (emergency stop, app exit); returns the number of sessions
+    pub fn release_all(&mut self) -> usize {
+        let sessions: Vec<String> = self.held.keys().cloned().collect();
+        for session_id in &sessions {
+            self.release(session_id);
+        }
+        sessions.len()
+    }
+
+    /// Move held assertions to a continuation session after rollover
+    pub fn transfer(&mut self, from: &str, to: &str) {
+        if let Some(handles) = self.held.remove(from) {
+            self.held.insert(to.to_string(), handles);
+        }
+    }
+
+    pub fn state(&self) -> PowerAssertionState {
+        let mut sessions: Vec<String> = self.held.keys().cloned().collect();
+        sessions.sort();
+        PowerAssertionState {
+            supported: self.platform.supported(),
+            active: !self.held.is_empty(),
+            prevents_display_sleep: self.held.values()
+                .flatten()
+                .any(|(kind, _)| *kind == AssertionKind::PreventDisplaySleep),
+            sessions,
+            last_error: self.last_error.clone(),
+        }
+    }
+}
+
+impl Drop for PowerAssertions {
+    fn drop(&mut self) {
+        self.release_all();
+    }
+}
+
+/// Power management of the running operating system
+pub fn native_platform() -> Box<dyn PowerPlatform> {
+    Box::new(NativePowerPlatform::default())
+}
+
+#[cfg(target_os = "macos")]
+mod native {
+    use super::{AssertionKind, PowerPlatform};
+    use std::ffi::{c_char, c_void, CString};
+
+    type CFStringRef = *const c_void;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(alloc: *const c_void, c_str: *const c_char, encoding: u32) -> CFStringRef;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            assertion_id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(assertion_id: u32) -> i32;
+    }
+
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const K_IOPM_ASSERTION_LEVEL_ON: u32 = 255;
+
+    fn cf_string(value: &str) -> Option<CFStringRef> {
+        let c_value = CString::new(value).ok()?;
+        let cf = unsafe { CFStringCreateWithCString(std::ptr::null(), c_value.as_ptr(), K_CF_STRING_ENCODING_UTF8) };
+        (!cf.is_null()).then_some(cf)
+    }
+
+    /// IOKit power assertions
+    #[derive(Default)]
+    pub struct NativePowerPlatform;
+
+    impl PowerPlatform for NativePowerPlatform {
+        fn supported(&self) -> bool {
+            true
+        }
+
+        fn acquire(&mut self, kind: AssertionKind, reason: &str) -> Result<u64, String> {
+            let assertion_type = match kind {
+                AssertionKind::PreventIdleSleep => "PreventUserIdleSystemSleep",
+                AssertionKind::PreventDisplaySleep => "PreventUserIdleDisplaySleep",
+            };
+            let cf_type = cf_string(assertion_type).ok_or("Failed to create assertion type")?;
+            let Some(cf_name) = cf_string(reason) else {
+                unsafe { CFRelease(cf_type) };
+                return Err("Failed to create assertion name".to_string());
+            };
+
+            let mut assertion_id = 0u32;
+            let result = unsafe {
+                IOPMAssertionCreateWithName(cf_type, K_IOPM_ASSERTION_LEVEL_ON, cf_name, &mut assertion_id)
+            };
+            unsafe {
+                CFRelease(cf_type);
+                CFRelease(cf_name);
+            }
+
+            if result == 0 {
+                Ok(assertion_id as u64)
+            } else {
+                Err(format!("IOPMAssertionCreateWithName failed with status {:#x}", result))
+            }
+        }
+
+        fn release(&mut self, handle: u64) {
+            unsafe {
+                IOPMAssertionRelease(handle as u32);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod native {
+    use super::{AssertionKind, PowerPlatform};
+    use std::collections::HashMap;
+    use std::sync::mpsc;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn SetThreadExecutionState(flags: u32) -> u32;
+    }
+
+    const ES_CONTINUOUS: u32 = 0x8000_0000;
+    const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+    const ES_DISPLAY_REQUIRED: u32 = 0x0000_0002;
+
+    /// SetThreadExecutionState is per thread, so the state is owned by a
+    /// dedicated thread that lives as long as the platform
+    pub struct NativePowerPlatform {
+        requests: mpsc::Sender<(u32, mpsc::Sender<bool>)>,
+        held: HashMap<u64, AssertionKind>,
+        next_handle: u64,
+    }
+
+    impl Default for NativePowerPlatform {
+        fn default() -> Self {
+            let (requests, receiver) = mpsc::channel::<(u32, mpsc::Sender<bool>)>();
+            std::thread::spawn(move || {
+                while let Ok((flags, reply)) = receiver.recv() {
+                    let ok = unsafe { SetThreadExecutionState(flags) } != 0;
+                    let _ = reply.send(ok);
+                }
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            });
+            Self { requests, held: HashMap::new(), next_handle: 1 }
+        }
+    }
+
+    impl NativePowerPlatform {
+        fn apply(&self) -> bool {
+            let mut flags = ES_CONTINUOUS;
+            for kind in self.held.values() {
+                flags |= match kind {
+                    AssertionKind::PreventIdleSleep => ES_SYSTEM_REQUIRED,
+                    AssertionKind::PreventDisplaySleep => ES_DISPLAY_REQUIRED,
+                };
+            }
+            let (reply, result) = mpsc::channel();
+            self.requests.send((flags, reply)).is_ok() && result.recv().unwrap_or(false)
+        }
+    }
+
+    impl PowerPlatform for NativePowerPlatform {
+        fn supported(&self) -> bool {
+            true
+        }
+
+        fn acquire(&mut self, kind: AssertionKind, _reason: &str) -> Result<u64, String> {
+            let handle = self.next_handle;
+            self.next_handle += 1;
+            self.held.insert(handle, kind);
+            if self.apply() {
+                Ok(handle)
+            } else {
+                self.held.remove(&handle);
+                self.apply();
+                Err("SetThreadExecutionState was refused".to_string())
+            }
+        }
+
+        fn release(&mut self, handle: u64) {
+            if self.held.remove(&handle).is_some() {
+                self.apply();
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod native {
+    use super::{AssertionKind, PowerPlatform};
+
+    /// No power assertion support on this platform
+    #[derive(Default)]
+    pub struct NativePowerPlatform;
+
+    impl PowerPlatform for NativePowerPlatform {
+        fn supported(&self) -> bool {
+            false
+        }
+
+        fn acquire(&mut self, _kind: AssertionKind, _reason: &str) -> Result<u64, String> {
+            Err("Power assertions are not supported on this platform".to_string())
+        }
+
+        fn release(&mut self, _handle: u64) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records outstanding handles; can be told to deny display sleep
+    #[derive(Clone, Default)]
+    struct MockPlatform {
+        outstanding: Arc<Mutex<Vec<u64>>>,
+        next: Arc<Mutex<u64>>,
+        deny_display: bool,
+    }
+
+    impl PowerPlatform for MockPlatform {
+        fn supported(&self) -> bool {
+            true
+        }
+
+        fn acquire(&mut self, kind: AssertionKind, _reason: &str) -> Result<u64, String> {
+            if self.deny_display && kind == AssertionKind::PreventDisplaySleep {
+                return Err("denied by policy".to_string());
+            }
+            let mut next = self.next.lock().unwrap();
+            *next += 1;
+            self.outstanding.lock().unwrap().push(*next);
+            Ok(*next)
+        }
+
+        fn release(&mut self, handle: u64) {
+            let mut outstanding = self.outstanding.lock().unwrap();
+            let position = outstanding.iter().position(|h| *h == handle).expect("released an unknown handle");
+            outstanding.remove(position);
+        }
+    }
+
+    #[test]
+    fn test_start_pause_resume_stop_pairs_acquire_and_release() {
+        let platform = MockPlatform::default();
+        let outstanding = platform.outstanding.clone();
+        let mut assertions = PowerAssertions::new(Box::new(platform));
+
+        // start
+        assertions.acquire("session", true).unwrap();
+        assert_eq!(outstanding.lock().unwrap().len(), 2);
+        assert!(assertions.state().prevents_display_sleep);
+        // a second acquire for the same session is a no-op
+        assertions.acquire("session", true).unwrap();
+        assert_eq!(outstanding.lock().unwrap().len(), 2);
+
+        // pause, resume, stop
+        assert!(assertions.release("session"));
+        assert!(outstanding.lock().unwrap().is_empty());
+        assertions.acquire("session", false).unwrap();
+        assert!(assertions.release("session"));
+        assert!(!assertions.release("session"));
+
+        assert!(outstanding.lock().unwrap().is_empty());
+        assert!(!assertions.state().active);
+    }
+
+    #[test]
+    fn test_emergency_stop_releases_every_session() {
+        let platform = MockPlatform::default();
+        let outstanding = platform.outstanding.clone();
+        let mut assertions = PowerAssertions::new(Box::new(platform));
+        assertions.acquire("a", false).unwrap();
+        assertions.acquire("b", true).unwrap();
+
+        assert_eq!(assertions.release_all(), 2);
+        assert!(outstanding.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_denied_assertion_is_reported_and_nothing_leaks() {
+        let platform = MockPlatform { deny_display: true, ..Default::default() };
+        let outstanding = platform.outstanding.clone();
+        let mut assertions = PowerAssertions::new(Box::new(platform));
+
+        let error = assertions.acquire("session", true).unwrap_err();
+        assert!(error.contains("denied"));
+        assert!(outstanding.lock().unwrap().is_empty());
+
+        let state = assertions.state();
+        assert!(!state.active);
+        assert_eq!(state.last_error.as_deref(), Some("denied by policy"));
+    }
+
+    #[test]
+    fn test_rollover_transfers_assertion() {
+        let platform = MockPlatform::default();
+        let outstanding = platform.outstanding.clone();
+        let mut assertions = PowerAssertions::new(Box::new(platform));
+        assertions.acquire("part-1", false).unwrap();
+
+        assertions.transfer("part-1", "part-2");
+        assert_eq!(assertions.state().sessions, vec!["part-2".to_string()]);
+        assert!(assertions.release("part-2"));
+        assert!(outstanding.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+//! Diarization ground truth files
+//!
+//! The JSON format of the diarization validation framework: speaker segments
+//! of a recording with its duration and free-form metadata. Finished sessions
+//! can be exported in this format, corrected by hand and read back as an
+//! evaluation corpus.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use super::analyzer::QualityAnalysisError;
+
+/// Speaker of segments that have none
+pub const UNKNOWN_SPEAKER: &str = "unknown";
+
+/// Ground truth segment for validation comparison
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroundTruthSegment {
+    /// True speaker identifier
+    pub speaker_id: String,
+    /// Start time in seconds
+    pub start_time: f32,
+    /// End time in seconds
+    pub end_time: f32,
+    /// Optional text content for reference
+    pub text: Option<String>,
+    /// Audio file path if available
+    pub audio_file: Option<String>,
+    /// Quality score for this ground truth segment (0.0-1.0)
+    pub quality: f32,
+}
+
+/// Complete ground truth data for a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroundTruthData {
+    /// Recording identifier
+    pub recording_id: String,
+    /// All ground truth segments
+    pub segments: Vec<GroundTruthSegment>,
+    /// Total number of speakers
+    pub total_speakers: usize,
+    /// Recording duration in seconds
+    pub duration: f32,
+    /// Sample rate
+    pub sample_rate: u32,
+    /// Metadata about the recording
+    pub metadata: HashMap<String, String>,
+}
+
+/// A schema problem, located by segment index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroundTruthIssue {
+    /// Index into `segments`
+    pub segment: usize,
+    /// Offending field, e.g. `start_time`
+    pub field: String,
+    pub message: String,
+}
+
+impl GroundTruthData {
+    /// Ground truth from stored session segments (`speaker`, `startTime`,
+    /// `endTime`, `text`, `confidence`). The decoder confidence becomes the
+    /// segment quality until the transcript is corrected.
+    pub fn from_session_segments(
+        recording_id: &str,
+        segments: &[serde_json::Value],
+        duration: f32,
+        sample_rate: u32,
+        metadata: HashMap<String, String>,
+    ) -> Self {
+        let segments: Vec<GroundTruthSegment> = segments.iter()
+            .map(|segment| GroundTruthSegment {
+                speaker_id: segment["speaker"].as_str().unwrap_or(UNKNOWN_SPEAKER).to_string(),
+                start_time: segment["startTime"].as_f64().unwrap_or(0.0) as f32,
+                end_time: segment["endTime"].as_f64().unwrap_or(0.0) as f32,
+                text: segment["text"].as_str().map(|text| text.trim().to_string()),
+                audio_file: None,
+                quality: segment["confidence"].as_f64().map(|c| c.clamp(0.0, 1.0) as f32).unwrap_or(1.0),
+            })
+            .collect();
+        let total_speakers = segments.iter().map(|s| s.speaker_id.as_str()).collect::<BTreeSet<_>>().len();
+        let duration = segments.iter().map(|s| s.end_time).fold(duration.max(0.0), f32::max);
+        Self {
+            recording_id: recording_id.to_string(),
+            segments,
+            total_speakers,
+            duration,
+            sample_rate,
+            metadata,
+        }
+    }
+
+    /// Schema problems: non-finite or negative times, segments ending before
+    /// they start, and overlapping segments of the same speaker
+    pub fn validate(&self) -> Vec<GroundTruthIssue> {
+        let mut issues = Vec::new();
+        let mut issue = |segment: usize, field: &str, message: String| {
+            issues.push(GroundTruthIssue { segment, field: field.to_string(), message });
+        };
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            for (field, value) in [("start_time", segment.start_time), ("end_time", segment.end_time)] {
+                if !value.is_finite() {
+                    issue(index, field, format!("{} is not a number", field));
+                } else if value < 0.0 {
+                    issue(index, field, format!("{} is negative ({})", field, value));
+                }
+            }
+            if segment.end_time < segment.start_time {
+                issue(index, "end_time", format!("Ends at {} before it starts at {}", segment.end_time, segment.start_time));
+            }
+            if segment.speaker_id.trim().is_empty() {
+                issue(index, "speaker_id", "Speaker is empty".to_string());
+            }
+        }
+
+        // Same-speaker overlaps, reported on the later segment
+        let mut by_start: Vec<usize> = (0..self.segments.len())
+            .filter(|&index| {
+                let segment = &self.segments[index];
+                segment.start_time.is_finite() && segment.end_time >= segment.start_time
+            })
+            .collect();
+        by_start.sort_by(|&a, &b| self.segments[a].start_time.total_cmp(&self.segments[b].start_time).then(a.cmp(&b)));
+        let mut latest_end: HashMap<&str, (usize, f32)> = HashMap::new();
+        for index in by_start {
+            let segment = &self.segments[index];
+            if let Some(&(previous, end)) = latest_end.get(segment.speaker_id.as_str()) {
+                if segment.start_time < end {
+                    issue(index, "start_time", format!(
+                        "Overlaps segment {} of the same speaker {}", previous, segment.speaker_id
+                    ));
+                }
+            }
+            let entry = latest_end.entry(segment.speaker_id.as_str()).or_insert((index, segment.end_time));
+            if segment.end_time >= entry.1 {
+                *entry = (index, segment.end_time);
+            }
+        }
+
+        issues.sort_by_key(|issue| issue.segment);
+        issues
+    }
+}
+
+/// Load ground truth data from JSON file
+pub fn load_ground_truth(path: &str) -> Result<GroundTruthData, QualityAnalysisError> {
+    let content = fs::read_to_string(path)
+        .map_err(|_| QualityAnalysisError::GroundTruthNotFound { path: path.to_string() })?;
+    serde_json::from_str(&content)
+        .map_err(|e| QualityAnalysisError::InvalidGroundTruth { message: e.to_string() })
+}
+
+/// Save ground truth data to JSON file
+pub fn save_ground_truth(data: &GroundTruthData, path: &str) -> Result<(), QualityAnalysisError> {
+    let content = serde_json::to_string_pretty(data)
+        .map_err(|e| QualityAnalysisError::IoError { message: format!("JSON serialization failed: {}", e) })?;
+    if let Some(parent) = Path::new(path).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| QualityAnalysisError::IoError { message: format!("Failed to create directory: {}", e) })?;
+    }
+    fs::write(path, content)
+        .map_err(|e| QualityAnalysisError::IoError { message: format!("Failed to write file: {}", e) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(speaker: &str, start: f32, end: f32) -> GroundTruthSegment {
+        GroundTruthSegment {
+            speaker_id: speaker.to_string(),
+            start_time: start,
+            end_time: end,
+            text: Some("hello".to_string()),
+            audio_file: None,
+            quality: 1.0,
+        }
+    }
+
+    fn data(segments: Vec<GroundTruthSegment>) -> GroundTruthData {
+        GroundTruthData {
+            recording_id: "rec".to_string(),
+            segments,
+            total_speakers: 2,
+            duration: 10.0,
+            sample_rate: 16000,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_from_session_segments_round_trips() {
+        let segments = vec![
+            serde_json::json!({ "speaker": "alice", "startTime": 0.0, "endTime": 2.5, "text": " hello there ", "confidence": 0.8 }),
+            serde_json::json!({ "speaker": "bob", "startTime": 2.5, "endTime": 4.0, "text": "hi" }),
+            serde_json::json!({ "startTime": 4.0, "endTime": 5.0, "text": "who" }),
+        ];
+        let metadata = HashMap::from([("model_tier".to_string(), "standard".to_string())]);
+        let ground_truth = GroundTruthData::from_session_segments("session-1", &segments, 4.5, 16000, metadata);
+
+        assert_eq!(ground_truth.total_speakers, 3);
+        assert_eq!(ground_truth.duration, 5.0);
+        assert_eq!(ground_truth.segments[0].text.as_deref(), Some("hello there"));
+        assert_eq!((ground_truth.segments[0].quality, ground_truth.segments[1].quality), (0.8, 1.0));
+        assert_eq!(ground_truth.segments[2].speaker_id, UNKNOWN_SPEAKER);
+        assert!(ground_truth.validate().is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corpus").join("session-1.json");
+        save_ground_truth(&ground_truth, path.to_str().unwrap()).unwrap();
+        assert_eq!(load_ground_truth(path.to_str().unwrap()).unwrap(), ground_truth);
+    }
+
+    #[test]
+    fn test_validate_reports_segment_locations() {
+        let ground_truth = data(vec![
+            segment("alice", 0.0, 3.0),
+            segment("bob", 1.0, 2.0),     // other speaker may overlap
+            segment("alice", 2.0, 4.0),   // overlaps segment 0
+            segment("bob", -1.0, 0.5),    // negative start
+            segment("bob", 6.0, 5.0),     // ends before start
+        ]);
+        let issues = ground_truth.validate();
+        let located: Vec<(usize, &str)> = issues.iter().map(|i| (i.segment, i.field.as_str())).collect();
+        assert_eq!(located, vec![(2, "start_time"), (3, "start_time"), (4, "end_time")]);
+        assert!(issues[0].message.contains("segment 0"));
+    }
+
+    #[test]
+    fn test_load_rejects_malformed_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.json");
+        fs::write(&path, r#"{ "recording_id": "x", "segments": [] }"#).unwrap();
+        let error = load_ground_truth(path.to_str().unwrap()).unwrap_err();
+        assert_eq!(error.kind(), "invalid_ground_truth");
+        assert_eq!(load_ground_truth("/nonexistent/gt.json").unwrap_err().kind(), "ground_truth_not_found");
+    }
+}
@@ -0,0 +1,76 @@
+//! Transcription quality analysis
+//!
+//! WER, CER and speaker-attributed WER against ground truth, quality levels,
+//! recommendations and reports, plus loaders for LibriSpeech and reference
+//! transcripts and the diarization ground-truth format. Diarization accuracy (DER) is an input: callers compute it
+//! with their diarization validator and pass it as [`DiarizationMetrics`].
+//!
+//! ```
+//! use kaginote_lib::quality::{
+//!     DiarizationMetrics, GroundTruthSpeakerSegment, GroundTruthWord, QualityAnalysisConfig,
+//!     QualityLevel, SystemPerformanceMetrics, TranscriptionGroundTruth, TranscriptionQualityAnalyzer,
+//!     TranscriptionResult, TranscriptionWord,
+//! };
+//!
+//! let transcription = TranscriptionResult {
+//!     text: "hello world".to_string(),
+//!     words: vec![
+//!         TranscriptionWord { word: "hello".into(), start_time: 0.0, end_time: 0.5, confidence: 0.9, speaker_id: Some("alice".into()) },
+//!         TranscriptionWord { word: "world".into(), start_time: 0.5, end_time: 1.0, confidence: 0.9, speaker_id: Some("alice".into()) },
+//!     ],
+//!     confidence: 0.9,
+//!     language: "en".to_string(),
+//!     processing_time_ms: 200,
+//!     real_time_factor: 0.2,
+//! };
+//! let ground_truth = TranscriptionGroundTruth {
+//!     text: "hello world".to_string(),
+//!     words: vec![
+//!         GroundTruthWord { word: "hello".into(), start_time: 0.0, end_time: 0.5, speaker_id: "alice".into(), is_correct_pronunciation: true },
+//!         GroundTruthWord { word: "world".into(), start_time: 0.5, end_time: 1.0, speaker_id: "alice".into(), is_correct_pronunciation: true },
+//!     ],
+//!     speaker_segments: vec![GroundTruthSpeakerSegment {
+//!         speaker_id: "alice".into(),
+//!         start_time: 0.0,
+//!         end_time: 1.0,
+//!         text: "hello world".into(),
+//!     }],
+//!     total_duration: 1.0,
+//!     num_speakers: 1,
+//! };
+//! let performance = SystemPerformanceMetrics {
+//!     real_time_factor: 0.2,
+//!     memory_usage_mb: 0.0,
+//!     cpu_utilization: 0.0,
+//!     latency_ms: 200,
+//!     throughput: 5.0,
+//!     model_load_time_ms: 0,
+//! };
+//!
+//! let mut analyzer = TranscriptionQualityAnalyzer::with_config(QualityAnalysisConfig {
+//!     generate_html_report: false,
+//!     generate_json_report: false,
+//!     ..QualityAnalysisConfig::default()
+//! });
+//! // A perfect diarization: DER of 0
+//! let result = analyzer
+//!     .analyze_quality(transcription, ground_truth, DiarizationMetrics::default(), performance)
+//!     .unwrap();
+//! assert_eq!(result.wer_result.wer_percentage, 0.0);
+//! assert_eq!(result.quality_level, QualityLevel::Excellent);
+//! ```
+
+pub mod analyzer;
+pub mod ground_truth;
+pub mod reference;
+pub mod transcribe;
+
+pub use analyzer::{
+    speaker_segments, DerMetrics, DiarizationMetrics, GroundTruthSpeakerSegment, GroundTruthWord,
+    QualityAnalysisConfig, QualityAnalysisError, QualityAnalysisResult, QualityLevel, QualityRecommendations,
+    SAWERResult, SpeakerConsistencyMetrics, SystemPerformanceMetrics, TranscriptionGroundTruth,
+    TranscriptionQualityAnalyzer, TranscriptionResult, TranscriptionWord, WERResult,
+};
+pub use ground_truth::{load_ground_truth, save_ground_truth, GroundTruthData, GroundTruthIssue, GroundTruthSegment};
+pub use reference::{librispeech_utterance_id, load_librispeech_ground_truth, load_reference_transcript, load_wav};
+pub use transcribe::transcribe_for_analysis;
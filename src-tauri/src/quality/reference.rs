@@ -0,0 +1,316 @@
+//! Ground truth for quality analysis
+//!
+//! Loads LibriSpeech transcripts (with optional word alignments) and the
+//! reference transcripts accepted by the `quality_analysis` tool.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::audio::types::{AudioData, AudioSource};
+
+use super::analyzer::{GroundTruthSpeakerSegment, GroundTruthWord, QualityAnalysisError, TranscriptionGroundTruth};
+
+/// Average LibriSpeech reading pace, used when neither alignments nor audio give a duration
+const LIBRISPEECH_SECONDS_PER_WORD: f32 = 0.37;
+
+/// Utterance id of a LibriSpeech audio file, e.g. `1089-134686-0000` for `1089-134686-0000.flac.wav`
+pub fn librispeech_utterance_id(audio_file_path: &str) -> Option<String> {
+    Path::new(audio_file_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split('.').next())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
+fn io_error(path: &Path, error: impl std::fmt::Display) -> QualityAnalysisError {
+    QualityAnalysisError::IoError { message: format!("{}: {}", path.display(), error) }
+}
+
+/// Read a 16 kHz mono WAV; other formats are converted to 16 kHz mono
+pub fn load_wav(path: &Path) -> Result<AudioData, QualityAnalysisError> {
+
+    let mut reader = hound::WavReader::open(path).map_err(|e| io_error(path, e))?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1u32 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>().map(|s| s.map(|s| s as f32 / scale)).collect::<Result<_, _>>()
+        }
+    }.map_err(|e| io_error(path, e))?;
+
+    let duration_seconds = samples.len() as f32 / (spec.sample_rate * spec.channels as u32) as f32;
+    let audio = AudioData {
+        samples,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels as u8,
+        timestamp: SystemTime::now(),
+        source_channel: AudioSource::Microphone,
+        duration_seconds,
+    };
+    crate::audio::ResamplerUtils::to_whisper_format(&audio)
+        .map_err(|e| QualityAnalysisError::InvalidTranscription { message: e.to_string() })
+}
+
+/// Duration of the utterance's audio next to the transcript, if it is there
+fn librispeech_audio_duration(dir: &Path, utterance_id: &str) -> Option<f32> {
+    ["wav", "flac.wav"].iter()
+        .map(|extension| dir.join(format!("{}.{}", utterance_id, extension)))
+        .find(|path| path.exists())
+        .and_then(|path| hound::WavReader::open(path).ok())
+        .map(|reader| reader.duration() as f32 / reader.spec().sample_rate as f32)
+}
+
+/// Word with its start and end time (seconds)
+type TimedWord = (String, f32, f32);
+
+/// Timed words and the end of the last interval from a `<chapter>.alignment.txt` line:
+/// `<utterance-id> ",WORD,WORD," "0.490,0.890,1.100"`, where empty words are silences
+fn parse_alignment_line(line: &str) -> Option<(Vec<TimedWord>, f32)> {
+    let mut fields = line.split_whitespace();
+    let _utterance_id = fields.next()?;
+    let words: Vec<&str> = fields.next()?.trim_matches('"').split(',').collect();
+    let ends: Vec<f32> = fields.next()?.trim_matches('"').split(',')
+        .map(|end| end.parse::<f32>().ok())
+        .collect::<Option<_>>()?;
+    if words.len() != ends.len() {
+        return None;
+    }
+
+    let mut start = 0.0;
+    let mut timed = Vec::new();
+    for (word, end) in words.iter().zip(ends) {
+        if !word.is_empty() {
+            timed.push((word.to_string(), start, end));
+        }
+        start = end;
+    }
+    Some((timed, start))
+}
+
+/// Load ground truth from LibriSpeech format.
+///
+/// `librispeech_transcript_path` is a chapter's `.trans.txt` file with one
+/// `<utterance-id> <TRANSCRIPT>` line per utterance. Word timings come from the
+/// chapter's `.alignment.txt` when present, otherwise words are spread evenly
+/// over the utterance's audio (or an average reading pace without audio).
+/// LibriSpeech utterances have a single reader, named after the speaker id.
+pub fn load_librispeech_ground_truth(
+    librispeech_transcript_path: &str,
+    utterance_id: &str,
+) -> Result<TranscriptionGroundTruth, QualityAnalysisError> {
+    let transcript_path = Path::new(librispeech_transcript_path);
+    if !transcript_path.exists() {
+        return Err(QualityAnalysisError::GroundTruthNotFound { path: librispeech_transcript_path.to_string() });
+    }
+    let transcripts = fs::read_to_string(transcript_path).map_err(|e| io_error(transcript_path, e))?;
+    let text = transcripts.lines()
+        .filter_map(|line| line.trim().split_once(' '))
+        .find(|(id, _)| *id == utterance_id)
+        .map(|(_, text)| text.trim().to_string())
+        .ok_or_else(|| QualityAnalysisError::GroundTruthNotFound {
+            path: format!("{}#{}", librispeech_transcript_path, utterance_id),
+        })?;
+
+    let speaker_id = format!("speaker_{}", utterance_id.split('-').next().unwrap_or(utterance_id));
+    let dir = transcript_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let alignment_path = transcript_path.to_str()
+        .and_then(|path| path.strip_suffix(".trans.txt"))
+        .map(|chapter| Path::new(&format!("{}.alignment.txt", chapter)).to_path_buf());
+    let aligned = match alignment_path.filter(|path| path.exists()) {
+        Some(path) => fs::read_to_string(&path).map_err(|e| io_error(&path, e))?
+            .lines()
+            .find(|line| line.split_whitespace().next() == Some(utterance_id))
+            .and_then(parse_alignment_line),
+        None => None,
+    };
+
+    let (timed_words, total_duration) = match aligned {
+        Some((timed, end)) => {
+            let duration = librispeech_audio_duration(dir, utterance_id).unwrap_or(end).max(end);
+            (timed, duration)
+        }
+        None => {
+            let words: Vec<&str> = text.split_whitespace().collect();
+            let duration = librispeech_audio_duration(dir, utterance_id)
+                .unwrap_or(words.len() as f32 * LIBRISPEECH_SECONDS_PER_WORD);
+            let step = duration / words.len().max(1) as f32;
+            let timed = words.iter().enumerate()
+                .map(|(i, word)| (word.to_string(), i as f32 * step, (i + 1) as f32 * step))
+                .collect();
+            (timed, duration)
+        }
+    };
+
+    let words: Vec<GroundTruthWord> = timed_words.into_iter()
+        .map(|(word, start_time, end_time)| GroundTruthWord {
+            word,
+            start_time,
+            end_time,
+            speaker_id: speaker_id.clone(),
+            is_correct_pronunciation: true,
+        })
+        .collect();
+    let speaker_segments = vec![GroundTruthSpeakerSegment {
+        speaker_id,
+        start_time: words.first().map(|w| w.start_time).unwrap_or(0.0),
+        end_time: words.last().map(|w| w.end_time).unwrap_or(total_duration),
+        text: text.clone(),
+    }];
+
+    Ok(TranscriptionGroundTruth {
+        text,
+        words,
+        speaker_segments,
+        total_duration,
+        num_speakers: 1,
+    })
+}
+
+/// Ground truth from a reference transcript for a recording of `audio_duration` seconds.
+///
+/// Accepts a serialized [`TranscriptionGroundTruth`], a diarization ground-truth
+/// file (`segments` with `speaker_id`, `start_time`, `end_time` and `text`), or
+/// plain text read by one speaker. Words without timings are spread evenly over
+/// their segment, or over the whole recording for plain text.
+pub fn load_reference_transcript(
+    reference_path: &str,
+    audio_duration: f32,
+) -> Result<TranscriptionGroundTruth, QualityAnalysisError> {
+    #[derive(Deserialize)]
+    struct SegmentFile {
+        segments: Vec<GroundTruthSpeakerSegment>,
+    }
+
+    let path = Path::new(reference_path);
+    if !path.exists() {
+        return Err(QualityAnalysisError::GroundTruthNotFound { path: reference_path.to_string() });
+    }
+    let content = fs::read_to_string(path).map_err(|e| io_error(path, e))?;
+
+    let is_json = path.extension().and_then(|e| e.to_str()).map(|e| e.eq_ignore_ascii_case("json")).unwrap_or(false);
+    let segments = if is_json {
+        if let Ok(ground_truth) = serde_json::from_str::<TranscriptionGroundTruth>(&content) {
+            return Ok(ground_truth);
+        }
+        serde_json::from_str::<SegmentFile>(&content)
+            .map_err(|e| QualityAnalysisError::InvalidTranscription {
+                message: format!("{} is neither ground truth nor a segment file: {}", reference_path, e),
+            })?
+            .segments
+    } else {
+        vec![GroundTruthSpeakerSegment {
+            speaker_id: "speaker_1".to_string(),
+            start_time: 0.0,
+            end_time: audio_duration,
+            text: content.split_whitespace().collect::<Vec<_>>().join(" "),
+        }]
+    };
+
+    let words: Vec<GroundTruthWord> = segments.iter()
+        .flat_map(|segment| {
+            let words: Vec<&str> = segment.text.split_whitespace().collect();
+            let step = (segment.end_time - segment.start_time).max(0.0) / words.len().max(1) as f32;
+            words.into_iter().enumerate().map(move |(i, word)| GroundTruthWord {
+                word: word.to_string(),
+                start_time: segment.start_time + i as f32 * step,
+                end_time: segment.start_time + (i + 1) as f32 * step,
+                speaker_id: segment.speaker_id.clone(),
+                is_correct_pronunciation: true,
+            })
+        })
+        .collect();
+    let text = segments.iter().map(|s| s.text.trim()).filter(|t| !t.is_empty()).collect::<Vec<_>>().join(" ");
+    let num_speakers = segments.iter().map(|s| s.speaker_id.as_str()).collect::<std::collections::HashSet<_>>().len();
+    let total_duration = segments.iter().map(|s| s.end_time).fold(audio_duration, f32::max);
+
+    Ok(TranscriptionGroundTruth {
+        text,
+        words,
+        speaker_segments: segments,
+        total_duration,
+        num_speakers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRISPEECH_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/diarization_realtime/test_audio/LibriSpeech/test-clean");
+
+    #[test]
+    fn test_load_librispeech_ground_truth_spreads_words_over_audio() {
+        let transcript = format!("{}/6930/75918/6930-75918.trans.txt", LIBRISPEECH_DIR);
+        let ground_truth = load_librispeech_ground_truth(&transcript, "6930-75918-0000").unwrap();
+
+        assert_eq!(ground_truth.text, "CONCORD RETURNED TO ITS PLACE AMIDST THE TENTS");
+        assert_eq!(ground_truth.words.len(), 8);
+        assert_eq!(ground_truth.num_speakers, 1);
+        assert!(ground_truth.words.iter().all(|w| w.speaker_id == "speaker_6930"));
+        // No alignment file: words cover the 3.5 s recording evenly
+        assert!((ground_truth.total_duration - 3.505).abs() < 0.01);
+        assert_eq!(ground_truth.words[0].start_time, 0.0);
+        assert!((ground_truth.words[7].end_time - ground_truth.total_duration).abs() < 1e-3);
+
+        assert!(matches!(
+            load_librispeech_ground_truth(&transcript, "6930-75918-9999"),
+            Err(QualityAnalysisError::GroundTruthNotFound { .. })
+        ));
+        assert_eq!(librispeech_utterance_id("/data/6930-75918-0000.flac.wav").as_deref(), Some("6930-75918-0000"));
+    }
+
+    #[test]
+    fn test_load_librispeech_ground_truth_uses_alignments() {
+        let dir = tempfile::tempdir().unwrap();
+        let transcript = dir.path().join("2830-3980.trans.txt");
+        fs::write(&transcript, "2830-3980-0000 IN EVERY WAY\n2830-3980-0001 OTHER\n").unwrap();
+        fs::write(dir.path().join("2830-3980.alignment.txt"),
+                  "2830-3980-0000 \",IN,EVERY,,WAY,\" \"0.400,0.550,0.900,1.200,1.600,1.900\"\n").unwrap();
+
+        let ground_truth = load_librispeech_ground_truth(transcript.to_str().unwrap(), "2830-3980-0000").unwrap();
+        let timings: Vec<(&str, f32, f32)> = ground_truth.words.iter()
+            .map(|w| (w.word.as_str(), w.start_time, w.end_time))
+            .collect();
+        assert_eq!(timings, vec![("IN", 0.4, 0.55), ("EVERY", 0.55, 0.9), ("WAY", 1.2, 1.6)]);
+        assert_eq!(ground_truth.total_duration, 1.9);
+        assert_eq!(ground_truth.speaker_segments[0].speaker_id, "speaker_2830");
+    }
+
+    #[test]
+    fn test_load_reference_transcript_formats() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let plain = dir.path().join("reference.txt");
+        fs::write(&plain, "hello  world\nhow are you\n").unwrap();
+        let ground_truth = load_reference_transcript(plain.to_str().unwrap(), 5.0).unwrap();
+        assert_eq!(ground_truth.text, "hello world how are you");
+        assert_eq!((ground_truth.words.len(), ground_truth.num_speakers), (5, 1));
+        assert_eq!(ground_truth.words[4].end_time, 5.0);
+
+        let segments = dir.path().join("reference.json");
+        fs::write(&segments, serde_json::json!({
+            "segments": [
+                { "speaker_id": "alice", "start_time": 0.0, "end_time": 2.0, "text": "hello world" },
+                { "speaker_id": "bob", "start_time": 2.5, "end_time": 4.0, "text": "how are you" },
+            ]
+        }).to_string()).unwrap();
+        let ground_truth = load_reference_transcript(segments.to_str().unwrap(), 4.0).unwrap();
+        assert_eq!(ground_truth.num_speakers, 2);
+        assert_eq!(ground_truth.words[2].speaker_id, "bob");
+        assert_eq!(ground_truth.words[2].start_time, 2.5);
+
+        // A serialized ground truth is read back as is
+        let serialized = dir.path().join("ground_truth.json");
+        fs::write(&serialized, serde_json::to_string(&ground_truth).unwrap()).unwrap();
+        let reloaded = load_reference_transcript(serialized.to_str().unwrap(), 0.0).unwrap();
+        assert_eq!((reloaded.text, reloaded.total_duration), (ground_truth.text, ground_truth.total_duration));
+
+        let missing = load_reference_transcript("/nonexistent/reference.txt", 1.0).unwrap_err();
+        assert_eq!(missing.kind(), "ground_truth_not_found");
+    }
+}
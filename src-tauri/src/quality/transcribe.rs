@@ -0,0 +1,65 @@
+//! Transcription of a recording for quality analysis
+
+use crate::asr::types::{Device, ModelTier, Task, TranscriptionContext};
+use crate::asr::whisper::{WhisperConfig, WhisperEngine};
+use crate::audio::types::AudioData;
+
+use super::analyzer::{QualityAnalysisError, SystemPerformanceMetrics, TranscriptionResult, TranscriptionWord};
+
+/// Transcribe `audio` with the production WhisperEngine at `model_tier`.
+///
+/// Without diarization every word is attributed to `speaker_id`.
+pub async fn transcribe_for_analysis(
+    audio: &AudioData,
+    model_tier: ModelTier,
+    speaker_id: Option<String>,
+) -> Result<(TranscriptionResult, SystemPerformanceMetrics), QualityAnalysisError> {
+    let audio_seconds = audio.samples.len() as f32 / (audio.sample_rate.max(1) * audio.channels.max(1) as u32) as f32;
+
+    let load_start = std::time::Instant::now();
+    let engine = WhisperEngine::new(WhisperConfig {
+        model_tier,
+        device: Device::Auto,
+        language: Some("en".parse().map_err(|_| QualityAnalysisError::CalculationError {
+            message: "English is not a supported language".to_string(),
+        })?),
+        task: Task::Transcribe,
+        enable_word_timestamps: true,
+        temperature: 0.0,
+        ..Default::default()
+    }).await.map_err(|e| QualityAnalysisError::CalculationError { message: format!("Whisper failed to load: {}", e) })?;
+    let model_load_time_ms = load_start.elapsed().as_millis() as u64;
+
+    let transcribe_start = std::time::Instant::now();
+    let asr = engine.transcribe(audio, &TranscriptionContext::default()).await
+        .map_err(|e| QualityAnalysisError::CalculationError { message: format!("Transcription failed: {}", e) })?;
+    let processing_time_ms = transcribe_start.elapsed().as_millis() as u64;
+    let real_time_factor = processing_time_ms as f32 / 1000.0 / audio_seconds.max(f32::EPSILON);
+
+    let transcription = TranscriptionResult {
+        text: asr.text.trim().to_string(),
+        words: asr.words.iter()
+            .map(|word| TranscriptionWord {
+                word: word.word.trim().to_string(),
+                start_time: word.start_time,
+                end_time: word.end_time,
+                confidence: word.confidence,
+                speaker_id: speaker_id.clone(),
+            })
+            .collect(),
+        confidence: asr.confidence,
+        language: asr.language,
+        processing_time_ms,
+        real_time_factor,
+    };
+    let performance = SystemPerformanceMetrics {
+        real_time_factor,
+        // Not sampled for a single file
+        memory_usage_mb: 0.0,
+        cpu_utilization: 0.0,
+        latency_ms: processing_time_ms,
+        throughput: 1.0 / real_time_factor.max(f32::EPSILON),
+        model_load_time_ms,
+    };
+    Ok((transcription, performance))
+}
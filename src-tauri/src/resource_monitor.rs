@@ -0,0 +1,254 @@
+//! Process memory and CPU sampling
+//!
+//! A background thread refreshes this process in sysinfo at a fixed interval
+//! and keeps the readings, so validation runs and the live system-status
+//! event report measured resource use. CPU is sysinfo's per-process figure:
+//! percent of one core, so it exceeds 100 on several busy cores. The first
+//! reading after start has no CPU figure to compare with and reads 0.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Default time between samples
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+/// Samples kept for the running summary (10 minutes at the default interval)
+const MAX_SAMPLES: usize = 6000;
+
+const BYTES_PER_MB: f32 = 1024.0 * 1024.0;
+
+/// One reading of the process
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessSample {
+    /// Time since sampling started
+    pub elapsed_ms: u64,
+    /// Resident set size
+    pub memory_mb: f32,
+    pub cpu_percent: f32,
+}
+
+/// Peak and average of the samples taken so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSummary {
+    pub samples: usize,
+    pub current_memory_mb: f32,
+    pub peak_memory_mb: f32,
+    pub average_memory_mb: f32,
+    pub current_cpu_percent: f32,
+    pub peak_cpu_percent: f32,
+    pub average_cpu_percent: f32,
+    /// Memory the OS reports as available to new allocations
+    pub available_system_memory_mb: f32,
+    pub total_system_memory_mb: f32,
+}
+
+impl ResourceSummary {
+    fn from_samples(samples: &[ProcessSample], available_system_memory_mb: f32, total_system_memory_mb: f32) -> Self {
+        let Some(current) = samples.last() else {
+            return Self { available_system_memory_mb, total_system_memory_mb, ..Self::default() };
+        };
+        let count = samples.len() as f32;
+        Self {
+            samples: samples.len(),
+            current_memory_mb: current.memory_mb,
+            peak_memory_mb: samples.iter().map(|s| s.memory_mb).fold(0.0, f32::max),
+            average_memory_mb: samples.iter().map(|s| s.memory_mb).sum::<f32>() / count,
+            current_cpu_percent: current.cpu_percent,
+            peak_cpu_percent: samples.iter().map(|s| s.cpu_percent).fold(0.0, f32::max),
+            average_cpu_percent: samples.iter().map(|s| s.cpu_percent).sum::<f32>() / count,
+            available_system_memory_mb,
+            total_system_memory_mb,
+        }
+    }
+}
+
+/// Resident memory of this process in bytes, 0 if it cannot be read
+pub fn current_memory_bytes() -> u64 {
+    let Ok(pid) = sysinfo::get_current_pid() else {
+        return 0;
+    };
+    let mut system = sysinfo::System::new();
+    if !system.refresh_process(pid) {
+        return 0;
+    }
+    system.process(pid).map(|process| process.memory()).unwrap_or(0)
+}
+
+struct Probe {
+    system: sysinfo::System,
+    pid: Option<sysinfo::Pid>,
+    started: Instant,
+}
+
+impl Probe {
+    fn new() -> Self {
+        let mut probe = Self {
+            system: sysinfo::System::new(),
+            pid: sysinfo::get_current_pid().ok(),
+            started: Instant::now(),
+        };
+        // Baseline for the CPU figure of the first sample
+        if let Some(pid) = probe.pid {
+            probe.system.refresh_process(pid);
+        }
+        probe
+    }
+
+    fn sample(&mut self) -> Option<ProcessSample> {
+        let pid = self.pid?;
+        if !self.system.refresh_process(pid) {
+            return None;
+        }
+        let process = self.system.process(pid)?;
+        Some(ProcessSample {
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            memory_mb: process.memory() as f32 / BYTES_PER_MB,
+            cpu_percent: process.cpu_usage(),
+        })
+    }
+
+    /// Available and total system memory (MB)
+    fn system_memory(&mut self) -> (f32, f32) {
+        self.system.refresh_memory();
+        (self.system.available_memory() as f32 / BYTES_PER_MB, self.system.total_memory() as f32 / BYTES_PER_MB)
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    samples: Vec<ProcessSample>,
+    system_memory_mb: (f32, f32),
+}
+
+/// Samples this process on a background thread until stopped or dropped
+pub struct ResourceSampler {
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ResourceSampler {
+    /// Start sampling every `interval`
+    pub fn start(interval: Duration) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let shared = shared.clone();
+            let stop = stop.clone();
+            std::thread::Builder::new()
+                .name("resource-sampler".to_string())
+                .spawn(move || {
+                    let mut probe = Probe::new();
+                    loop {
+                        std::thread::sleep(interval);
+                        let sample = probe.sample();
+                        let system_memory = probe.system_memory();
+                        if let Ok(mut shared) = shared.lock() {
+                            if let Some(sample) = sample {
+                                if shared.samples.len() == MAX_SAMPLES {
+                                    shared.samples.remove(0);
+                                }
+                                shared.samples.push(sample);
+                            }
+                            shared.system_memory_mb = system_memory;
+                        }
+                        // Checked after sampling so even a short run gets a reading
+                        if stop.load(Ordering::Relaxed) {
+                            break;
+                        }
+                    }
+                })
+                .map_err(|e| tracing::warn!("Failed to start resource sampler: {}", e))
+                .ok()
+        };
+        Self { shared, stop, thread }
+    }
+
+    /// Readings so far, oldest first
+    pub fn samples(&self) -> Vec<ProcessSample> {
+        self.shared.lock().map(|shared| shared.samples.clone()).unwrap_or_default()
+    }
+
+    pub fn summary(&self) -> ResourceSummary {
+        self.shared
+            .lock()
+            .map(|shared| {
+                let (available, total) = shared.system_memory_mb;
+                ResourceSummary::from_samples(&shared.samples, available, total)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Stop sampling and summarize every reading taken
+    pub fn finish(mut self) -> ResourceSummary {
+        self.join();
+        self.summary()
+    }
+
+    fn join(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ResourceSampler {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn busy_for(duration: Duration) -> u64 {
+        let started = Instant::now();
+        let mut acc = 0u64;
+        while started.elapsed() < duration {
+            for i in 0..10_000u64 {
+                acc = acc.wrapping_mul(31).wrapping_add(i);
+            }
+        }
+        acc
+    }
+
+    #[test]
+    fn test_sampler_measures_busy_loop() {
+        let sampler = ResourceSampler::start(Duration::from_millis(50));
+        std::hint::black_box(busy_for(Duration::from_millis(600)));
+        let samples = sampler.samples();
+        let summary = sampler.finish();
+
+        assert!(samples.len() >= 5, "only {} samples", samples.len());
+        assert!(samples.windows(2).all(|pair| pair[1].elapsed_ms > pair[0].elapsed_ms));
+        assert!(samples.iter().all(|s| s.memory_mb > 1.0 && s.memory_mb < 1024.0 * 1024.0));
+        // A spinning thread keeps at least part of a core busy
+        assert!(summary.peak_cpu_percent > 10.0, "peak CPU {}%", summary.peak_cpu_percent);
+        assert!(summary.peak_memory_mb >= summary.average_memory_mb);
+        assert!(summary.average_cpu_percent <= summary.peak_cpu_percent);
+        assert!(summary.total_system_memory_mb >= summary.available_system_memory_mb);
+        assert!(summary.samples >= samples.len());
+    }
+
+    #[test]
+    fn test_summary_of_samples() {
+        let samples = [
+            ProcessSample { elapsed_ms: 100, memory_mb: 100.0, cpu_percent: 10.0 },
+            ProcessSample { elapsed_ms: 200, memory_mb: 300.0, cpu_percent: 50.0 },
+            ProcessSample { elapsed_ms: 300, memory_mb: 200.0, cpu_percent: 30.0 },
+        ];
+        let summary = ResourceSummary::from_samples(&samples, 4000.0, 8000.0);
+        assert_eq!((summary.peak_memory_mb, summary.average_memory_mb, summary.current_memory_mb), (300.0, 200.0, 200.0));
+        assert_eq!((summary.peak_cpu_percent, summary.average_cpu_percent), (50.0, 30.0));
+
+        let empty = ResourceSummary::from_samples(&[], 4000.0, 8000.0);
+        assert_eq!((empty.samples, empty.peak_memory_mb, empty.total_system_memory_mb), (0, 0.0, 8000.0));
+        assert!(current_memory_bytes() > 0);
+    }
+}
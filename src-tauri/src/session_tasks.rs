@@ -0,0 +1,177 @@
+//! Background work of active sessions
+//!
+//! Starting a session spawns its model initialization (which may download the
+//! model) and then its transcription loop. Stopping the session has to reach
+//! that work: a late initialization would otherwise register its engine, emit
+//! model-ready and start a loop for a session that no longer exists. Every
+//! session gets a [`SessionCancellation`] that its loop watches, and the
+//! handle of its initialization task, which is aborted outright.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Cancellation signal of a session's background work
+#[derive(Debug, Clone)]
+pub struct SessionCancellation(Arc<watch::Sender<bool>>);
+
+impl SessionCancellation {
+    pub fn new() -> Self {
+        Self(Arc::new(watch::channel(false).0))
+    }
+
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once the session is cancelled
+    pub async fn cancelled(&self) {
+        let mut receiver = self.0.subscribe();
+        // The sender lives as long as `self`, so this only returns on cancellation
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Default for SessionCancellation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+struct SessionWork {
+    cancellation: SessionCancellation,
+    init: Option<JoinHandle<()>>,
+}
+
+/// Background work by session id
+#[derive(Debug, Default)]
+pub struct SessionTasks {
+    sessions: HashMap<String, SessionWork>,
+}
+
+impl SessionTasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancellation of `session_id`, registering the session on first use
+    pub fn cancellation(&mut self, session_id: &str) -> SessionCancellation {
+        self.sessions.entry(session_id.to_string())
+            .or_insert_with(|| SessionWork { cancellation: SessionCancellation::new(), init: None })
+            .cancellation
+            .clone()
+    }
+
+    /// Spawn the initialization task of a session, aborted if the session is
+    /// cancelled before it completes
+    pub fn spawn_init<F>(&mut self, session_id: &str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.cancellation(session_id);
+        if let Some(work) = self.sessions.get_mut(session_id) {
+            work.init = Some(tokio::spawn(task));
+        }
+    }
+
+    /// Keep a rolled-over session's work reachable under its continuation id
+    pub fn rename(&mut self, from: &str, to: &str) {
+        if let Some(work) = self.sessions.remove(from) {
+            self.sessions.insert(to.to_string(), work);
+        }
+    }
+
+    /// Cancel a session's background work. Returns whether it had any.
+    pub fn cancel(&mut self, session_id: &str) -> bool {
+        match self.sessions.remove(session_id) {
+            Some(work) => {
+                Self::stop(work);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel a session but let its initialization wind down on its own, as a
+    /// cancelled download does after removing its partial file
+    pub fn detach(&mut self, session_id: &str) {
+        if let Some(work) = self.sessions.remove(session_id) {
+            work.cancellation.cancel();
+        }
+    }
+
+    /// Cancel the work of every session, returning how many there were
+    pub fn cancel_all(&mut self) -> usize {
+        let count = self.sessions.len();
+        self.sessions.drain().for_each(|(_, work)| Self::stop(work));
+        count
+    }
+
+    fn stop(work: SessionWork) {
+        work.cancellation.cancel();
+        if let Some(init) = work.init {
+            init.abort();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_cancel_all_aborts_slow_init() {
+        let mut tasks = SessionTasks::new();
+        let (events, mut received) = mpsc::unbounded_channel();
+        for session_id in ["a", "b"] {
+            let events = events.clone();
+            // Stands in for a model load that finishes after the emergency stop
+            tasks.spawn_init(session_id, async move {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                let _ = events.send(format!("model-ready {}", session_id));
+            });
+        }
+        drop(events);
+
+        assert_eq!(tasks.cancel_all(), 2);
+        assert!(tasks.is_empty());
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(received.recv().await, None, "no model-ready after the emergency stop");
+    }
+
+    #[tokio::test]
+    async fn test_loop_observes_cancellation_across_rollover() {
+        let mut tasks = SessionTasks::new();
+        let cancellation = tasks.cancellation("part-1");
+        let looping = tokio::spawn({
+            let cancellation = cancellation.clone();
+            async move { cancellation.cancelled().await }
+        });
+
+        tasks.rename("part-1", "part-2");
+        assert!(!tasks.cancel("part-1"));
+        assert!(!cancellation.is_cancelled());
+        assert!(tasks.cancel("part-2"));
+        tokio::time::timeout(Duration::from_secs(1), looping).await
+            .expect("the loop should see the cancellation promptly")
+            .unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+//! Cleanup when the app exits
+//!
+//! Quitting (or closing the last window) while recording must not kill the
+//! capture stream and WAV writers mid-file. The first exit request is held
+//! back while active sessions are stopped the way stop_transcription stops
+//! them, for at most a grace period; requests arriving during that cleanup
+//! keep waiting, and the exit that follows it goes through.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+/// Default `exitGracePeriodSeconds`
+pub const DEFAULT_EXIT_GRACE_SECONDS: u32 = 10;
+/// Longest accepted `exitGracePeriodSeconds`
+pub const MAX_EXIT_GRACE_SECONDS: u32 = 60;
+
+const IDLE: u8 = 0;
+const CLEANING: u8 = 1;
+const DONE: u8 = 2;
+
+/// What to do with an exit request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitDecision {
+    /// Hold the exit and start the cleanup
+    Cleanup,
+    /// Hold the exit; the cleanup is still running
+    Wait,
+    /// Let the app exit
+    Exit,
+}
+
+/// Progress of the exit cleanup; it runs once however often exit is requested
+#[derive(Debug, Default)]
+pub struct ExitCleanup(AtomicU8);
+
+impl ExitCleanup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn request_exit(&self) -> ExitDecision {
+        match self.0.compare_exchange(IDLE, CLEANING, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => ExitDecision::Cleanup,
+            Err(CLEANING) => ExitDecision::Wait,
+            Err(_) => ExitDecision::Exit,
+        }
+    }
+
+    pub fn finish(&self) {
+        self.0.store(DONE, Ordering::SeqCst);
+    }
+}
+
+/// Grace period for stopping sessions: the longest any of them asks for
+pub fn grace_period(session_grace_seconds: impl IntoIterator<Item = u32>) -> Duration {
+    let seconds = session_grace_seconds.into_iter().max().unwrap_or(DEFAULT_EXIT_GRACE_SECONDS);
+    Duration::from_secs(seconds.min(MAX_EXIT_GRACE_SECONDS) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleanup_runs_once() {
+        let cleanup = ExitCleanup::new();
+        assert_eq!(cleanup.request_exit(), ExitDecision::Cleanup);
+        // The user quitting again while sessions are saved
+        assert_eq!(cleanup.request_exit(), ExitDecision::Wait);
+        cleanup.finish();
+        assert_eq!(cleanup.request_exit(), ExitDecision::Exit);
+        assert_eq!(cleanup.request_exit(), ExitDecision::Exit);
+    }
+
+    #[test]
+    fn test_grace_period_takes_longest_session() {
+        assert_eq!(grace_period([5, 20]), Duration::from_secs(20));
+        assert_eq!(grace_period([]), Duration::from_secs(DEFAULT_EXIT_GRACE_SECONDS as u64));
+        assert_eq!(grace_period([600]), Duration::from_secs(MAX_EXIT_GRACE_SECONDS as u64));
+    }
+}
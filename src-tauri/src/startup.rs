@@ -0,0 +1,138 @@
+//! Startup readiness tracking
+//!
+//! Heavy application state is initialized in the background after the window
+//! appears. Commands use a [`ReadinessGate`] to wait briefly for a component and
+//! report a clear "initializing" error if the user races ahead of startup.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Initialization state of a lazily initialized component
+#[derive(Debug, Clone, PartialEq)]
+pub enum InitState {
+    Pending,
+    Initializing,
+    Ready,
+    Failed(String),
+}
+
+/// Shared readiness signal for one component
+#[derive(Clone)]
+pub struct ReadinessGate {
+    name: &'static str,
+    sender: Arc<watch::Sender<InitState>>,
+}
+
+impl ReadinessGate {
+    pub fn new(name: &'static str) -> Self {
+        let (sender, _receiver) = watch::channel(InitState::Pending);
+        Self {
+            name,
+            sender: Arc::new(sender),
+        }
+    }
+
+    pub fn state(&self) -> InitState {
+        self.sender.borrow().clone()
+    }
+
+    pub fn set(&self, state: InitState) {
+        self.sender.send_replace(state);
+    }
+
+    /// Wait up to `timeout` for an in-progress initialization to finish
+    pub async fn wait_ready(&self, timeout: Duration) -> Result<(), String> {
+        let mut receiver = self.sender.subscribe();
+        let _ = tokio::time::timeout(
+            timeout,
+            receiver.wait_for(|state| *state != InitState::Initializing),
+        ).await;
+
+        match self.state() {
+            InitState::Ready => Ok(()),
+            InitState::Pending => Err(format!("{} not initialized", self.name)),
+            InitState::Initializing => Err(format!(
+                "{} is still initializing. Please try again in a moment.",
+                self.name
+            )),
+            InitState::Failed(e) => Err(format!("{} failed to initialize: {}", self.name, e)),
+        }
+    }
+}
+
+/// Duration of a single startup phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupPhase {
+    pub name: String,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+}
+
+/// Timings of the startup phases, exposed for diagnostics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartupReport {
+    pub phases: Vec<StartupPhase>,
+}
+
+impl StartupReport {
+    pub fn record(&mut self, name: &str, elapsed: Duration) {
+        tracing::info!("⏱️ Startup phase '{}' took {}ms", name, elapsed.as_millis());
+        self.phases.push(StartupPhase {
+            name: name.to_string(),
+            duration_ms: elapsed.as_millis() as u64,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pending_component_fails_fast() {
+        let gate = ReadinessGate::new("Speaker storage");
+        let result = gate.wait_ready(Duration::from_secs(5)).await;
+        assert_eq!(result.unwrap_err(), "Speaker storage not initialized");
+    }
+
+    #[tokio::test]
+    async fn test_slow_initialization_reports_initializing_then_ready() {
+        let gate = ReadinessGate::new("Speaker storage");
+        gate.set(InitState::Initializing);
+
+        // Simulated slow storage: becomes ready after a delay
+        let background = gate.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            background.set(InitState::Ready);
+        });
+
+        // A command racing ahead of startup gets a clear error
+        let early = gate.wait_ready(Duration::from_millis(20)).await;
+        assert!(early.unwrap_err().contains("still initializing"));
+
+        // A command with enough patience sees eventual readiness
+        assert!(gate.wait_ready(Duration::from_secs(2)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_failed_initialization_is_reported() {
+        let gate = ReadinessGate::new("Speaker storage");
+        gate.set(InitState::Failed("disk full".to_string()));
+
+        let error = gate.wait_ready(Duration::from_millis(10)).await.unwrap_err();
+        assert!(error.contains("disk full"));
+    }
+
+    #[test]
+    fn test_startup_report_records_phases() {
+        let mut report = StartupReport::default();
+        report.record("app_state", Duration::from_millis(3));
+        report.record("speaker_storage", Duration::from_millis(120));
+
+        assert_eq!(report.phases.len(), 2);
+        assert_eq!(report.phases[1].duration_ms, 120);
+    }
+}
@@ -0,0 +1,186 @@
+//! Periodic transcript autosave for crash recovery
+//!
+//! While a session runs, its segments and config are written every few
+//! seconds to `autosave_<session_id>.json` in the app data directory. The
+//! file is written aside and renamed over the previous one, so it is always
+//! either the old or the new snapshot. A session that ends cleanly removes its
+//! file; whatever is left at the next launch came from a session cut short
+//! and is offered for recovery.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Seconds between autosaves unless the session config says otherwise
+pub const DEFAULT_AUTOSAVE_INTERVAL_SECONDS: u32 = 30;
+/// Layout version of autosave files
+pub const AUTOSAVE_FORMAT_VERSION: u32 = 1;
+
+const AUTOSAVE_PREFIX: &str = "autosave_";
+const AUTOSAVE_EXTENSION: &str = "json";
+
+/// Snapshot of a running session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionAutosave {
+    pub format_version: u32,
+    pub session_id: String,
+    /// Session start, seconds since UNIX epoch
+    pub start_time: u64,
+    /// When this snapshot was taken, seconds since UNIX epoch
+    pub saved_at: u64,
+    pub continuation_of: Option<String>,
+    pub config: serde_json::Value,
+    pub segments: Vec<serde_json::Value>,
+}
+
+/// What the recovery prompt shows about an autosave
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverableSession {
+    pub session_id: String,
+    pub start_time: u64,
+    pub saved_at: u64,
+    pub continuation_of: Option<String>,
+    pub segment_count: usize,
+    /// End time of the last segment, in seconds of session audio
+    pub transcribed_seconds: f64,
+}
+
+impl From<&SessionAutosave> for RecoverableSession {
+    fn from(autosave: &SessionAutosave) -> Self {
+        Self {
+            session_id: autosave.session_id.clone(),
+            start_time: autosave.start_time,
+            saved_at: autosave.saved_at,
+            continuation_of: autosave.continuation_of.clone(),
+            segment_count: autosave.segments.len(),
+            transcribed_seconds: autosave.segments.iter()
+                .filter_map(|segment| segment.get("endTime").and_then(|end| end.as_f64()))
+                .fold(0.0, f64::max),
+        }
+    }
+}
+
+/// Directory autosave files are written to
+pub fn autosave_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("KagiNote"))
+}
+
+pub fn autosave_path(dir: &Path, session_id: &str) -> PathBuf {
+    dir.join(format!("{}{}.{}", AUTOSAVE_PREFIX, session_id, AUTOSAVE_EXTENSION))
+}
+
+/// Write `autosave` over the session's previous snapshot
+pub fn write_autosave(dir: &Path, autosave: &SessionAutosave) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create autosave directory {}", dir.display()))?;
+    let path = autosave_path(dir, &autosave.session_id);
+    let partial = path.with_extension("json.partial");
+    let bytes = serde_json::to_vec(autosave).context("Failed to encode autosave")?;
+    fs::write(&partial, bytes)
+        .with_context(|| format!("Failed to write autosave {}", partial.display()))?;
+    fs::rename(&partial, &path)
+        .with_context(|| format!("Failed to replace autosave {}", path.display()))?;
+    Ok(())
+}
+
+/// Read the autosave of `session_id`, if there is one
+pub fn read_autosave(dir: &Path, session_id: &str) -> Result<Option<SessionAutosave>> {
+    let path = autosave_path(dir, session_id);
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read autosave {}", path.display())),
+    };
+    let autosave = serde_json::from_slice(&bytes)
+        .with_context(|| format!("Autosave {} is unreadable", path.display()))?;
+    Ok(Some(autosave))
+}
+
+/// Delete the autosave of `session_id`; returns whether there was one
+pub fn remove_autosave(dir: &Path, session_id: &str) -> Result<bool> {
+    match fs::remove_file(autosave_path(dir, session_id)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e).context("Failed to delete autosave"),
+    }
+}
+
+/// Autosaves found in `dir`, most recently saved first. Unreadable files are
+/// skipped with a warning and left in place.
+pub fn scan_autosaves(dir: &Path) -> Result<Vec<RecoverableSession>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to list {}", dir.display())),
+    };
+
+    let mut sessions = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(session_id) = file_name.to_str()
+            .and_then(|name| name.strip_prefix(AUTOSAVE_PREFIX))
+            .and_then(|name| name.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        match read_autosave(dir, session_id) {
+            Ok(Some(autosave)) => sessions.push(RecoverableSession::from(&autosave)),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Skipping autosave of session {}: {:#}", session_id, e),
+        }
+    }
+    sessions.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn autosave(session_id: &str, saved_at: u64, segments: usize) -> SessionAutosave {
+        SessionAutosave {
+            format_version: AUTOSAVE_FORMAT_VERSION,
+            session_id: session_id.to_string(),
+            start_time: 1_760_000_000,
+            saved_at,
+            continuation_of: None,
+            config: serde_json::json!({ "qualityTier": "standard" }),
+            segments: (0..segments)
+                .map(|i| serde_json::json!({ "text": format!("segment {}", i), "startTime": i as f64 * 3.0, "endTime": i as f64 * 3.0 + 2.5 }))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_autosave_overwrites_and_scans() {
+        let dir = tempfile::tempdir().unwrap();
+        write_autosave(dir.path(), &autosave("crashed", 100, 2)).unwrap();
+        write_autosave(dir.path(), &autosave("crashed", 130, 4)).unwrap();
+        write_autosave(dir.path(), &autosave("older", 50, 1)).unwrap();
+        // Neither leftovers of an interrupted write nor other files are autosaves
+        fs::write(dir.path().join("autosave_torn.json.partial"), b"{\"sessionId\"").unwrap();
+        fs::write(dir.path().join("speakers.db"), b"").unwrap();
+        fs::write(autosave_path(dir.path(), "garbled"), b"not json").unwrap();
+
+        let sessions = scan_autosaves(dir.path()).unwrap();
+        assert_eq!(sessions.iter().map(|s| s.session_id.as_str()).collect::<Vec<_>>(), ["crashed", "older"]);
+        assert_eq!((sessions[0].segment_count, sessions[0].saved_at), (4, 130));
+        assert_eq!(sessions[0].transcribed_seconds, 11.5);
+        assert!(scan_autosaves(&dir.path().join("absent")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let saved = autosave("session-1", 100, 3);
+        write_autosave(dir.path(), &saved).unwrap();
+
+        assert_eq!(read_autosave(dir.path(), "session-1").unwrap(), Some(saved));
+        assert!(remove_autosave(dir.path(), "session-1").unwrap());
+        assert!(!remove_autosave(dir.path(), "session-1").unwrap());
+        assert_eq!(read_autosave(dir.path(), "session-1").unwrap(), None);
+    }
+}
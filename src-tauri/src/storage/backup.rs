@@ -0,0 +1,478 @@
+//! Backup sets of the local data
+//!
+//! A backup set is a timestamped directory holding copies of the speaker
+//! database, settings and saved sessions together with a manifest of SHA-256
+//! checksums. Backups are incremental: only new or changed files are copied,
+//! and files unchanged since the previous set are hard links to its copies
+//! (copied again where the backup drive has no hard links). Every set is
+//! therefore self-contained, so rotating old sets never breaks a restore.
+//! Files are copied byte for byte: data that is encrypted at rest stays
+//! encrypted in the backup.
+//!
+//! A restore writes into the live data directory, after copying every file it
+//! replaces into a safety directory.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const SET_PREFIX: &str = "kaginote-backup-";
+
+/// Where backups go and how many sets to keep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupPolicy {
+    pub directory: PathBuf,
+    #[serde(rename = "keepSets")]
+    pub keep_sets: usize,
+}
+
+/// Content to include in a backup set under a relative name
+#[derive(Debug, Clone)]
+pub enum BackupSource {
+    File { name: String, path: PathBuf },
+    Data { name: String, bytes: Vec<u8> },
+}
+
+impl BackupSource {
+    fn name(&self) -> &str {
+        match self {
+            BackupSource::File { name, .. } | BackupSource::Data { name, .. } => name,
+        }
+    }
+}
+
+/// A file recorded in the manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFileEntry {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// Unix seconds
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    pub files: Vec<BackupFileEntry>,
+}
+
+/// A completed backup set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSetInfo {
+    pub path: PathBuf,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "fileCount")]
+    pub file_count: usize,
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+    /// Files linked from the previous set instead of copied
+    #[serde(rename = "unchangedFiles", default)]
+    pub unchanged_files: usize,
+}
+
+/// Result of checking a set against its manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerification {
+    pub path: PathBuf,
+    pub valid: bool,
+    /// Files whose checksum or size no longer matches
+    pub corrupted: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+/// Last backup outcome, reported through events and diagnostics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupStatus {
+    #[serde(rename = "lastBackup")]
+    pub last_backup: Option<BackupSetInfo>,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+    /// Unix seconds of the last attempt
+    #[serde(rename = "lastAttemptAt")]
+    pub last_attempt_at: Option<i64>,
+}
+
+/// Result of restoring a set into the live data directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRestore {
+    pub verification: BackupVerification,
+    /// Files written into the data directory
+    pub restored: Vec<String>,
+    /// Copies of the live files the restore replaced
+    #[serde(rename = "safetyDir")]
+    pub safety_dir: PathBuf,
+}
+
+/// Write a new backup set into the policy directory and verify it
+pub fn create_backup_set(policy: &BackupPolicy, sources: &[BackupSource], created_at: i64) -> Result<BackupSetInfo> {
+    fs::create_dir_all(&policy.directory)
+        .with_context(|| format!("Failed to create backup directory {:?}", policy.directory))?;
+
+    let set_path = unique_set_path(&policy.directory, created_at);
+    // Build under a temporary name so an interrupted backup never looks complete
+    let partial_path = set_path.with_extension("partial");
+    if partial_path.exists() {
+        fs::remove_dir_all(&partial_path)?;
+    }
+    fs::create_dir_all(&partial_path)?;
+
+    // Unchanged files are linked from the newest intact set
+    let previous = list_backup_sets(&policy.directory)?.into_iter().rev()
+        .find_map(|set| read_manifest(&set).ok().map(|manifest| (set, manifest)));
+
+    let mut files = Vec::with_capacity(sources.len());
+    let mut unchanged_files = 0;
+    for source in sources {
+        let relative = safe_relative_path(source.name())?;
+        let destination = partial_path.join(&relative);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let (size, sha256) = match source {
+            BackupSource::File { path, .. } => checksum_file(path).with_context(|| format!("Failed to back up {:?}", path))?,
+            BackupSource::Data { bytes, .. } => checksum_bytes(bytes),
+        };
+        // A damaged previous copy is copied afresh rather than linked
+        let unchanged = previous.as_ref()
+            .filter(|(_, manifest)| manifest.files.iter().any(|f| f.name == source.name() && f.size == size && f.sha256 == sha256))
+            .map(|(set, _)| set.join(&relative))
+            .filter(|earlier| checksum_file(earlier).is_ok_and(|checksum| checksum == (size, sha256.clone())))
+            .is_some_and(|earlier| fs::hard_link(earlier, &destination).is_ok());
+        if unchanged {
+            unchanged_files += 1;
+        } else {
+            match source {
+                BackupSource::File { path, .. } => {
+                    fs::copy(path, &destination).with_context(|| format!("Failed to back up {:?}", path))?;
+                }
+                BackupSource::Data { bytes, .. } => fs::write(&destination, bytes)?,
+            }
+        }
+        files.push(BackupFileEntry { name: source.name().to_string(), size, sha256 });
+    }
+
+    let manifest = BackupManifest { created_at, files };
+    fs::write(partial_path.join(MANIFEST_FILE), serde_json::to_vec_pretty(&manifest)?)?;
+    fs::rename(&partial_path, &set_path).context("Failed to finalize backup set")?;
+
+    let verification = verify_backup_set(&set_path)?;
+    if !verification.valid {
+        bail!("Backup set {:?} failed verification", set_path);
+    }
+
+    tracing::info!("💾 Created backup set {:?} with {} files ({} unchanged)", set_path, manifest.files.len(), unchanged_files);
+    Ok(BackupSetInfo {
+        path: set_path,
+        created_at,
+        file_count: manifest.files.len(),
+        total_bytes: manifest.files.iter().map(|f| f.size).sum(),
+        unchanged_files,
+    })
+}
+
+/// Check every file of a set against the checksums in its manifest
+pub fn verify_backup_set(set_path: &Path) -> Result<BackupVerification> {
+    let manifest = read_manifest(set_path)?;
+    let mut corrupted = Vec::new();
+    let mut missing = Vec::new();
+
+    for entry in &manifest.files {
+        let path = set_path.join(safe_relative_path(&entry.name)?);
+        match checksum_file(&path) {
+            Ok((size, sha256)) if size == entry.size && sha256 == entry.sha256 => {}
+            Ok(_) => corrupted.push(entry.name.clone()),
+            Err(_) => missing.push(entry.name.clone()),
+        }
+    }
+
+    Ok(BackupVerification {
+        path: set_path.to_path_buf(),
+        valid: corrupted.is_empty() && missing.is_empty(),
+        corrupted,
+        missing,
+    })
+}
+
+/// Completed backup sets in the directory, oldest first
+pub fn list_backup_sets(directory: &Path) -> Result<Vec<PathBuf>> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let mut sets: Vec<PathBuf> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir() && path.join(MANIFEST_FILE).exists())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with(SET_PREFIX) && !n.ends_with(".partial"))
+                .unwrap_or(false)
+        })
+        .collect();
+    // Set names embed a sortable UTC timestamp
+    sets.sort();
+    Ok(sets)
+}
+
+/// Delete the oldest sets beyond `keep_sets`, returning the removed paths
+pub fn rotate_backup_sets(policy: &BackupPolicy) -> Result<Vec<PathBuf>> {
+    let sets = list_backup_sets(&policy.directory)?;
+    let excess = sets.len().saturating_sub(policy.keep_sets.max(1));
+    let mut removed = Vec::with_capacity(excess);
+    for set in sets.into_iter().take(excess) {
+        fs::remove_dir_all(&set).with_context(|| format!("Failed to remove old backup set {:?}", set))?;
+        removed.push(set);
+    }
+    Ok(removed)
+}
+
+/// Fail unless every file of the set matches its manifest
+pub fn ensure_intact(set_path: &Path) -> Result<BackupVerification> {
+    let verification = verify_backup_set(set_path)?;
+    if !verification.valid {
+        bail!(
+            "Backup set {:?} is damaged (corrupted: {:?}, missing: {:?})",
+            set_path, verification.corrupted, verification.missing
+        );
+    }
+    Ok(verification)
+}
+
+/// Restore a verified set into the live `data_dir`. Each live file about to be
+/// replaced is first copied into `safety_dir`, which must be new or empty.
+/// Files for which `skip` returns true are left to the caller, such as the
+/// open database, which is restored through its connection.
+pub fn restore_backup_set(
+    set_path: &Path,
+    data_dir: &Path,
+    safety_dir: &Path,
+    skip: impl Fn(&str) -> bool,
+) -> Result<BackupRestore> {
+    let verification = ensure_intact(set_path)?;
+    if safety_dir.exists() && fs::read_dir(safety_dir)?.next().is_some() {
+        bail!("Safety directory {:?} is not empty", safety_dir);
+    }
+
+    let manifest = read_manifest(set_path)?;
+    let entries: Vec<&BackupFileEntry> = manifest.files.iter().filter(|entry| !skip(&entry.name)).collect();
+    // Everything is saved before anything is overwritten
+    for entry in &entries {
+        let relative = safe_relative_path(&entry.name)?;
+        let live = data_dir.join(&relative);
+        if live.exists() {
+            let safety = safety_dir.join(&relative);
+            if let Some(parent) = safety.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&live, &safety).with_context(|| format!("Failed to save a copy of {:?}", live))?;
+        }
+    }
+
+    let mut restored = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let relative = safe_relative_path(&entry.name)?;
+        let destination = data_dir.join(&relative);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(set_path.join(&relative), &destination)
+            .with_context(|| format!("Failed to restore {}", entry.name))?;
+        restored.push(entry.name.clone());
+    }
+
+    tracing::info!("💾 Restored {} files of backup set {:?} into {:?}", restored.len(), set_path, data_dir);
+    Ok(BackupRestore { verification, restored, safety_dir: safety_dir.to_path_buf() })
+}
+
+fn read_manifest(set_path: &Path) -> Result<BackupManifest> {
+    let bytes = fs::read(set_path.join(MANIFEST_FILE))
+        .with_context(|| format!("Backup set {:?} has no manifest", set_path))?;
+    serde_json::from_slice(&bytes).context("Failed to parse backup manifest")
+}
+
+fn unique_set_path(directory: &Path, created_at: i64) -> PathBuf {
+    let stamp = chrono::DateTime::from_timestamp(created_at, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ");
+    let base = directory.join(format!("{}{}", SET_PREFIX, stamp));
+    let mut candidate = base.clone();
+    let mut suffix = 1;
+    while candidate.exists() {
+        candidate = directory.join(format!("{}{}-{}", SET_PREFIX, stamp, suffix));
+        suffix += 1;
+    }
+    candidate
+}
+
+/// Reject names that would escape the set directory
+fn safe_relative_path(name: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(name);
+    if name.is_empty() || path.components().any(|c| !matches!(c, Component::Normal(_))) {
+        bail!("Invalid backup file name: {}", name);
+    }
+    Ok(path)
+}
+
+fn hex_digest(hasher: Sha256) -> String {
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn checksum_bytes(bytes: &[u8]) -> (u64, String) {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    (bytes.len() as u64, hex_digest(hasher))
+}
+
+pub(crate) fn checksum_file(path: &Path) -> io::Result<(u64, String)> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+    Ok((size, hex_digest(hasher)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sources(data_dir: &Path) -> Vec<BackupSource> {
+        let db_path = data_dir.join("speakers.db");
+        fs::write(&db_path, b"sqlite bytes").unwrap();
+        vec![
+            BackupSource::File { name: "speakers.db".to_string(), path: db_path },
+            BackupSource::Data { name: "sessions/abc.json".to_string(), bytes: br#"{"sessionId":"abc"}"#.to_vec() },
+        ]
+    }
+
+    fn policy(dir: &TempDir, keep_sets: usize) -> BackupPolicy {
+        BackupPolicy { directory: dir.path().join("backups"), keep_sets }
+    }
+
+    #[test]
+    fn test_create_and_verify_backup_set() {
+        let dir = TempDir::new().unwrap();
+        let info = create_backup_set(&policy(&dir, 3), &sources(dir.path()), 1_700_000_000).unwrap();
+
+        assert_eq!(info.file_count, 2);
+        assert!(info.path.join("sessions/abc.json").exists());
+        assert!(verify_backup_set(&info.path).unwrap().valid);
+    }
+
+    #[test]
+    fn test_corrupted_set_is_detected_and_not_restored() {
+        let dir = TempDir::new().unwrap();
+        let info = create_backup_set(&policy(&dir, 3), &sources(dir.path()), 1_700_000_000).unwrap();
+        fs::write(info.path.join("speakers.db"), b"bit rot").unwrap();
+        fs::remove_file(info.path.join("sessions/abc.json")).unwrap();
+
+        let verification = verify_backup_set(&info.path).unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.corrupted, vec!["speakers.db".to_string()]);
+        assert_eq!(verification.missing, vec!["sessions/abc.json".to_string()]);
+
+        let data_dir = dir.path().join("live");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("speakers.db"), b"live bytes").unwrap();
+        let safety_dir = dir.path().join("safety");
+        assert!(restore_backup_set(&info.path, &data_dir, &safety_dir, |_| false).is_err());
+        assert_eq!(fs::read(data_dir.join("speakers.db")).unwrap(), b"live bytes");
+        assert!(!safety_dir.exists());
+    }
+
+    #[test]
+    fn test_restore_into_live_data_after_safety_copy() {
+        let dir = TempDir::new().unwrap();
+        let info = create_backup_set(&policy(&dir, 3), &sources(dir.path()), 1_700_000_000).unwrap();
+
+        // Live data changed since the backup
+        let data_dir = dir.path().join("live");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("speakers.db"), b"newer live bytes").unwrap();
+        fs::write(data_dir.join("settings.json"), b"untouched").unwrap();
+
+        let safety_dir = dir.path().join("safety");
+        let restore = restore_backup_set(&info.path, &data_dir, &safety_dir, |_| false).unwrap();
+        assert_eq!(restore.restored, vec!["speakers.db".to_string(), "sessions/abc.json".to_string()]);
+        assert_eq!(fs::read(data_dir.join("speakers.db")).unwrap(), b"sqlite bytes");
+        assert_eq!(fs::read(data_dir.join("sessions/abc.json")).unwrap(), br#"{"sessionId":"abc"}"#);
+        assert_eq!(fs::read(data_dir.join("settings.json")).unwrap(), b"untouched");
+        // Only replaced files are saved, and only once
+        assert_eq!(fs::read(safety_dir.join("speakers.db")).unwrap(), b"newer live bytes");
+        assert!(!safety_dir.join("sessions/abc.json").exists());
+        assert!(restore_backup_set(&info.path, &data_dir, &safety_dir, |_| false).is_err());
+
+        // Skipped files are left to the caller
+        let restore = restore_backup_set(&info.path, &data_dir, &dir.path().join("safety-2"), |name| name == "speakers.db").unwrap();
+        assert_eq!(restore.restored, vec!["sessions/abc.json".to_string()]);
+    }
+
+    #[test]
+    fn test_backups_copy_only_new_or_changed_files() {
+        let dir = TempDir::new().unwrap();
+        let policy = policy(&dir, 3);
+        let first = create_backup_set(&policy, &sources(dir.path()), 1_700_000_000).unwrap();
+        assert_eq!(first.unchanged_files, 0);
+
+        let mut changed = sources(dir.path());
+        changed.push(BackupSource::Data { name: "sessions/new.json".to_string(), bytes: br#"{"sessionId":"new"}"#.to_vec() });
+        if let BackupSource::Data { bytes, .. } = &mut changed[1] {
+            *bytes = br#"{"sessionId":"abc","edited":true}"#.to_vec();
+        }
+        let second = create_backup_set(&policy, &changed, 1_700_086_400).unwrap();
+
+        // Only the database is unchanged; the edited and the new session are copied
+        assert_eq!((second.file_count, second.unchanged_files), (3, 1));
+        assert!(verify_backup_set(&second.path).unwrap().valid);
+
+        // A damaged earlier copy is not carried forward
+        fs::write(second.path.join("sessions/new.json"), br#"{"sessionId":"ne?"}"#).unwrap();
+        let third = create_backup_set(&policy, &changed, 1_700_172_800).unwrap();
+        assert_eq!(third.unchanged_files, 2);
+        assert!(verify_backup_set(&third.path).unwrap().valid);
+        fs::remove_dir_all(&third.path).unwrap();
+        fs::write(second.path.join("sessions/new.json"), br#"{"sessionId":"new"}"#).unwrap();
+
+        // Each set still restores on its own once the older one is rotated out
+        fs::remove_dir_all(&first.path).unwrap();
+        let data_dir = dir.path().join("live");
+        restore_backup_set(&second.path, &data_dir, &dir.path().join("safety"), |_| false).unwrap();
+        assert_eq!(fs::read(data_dir.join("speakers.db")).unwrap(), b"sqlite bytes");
+        assert_eq!(fs::read(data_dir.join("sessions/new.json")).unwrap(), br#"{"sessionId":"new"}"#);
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest_sets() {
+        let dir = TempDir::new().unwrap();
+        let policy = policy(&dir, 2);
+        for i in 0..4 {
+            create_backup_set(&policy, &sources(dir.path()), 1_700_000_000 + i * 86_400).unwrap();
+        }
+
+        let removed = rotate_backup_sets(&policy).unwrap();
+        assert_eq!(removed.len(), 2);
+        let remaining = list_backup_sets(&policy.directory).unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining[1].to_string_lossy().ends_with("20231117T221320Z"));
+    }
+
+    #[test]
+    fn test_names_cannot_escape_set_directory() {
+        assert!(safe_relative_path("../outside").is_err());
+        assert!(safe_relative_path("/etc/passwd").is_err());
+        assert!(safe_relative_path("sessions/a.json").is_ok());
+    }
+}
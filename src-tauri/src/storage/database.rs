@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
-use rusqlite::{Connection, OpenFlags};
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tokio::task;
@@ -52,10 +53,19 @@ impl Database {
         task::spawn_blocking(move || -> Result<()> {
             let conn = connection.lock().unwrap();
             
-            // Read and execute migration file
-            let migration_sql = include_str!("../../migrations/001_create_speaker_profiles.up.sql");
-            conn.execute_batch(migration_sql)
-                .context("Failed to execute migration")?;
+            // Read and execute migration files in order
+            let migrations = [
+                include_str!("../../migrations/001_create_speaker_profiles.up.sql"),
+                include_str!("../../migrations/002_create_usage_metrics.up.sql"),
+                include_str!("../../migrations/003_create_sessions.up.sql"),
+                include_str!("../../migrations/004_create_transcript_search.up.sql"),
+                include_str!("../../migrations/005_create_speaker_samples.up.sql"),
+                include_str!("../../migrations/006_create_session_speakers.up.sql"),
+            ];
+            for migration_sql in migrations {
+                conn.execute_batch(migration_sql)
+                    .context("Failed to execute migration")?;
+            }
                 
             Ok(())
         }).await?
@@ -106,6 +116,33 @@ impl Database {
             Ok(result == 1)
         }).await?
     }
+    
+    /// Write a consistent copy of the database (including WAL contents) to `path`
+    pub async fn snapshot_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let connection = Arc::clone(&self.connection);
+        let path = path.as_ref().to_string_lossy().to_string();
+        
+        task::spawn_blocking(move || -> Result<()> {
+            let conn = connection.lock().unwrap();
+            conn.execute("VACUUM INTO ?1", [path])
+                .context("Failed to snapshot database")?;
+            Ok(())
+        }).await?
+    }
+    
+    /// Replace the contents of the open database with the database file at
+    /// `path`, through the connection so WAL and readers stay consistent
+    pub async fn restore_from<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let connection = Arc::clone(&self.connection);
+        let path = path.as_ref().to_path_buf();
+        
+        task::spawn_blocking(move || -> Result<()> {
+            let mut conn = connection.lock().unwrap();
+            conn.restore(DatabaseName::Main, &path, None::<fn(Progress)>)
+                .context("Failed to restore database")?;
+            Ok(())
+        }).await?
+    }
 }
 
 /// Convert Vec<f32> to BLOB for storage
@@ -177,6 +214,8 @@ mod tests {
             assert!(tables.contains(&"speaker_profiles".to_string()));
             assert!(tables.contains(&"voice_embeddings".to_string()));
             assert!(tables.contains(&"meeting_speakers".to_string()));
+            assert!(tables.contains(&"speaker_samples".to_string()));
+            assert!(tables.contains(&"session_speakers".to_string()));
             
             Ok(())
         }).await??;
@@ -184,6 +223,48 @@ mod tests {
         Ok(())
     }
     
+    #[tokio::test]
+    async fn test_search_works_on_freshly_migrated_database() -> Result<()> {
+        use crate::storage::transcript_search::SearchOptions;
+        use crate::storage::SessionStore;
+        use serde_json::json;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).await?;
+        db.migrate().await?;
+        // Migrations run again on every startup
+        db.migrate().await?;
+        let store = SessionStore::new(db);
+        store.create_session("s1", 100, None, json!({})).await?;
+        store.append_segments("s1", 0, vec![
+            json!({ "text": "the budget is approved", "startTime": 0.0, "endTime": 2.0, "speaker": "speaker_1", "speakerName": "Aiko" }),
+        ]).await?;
+
+        let page = store.search("budget", &SearchOptions::default()).await?;
+        assert_eq!(page.total, 1);
+        let by_name = SearchOptions { speaker: Some("aiko".into()), ..Default::default() };
+        assert_eq!(store.search("budget", &by_name).await?.total, 1);
+        assert!(store.replace_segments("s1", vec![]).await?);
+        Ok(())
+    }
+    
+    #[tokio::test]
+    async fn test_restore_replaces_open_database() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let live = Database::new(dir.path().join("live.db")).await?;
+        live.execute("CREATE TABLE notes (text TEXT)", []).await?;
+        live.execute("INSERT INTO notes VALUES ('before backup')", []).await?;
+        live.snapshot_to(dir.path().join("backup.db")).await?;
+        live.execute("INSERT INTO notes VALUES ('after backup')", []).await?;
+        
+        live.restore_from(dir.path().join("backup.db")).await?;
+        
+        let count: i64 = live.connection.lock().unwrap()
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))?;
+        assert_eq!(count, 1);
+        Ok(())
+    }
+    
     #[test]
     fn test_vector_blob_conversion() {
         let original = vec![1.0, 2.5, -3.7, 0.0, 100.1];
@@ -1,10 +1,56 @@
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use thiserror::Error;
 use uuid::Uuid;
 
 use crate::models::{VoiceEmbedding, SimilarSpeaker};
 
+/// File the index is saved to, in the app data directory
+pub const EMBEDDING_INDEX_FILE: &str = "embedding_index.bin";
+/// Layout version of the index file; files of other versions are rebuilt
+pub const INDEX_FILE_VERSION: u32 = 2;
+const INDEX_FILE_MAGIC: &[u8; 4] = b"KNEI";
+/// Magic, version, content fingerprint, body length and body checksum
+const INDEX_HEADER_LEN: usize = 4 + 4 + 8 + 8 + 8;
+
+/// How long the index must go without changes before it is saved
+pub const INDEX_SAVE_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Debounce periods an index that keeps changing stays unsaved at most
+const MAX_UNSAVED_PERIODS: u32 = 15;
+
+/// Why a saved index could not be used
+#[derive(Debug, Error)]
+pub enum IndexLoadError {
+    #[error("no saved index at {0}")]
+    Missing(PathBuf),
+
+    #[error("saved index is corrupt: {0}")]
+    Corrupt(String),
+
+    #[error("saved index has layout version {0}, this version reads {}", INDEX_FILE_VERSION)]
+    UnsupportedVersion(u32),
+
+    #[error("saved index uses {found_dimension} dimensions and {found_hashes} hashes, expected {dimension} and {num_hashes}")]
+    Mismatch { dimension: usize, num_hashes: usize, found_dimension: usize, found_hashes: usize },
+
+    #[error("saved index was built from other embeddings than storage holds (fingerprint {found:016x}, expected {expected:016x})")]
+    Stale { expected: u64, found: u64 },
+}
+
+/// Index contents as written after the file header
+#[derive(Serialize, Deserialize)]
+struct IndexSnapshot {
+    embedding_dimension: usize,
+    num_hashes: usize,
+    embeddings: HashMap<Uuid, Vec<VoiceEmbedding>>,
+    lsh_buckets: HashMap<String, Vec<Uuid>>,
+}
+
 /// Fast in-memory index for voice embedding similarity search
 /// Uses a simplified LSH (Locality Sensitive Hashing) approach for performance
 pub struct EmbeddingIndex {
@@ -16,6 +62,10 @@ pub struct EmbeddingIndex {
     num_hashes: usize,
     /// Dimension of embeddings (assumed consistent)
     embedding_dimension: usize,
+    /// Bumped by every change to the index
+    generation: AtomicU64,
+    /// Generation last written to disk
+    saved_generation: AtomicU64,
 }
 
 impl EmbeddingIndex {
@@ -26,9 +76,29 @@ impl EmbeddingIndex {
             lsh_buckets: Arc::new(RwLock::new(HashMap::new())),
             num_hashes,
             embedding_dimension,
+            generation: AtomicU64::new(0),
+            saved_generation: AtomicU64::new(0),
         }
     }
 
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
+    }
+
+    /// Changed since it was loaded or last saved
+    pub fn is_dirty(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) != self.saved_generation.load(Ordering::SeqCst)
+    }
+
+    fn touch(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Dimension every indexed embedding must have
+    pub fn embedding_dimension(&self) -> usize {
+        self.embedding_dimension
+    }
+
     /// Add an embedding to the index
     pub fn add_embedding(&self, embedding: VoiceEmbedding) -> Result<()> {
         let speaker_id = embedding.speaker_id;
@@ -56,6 +126,7 @@ impl EmbeddingIndex {
             }
         }
 
+        self.touch();
         Ok(())
     }
 
@@ -82,6 +153,7 @@ impl EmbeddingIndex {
             buckets.retain(|_, speakers| !speakers.is_empty());
         }
 
+        self.touch();
         Ok(())
     }
 
@@ -193,9 +265,95 @@ impl EmbeddingIndex {
             buckets.clear();
         }
 
+        self.touch();
         Ok(())
     }
 
+    /// Encode the index for saving; returns the file contents and the
+    /// generation they hold
+    pub fn to_file_bytes(&self) -> Result<(Vec<u8>, u64)> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        let snapshot = IndexSnapshot {
+            embedding_dimension: self.embedding_dimension,
+            num_hashes: self.num_hashes,
+            embeddings: self.embeddings.read()
+                .map_err(|_| anyhow::anyhow!("Failed to acquire read lock on embeddings"))?
+                .clone(),
+            lsh_buckets: self.lsh_buckets.read()
+                .map_err(|_| anyhow::anyhow!("Failed to acquire read lock on LSH buckets"))?
+                .clone(),
+        };
+        let fingerprint = snapshot_fingerprint(&snapshot.embeddings);
+        let body = bincode::serialize(&snapshot).context("Failed to encode embedding index")?;
+
+        let mut bytes = Vec::with_capacity(INDEX_HEADER_LEN + body.len());
+        bytes.extend_from_slice(INDEX_FILE_MAGIC);
+        bytes.extend_from_slice(&INDEX_FILE_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&fingerprint.to_le_bytes());
+        bytes.extend_from_slice(&(body.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&checksum(&body).to_le_bytes());
+        bytes.extend_from_slice(&body);
+        Ok((bytes, generation))
+    }
+
+    /// Record that the contents of `generation` are on disk
+    pub fn mark_saved(&self, generation: u64) {
+        self.saved_generation.store(generation, Ordering::SeqCst);
+    }
+
+    /// Decode a saved index, which must use `embedding_dimension` and `num_hashes`
+    pub fn from_file_bytes(bytes: &[u8], embedding_dimension: usize, num_hashes: usize) -> Result<Self, IndexLoadError> {
+        let fingerprint = file_fingerprint(bytes)?;
+        let body_len = u64::from_le_bytes(bytes[16..24].try_into().unwrap_or_default());
+        let expected_checksum = u64::from_le_bytes(bytes[24..32].try_into().unwrap_or_default());
+        let body = &bytes[INDEX_HEADER_LEN..];
+        if body.len() as u64 != body_len {
+            return Err(IndexLoadError::Corrupt(format!("expected {} bytes of index data, found {}", body_len, body.len())));
+        }
+        if checksum(body) != expected_checksum {
+            return Err(IndexLoadError::Corrupt("checksum mismatch".to_string()));
+        }
+
+        let snapshot: IndexSnapshot = bincode::deserialize(body)
+            .map_err(|e| IndexLoadError::Corrupt(e.to_string()))?;
+        if snapshot.embedding_dimension != embedding_dimension || snapshot.num_hashes != num_hashes {
+            return Err(IndexLoadError::Mismatch {
+                dimension: embedding_dimension,
+                num_hashes,
+                found_dimension: snapshot.embedding_dimension,
+                found_hashes: snapshot.num_hashes,
+            });
+        }
+        if let Some(embedding) = snapshot.embeddings.values().flatten().find(|e| e.vector.len() != embedding_dimension) {
+            return Err(IndexLoadError::Corrupt(format!("embedding {} has {} dimensions", embedding.id, embedding.vector.len())));
+        }
+        if snapshot_fingerprint(&snapshot.embeddings) != fingerprint {
+            return Err(IndexLoadError::Corrupt("fingerprint does not match the index contents".to_string()));
+        }
+
+        let index = Self::new(embedding_dimension, num_hashes);
+        *index.embeddings.write().map_err(|_| IndexLoadError::Corrupt("poisoned lock".to_string()))? = snapshot.embeddings;
+        *index.lsh_buckets.write().map_err(|_| IndexLoadError::Corrupt("poisoned lock".to_string()))? = snapshot.lsh_buckets;
+        Ok(index)
+    }
+
+    /// Load the index saved at `path`. `expected_fingerprint` is the
+    /// [`content_fingerprint`] of the embeddings storage holds for it; a file
+    /// built from other embeddings was saved before later changes and is not used.
+    pub fn load(path: &Path, embedding_dimension: usize, num_hashes: usize, expected_fingerprint: u64) -> Result<Self, IndexLoadError> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(IndexLoadError::Missing(path.to_path_buf())),
+            Err(e) => return Err(IndexLoadError::Corrupt(format!("failed to read {}: {}", path.display(), e))),
+        };
+        // Compared before decoding, so a stale file costs only its header
+        let found = file_fingerprint(&bytes)?;
+        if found != expected_fingerprint {
+            return Err(IndexLoadError::Stale { expected: expected_fingerprint, found });
+        }
+        Self::from_file_bytes(&bytes, embedding_dimension, num_hashes)
+    }
+
     /// Rebuild the entire index from a fresh set of embeddings
     pub fn rebuild(&self, all_embeddings: Vec<VoiceEmbedding>) -> Result<()> {
         self.clear()?;
@@ -263,8 +421,92 @@ impl EmbeddingIndex {
     }
 }
 
+/// Write the index to `path` if it changed since it was last saved.
+/// Returns whether it was written.
+pub async fn save_index(index: &tokio::sync::Mutex<EmbeddingIndex>, path: &Path) -> Result<bool> {
+    let (bytes, generation) = {
+        let index = index.lock().await;
+        if !index.is_dirty() {
+            return Ok(false);
+        }
+        index.to_file_bytes()?
+    };
+
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        // Written aside and renamed, so a crash mid-write leaves the previous file
+        let partial = path.with_extension("bin.partial");
+        std::fs::write(&partial, &bytes).context("Failed to write embedding index")?;
+        std::fs::rename(&partial, &path).context("Failed to replace embedding index")?;
+        Ok(())
+    }).await??;
+
+    index.lock().await.mark_saved(generation);
+    Ok(true)
+}
+
+/// Save the index whenever it has been left unchanged for a debounce period
+/// after a change, or has stayed unsaved for too long. Runs until the app exits.
+pub async fn save_index_when_idle(index: Arc<tokio::sync::Mutex<EmbeddingIndex>>, path: PathBuf) {
+    let mut ticks = tokio::time::interval(INDEX_SAVE_DEBOUNCE);
+    let mut last_generation = None;
+    let mut unsaved_periods = 0;
+    loop {
+        ticks.tick().await;
+        let (generation, dirty) = {
+            let index = index.lock().await;
+            (index.generation.load(Ordering::SeqCst), index.is_dirty())
+        };
+        let quiet = last_generation.replace(generation) == Some(generation);
+        if !dirty {
+            unsaved_periods = 0;
+            continue;
+        }
+        unsaved_periods += 1;
+        if quiet || unsaved_periods >= MAX_UNSAVED_PERIODS {
+            unsaved_periods = 0;
+            if let Err(e) = save_index(&index, &path).await {
+                tracing::warn!("Failed to save the embedding index: {}", e);
+            }
+        }
+    }
+}
+
+/// FNV-1a over the index data, to catch damaged files
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Fingerprint of a set of embeddings given as (embedding id, speaker id)
+/// pairs, in any order. Stored embeddings are never modified in place, so
+/// the pairs identify the index contents.
+pub fn content_fingerprint(entries: impl IntoIterator<Item = (Uuid, Uuid)>) -> u64 {
+    let mut entries: Vec<(Uuid, Uuid)> = entries.into_iter().collect();
+    entries.sort_unstable();
+    let bytes: Vec<u8> = entries.iter()
+        .flat_map(|(id, speaker_id)| id.as_bytes().iter().chain(speaker_id.as_bytes()).copied())
+        .collect();
+    checksum(&bytes)
+}
+
+fn snapshot_fingerprint(embeddings: &HashMap<Uuid, Vec<VoiceEmbedding>>) -> u64 {
+    content_fingerprint(embeddings.values().flatten().map(|e| (e.id, e.speaker_id)))
+}
+
+/// Fingerprint recorded in the header of a saved index
+fn file_fingerprint(bytes: &[u8]) -> Result<u64, IndexLoadError> {
+    if bytes.len() < INDEX_HEADER_LEN || &bytes[..4] != INDEX_FILE_MAGIC {
+        return Err(IndexLoadError::Corrupt("not an embedding index file".to_string()));
+    }
+    let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap_or_default());
+    if version != INDEX_FILE_VERSION {
+        return Err(IndexLoadError::UnsupportedVersion(version));
+    }
+    Ok(u64::from_le_bytes(bytes[8..16].try_into().unwrap_or_default()))
+}
+
 /// Statistics about the embedding index
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct IndexStats {
     pub total_speakers: usize,
     pub total_embeddings: usize,
@@ -402,4 +644,98 @@ mod tests {
         let d = vec![2.0, 0.0, 0.0];
         assert!((cosine_similarity(&a, &d) - 1.0).abs() < 0.001);
     }
-}
\ No newline at end of file
+
+    /// Saves an index of two embeddings; returns its path, the first
+    /// embedding's speaker and the fingerprint of the stored embeddings
+    fn saved_index(dir: &Path) -> (PathBuf, Uuid, u64) {
+        let index = EmbeddingIndex::new(3, 4);
+        let embeddings = [
+            create_test_embedding(Uuid::new_v4(), vec![1.0, 0.0, 0.0]),
+            create_test_embedding(Uuid::new_v4(), vec![0.0, 1.0, 0.0]),
+        ];
+        for embedding in &embeddings {
+            index.add_embedding(embedding.clone()).unwrap();
+        }
+        let path = dir.join(EMBEDDING_INDEX_FILE);
+        std::fs::write(&path, index.to_file_bytes().unwrap().0).unwrap();
+        let fingerprint = content_fingerprint(embeddings.iter().map(|e| (e.id, e.speaker_id)));
+        (path, embeddings[0].speaker_id, fingerprint)
+    }
+
+    #[test]
+    fn test_saved_index_loads_and_searches() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, speaker, fingerprint) = saved_index(dir.path());
+
+        let loaded = EmbeddingIndex::load(&path, 3, 4, fingerprint).unwrap();
+        assert!(!loaded.is_dirty());
+        let stats = loaded.get_stats().unwrap();
+        assert_eq!((stats.total_speakers, stats.total_embeddings), (2, 2));
+        assert!(stats.total_buckets > 0);
+        assert_eq!(loaded.find_similar_embeddings(&[0.9, 0.1, 0.0], 0.8, 1).unwrap()[0].0, speaker);
+    }
+
+    #[test]
+    fn test_unusable_index_files_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("absent.bin");
+        assert!(matches!(EmbeddingIndex::load(&missing, 3, 4, 0), Err(IndexLoadError::Missing(_))));
+
+        let (path, _, _) = saved_index(dir.path());
+        let bytes = std::fs::read(&path).unwrap();
+
+        // A flipped bit in the data, a truncated file and something else entirely
+        let mut flipped = bytes.clone();
+        *flipped.last_mut().unwrap() ^= 0x10;
+        assert!(matches!(EmbeddingIndex::from_file_bytes(&flipped, 3, 4), Err(IndexLoadError::Corrupt(_))));
+        assert!(matches!(EmbeddingIndex::from_file_bytes(&bytes[..bytes.len() / 2], 3, 4), Err(IndexLoadError::Corrupt(_))));
+        assert!(matches!(EmbeddingIndex::from_file_bytes(b"{\"embeddings\": []}", 3, 4), Err(IndexLoadError::Corrupt(_))));
+
+        let mut future = bytes.clone();
+        future[4..8].copy_from_slice(&(INDEX_FILE_VERSION + 1).to_le_bytes());
+        assert!(matches!(EmbeddingIndex::from_file_bytes(&future, 3, 4), Err(IndexLoadError::UnsupportedVersion(_))));
+
+        assert!(matches!(EmbeddingIndex::from_file_bytes(&bytes, 512, 4), Err(IndexLoadError::Mismatch { found_dimension: 3, .. })));
+        assert!(matches!(EmbeddingIndex::from_file_bytes(&bytes, 3, 8), Err(IndexLoadError::Mismatch { found_hashes: 4, .. })));
+    }
+
+    #[test]
+    fn test_index_of_other_embeddings_is_stale_even_with_the_same_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, speaker, fingerprint) = saved_index(dir.path());
+
+        // One embedding replaced by another: storage still holds two
+        let replaced = create_test_embedding(speaker, vec![0.0, 0.0, 1.0]);
+        let bytes = std::fs::read(&path).unwrap();
+        let stored = EmbeddingIndex::from_file_bytes(&bytes, 3, 4).unwrap();
+        let kept = stored.embeddings.read().unwrap().values().flatten()
+            .find(|e| e.speaker_id != speaker).map(|e| (e.id, e.speaker_id)).unwrap();
+        let current = content_fingerprint([kept, (replaced.id, replaced.speaker_id)]);
+
+        assert_ne!(current, fingerprint);
+        assert!(matches!(
+            EmbeddingIndex::load(&path, 3, 4, current),
+            Err(IndexLoadError::Stale { expected, found }) if expected == current && found == fingerprint
+        ));
+        assert_eq!(content_fingerprint([(replaced.id, replaced.speaker_id), kept]), current, "order does not matter");
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_file_is_replaced_after_rebuild() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EMBEDDING_INDEX_FILE);
+        std::fs::write(&path, b"KNEI garbage").unwrap();
+        let embedding = create_test_embedding(Uuid::new_v4(), vec![0.0, 0.0, 1.0]);
+        let fingerprint = content_fingerprint([(embedding.id, embedding.speaker_id)]);
+        assert!(EmbeddingIndex::load(&path, 3, 4, fingerprint).is_err());
+
+        // Startup falls back to a rebuild, which leaves the index dirty for the saver
+        let index = tokio::sync::Mutex::new(EmbeddingIndex::new(3, 4));
+        index.lock().await.rebuild(vec![embedding]).unwrap();
+        assert!(save_index(&index, &path).await.unwrap());
+        assert!(!save_index(&index, &path).await.unwrap(), "an unchanged index is not written again");
+
+        let reloaded = EmbeddingIndex::load(&path, 3, 4, fingerprint).unwrap();
+        assert_eq!(reloaded.get_stats().unwrap().total_embeddings, 1);
+    }
+}
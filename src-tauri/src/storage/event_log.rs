@@ -0,0 +1,451 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Size bound for a single session event log without a workspace quota (5MB)
+pub const DEFAULT_EVENT_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// A session event log may take this fraction (1/n) of the workspace quota
+pub const EVENT_LOG_QUOTA_DIVISOR: u64 = 200;
+/// Smallest bound under any quota, so the latest events are still kept
+pub const MIN_EVENT_LOG_MAX_BYTES: u64 = 256 * 1024;
+/// Events waiting for the writer thread before new ones are dropped
+pub const EVENT_LOG_QUEUE_CAPACITY: usize = 4096;
+
+/// Payload fields holding user content, replaced when redaction is enabled;
+/// speaker name suggestions quote the introduction and the name it gave
+const REDACTED_FIELDS: &[&str] = &[
+    "text", "rawText", "snippet", "word", "transcript",
+    "evidence", "suggestedName", "profileName",
+];
+
+/// A single emitted session event as seen by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoggedEvent {
+    pub sequence: u64,
+    #[serde(rename = "timestampMs")]
+    pub timestamp_ms: u64,
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+/// Append-only, size-bounded JSON-lines log of the events emitted for one session
+pub struct SessionEventLog {
+    path: PathBuf,
+    max_bytes: u64,
+    redact_text: bool,
+    next_sequence: u64,
+}
+
+impl SessionEventLog {
+    /// Open (or resume) the event log for a session inside `dir`
+    pub fn open(dir: &Path, session_id: &str, max_bytes: u64, redact_text: bool) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create event log directory {:?}", dir))?;
+        let path = Self::log_path(dir, session_id);
+
+        // Continue numbering after the last persisted event
+        let next_sequence = if path.exists() {
+            read_events(&path, None)?
+                .last()
+                .map(|e| e.sequence + 1)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        Ok(Self { path, max_bytes, redact_text, next_sequence })
+    }
+
+    /// Location of a session's event log inside `dir`
+    pub fn log_path(dir: &Path, session_id: &str) -> PathBuf {
+        dir.join(format!("{}.events.jsonl", session_id))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record an event, returning its sequence number
+    pub fn append(&mut self, event: &str, payload: &serde_json::Value, timestamp_ms: u64) -> Result<u64> {
+        self.append_batch(&[(event.to_string(), payload.clone(), timestamp_ms)])?;
+        Ok(self.next_sequence - 1)
+    }
+
+    /// Record `(event, payload, timestamp_ms)` entries in order with one write
+    pub fn append_batch(&mut self, events: &[(String, serde_json::Value, u64)]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for (offset, (event, payload, timestamp_ms)) in events.iter().enumerate() {
+            let entry = LoggedEvent {
+                sequence: self.next_sequence + offset as u64,
+                timestamp_ms: *timestamp_ms,
+                event: event.clone(),
+                payload: if self.redact_text { redact_payload(payload) } else { payload.clone() },
+            };
+            lines.push_str(&serde_json::to_string(&entry).context("Failed to serialize event")?);
+            lines.push('\n');
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open event log {:?}", self.path))?;
+        file.write_all(lines.as_bytes()).context("Failed to append events")?;
+        drop(file);
+
+        self.next_sequence += events.len() as u64;
+        self.enforce_size_bound()
+    }
+
+    /// Truncate the oldest events once the log grows past its size bound
+    fn enforce_size_bound(&self) -> Result<()> {
+        let size = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if size <= self.max_bytes {
+            return Ok(());
+        }
+
+        // Keep roughly the newest half so truncation doesn't run on every append
+        let target = self.max_bytes / 2;
+        let reader = BufReader::new(File::open(&self.path)?);
+        let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
+
+        let mut kept_bytes = 0u64;
+        let mut first_kept = lines.len();
+        for (i, line) in lines.iter().enumerate().rev() {
+            let line_bytes = line.len() as u64 + 1;
+            if kept_bytes + line_bytes > target {
+                break;
+            }
+            kept_bytes += line_bytes;
+            first_kept = i;
+        }
+
+        let mut contents = lines[first_kept..].join("\n");
+        if !contents.is_empty() {
+            contents.push('\n');
+        }
+
+        // Rewrite atomically so a crash never leaves a half-written log
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, contents).context("Failed to write truncated event log")?;
+        fs::rename(&tmp_path, &self.path).context("Failed to replace event log")?;
+        tracing::debug!("Truncated {} old events from {:?}", first_kept, self.path);
+        Ok(())
+    }
+}
+
+enum WriterMessage {
+    Event(String, serde_json::Value, u64),
+    Flush(mpsc::Sender<()>),
+}
+
+/// Session event log written on a background thread, so emitting an event
+/// never waits on the disk. Whatever is queued when the thread wakes up is
+/// written in one batch; when the disk cannot keep up and the queue is full,
+/// new events are dropped and counted. Dropping the writer writes out the
+/// queue first.
+pub struct EventLogWriter {
+    path: PathBuf,
+    to_writer: Option<mpsc::SyncSender<WriterMessage>>,
+    worker: Option<JoinHandle<()>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl EventLogWriter {
+    pub fn spawn(mut log: SessionEventLog, capacity: usize) -> Self {
+        let path = log.path().to_path_buf();
+        let (to_writer, from_emitters) = mpsc::sync_channel::<WriterMessage>(capacity.max(1));
+        let worker = thread::spawn(move || {
+            while let Ok(first) = from_emitters.recv() {
+                let mut events = Vec::new();
+                let mut flushed = Vec::new();
+                for message in std::iter::once(first).chain(from_emitters.try_iter()) {
+                    match message {
+                        WriterMessage::Event(event, payload, timestamp_ms) => events.push((event, payload, timestamp_ms)),
+                        WriterMessage::Flush(done) => flushed.push(done),
+                    }
+                }
+                if let Err(e) = log.append_batch(&events) {
+                    tracing::warn!("Failed to record {} events in {:?}: {}", events.len(), log.path(), e);
+                }
+                for done in flushed {
+                    let _ = done.send(());
+                }
+            }
+        });
+        Self { path, to_writer: Some(to_writer), worker: Some(worker), dropped: Arc::new(AtomicU64::new(0)) }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Queue an event without blocking; returns false when it was dropped
+    pub fn record(&self, event: &str, payload: &serde_json::Value, timestamp_ms: u64) -> bool {
+        let Some(to_writer) = &self.to_writer else {
+            return false;
+        };
+        match to_writer.try_send(WriterMessage::Event(event.to_string(), payload.clone(), timestamp_ms)) {
+            Ok(()) => true,
+            Err(_) => {
+                if self.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+                    tracing::warn!("Event log writer for {:?} is behind, dropping events", self.path);
+                }
+                false
+            }
+        }
+    }
+
+    /// Events dropped because the queue was full
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Wait until everything queued so far is written
+    pub fn flush(&self) {
+        let Some(to_writer) = &self.to_writer else {
+            return;
+        };
+        let (done, written) = mpsc::channel();
+        if to_writer.send(WriterMessage::Flush(done)).is_ok() {
+            let _ = written.recv();
+        }
+    }
+}
+
+impl Drop for EventLogWriter {
+    fn drop(&mut self) {
+        self.to_writer = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Size bound of one session's event log under the configured workspace quota
+pub fn event_log_max_bytes(workspace_quota_bytes: Option<u64>) -> u64 {
+    match workspace_quota_bytes {
+        Some(quota) => (quota / EVENT_LOG_QUOTA_DIVISOR).max(MIN_EVENT_LOG_MAX_BYTES),
+        None => DEFAULT_EVENT_LOG_MAX_BYTES,
+    }
+}
+
+/// Read events from a log file, optionally limited to an inclusive sequence range
+pub fn read_events(path: &Path, range: Option<(u64, u64)>) -> Result<Vec<LoggedEvent>> {
+    let file = File::open(path).with_context(|| format!("Failed to open event log {:?}", path))?;
+    let mut events = Vec::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Skip lines damaged by a crash mid-write rather than failing the whole dump
+        let Ok(event) = serde_json::from_str::<LoggedEvent>(&line) else {
+            tracing::warn!("Skipping malformed event log line in {:?}", path);
+            continue;
+        };
+        if let Some((start, end)) = range {
+            if event.sequence < start || event.sequence > end {
+                continue;
+            }
+        }
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+/// Replace transcript content in a payload with a placeholder
+pub fn redact_payload(payload: &serde_json::Value) -> serde_json::Value {
+    match payload {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if REDACTED_FIELDS.contains(&key.as_str()) && value.is_string() {
+                        serde_json::Value::String("[redacted]".to_string())
+                    } else {
+                        redact_payload(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(redact_payload).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Delays between replayed events, scaled by `speed` (2.0 replays twice as fast)
+pub fn replay_schedule(events: &[LoggedEvent], speed: f32) -> Vec<Duration> {
+    let speed = if speed > 0.0 { speed } else { 1.0 };
+    let mut previous = events.first().map(|e| e.timestamp_ms).unwrap_or(0);
+
+    events.iter()
+        .map(|event| {
+            let gap_ms = event.timestamp_ms.saturating_sub(previous);
+            previous = event.timestamp_ms;
+            Duration::from_secs_f32(gap_ms as f32 / 1000.0 / speed)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_and_read_range() {
+        let dir = TempDir::new().unwrap();
+        let mut log = SessionEventLog::open(dir.path(), "session", DEFAULT_EVENT_LOG_MAX_BYTES, false).unwrap();
+
+        for i in 0..5 {
+            let seq = log.append("transcription-update", &serde_json::json!({ "index": i }), 1000 + i).unwrap();
+            assert_eq!(seq, i);
+        }
+
+        let events = read_events(log.path(), Some((1, 3))).unwrap();
+        let sequences: Vec<u64> = events.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![1, 2, 3]);
+
+        // Reopening resumes numbering
+        let mut reopened = SessionEventLog::open(dir.path(), "session", DEFAULT_EVENT_LOG_MAX_BYTES, false).unwrap();
+        assert_eq!(reopened.append("audio-level", &serde_json::json!({}), 2000).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest_events_under_bound() {
+        let dir = TempDir::new().unwrap();
+        let max_bytes = 2048;
+        let mut log = SessionEventLog::open(dir.path(), "session", max_bytes, false).unwrap();
+
+        for i in 0..200 {
+            log.append("audio-level", &serde_json::json!({ "level": 0.5, "index": i }), i).unwrap();
+            assert!(fs::metadata(log.path()).unwrap().len() <= max_bytes);
+        }
+
+        let events = read_events(log.path(), None).unwrap();
+        assert_eq!(events.last().unwrap().sequence, 199);
+        // Remaining events are the contiguous newest tail
+        for pair in events.windows(2) {
+            assert_eq!(pair[1].sequence, pair[0].sequence + 1);
+        }
+    }
+
+    #[test]
+    fn test_redaction_strips_transcript_text() {
+        let dir = TempDir::new().unwrap();
+        let mut log = SessionEventLog::open(dir.path(), "session", DEFAULT_EVENT_LOG_MAX_BYTES, true).unwrap();
+        log.append("transcription-update", &serde_json::json!({
+            "sessionId": "session",
+            "segment": { "text": "confidential plans", "rawText": "confidential plans", "startTime": 1.0 }
+        }), 0).unwrap();
+        log.append("highlight-captured", &serde_json::json!({
+            "sessionId": "session",
+            "highlight": { "id": "h1", "transcript": "confidential numbers", "startTime": 0.0, "endTime": 30.0 }
+        }), 10).unwrap();
+
+        let contents = fs::read_to_string(log.path()).unwrap();
+        assert!(!contents.contains("confidential"));
+        assert!(contents.contains("[redacted]"));
+    }
+
+    #[test]
+    fn test_redaction_strips_segment_words() {
+        let dir = TempDir::new().unwrap();
+        let mut log = SessionEventLog::open(dir.path(), "session", DEFAULT_EVENT_LOG_MAX_BYTES, true).unwrap();
+        log.append("transcription-update", &serde_json::json!({
+            "sessionId": "session",
+            "segment": {
+                "text": "merger talks",
+                "words": [
+                    { "word": "merger", "start": 1.0, "end": 1.4, "confidence": 0.9 },
+                    { "word": "talks", "start": 1.4, "end": 1.8, "confidence": 0.8 }
+                ]
+            }
+        }), 0).unwrap();
+
+        let contents = fs::read_to_string(log.path()).unwrap();
+        assert!(!contents.contains("merger") && !contents.contains("talks"));
+        // Timing survives so replays keep their word highlighting
+        let events = read_events(log.path(), None).unwrap();
+        assert_eq!(events[0].payload["segment"]["words"][1]["start"], 1.4);
+    }
+
+    #[test]
+    fn test_redaction_strips_speaker_name_suggestions() {
+        let dir = TempDir::new().unwrap();
+        let mut log = SessionEventLog::open(dir.path(), "session", DEFAULT_EVENT_LOG_MAX_BYTES, true).unwrap();
+        log.append("speaker-name-suggestion", &serde_json::json!({
+            "sessionId": "session",
+            "speakerId": "speaker_1",
+            "suggestedName": "Akiko Tanaka",
+            "evidence": "Hi everyone, I'm Akiko Tanaka from finance",
+            "confidence": 0.9,
+            "profileMatch": { "profileId": "p1", "profileName": "Akiko Tanaka", "similarity": 1.0 }
+        }), 0).unwrap();
+
+        let contents = fs::read_to_string(log.path()).unwrap();
+        assert!(!contents.contains("Akiko") && !contents.contains("finance"));
+        let events = read_events(log.path(), None).unwrap();
+        assert_eq!(events[0].payload["speakerId"], "speaker_1");
+    }
+
+    #[test]
+    fn test_writer_batches_events_in_order() {
+        let dir = TempDir::new().unwrap();
+        let log = SessionEventLog::open(dir.path(), "session", DEFAULT_EVENT_LOG_MAX_BYTES, false).unwrap();
+        let writer = EventLogWriter::spawn(log, EVENT_LOG_QUEUE_CAPACITY);
+
+        for i in 0..500 {
+            assert!(writer.record("audio-level", &serde_json::json!({ "index": i }), i));
+        }
+        writer.flush();
+        let events = read_events(writer.path(), None).unwrap();
+        assert_eq!(events.len(), 500);
+        assert!(events.iter().enumerate().all(|(i, e)| e.sequence == i as u64 && e.payload["index"] == i));
+
+        // Dropping the writer writes out what is still queued
+        writer.record("session-stopped", &serde_json::json!({}), 600);
+        let path = writer.path().to_path_buf();
+        drop(writer);
+        assert_eq!(read_events(&path, None).unwrap().last().unwrap().sequence, 500);
+        assert_eq!(SessionEventLog::open(dir.path(), "session", DEFAULT_EVENT_LOG_MAX_BYTES, false).unwrap()
+            .append("audio-level", &serde_json::json!({}), 700).unwrap(), 501);
+    }
+
+    #[test]
+    fn test_replay_preserves_order_and_scales_timing() {
+        let events: Vec<LoggedEvent> = (0..3)
+            .map(|i| LoggedEvent {
+                sequence: i,
+                timestamp_ms: 1000 + i * 500,
+                event: "transcription-update".to_string(),
+                payload: serde_json::json!({}),
+            })
+            .collect();
+
+        let delays = replay_schedule(&events, 2.0);
+        assert_eq!(delays.len(), events.len());
+        assert_eq!(delays[0], Duration::ZERO);
+        assert_eq!(delays[1], Duration::from_millis(250));
+        assert!(events.windows(2).all(|w| w[0].sequence < w[1].sequence));
+    }
+
+    #[test]
+    fn test_log_bound_follows_workspace_quota() {
+        assert_eq!(event_log_max_bytes(None), DEFAULT_EVENT_LOG_MAX_BYTES);
+        assert_eq!(event_log_max_bytes(Some(2 * 1024 * 1024 * 1024)), 2 * 1024 * 1024 * 1024 / EVENT_LOG_QUOTA_DIVISOR);
+        assert_eq!(event_log_max_bytes(Some(10 * 1024 * 1024)), MIN_EVENT_LOG_MAX_BYTES);
+    }
+}
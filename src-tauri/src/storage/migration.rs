@@ -32,6 +32,36 @@ impl MigrationManager {
                 up_sql: include_str!("../../migrations/001_create_speaker_profiles.up.sql"),
                 down_sql: include_str!("../../migrations/001_create_speaker_profiles.down.sql"),
             },
+            Migration {
+                version: 2,
+                name: "create_usage_metrics".to_string(),
+                up_sql: include_str!("../../migrations/002_create_usage_metrics.up.sql"),
+                down_sql: include_str!("../../migrations/002_create_usage_metrics.down.sql"),
+            },
+            Migration {
+                version: 3,
+                name: "create_sessions".to_string(),
+                up_sql: include_str!("../../migrations/003_create_sessions.up.sql"),
+                down_sql: include_str!("../../migrations/003_create_sessions.down.sql"),
+            },
+            Migration {
+                version: 4,
+                name: "create_transcript_search".to_string(),
+                up_sql: include_str!("../../migrations/004_create_transcript_search.up.sql"),
+                down_sql: include_str!("../../migrations/004_create_transcript_search.down.sql"),
+            },
+            Migration {
+                version: 5,
+                name: "create_speaker_samples".to_string(),
+                up_sql: include_str!("../../migrations/005_create_speaker_samples.up.sql"),
+                down_sql: include_str!("../../migrations/005_create_speaker_samples.down.sql"),
+            },
+            Migration {
+                version: 6,
+                name: "create_session_speakers".to_string(),
+                up_sql: include_str!("../../migrations/006_create_session_speakers.up.sql"),
+                down_sql: include_str!("../../migrations/006_create_session_speakers.down.sql"),
+            },
         ]
     }
 
@@ -3,9 +3,20 @@ pub mod speaker_store;
 pub mod embedding_index;
 pub mod migration;
 pub mod seed;
+pub mod event_log;
+pub mod usage_metrics;
+pub mod backup;
+pub mod storage_usage;
+pub mod session_store;
+pub mod transcript_search;
+pub mod profile_transfer;
+pub mod autosave;
 
 pub use database::*;
 pub use speaker_store::*;
 pub use embedding_index::*;
 pub use migration::*;
-pub use seed::*;
\ No newline at end of file
+pub use seed::*;
+pub use event_log::*;
+pub use usage_metrics::*;
+pub use session_store::*;
\ No newline at end of file
@@ -0,0 +1,362 @@
+//! Speaker profile export and import
+//!
+//! An export carries profiles with their stored ids, and optionally their
+//! embeddings and reference samples keyed by those ids. Import creates or
+//! reuses a profile for each exported one and maps the old id to it. The
+//! embeddings and samples are then rewritten onto the mapped profile, so
+//! they stay linked even though stored profiles get fresh ids.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::metadata;
+use crate::models::{
+    CreateSpeakerProfileRequest, SpeakerProfile, SpeakerSample, UpdateSpeakerProfileRequest, VoiceEmbedding,
+};
+use crate::storage::{EmbeddingIndex, SpeakerStore, SPEAKER_SAMPLE_CAP_SECONDS};
+
+/// Version of the export layout
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// What to do with an imported profile whose name is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    /// Keep the stored profile and leave out the imported one with its data
+    #[default]
+    Skip,
+    /// Update the stored profile; its embeddings and samples are replaced
+    /// when the import carries any
+    Overwrite,
+    /// Import as another profile with the same name
+    Duplicate,
+}
+
+/// Contents of an export, as read back for import
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpeakerImport {
+    pub profiles: Vec<SpeakerProfile>,
+    #[serde(default)]
+    pub embeddings: Option<Vec<VoiceEmbedding>>,
+    #[serde(default)]
+    pub samples: Option<Vec<SpeakerSample>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportOutcome {
+    Created,
+    Skipped,
+    Overwritten,
+    Duplicated,
+    Failed,
+}
+
+/// What happened to one imported profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileImport {
+    /// Id of the profile in the export
+    pub source_id: Uuid,
+    /// Stored profile its data went to, unless skipped or failed
+    pub profile_id: Option<Uuid>,
+    pub name: String,
+    pub outcome: ImportOutcome,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub profiles: Vec<ProfileImport>,
+    pub imported_embeddings: usize,
+    pub imported_samples: usize,
+    pub errors: Vec<String>,
+}
+
+impl ImportReport {
+    /// Profiles whose data was stored
+    pub fn imported_profiles(&self) -> usize {
+        self.profiles.iter()
+            .filter(|p| matches!(p.outcome, ImportOutcome::Created | ImportOutcome::Overwritten | ImportOutcome::Duplicated))
+            .count()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Embedding {embedding_id} has {actual} dimensions, but the speaker index uses {expected}")]
+    DimensionMismatch { embedding_id: Uuid, expected: usize, actual: usize },
+
+    #[error(transparent)]
+    Storage(#[from] anyhow::Error),
+}
+
+/// Export all profiles, optionally with their embeddings and samples
+pub async fn export_speakers(
+    store: &SpeakerStore,
+    include_embeddings: bool,
+    include_samples: bool,
+) -> Result<serde_json::Value> {
+    let profiles = store.list_speaker_profiles(false).await?;
+
+    let mut export_data = serde_json::json!({
+        "metadata": {
+            "exported_at": chrono::Utc::now().to_rfc3339(),
+            "app_version": "1.0.0",
+            "total_profiles": profiles.len(),
+            "format_version": EXPORT_FORMAT_VERSION
+        },
+        "profiles": profiles
+    });
+
+    if include_embeddings {
+        let mut all_embeddings = Vec::new();
+        for profile in &profiles {
+            all_embeddings.extend(store.get_voice_embeddings(profile.id).await?);
+        }
+        export_data["embeddings"] = serde_json::to_value(all_embeddings)?;
+    }
+
+    if include_samples {
+        let mut all_samples = Vec::new();
+        for profile in &profiles {
+            all_samples.extend(store.get_speaker_samples(profile.id).await?);
+        }
+        export_data["samples"] = serde_json::to_value(all_samples)?;
+    }
+
+    Ok(export_data)
+}
+
+/// Check that every embedding fits an index of `expected` dimensions
+pub fn check_embedding_dimensions(embeddings: &[VoiceEmbedding], expected: usize) -> Result<(), ImportError> {
+    match embeddings.iter().find(|e| e.vector.len() != expected || e.dimensions as usize != e.vector.len()) {
+        Some(embedding) => Err(ImportError::DimensionMismatch {
+            embedding_id: embedding.id,
+            expected,
+            actual: embedding.vector.len(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Import profiles with their embeddings and samples, resolving name
+/// conflicts with `strategy`. Embeddings are checked against the index
+/// before anything is stored; imported embeddings are added to the index.
+pub async fn import_speakers(
+    store: &SpeakerStore,
+    index: &EmbeddingIndex,
+    import: SpeakerImport,
+    strategy: ConflictStrategy,
+) -> Result<ImportReport, ImportError> {
+    if let Some(embeddings) = &import.embeddings {
+        check_embedding_dimensions(embeddings, index.embedding_dimension())?;
+    }
+
+    let mut names: HashMap<String, Uuid> = store.list_speaker_profiles(false).await?
+        .into_iter()
+        .map(|profile| (profile.name, profile.id))
+        .collect();
+    let mut report = ImportReport::default();
+    // Export id -> stored profile receiving its embeddings and samples
+    let mut targets: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut outcomes: HashMap<Uuid, ImportOutcome> = HashMap::new();
+
+    for profile in import.profiles {
+        let source_id = profile.id;
+        let (outcome, result) = import_profile(store, &names, profile.clone(), strategy).await;
+        let (profile_id, error) = match result {
+            Ok(profile_id) => (profile_id, None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let outcome = if error.is_some() { ImportOutcome::Failed } else { outcome };
+        if let Some(error) = &error {
+            report.errors.push(format!("Failed to import profile {}: {}", source_id, error));
+        }
+        if let Some(profile_id) = profile_id {
+            if let Ok(request) = metadata::sanitize_create_profile(create_request(&profile)) {
+                names.insert(request.name, profile_id);
+            }
+            if outcome != ImportOutcome::Skipped {
+                targets.insert(source_id, profile_id);
+            }
+        }
+        outcomes.insert(source_id, outcome);
+        report.profiles.push(ProfileImport {
+            source_id,
+            profile_id: profile_id.filter(|_| outcome != ImportOutcome::Skipped),
+            name: profile.name,
+            outcome,
+            error,
+        });
+    }
+
+    // Overwritten profiles take the imported data in place of what they had
+    let overwritten: Vec<Uuid> = report.profiles.iter()
+        .filter(|p| p.outcome == ImportOutcome::Overwritten)
+        .filter_map(|p| p.profile_id)
+        .collect();
+    for profile_id in &overwritten {
+        if import.embeddings.is_some() {
+            store.delete_voice_embeddings(*profile_id).await?;
+            index.remove_speaker(*profile_id)?;
+        }
+        if import.samples.is_some() {
+            store.delete_speaker_samples(*profile_id).await?;
+        }
+    }
+
+    for embedding in import.embeddings.unwrap_or_default() {
+        let Some(&speaker_id) = targets.get(&embedding.speaker_id) else {
+            if !outcomes.contains_key(&embedding.speaker_id) {
+                report.errors.push(format!("Embedding {} belongs to no imported profile", embedding.id));
+            }
+            continue;
+        };
+        let embedding = VoiceEmbedding { id: Uuid::new_v4(), speaker_id, ..embedding };
+        match store.add_voice_embedding(embedding.clone()).await {
+            Ok(()) => {
+                report.imported_embeddings += 1;
+                if let Err(e) = index.add_embedding(embedding) {
+                    tracing::warn!("Failed to add embedding to index during import: {}", e);
+                }
+            }
+            Err(e) => report.errors.push(format!("Failed to import embedding {}: {}", embedding.id, e)),
+        }
+    }
+
+    for sample in import.samples.unwrap_or_default() {
+        let Some(&speaker_id) = targets.get(&sample.speaker_id) else {
+            if !outcomes.contains_key(&sample.speaker_id) {
+                report.errors.push(format!("Speaker sample {} belongs to no imported profile", sample.id));
+            }
+            continue;
+        };
+        let sample_id = sample.id;
+        let sample = SpeakerSample { id: Uuid::new_v4(), speaker_id, ..sample };
+        match store.add_speaker_sample(sample, SPEAKER_SAMPLE_CAP_SECONDS).await {
+            Ok(_) => report.imported_samples += 1,
+            Err(e) => report.errors.push(format!("Failed to import speaker sample {}: {}", sample_id, e)),
+        }
+    }
+
+    Ok(report)
+}
+
+fn create_request(profile: &SpeakerProfile) -> CreateSpeakerProfileRequest {
+    CreateSpeakerProfileRequest {
+        name: profile.name.clone(),
+        description: profile.description.clone(),
+        color: Some(profile.color.clone()),
+        confidence_threshold: Some(profile.confidence_threshold),
+    }
+}
+
+/// Store one imported profile; returns the stored profile it maps to
+async fn import_profile(
+    store: &SpeakerStore,
+    names: &HashMap<String, Uuid>,
+    profile: SpeakerProfile,
+    strategy: ConflictStrategy,
+) -> (ImportOutcome, Result<Option<Uuid>>) {
+    let request = match metadata::sanitize_create_profile(create_request(&profile)) {
+        Ok(request) => request,
+        Err(e) => return (ImportOutcome::Failed, Err(e.into())),
+    };
+    let existing = names.get(&request.name).copied();
+
+    match (existing, strategy) {
+        (Some(existing), ConflictStrategy::Skip) => (ImportOutcome::Skipped, Ok(Some(existing))),
+        (Some(existing), ConflictStrategy::Overwrite) => {
+            let update = UpdateSpeakerProfileRequest {
+                name: None,
+                description: Some(request.description.unwrap_or_default()),
+                color: request.color,
+                confidence_threshold: request.confidence_threshold,
+                is_active: Some(profile.is_active),
+            };
+            let updated = store.update_speaker_profile(existing, update).await
+                .map(|updated| updated.map(|p| p.id));
+            (ImportOutcome::Overwritten, updated)
+        }
+        (existing, _) => {
+            let outcome = if existing.is_some() { ImportOutcome::Duplicated } else { ImportOutcome::Created };
+            let created = store.create_speaker_profile(request).await.map(|p| Some(p.id));
+            (outcome, created)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Database;
+    use tempfile::NamedTempFile;
+
+    async fn create_test_store() -> (SpeakerStore, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).await.unwrap();
+        db.migrate().await.unwrap();
+        (SpeakerStore::new(db), temp_file)
+    }
+
+    async fn profile_with_embedding(store: &SpeakerStore, name: &str, vector: Vec<f32>) -> SpeakerProfile {
+        let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+            name: name.to_string(),
+            description: None,
+            color: None,
+            confidence_threshold: None,
+        }).await.unwrap();
+        let embedding = VoiceEmbedding::new(profile.id, vector, "test_model".to_string(), 0.9, 5.0);
+        store.add_voice_embedding(embedding).await.unwrap();
+        profile
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_names_follow_the_strategy() {
+        let (store, _temp_file) = create_test_store().await;
+        let index = EmbeddingIndex::new(3, 4);
+        let alice = profile_with_embedding(&store, "Alice", vec![1.0, 0.0, 0.0]).await;
+        let export: SpeakerImport = serde_json::from_value(export_speakers(&store, true, false).await.unwrap()).unwrap();
+
+        let skipped = import_speakers(&store, &index, export.clone(), ConflictStrategy::Skip).await.unwrap();
+        assert_eq!(skipped.profiles[0].outcome, ImportOutcome::Skipped);
+        assert_eq!((skipped.imported_profiles(), skipped.imported_embeddings), (0, 0));
+        assert!(skipped.errors.is_empty());
+
+        let duplicated = import_speakers(&store, &index, export.clone(), ConflictStrategy::Duplicate).await.unwrap();
+        assert_eq!(duplicated.profiles[0].outcome, ImportOutcome::Duplicated);
+        let duplicate_id = duplicated.profiles[0].profile_id.unwrap();
+        assert_ne!(duplicate_id, alice.id);
+        assert_eq!(store.get_voice_embeddings(duplicate_id).await.unwrap().len(), 1);
+
+        // Overwriting replaces the embeddings of a stored profile with that name
+        let mut changed = export.clone();
+        changed.embeddings.as_mut().unwrap()[0].vector = vec![0.0, 1.0, 0.0];
+        let overwritten = import_speakers(&store, &index, changed, ConflictStrategy::Overwrite).await.unwrap();
+        assert_eq!(overwritten.profiles[0].outcome, ImportOutcome::Overwritten);
+        let target = overwritten.profiles[0].profile_id.unwrap();
+        let embeddings = store.get_voice_embeddings(target).await.unwrap();
+        assert_eq!(embeddings.len(), 1);
+        assert_eq!(embeddings[0].vector, vec![0.0, 1.0, 0.0]);
+        assert_eq!(store.list_speaker_profiles(false).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_embedding_dimensions_are_rejected() {
+        let (store, _temp_file) = create_test_store().await;
+        profile_with_embedding(&store, "Bob", vec![0.5; 4]).await;
+        let export: SpeakerImport = serde_json::from_value(export_speakers(&store, true, false).await.unwrap()).unwrap();
+        let embedding_id = export.embeddings.as_ref().unwrap()[0].id;
+
+        let result = import_speakers(&store, &EmbeddingIndex::new(8, 4), export, ConflictStrategy::Duplicate).await;
+        match result {
+            Err(ImportError::DimensionMismatch { embedding_id: id, expected: 8, actual: 4 }) => assert_eq!(id, embedding_id),
+            other => panic!("expected a dimension mismatch, got {:?}", other),
+        }
+        // Nothing was stored
+        assert_eq!(store.list_speaker_profiles(false).await.unwrap().len(), 1);
+    }
+}
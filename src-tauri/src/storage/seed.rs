@@ -60,6 +60,7 @@ impl SeedManager {
             
             // Clear all tables in dependency order
             conn.execute("DELETE FROM meeting_speakers", [])?;
+            conn.execute("DELETE FROM speaker_samples", [])?;
             conn.execute("DELETE FROM voice_embeddings", [])?;
             conn.execute("DELETE FROM speaker_profiles", [])?;
             
@@ -136,6 +137,7 @@ impl SeedManager {
             
             // Clear existing data first
             tx.execute("DELETE FROM meeting_speakers", [])?;
+            tx.execute("DELETE FROM speaker_samples", [])?;
             tx.execute("DELETE FROM voice_embeddings", [])?;
             tx.execute("DELETE FROM speaker_profiles", [])?;
             
@@ -0,0 +1,507 @@
+use anyhow::{Context, Result};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::task;
+
+use crate::storage::Database;
+use crate::storage::transcript_search::{self, SearchOptions, SearchPage};
+
+/// Lifecycle of a persisted session. Sessions still "active" when the app
+/// starts were cut short by a crash or forced quit and become "interrupted".
+pub const SESSION_STATUS_ACTIVE: &str = "active";
+pub const SESSION_STATUS_COMPLETED: &str = "completed";
+pub const SESSION_STATUS_INTERRUPTED: &str = "interrupted";
+
+/// A session row as listed in the session history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSessionSummary {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    /// Unix seconds
+    #[serde(rename = "startedAt")]
+    pub started_at: i64,
+    #[serde(rename = "endedAt")]
+    pub ended_at: Option<i64>,
+    pub status: String,
+    #[serde(rename = "continuationOf")]
+    pub continuation_of: Option<String>,
+    #[serde(rename = "totalDuration")]
+    pub total_duration: f64,
+    #[serde(rename = "segmentCount")]
+    pub segment_count: u32,
+    #[serde(rename = "audioPath")]
+    pub audio_path: Option<String>,
+}
+
+/// A saved session with its configuration and full transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSession {
+    #[serde(flatten)]
+    pub summary: SavedSessionSummary,
+    pub config: serde_json::Value,
+    pub segments: Vec<serde_json::Value>,
+}
+
+/// Persistence for transcription sessions and their segments
+pub struct SessionStore {
+    db: Database,
+}
+
+impl SessionStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Record a newly started session
+    pub async fn create_session(
+        &self,
+        session_id: &str,
+        started_at: i64,
+        continuation_of: Option<String>,
+        config: serde_json::Value,
+    ) -> Result<()> {
+        let connection = Arc::clone(&self.db.connection);
+        let session_id = session_id.to_string();
+
+        task::spawn_blocking(move || -> Result<()> {
+            let conn = connection.lock().unwrap();
+            conn.execute(
+                "INSERT OR IGNORE INTO sessions (id, started_at, status, continuation_of, config_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![
+                    session_id,
+                    started_at,
+                    SESSION_STATUS_ACTIVE,
+                    continuation_of,
+                    config.to_string(),
+                ],
+            ).context("Failed to create session")?;
+            Ok(())
+        }).await?
+    }
+
+    /// Write segments starting at position `first_seq` in one transaction.
+    /// Rewriting a position replaces the stored segment.
+    pub async fn append_segments(
+        &self,
+        session_id: &str,
+        first_seq: usize,
+        segments: Vec<serde_json::Value>,
+    ) -> Result<()> {
+        if segments.is_empty() {
+            return Ok(());
+        }
+        let connection = Arc::clone(&self.db.connection);
+        let session_id = session_id.to_string();
+
+        task::spawn_blocking(move || -> Result<()> {
+            let mut conn = connection.lock().unwrap();
+            let tx = conn.transaction()?;
+            write_segments(&tx, &session_id, first_seq, &segments)?;
+            tx.commit().context("Failed to commit transcript segments")?;
+            Ok(())
+        }).await?
+    }
+
+    /// Close a session with `status` ("completed" or "interrupted") and replace
+    /// its transcript with the final segments
+    pub async fn finish_session(
+        &self,
+        session_id: &str,
+        status: &str,
+        ended_at: i64,
+        total_duration: f64,
+        audio_path: Option<String>,
+        segments: Vec<serde_json::Value>,
+    ) -> Result<()> {
+        let connection = Arc::clone(&self.db.connection);
+        let session_id = session_id.to_string();
+        let status = status.to_string();
+
+        task::spawn_blocking(move || -> Result<()> {
+            let mut conn = connection.lock().unwrap();
+            let tx = conn.transaction()?;
+            tx.execute(
+                "UPDATE sessions SET status = ?2, ended_at = ?3, total_duration = ?4, audio_path = ?5
+                 WHERE id = ?1",
+                rusqlite::params![session_id, status, ended_at, total_duration, audio_path],
+            ).context("Failed to finish session")?;
+            tx.execute("DELETE FROM transcript_segments WHERE session_id = ?1", [&session_id])?;
+            write_segments(&tx, &session_id, 0, &segments)?;
+            tx.commit().context("Failed to commit finished session")?;
+            Ok(())
+        }).await?
+    }
+
+    /// Replace the transcript of a saved session, keeping its status (e.g.
+    /// after re-diarization). Speaker names of ids no longer in the transcript
+    /// are dropped. Returns false when the session is not saved.
+    pub async fn replace_segments(&self, session_id: &str, segments: Vec<serde_json::Value>) -> Result<bool> {
+        let connection = Arc::clone(&self.db.connection);
+        let session_id = session_id.to_string();
+
+        task::spawn_blocking(move || -> Result<bool> {
+            let mut conn = connection.lock().unwrap();
+            let tx = conn.transaction()?;
+            let exists = tx.query_row("SELECT 1 FROM sessions WHERE id = ?1", [&session_id], |_| Ok(()))
+                .optional()?
+                .is_some();
+            if !exists {
+                return Ok(false);
+            }
+            tx.execute("DELETE FROM transcript_segments WHERE session_id = ?1", [&session_id])?;
+            write_segments(&tx, &session_id, 0, &segments)?;
+            tx.execute(
+                "DELETE FROM session_speakers WHERE session_id = ?1 AND speaker_id NOT IN
+                    (SELECT speaker FROM transcript_segments WHERE session_id = ?1 AND speaker IS NOT NULL)",
+                [&session_id],
+            )?;
+            tx.commit().context("Failed to commit replaced transcript")?;
+            Ok(true)
+        }).await?
+    }
+
+    /// Mark sessions left "active" by a previous run as interrupted. Their end
+    /// time is taken from the last saved segment. Returns the affected ids.
+    pub async fn mark_interrupted(&self) -> Result<Vec<String>> {
+        let connection = Arc::clone(&self.db.connection);
+
+        task::spawn_blocking(move || -> Result<Vec<String>> {
+            let mut conn = connection.lock().unwrap();
+            let tx = conn.transaction()?;
+            let ids = {
+                let mut stmt = tx.prepare("SELECT id FROM sessions WHERE status = ?1")?;
+                let rows = stmt.query_map([SESSION_STATUS_ACTIVE], |row| row.get::<_, String>(0))?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            };
+            tx.execute(
+                "UPDATE sessions SET
+                    status = ?1,
+                    total_duration = COALESCE(
+                        (SELECT MAX(end_time) FROM transcript_segments WHERE session_id = sessions.id),
+                        total_duration
+                    ),
+                    ended_at = started_at + CAST(COALESCE(
+                        (SELECT MAX(end_time) FROM transcript_segments WHERE session_id = sessions.id),
+                        total_duration
+                    ) AS INTEGER)
+                 WHERE status = ?2",
+                [SESSION_STATUS_INTERRUPTED, SESSION_STATUS_ACTIVE],
+            ).context("Failed to mark interrupted sessions")?;
+            tx.commit()?;
+            Ok(ids)
+        }).await?
+    }
+
+    /// All saved sessions, newest first
+    pub async fn list_sessions(&self) -> Result<Vec<SavedSessionSummary>> {
+        let connection = Arc::clone(&self.db.connection);
+
+        task::spawn_blocking(move || -> Result<Vec<SavedSessionSummary>> {
+            let conn = connection.lock().unwrap();
+            let mut stmt = conn.prepare(&format!("{} ORDER BY s.started_at DESC, s.id", SUMMARY_QUERY))?;
+            let sessions = stmt.query_map([], summary_from_row)?
+                .collect::<Result<Vec<_>, _>>()
+                .context("Failed to list sessions")?;
+            Ok(sessions)
+        }).await?
+    }
+
+    /// A saved session with its segments in transcript order
+    pub async fn get_session(&self, session_id: &str) -> Result<Option<SavedSession>> {
+        let connection = Arc::clone(&self.db.connection);
+        let session_id = session_id.to_string();
+
+        task::spawn_blocking(move || -> Result<Option<SavedSession>> {
+            let conn = connection.lock().unwrap();
+            let row = conn.query_row(
+                &format!("{} WHERE s.id = ?1", SUMMARY_QUERY),
+                [&session_id],
+                |row| Ok((summary_from_row(row)?, row.get::<_, String>(8)?)),
+            ).optional().context("Failed to load session")?;
+            let Some((summary, config_json)) = row else {
+                return Ok(None);
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT segment_json FROM transcript_segments WHERE session_id = ?1 ORDER BY seq",
+            )?;
+            let segments = stmt.query_map([&session_id], |row| row.get::<_, String>(0))?
+                .map(|json| Ok(serde_json::from_str(&json?)?))
+                .collect::<Result<Vec<serde_json::Value>>>()
+                .context("Failed to load transcript segments")?;
+
+            Ok(Some(SavedSession {
+                summary,
+                config: serde_json::from_str(&config_json).unwrap_or(serde_json::Value::Null),
+                segments,
+            }))
+        }).await?
+    }
+
+    /// Saved segments matching `query`, best first (see [`transcript_search`])
+    pub async fn search(&self, query: &str, options: &SearchOptions) -> Result<SearchPage> {
+        let connection = Arc::clone(&self.db.connection);
+        let query = query.to_string();
+        let options = options.clone();
+
+        task::spawn_blocking(move || -> Result<SearchPage> {
+            let conn = connection.lock().unwrap();
+            transcript_search::search(&conn, &query, &options).context("Failed to search transcripts")
+        }).await?
+    }
+
+    /// Id of the session that continues `session_id` after a rollover, if any
+    pub async fn get_continuation(&self, session_id: &str) -> Result<Option<String>> {
+        let connection = Arc::clone(&self.db.connection);
+        let session_id = session_id.to_string();
+
+        task::spawn_blocking(move || -> Result<Option<String>> {
+            let conn = connection.lock().unwrap();
+            conn.query_row(
+                "SELECT id FROM sessions WHERE continuation_of = ?1 ORDER BY started_at LIMIT 1",
+                [&session_id],
+                |row| row.get(0),
+            ).optional().context("Failed to load session continuation")
+        }).await?
+    }
+
+    /// Delete a session and its segments; returns false when it did not exist
+    pub async fn delete_session(&self, session_id: &str) -> Result<bool> {
+        let connection = Arc::clone(&self.db.connection);
+        let session_id = session_id.to_string();
+
+        task::spawn_blocking(move || -> Result<bool> {
+            let conn = connection.lock().unwrap();
+            let deleted = conn.execute("DELETE FROM sessions WHERE id = ?1", [&session_id])
+                .context("Failed to delete session")?;
+            Ok(deleted > 0)
+        }).await?
+    }
+}
+
+const SUMMARY_QUERY: &str = "SELECT s.id, s.started_at, s.ended_at, s.status, s.continuation_of,
+        s.total_duration, s.audio_path,
+        (SELECT COUNT(*) FROM transcript_segments t WHERE t.session_id = s.id),
+        s.config_json
+     FROM sessions s";
+
+fn summary_from_row(row: &rusqlite::Row) -> rusqlite::Result<SavedSessionSummary> {
+    Ok(SavedSessionSummary {
+        session_id: row.get(0)?,
+        started_at: row.get(1)?,
+        ended_at: row.get(2)?,
+        status: row.get(3)?,
+        continuation_of: row.get(4)?,
+        total_duration: row.get(5)?,
+        audio_path: row.get(6)?,
+        segment_count: row.get(7)?,
+    })
+}
+
+fn write_segments(
+    tx: &rusqlite::Transaction,
+    session_id: &str,
+    first_seq: usize,
+    segments: &[serde_json::Value],
+) -> Result<()> {
+    // An upsert rather than INSERT OR REPLACE, whose implicit delete would
+    // not reach the search index triggers
+    let mut stmt = tx.prepare(
+        "INSERT INTO transcript_segments
+            (session_id, seq, start_time, end_time, speaker, text, segment_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT (session_id, seq) DO UPDATE SET
+            start_time = excluded.start_time,
+            end_time = excluded.end_time,
+            speaker = excluded.speaker,
+            text = excluded.text,
+            segment_json = excluded.segment_json",
+    )?;
+    for (offset, segment) in segments.iter().enumerate() {
+        stmt.execute(rusqlite::params![
+            session_id,
+            (first_seq + offset) as i64,
+            segment["startTime"].as_f64(),
+            segment["endTime"].as_f64(),
+            segment["speaker"].as_str(),
+            segment["text"].as_str().unwrap_or_default(),
+            segment.to_string(),
+        ]).context("Failed to write transcript segment")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    async fn open_store(path: &std::path::Path) -> SessionStore {
+        let db = Database::new(path).await.unwrap();
+        db.migrate().await.unwrap();
+        SessionStore::new(db)
+    }
+
+    fn segment(index: usize) -> serde_json::Value {
+        json!({
+            "text": format!("segment {}", index),
+            "startTime": index as f64 * 2.0,
+            "endTime": index as f64 * 2.0 + 1.5,
+            "speaker": "speaker_1",
+            "confidence": 0.9,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_segments_round_trip_in_order() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let store = open_store(temp_file.path()).await;
+
+        store.create_session("s1", 1_700_000_000, None, json!({"language": "ja"})).await.unwrap();
+        store.append_segments("s1", 0, vec![segment(0), segment(1)]).await.unwrap();
+        store.append_segments("s1", 2, vec![segment(2)]).await.unwrap();
+        // Rewriting a position replaces it instead of duplicating
+        store.append_segments("s1", 2, vec![segment(2)]).await.unwrap();
+
+        let saved = store.get_session("s1").await.unwrap().unwrap();
+        assert_eq!(saved.summary.status, SESSION_STATUS_ACTIVE);
+        assert_eq!(saved.summary.segment_count, 3);
+        assert_eq!(saved.config["language"], "ja");
+        let texts: Vec<&str> = saved.segments.iter().map(|s| s["text"].as_str().unwrap()).collect();
+        assert_eq!(texts, vec!["segment 0", "segment 1", "segment 2"]);
+        assert_eq!(saved.segments[1]["confidence"], 0.9);
+
+        assert!(store.get_session("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_crash_leaves_readable_interrupted_session() {
+        let temp_file = NamedTempFile::new().unwrap();
+        {
+            let store = open_store(temp_file.path()).await;
+            store.create_session("crashed", 1_700_000_000, None, json!({})).await.unwrap();
+            store.append_segments("crashed", 0, (0..5).map(segment).collect()).await.unwrap();
+            store.create_session("finished", 1_700_000_100, None, json!({})).await.unwrap();
+            store.finish_session("finished", SESSION_STATUS_COMPLETED, 1_700_000_160, 60.0, None, vec![segment(0)]).await.unwrap();
+            // App state and connection dropped without stopping the session
+        }
+
+        let store = open_store(temp_file.path()).await;
+        let interrupted = store.mark_interrupted().await.unwrap();
+        assert_eq!(interrupted, vec!["crashed".to_string()]);
+
+        let saved = store.get_session("crashed").await.unwrap().unwrap();
+        assert_eq!(saved.summary.status, SESSION_STATUS_INTERRUPTED);
+        assert_eq!(saved.segments.len(), 5);
+        assert_eq!(saved.summary.total_duration, 9.5);
+        assert_eq!(saved.summary.ended_at, Some(1_700_000_009));
+
+        let finished = store.get_session("finished").await.unwrap().unwrap();
+        assert_eq!(finished.summary.status, SESSION_STATUS_COMPLETED);
+
+        // A second startup has nothing left to mark
+        assert!(store.mark_interrupted().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_finish_replaces_segments_and_list_is_newest_first() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let store = open_store(temp_file.path()).await;
+
+        store.create_session("first", 100, None, json!({})).await.unwrap();
+        store.append_segments("first", 0, (0..4).map(segment).collect()).await.unwrap();
+        // Final transcript after re-diarization has fewer segments
+        let mut relabelled = segment(0);
+        relabelled["speaker"] = json!("speaker_2");
+        store.finish_session("first", SESSION_STATUS_COMPLETED, 130, 30.0, Some("/tmp/first.wav".into()), vec![relabelled]).await.unwrap();
+        store.create_session("second", 200, Some("first".to_string()), json!({})).await.unwrap();
+
+        let sessions = store.list_sessions().await.unwrap();
+        assert_eq!(sessions.iter().map(|s| s.session_id.as_str()).collect::<Vec<_>>(), vec!["second", "first"]);
+        assert_eq!(sessions[0].continuation_of.as_deref(), Some("first"));
+        assert_eq!(sessions[1].segment_count, 1);
+        assert_eq!(sessions[1].ended_at, Some(130));
+        assert_eq!(sessions[1].audio_path.as_deref(), Some("/tmp/first.wav"));
+
+        let first = store.get_session("first").await.unwrap().unwrap();
+        assert_eq!(first.segments[0]["speaker"], "speaker_2");
+    }
+
+    #[tokio::test]
+    async fn test_continuation_follows_rollover_parts() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let store = open_store(temp_file.path()).await;
+
+        store.create_session("first", 100, None, json!({})).await.unwrap();
+        store.finish_session("first", SESSION_STATUS_COMPLETED, 130, 30.0, None, vec![segment(0)]).await.unwrap();
+        store.create_session("second", 130, Some("first".to_string()), json!({})).await.unwrap();
+        store.create_session("unrelated", 140, None, json!({})).await.unwrap();
+
+        assert_eq!(store.get_continuation("first").await.unwrap().as_deref(), Some("second"));
+        assert!(store.get_continuation("second").await.unwrap().is_none());
+        assert!(store.get_continuation("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replace_segments_keeps_status_and_drops_gone_speakers() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let store = open_store(temp_file.path()).await;
+
+        let mut named = segment(0);
+        named["speakerName"] = json!("Sato");
+        store.create_session("s1", 100, None, json!({})).await.unwrap();
+        store.finish_session("s1", SESSION_STATUS_COMPLETED, 130, 30.0, None, vec![named, segment(1)]).await.unwrap();
+
+        let mut relabelled = segment(0);
+        relabelled["speaker"] = json!("speaker_2");
+        assert!(store.replace_segments("s1", vec![relabelled, segment(1)]).await.unwrap());
+        assert!(!store.replace_segments("missing", vec![segment(0)]).await.unwrap());
+
+        let saved = store.get_session("s1").await.unwrap().unwrap();
+        assert_eq!(saved.summary.status, SESSION_STATUS_COMPLETED);
+        assert_eq!(saved.summary.ended_at, Some(130));
+        assert_eq!(saved.segments[0]["speaker"], "speaker_2");
+        // speaker_1 still speaks in segment 1 and keeps its name
+        let connection = Arc::clone(&store.db.connection);
+        let names: Vec<String> = task::spawn_blocking(move || {
+            let conn = connection.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT speaker_id FROM session_speakers WHERE session_id = 's1'").unwrap();
+            let names = stmt.query_map([], |row| row.get(0)).unwrap().collect::<rusqlite::Result<Vec<String>>>();
+            names
+        }).await.unwrap().unwrap();
+        assert_eq!(names, vec!["speaker_1".to_string()]);
+
+        let mut gone = segment(1);
+        gone["speaker"] = json!("speaker_3");
+        store.replace_segments("s1", vec![gone]).await.unwrap();
+        let connection = Arc::clone(&store.db.connection);
+        let remaining: i64 = task::spawn_blocking(move || {
+            let conn = connection.lock().unwrap();
+            conn.query_row("SELECT COUNT(*) FROM session_speakers", [], |row| row.get(0))
+        }).await.unwrap().unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_removes_segments() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let store = open_store(temp_file.path()).await;
+
+        store.create_session("s1", 100, None, json!({})).await.unwrap();
+        store.append_segments("s1", 0, vec![segment(0), segment(1)]).await.unwrap();
+
+        assert!(store.delete_session("s1").await.unwrap());
+        assert!(!store.delete_session("s1").await.unwrap());
+        assert!(store.get_session("s1").await.unwrap().is_none());
+
+        let connection = Arc::clone(&store.db.connection);
+        let remaining: i64 = task::spawn_blocking(move || {
+            let conn = connection.lock().unwrap();
+            conn.query_row("SELECT COUNT(*) FROM transcript_segments", [], |row| row.get(0))
+        }).await.unwrap().unwrap();
+        assert_eq!(remaining, 0);
+    }
+}
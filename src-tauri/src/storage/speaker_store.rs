@@ -7,11 +7,15 @@ use tokio::task;
 use uuid::Uuid;
 
 use crate::models::{
-    SpeakerProfile, VoiceCharacteristics, VoiceEmbedding, MeetingSpeaker,
+    SpeakerProfile, VoiceCharacteristics, VoiceEmbedding, SpeakerSample, MeetingSpeaker,
     SpeakerStats, MeetingStats, SimilarSpeaker,
     CreateSpeakerProfileRequest, UpdateSpeakerProfileRequest,
 };
 use crate::storage::{Database, vector_to_blob, blob_to_vector, uuid_to_string, string_to_uuid};
+use crate::storage::embedding_index::content_fingerprint;
+
+/// Seconds of reference audio kept per speaker; older samples are evicted first
+pub const SPEAKER_SAMPLE_CAP_SECONDS: f32 = 60.0;
 
 /// Speaker storage operations
 pub struct SpeakerStore {
@@ -296,6 +300,210 @@ impl SpeakerStore {
         }).await?
     }
 
+    /// Fingerprint of the voice embeddings of active speaker profiles, as
+    /// recorded in a saved embedding index built from them
+    pub async fn active_voice_embeddings_fingerprint(&self) -> Result<u64> {
+        let connection = Arc::clone(&self.db.connection);
+
+        task::spawn_blocking(move || -> Result<u64> {
+            let conn = connection.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT e.id, e.speaker_id FROM voice_embeddings e
+                 JOIN speaker_profiles p ON p.id = e.speaker_id
+                 WHERE p.is_active = 1",
+            )?;
+            let ids = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                .map(|ids| -> Result<(Uuid, Uuid)> {
+                    let (id, speaker_id) = ids?;
+                    Ok((string_to_uuid(&id)?, string_to_uuid(&speaker_id)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(content_fingerprint(ids))
+        }).await?
+    }
+
+    /// Add a reference audio sample for a speaker, then evict the speaker's
+    /// oldest samples until their total duration fits `cap_seconds`. The new
+    /// sample is always kept. Returns the ids of the evicted samples.
+    pub async fn add_speaker_sample(&self, sample: SpeakerSample, cap_seconds: f32) -> Result<Vec<Uuid>> {
+        let connection = Arc::clone(&self.db.connection);
+
+        task::spawn_blocking(move || -> Result<Vec<Uuid>> {
+            let conn = connection.lock().unwrap();
+            let tx = conn.unchecked_transaction()?;
+            let speaker_id_str = uuid_to_string(&sample.speaker_id);
+
+            tx.execute(
+                "INSERT INTO speaker_samples (
+                    id, speaker_id, wav, sample_rate, duration_seconds, created_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    uuid_to_string(&sample.id),
+                    speaker_id_str,
+                    sample.wav,
+                    sample.sample_rate,
+                    sample.duration_seconds,
+                    sample.created_at.to_rfc3339(),
+                ],
+            ).context("Failed to insert speaker sample")?;
+
+            // Newest first, so the running total reaches the cap at the oldest samples
+            let samples: Vec<(String, f32)> = {
+                let mut stmt = tx.prepare(
+                    "SELECT id, duration_seconds FROM speaker_samples
+                     WHERE speaker_id = ?1
+                     ORDER BY created_at DESC, rowid DESC"
+                )?;
+                let rows = stmt.query_map([&speaker_id_str], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            };
+            let mut kept_seconds = 0.0;
+            let mut evicted = Vec::new();
+            for (index, (id, duration)) in samples.into_iter().enumerate() {
+                kept_seconds += duration;
+                if index > 0 && kept_seconds > cap_seconds {
+                    tx.execute("DELETE FROM speaker_samples WHERE id = ?1", [&id])?;
+                    evicted.push(string_to_uuid(&id)?);
+                }
+            }
+
+            tx.commit()?;
+            Ok(evicted)
+        }).await?
+    }
+
+    /// Reference audio samples of a speaker, oldest first
+    pub async fn get_speaker_samples(&self, speaker_id: Uuid) -> Result<Vec<SpeakerSample>> {
+        let connection = Arc::clone(&self.db.connection);
+        let speaker_id_str = uuid_to_string(&speaker_id);
+
+        task::spawn_blocking(move || -> Result<Vec<SpeakerSample>> {
+            let conn = connection.lock().unwrap();
+
+            let mut stmt = conn.prepare(
+                "SELECT id, speaker_id, wav, sample_rate, duration_seconds, created_at
+                 FROM speaker_samples WHERE speaker_id = ?1
+                 ORDER BY created_at ASC, rowid ASC"
+            )?;
+
+            let samples = stmt.query_map([&speaker_id_str], |row| {
+                row_to_speaker_sample(row)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(samples)
+        }).await?
+    }
+
+    /// Delete one reference audio sample
+    pub async fn delete_speaker_sample(&self, sample_id: Uuid) -> Result<bool> {
+        let rows_affected = self.db.execute(
+            "DELETE FROM speaker_samples WHERE id = ?1",
+            [uuid_to_string(&sample_id)],
+        ).await?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Delete all voice embeddings of a speaker
+    pub async fn delete_voice_embeddings(&self, speaker_id: Uuid) -> Result<usize> {
+        self.db.execute(
+            "DELETE FROM voice_embeddings WHERE speaker_id = ?1",
+            [uuid_to_string(&speaker_id)],
+        ).await
+    }
+
+    /// Delete all reference audio samples of a speaker
+    pub async fn delete_speaker_samples(&self, speaker_id: Uuid) -> Result<usize> {
+        self.db.execute(
+            "DELETE FROM speaker_samples WHERE speaker_id = ?1",
+            [uuid_to_string(&speaker_id)],
+        ).await
+    }
+
+    /// Make the meeting's speaker links match `links`: update the talk time of
+    /// profiles still present (keeping verification and notes), add new ones
+    /// and drop profiles no longer attributed. Links to unknown profiles are
+    /// skipped. Returns the number of links stored.
+    pub async fn sync_meeting_speakers(&self, meeting_id: Uuid, links: Vec<MeetingSpeaker>) -> Result<usize> {
+        let connection = Arc::clone(&self.db.connection);
+
+        task::spawn_blocking(move || -> Result<usize> {
+            let conn = connection.lock().unwrap();
+            let tx = conn.unchecked_transaction()?;
+            let meeting_id_str = uuid_to_string(&meeting_id);
+
+            let kept: Vec<String> = links.iter().map(|link| uuid_to_string(&link.speaker_id)).collect();
+            let existing: Vec<String> = {
+                let mut stmt = tx.prepare("SELECT speaker_id FROM meeting_speakers WHERE meeting_id = ?1")?;
+                let rows = stmt.query_map([&meeting_id_str], |row| row.get(0))?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            };
+            for speaker_id in existing.iter().filter(|id| !kept.contains(id)) {
+                tx.execute(
+                    "DELETE FROM meeting_speakers WHERE meeting_id = ?1 AND speaker_id = ?2",
+                    [&meeting_id_str, speaker_id],
+                )?;
+            }
+
+            let mut stored = 0;
+            for link in &links {
+                stored += tx.execute(
+                    "INSERT INTO meeting_speakers (
+                        id, meeting_id, speaker_id, display_name,
+                        speaking_time_seconds, segment_count, average_confidence,
+                        first_spoken_at, last_spoken_at, is_verified, notes
+                    )
+                    SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
+                    WHERE EXISTS (SELECT 1 FROM speaker_profiles WHERE id = ?3)
+                    ON CONFLICT (meeting_id, speaker_id) DO UPDATE SET
+                        display_name = excluded.display_name,
+                        speaking_time_seconds = excluded.speaking_time_seconds,
+                        segment_count = excluded.segment_count,
+                        average_confidence = excluded.average_confidence,
+                        first_spoken_at = excluded.first_spoken_at,
+                        last_spoken_at = excluded.last_spoken_at",
+                    rusqlite::params![
+                        uuid_to_string(&link.id),
+                        meeting_id_str,
+                        uuid_to_string(&link.speaker_id),
+                        link.display_name,
+                        link.speaking_time_seconds,
+                        link.segment_count,
+                        link.average_confidence,
+                        link.first_spoken_at.to_rfc3339(),
+                        link.last_spoken_at.to_rfc3339(),
+                        link.is_verified,
+                        link.notes,
+                    ],
+                ).context("Failed to store meeting speaker")?;
+            }
+
+            tx.commit()?;
+            Ok(stored)
+        }).await?
+    }
+
+    /// Speaker links of a meeting, longest talk time first
+    pub async fn get_meeting_speakers(&self, meeting_id: Uuid) -> Result<Vec<MeetingSpeaker>> {
+        let connection = Arc::clone(&self.db.connection);
+        let meeting_id_str = uuid_to_string(&meeting_id);
+
+        task::spawn_blocking(move || -> Result<Vec<MeetingSpeaker>> {
+            let conn = connection.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT id, meeting_id, speaker_id, display_name, speaking_time_seconds,
+                        segment_count, average_confidence, first_spoken_at, last_spoken_at,
+                        is_verified, notes
+                 FROM meeting_speakers WHERE meeting_id = ?1
+                 ORDER BY speaking_time_seconds DESC"
+            )?;
+            let links = stmt.query_map([&meeting_id_str], row_to_meeting_speaker)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(links)
+        }).await?
+    }
+
     /// Search for similar speakers based on embedding
     pub async fn find_similar_speakers(
         &self,
@@ -461,6 +669,51 @@ fn row_to_voice_embedding(row: &Row) -> Result<VoiceEmbedding, rusqlite::Error>
     })
 }
 
+/// Convert database row to MeetingSpeaker
+fn row_to_meeting_speaker(row: &Row) -> Result<MeetingSpeaker, rusqlite::Error> {
+    // Seeded rows carry SQLite's own timestamp format
+    let timestamp = |column: &str| -> Result<DateTime<Utc>, rusqlite::Error> {
+        let text = row.get::<_, String>(column)?;
+        DateTime::parse_from_rfc3339(&text)
+            .map(|time| time.with_timezone(&Utc))
+            .or_else(|_| chrono::NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S").map(|time| time.and_utc()))
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, column.to_string(), rusqlite::types::Type::Text))
+    };
+
+    Ok(MeetingSpeaker {
+        id: string_to_uuid(&row.get::<_, String>("id")?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "id".to_string(), rusqlite::types::Type::Text))?,
+        meeting_id: string_to_uuid(&row.get::<_, String>("meeting_id")?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "meeting_id".to_string(), rusqlite::types::Type::Text))?,
+        speaker_id: string_to_uuid(&row.get::<_, String>("speaker_id")?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "speaker_id".to_string(), rusqlite::types::Type::Text))?,
+        display_name: row.get("display_name")?,
+        speaking_time_seconds: row.get("speaking_time_seconds")?,
+        segment_count: row.get("segment_count")?,
+        average_confidence: row.get("average_confidence")?,
+        first_spoken_at: timestamp("first_spoken_at")?,
+        last_spoken_at: timestamp("last_spoken_at")?,
+        is_verified: row.get("is_verified")?,
+        notes: row.get("notes")?,
+    })
+}
+
+/// Convert database row to SpeakerSample
+fn row_to_speaker_sample(row: &Row) -> Result<SpeakerSample, rusqlite::Error> {
+    Ok(SpeakerSample {
+        id: string_to_uuid(&row.get::<_, String>("id")?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "id".to_string(), rusqlite::types::Type::Text))?,
+        speaker_id: string_to_uuid(&row.get::<_, String>("speaker_id")?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "speaker_id".to_string(), rusqlite::types::Type::Text))?,
+        wav: row.get("wav")?,
+        sample_rate: row.get("sample_rate")?,
+        duration_seconds: row.get::<_, f64>("duration_seconds")? as f32,
+        created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>("created_at")?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "created_at".to_string(), rusqlite::types::Type::Text))?
+            .with_timezone(&Utc),
+    })
+}
+
 /// Calculate cosine similarity between two vectors
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
@@ -547,6 +800,28 @@ mod tests {
         assert_eq!(embeddings[0].vector, vec![1.0, 0.5, -0.3, 2.1]);
     }
 
+    #[tokio::test]
+    async fn test_embedding_fingerprint_follows_contents_not_count() {
+        let (store, _temp_file) = create_test_store().await;
+        let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+            name: "Fingerprinted".to_string(),
+            description: None,
+            color: None,
+            confidence_threshold: None,
+        }).await.unwrap();
+        let embedding = |vector: Vec<f32>| VoiceEmbedding::new(profile.id, vector, "test_model".to_string(), 0.9, 5.0);
+
+        let first = embedding(vec![1.0, 0.0, 0.0, 0.0]);
+        store.add_voice_embedding(first.clone()).await.unwrap();
+        let fingerprint = store.active_voice_embeddings_fingerprint().await.unwrap();
+        assert_eq!(fingerprint, content_fingerprint([(first.id, profile.id)]));
+
+        // Replaced by another embedding: same count, different contents
+        store.delete_voice_embeddings(profile.id).await.unwrap();
+        store.add_voice_embedding(embedding(vec![0.0, 1.0, 0.0, 0.0])).await.unwrap();
+        assert_ne!(store.active_voice_embeddings_fingerprint().await.unwrap(), fingerprint);
+    }
+
     #[tokio::test]
     async fn test_similarity_search() {
         let (store, _temp_file) = create_test_store().await;
@@ -579,4 +854,95 @@ mod tests {
         assert_eq!(results[0].speaker.id, profile.id);
         assert!(results[0].similarity_score > 0.8);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_speaker_samples_are_capped_oldest_first() {
+        let (store, _temp_file) = create_test_store().await;
+        let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+            name: "Sampled Speaker".to_string(),
+            description: None,
+            color: None,
+            confidence_threshold: None,
+        }).await.unwrap();
+
+        // Four 1 s clips under a 2.5 s cap: the first two are evicted
+        let mut ids = Vec::new();
+        let mut evicted = Vec::new();
+        for _ in 0..4 {
+            let sample = SpeakerSample::from_samples(profile.id, &[0.1; 16000], 16000).unwrap();
+            ids.push(sample.id);
+            evicted.extend(store.add_speaker_sample(sample, 2.5).await.unwrap());
+        }
+        assert_eq!(evicted, vec![ids[0], ids[1]]);
+
+        let samples = store.get_speaker_samples(profile.id).await.unwrap();
+        assert_eq!(samples.iter().map(|s| s.id).collect::<Vec<_>>(), vec![ids[2], ids[3]]);
+        assert_eq!(samples[0].sample_rate, 16000);
+        assert!((samples[0].duration_seconds - 1.0).abs() < 1e-6);
+
+        // A clip longer than the cap still replaces everything before it
+        let long = SpeakerSample::from_samples(profile.id, &[0.1; 48000], 16000).unwrap();
+        assert_eq!(store.add_speaker_sample(long.clone(), 2.5).await.unwrap().len(), 2);
+        assert!(store.delete_speaker_sample(long.id).await.unwrap());
+        assert!(!store.delete_speaker_sample(long.id).await.unwrap());
+        assert!(store.get_speaker_samples(profile.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_speaker_samples_are_deleted_with_the_profile() {
+        let (store, _temp_file) = create_test_store().await;
+        let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+            name: "Departing Speaker".to_string(),
+            description: None,
+            color: None,
+            confidence_threshold: None,
+        }).await.unwrap();
+        let sample = SpeakerSample::from_samples(profile.id, &[0.2; 8000], 16000).unwrap();
+        store.add_speaker_sample(sample, SPEAKER_SAMPLE_CAP_SECONDS).await.unwrap();
+
+        store.delete_speaker_profile(profile.id).await.unwrap();
+        assert!(store.get_speaker_samples(profile.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_meeting_speaker_links_follow_attribution() {
+        let (store, _temp_file) = create_test_store().await;
+        let profile = |name: &str| CreateSpeakerProfileRequest {
+            name: name.to_string(),
+            description: None,
+            color: None,
+            confidence_threshold: None,
+        };
+        let alice = store.create_speaker_profile(profile("Alice")).await.unwrap();
+        let bob = store.create_speaker_profile(profile("Bob")).await.unwrap();
+        let meeting = Uuid::new_v4();
+
+        let mut alice_link = MeetingSpeaker::new(meeting, alice.id, "Alice".to_string());
+        alice_link.speaking_time_seconds = 30.0;
+        let unknown_link = MeetingSpeaker::new(meeting, Uuid::new_v4(), "Ghost".to_string());
+        assert_eq!(store.sync_meeting_speakers(meeting, vec![alice_link.clone(), unknown_link]).await.unwrap(), 1);
+
+        // Verification survives a later re-attribution
+        store.db.execute(
+            "UPDATE meeting_speakers SET is_verified = 1 WHERE speaker_id = ?1",
+            [uuid_to_string(&alice.id)],
+        ).await.unwrap();
+        alice_link.speaking_time_seconds = 12.0;
+        let mut bob_link = MeetingSpeaker::new(meeting, bob.id, "Bob".to_string());
+        bob_link.speaking_time_seconds = 18.0;
+        store.sync_meeting_speakers(meeting, vec![alice_link, bob_link]).await.unwrap();
+
+        let links = store.get_meeting_speakers(meeting).await.unwrap();
+        assert_eq!(links.iter().map(|l| l.speaker_id).collect::<Vec<_>>(), vec![bob.id, alice.id]);
+        assert_eq!(links[1].speaking_time_seconds, 12.0);
+        assert!(links[1].is_verified);
+
+        // Bob no longer attributed
+        let mut alice_only = MeetingSpeaker::new(meeting, alice.id, "Alice".to_string());
+        alice_only.speaking_time_seconds = 30.0;
+        store.sync_meeting_speakers(meeting, vec![alice_only]).await.unwrap();
+        let links = store.get_meeting_speakers(meeting).await.unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].speaker_id, alice.id);
+    }
+}
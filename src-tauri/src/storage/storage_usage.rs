@@ -0,0 +1,507 @@
+//! Disk usage breakdown of the local data
+//!
+//! Walks the data directory (and the models and backup directories when they
+//! live elsewhere) and groups file sizes by category: models per tier, the
+//! speaker database, session audio per session, temporary files, backups and
+//! logs. The walk is bounded in depth and can be cancelled between entries.
+//! Because it can be slow on large trees, the last finished report is cached
+//! with its timestamp; a cancelled walk never replaces the cached report.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::fs;
+
+/// Directory levels below a root that are still walked
+pub const MAX_WALK_DEPTH: usize = 8;
+/// Sessions listed individually unless the caller asks otherwise
+pub const DEFAULT_TOP_SESSIONS: usize = 10;
+/// Age after which a cached report is recomputed (seconds)
+pub const DEFAULT_MAX_AGE_SECS: u64 = 300;
+
+/// What a file is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageCategory {
+    Models,
+    SpeakerDatabase,
+    SessionAudio,
+    Temporary,
+    Backups,
+    Logs,
+    Other,
+}
+
+/// Existing command that frees space in a category
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuickAction {
+    pub command: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryUsage {
+    pub category: StorageCategory,
+    pub bytes: u64,
+    pub files: u64,
+    /// Whether the quick action can be used without losing data the app still needs
+    #[serde(rename = "safelyDeletable")]
+    pub safely_deletable: bool,
+    #[serde(rename = "quickAction")]
+    pub quick_action: Option<QuickAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionUsage {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageUsageReport {
+    /// Unix milliseconds when the walk finished
+    #[serde(rename = "computedAt")]
+    pub computed_at: u64,
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+    pub categories: Vec<CategoryUsage>,
+    /// Model bytes by tier label ("standard/turbo" when tiers share a file)
+    #[serde(rename = "modelTiers")]
+    pub model_tiers: BTreeMap<String, u64>,
+    #[serde(rename = "sessionCount")]
+    pub session_count: usize,
+    /// Largest sessions, biggest first
+    #[serde(rename = "largestSessions")]
+    pub largest_sessions: Vec<SessionUsage>,
+    /// Directories below the depth bound were not counted
+    #[serde(rename = "depthLimited")]
+    pub depth_limited: bool,
+    /// Entries that could not be read
+    #[serde(rename = "unreadableEntries")]
+    pub unreadable_entries: u64,
+    /// Served from the cache instead of a new walk
+    #[serde(rename = "fromCache")]
+    pub from_cache: bool,
+}
+
+impl StorageUsageReport {
+    pub fn category(&self, category: StorageCategory) -> Option<&CategoryUsage> {
+        self.categories.iter().find(|c| c.category == category)
+    }
+}
+
+/// Disk space the user allows the app's data directory to take
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceQuota {
+    #[serde(rename = "maxBytes")]
+    pub max_bytes: u64,
+}
+
+/// Where the app keeps its data
+#[derive(Debug, Clone, Default)]
+pub struct StorageLayout {
+    /// App data directory holding speakers.db, sessions/, event_logs/, ...
+    pub data_dir: PathBuf,
+    /// Whisper models; may differ from `data_dir/models` on some platforms
+    pub models_dir: Option<PathBuf>,
+    /// Backup target from the backup policy
+    pub backup_dir: Option<PathBuf>,
+    /// Model file name -> quality tier
+    pub model_files: Vec<(String, String)>,
+}
+
+/// Walk the layout and build a report; `is_cancelled` is polled before every
+/// entry and `Ok(None)` is returned when it fires.
+pub async fn compute_storage_usage(
+    layout: &StorageLayout,
+    top_sessions: usize,
+    is_cancelled: impl Fn() -> bool,
+) -> Result<Option<StorageUsageReport>> {
+    let mut totals = Totals::default();
+    let models_dir = layout.models_dir.clone().unwrap_or_else(|| layout.data_dir.join("models"));
+
+    let mut roots = vec![(layout.data_dir.clone(), Root::Data)];
+    if !models_dir.starts_with(&layout.data_dir) {
+        roots.push((models_dir.clone(), Root::Models));
+    }
+    if let Some(backup_dir) = layout.backup_dir.as_ref().filter(|d| !d.starts_with(&layout.data_dir)) {
+        roots.push((backup_dir.clone(), Root::Backups));
+    }
+
+    for (root, kind) in roots {
+        // Iterative walk: (directory, depth)
+        let mut pending = vec![(root.clone(), 0usize)];
+        while let Some((dir, depth)) = pending.pop() {
+            let mut entries = match fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound && dir == root => break,
+                Err(_) => {
+                    totals.unreadable += 1;
+                    continue;
+                }
+            };
+            loop {
+                if is_cancelled() {
+                    return Ok(None);
+                }
+                let entry = match entries.next_entry().await {
+                    Ok(Some(entry)) => entry,
+                    Ok(None) => break,
+                    Err(_) => {
+                        totals.unreadable += 1;
+                        break;
+                    }
+                };
+                let Ok(metadata) = entry.metadata().await else {
+                    totals.unreadable += 1;
+                    continue;
+                };
+                let path = entry.path();
+                if metadata.is_dir() {
+                    if depth + 1 < MAX_WALK_DEPTH {
+                        pending.push((path, depth + 1));
+                    } else {
+                        totals.depth_limited = true;
+                    }
+                } else if metadata.is_file() {
+                    let relative = path.strip_prefix(&root).unwrap_or(&path);
+                    let (category, detail) = classify(kind, relative, &path, layout, &models_dir);
+                    totals.add(category, detail, metadata.len());
+                }
+            }
+        }
+    }
+
+    Ok(Some(totals.into_report(top_sessions)))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Root {
+    Data,
+    Models,
+    Backups,
+}
+
+/// Extra grouping inside a category
+enum Detail {
+    None,
+    ModelTier(String),
+    Session(String),
+}
+
+fn classify(root: Root, relative: &Path, path: &Path, layout: &StorageLayout, models_dir: &Path) -> (StorageCategory, Detail) {
+    match root {
+        Root::Models => return (StorageCategory::Models, model_detail(relative, layout)),
+        Root::Backups => return (StorageCategory::Backups, Detail::None),
+        Root::Data => {}
+    }
+    if path.starts_with(models_dir) {
+        let within = path.strip_prefix(models_dir).unwrap_or(relative);
+        return (StorageCategory::Models, model_detail(within, layout));
+    }
+    if layout.backup_dir.as_ref().map(|d| path.starts_with(d)).unwrap_or(false) {
+        return (StorageCategory::Backups, Detail::None);
+    }
+
+    let mut components = relative.components().filter_map(|c| c.as_os_str().to_str());
+    let first = components.next().unwrap_or_default();
+    let file_name = relative.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    match first {
+        "speakers.db" | "speakers.db-wal" | "speakers.db-shm" | "speakers.db-journal" => {
+            (StorageCategory::SpeakerDatabase, Detail::None)
+        }
+        "sessions" => match components.next() {
+            Some(session_id) if relative.components().count() > 2 => {
+                (StorageCategory::SessionAudio, Detail::Session(session_id.to_string()))
+            }
+            _ => (StorageCategory::SessionAudio, Detail::None),
+        },
+        "recording_spill" | "workspaces" | "tmp" | "backup_snapshot.db" => (StorageCategory::Temporary, Detail::None),
+        "event_logs" | "logs" => (StorageCategory::Logs, Detail::None),
+        _ if file_name.ends_with(".tmp") || file_name.ends_with(".part") => (StorageCategory::Temporary, Detail::None),
+        _ if file_name.ends_with(".log") => (StorageCategory::Logs, Detail::None),
+        _ => (StorageCategory::Other, Detail::None),
+    }
+}
+
+fn model_detail(within_models: &Path, layout: &StorageLayout) -> Detail {
+    let first = within_models.components().next().and_then(|c| c.as_os_str().to_str()).unwrap_or_default();
+    if first == "diarization" {
+        return Detail::ModelTier("diarization".to_string());
+    }
+    let tiers: Vec<&str> = layout.model_files.iter()
+        .filter(|(file, _)| file == first)
+        .map(|(_, tier)| tier.as_str())
+        .collect();
+    if tiers.is_empty() {
+        Detail::ModelTier("other".to_string())
+    } else {
+        Detail::ModelTier(tiers.join("/"))
+    }
+}
+
+#[derive(Default)]
+struct Totals {
+    categories: BTreeMap<StorageCategory, (u64, u64)>,
+    model_tiers: BTreeMap<String, u64>,
+    sessions: HashMap<String, (u64, u64)>,
+    depth_limited: bool,
+    unreadable: u64,
+}
+
+impl Totals {
+    fn add(&mut self, category: StorageCategory, detail: Detail, bytes: u64) {
+        let entry = self.categories.entry(category).or_default();
+        entry.0 += bytes;
+        entry.1 += 1;
+        match detail {
+            Detail::ModelTier(tier) => *self.model_tiers.entry(tier).or_default() += bytes,
+            Detail::Session(session_id) => {
+                let session = self.sessions.entry(session_id).or_default();
+                session.0 += bytes;
+                session.1 += 1;
+            }
+            Detail::None => {}
+        }
+    }
+
+    fn into_report(self, top_sessions: usize) -> StorageUsageReport {
+        let mut sessions: Vec<SessionUsage> = self.sessions.into_iter()
+            .map(|(session_id, (bytes, files))| SessionUsage { session_id, bytes, files })
+            .collect();
+        sessions.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.session_id.cmp(&b.session_id)));
+        let session_count = sessions.len();
+        sessions.truncate(top_sessions);
+
+        let categories: Vec<CategoryUsage> = self.categories.into_iter()
+            .map(|(category, (bytes, files))| {
+                let (safely_deletable, quick_action) = quick_action(category);
+                CategoryUsage { category, bytes, files, safely_deletable, quick_action }
+            })
+            .collect();
+
+        StorageUsageReport {
+            computed_at: now_millis(),
+            total_bytes: categories.iter().map(|c| c.bytes).sum(),
+            categories,
+            model_tiers: self.model_tiers,
+            session_count,
+            largest_sessions: sessions,
+            depth_limited: self.depth_limited,
+            unreadable_entries: self.unreadable,
+            from_cache: false,
+        }
+    }
+}
+
+/// Cleanup offered for a category through commands that already exist
+fn quick_action(category: StorageCategory) -> (bool, Option<QuickAction>) {
+    let action = |command: &str, description: &str| Some(QuickAction {
+        command: command.to_string(),
+        description: description.to_string(),
+    });
+    match category {
+        StorageCategory::Backups => (true, action("configure_backups", "Keep fewer backup sets; older sets are rotated out")),
+        StorageCategory::SpeakerDatabase => (false, action("clear_all_speaker_data", "Delete all speaker profiles and voice embeddings")),
+        _ => (false, None),
+    }
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Last finished report and the walk currently in progress
+#[derive(Debug, Default)]
+pub struct StorageUsageCache {
+    report: Option<StorageUsageReport>,
+    running: Option<Arc<AtomicBool>>,
+}
+
+impl StorageUsageCache {
+    /// Cached report if it is younger than `max_age_secs`
+    pub fn fresh(&self, max_age_secs: u64) -> Option<StorageUsageReport> {
+        let now = now_millis();
+        self.report.as_ref()
+            .filter(|r| now.saturating_sub(r.computed_at) <= max_age_secs * 1000)
+            .map(|r| StorageUsageReport { from_cache: true, ..r.clone() })
+    }
+
+    pub fn last_report(&self) -> Option<&StorageUsageReport> {
+        self.report.as_ref()
+    }
+
+    /// Drop the cached report after data was deleted outside a walk
+    pub fn invalidate(&mut self) {
+        self.report = None;
+    }
+
+    /// Start a walk, cancelling one that is still running; returns its cancel flag
+    pub fn begin(&mut self) -> Arc<AtomicBool> {
+        self.cancel();
+        let flag = Arc::new(AtomicBool::new(false));
+        self.running = Some(flag.clone());
+        flag
+    }
+
+    /// Store a finished walk; results of cancelled or superseded walks are dropped
+    pub fn finish(&mut self, flag: &Arc<AtomicBool>, report: Option<StorageUsageReport>) -> bool {
+        let current = self.running.as_ref().map(|r| Arc::ptr_eq(r, flag)).unwrap_or(false);
+        if current {
+            self.running = None;
+        }
+        match report {
+            Some(report) if current && !flag.load(Ordering::SeqCst) => {
+                self.report = Some(report);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Cancel the running walk; returns whether one was running
+    pub fn cancel(&mut self) -> bool {
+        match self.running.take() {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn write(path: &Path, bytes: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; bytes]).unwrap();
+    }
+
+    /// Synthetic data directory with every category represented
+    fn synthetic_layout(dir: &tempfile::TempDir) -> StorageLayout {
+        let data = dir.path().join("KagiNote");
+        write(&data.join("models/ggml-medium.bin"), 5000);
+        write(&data.join("models/ggml-medium-q5_0.bin"), 2000);
+        write(&data.join("models/diarization/embedding.onnx"), 700);
+        write(&data.join("speakers.db"), 800);
+        write(&data.join("speakers.db-wal"), 200);
+        for (i, size) in [300, 1200, 50, 900].into_iter().enumerate() {
+            write(&data.join(format!("sessions/session-{}/audio.wav", i)), size);
+        }
+        write(&data.join("recording_spill/session-1.spill"), 400);
+        write(&data.join("event_logs/session-1.jsonl"), 120);
+        write(&data.join("backup_policy.json"), 30);
+        let backups = dir.path().join("Backups");
+        write(&backups.join("kaginote-backup-1/speakers.db"), 800);
+
+        StorageLayout {
+            data_dir: data,
+            models_dir: None,
+            backup_dir: Some(backups),
+            model_files: vec![
+                ("ggml-medium.bin".to_string(), "standard".to_string()),
+                ("ggml-medium-q5_0.bin".to_string(), "high-accuracy".to_string()),
+                ("ggml-medium.bin".to_string(), "turbo".to_string()),
+            ],
+        }
+    }
+
+    fn bytes(report: &StorageUsageReport, category: StorageCategory) -> u64 {
+        report.category(category).map(|c| c.bytes).unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn test_categorized_totals() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = synthetic_layout(&dir);
+        let report = compute_storage_usage(&layout, DEFAULT_TOP_SESSIONS, || false).await.unwrap().unwrap();
+
+        assert_eq!(bytes(&report, StorageCategory::Models), 7700);
+        assert_eq!(bytes(&report, StorageCategory::SpeakerDatabase), 1000);
+        assert_eq!(bytes(&report, StorageCategory::SessionAudio), 2450);
+        assert_eq!(bytes(&report, StorageCategory::Temporary), 400);
+        assert_eq!(bytes(&report, StorageCategory::Logs), 120);
+        assert_eq!(bytes(&report, StorageCategory::Backups), 800);
+        assert_eq!(bytes(&report, StorageCategory::Other), 30);
+        assert_eq!(report.total_bytes, 12500);
+
+        assert_eq!(report.model_tiers["standard/turbo"], 5000);
+        assert_eq!(report.model_tiers["high-accuracy"], 2000);
+        assert_eq!(report.model_tiers["diarization"], 700);
+        assert!(report.category(StorageCategory::Backups).unwrap().safely_deletable);
+    }
+
+    #[tokio::test]
+    async fn test_top_sessions_are_largest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = synthetic_layout(&dir);
+        let report = compute_storage_usage(&layout, 2, || false).await.unwrap().unwrap();
+
+        assert_eq!(report.session_count, 4);
+        let top: Vec<(&str, u64)> = report.largest_sessions.iter().map(|s| (s.session_id.as_str(), s.bytes)).collect();
+        assert_eq!(top, vec![("session-1", 1200), ("session-3", 900)]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_walk_keeps_previous_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = synthetic_layout(&dir);
+        let mut cache = StorageUsageCache::default();
+
+        let flag = cache.begin();
+        let first = compute_storage_usage(&layout, DEFAULT_TOP_SESSIONS, || flag.load(Ordering::SeqCst)).await.unwrap();
+        assert!(cache.finish(&flag, first.clone()));
+
+        // Grow the tree, then cancel the second walk a few entries in
+        write(&layout.data_dir.join("sessions/session-9/audio.wav"), 10_000);
+        let flag = cache.begin();
+        let polls = AtomicUsize::new(0);
+        let second = compute_storage_usage(&layout, DEFAULT_TOP_SESSIONS, || {
+            if polls.fetch_add(1, Ordering::SeqCst) == 3 {
+                flag.store(true, Ordering::SeqCst);
+            }
+            flag.load(Ordering::SeqCst)
+        }).await.unwrap();
+
+        assert!(second.is_none());
+        assert!(!cache.finish(&flag, second));
+        assert_eq!(cache.last_report(), first.as_ref());
+        let cached = cache.fresh(DEFAULT_MAX_AGE_SECS).unwrap();
+        assert!(cached.from_cache);
+        assert_eq!(cached.total_bytes, 12500);
+    }
+
+    #[tokio::test]
+    async fn test_depth_bound_and_missing_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("KagiNote");
+        let mut deep = data.join("workspaces");
+        for level in 0..MAX_WALK_DEPTH + 2 {
+            deep = deep.join(format!("l{}", level));
+        }
+        write(&deep.join("scratch.bin"), 64);
+        write(&data.join("workspaces/top.bin"), 16);
+        let layout = StorageLayout {
+            data_dir: data,
+            models_dir: Some(dir.path().join("missing-models")),
+            ..StorageLayout::default()
+        };
+
+        let report = compute_storage_usage(&layout, DEFAULT_TOP_SESSIONS, || false).await.unwrap().unwrap();
+        assert!(report.depth_limited);
+        assert_eq!(bytes(&report, StorageCategory::Temporary), 16);
+        assert_eq!(report.unreadable_entries, 0);
+    }
+}
@@ -0,0 +1,586 @@
+//! Search across saved transcripts
+//!
+//! Segment text is indexed in the `transcript_search` FTS5 table (migration
+//! 004), whose trigram tokenizer matches substrings in any script. Every
+//! segment containing the query is a candidate, then the best-ranked segments
+//! sharing some of its trigrams; each is scored here: a case-insensitive substring match ranks above a fuzzy one,
+//! a whole-word match above a partial word, and a fuzzy match needs at least
+//! half of the query's trigrams. Queries under three characters have no
+//! trigrams and are matched with LIKE instead.
+//!
+//! With `ftsSyntax` the query goes to FTS5 as written, so phrases in double
+//! quotes and `NEAR(...)` groups work; those hits are ranked by bm25. Speaker
+//! filters are joined in through `session_speakers` (each meeting's display
+//! names, migration 006) and the speaker profiles, so paging counts only
+//! segments of that speaker.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Hits per page when the caller gives no limit
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 100;
+/// Fraction of the query's trigrams a fuzzy match must contain
+pub const MIN_FUZZY_SIMILARITY: f32 = 0.5;
+/// Characters of context on each side of the match in a snippet
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+/// Near misses scored per search at most, best bm25 rank first; segments
+/// containing the query are always all scored
+const MAX_FUZZY_CANDIDATES: usize = 5000;
+
+const EXACT_SCORE: f32 = 1.0;
+const WHOLE_WORD_BONUS: f32 = 0.1;
+/// A fuzzy match scores its similarity times this, below any exact match
+const FUZZY_WEIGHT: f32 = 0.9;
+
+/// Filters and paging of a transcript search
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchOptions {
+    pub session_id: Option<String>,
+    /// Speaker id, display name in the meeting or profile name (case-insensitive)
+    pub speaker: Option<String>,
+    /// Speaker profile id or session-local speaker id, matched exactly
+    pub speaker_id: Option<String>,
+    /// Pass the query to FTS5 unchanged: `"phrases"`, `NEAR(...)`, AND/OR/NOT.
+    /// Terms must be at least three characters long.
+    pub fts_syntax: bool,
+    /// Sessions started at or after (Unix seconds)
+    pub from: Option<i64>,
+    /// Sessions started at or before (Unix seconds)
+    pub to: Option<i64>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+/// A matching segment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub session_id: String,
+    pub segment_index: usize,
+    /// Unix seconds
+    pub session_started_at: i64,
+    pub start_time: Option<f64>,
+    pub end_time: Option<f64>,
+    pub speaker: Option<String>,
+    /// Display name of the speaker in that meeting
+    pub speaker_name: Option<String>,
+    /// Current name of the speaker's profile, for recognised speakers
+    pub profile_name: Option<String>,
+    /// Segment text around the match
+    pub snippet: String,
+    /// `[start, end)` character ranges of the match within `snippet`
+    pub highlights: Vec<[usize; 2]>,
+    pub score: f32,
+    /// Whether the text contains the query itself rather than a near miss
+    pub exact: bool,
+}
+
+/// One page of hits, best first
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchPage {
+    pub query: String,
+    pub hits: Vec<SearchHit>,
+    /// Hits on all pages
+    pub total: usize,
+    /// Whether lower-ranked near misses were left unscored, making `total` a
+    /// lower bound; every segment containing the query is counted
+    pub truncated: bool,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Where and how well a segment text matches a query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextMatch {
+    pub score: f32,
+    pub exact: bool,
+    /// `[start, end)` character range of the match in the text
+    pub range: (usize, usize),
+}
+
+/// Case folding that keeps one character per character, so ranges in the
+/// folded text are ranges in the original
+fn fold(text: &str) -> Vec<char> {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+fn trigrams(chars: &[char]) -> HashSet<[char; 3]> {
+    chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// Score `text` against `query`; None when it neither contains the query nor
+/// enough of its trigrams
+pub fn match_text(text: &str, query: &str) -> Option<TextMatch> {
+    let text = fold(text);
+    let query = fold(query.trim());
+    if query.is_empty() || text.is_empty() {
+        return None;
+    }
+
+    // Best exact occurrence: a whole word if there is one, else the first
+    let is_word = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric());
+    let mut exact: Option<TextMatch> = None;
+    if query.len() <= text.len() {
+        for start in 0..=text.len() - query.len() {
+            if text[start..start + query.len()] != query[..] {
+                continue;
+            }
+            let end = start + query.len();
+            // Scripts without spaces have no word boundaries to check
+            let whole_word = !is_word(start.checked_sub(1).and_then(|i| text.get(i))) && !is_word(text.get(end));
+            let score = EXACT_SCORE + if whole_word { WHOLE_WORD_BONUS } else { 0.0 };
+            if exact.is_none_or(|best| score > best.score) {
+                exact = Some(TextMatch { score, exact: true, range: (start, end) });
+            }
+            if whole_word {
+                break;
+            }
+        }
+    }
+    if exact.is_some() {
+        return exact;
+    }
+
+    // Near miss: the window of the query's length holding most of its trigrams
+    let wanted = trigrams(&query);
+    if wanted.is_empty() {
+        return None;
+    }
+    let width = query.len().min(text.len());
+    let (start, found) = (0..=text.len() - width)
+        .map(|start| {
+            let window = trigrams(&text[start..start + width]);
+            (start, wanted.iter().filter(|t| window.contains(*t)).count())
+        })
+        .max_by_key(|&(start, found)| (found, std::cmp::Reverse(start)))?;
+    let similarity = found as f32 / wanted.len() as f32;
+    (similarity >= MIN_FUZZY_SIMILARITY).then_some(TextMatch {
+        score: similarity * FUZZY_WEIGHT,
+        exact: false,
+        range: (start, start + width),
+    })
+}
+
+/// Text around `range` with the match position inside the snippet
+pub fn snippet(text: &str, range: (usize, usize)) -> (String, [usize; 2]) {
+    let chars: Vec<char> = text.chars().collect();
+    let start = range.0.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (range.1 + SNIPPET_CONTEXT_CHARS).min(chars.len());
+    let mut snippet = String::new();
+    let mut offset = range.0 - start;
+    if start > 0 {
+        snippet.push('…');
+        offset += 1;
+    }
+    snippet.extend(&chars[start..end]);
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    (snippet, [offset, offset + range.1 - range.0])
+}
+
+/// The text as one FTS5 phrase
+fn quote_fts(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', "\"\""))
+}
+
+/// FTS5 query for segments holding any of the query's trigrams
+fn trigram_query(query: &str) -> String {
+    let chars: Vec<char> = query.chars().collect();
+    let mut terms: Vec<String> = chars.windows(3).map(|w| quote_fts(&w.iter().collect::<String>())).collect();
+    terms.sort_unstable();
+    terms.dedup();
+    terms.join(" OR ")
+}
+
+/// Character range of the first span `highlight()` marked with
+/// HIGHLIGHT_OPEN/HIGHLIGHT_CLOSE, and the text without the markers
+fn highlighted_range(marked: &str) -> (String, (usize, usize)) {
+    let mut text = String::with_capacity(marked.len());
+    let mut range = None;
+    let mut start = 0;
+    let mut count = 0;
+    for c in marked.chars() {
+        match c {
+            HIGHLIGHT_OPEN => start = count,
+            HIGHLIGHT_CLOSE => {
+                range.get_or_insert((start, count));
+            }
+            c => {
+                text.push(c);
+                count += 1;
+            }
+        }
+    }
+    (text, range.unwrap_or((0, 0)))
+}
+
+const HIGHLIGHT_OPEN: char = '\u{1}';
+const HIGHLIGHT_CLOSE: char = '\u{2}';
+
+fn like_pattern(query: &str) -> String {
+    let escaped = query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+const FTS_SOURCE: &str = "transcript_search f JOIN transcript_segments t ON t.id = f.rowid";
+
+/// Columns of a candidate row; the text is selected last
+const HIT_COLUMNS: &str = "t.session_id, t.seq, s.started_at, t.start_time, t.end_time, t.speaker,
+        COALESCE(a.display_name, json_extract(t.segment_json, '$.speakerName')), p.name";
+
+/// Joins and filters shared by every search; `?1` is the match pattern
+fn search_sql(columns: &str, source: &str, condition: &str, tail: &str) -> String {
+    format!(
+        "SELECT {columns}
+         FROM {source}
+         JOIN sessions s ON s.id = t.session_id
+         LEFT JOIN session_speakers a ON a.session_id = t.session_id AND a.speaker_id = t.speaker
+         LEFT JOIN speaker_profiles p ON p.id = t.speaker
+         WHERE {condition}
+           AND (?2 IS NULL OR t.session_id = ?2)
+           AND (?3 IS NULL OR s.started_at >= ?3)
+           AND (?4 IS NULL OR s.started_at <= ?4)
+           AND (?5 IS NULL OR lower(t.speaker) = lower(?5) OR lower(a.display_name) = lower(?5)
+                OR lower(p.name) = lower(?5))
+           AND (?6 IS NULL OR t.speaker = ?6)
+         {tail}"
+    )
+}
+
+fn hit_from_row(row: &rusqlite::Row) -> rusqlite::Result<SearchHit> {
+    Ok(SearchHit {
+        session_id: row.get(0)?,
+        segment_index: row.get::<_, i64>(1)? as usize,
+        session_started_at: row.get(2)?,
+        start_time: row.get(3)?,
+        end_time: row.get(4)?,
+        speaker: row.get(5)?,
+        speaker_name: row.get(6)?,
+        profile_name: row.get(7)?,
+        snippet: String::new(),
+        highlights: Vec::new(),
+        score: 0.0,
+        exact: false,
+    })
+}
+
+/// Search the saved segments for `query`
+pub fn search(conn: &rusqlite::Connection, query: &str, options: &SearchOptions) -> Result<SearchPage> {
+    let query = query.trim();
+    let limit = options.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let mut page = SearchPage { query: query.to_string(), hits: Vec::new(), total: 0, truncated: false, offset: options.offset, limit };
+    if query.is_empty() {
+        return Ok(page);
+    }
+    if options.fts_syntax {
+        return search_fts_syntax(conn, query, options, page);
+    }
+
+    let columns = format!("{HIT_COLUMNS}, t.text");
+    let mut hits = Vec::new();
+
+    // Every segment containing the query: the phrase from the index, or LIKE
+    // for queries too short to have trigrams
+    let fuzzy = query.chars().count() >= 3;
+    let exact_sql = if fuzzy {
+        search_sql(&columns, FTS_SOURCE, "transcript_search MATCH ?1", "")
+    } else {
+        search_sql(&columns, "transcript_segments t", "t.text LIKE ?1 ESCAPE '\\'", "")
+    };
+    let pattern = if fuzzy { quote_fts(query) } else { like_pattern(query) };
+    score_candidates(conn, &exact_sql, &pattern, options, query, usize::MAX, &mut hits)?;
+
+    // Near misses: the best-ranked segments sharing trigrams without the phrase
+    if fuzzy {
+        let fuzzy_sql = search_sql(
+            &columns,
+            FTS_SOURCE,
+            "transcript_search MATCH ?1",
+            &format!("ORDER BY f.rank, t.id LIMIT {}", MAX_FUZZY_CANDIDATES + 1),
+        );
+        let pattern = format!("({}) NOT {}", trigram_query(query), quote_fts(query));
+        page.truncated = score_candidates(conn, &fuzzy_sql, &pattern, options, query, MAX_FUZZY_CANDIDATES, &mut hits)?;
+    }
+
+    hits.sort_by(|a, b| {
+        b.score.total_cmp(&a.score)
+            .then(b.session_started_at.cmp(&a.session_started_at))
+            .then_with(|| a.session_id.cmp(&b.session_id))
+            .then(a.segment_index.cmp(&b.segment_index))
+    });
+
+    page.total = hits.len();
+    page.hits = hits.into_iter().skip(options.offset).take(limit).collect();
+    Ok(page)
+}
+
+/// Score at most `max` candidate rows of `sql` into `hits`; true when there
+/// were more
+fn score_candidates(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    pattern: &str,
+    options: &SearchOptions,
+    query: &str,
+    max: usize,
+    hits: &mut Vec<SearchHit>,
+) -> Result<bool> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(
+        rusqlite::params![pattern, options.session_id, options.from, options.to, options.speaker, options.speaker_id],
+        |row| Ok((hit_from_row(row)?, row.get::<_, String>(8)?)),
+    )?;
+    for (read, row) in rows.enumerate() {
+        if read == max {
+            return Ok(true);
+        }
+        let (mut hit, text) = row.context("Failed to read search candidate")?;
+        let Some(found) = match_text(&text, query) else {
+            continue;
+        };
+        let (snippet, highlight) = snippet(&text, found.range);
+        hit.snippet = snippet;
+        hit.highlights = vec![highlight];
+        hit.score = found.score;
+        hit.exact = found.exact;
+        hits.push(hit);
+    }
+    Ok(false)
+}
+
+/// A query in FTS5 syntax: matched, ranked and paged by SQLite
+fn search_fts_syntax(conn: &rusqlite::Connection, query: &str, options: &SearchOptions, mut page: SearchPage) -> Result<SearchPage> {
+    let condition = "transcript_search MATCH ?1";
+    let params = rusqlite::params![query, options.session_id, options.from, options.to, options.speaker, options.speaker_id];
+
+    let count_sql = search_sql("COUNT(*)", FTS_SOURCE, condition, "");
+    let total: i64 = conn.query_row(&count_sql, params, |row| row.get(0))
+        .with_context(|| format!("Invalid FTS5 query '{}'", query))?;
+    page.total = total as usize;
+
+    let sql = search_sql(
+        &format!("{HIT_COLUMNS}, highlight(transcript_search, 0, char(1), char(2)), f.rank"),
+        FTS_SOURCE,
+        condition,
+        &format!("ORDER BY f.rank, t.id LIMIT {} OFFSET {}", page.limit, page.offset),
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params, |row| {
+        Ok((hit_from_row(row)?, row.get::<_, String>(8)?, row.get::<_, f64>(9)?))
+    })?;
+    for row in rows {
+        let (mut hit, marked, rank) = row.context("Failed to read search hit")?;
+        let (text, range) = highlighted_range(&marked);
+        let (snippet, highlight) = snippet(&text, range);
+        hit.snippet = snippet;
+        hit.highlights = vec![highlight];
+        // bm25 is negative, lower is better
+        hit.score = -rank as f32;
+        hit.exact = true;
+        page.hits.push(hit);
+    }
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{Database, SessionStore};
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    fn segment(text: &str, speaker: &str) -> serde_json::Value {
+        json!({ "text": text, "startTime": 1.0, "endTime": 2.0, "speaker": speaker })
+    }
+
+    fn named_segment(text: &str, speaker: &str, name: &str) -> serde_json::Value {
+        json!({ "text": text, "startTime": 1.0, "endTime": 2.0, "speaker": speaker, "speakerName": name })
+    }
+
+    async fn store_with(sessions: &[(&str, i64, Vec<serde_json::Value>)]) -> (SessionStore, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).await.unwrap();
+        db.migrate().await.unwrap();
+        let store = SessionStore::new(db);
+        for (id, started_at, segments) in sessions {
+            store.create_session(id, *started_at, None, json!({})).await.unwrap();
+            store.append_segments(id, 0, segments.clone()).await.unwrap();
+        }
+        (store, temp_file)
+    }
+
+    #[test]
+    fn test_match_quality_ordering() {
+        let word = match_text("We approved the budget today", "Budget").unwrap();
+        let partial = match_text("Budgets were approved", "budget").unwrap();
+        let fuzzy = match_text("the budgit is final", "budget").unwrap();
+        assert!(word.score > partial.score && partial.score > fuzzy.score);
+        assert!(!fuzzy.exact);
+        assert_eq!(word.range, (16, 22));
+        assert!(match_text("nothing relevant", "budget").is_none());
+
+        let (snippet, highlight) = snippet(&"x".repeat(200), (60, 66));
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert_eq!(highlight, [41, 47]);
+    }
+
+    #[tokio::test]
+    async fn test_japanese_queries() {
+        let (store, _file) = store_with(&[
+            ("ja", 100, vec![segment("来期の予算について話しました", "speaker_1"), segment("次の会議は月曜日です", "speaker_2")]),
+        ]).await;
+
+        // Two characters: below trigram length
+        let short = store.search("予算", &SearchOptions::default()).await.unwrap();
+        assert_eq!(short.hits.len(), 1);
+        assert_eq!(short.hits[0].highlights, vec![[3, 5]]);
+
+        let long = store.search("予算について", &SearchOptions::default()).await.unwrap();
+        assert_eq!((long.total, long.hits[0].segment_index), (1, 0));
+        assert!(long.hits[0].exact);
+
+        let monday = store.search("会議は月曜", &SearchOptions::default()).await.unwrap();
+        assert_eq!(monday.hits[0].segment_index, 1);
+        assert!(store.search("火曜日", &SearchOptions::default()).await.unwrap().hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ranking_filters_and_pages() {
+        let (store, _file) = store_with(&[
+            ("old", 100, vec![segment("the budgit draft", "speaker_1"), segment("Budgets for Q3", "speaker_2")]),
+            ("new", 200, vec![segment("We approved the budget", "speaker_1"), segment("lunch plans", "speaker_1")]),
+        ]).await;
+
+        let all = store.search("budget", &SearchOptions::default()).await.unwrap();
+        assert!(!all.truncated);
+        let order: Vec<(&str, usize)> = all.hits.iter().map(|h| (h.session_id.as_str(), h.segment_index)).collect();
+        assert_eq!(order, vec![("new", 0), ("old", 1), ("old", 0)]);
+
+        // Rewritten segments are searched by their new text
+        store.append_segments("new", 1, vec![segment("budget lunch", "speaker_1")]).await.unwrap();
+        let page = store.search("budget", &SearchOptions { offset: 1, limit: Some(2), ..SearchOptions::default() }).await.unwrap();
+        assert_eq!((page.total, page.hits.len()), (4, 2));
+
+        let filtered = store.search("budget", &SearchOptions {
+            speaker: Some("SPEAKER_2".to_string()),
+            from: Some(50),
+            to: Some(150),
+            ..SearchOptions::default()
+        }).await.unwrap();
+        assert_eq!(filtered.hits.iter().map(|h| h.segment_index).collect::<Vec<_>>(), vec![1]);
+
+        store.delete_session("old").await.unwrap();
+        let after_delete = store.search("budget", &SearchOptions { session_id: Some("old".to_string()), ..SearchOptions::default() }).await.unwrap();
+        assert_eq!(after_delete.total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_exact_matches_survive_many_near_misses() {
+        let mut segments: Vec<_> = (0..MAX_FUZZY_CANDIDATES + 1)
+            .map(|i| segment(&format!("the budgit draft {}", i), "speaker_1"))
+            .collect();
+        segments.push(segment("We approved the budget", "speaker_2"));
+        let (store, _file) = store_with(&[("long", 100, segments)]).await;
+
+        let page = store.search("budget", &SearchOptions::default()).await.unwrap();
+        assert_eq!(page.hits[0].segment_index, MAX_FUZZY_CANDIDATES + 1);
+        assert!(page.hits[0].exact && !page.hits[1].exact);
+        assert_eq!(page.total, MAX_FUZZY_CANDIDATES + 1);
+        assert!(page.truncated);
+
+        let exact_only = store.search("approved", &SearchOptions::default()).await.unwrap();
+        assert_eq!((exact_only.total, exact_only.truncated), (1, false));
+    }
+
+    #[tokio::test]
+    async fn test_speaker_filter_is_joined_before_paging() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).await.unwrap();
+        db.migrate().await.unwrap();
+        let profile_id = "7d1f0c8e-0000-4000-8000-000000000001";
+        db.connection.lock().unwrap().execute(
+            "INSERT INTO speaker_profiles (id, name, color) VALUES (?1, 'Tanaka Yuki', '#FF0000')",
+            [profile_id],
+        ).unwrap();
+        let store = SessionStore::new(db);
+
+        // Tanaka is a recognised profile in one meeting; other speakers are session-local
+        let mut first = Vec::new();
+        for i in 0..6 {
+            first.push(named_segment(&format!("deadline item {}", i), profile_id, "Tanaka-san"));
+            first.push(named_segment(&format!("deadline reply {}", i), "speaker_2", "Sato"));
+        }
+        store.create_session("first", 100, None, json!({})).await.unwrap();
+        store.append_segments("first", 0, first).await.unwrap();
+        store.create_session("second", 200, None, json!({})).await.unwrap();
+        store.append_segments("second", 0, vec![
+            named_segment("the deadline moved", "speaker_1", "Tanaka-san"),
+            named_segment("deadline again", "speaker_2", "Kim"),
+        ]).await.unwrap();
+
+        // By profile id: only that profile's segments, each page full until the end
+        let by_id = |offset| SearchOptions {
+            speaker_id: Some(profile_id.to_string()),
+            offset,
+            limit: Some(4),
+            ..SearchOptions::default()
+        };
+        let first_page = store.search("deadline", &by_id(0)).await.unwrap();
+        let second_page = store.search("deadline", &by_id(4)).await.unwrap();
+        assert_eq!((first_page.total, first_page.hits.len(), second_page.hits.len()), (6, 4, 2));
+        let mut seen: Vec<usize> = first_page.hits.iter().chain(&second_page.hits).map(|h| h.segment_index).collect();
+        seen.sort_unstable();
+        assert_eq!(seen, vec![0, 2, 4, 6, 8, 10]);
+        assert_eq!(first_page.hits[0].speaker_name.as_deref(), Some("Tanaka-san"));
+        assert_eq!(first_page.hits[0].profile_name.as_deref(), Some("Tanaka Yuki"));
+
+        // By name: the meeting alias matches in both sessions, the profile name only where recognised
+        let by_alias = store.search("deadline", &SearchOptions { speaker: Some("tanaka-san".to_string()), ..SearchOptions::default() }).await.unwrap();
+        assert_eq!(by_alias.total, 7);
+        let by_profile = store.search("deadline", &SearchOptions { speaker: Some("Tanaka Yuki".to_string()), ..SearchOptions::default() }).await.unwrap();
+        assert_eq!(by_profile.total, 6);
+
+        // Renaming a speaker updates the alias the filter joins against
+        store.append_segments("second", 1, vec![named_segment("deadline again", "speaker_2", "Kim Minjun")]).await.unwrap();
+        let renamed = store.search("deadline", &SearchOptions { speaker: Some("Kim Minjun".to_string()), ..SearchOptions::default() }).await.unwrap();
+        assert_eq!(renamed.hits.iter().map(|h| (h.session_id.as_str(), h.segment_index)).collect::<Vec<_>>(), vec![("second", 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_phrase_and_near_queries_pass_through_to_fts() {
+        let (store, _file) = store_with(&[
+            ("ja", 100, vec![
+                named_segment("来期の予算について話しました", "speaker_1", "田中"),
+                named_segment("来期の予算は別の会議で詳しく何度も話しました", "speaker_2", "佐藤"),
+                segment("we approved the budget today", "speaker_1"),
+                segment("the budget was not approved", "speaker_2"),
+            ]),
+        ]).await;
+        let fts = |speaker: Option<&str>| SearchOptions {
+            fts_syntax: true,
+            speaker: speaker.map(str::to_string),
+            ..SearchOptions::default()
+        };
+
+        let phrase = store.search("\"予算について\"", &fts(None)).await.unwrap();
+        assert_eq!((phrase.total, phrase.hits[0].segment_index), (1, 0));
+        assert_eq!(phrase.hits[0].snippet, "来期の予算について話しました");
+        assert_eq!(phrase.hits[0].highlights, vec![[3, 9]]);
+        assert_eq!(phrase.hits[0].speaker_name.as_deref(), Some("田中"));
+
+        // Both segments have both phrases; only the first has them close together
+        let near = store.search("NEAR(\"来期の予算\" \"話しました\", 8)", &fts(None)).await.unwrap();
+        assert_eq!(near.hits.iter().map(|h| h.segment_index).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(store.search("\"来期の予算\" NOT \"について\"", &fts(None)).await.unwrap().hits[0].segment_index, 1);
+        assert_eq!(store.search("\"来期の\"", &fts(Some("佐藤"))).await.unwrap().hits[0].segment_index, 1);
+
+        let english = store.search("\"approved the budget\"", &fts(None)).await.unwrap();
+        assert_eq!(english.hits.iter().map(|h| h.segment_index).collect::<Vec<_>>(), vec![2]);
+        assert!(store.search("NEAR(\"unclosed", &fts(None)).await.is_err());
+    }
+}
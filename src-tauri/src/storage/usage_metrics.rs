@@ -0,0 +1,278 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task;
+
+use crate::storage::Database;
+
+/// Columns allowed in the usage_metrics table. Anything else (text, names,
+/// audio characteristics) must never be stored.
+pub const USAGE_METRICS_COLUMNS: &[&str] = &[
+    "id",
+    "ended_at",
+    "duration_seconds",
+    "model_tier",
+    "outcome",
+    "segment_count",
+    "error_count",
+    "diarization_enabled",
+];
+
+const SECONDS_PER_WEEK: i64 = 7 * 24 * 3600;
+/// 1970-01-05 was the first Monday after the epoch
+const FIRST_MONDAY_OFFSET: i64 = 4 * 24 * 3600;
+
+/// Coarse, content-free metrics recorded when a session ends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionUsageMetrics {
+    /// Unix seconds
+    #[serde(rename = "endedAt")]
+    pub ended_at: i64,
+    #[serde(rename = "durationSeconds")]
+    pub duration_seconds: f64,
+    /// One of "standard", "high-accuracy", "turbo"
+    #[serde(rename = "modelTier")]
+    pub model_tier: String,
+    /// One of "completed", "error", "emergency_stop"
+    pub outcome: String,
+    #[serde(rename = "segmentCount")]
+    pub segment_count: u32,
+    #[serde(rename = "errorCount")]
+    pub error_count: u32,
+    #[serde(rename = "diarizationEnabled")]
+    pub diarization_enabled: bool,
+}
+
+/// Usage for a single Monday-aligned week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyUsage {
+    #[serde(rename = "weekStart")]
+    pub week_start: i64,
+    pub sessions: u32,
+    pub hours: f64,
+}
+
+/// Aggregated usage for the in-app dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageSummary {
+    #[serde(rename = "totalSessions")]
+    pub total_sessions: u32,
+    #[serde(rename = "totalHours")]
+    pub total_hours: f64,
+    #[serde(rename = "averageSessionMinutes")]
+    pub average_session_minutes: f64,
+    /// Fraction of sessions that reported at least one error
+    #[serde(rename = "errorRate")]
+    pub error_rate: f64,
+    #[serde(rename = "sessionsByTier")]
+    pub sessions_by_tier: HashMap<String, u32>,
+    pub weekly: Vec<WeeklyUsage>,
+}
+
+/// Local-only usage metrics storage (never leaves the machine)
+pub struct UsageMetricsStore {
+    db: Database,
+}
+
+impl UsageMetricsStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Record the metrics of a finished session
+    pub async fn record_session(&self, metrics: SessionUsageMetrics) -> Result<()> {
+        let connection = Arc::clone(&self.db.connection);
+
+        task::spawn_blocking(move || -> Result<()> {
+            let conn = connection.lock().unwrap();
+            conn.execute(
+                "INSERT INTO usage_metrics (
+                    ended_at, duration_seconds, model_tier, outcome,
+                    segment_count, error_count, diarization_enabled
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    metrics.ended_at,
+                    metrics.duration_seconds,
+                    metrics.model_tier,
+                    metrics.outcome,
+                    metrics.segment_count,
+                    metrics.error_count,
+                    metrics.diarization_enabled,
+                ],
+            ).context("Failed to record usage metrics")?;
+            Ok(())
+        }).await?
+    }
+
+    /// Aggregate metrics for sessions that ended within [start, end] (Unix seconds)
+    pub async fn summarize(&self, start: Option<i64>, end: Option<i64>) -> Result<UsageSummary> {
+        let connection = Arc::clone(&self.db.connection);
+
+        let rows = task::spawn_blocking(move || -> Result<Vec<SessionUsageMetrics>> {
+            let conn = connection.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT ended_at, duration_seconds, model_tier, outcome,
+                        segment_count, error_count, diarization_enabled
+                 FROM usage_metrics
+                 WHERE ended_at >= ?1 AND ended_at <= ?2
+                 ORDER BY ended_at",
+            )?;
+            let rows = stmt.query_map(
+                [start.unwrap_or(i64::MIN), end.unwrap_or(i64::MAX)],
+                |row| {
+                    Ok(SessionUsageMetrics {
+                        ended_at: row.get(0)?,
+                        duration_seconds: row.get(1)?,
+                        model_tier: row.get(2)?,
+                        outcome: row.get(3)?,
+                        segment_count: row.get(4)?,
+                        error_count: row.get(5)?,
+                        diarization_enabled: row.get(6)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        }).await??;
+
+        Ok(aggregate(&rows))
+    }
+
+    /// Delete all recorded metrics, returning the number of removed rows
+    pub async fn clear(&self) -> Result<usize> {
+        self.db.execute("DELETE FROM usage_metrics", []).await
+    }
+}
+
+/// Start of the Monday-aligned week containing `timestamp`
+pub fn week_start(timestamp: i64) -> i64 {
+    (timestamp - FIRST_MONDAY_OFFSET).div_euclid(SECONDS_PER_WEEK) * SECONDS_PER_WEEK + FIRST_MONDAY_OFFSET
+}
+
+fn aggregate(rows: &[SessionUsageMetrics]) -> UsageSummary {
+    let total_sessions = rows.len() as u32;
+    let total_seconds: f64 = rows.iter().map(|r| r.duration_seconds).sum();
+    let sessions_with_errors = rows.iter().filter(|r| r.error_count > 0 || r.outcome == "error").count();
+
+    let mut sessions_by_tier = HashMap::new();
+    let mut weeks: Vec<WeeklyUsage> = Vec::new();
+    for row in rows {
+        *sessions_by_tier.entry(row.model_tier.clone()).or_insert(0) += 1;
+
+        // Rows are ordered by ended_at, so weeks arrive in order
+        let week = week_start(row.ended_at);
+        match weeks.last_mut() {
+            Some(last) if last.week_start == week => {
+                last.sessions += 1;
+                last.hours += row.duration_seconds / 3600.0;
+            }
+            _ => weeks.push(WeeklyUsage {
+                week_start: week,
+                sessions: 1,
+                hours: row.duration_seconds / 3600.0,
+            }),
+        }
+    }
+
+    UsageSummary {
+        total_sessions,
+        total_hours: total_seconds / 3600.0,
+        average_session_minutes: if total_sessions > 0 { total_seconds / 60.0 / total_sessions as f64 } else { 0.0 },
+        error_rate: if total_sessions > 0 { sessions_with_errors as f64 / total_sessions as f64 } else { 0.0 },
+        sessions_by_tier,
+        weekly: weeks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    async fn create_test_store() -> (UsageMetricsStore, Database) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path()).await.unwrap();
+        db.migrate().await.unwrap();
+        (UsageMetricsStore::new(db.clone()), db)
+    }
+
+    fn session(ended_at: i64, minutes: f64, tier: &str, errors: u32) -> SessionUsageMetrics {
+        SessionUsageMetrics {
+            ended_at,
+            duration_seconds: minutes * 60.0,
+            model_tier: tier.to_string(),
+            outcome: "completed".to_string(),
+            segment_count: 10,
+            error_count: errors,
+            diarization_enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schema_contains_only_whitelisted_columns() {
+        let (_store, db) = create_test_store().await;
+        let connection = Arc::clone(&db.connection);
+
+        let columns = task::spawn_blocking(move || -> Result<Vec<(String, String)>> {
+            let conn = connection.lock().unwrap();
+            let mut stmt = conn.prepare("PRAGMA table_info(usage_metrics)")?;
+            let columns = stmt.query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(columns)
+        }).await.unwrap().unwrap();
+
+        assert_eq!(columns.len(), USAGE_METRICS_COLUMNS.len());
+        for (name, column_type) in &columns {
+            assert!(USAGE_METRICS_COLUMNS.contains(&name.as_str()), "unexpected column {}", name);
+            // Free text is only allowed for CHECK-constrained enum columns
+            if column_type == "TEXT" {
+                assert!(name == "model_tier" || name == "outcome", "text column {} is not an enum", name);
+            }
+        }
+
+        // Enum constraints reject arbitrary strings
+        let (store, _db) = create_test_store().await;
+        let mut invalid = session(0, 1.0, "standard", 0);
+        invalid.model_tier = "Meeting with Tanaka".to_string();
+        assert!(store.record_session(invalid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_aggregation_over_multiple_weeks() {
+        let (store, _db) = create_test_store().await;
+        // Monday 2024-01-01 00:00 UTC
+        let monday = 1_704_067_200;
+        let day = 24 * 3600;
+
+        store.record_session(session(monday + day, 60.0, "standard", 0)).await.unwrap();
+        store.record_session(session(monday + 2 * day, 30.0, "turbo", 1)).await.unwrap();
+        store.record_session(session(monday + 8 * day, 90.0, "standard", 0)).await.unwrap();
+        store.record_session(session(monday + 15 * day, 60.0, "high-accuracy", 0)).await.unwrap();
+
+        let summary = store.summarize(None, None).await.unwrap();
+        assert_eq!(summary.total_sessions, 4);
+        assert!((summary.total_hours - 4.0).abs() < 1e-6);
+        assert!((summary.average_session_minutes - 60.0).abs() < 1e-6);
+        assert!((summary.error_rate - 0.25).abs() < 1e-6);
+        assert_eq!(summary.sessions_by_tier.get("standard"), Some(&2));
+
+        assert_eq!(summary.weekly.len(), 3);
+        assert_eq!(summary.weekly[0].week_start, monday);
+        assert_eq!(summary.weekly[0].sessions, 2);
+        assert!((summary.weekly[0].hours - 1.5).abs() < 1e-6);
+
+        // Range filter only covers the second week
+        let ranged = store.summarize(Some(monday + 7 * day), Some(monday + 14 * day - 1)).await.unwrap();
+        assert_eq!(ranged.total_sessions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_clear_metrics() {
+        let (store, _db) = create_test_store().await;
+        store.record_session(session(1_704_067_200, 10.0, "standard", 0)).await.unwrap();
+
+        assert_eq!(store.clear().await.unwrap(), 1);
+        assert_eq!(store.summarize(None, None).await.unwrap().total_sessions, 0);
+    }
+}
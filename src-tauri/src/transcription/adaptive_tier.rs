@@ -0,0 +1,147 @@
+//! Adaptive model tier
+//!
+//! A tier too slow for the machine falls further behind with every chunk. With
+//! allowAdaptiveTier set, the transcription loop tracks a rolling real-time
+//! factor (processing time / audio time) and, once it stays above the
+//! threshold for several chunks in a row, moves the session to the next
+//! faster tier. The decision is taken after a chunk was transcribed, so the
+//! switch always happens at a buffer flush and never mid-utterance.
+
+use crate::asr::types::ModelTier;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Real-time factor above which the session is falling behind
+pub const DEFAULT_RTF_THRESHOLD: f32 = 1.0;
+/// Chunks in a row over the threshold before switching
+pub const SLOW_CHUNKS_BEFORE_SWITCH: u32 = 3;
+/// Chunks in the rolling real-time factor
+const RTF_WINDOW: usize = 5;
+
+/// The tier decoding faster than `tier`, if any
+pub fn next_faster_tier(tier: ModelTier) -> Option<ModelTier> {
+    match tier {
+        ModelTier::HighAccuracy => Some(ModelTier::Standard),
+        ModelTier::Standard => Some(ModelTier::Turbo),
+        ModelTier::Turbo => None,
+    }
+}
+
+/// Rolling real-time factor of a session and its downgrade decisions
+#[derive(Debug, Clone)]
+pub struct TierAdapter {
+    threshold: f32,
+    tier: ModelTier,
+    recent: VecDeque<f32>,
+    slow_chunks: u32,
+    /// A switch was proposed and is being carried out
+    switching: bool,
+}
+
+impl TierAdapter {
+    pub fn new(tier: ModelTier, threshold: f32) -> Self {
+        Self {
+            threshold,
+            tier,
+            recent: VecDeque::with_capacity(RTF_WINDOW),
+            slow_chunks: 0,
+            switching: false,
+        }
+    }
+
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Mean real-time factor of the recent chunks
+    pub fn rolling_rtf(&self) -> Option<f32> {
+        (!self.recent.is_empty()).then(|| self.recent.iter().sum::<f32>() / self.recent.len() as f32)
+    }
+
+    /// Record a transcribed chunk. Returns the tier to switch to when the
+    /// session has been too slow for long enough and a faster tier exists.
+    pub fn record(&mut self, processing: Duration, audio_seconds: f32) -> Option<ModelTier> {
+        if audio_seconds <= 0.0 {
+            return None;
+        }
+        if self.recent.len() == RTF_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(processing.as_secs_f32() / audio_seconds);
+
+        let too_slow = self.rolling_rtf().is_some_and(|rtf| rtf > self.threshold);
+        self.slow_chunks = if too_slow { self.slow_chunks + 1 } else { 0 };
+        if self.switching || self.slow_chunks < SLOW_CHUNKS_BEFORE_SWITCH {
+            return None;
+        }
+        let next = next_faster_tier(self.tier)?;
+        self.switching = true;
+        Some(next)
+    }
+
+    /// The session now runs on `tier`; measuring starts over
+    pub fn switched(&mut self, tier: ModelTier) {
+        self.tier = tier;
+        self.recent.clear();
+        self.slow_chunks = 0;
+        self.switching = false;
+    }
+
+    /// The proposed switch could not be made; `tier` is skipped next time
+    pub fn switch_failed(&mut self, tier: ModelTier) {
+        self.tier = tier;
+        self.slow_chunks = 0;
+        self.switching = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(adapter: &mut TierAdapter, rtf: f32) -> Option<ModelTier> {
+        adapter.record(Duration::from_secs_f32(2.0 * rtf), 2.0)
+    }
+
+    #[test]
+    fn test_sustained_slowness_switches_to_faster_tier() {
+        let mut adapter = TierAdapter::new(ModelTier::Standard, DEFAULT_RTF_THRESHOLD);
+        assert_eq!(chunk(&mut adapter, 2.5), None);
+        assert_eq!(chunk(&mut adapter, 2.5), None);
+        assert_eq!(chunk(&mut adapter, 2.5), Some(ModelTier::Turbo));
+        // Pending switch is not proposed twice
+        assert_eq!(chunk(&mut adapter, 2.5), None);
+
+        adapter.switched(ModelTier::Turbo);
+        assert_eq!(adapter.rolling_rtf(), None);
+        for _ in 0..5 {
+            assert_eq!(chunk(&mut adapter, 2.5), None);
+        }
+    }
+
+    #[test]
+    fn test_single_slow_chunk_is_tolerated() {
+        let mut adapter = TierAdapter::new(ModelTier::HighAccuracy, DEFAULT_RTF_THRESHOLD);
+        for _ in 0..10 {
+            assert_eq!(chunk(&mut adapter, 0.4), None);
+        }
+        // One slow chunk barely moves the rolling factor
+        assert_eq!(chunk(&mut adapter, 3.0), None);
+        assert_eq!(chunk(&mut adapter, 0.4), None);
+        assert!(adapter.rolling_rtf().unwrap() < DEFAULT_RTF_THRESHOLD);
+    }
+
+    #[test]
+    fn test_failed_switch_moves_on_to_the_next_tier() {
+        let mut adapter = TierAdapter::new(ModelTier::HighAccuracy, DEFAULT_RTF_THRESHOLD);
+        for _ in 0..2 {
+            chunk(&mut adapter, 2.0);
+        }
+        assert_eq!(chunk(&mut adapter, 2.0), Some(ModelTier::Standard));
+        adapter.switch_failed(ModelTier::Standard);
+        for _ in 0..2 {
+            chunk(&mut adapter, 2.0);
+        }
+        assert_eq!(chunk(&mut adapter, 2.0), Some(ModelTier::Turbo));
+    }
+}
@@ -0,0 +1,134 @@
+//! Session time limits
+//!
+//! `maxDurationMinutes` ends a session after that much captured audio and
+//! `autoStopAfterSilenceMinutes` after that long without voice activity. Both
+//! run on the audio the loop processes, so time spent paused (whose audio is
+//! discarded) counts toward neither. A warning is raised
+//! [`WARNING_LEAD_SECONDS`] before a limit is reached; speech resets the
+//! silence limit and re-arms its warning.
+
+/// How long before a limit the warning is raised
+pub const WARNING_LEAD_SECONDS: f64 = 60.0;
+
+/// Why a session was stopped without a stop_transcription call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoStopReason {
+    MaxDuration,
+    Silence,
+}
+
+impl AutoStopReason {
+    /// Name reported as `autoStopReason`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AutoStopReason::MaxDuration => "maxDuration",
+            AutoStopReason::Silence => "silence",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutoStopEvent {
+    /// The limit of `reason` is reached in `remaining_seconds`
+    Warning { reason: AutoStopReason, remaining_seconds: f64 },
+    Stop(AutoStopReason),
+}
+
+/// Tracks a session's active and silent time against its limits
+#[derive(Debug, Clone)]
+pub struct AutoStopTimer {
+    max_duration: Option<f64>,
+    silence_limit: Option<f64>,
+    elapsed: f64,
+    silent: f64,
+    duration_warned: bool,
+    silence_warned: bool,
+    stopped: bool,
+}
+
+impl AutoStopTimer {
+    /// Timer for the configured limits in minutes; `None` disables a limit
+    pub fn new(max_duration_minutes: Option<u32>, silence_minutes: Option<u32>) -> Self {
+        Self {
+            max_duration: max_duration_minutes.map(|m| m as f64 * 60.0),
+            silence_limit: silence_minutes.map(|m| m as f64 * 60.0),
+            elapsed: 0.0,
+            silent: 0.0,
+            duration_warned: false,
+            silence_warned: false,
+            stopped: false,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_duration.is_some() || self.silence_limit.is_some()
+    }
+
+    /// Account for a processed chunk of `seconds`, `active` when it had voice
+    /// activity. Returns the warning or stop it triggers; a stop is returned once.
+    pub fn observe(&mut self, seconds: f64, active: bool) -> Option<AutoStopEvent> {
+        if self.stopped {
+            return None;
+        }
+        self.elapsed += seconds;
+        if active {
+            self.silent = 0.0;
+            self.silence_warned = false;
+        } else {
+            self.silent += seconds;
+        }
+
+        let duration_left = self.max_duration.map(|limit| limit - self.elapsed);
+        let silence_left = self.silence_limit.map(|limit| limit - self.silent);
+        for (left, reason) in [(duration_left, AutoStopReason::MaxDuration), (silence_left, AutoStopReason::Silence)] {
+            if left.is_some_and(|left| left <= 0.0) {
+                self.stopped = true;
+                return Some(AutoStopEvent::Stop(reason));
+            }
+        }
+        for (left, reason) in [(duration_left, AutoStopReason::MaxDuration), (silence_left, AutoStopReason::Silence)] {
+            let Some(left) = left.filter(|&left| left <= WARNING_LEAD_SECONDS) else {
+                continue;
+            };
+            let warned = match reason {
+                AutoStopReason::MaxDuration => &mut self.duration_warned,
+                AutoStopReason::Silence => &mut self.silence_warned,
+            };
+            if !*warned {
+                *warned = true;
+                return Some(AutoStopEvent::Warning { reason, remaining_seconds: left });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(timer: &mut AutoStopTimer, seconds: u32, active: bool) -> Vec<AutoStopEvent> {
+        (0..seconds).filter_map(|_| timer.observe(1.0, active)).collect()
+    }
+
+    #[test]
+    fn test_max_duration_warns_then_stops_once() {
+        let mut timer = AutoStopTimer::new(Some(2), None);
+        assert!(run(&mut timer, 59, true).is_empty());
+        assert_eq!(run(&mut timer, 1, true), vec![AutoStopEvent::Warning { reason: AutoStopReason::MaxDuration, remaining_seconds: 60.0 }]);
+        assert!(run(&mut timer, 59, false).is_empty());
+        assert_eq!(run(&mut timer, 5, true), vec![AutoStopEvent::Stop(AutoStopReason::MaxDuration)]);
+        assert!(!AutoStopTimer::new(None, None).is_enabled());
+    }
+
+    #[test]
+    fn test_speech_resets_silence_and_rearms_warning() {
+        let mut timer = AutoStopTimer::new(None, Some(2));
+        let warning = AutoStopEvent::Warning { reason: AutoStopReason::Silence, remaining_seconds: 60.0 };
+        assert_eq!(run(&mut timer, 90, false), vec![warning]);
+        run(&mut timer, 1, true);
+        assert_eq!(run(&mut timer, 60, false), vec![warning]);
+        assert_eq!(run(&mut timer, 60, false), vec![AutoStopEvent::Stop(AutoStopReason::Silence)]);
+        assert_eq!(AutoStopReason::Silence.as_str(), "silence");
+    }
+}
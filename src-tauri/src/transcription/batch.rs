@@ -0,0 +1,271 @@
+//! Batch transcription of a folder of recordings
+//!
+//! Supported audio files directly inside the folder are transcribed one after
+//! another, in name order. Each result is written next to its source as
+//! `<name>.transcript.json`, optionally with a subtitle file. A file that
+//! fails is recorded in the summary and the batch moves on; cancelling stops
+//! the batch after the file in progress.
+
+use crate::asr::types::{ASRResult, WordResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Segments end at a pause between words longer than this (seconds)
+const SEGMENT_PAUSE_SECONDS: f32 = 1.0;
+/// Segments are cut once they reach this length (seconds)
+const MAX_SEGMENT_SECONDS: f32 = 10.0;
+
+/// Cancellation flag of the running batch
+#[derive(Debug, Clone, Default)]
+pub struct BatchCancellation(Arc<AtomicBool>);
+
+impl BatchCancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Progress of a batch, sent before each file and once the batch ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub current_file: String,
+    /// Zero-based index of `current_file`
+    pub file_index: usize,
+    pub total_files: usize,
+    pub percent_complete: f32,
+}
+
+impl BatchProgress {
+    /// `completed` of `total_files` files are done and `current_file` is next
+    pub fn new(current_file: &Path, file_index: usize, completed: usize, total_files: usize) -> Self {
+        let percent_complete = if total_files == 0 {
+            100.0
+        } else {
+            (completed as f32 / total_files as f32 * 1000.0).round() / 10.0
+        };
+        Self {
+            current_file: current_file.to_string_lossy().to_string(),
+            file_index,
+            total_files,
+            percent_complete,
+        }
+    }
+}
+
+/// Outcome of one file of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFileOutcome {
+    pub file: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Files written for this source
+    pub outputs: Vec<String>,
+    pub segment_count: usize,
+}
+
+/// What a batch did, returned when it ends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSummary {
+    pub folder: String,
+    pub total_files: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// The batch was cancelled before every file was processed
+    pub cancelled: bool,
+    pub files: Vec<BatchFileOutcome>,
+}
+
+impl BatchSummary {
+    pub fn new(folder: &Path, total_files: usize) -> Self {
+        Self {
+            folder: folder.to_string_lossy().to_string(),
+            total_files,
+            succeeded: 0,
+            failed: 0,
+            cancelled: false,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, outcome: BatchFileOutcome) {
+        if outcome.success {
+            self.succeeded += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.files.push(outcome);
+    }
+}
+
+/// The file has an extension the file decoder reads
+pub fn is_supported_audio_file(path: &Path) -> bool {
+    let extension = path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    extension == "wav" || crate::audio::file_decoder::COMPRESSED_EXTENSIONS.contains(&extension.as_str())
+}
+
+/// Supported audio files directly inside `folder`, in name order. Hidden files
+/// and subfolders are skipped.
+pub fn list_audio_files(folder: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_supported_audio_file(path))
+        .filter(|path| !path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with('.')))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Path of an output written next to `source`, e.g. `standup.wav` -> `standup.transcript.json`
+pub fn output_path(source: &Path, suffix: &str) -> PathBuf {
+    let stem = source.file_stem().and_then(|stem| stem.to_str()).unwrap_or("audio");
+    source.with_file_name(format!("{}.{}", stem, suffix))
+}
+
+/// Split a whole-file result into transcript segments at sentence ends and
+/// pauses, in the shape live sessions store them
+pub fn segments_from_result(result: &ASRResult, duration_seconds: f32) -> Vec<serde_json::Value> {
+    if result.words.is_empty() {
+        let text = result.text.trim();
+        if text.is_empty() {
+            return Vec::new();
+        }
+        return vec![segment_json(0, text, 0.0, duration_seconds, result.confidence, &result.language)];
+    }
+
+    let mut segments = Vec::new();
+    let mut current: Vec<&WordResult> = Vec::new();
+    for (index, word) in result.words.iter().enumerate() {
+        current.push(word);
+        let next = result.words.get(index + 1);
+        let sentence_end = word.word.trim_end().ends_with(['.', '?', '!']);
+        let pause = next.is_some_and(|next| next.start_time - word.end_time > SEGMENT_PAUSE_SECONDS);
+        let too_long = word.end_time - current[0].start_time >= MAX_SEGMENT_SECONDS;
+        if next.is_none() || sentence_end || pause || too_long {
+            let text = current.iter().map(|w| w.word.trim()).collect::<Vec<_>>().join(" ");
+            let confidence = current.iter().map(|w| w.confidence).sum::<f32>() / current.len() as f32;
+            segments.push(segment_json(
+                segments.len(),
+                &text,
+                current[0].start_time,
+                word.end_time,
+                confidence,
+                &result.language,
+            ));
+            current.clear();
+        }
+    }
+    segments
+}
+
+fn segment_json(index: usize, text: &str, start: f32, end: f32, confidence: f32, language: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": format!("segment-{}", index + 1),
+        "text": text,
+        "startTime": start,
+        "endTime": end.max(start),
+        "confidence": confidence,
+        "language": language,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start: f32, end: f32) -> WordResult {
+        WordResult { word: text.to_string(), start_time: start, end_time: end, confidence: 0.8 }
+    }
+
+    fn result(words: Vec<WordResult>, text: &str) -> ASRResult {
+        ASRResult {
+            text: text.to_string(),
+            confidence: 0.7,
+            language: "en".to_string(),
+            language_confidence: 1.0,
+            words,
+            word_timestamps: true,
+            estimated_snr: None,
+            speaker_consistency_score: None,
+            language_segments: None,
+            decode_temperature: 0.0,
+            decode_attempts: 1,
+            decode_failed: false,
+            decode_budget_exhausted: false,
+            prompt_budget: None,
+            device: None,
+        }
+    }
+
+    #[test]
+    fn test_lists_supported_files_in_name_order() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in ["b.WAV", "a.mp3", "notes.txt", ".hidden.wav", "c.m4a"] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+        std::fs::create_dir(dir.path().join("nested.wav")).unwrap();
+
+        let names: Vec<String> = list_audio_files(dir.path()).unwrap().iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["a.mp3", "b.WAV", "c.m4a"]);
+    }
+
+    #[test]
+    fn test_outputs_sit_next_to_the_source() {
+        let source = Path::new("/recordings/2024-05-01 standup.wav");
+        assert_eq!(output_path(source, "transcript.json"), Path::new("/recordings/2024-05-01 standup.transcript.json"));
+        assert_eq!(output_path(source, "srt"), Path::new("/recordings/2024-05-01 standup.srt"));
+    }
+
+    #[test]
+    fn test_segments_split_at_sentences_and_pauses() {
+        let words = vec![
+            word("Hello", 0.0, 0.4), word("everyone.", 0.5, 1.0),
+            word("Let's", 1.2, 1.5), word("start", 1.6, 2.0),
+            word("now", 4.0, 4.3),
+        ];
+        let segments = segments_from_result(&result(words, "Hello everyone. Let's start now"), 5.0);
+        let texts: Vec<&str> = segments.iter().map(|s| s["text"].as_str().unwrap()).collect();
+        assert_eq!(texts, vec!["Hello everyone.", "Let's start", "now"]);
+        assert_eq!(segments[1]["startTime"].as_f64(), Some(1.2f32 as f64));
+        assert_eq!(segments[2]["id"].as_str(), Some("segment-3"));
+    }
+
+    #[test]
+    fn test_result_without_words_is_one_segment() {
+        let segments = segments_from_result(&result(Vec::new(), " Quick note. "), 3.0);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0]["text"].as_str(), Some("Quick note."));
+        assert_eq!(segments[0]["endTime"].as_f64(), Some(3.0));
+        assert!(segments_from_result(&result(Vec::new(), "  "), 3.0).is_empty());
+    }
+
+    #[test]
+    fn test_progress_and_summary() {
+        let progress = BatchProgress::new(Path::new("a.wav"), 1, 1, 3);
+        assert_eq!(progress.percent_complete, 33.3);
+        assert_eq!(BatchProgress::new(Path::new("a.wav"), 0, 0, 0).percent_complete, 100.0);
+
+        let mut summary = BatchSummary::new(Path::new("/recordings"), 2);
+        summary.record(BatchFileOutcome { file: "a.wav".into(), success: true, error: None, outputs: vec![], segment_count: 2 });
+        summary.record(BatchFileOutcome { file: "b.wav".into(), success: false, error: Some("bad header".into()), outputs: vec![], segment_count: 0 });
+        assert_eq!((summary.succeeded, summary.failed), (1, 1));
+    }
+}
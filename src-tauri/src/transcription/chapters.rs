@@ -0,0 +1,380 @@
+//! Chapters of a finished transcript
+//!
+//! A long meeting is split into chapters where the conversation visibly moves
+//! on. Three signals are looked at between consecutive segments:
+//! - a long pause, found with the temporal analyzer's silence gaps
+//! - a change in who is talking, comparing the speakers of the windows before
+//!   and after (Jaccard similarity)
+//! - a lexical shift, comparing the word frequencies of the same windows
+//!   (cosine similarity)
+//!
+//! A long pause starts a chapter on its own; otherwise both other signals have
+//! to agree, and both windows have to hold at least half a window of
+//! transcript. Chapters are never cut where the boundary detector saw the speaker
+//! still mid-sentence, nor before a chapter has lasted `minChapterSeconds`.
+//! Each chapter is titled with its first sentence, or with its most frequent
+//! words when that sentence is too short to say anything.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::diarization::speaker_stats::UNKNOWN_SPEAKER;
+use crate::transcription::keywords::is_unspaced;
+use crate::transcription::temporal_analyzer::{TemporalAnalyzer, TemporalSegment};
+
+/// Longest title taken from a first sentence (characters)
+pub const MAX_TITLE_CHARS: usize = 60;
+/// First sentences shorter than this ("Yes.", "Okay, so.") give way to keywords
+pub const MIN_TITLE_WORDS: usize = 3;
+/// Words in a keyword title
+pub const TITLE_KEYWORDS: usize = 3;
+/// Speakers listed per chapter
+pub const MAX_CHAPTER_SPEAKERS: usize = 3;
+/// Share of a chapter's talk time a speaker needs to be listed
+pub const MIN_SPEAKER_SHARE: f32 = 0.2;
+
+/// Boundary types written by the live loop after which a sentence is still running
+const MID_SENTENCE_BOUNDARIES: &[&str] = &["none", "soft"];
+
+/// Words too common to tell topics apart
+const STOPWORDS: &[&str] = &[
+    "about", "again", "all", "also", "and", "any", "are", "because", "been", "but", "can", "could",
+    "did", "does", "for", "from", "had", "has", "have", "her", "here", "his", "how", "into", "its",
+    "just", "let", "like", "more", "not", "now", "our", "out", "really", "she", "should", "some",
+    "that", "the", "their", "them", "then", "there", "these", "they", "this", "those", "too", "very",
+    "was", "were", "what", "when", "where", "which", "who", "why", "will", "with", "would", "yes",
+    "you", "your",
+];
+
+/// Thresholds of the chaptering pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChapterConfig {
+    pub enabled: bool,
+    /// Silence that starts a new chapter by itself (seconds)
+    pub min_pause_seconds: f32,
+    /// Speaker sets before and after less similar than this count as a change
+    pub speaker_change_threshold: f32,
+    /// Word frequencies before and after less similar than this count as a shift
+    pub lexical_similarity_threshold: f32,
+    /// Transcript compared on either side of a candidate boundary (seconds)
+    pub window_seconds: f32,
+    /// Shortest chapter (seconds)
+    pub min_chapter_seconds: f32,
+}
+
+impl Default for ChapterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_pause_seconds: 20.0,
+            speaker_change_threshold: 0.5,
+            lexical_similarity_threshold: 0.15,
+            window_seconds: 90.0,
+            min_chapter_seconds: 180.0,
+        }
+    }
+}
+
+/// Why a chapter starts where it does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChapterSignal {
+    Pause,
+    SpeakerChange,
+    LexicalShift,
+}
+
+/// A chapter of the transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub index: usize,
+    pub title: String,
+    pub start_time: f32,
+    pub end_time: f32,
+    /// Speakers with a large share of the talk time, most talkative first
+    pub speakers: Vec<String>,
+    /// Index of the chapter's first segment in the transcript
+    pub first_segment: usize,
+    pub segment_count: usize,
+    /// Signals at the start of the chapter; empty for the first one
+    pub signals: Vec<ChapterSignal>,
+}
+
+/// A transcript segment as chaptering sees it
+struct Entry {
+    index: usize,
+    segment: TemporalSegment,
+    mid_sentence: bool,
+}
+
+fn entries(segments: &[serde_json::Value]) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = segments.iter().enumerate()
+        .filter_map(|(index, segment)| {
+            let text = segment["text"].as_str()?.trim();
+            if text.is_empty() {
+                return None;
+            }
+            let start_time = segment["startTime"].as_f64().unwrap_or(0.0) as f32;
+            Some(Entry {
+                index,
+                segment: TemporalSegment {
+                    text: text.to_string(),
+                    start_time,
+                    end_time: segment["endTime"].as_f64().map(|end| end as f32).unwrap_or(start_time).max(start_time),
+                    confidence: segment["confidence"].as_f64().unwrap_or(0.0) as f32,
+                    speaker_id: segment["speaker"].as_str().filter(|id| !id.is_empty()).unwrap_or(UNKNOWN_SPEAKER).to_string(),
+                },
+                mid_sentence: segment["qualityScore"]["boundaryType"].as_str()
+                    .is_some_and(|boundary| MID_SENTENCE_BOUNDARIES.contains(&boundary)),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.segment.start_time.total_cmp(&b.segment.start_time));
+    entries
+}
+
+/// Split `segments` into chapters; no chapters without transcribed text
+pub fn split_chapters(segments: &[serde_json::Value], config: &ChapterConfig) -> Vec<Chapter> {
+    let entries = entries(segments);
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    let mut analyzer = TemporalAnalyzer::new(entries.len(), 0.0, 0.0);
+    for entry in &entries {
+        analyzer.add_segment(entry.segment.clone());
+    }
+    let pauses_end_at: Vec<f32> = analyzer.find_silence_gaps(config.min_pause_seconds)
+        .into_iter()
+        .map(|(_, gap_end)| gap_end)
+        .collect();
+
+    // (position in entries, signals) of every chapter start
+    let mut starts: Vec<(usize, Vec<ChapterSignal>)> = vec![(0, Vec::new())];
+    for position in 1..entries.len() {
+        let start_time = entries[position].segment.start_time;
+        let chapter_start = entries[starts.last().map(|(start, _)| *start).unwrap_or(0)].segment.start_time;
+        if start_time - chapter_start < config.min_chapter_seconds {
+            continue;
+        }
+
+        let mut signals = Vec::new();
+        if pauses_end_at.contains(&start_time) {
+            signals.push(ChapterSignal::Pause);
+        } else if entries[position - 1].mid_sentence {
+            continue;
+        }
+        let before: Vec<&TemporalSegment> = entries.iter()
+            .map(|entry| &entry.segment)
+            .filter(|segment| segment.start_time < start_time && segment.end_time > start_time - config.window_seconds)
+            .collect();
+        let after: Vec<&TemporalSegment> = entries[position..].iter()
+            .map(|entry| &entry.segment)
+            .take_while(|segment| segment.start_time < start_time + config.window_seconds)
+            .collect();
+        // Near either end of the transcript a window holds too little to compare
+        let before_span = start_time - before.iter().map(|segment| segment.start_time).fold(start_time, f32::min);
+        let after_span = after.iter().map(|segment| segment.end_time).fold(start_time, f32::max) - start_time;
+        if before_span.min(after_span).min(config.window_seconds) >= config.window_seconds / 2.0 {
+            if speaker_similarity(&before, &after) < config.speaker_change_threshold {
+                signals.push(ChapterSignal::SpeakerChange);
+            }
+            if lexical_similarity(&before, &after) < config.lexical_similarity_threshold {
+                signals.push(ChapterSignal::LexicalShift);
+            }
+        }
+
+        if signals.contains(&ChapterSignal::Pause) || signals.len() >= 2 {
+            starts.push((position, signals));
+        }
+    }
+
+    let ends = starts.iter().skip(1).map(|(start, _)| *start).chain(std::iter::once(entries.len()));
+    starts.iter().zip(ends).enumerate()
+        .map(|(index, ((start, signals), end))| {
+            let chapter = &entries[*start..end];
+            Chapter {
+                index,
+                title: title(chapter),
+                start_time: chapter[0].segment.start_time,
+                end_time: chapter.iter().map(|entry| entry.segment.end_time).fold(0.0, f32::max),
+                speakers: dominant_speakers(chapter),
+                first_segment: chapter.iter().map(|entry| entry.index).min().unwrap_or(0),
+                segment_count: chapter.len(),
+                signals: signals.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Jaccard similarity of the speaker sets; 1.0 when either side is empty
+fn speaker_similarity(before: &[&TemporalSegment], after: &[&TemporalSegment]) -> f32 {
+    let speakers = |segments: &[&TemporalSegment]| -> BTreeSet<String> {
+        segments.iter().map(|segment| segment.speaker_id.clone()).collect()
+    };
+    let (before, after) = (speakers(before), speakers(after));
+    if before.is_empty() || after.is_empty() {
+        return 1.0;
+    }
+    before.intersection(&after).count() as f32 / before.union(&after).count() as f32
+}
+
+/// Cosine similarity of the word frequencies; 1.0 when either side has no words
+fn lexical_similarity(before: &[&TemporalSegment], after: &[&TemporalSegment]) -> f32 {
+    let (before, after) = (term_frequencies(before), term_frequencies(after));
+    if before.is_empty() || after.is_empty() {
+        return 1.0;
+    }
+    let dot: f32 = before.iter()
+        .filter_map(|(term, count)| after.get(term).map(|other| count * other))
+        .sum();
+    let norm = |terms: &HashMap<String, f32>| terms.values().map(|count| count * count).sum::<f32>().sqrt();
+    dot / (norm(&before) * norm(&after))
+}
+
+fn term_frequencies(segments: &[&TemporalSegment]) -> HashMap<String, f32> {
+    let mut frequencies = HashMap::new();
+    for segment in segments {
+        for term in terms(&segment.text) {
+            *frequencies.entry(term).or_insert(0.0) += 1.0;
+        }
+    }
+    frequencies
+}
+
+/// Lowercased content words; text in scripts without spaces is split into
+/// character bigrams instead
+fn terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+        let word = word.to_lowercase();
+        if word.chars().any(is_unspaced) {
+            let chars: Vec<char> = word.chars().collect();
+            terms.extend(chars.windows(2).map(|pair| pair.iter().collect::<String>()));
+        } else if word.chars().count() >= 3 && !STOPWORDS.contains(&word.as_str()) && !word.chars().all(|c| c.is_numeric()) {
+            terms.push(word);
+        }
+    }
+    terms
+}
+
+fn title(chapter: &[Entry]) -> String {
+    let text = chapter[0].segment.text.as_str();
+    let sentence_end = text.find(['.', '?', '!', '。', '？', '！'])
+        .map(|end| end + text[end..].chars().next().map(char::len_utf8).unwrap_or(0))
+        .unwrap_or(text.len());
+    let sentence = text[..sentence_end].trim();
+    if sentence.split_whitespace().count() >= MIN_TITLE_WORDS || sentence.chars().any(is_unspaced) {
+        return shorten(sentence);
+    }
+
+    let segments: Vec<&TemporalSegment> = chapter.iter().map(|entry| &entry.segment).collect();
+    let mut keywords: Vec<(String, f32)> = term_frequencies(&segments).into_iter().collect();
+    keywords.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    if keywords.is_empty() {
+        return shorten(sentence);
+    }
+    let keywords: Vec<String> = keywords.into_iter().take(TITLE_KEYWORDS).map(|(term, _)| term).collect();
+    let title = keywords.join(", ");
+    let mut chars = title.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or(title)
+}
+
+/// `sentence` cut to [`MAX_TITLE_CHARS`] at a word boundary
+fn shorten(sentence: &str) -> String {
+    if sentence.chars().count() <= MAX_TITLE_CHARS {
+        return sentence.to_string();
+    }
+    let cut = sentence.char_indices().nth(MAX_TITLE_CHARS).map(|(offset, _)| offset).unwrap_or(sentence.len());
+    let shortened = sentence[..cut].rsplit_once(' ').map(|(words, _)| words).unwrap_or(&sentence[..cut]);
+    format!("{}…", shortened.trim_end_matches([',', ';', ':', ' ']))
+}
+
+fn dominant_speakers(chapter: &[Entry]) -> Vec<String> {
+    let mut talk_time: Vec<(String, f32)> = Vec::new();
+    for entry in chapter {
+        let duration = entry.segment.end_time - entry.segment.start_time;
+        match talk_time.iter_mut().find(|(speaker, _)| *speaker == entry.segment.speaker_id) {
+            Some((_, seconds)) => *seconds += duration,
+            None => talk_time.push((entry.segment.speaker_id.clone(), duration)),
+        }
+    }
+    let total: f32 = talk_time.iter().map(|(_, seconds)| seconds).sum();
+    talk_time.sort_by(|a, b| b.1.total_cmp(&a.1));
+    talk_time.into_iter()
+        .filter(|(_, seconds)| total <= 0.0 || seconds / total >= MIN_SPEAKER_SHARE)
+        .take(MAX_CHAPTER_SPEAKERS)
+        .map(|(speaker, _)| speaker)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> ChapterConfig {
+        ChapterConfig { min_pause_seconds: 10.0, window_seconds: 30.0, min_chapter_seconds: 20.0, ..ChapterConfig::default() }
+    }
+
+    #[test]
+    fn test_pause_splits_unless_chapter_is_too_short() {
+        let segments = vec![
+            json!({"text": "Yes.", "startTime": 0.0, "endTime": 1.0, "speaker": "speaker_1"}),
+            json!({"text": "Budget budget forecast.", "startTime": 1.5, "endTime": 25.0, "speaker": "speaker_1"}),
+            json!({"text": "Back from the break, let's look at hiring.", "startTime": 40.0, "endTime": 44.0, "speaker": "speaker_1"}),
+            json!({"text": "Another short pause.", "startTime": 56.0, "endTime": 58.0, "speaker": "speaker_1"}),
+        ];
+        let chapters = split_chapters(&segments, &config());
+
+        assert_eq!(chapters.len(), 2, "the second pause comes too soon after the first chapter start");
+        assert_eq!(chapters[0].title, "Budget, forecast");
+        assert_eq!((chapters[0].start_time, chapters[0].end_time, chapters[0].segment_count), (0.0, 25.0, 2));
+        assert_eq!(chapters[1].title, "Back from the break, let's look at hiring.");
+        assert_eq!(chapters[1].signals[0], ChapterSignal::Pause);
+        assert_eq!((chapters[1].first_segment, chapters[1].segment_count), (2, 2));
+        assert!(split_chapters(&[json!({"text": "  ", "startTime": 0.0})], &config()).is_empty());
+    }
+
+    #[test]
+    fn test_no_split_mid_sentence_without_a_pause() {
+        let topic_a = "Quarterly revenue forecast numbers improved";
+        let topic_b = "Kubernetes cluster migration deployment planning";
+        let segment = |text: &str, start: f32, speaker: &str, boundary: &str| json!({
+            "text": text, "startTime": start, "endTime": start + 9.5, "speaker": speaker,
+            "qualityScore": { "boundaryType": boundary }
+        });
+        let segments = |boundary: &str| vec![
+            segment(topic_a, 0.0, "speaker_1", "sentence_end"),
+            segment(topic_a, 10.0, "speaker_1", "sentence_end"),
+            segment(topic_a, 20.0, "speaker_1", boundary),
+            segment(topic_b, 30.0, "speaker_2", "sentence_end"),
+            segment(topic_b, 40.0, "speaker_2", "sentence_end"),
+        ];
+
+        let chapters = split_chapters(&segments("hard"), &config());
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].signals, [ChapterSignal::SpeakerChange, ChapterSignal::LexicalShift]);
+        assert_eq!(chapters[1].speakers, ["speaker_2"]);
+        assert_eq!(chapters[1].title, "Kubernetes cluster migration deployment planning");
+        // The boundary detector saw the speaker still talking: no chapter in mid-sentence
+        assert_eq!(split_chapters(&segments("none"), &config()).len(), 1);
+    }
+
+    #[test]
+    fn test_titles_are_shortened_and_unspaced_text_compared_by_bigrams() {
+        let long = "We should decide today whether the onboarding flow needs another round of testing. Then lunch.";
+        let chapters = split_chapters(&[json!({"text": long, "startTime": 0.0, "endTime": 5.0})], &config());
+        assert_eq!(chapters[0].title, "We should decide today whether the onboarding flow needs…");
+        assert_eq!(chapters[0].speakers, [UNKNOWN_SPEAKER]);
+
+        assert_eq!(terms("価格改定"), ["価格", "格改", "改定"]);
+        let japanese = |text: &str| TemporalSegment {
+            text: text.to_string(), start_time: 0.0, end_time: 1.0, confidence: 0.9, speaker_id: "speaker_1".to_string(),
+        };
+        let (a, b, c) = (japanese("来期の価格改定"), japanese("価格改定の時期"), japanese("採用計画の確認"));
+        assert!(lexical_similarity(&[&a], &[&b]) > lexical_similarity(&[&a], &[&c]));
+    }
+}
@@ -0,0 +1,113 @@
+//! Transcription buffer window
+//!
+//! The live loop collects audio until an utterance ends or the buffer reaches
+//! its window, then hands it to Whisper. `minChunkMs` is the shortest buffer
+//! worth transcribing, `maxChunkMs` the point at which it is flushed whatever
+//! the speaker is doing. A flush that cuts through speech can split a word;
+//! `overlapMs` keeps the end of the flushed buffer as the start of the next
+//! one, and the repeated words are trimmed from the next segment.
+
+/// Defaults of `minChunkMs`, `maxChunkMs` and `overlapMs`
+pub const DEFAULT_MIN_CHUNK_MS: u64 = 4500;
+pub const DEFAULT_MAX_CHUNK_MS: u64 = 20000;
+pub const DEFAULT_OVERLAP_MS: u64 = 0;
+
+/// Accepted window bounds
+pub const MIN_CHUNK_FLOOR_MS: u64 = 250;
+pub const MAX_CHUNK_CEILING_MS: u64 = 30000;
+
+/// Buffer sizes are counted at Whisper's sample rate, which capture resamples to
+const BUFFER_SAMPLE_RATE: u64 = 16000;
+
+/// Window of a session's transcription buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkWindow {
+    pub min_ms: u64,
+    pub max_ms: u64,
+    pub overlap_ms: u64,
+}
+
+impl Default for ChunkWindow {
+    fn default() -> Self {
+        Self { min_ms: DEFAULT_MIN_CHUNK_MS, max_ms: DEFAULT_MAX_CHUNK_MS, overlap_ms: DEFAULT_OVERLAP_MS }
+    }
+}
+
+impl ChunkWindow {
+    /// Most samples the buffer may hold before its oldest audio is dropped
+    pub fn max_buffer_samples(&self) -> usize {
+        (self.max_ms * BUFFER_SAMPLE_RATE / 1000) as usize
+    }
+
+    /// Empty the buffer after it was transcribed. With `mid_speech` (the
+    /// flush cut an utterance rather than ending at one) the last `overlap_ms`
+    /// stay as the start of the next buffer. Returns how many samples were
+    /// dropped from the front, for moving the buffer's start on the stream clock.
+    pub fn flush(&self, buffer: &mut Vec<f32>, sample_rate: u32, mid_speech: bool) -> usize {
+        let overlap = (self.overlap_ms * sample_rate as u64 / 1000) as usize;
+        let keep = if mid_speech { overlap.min(buffer.len()) } else { 0 };
+        buffer.drain(..buffer.len() - keep).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::stream_clock::StreamClock;
+
+    const RATE: u32 = 16000;
+    const CHUNK: usize = 1600; // 100 ms
+
+    /// Feeds `chunks` of continuous speech, flushing whenever the buffer holds
+    /// `max_ms`, and returns the span and first sample of each flushed buffer
+    fn run(window: ChunkWindow, chunks: usize) -> Vec<((f64, f64), f32)> {
+        let mut clock = StreamClock::new();
+        let mut buffer: Vec<f32> = Vec::new();
+        let mut flushed = Vec::new();
+        for chunk in 0..chunks {
+            let chunk_start = clock.advance(CHUNK, RATE);
+            clock.begin_buffer(chunk_start);
+            // Every sample carries the index of its chunk
+            buffer.extend(vec![chunk as f32; CHUNK]);
+            if buffer.len() as u64 * 1000 / RATE as u64 >= window.max_ms {
+                flushed.push((clock.buffer_span(buffer.len(), RATE), buffer[0]));
+                let dropped = window.flush(&mut buffer, RATE, true);
+                if buffer.is_empty() {
+                    clock.clear_buffer();
+                } else {
+                    clock.trim_buffer(dropped, RATE);
+                }
+            }
+        }
+        flushed
+    }
+
+    #[test]
+    fn test_overlap_carries_the_buffer_tail() {
+        let window = ChunkWindow { min_ms: 500, max_ms: 1000, overlap_ms: 300 };
+        let flushed = run(window, 24);
+        // 1 s windows advancing by 0.7 s; each starts with the last 3 chunks of the previous one
+        let spans: Vec<(f64, f64)> = flushed.iter().map(|(span, _)| *span).collect();
+        let expected = [(0.0, 1.0), (0.7, 1.7), (1.4, 2.4)];
+        for (span, expected) in spans.iter().zip(expected) {
+            assert!((span.0 - expected.0).abs() < 1e-9 && (span.1 - expected.1).abs() < 1e-9, "{:?} vs {:?}", span, expected);
+        }
+        let first_chunks: Vec<f32> = flushed.iter().map(|(_, first)| *first).collect();
+        assert_eq!(&first_chunks[..3], &[0.0, 7.0, 14.0]);
+    }
+
+    #[test]
+    fn test_default_window_flushes_without_overlap() {
+        let window = ChunkWindow::default();
+        assert_eq!(window.max_buffer_samples(), 16000 * 20);
+        let flushed = run(ChunkWindow { max_ms: 1000, ..window }, 30);
+        let starts: Vec<f32> = flushed.iter().map(|(_, first)| *first).collect();
+        assert_eq!(starts, vec![0.0, 10.0, 20.0]);
+
+        // Ending at an utterance boundary never keeps audio
+        let mut buffer = vec![0.0; CHUNK * 10];
+        let overlapping = ChunkWindow { overlap_ms: 300, ..window };
+        assert_eq!(overlapping.flush(&mut buffer, RATE, false), CHUNK * 10);
+        assert!(buffer.is_empty());
+    }
+}
@@ -220,6 +220,103 @@ impl ContentHasher {
     }
 }
 
+/// Words of the previous segment compared when trimming a repeated boundary
+pub const DEFAULT_OVERLAP_WORDS: usize = 8;
+/// Fewest repeated words trimmed; a single shared word is too often genuine
+const MIN_OVERLAP_WORDS: usize = 2;
+/// Shortest fragment matched against part of a word
+const MIN_PARTIAL_WORD_CHARS: usize = 2;
+
+/// A word of the original text (ending at byte `end`) with its normalized form
+struct BoundaryWord {
+    end: usize,
+    normalized: String,
+}
+
+/// Kana and CJK ideographs are written without spaces, so each character counts as a word
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' | '\u{FF66}'..='\u{FF9F}')
+}
+
+/// Split `text` into words with byte positions. Punctuation is dropped from the
+/// normalized form; words that are only punctuation are skipped.
+fn boundary_words(text: &str) -> Vec<BoundaryWord> {
+    let mut words = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let normalized: String = text[start..end].chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        if !normalized.is_empty() {
+            words.push(BoundaryWord { end, normalized });
+        }
+    };
+
+    let mut word_start: Option<usize> = None;
+    for (index, c) in text.char_indices() {
+        if c.is_whitespace() || is_unspaced_script(c) {
+            if let Some(start) = word_start.take() {
+                push(start, index);
+            }
+            if is_unspaced_script(c) {
+                push(index, index + c.len_utf8());
+            }
+        } else if word_start.is_none() {
+            word_start = Some(index);
+        }
+    }
+    if let Some(start) = word_start {
+        push(start, text.len());
+    }
+    words
+}
+
+/// Words of `head` to drop when they repeat `tail`. The first repeated word may
+/// be the end of a word cut off by the buffer ("arter" for "quarter"), and the
+/// last word of `tail` may be a word the previous buffer cut off ("quar"), in
+/// which case the complete word in `head` is kept.
+fn repeated_words(tail: &[BoundaryWord], head: &[BoundaryWord]) -> Option<usize> {
+    let last = tail.len() - 1;
+    let mut trimmed = tail.len();
+    for (i, (previous, next)) in tail.iter().zip(head).enumerate() {
+        if previous.normalized == next.normalized {
+            continue;
+        }
+        let partial = |word: &BoundaryWord| word.normalized.chars().count() >= MIN_PARTIAL_WORD_CHARS;
+        if i == 0 && partial(next) && previous.normalized.ends_with(&next.normalized) {
+            continue;
+        }
+        if i == last && i > 0 && partial(previous) && next.normalized.starts_with(&previous.normalized) {
+            trimmed -= 1;
+            continue;
+        }
+        return None;
+    }
+    Some(trimmed)
+}
+
+/// Remove the start of `text` that repeats the end of `previous`, comparing up
+/// to `max_words` words. Chunk buffers overlap around utterance boundaries, so
+/// the decoder often hears the last words of the previous segment again.
+pub fn trim_repeated_boundary<'a>(previous: &str, text: &'a str, max_words: usize) -> &'a str {
+    let previous_words = boundary_words(previous);
+    let words = boundary_words(text);
+    let longest = max_words.min(previous_words.len()).min(words.len());
+
+    for length in (MIN_OVERLAP_WORDS..=longest).rev() {
+        let tail = &previous_words[previous_words.len() - length..];
+        let Some(trimmed) = repeated_words(tail, &words[..length]) else {
+            continue;
+        };
+        if trimmed == 0 {
+            continue;
+        }
+        return text[words[trimmed - 1].end..]
+            .trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ',' | '.' | ';' | ':' | '!' | '?' | '、' | '。' | '，' | '…'));
+    }
+    text
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,4 +360,33 @@ mod tests {
         let (cache_size, _) = hasher.get_cache_stats();
         assert!(cache_size <= 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_repeated_boundary_words_are_trimmed() {
+        let previous = "We expect revenue to grow next quarter.";
+        assert_eq!(trim_repeated_boundary(previous, "next quarter. Next quarter we will hire.", 8), "Next quarter we will hire.");
+        assert_eq!(trim_repeated_boundary(previous, "Next quarter, we will hire.", 8), "we will hire.");
+        assert_eq!(trim_repeated_boundary(previous, "grow next quarter.", 8), "");
+        // A single shared word or a disabled window leaves the text alone
+        assert_eq!(trim_repeated_boundary(previous, "Quarter results are out.", 8), "Quarter results are out.");
+        assert_eq!(trim_repeated_boundary(previous, "next quarter we will hire.", 0), "next quarter we will hire.");
+        // Only the last `max_words` words of the previous segment are compared
+        assert_eq!(trim_repeated_boundary(previous, "revenue to grow next quarter and more", 3), "revenue to grow next quarter and more");
+    }
+
+    #[test]
+    fn test_partial_word_overlaps() {
+        // The new buffer starts inside a word; a lone fragment is not enough to trim
+        assert_eq!(trim_repeated_boundary("see you next quarter", "arter we will hire", 8), "arter we will hire");
+        assert_eq!(trim_repeated_boundary("see you next quarter", "xt quarter we will hire", 8), "we will hire");
+        // The previous buffer cut the last word off; the complete word is kept
+        assert_eq!(trim_repeated_boundary("see you next quar", "next quarter we will hire", 8), "quarter we will hire");
+    }
+
+    #[test]
+    fn test_unicode_boundaries() {
+        assert_eq!(trim_repeated_boundary("来週の会議で決めましょう。", "決めましょう。では次の議題です。", 8), "では次の議題です。");
+        assert_eq!(trim_repeated_boundary("Le café était très bon", "Très bon, et ensuite", 8), "et ensuite");
+        assert_eq!(trim_repeated_boundary("Продажи выросли в квартале", "в квартале мы наняли", 8), "мы наняли");
+    }
+}
@@ -0,0 +1,199 @@
+//! Hallucination filter
+//!
+//! Whisper produces text on near-silent audio: closing phrases from its video
+//! training data ("Thank you for watching") or the same sentence over and over.
+//! After decoding, a segment is held back when its buffer carried almost no
+//! speech, when its text is nothing but known hallucination phrases, or when it
+//! repeats the previous segment verbatim too many times. Filtered segments are
+//! counted for the quality metrics; in debug mode they are kept and flagged.
+
+use serde::{Deserialize, Serialize};
+
+/// Mean speech probability of a buffer below which its text is not trusted
+pub const LOW_SPEECH_PROBABILITY: f32 = 0.1;
+/// Verbatim repeats of the previous segment allowed before filtering
+pub const DEFAULT_MAX_REPEATS: u32 = 2;
+
+/// Phrases Whisper is known to produce on silence
+pub const DEFAULT_BLOCKLIST: &[&str] = &[
+    "thank you for watching",
+    "thanks for watching",
+    "thank you so much for watching",
+    "please subscribe",
+    "like and subscribe",
+    "subtitles by the amaraorg community",
+    "see you in the next video",
+    "ご視聴ありがとうございました",
+    "チャンネル登録お願いします",
+];
+
+/// Why a segment was taken for a hallucination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HallucinationReason {
+    LowSpeechProbability,
+    Blocklisted,
+    Repetition,
+}
+
+impl HallucinationReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LowSpeechProbability => "low_speech_probability",
+            Self::Blocklisted => "blocklisted",
+            Self::Repetition => "repetition",
+        }
+    }
+}
+
+/// Lowercase letters and digits, single spaces between words
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Mean speech probability of the chunks in the transcription buffer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BufferSpeech {
+    sum: f32,
+    chunks: u32,
+}
+
+impl BufferSpeech {
+    pub fn observe(&mut self, speech_probability: f32) {
+        if speech_probability.is_finite() {
+            self.sum += speech_probability.clamp(0.0, 1.0);
+            self.chunks += 1;
+        }
+    }
+
+    pub fn mean(&self) -> Option<f32> {
+        (self.chunks > 0).then(|| self.sum / self.chunks as f32)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Post-ASR check of each decoded segment
+#[derive(Debug, Clone)]
+pub struct HallucinationFilter {
+    blocklist: Vec<String>,
+    max_repeats: u32,
+    previous: Option<String>,
+    repeats: u32,
+}
+
+impl HallucinationFilter {
+    /// Filter with the default phrases plus `extra_phrases`
+    pub fn new(extra_phrases: &[String], max_repeats: u32) -> Self {
+        let mut blocklist: Vec<String> = DEFAULT_BLOCKLIST.iter()
+            .map(|phrase| normalize(phrase))
+            .chain(extra_phrases.iter().map(|phrase| normalize(phrase)))
+            .filter(|phrase| !phrase.is_empty())
+            .collect();
+        // Longest first, so a phrase containing another is removed whole
+        blocklist.sort_by_key(|phrase| std::cmp::Reverse(phrase.len()));
+        blocklist.dedup();
+        Self {
+            blocklist,
+            max_repeats,
+            previous: None,
+            repeats: 0,
+        }
+    }
+
+    /// The text consists only of blocklisted phrases
+    pub fn is_blocklisted(&self, text: &str) -> bool {
+        let mut remaining = normalize(text);
+        if remaining.is_empty() {
+            return false;
+        }
+        for phrase in &self.blocklist {
+            remaining = remaining.replace(phrase.as_str(), " ");
+        }
+        remaining.trim().is_empty()
+    }
+
+    /// Track `text` against the previous segment; true once it has been
+    /// repeated verbatim more than the allowed number of times
+    pub fn is_repetition(&mut self, text: &str) -> bool {
+        let normalized = normalize(text);
+        if self.previous.as_deref() == Some(normalized.as_str()) {
+            self.repeats += 1;
+        } else {
+            self.previous = Some(normalized);
+            self.repeats = 0;
+        }
+        self.repeats > self.max_repeats
+    }
+
+    /// Check a decoded segment and the mean speech probability of its buffer
+    pub fn check(&mut self, text: &str, buffer_speech: Option<f32>) -> Option<HallucinationReason> {
+        // Repeats are tracked for every segment so a run is counted from its start
+        let repeated = self.is_repetition(text);
+        if buffer_speech.is_some_and(|speech| speech < LOW_SPEECH_PROBABILITY) {
+            Some(HallucinationReason::LowSpeechProbability)
+        } else if self.is_blocklisted(text) {
+            Some(HallucinationReason::Blocklisted)
+        } else if repeated {
+            Some(HallucinationReason::Repetition)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocklist_matches_whole_phrases_only() {
+        let filter = HallucinationFilter::new(&["Untertitel im Auftrag des ZDF".to_string()], DEFAULT_MAX_REPEATS);
+        assert!(filter.is_blocklisted("Thank you for watching!"));
+        assert!(filter.is_blocklisted("  THANKS FOR WATCHING. Please subscribe."));
+        assert!(filter.is_blocklisted("ご視聴ありがとうございました。"));
+        assert!(filter.is_blocklisted("Untertitel im Auftrag des ZDF."));
+        // Real speech containing a phrase is kept
+        assert!(!filter.is_blocklisted("Thank you for watching the demo, now the numbers."));
+        assert!(!filter.is_blocklisted("Thank you."));
+        assert!(!filter.is_blocklisted("..."));
+    }
+
+    #[test]
+    fn test_repetition_detector() {
+        let mut filter = HallucinationFilter::new(&[], 2);
+        assert!(!filter.is_repetition("The meeting is adjourned."));
+        assert!(!filter.is_repetition("the meeting is adjourned"));
+        assert!(!filter.is_repetition("The meeting is adjourned."));
+        assert!(filter.is_repetition("The meeting is adjourned."));
+        assert!(filter.is_repetition("The meeting is adjourned!"));
+        // A different segment ends the run
+        assert!(!filter.is_repetition("Next item."));
+        assert!(!filter.is_repetition("The meeting is adjourned."));
+    }
+
+    #[test]
+    fn test_check_reports_reason() {
+        let mut filter = HallucinationFilter::new(&[], 0);
+        assert_eq!(filter.check("Let's begin.", Some(0.8)), None);
+        assert_eq!(filter.check("Let's begin.", Some(0.8)), Some(HallucinationReason::Repetition));
+        assert_eq!(filter.check("Quarterly numbers.", Some(0.02)), Some(HallucinationReason::LowSpeechProbability));
+        assert_eq!(filter.check("Thanks for watching", None), Some(HallucinationReason::Blocklisted));
+
+        let mut speech = BufferSpeech::default();
+        assert_eq!(speech.mean(), None);
+        speech.observe(0.9);
+        speech.observe(0.1);
+        assert!((speech.mean().unwrap() - 0.5).abs() < 1e-6);
+        speech.reset();
+        assert_eq!(speech.mean(), None);
+    }
+}
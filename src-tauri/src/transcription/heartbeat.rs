@@ -0,0 +1,74 @@
+//! Liveness of a session's transcription loop
+//!
+//! A loop that panics (a whisper.cpp FFI error, say) or hangs leaves its
+//! session in the active sessions, blocking every later start with "another
+//! session is already active". The loop beats on every iteration; a session
+//! whose loop has started but not beaten for [`STALL_TIMEOUT`] is treated as
+//! dead and cleaned up. Before the loop starts (while the model loads) there
+//! is no heartbeat and the session is never considered stalled.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Silence after which a running loop is considered dead. Well above the
+/// longest single decode of a 20 s buffer on a slow machine.
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Last iteration of a session's loop, shared by the session state and the loop
+#[derive(Debug, Clone, Default)]
+pub struct SessionHeartbeat {
+    last: Arc<Mutex<Option<Instant>>>,
+}
+
+impl SessionHeartbeat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn beat(&self) {
+        *self.last.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Instant::now());
+    }
+
+    /// Time since the last beat; `None` until the loop has started
+    pub fn age(&self) -> Option<Duration> {
+        self.last.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).map(|last| last.elapsed())
+    }
+
+    pub fn is_stalled(&self, timeout: Duration) -> bool {
+        self.age().is_some_and(|age| age > timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_heartbeat_before_the_loop_starts() {
+        let heartbeat = SessionHeartbeat::new();
+        assert_eq!(heartbeat.age(), None);
+        assert!(!heartbeat.is_stalled(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_loop_that_stops_beating_is_stalled() {
+        let timeout = Duration::from_millis(100);
+        let heartbeat = SessionHeartbeat::new();
+        let looping = tokio::spawn({
+            let heartbeat = heartbeat.clone();
+            async move {
+                for _ in 0..5 {
+                    heartbeat.beat();
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                panic!("whisper FFI error");
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!heartbeat.is_stalled(timeout));
+        assert!(looping.await.is_err());
+        tokio::time::sleep(timeout * 2).await;
+        assert!(heartbeat.is_stalled(timeout));
+    }
+}
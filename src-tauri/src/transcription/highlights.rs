@@ -0,0 +1,306 @@
+//! Highlight capture
+//!
+//! "Save the last two minutes": a highlight is the trailing window of a
+//! session saved next to the session audio as a standalone clip, together with
+//! the transcript snippet and the speakers heard in it. Each highlight is a
+//! `<id>.json` description plus, when the session audio is retained, a
+//! `<id>.wav` clip in the session's `highlights` directory. Without retained
+//! audio the highlight is transcript-only and carries a warning.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::diarization::muting;
+use crate::metadata;
+
+/// Directory name below the session directory
+pub const HIGHLIGHTS_DIR: &str = "highlights";
+/// Longest accepted lookback (seconds)
+pub const MAX_LOOKBACK_SECS: f32 = 30.0 * 60.0;
+
+/// A saved highlight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Highlight {
+    pub id: String,
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    pub label: String,
+    /// Unix milliseconds
+    #[serde(rename = "createdAt")]
+    pub created_at: u64,
+    /// Window in session time (seconds)
+    #[serde(rename = "startTime")]
+    pub start_time: f32,
+    #[serde(rename = "endTime")]
+    pub end_time: f32,
+    /// Lookback was longer than the session so far
+    #[serde(rename = "lookbackClamped")]
+    pub lookback_clamped: bool,
+    /// Transcript snippet of the window
+    pub transcript: String,
+    pub segments: Vec<serde_json::Value>,
+    pub speakers: Vec<String>,
+    /// Clip file, absent for transcript-only highlights
+    #[serde(rename = "audioPath")]
+    pub audio_path: Option<String>,
+    #[serde(rename = "audioDuration")]
+    pub audio_duration: Option<f32>,
+    pub warning: Option<String>,
+}
+
+/// What to capture from which session
+#[derive(Debug, Clone)]
+pub struct HighlightRequest<'a> {
+    pub session_id: &'a str,
+    pub label: &'a str,
+    pub lookback_secs: f32,
+    /// Session time at capture (seconds since the session started)
+    pub session_elapsed: f32,
+    pub segments: &'a [serde_json::Value],
+    /// Retained session audio, if any; its end is aligned with `session_elapsed`
+    pub recording_path: Option<&'a Path>,
+    pub highlights_dir: &'a Path,
+    pub created_at: u64,
+}
+
+/// Trailing window `[end - lookback, end]`, clamped to the session start
+pub fn highlight_window(session_elapsed: f32, lookback_secs: f32) -> (f32, f32, bool) {
+    let end = session_elapsed.max(0.0);
+    let lookback = lookback_secs.clamp(0.0, MAX_LOOKBACK_SECS);
+    let clamped = lookback > end;
+    ((end - lookback).max(0.0), end, clamped)
+}
+
+/// Stored segments overlapping the window; muted speakers are left out
+pub fn segments_in_window(segments: &[serde_json::Value], start: f32, end: f32) -> Vec<serde_json::Value> {
+    segments.iter()
+        .filter(|segment| !muting::is_muted_segment(segment))
+        .filter(|segment| {
+            let segment_start = segment["startTime"].as_f64().unwrap_or(0.0) as f32;
+            let segment_end = segment["endTime"].as_f64().unwrap_or(segment_start as f64) as f32;
+            segment_end > start && segment_start < end
+        })
+        .cloned()
+        .collect()
+}
+
+/// Capture a highlight and write it to `request.highlights_dir`
+pub fn capture_highlight(request: &HighlightRequest) -> io::Result<Highlight> {
+    let (start_time, end_time, lookback_clamped) = highlight_window(request.session_elapsed, request.lookback_secs);
+    let segments = segments_in_window(request.segments, start_time, end_time);
+    let speakers: Vec<String> = segments.iter()
+        .filter_map(|s| s["speaker"].as_str().map(str::to_string))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let transcript = segments.iter()
+        .filter_map(|s| s["text"].as_str())
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    fs::create_dir_all(request.highlights_dir)?;
+    // Labels become part of the file names; two captures in the same millisecond get a suffix
+    let base = format!("highlight-{}-{}", request.created_at, metadata::slugify(request.label));
+    let id = metadata::unique_slug(&base, |candidate| {
+        request.highlights_dir.join(format!("{}.json", candidate)).exists()
+    });
+    let wanted = end_time - start_time;
+
+    let (audio_path, audio_duration, warning) = match request.recording_path.filter(|p| p.exists()) {
+        Some(recording) => {
+            let clip_path = request.highlights_dir.join(format!("{}.wav", id));
+            let duration = extract_trailing_clip(recording, &clip_path, wanted)?;
+            let warning = (duration + 0.05 < wanted).then(|| {
+                format!("Only {:.1}s of the requested {:.1}s of audio were recorded", duration, wanted)
+            });
+            (Some(clip_path.to_string_lossy().to_string()), Some(duration), warning)
+        }
+        None => (None, None, Some("Session audio is not retained; highlight saved without audio".to_string())),
+    };
+
+    let highlight = Highlight {
+        id,
+        session_id: request.session_id.to_string(),
+        label: request.label.to_string(),
+        created_at: request.created_at,
+        start_time,
+        end_time,
+        lookback_clamped,
+        transcript,
+        segments,
+        speakers,
+        audio_path,
+        audio_duration,
+        warning,
+    };
+    let description = serde_json::to_vec_pretty(&highlight).map_err(io::Error::other)?;
+    fs::write(request.highlights_dir.join(format!("{}.json", highlight.id)), description)?;
+    Ok(highlight)
+}
+
+/// Copy the last `seconds` of a WAV recording into a new file; returns the clip duration
+pub fn extract_trailing_clip(source: &Path, target: &Path, seconds: f32) -> io::Result<f32> {
+    let to_io = |e: hound::Error| match e {
+        hound::Error::IoError(e) => e,
+        other => io::Error::other(other.to_string()),
+    };
+    let mut reader = hound::WavReader::new(BufReader::new(fs::File::open(source)?)).map_err(to_io)?;
+    let spec = reader.spec();
+    let total_frames = reader.duration();
+    let wanted_frames = (seconds.max(0.0) * spec.sample_rate as f32).round() as u32;
+    let start_frame = total_frames.saturating_sub(wanted_frames);
+    reader.seek(start_frame)?;
+
+    let mut writer = hound::WavWriter::create(target, spec).map_err(to_io)?;
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            for sample in reader.samples::<f32>() {
+                writer.write_sample(sample.map_err(to_io)?).map_err(to_io)?;
+            }
+        }
+        hound::SampleFormat::Int => {
+            for sample in reader.samples::<i32>() {
+                writer.write_sample(sample.map_err(to_io)?).map_err(to_io)?;
+            }
+        }
+    }
+    writer.finalize().map_err(to_io)?;
+    Ok((total_frames - start_frame) as f32 / spec.sample_rate as f32)
+}
+
+/// Highlights saved in `dir`, oldest first
+pub fn list_highlights(dir: &Path) -> io::Result<Vec<Highlight>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut highlights = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        match fs::read(&path).map(|bytes| serde_json::from_slice::<Highlight>(&bytes)) {
+            Ok(Ok(highlight)) => highlights.push(highlight),
+            _ => tracing::warn!("Skipping unreadable highlight {:?}", path),
+        }
+    }
+    highlights.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+    Ok(highlights)
+}
+
+/// Highlights directory of a session: next to its recording, or in `default_session_dir`
+pub fn highlights_dir(recording_path: Option<&Path>, default_session_dir: &Path) -> PathBuf {
+    recording_path
+        .and_then(Path::parent)
+        .unwrap_or(default_session_dir)
+        .join(HIGHLIGHTS_DIR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 1000;
+
+    /// 60s session: one 4s segment every 5s, speakers alternating, recording of `recorded` seconds
+    fn synthetic_session(dir: &Path, recorded: f32) -> (Vec<serde_json::Value>, PathBuf) {
+        let segments = (0..12).map(|i| {
+            serde_json::json!({
+                "text": format!("sentence {}", i),
+                "startTime": i as f32 * 5.0,
+                "endTime": i as f32 * 5.0 + 4.0,
+                "speaker": if i % 2 == 0 { "speaker_1" } else { "speaker_2" },
+            })
+        }).collect();
+
+        let path = dir.join("audio.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: SAMPLE_RATE, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..(recorded * SAMPLE_RATE as f32) as u32 {
+            writer.write_sample((i % 100) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        (segments, path)
+    }
+
+    fn request<'a>(segments: &'a [serde_json::Value], recording: Option<&'a Path>, dir: &'a Path, elapsed: f32, lookback: f32, at: u64) -> HighlightRequest<'a> {
+        HighlightRequest {
+            session_id: "session-1",
+            label: "Decision",
+            lookback_secs: lookback,
+            session_elapsed: elapsed,
+            segments,
+            recording_path: recording,
+            highlights_dir: dir,
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn test_clip_duration_and_snippet_boundaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let (segments, recording) = synthetic_session(dir.path(), 30.0);
+        let highlights = dir.path().join(HIGHLIGHTS_DIR);
+
+        // At 30s, the last 12s cover [18, 30]: segments 3 (15-19) through 5 (25-29)
+        let highlight = capture_highlight(&request(&segments, Some(&recording), &highlights, 30.0, 12.0, 1)).unwrap();
+        assert_eq!((highlight.start_time, highlight.end_time), (18.0, 30.0));
+        assert_eq!(highlight.transcript, "sentence 3 sentence 4 sentence 5");
+        assert_eq!(highlight.speakers, vec!["speaker_1".to_string(), "speaker_2".to_string()]);
+        assert_eq!(highlight.audio_duration, Some(12.0));
+        assert!(highlight.warning.is_none());
+
+        let reader = hound::WavReader::open(highlight.audio_path.as_ref().unwrap()).unwrap();
+        assert_eq!(reader.duration(), 12 * SAMPLE_RATE);
+        // The clip is the tail of the recording
+        let first: i16 = reader.into_samples::<i16>().next().unwrap().unwrap();
+        assert_eq!(first, ((18 * SAMPLE_RATE) % 100) as i16);
+    }
+
+    #[test]
+    fn test_lookback_longer_than_session_clamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let (segments, recording) = synthetic_session(dir.path(), 8.0);
+        let highlights = dir.path().join(HIGHLIGHTS_DIR);
+
+        let highlight = capture_highlight(&request(&segments, Some(&recording), &highlights, 8.0, 120.0, 1)).unwrap();
+        assert!(highlight.lookback_clamped);
+        assert_eq!(highlight.start_time, 0.0);
+        assert_eq!(highlight.audio_duration, Some(8.0));
+        assert_eq!(highlight.transcript, "sentence 0 sentence 1");
+    }
+
+    #[test]
+    fn test_transcript_only_when_audio_not_retained() {
+        let dir = tempfile::tempdir().unwrap();
+        let (segments, _) = synthetic_session(dir.path(), 1.0);
+        let highlights = dir.path().join(HIGHLIGHTS_DIR);
+
+        let highlight = capture_highlight(&request(&segments, None, &highlights, 60.0, 10.0, 1)).unwrap();
+        assert!(highlight.audio_path.is_none());
+        assert!(highlight.warning.as_deref().unwrap().contains("not retained"));
+        assert_eq!(highlight.transcript, "sentence 10 sentence 11");
+    }
+
+    #[test]
+    fn test_highlights_are_listed_in_capture_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let (segments, recording) = synthetic_session(dir.path(), 60.0);
+        let highlights = dir.path().join(HIGHLIGHTS_DIR);
+
+        for (elapsed, at) in [(20.0, 300), (45.0, 100), (60.0, 200)] {
+            capture_highlight(&request(&segments, Some(&recording), &highlights, elapsed, 10.0, at)).unwrap();
+        }
+        let listed = list_highlights(&highlights).unwrap();
+        let ends: Vec<f32> = listed.iter().map(|h| h.end_time).collect();
+        assert_eq!(ends, vec![45.0, 60.0, 20.0]);
+        assert!(listed.iter().all(|h| h.audio_duration == Some(10.0)));
+        assert!(list_highlights(&dir.path().join("missing")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,449 @@
+//! Keyword spotting
+//!
+//! Every stored segment is checked against the session's keyword rules so the
+//! frontend can alert as soon as a competitor or pricing comes up. A rule
+//! matches its phrase:
+//! - `exact`: anywhere in the text, as typed
+//! - `wordBoundary`: only as whole words
+//! - `fuzzy`: as whole words, each within a small edit distance, so
+//!   "Acme Corp" still matches a transcribed "Akme Corp"
+//!
+//! Scripts written without spaces (Japanese, Chinese, Thai, ...) have no word
+//! boundaries to look for, so phrases in them and phrases next to them fall
+//! back to substring matching. Rules are compiled once per session and the
+//! text is case-folded once per segment, which keeps a few hundred rules well
+//! below a millisecond per segment (see `benches/keyword_spotting.rs`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Most rules a session accepts
+pub const MAX_KEYWORD_RULES: usize = 500;
+/// Longest accepted phrase (characters)
+pub const MAX_KEYWORD_CHARS: usize = 100;
+/// Characters of context on each side of a match in a snippet
+pub const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+const ELLIPSIS: &str = "…";
+
+/// How a rule's phrase has to appear in a segment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchMode {
+    Exact,
+    #[default]
+    WordBoundary,
+    Fuzzy,
+}
+
+/// A phrase to spot, from the session config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordRule {
+    pub phrase: String,
+    #[serde(default)]
+    pub match_mode: MatchMode,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+impl KeywordRule {
+    pub fn new(phrase: &str, match_mode: MatchMode) -> Self {
+        Self { phrase: phrase.to_string(), match_mode, case_sensitive: false }
+    }
+}
+
+/// Trimmed rules with single spaces, without blank, over-long or duplicate
+/// phrases, capped at [`MAX_KEYWORD_RULES`]. A phrase listed twice is a
+/// duplicate whatever its mode; the first rule for it is kept.
+pub fn normalize_rules(rules: &[KeywordRule]) -> Vec<KeywordRule> {
+    let mut seen = HashSet::new();
+    rules.iter()
+        .map(|rule| KeywordRule {
+            phrase: rule.phrase.split_whitespace().collect::<Vec<_>>().join(" "),
+            ..rule.clone()
+        })
+        .filter(|rule| !rule.phrase.is_empty() && rule.phrase.chars().count() <= MAX_KEYWORD_CHARS)
+        .filter(|rule| seen.insert(rule.phrase.to_lowercase()))
+        .take(MAX_KEYWORD_RULES)
+        .collect()
+}
+
+/// One occurrence of a rule's phrase; `start..end` are byte offsets into the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordMatch {
+    pub keyword: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Payload of the keyword-detected event: a keyword found in a stored segment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordDetection {
+    pub keyword: String,
+    pub segment_index: usize,
+    /// Segment start in session time (seconds)
+    pub timestamp: f64,
+    /// The first occurrence with some surrounding text
+    pub snippet: String,
+    /// Occurrences in the segment
+    pub count: usize,
+}
+
+/// How often a keyword came up over a whole session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordSummary {
+    pub keyword: String,
+    pub count: usize,
+    /// Start of the segment of each occurrence (seconds)
+    pub timestamps: Vec<f64>,
+}
+
+struct CompiledRule {
+    keyword: String,
+    needle: String,
+    mode: MatchMode,
+    case_sensitive: bool,
+    /// Written in a script without spaces, so only substrings can be matched
+    unspaced: bool,
+    /// Words of the needle, for fuzzy matching
+    words: Vec<Vec<char>>,
+}
+
+/// Matches a session's keyword rules against segment text
+pub struct KeywordMatcher {
+    rules: Vec<CompiledRule>,
+}
+
+impl KeywordMatcher {
+    /// Compile `rules`, dropping those without a usable phrase
+    pub fn new(rules: &[KeywordRule]) -> Self {
+        let rules = normalize_rules(rules).into_iter()
+            .map(|rule| {
+                let needle = if rule.case_sensitive { rule.phrase.clone() } else { fold_case(&rule.phrase).text };
+                CompiledRule {
+                    unspaced: needle.chars().any(is_unspaced),
+                    words: word_spans(&needle).into_iter().map(|(start, end)| needle[start..end].chars().collect()).collect(),
+                    keyword: rule.phrase,
+                    needle,
+                    mode: rule.match_mode,
+                    case_sensitive: rule.case_sensitive,
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Every occurrence of every rule in `text`, ordered by position
+    pub fn find(&self, text: &str) -> Vec<KeywordMatch> {
+        let mut matches = Vec::new();
+        if self.rules.is_empty() || text.is_empty() {
+            return matches;
+        }
+        let folded = fold_case(text);
+        let mut original_words = None;
+        let mut folded_words = None;
+
+        for rule in &self.rules {
+            let (haystack, words) = if rule.case_sensitive {
+                (text, &mut original_words)
+            } else {
+                (folded.text.as_str(), &mut folded_words)
+            };
+            let mut found = |start: usize, end: usize| {
+                let (start, end) = if rule.case_sensitive { (start, end) } else { (folded.origin[start], folded.origin[end]) };
+                matches.push(KeywordMatch { keyword: rule.keyword.clone(), start, end });
+            };
+
+            match rule.mode {
+                MatchMode::Fuzzy if !rule.unspaced => {
+                    let words = words.get_or_insert_with(|| TextWords::new(haystack));
+                    for (start, end) in words.fuzzy_matches(&rule.words) {
+                        found(start, end);
+                    }
+                }
+                mode => {
+                    for (start, matched) in haystack.match_indices(rule.needle.as_str()) {
+                        let end = start + matched.len();
+                        if mode == MatchMode::Exact || rule.unspaced || at_word_boundaries(haystack, start, end) {
+                            found(start, end);
+                        }
+                    }
+                }
+            }
+        }
+        matches.sort_by_key(|m| (m.start, m.end));
+        matches
+    }
+
+    /// Keywords found in a stored segment, one detection per keyword
+    pub fn detections(&self, segment: &serde_json::Value, segment_index: usize) -> Vec<KeywordDetection> {
+        let text = segment["text"].as_str().unwrap_or_default();
+        let mut detections: Vec<KeywordDetection> = Vec::new();
+        for found in self.find(text) {
+            match detections.iter_mut().find(|detection| detection.keyword == found.keyword) {
+                Some(detection) => detection.count += 1,
+                None => detections.push(KeywordDetection {
+                    snippet: snippet(text, found.start, found.end),
+                    keyword: found.keyword,
+                    segment_index,
+                    timestamp: segment["startTime"].as_f64().unwrap_or(0.0),
+                    count: 1,
+                }),
+            }
+        }
+        detections
+    }
+
+    /// Occurrences of every rule over `segments`, in rule order; keywords
+    /// that never came up are listed with a count of zero
+    pub fn summarize(&self, segments: &[serde_json::Value]) -> Vec<KeywordSummary> {
+        let mut summaries: Vec<KeywordSummary> = self.rules.iter()
+            .map(|rule| KeywordSummary { keyword: rule.keyword.clone(), count: 0, timestamps: Vec::new() })
+            .collect();
+        for segment in segments {
+            let timestamp = segment["startTime"].as_f64().unwrap_or(0.0);
+            for found in self.find(segment["text"].as_str().unwrap_or_default()) {
+                if let Some(summary) = summaries.iter_mut().find(|summary| summary.keyword == found.keyword) {
+                    summary.count += 1;
+                    summary.timestamps.push(timestamp);
+                }
+            }
+        }
+        summaries
+    }
+}
+
+/// Lowercased text with the byte offset in the original text of every byte
+struct Folded {
+    text: String,
+    origin: Vec<usize>,
+}
+
+fn fold_case(text: &str) -> Folded {
+    let mut folded = Folded { text: String::with_capacity(text.len()), origin: Vec::with_capacity(text.len() + 1) };
+    for (offset, c) in text.char_indices() {
+        for lower in c.to_lowercase() {
+            folded.text.push(lower);
+            folded.origin.resize(folded.text.len(), offset);
+        }
+    }
+    folded.origin.push(text.len());
+    folded
+}
+
+/// Characters of scripts written without spaces between words
+pub(crate) fn is_unspaced(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0EFF}'     // Thai, Lao
+        | '\u{1000}'..='\u{109F}'   // Myanmar
+        | '\u{1780}'..='\u{17FF}'   // Khmer
+        | '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+        | '\u{31F0}'..='\u{31FF}'
+        | '\u{3400}'..='\u{4DBF}'   // CJK ideographs
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF66}'..='\u{FF9F}'   // Halfwidth Katakana
+        | '\u{20000}'..='\u{2FA1F}')
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '\''
+}
+
+/// Whether `text[start..end]` neither starts nor ends inside a word. Next to
+/// a script without spaces any position is a boundary.
+fn at_word_boundaries(text: &str, start: usize, end: usize) -> bool {
+    let matched = &text[start..end];
+    let separated = |outside: Option<char>, inside: Option<char>| match (outside, inside) {
+        (Some(outside), Some(inside)) => !is_word_char(outside) || !is_word_char(inside) || is_unspaced(outside) || is_unspaced(inside),
+        _ => true,
+    };
+    separated(text[..start].chars().next_back(), matched.chars().next())
+        && separated(text[end..].chars().next(), matched.chars().next_back())
+}
+
+/// Byte ranges of the words of `text`
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (offset, c) in text.char_indices() {
+        match (is_word_char(c), start) {
+            (true, None) => start = Some(offset),
+            (false, Some(word_start)) => {
+                spans.push((word_start, offset));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        spans.push((word_start, text.len()));
+    }
+    spans
+}
+
+/// Words of a segment, split once and shared by all fuzzy rules
+struct TextWords {
+    spans: Vec<(usize, usize)>,
+    chars: Vec<Vec<char>>,
+    /// Which words are close to a phrase word, for each phrase word seen so
+    /// far; rules often share words ("competitor X", "competitor Y")
+    close: HashMap<Vec<char>, Vec<bool>>,
+}
+
+impl TextWords {
+    fn new(text: &str) -> Self {
+        let spans = word_spans(text);
+        let chars = spans.iter().map(|&(start, end)| text[start..end].chars().collect()).collect();
+        Self { spans, chars, close: HashMap::new() }
+    }
+
+    /// Non-overlapping runs of words each close to the corresponding phrase word
+    fn fuzzy_matches(&mut self, phrase: &[Vec<char>]) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        if phrase.is_empty() || phrase.len() > self.chars.len() {
+            return found;
+        }
+        for expected in phrase {
+            if !self.close.contains_key(expected) {
+                let tolerance = fuzzy_tolerance(expected.len());
+                let close = self.chars.iter().map(|word| within_distance(expected, word, tolerance)).collect();
+                self.close.insert(expected.clone(), close);
+            }
+        }
+        let close: Vec<&Vec<bool>> = phrase.iter().map(|expected| &self.close[expected]).collect();
+
+        let mut index = 0;
+        while index + phrase.len() <= self.chars.len() {
+            let close = close.iter().enumerate().all(|(offset, close)| close[index + offset]);
+            if close {
+                found.push((self.spans[index].0, self.spans[index + phrase.len() - 1].1));
+                index += phrase.len();
+            } else {
+                index += 1;
+            }
+        }
+        found
+    }
+}
+
+/// Edits allowed in a word of `len` characters: none up to three characters,
+/// then one per four
+fn fuzzy_tolerance(len: usize) -> usize {
+    len / 4
+}
+
+/// Whether the Levenshtein distance between `a` and `b` is at most `max`
+fn within_distance(a: &[char], b: &[char], max: usize) -> bool {
+    if a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+    if max == 0 {
+        return a == b;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, &ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().min().is_some_and(|&best| best > max) {
+            return false;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()] <= max
+}
+
+/// `text[start..end]` with up to [`SNIPPET_CONTEXT_CHARS`] on either side
+fn snippet(text: &str, start: usize, end: usize) -> String {
+    let before_start = text[..start].char_indices().rev()
+        .nth(SNIPPET_CONTEXT_CHARS - 1)
+        .map(|(offset, _)| offset)
+        .unwrap_or(0);
+    let after_end = text[end..].char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS)
+        .map(|(offset, _)| end + offset)
+        .unwrap_or(text.len());
+    format!(
+        "{}{}{}",
+        if before_start > 0 { ELLIPSIS } else { "" },
+        text[before_start..after_end].trim(),
+        if after_end < text.len() { ELLIPSIS } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(matcher: &KeywordMatcher, text: &str) -> Vec<String> {
+        matcher.find(text).into_iter().map(|m| text[m.start..m.end].to_string()).collect()
+    }
+
+    #[test]
+    fn test_modes_and_case_sensitivity() {
+        let matcher = KeywordMatcher::new(&[
+            KeywordRule::new("price", MatchMode::WordBoundary),
+            KeywordRule::new("  Acme   Corp ", MatchMode::Exact),
+            KeywordRule { case_sensitive: true, ..KeywordRule::new("SLA", MatchMode::WordBoundary) },
+            KeywordRule::new(" ", MatchMode::Exact),
+        ]);
+
+        assert_eq!(keywords(&matcher, "The PRICE of the ACME CORPORATION, the price."), ["PRICE", "ACME CORP", "price"]);
+        assert!(keywords(&matcher, "Pricey, but the overpriced sla was fine").is_empty());
+        assert_eq!(keywords(&matcher, "Our SLA (\"SLA\") covers it"), ["SLA", "SLA"]);
+        // Case folding that changes the byte length still maps back to the original text
+        assert_eq!(keywords(&matcher, "İİ price"), ["price"]);
+    }
+
+    #[test]
+    fn test_unspaced_scripts_fall_back_to_substrings() {
+        let matcher = KeywordMatcher::new(&[
+            KeywordRule::new("価格", MatchMode::WordBoundary),
+            KeywordRule::new("競合他社", MatchMode::Fuzzy),
+            KeywordRule::new("Acme", MatchMode::WordBoundary),
+        ]);
+
+        assert_eq!(keywords(&matcher, "来年の価格改定について競合他社と比べました"), ["価格", "競合他社"]);
+        assert_eq!(keywords(&matcher, "Acmeの提案とAcmeCorp"), ["Acme"]);
+    }
+
+    #[test]
+    fn test_fuzzy_matches_detections_and_summary() {
+        let matcher = KeywordMatcher::new(&[
+            KeywordRule::new("Northwind Traders", MatchMode::Fuzzy),
+            KeywordRule::new("discount", MatchMode::WordBoundary),
+            KeywordRule::new("renewal", MatchMode::WordBoundary),
+        ]);
+        assert_eq!(keywords(&matcher, "We met north wind and Northwnd Traiders today"), ["Northwnd Traiders"]);
+        assert!(keywords(&matcher, "Northbound Trade").is_empty());
+
+        let segments = vec![
+            serde_json::json!({ "text": "Northwind Traders asked for a discount, a big discount", "startTime": 12.5 }),
+            serde_json::json!({ "text": "Nothing to see here", "startTime": 20.0 }),
+            serde_json::json!({ "text": "Northwind Traiders again", "startTime": 31.0 }),
+        ];
+        let detections = matcher.detections(&segments[0], 0);
+        assert_eq!(detections.len(), 2);
+        assert_eq!((detections[1].keyword.as_str(), detections[1].count, detections[1].timestamp), ("discount", 2, 12.5));
+        assert_eq!(detections[1].snippet, "Northwind Traders asked for a discount, a big discount");
+
+        let long = format!("{} discount {}", "word ".repeat(20), "word ".repeat(20));
+        assert_eq!(matcher.detections(&serde_json::json!({ "text": long }), 3)[0].snippet.chars().count(), 2 + 8 + 2 * SNIPPET_CONTEXT_CHARS);
+
+        let summary = matcher.summarize(&segments);
+        assert_eq!(summary.iter().map(|s| (s.keyword.as_str(), s.count)).collect::<Vec<_>>(),
+                   [("Northwind Traders", 2), ("discount", 2), ("renewal", 0)]);
+        assert_eq!(summary[0].timestamps, [12.5, 31.0]);
+    }
+}
@@ -0,0 +1,280 @@
+//! Meeting notes export
+//!
+//! Renders a finished (or running) session as a Markdown document: meeting
+//! metadata, the transcript as speaker turns with timestamps, and the
+//! per-speaker talk-time table. Consecutive segments of one speaker are merged
+//! into a paragraph with the segment merger, so the notes read as turns
+//! rather than as recognition windows. A transcript split into several
+//! chapters gets a heading per chapter.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::diarization::segment_merger::SegmentMerger;
+use crate::diarization::speaker_names::SPEAKER_NAME_FIELD;
+use crate::diarization::speaker_stats::{self, UNKNOWN_SPEAKER};
+use crate::diarization::types::{DiarizationConfig, FinalSegment};
+use crate::transcription::chapters::Chapter;
+use crate::transcription::session_chain::SessionRecord;
+use crate::transcription::subtitles::default_speaker_label;
+
+/// Pause after which the same speaker starts a new paragraph (seconds)
+pub const TURN_GAP_SECONDS: f32 = 5.0;
+
+/// Written meeting notes file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeetingNotesExport {
+    pub path: String,
+    #[serde(rename = "byteCount")]
+    pub byte_count: usize,
+    #[serde(rename = "turnCount")]
+    pub turn_count: usize,
+    pub participants: Vec<String>,
+}
+
+/// Rendered meeting notes
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeetingNotes {
+    pub content: String,
+    pub turn_count: usize,
+    pub participants: Vec<String>,
+}
+
+/// `HH:MM:SS`; hours grow past two digits
+pub fn format_timestamp(seconds: f32) -> String {
+    let total = if seconds.is_finite() && seconds > 0.0 { seconds as u64 } else { 0 };
+    format!("{:02}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+}
+
+/// "1h 02m 05s", "12m 05s" or "45s"
+pub fn format_duration(seconds: f64) -> String {
+    let total = if seconds.is_finite() && seconds > 0.0 { seconds.round() as u64 } else { 0 };
+    let (hours, minutes, secs) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, secs)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn speaker_of(segment: &serde_json::Value) -> String {
+    segment["speaker"].as_str()
+        .filter(|id| !id.is_empty())
+        .unwrap_or(UNKNOWN_SPEAKER)
+        .to_string()
+}
+
+/// Display name per speaker id: the name written on the segments (renamed
+/// during the session), then `profile_names` (stored speaker profiles), then
+/// the default "Speaker N" label.
+pub fn display_names(segments: &[serde_json::Value], profile_names: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut names: HashMap<String, String> = HashMap::new();
+    for segment in segments {
+        let speaker = speaker_of(segment);
+        if let Some(name) = segment[SPEAKER_NAME_FIELD].as_str().filter(|name| !name.trim().is_empty()) {
+            names.insert(speaker, name.trim().to_string());
+        } else {
+            let profile_name = profile_names.get(&speaker).cloned();
+            names.entry(speaker)
+                .or_insert_with_key(|speaker| profile_name.unwrap_or_else(|| default_speaker_label(speaker)));
+        }
+    }
+    names
+}
+
+/// Transcript segments as speaker turns, in time order
+pub fn speaker_turns(segments: &[serde_json::Value]) -> Vec<FinalSegment> {
+    let mut final_segments: Vec<FinalSegment> = segments.iter()
+        .filter_map(|segment| {
+            let text = segment["text"].as_str()?.trim();
+            if text.is_empty() {
+                return None;
+            }
+            let start_time = segment["startTime"].as_f64().unwrap_or(0.0) as f32;
+            let end_time = segment["endTime"].as_f64().map(|end| end as f32).unwrap_or(start_time).max(start_time);
+            let confidence = segment["confidence"].as_f64().unwrap_or(0.0) as f32;
+            Some(FinalSegment {
+                start_time,
+                end_time,
+                speaker_id: speaker_of(segment),
+                text: text.to_string(),
+                transcription_confidence: confidence,
+                speaker_confidence: 0.0,
+                overall_confidence: confidence,
+                was_merged: false,
+            })
+        })
+        .collect();
+    final_segments.sort_by(|a, b| a.start_time.partial_cmp(&b.start_time).unwrap_or(std::cmp::Ordering::Equal));
+    SegmentMerger::new(DiarizationConfig::default()).merge_speaker_turns(&final_segments, TURN_GAP_SECONDS)
+}
+
+/// Keep names from breaking table rows and bold markers
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|").replace('*', "\\*")
+}
+
+/// Render `record` as Markdown meeting notes, with a heading per chapter
+/// when there is more than one
+pub fn render_meeting_notes(record: &SessionRecord, profile_names: &HashMap<String, String>, chapters: &[Chapter]) -> MeetingNotes {
+    let names = display_names(&record.segments, profile_names);
+    let name_of = |speaker: &str| names.get(speaker).cloned().unwrap_or_else(|| default_speaker_label(speaker));
+    let turns = speaker_turns(&record.segments);
+    let stats = speaker_stats::compute_speaker_stats(&record.segments);
+
+    // Participants in order of first appearance
+    let mut participants: Vec<String> = Vec::new();
+    for turn in &turns {
+        let name = name_of(&turn.speaker_id);
+        if !participants.contains(&name) {
+            participants.push(name);
+        }
+    }
+
+    let date = chrono::DateTime::<chrono::Utc>::from_timestamp(record.start_time as i64, 0)
+        .map(|start| start.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let mut content = String::from("# Meeting notes\n\n");
+    content.push_str(&format!("- **Date:** {}\n", date));
+    content.push_str(&format!("- **Duration:** {}\n", format_duration(record.total_duration as f64)));
+    let participant_list = if participants.is_empty() {
+        "none".to_string()
+    } else {
+        participants.iter().map(|name| escape_markdown(name)).collect::<Vec<_>>().join(", ")
+    };
+    content.push_str(&format!("- **Participants:** {}\n", participant_list));
+    content.push_str(&format!("- **Session:** `{}`\n\n", record.session_id));
+
+    content.push_str("## Transcript\n\n");
+    if turns.is_empty() {
+        content.push_str("_No speech was transcribed._\n\n");
+    }
+    let mut headings = chapters.iter().filter(|_| chapters.len() > 1).peekable();
+    for turn in &turns {
+        while let Some(chapter) = headings.next_if(|chapter| chapter.start_time <= turn.start_time) {
+            content.push_str(&format!("### [{}] {}\n\n", format_timestamp(chapter.start_time), escape_markdown(&chapter.title)));
+        }
+        content.push_str(&format!(
+            "**[{}] {}:** {}\n\n",
+            format_timestamp(turn.start_time),
+            escape_markdown(&name_of(&turn.speaker_id)),
+            turn.text
+        ));
+    }
+
+    content.push_str("## Talk time\n\n");
+    content.push_str("| Speaker | Talk time | Share | Segments |\n");
+    content.push_str("| --- | ---: | ---: | ---: |\n");
+    for speaker in &stats.speakers {
+        content.push_str(&format!(
+            "| {} | {} | {:.0}% | {} |\n",
+            escape_markdown(&name_of(&speaker.speaker_id)),
+            format_duration(speaker.speaking_seconds),
+            speaker.talk_time_percent,
+            speaker.segment_count
+        ));
+    }
+
+    MeetingNotes {
+        content,
+        turn_count: turns.len(),
+        participants,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(segments: Vec<serde_json::Value>) -> SessionRecord {
+        SessionRecord {
+            session_id: "session-1".to_string(),
+            continuation_of: None,
+            start_time: 1_700_000_000,
+            total_duration: 3725.0,
+            segments,
+            audio_path: None,
+            speaker_mutes: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_meeting_notes_structure_and_timestamps() {
+        let notes = render_meeting_notes(&record(vec![
+            json!({"text": "Good morning.", "startTime": 0.5, "endTime": 2.0, "speaker": "speaker_1"}),
+            json!({"text": "Let's start.", "startTime": 2.5, "endTime": 4.0, "speaker": "speaker_1"}),
+            json!({"text": "Agreed.", "startTime": 4.5, "endTime": 5.5, "speaker": "speaker_2"}),
+            json!({"text": "One more thing.", "startTime": 3723.0, "endTime": 3725.0, "speaker": "speaker_1"}),
+        ]), &HashMap::new(), &[]);
+
+        let headings: Vec<&str> = notes.content.lines().filter(|line| line.starts_with('#')).collect();
+        assert_eq!(headings, ["# Meeting notes", "## Transcript", "## Talk time"]);
+        assert!(notes.content.contains("- **Date:** 2023-11-14 22:13 UTC\n"));
+        assert!(notes.content.contains("- **Duration:** 1h 02m 05s\n"));
+        assert!(notes.content.contains("- **Participants:** Speaker 1, Speaker 2\n"));
+        // Consecutive segments of one speaker form a single paragraph
+        assert!(notes.content.contains("**[00:00:00] Speaker 1:** Good morning. Let's start.\n"));
+        assert!(notes.content.contains("**[00:00:04] Speaker 2:** Agreed.\n"));
+        assert!(notes.content.contains("**[01:02:03] Speaker 1:** One more thing.\n"));
+        assert_eq!(notes.turn_count, 3);
+        assert!(notes.content.contains("| Speaker 1 | 5s | 83% | 3 |\n"));
+    }
+
+    #[test]
+    fn test_display_names_prefer_renames_then_profiles() {
+        let profile_id = "5f0c6a3e-0000-4000-8000-000000000001";
+        let segments = vec![
+            json!({"text": "Hi", "startTime": 0.0, "endTime": 1.0, "speaker": "speaker_1", "speakerName": "Aiko"}),
+            json!({"text": "Hello", "startTime": 1.0, "endTime": 2.0, "speaker": profile_id}),
+            json!({"text": "Hey", "startTime": 2.0, "endTime": 3.0, "speaker": "speaker_3"}),
+        ];
+        let profiles = HashMap::from([(profile_id.to_string(), "Kenji | PM".to_string())]);
+
+        let notes = render_meeting_notes(&record(segments), &profiles, &[]);
+        assert_eq!(notes.participants, ["Aiko", "Kenji | PM", "Speaker 3"]);
+        assert!(notes.content.contains("**[00:00:01] Kenji \\| PM:** Hello\n"));
+        assert!(notes.content.contains("| Kenji \\| PM | 1s |"));
+    }
+
+    #[test]
+    fn test_long_pause_starts_a_new_paragraph() {
+        let turns = speaker_turns(&[
+            json!({"text": "Before the break.", "startTime": 0.0, "endTime": 2.0, "speaker": "speaker_1"}),
+            json!({"text": "   ", "startTime": 2.0, "endTime": 3.0, "speaker": "speaker_1"}),
+            json!({"text": "After the break.", "startTime": 60.0, "endTime": 62.0, "speaker": "speaker_1"}),
+        ]);
+        assert_eq!(turns.len(), 2);
+        assert_eq!(format_timestamp(turns[1].start_time), "00:01:00");
+        assert_eq!(format_duration(0.4), "0s");
+        assert_eq!(format_duration(125.0), "2m 05s");
+    }
+
+    #[test]
+    fn test_chapters_become_headings() {
+        use crate::transcription::chapters::{split_chapters, ChapterConfig};
+
+        let segments = vec![
+            json!({"text": "Let's review the budget first.", "startTime": 0.0, "endTime": 4.0, "speaker": "speaker_1"}),
+            json!({"text": "The forecast looks fine.", "startTime": 5.0, "endTime": 8.0, "speaker": "speaker_2"}),
+            json!({"text": "Moving on to hiring plans.", "startTime": 300.0, "endTime": 304.0, "speaker": "speaker_1"}),
+        ];
+        let chapters = split_chapters(&segments, &ChapterConfig { min_chapter_seconds: 60.0, ..ChapterConfig::default() });
+        assert_eq!(chapters.len(), 2);
+
+        let notes = render_meeting_notes(&record(segments), &HashMap::new(), &chapters);
+        let headings: Vec<&str> = notes.content.lines().filter(|line| line.starts_with('#')).collect();
+        assert_eq!(headings, [
+            "# Meeting notes",
+            "## Transcript",
+            "### [00:00:00] Let's review the budget first.",
+            "### [00:05:00] Moving on to hiring plans.",
+            "## Talk time",
+        ]);
+        // A single chapter adds no heading
+        let single = render_meeting_notes(&record(vec![]), &HashMap::new(), &chapters[..1]);
+        assert!(!single.content.contains("###"));
+    }
+}
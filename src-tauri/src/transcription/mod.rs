@@ -1,7 +1,32 @@
 pub mod content_hasher;
 pub mod temporal_analyzer;
 pub mod boundary_detector;
+pub mod session_chain;
+pub mod postprocess;
+pub mod speech_rate;
+pub mod window_routing;
+pub mod speaker_naming;
+pub mod highlights;
+pub mod subtitles;
+pub mod quality_metrics;
+pub mod stream_clock;
+pub mod session_clock;
+pub mod partials;
+pub mod batch;
+pub mod adaptive_tier;
+pub mod markdown;
+pub mod hallucination;
+pub mod session_progress;
+pub mod review;
+pub mod translation;
+pub mod auto_stop;
+pub mod heartbeat;
+pub mod chunk_window;
+pub mod system_status;
+pub mod keywords;
+pub mod chapters;
 
 pub use content_hasher::ContentHasher;
 pub use temporal_analyzer::TemporalAnalyzer;
-pub use boundary_detector::BoundaryDetector;
\ No newline at end of file
+pub use boundary_detector::BoundaryDetector;
+pub use session_chain::{RolloverPolicy, SessionRecord};
\ No newline at end of file
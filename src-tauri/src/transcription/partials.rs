@@ -0,0 +1,144 @@
+//! Partial results while an utterance is still being buffered
+//!
+//! A fast decode of the growing buffer is shown as a provisional segment and
+//! replaced by the final transcript when the utterance ends. Each utterance
+//! gets one segment id shared by its partials and its final version. The
+//! transcription loop runs both passes itself, so time spent on partials
+//! delays chunk processing and the final pass; the scheduler keeps partial
+//! passes to a fixed share of wall time, smaller on machines with few cores.
+
+use std::time::{Duration, Instant};
+
+/// Default time between partial passes
+pub const DEFAULT_PARTIAL_INTERVAL_MS: u64 = 800;
+/// Buffered speech needed before the first partial of an utterance
+pub const MIN_PARTIAL_AUDIO_SECONDS: f64 = 0.5;
+
+/// Share of wall time partial passes may take on `cores` logical cores
+pub fn partial_load_budget(cores: usize) -> f64 {
+    if cores <= 4 { 0.25 } else { 0.5 }
+}
+
+/// Decoder threads for partial passes: half the cores, never more than the final pass uses
+pub fn partial_threads(cores: usize, final_threads: usize) -> usize {
+    (cores / 2).clamp(1, final_threads.max(1))
+}
+
+/// Decides when the next partial pass runs and tracks the utterance's segment id
+#[derive(Debug, Clone)]
+pub struct PartialScheduler {
+    base_interval: Duration,
+    interval: Duration,
+    load_budget: f64,
+    last_run: Option<Instant>,
+    segment_id: Option<String>,
+    revision: u32,
+}
+
+impl PartialScheduler {
+    pub fn new(interval: Duration, cores: usize) -> Self {
+        Self {
+            base_interval: interval,
+            interval,
+            load_budget: partial_load_budget(cores),
+            last_run: None,
+            segment_id: None,
+            revision: 0,
+        }
+    }
+
+    /// Current spacing of partial passes, stretched when they run slow
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Segment id of the utterance being buffered, created on first use
+    pub fn segment_id(&mut self, new_id: impl FnOnce() -> String) -> &str {
+        self.segment_id.get_or_insert_with(new_id)
+    }
+
+    /// A partial pass should run now over `buffered_seconds` of audio
+    pub fn is_due(&self, now: Instant, buffered_seconds: f64, utterance_started: Instant) -> bool {
+        if buffered_seconds < MIN_PARTIAL_AUDIO_SECONDS {
+            return false;
+        }
+        let since = self.last_run.map_or(utterance_started, |last| last.max(utterance_started));
+        now.saturating_duration_since(since) >= self.interval
+    }
+
+    /// A partial pass finished at `now` after `elapsed`; returns its revision
+    /// number. Passes slower than the load budget allows push the next one out.
+    pub fn record(&mut self, now: Instant, elapsed: Duration) -> u32 {
+        self.last_run = Some(now);
+        self.revision += 1;
+        let needed = elapsed.as_secs_f64() / self.load_budget;
+        self.interval = self.base_interval.max(Duration::from_secs_f64(needed));
+        self.revision
+    }
+
+    /// The utterance ended; returns the id its final segment takes over
+    pub fn finish(&mut self) -> Option<String> {
+        self.revision = 0;
+        self.segment_id.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partials_follow_the_interval() {
+        let start = Instant::now();
+        let mut scheduler = PartialScheduler::new(Duration::from_millis(800), 8);
+
+        assert!(!scheduler.is_due(start + Duration::from_secs(1), 0.3, start));
+        assert!(!scheduler.is_due(start + Duration::from_millis(700), 1.0, start));
+        assert!(scheduler.is_due(start + Duration::from_millis(800), 1.0, start));
+
+        let revision = scheduler.record(start + Duration::from_millis(900), Duration::from_millis(100));
+        assert_eq!(revision, 1);
+        assert!(!scheduler.is_due(start + Duration::from_millis(1500), 1.5, start));
+        assert!(scheduler.is_due(start + Duration::from_millis(1700), 1.7, start));
+    }
+
+    #[test]
+    fn test_slow_passes_back_off_more_on_four_cores() {
+        let now = Instant::now();
+        let mut quad = PartialScheduler::new(Duration::from_millis(800), 4);
+        let mut many = PartialScheduler::new(Duration::from_millis(800), 16);
+        quad.record(now, Duration::from_millis(400));
+        many.record(now, Duration::from_millis(400));
+
+        // 400 ms passes may take a quarter of the time on 4 cores, half on 16
+        assert_eq!(quad.interval(), Duration::from_millis(1600));
+        assert_eq!(many.interval(), Duration::from_millis(800));
+
+        // Fast passes bring the interval back down
+        quad.record(now, Duration::from_millis(50));
+        assert_eq!(quad.interval(), Duration::from_millis(800));
+    }
+
+    #[test]
+    fn test_segment_id_is_stable_per_utterance() {
+        let mut scheduler = PartialScheduler::new(Duration::from_millis(800), 8);
+        let mut next = 0;
+        let mut new_id = || {
+            next += 1;
+            format!("utterance-{}", next)
+        };
+
+        assert_eq!(scheduler.segment_id(&mut new_id), "utterance-1");
+        assert_eq!(scheduler.segment_id(&mut new_id), "utterance-1");
+        assert_eq!(scheduler.finish().as_deref(), Some("utterance-1"));
+        assert_eq!(scheduler.finish(), None);
+        assert_eq!(scheduler.segment_id(&mut new_id), "utterance-2");
+    }
+
+    #[test]
+    fn test_partial_threads() {
+        assert_eq!(partial_threads(4, 4), 2);
+        assert_eq!(partial_threads(1, 4), 1);
+        assert_eq!(partial_threads(16, 4), 4);
+    }
+}
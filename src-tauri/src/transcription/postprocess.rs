@@ -0,0 +1,370 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::asr::types::{Language, WordResult};
+
+/// Sentence-final punctuation across the scripts we transcribe
+const SENTENCE_FINAL: &[char] = &['.', '!', '?', '。', '！', '？'];
+/// Any punctuation mark we may insert or find at a word end
+const ANY_PUNCTUATION: &[char] = &['.', '!', '?', ',', ';', ':', '。', '！', '？', '、', '，'];
+
+/// Pause-based punctuation rules for a single language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunctuationRules {
+    /// Pause (ms) after a word that ends a sentence
+    pub period_pause_ms: u32,
+    /// Pause (ms) after a word that gets a comma
+    pub comma_pause_ms: u32,
+    pub period: String,
+    pub comma: String,
+    /// Separator between words ("" for scripts without spaces)
+    pub word_separator: String,
+    /// Capitalize the first word of each sentence
+    pub capitalize: bool,
+}
+
+impl PunctuationRules {
+    /// Default rules for a language code (e.g. "en", "ja")
+    pub fn for_language(language: &str) -> Self {
+        match language {
+            "ja" => Self {
+                period_pause_ms: 700,
+                comma_pause_ms: 300,
+                period: "。".to_string(),
+                comma: "、".to_string(),
+                word_separator: String::new(),
+                capitalize: false,
+            },
+            "zh" => Self {
+                period_pause_ms: 700,
+                comma_pause_ms: 300,
+                period: "。".to_string(),
+                comma: "，".to_string(),
+                word_separator: String::new(),
+                capitalize: false,
+            },
+            _ => Self {
+                period_pause_ms: 800,
+                comma_pause_ms: 350,
+                period: ".".to_string(),
+                comma: ",".to_string(),
+                word_separator: " ".to_string(),
+                capitalize: true,
+            },
+        }
+    }
+}
+
+/// Configuration for the punctuation restoration stage
+#[derive(Debug, Clone)]
+pub struct PunctuationConfig {
+    /// Per-segment latency budget; the raw text is kept when exceeded
+    pub latency_budget: Duration,
+    /// Language-specific overrides of the default rules
+    pub language_rules: HashMap<String, PunctuationRules>,
+    /// Sentence-final marks per word above which the ASR output counts as
+    /// punctuated and is left alone
+    pub skip_density_threshold: f32,
+}
+
+impl Default for PunctuationConfig {
+    fn default() -> Self {
+        Self {
+            latency_budget: Duration::from_millis(20),
+            language_rules: HashMap::new(),
+            skip_density_threshold: 0.05,
+        }
+    }
+}
+
+impl PunctuationConfig {
+    /// Default configuration with the given per-language rules, keyed by any
+    /// form of language code ("ja", "ja-JP", "jpn")
+    pub fn with_language_rules(rules: &HashMap<String, PunctuationRules>) -> Self {
+        let language_rules = rules.iter()
+            .map(|(code, rules)| {
+                let code = Language::parse(code).map(|l| l.code()).unwrap_or(code);
+                (code.to_string(), rules.clone())
+            })
+            .collect();
+        Self { language_rules, ..Self::default() }
+    }
+}
+
+/// Result of punctuation restoration, keeping the original ASR text
+#[derive(Debug, Clone)]
+pub struct PunctuatedText {
+    pub text: String,
+    pub raw_text: String,
+    pub applied: bool,
+}
+
+/// Rule-based punctuation restoration driven by pauses between word timestamps
+pub struct PunctuationRestorer {
+    config: PunctuationConfig,
+}
+
+impl PunctuationRestorer {
+    pub fn new(config: PunctuationConfig) -> Self {
+        Self { config }
+    }
+
+    /// Restore punctuation for a segment, falling back to the raw text when not applicable.
+    ///
+    /// `words` must carry decoder word timestamps; evenly spread estimates have
+    /// no pauses to read. Marks are inserted into `raw_text` after the words
+    /// they follow, so everything the ASR produced (apostrophes, casing, text
+    /// between words) is kept. Text that is already punctuated, with more
+    /// sentence-final marks per word than `skip_density_threshold`, is left
+    /// alone; marks the ASR did produce are never doubled.
+    pub fn restore(&self, raw_text: &str, words: &[WordResult], language: &str) -> PunctuatedText {
+        let unchanged = || PunctuatedText {
+            text: raw_text.to_string(),
+            raw_text: raw_text.to_string(),
+            applied: false,
+        };
+
+        if words.is_empty() || raw_text.trim().is_empty() {
+            return unchanged();
+        }
+        let word_count = words.iter().filter(|word| !word.word.trim().is_empty()).count().max(1);
+        let sentence_marks = raw_text.matches(SENTENCE_FINAL).count();
+        if sentence_marks as f32 / word_count as f32 > self.config.skip_density_threshold {
+            return unchanged();
+        }
+
+        let started = Instant::now();
+        // Rules are keyed by canonical code, so "ja-JP" or "jpn" find the Japanese rules
+        let language = Language::parse(language).map(|l| l.code()).unwrap_or(language);
+        let rules = self.config.language_rules.get(language)
+            .cloned()
+            .unwrap_or_else(|| PunctuationRules::for_language(language));
+
+        let mut output = String::with_capacity(raw_text.len() + words.len());
+        // Byte position in the raw text up to which it was copied
+        let mut cursor = 0;
+        let mut sentence_start = true;
+
+        for (i, word) in words.iter().enumerate() {
+            if started.elapsed() > self.config.latency_budget {
+                tracing::debug!("Punctuation restoration exceeded latency budget, keeping raw text");
+                return unchanged();
+            }
+
+            let token = word.word.trim();
+            if token.is_empty() {
+                continue;
+            }
+            // Words that do not line up with the text leave it untouched
+            let Some(found) = raw_text[cursor..].find(token) else {
+                return unchanged();
+            };
+            let start = cursor + found;
+            let end = start + token.len();
+            output.push_str(&raw_text[cursor..start]);
+            if sentence_start && rules.capitalize {
+                output.push_str(&capitalize_first(token));
+            } else {
+                output.push_str(token);
+            }
+            sentence_start = false;
+            cursor = end;
+
+            let is_last = !words[i + 1..].iter().any(|next| !next.word.trim().is_empty());
+            // A mark the ASR already put here, in the word or right after it, stays the only one
+            let existing_mark = token.chars().last()
+                .filter(|c| ANY_PUNCTUATION.contains(c))
+                .or_else(|| raw_text[end..].chars().next().filter(|c| ANY_PUNCTUATION.contains(c)));
+            if let Some(mark) = existing_mark {
+                sentence_start = SENTENCE_FINAL.contains(&mark);
+                continue;
+            }
+            // With spaces, marks only go where a word ends in the text, never inside "don't"
+            let at_word_end = rules.word_separator.is_empty()
+                || raw_text[end..].chars().next().is_none_or(char::is_whitespace);
+            if !at_word_end {
+                continue;
+            }
+            let pause_ms = words.get(i + 1)
+                .map(|next| ((next.start_time - word.end_time).max(0.0) * 1000.0) as u32)
+                .unwrap_or(0);
+
+            if is_last || pause_ms >= rules.period_pause_ms {
+                output.push_str(&rules.period);
+                sentence_start = true;
+            } else if pause_ms >= rules.comma_pause_ms {
+                output.push_str(&rules.comma);
+            }
+        }
+        output.push_str(&raw_text[cursor..]);
+
+        PunctuatedText {
+            text: output,
+            raw_text: raw_text.to_string(),
+            applied: true,
+        }
+    }
+}
+
+fn capitalize_first(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Normalize text for punctuation-insensitive WER comparisons
+pub fn normalize_for_wer(text: &str) -> String {
+    text.chars()
+        .filter(|c| !ANY_PUNCTUATION.contains(c))
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Words per sentence, used as a readability proxy
+pub fn sentence_lengths(text: &str) -> Vec<usize> {
+    text.split(SENTENCE_FINAL)
+        .map(|sentence| sentence.split_whitespace().count())
+        .filter(|&count| count > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asr::whisper::calculate_word_error_rate;
+
+    /// Build word timings from (word, gap_after_seconds) pairs with 0.3s words
+    fn timed_words(words: &[(&str, f32)]) -> Vec<WordResult> {
+        let mut time = 0.0;
+        words.iter().map(|(word, gap)| {
+            let result = WordResult {
+                word: word.to_string(),
+                start_time: time,
+                end_time: time + 0.3,
+                confidence: 0.9,
+            };
+            time += 0.3 + gap;
+            result
+        }).collect()
+    }
+
+    #[test]
+    fn test_pauses_become_punctuation() {
+        let words = timed_words(&[
+            ("so", 0.05), ("we", 0.05), ("shipped", 1.0),
+            ("next", 0.05), ("week", 0.4), ("we", 0.05), ("review", 0.0),
+        ]);
+        let restorer = PunctuationRestorer::new(PunctuationConfig::default());
+        let result = restorer.restore("so we shipped next week we review", &words, "en");
+
+        assert!(result.applied);
+        assert_eq!(result.text, "So we shipped. Next week, we review.");
+        assert_eq!(result.raw_text, "so we shipped next week we review");
+    }
+
+    #[test]
+    fn test_japanese_uses_fullwidth_marks_without_spaces() {
+        let words = timed_words(&[("今日は", 0.4), ("会議", 0.05), ("です", 0.0)]);
+        let restorer = PunctuationRestorer::new(PunctuationConfig::default());
+        let result = restorer.restore("今日は会議です", &words, "ja");
+
+        assert_eq!(result.text, "今日は、会議です。");
+    }
+
+    #[test]
+    fn test_skips_already_punctuated_text() {
+        let words = timed_words(&[("Hello.", 1.0), ("Yes.", 0.0)]);
+        let restorer = PunctuationRestorer::new(PunctuationConfig::default());
+        let result = restorer.restore("Hello. Yes.", &words, "en");
+
+        assert!(!result.applied);
+        assert_eq!(result.text, "Hello. Yes.");
+
+        // A comma in long unpunctuated text keeps its place and gets no company
+        let words = timed_words(&[("well", 0.05), ("I", 0.05), ("think", 1.0), ("so", 0.0)]);
+        let result = restorer.restore("well, I think so", &words, "en");
+        assert_eq!((result.applied, result.text.as_str()), (true, "Well, I think. So."));
+    }
+
+    #[test]
+    fn test_sparse_sentence_marks_are_completed() {
+        let tokens = ["we", "agreed", "on", "the", "budget", "then", "we", "moved", "to", "hiring",
+                      "and", "finally", "we", "discussed", "the", "launch", "date", "for", "spring", "OK"];
+        let words: Vec<(&str, f32)> = tokens.iter().enumerate()
+            .map(|(i, w)| (*w, if i == 4 || i == 9 { 1.0 } else { 0.05 }))
+            .collect();
+        let words = timed_words(&words);
+        let raw = "we agreed on the budget then we moved to hiring and finally we discussed the launch date for spring. OK";
+
+        // One mark in twenty words is not above the default density
+        let restorer = PunctuationRestorer::new(PunctuationConfig::default());
+        let result = restorer.restore(raw, &words, "en");
+        assert!(result.applied);
+        assert_eq!(result.text, "We agreed on the budget. Then we moved to hiring. And finally we discussed the launch date for spring. OK.");
+
+        let strict = PunctuationRestorer::new(PunctuationConfig { skip_density_threshold: 0.02, ..PunctuationConfig::default() });
+        assert!(!strict.restore(raw, &words, "en").applied);
+    }
+
+    #[test]
+    fn test_configured_rules_apply_by_language_code() {
+        let mut rules = HashMap::new();
+        rules.insert("en-US".to_string(), PunctuationRules {
+            period_pause_ms: 2000,
+            ..PunctuationRules::for_language("en")
+        });
+        let restorer = PunctuationRestorer::new(PunctuationConfig::with_language_rules(&rules));
+
+        // A one second pause is only a comma with the longer period pause
+        let words = timed_words(&[("so", 1.0), ("we", 0.05), ("shipped", 0.0)]);
+        assert_eq!(restorer.restore("so we shipped", &words, "en").text, "So, we shipped.");
+
+        let rules: HashMap<String, PunctuationRules> = serde_json::from_value(serde_json::json!({
+            "ja": {"periodPauseMs": 500, "commaPauseMs": 200, "period": "。", "comma": "、", "wordSeparator": "", "capitalize": false}
+        })).unwrap();
+        assert_eq!(rules["ja"].period_pause_ms, 500);
+    }
+
+    #[test]
+    fn test_marks_are_inserted_into_the_raw_text() {
+        let restorer = PunctuationRestorer::new(PunctuationConfig::default());
+
+        // Apostrophes and casing of the raw text survive; no mark inside "don't"
+        let words = timed_words(&[("we", 0.05), ("don", 0.9), ("'t", 0.05), ("know", 1.0), ("OK", 0.0)]);
+        let result = restorer.restore("we don't know OK", &words, "en");
+        assert_eq!(result.text, "We don't know. OK.");
+
+        // Words that are not in the text leave it unchanged
+        let words = timed_words(&[("we", 1.0), ("shipped", 0.0)]);
+        let result = restorer.restore("we launched", &words, "en");
+        assert_eq!((result.applied, result.text.as_str()), (false, "we launched"));
+    }
+
+    #[test]
+    fn test_readability_improves_without_worsening_wer() {
+        let reference = "we agreed on the budget then we moved to hiring and finally we discussed the launch date";
+        let tokens: Vec<&str> = reference.split_whitespace().collect();
+        let words: Vec<(&str, f32)> = tokens.iter().enumerate()
+            .map(|(i, w)| (*w, if i == 4 || i == 10 { 1.0 } else { 0.05 }))
+            .collect();
+        let words = timed_words(&words);
+
+        let restorer = PunctuationRestorer::new(PunctuationConfig::default());
+        let result = restorer.restore(reference, &words, "en");
+
+        let raw_longest = sentence_lengths(&result.raw_text).into_iter().max().unwrap();
+        let restored_longest = sentence_lengths(&result.text).into_iter().max().unwrap();
+        assert!(restored_longest < raw_longest);
+
+        let raw_wer = calculate_word_error_rate(&normalize_for_wer(&result.raw_text), reference);
+        let restored_wer = calculate_word_error_rate(&normalize_for_wer(&result.text), reference);
+        assert!(restored_wer <= raw_wer);
+    }
+}
@@ -0,0 +1,184 @@
+//! Session quality metrics
+//!
+//! Aggregates what was actually measured during a session: decoder confidence
+//! from the stored segments and transcription time against the audio it
+//! covered. There is no reference transcript at runtime, so no word error rate
+//! is reported; `estimatedErrorRateFromConfidence` is only `1 - confidence`.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::transcription::review;
+
+/// Segments below this confidence count as low confidence
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.6;
+
+/// Transcription time measured in the session loop
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessingStats {
+    pub processing_time: Duration,
+    pub audio_seconds: f64,
+    pub chunks: u32,
+    /// Decoded segments the hallucination filter held back or flagged
+    pub hallucinations_filtered: u32,
+    /// Capture of the newest audio in the last transcribed buffer to its transcript
+    pub last_chunk_latency: Option<Duration>,
+}
+
+impl ProcessingStats {
+    /// Record one transcription call over `audio_seconds` of audio
+    pub fn record(&mut self, elapsed: Duration, audio_seconds: f64) {
+        self.processing_time += elapsed;
+        self.audio_seconds += audio_seconds.max(0.0);
+        self.chunks += 1;
+    }
+
+    pub fn record_latency(&mut self, latency: Duration) {
+        self.last_chunk_latency = Some(latency);
+    }
+
+    pub fn record_hallucination(&mut self) {
+        self.hallucinations_filtered += 1;
+    }
+
+    /// Processing time over audio time; below 1.0 is faster than real time
+    pub fn real_time_factor(&self) -> Option<f32> {
+        (self.audio_seconds > 0.0).then(|| (self.processing_time.as_secs_f64() / self.audio_seconds) as f32)
+    }
+}
+
+/// Quality metrics returned when a session stops
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionQualityMetrics {
+    /// Mean confidence of segments that reported one
+    #[serde(rename = "averageConfidence")]
+    pub average_confidence: Option<f32>,
+    #[serde(rename = "minConfidence")]
+    pub min_confidence: Option<f32>,
+    /// Share of scored segments below LOW_CONFIDENCE_THRESHOLD
+    #[serde(rename = "lowConfidenceRatio")]
+    pub low_confidence_ratio: Option<f32>,
+    /// `1 - averageConfidence`; a rough indicator, not a measured word error rate
+    #[serde(rename = "estimatedErrorRateFromConfidence")]
+    pub estimated_error_rate_from_confidence: Option<f32>,
+    #[serde(rename = "realTimeFactor")]
+    pub real_time_factor: Option<f32>,
+    #[serde(rename = "segmentCount")]
+    pub segment_count: usize,
+    #[serde(rename = "transcribedAudioSeconds")]
+    pub transcribed_audio_seconds: f64,
+    #[serde(rename = "transcriptionChunks")]
+    pub transcription_chunks: u32,
+    #[serde(rename = "hallucinationsFiltered")]
+    pub hallucinations_filtered: u32,
+    /// Segments marked `needsReview` for their low confidence
+    #[serde(rename = "lowConfidenceSegments", default)]
+    pub low_confidence_segments: usize,
+}
+
+/// Aggregate stored segments and measured processing into session metrics.
+/// Confidence values outside 0..=1 or non-numeric are ignored.
+pub fn compute(segments: &[serde_json::Value], stats: &ProcessingStats) -> SessionQualityMetrics {
+    let confidences: Vec<f32> = segments.iter()
+        .filter_map(|segment| segment["confidence"].as_f64())
+        .filter(|c| c.is_finite() && (0.0..=1.0).contains(c))
+        .map(|c| c as f32)
+        .collect();
+
+    let scored = confidences.len();
+    let average_confidence = (scored > 0).then(|| confidences.iter().sum::<f32>() / scored as f32);
+    let min_confidence = confidences.iter().copied().reduce(f32::min);
+    let low_confidence_ratio = (scored > 0).then(|| {
+        confidences.iter().filter(|&&c| c < LOW_CONFIDENCE_THRESHOLD).count() as f32 / scored as f32
+    });
+
+    SessionQualityMetrics {
+        average_confidence,
+        min_confidence,
+        low_confidence_ratio,
+        estimated_error_rate_from_confidence: average_confidence.map(|c| 1.0 - c),
+        real_time_factor: stats.real_time_factor(),
+        segment_count: segments.len(),
+        transcribed_audio_seconds: stats.audio_seconds,
+        transcription_chunks: stats.chunks,
+        hallucinations_filtered: stats.hallucinations_filtered,
+        low_confidence_segments: review::needs_review_count(segments),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn approx(actual: Option<f32>, expected: f32) -> bool {
+        actual.is_some_and(|a| (a - expected).abs() < 1e-5)
+    }
+
+    #[test]
+    fn test_confidence_aggregation() {
+        let segments = [
+            json!({"text": "a", "confidence": 0.9}),
+            json!({"text": "b", "confidence": 0.5}),
+            json!({"text": "c", "confidence": 0.7}),
+            json!({"text": "d", "confidence": 1.0}),
+        ];
+        let metrics = compute(&segments, &ProcessingStats::default());
+
+        assert!(approx(metrics.average_confidence, 0.775));
+        assert!(approx(metrics.min_confidence, 0.5));
+        assert!(approx(metrics.low_confidence_ratio, 0.25));
+        assert!(approx(metrics.estimated_error_rate_from_confidence, 0.225));
+        assert_eq!(metrics.segment_count, 4);
+        assert_eq!(metrics.low_confidence_segments, 0);
+
+        let flagged = [json!({"text": "a", "confidence": 0.3, "needsReview": true}), json!({"text": "b", "needsReview": false})];
+        assert_eq!(compute(&flagged, &ProcessingStats::default()).low_confidence_segments, 1);
+    }
+
+    #[test]
+    fn test_invalid_and_missing_confidence_is_ignored() {
+        let segments = [
+            json!({"text": "no score"}),
+            json!({"text": "bad", "confidence": "high"}),
+            json!({"text": "out of range", "confidence": 3.2}),
+            json!({"text": "ok", "confidence": 0.8}),
+        ];
+        let metrics = compute(&segments, &ProcessingStats::default());
+        assert!(approx(metrics.average_confidence, 0.8));
+        assert_eq!(metrics.segment_count, 4);
+
+        let empty = compute(&[json!({"text": "no score"})], &ProcessingStats::default());
+        assert_eq!(empty.average_confidence, None);
+        assert_eq!(empty.estimated_error_rate_from_confidence, None);
+        assert_eq!(empty.low_confidence_ratio, None);
+    }
+
+    #[test]
+    fn test_real_time_factor_from_measured_chunks() {
+        let mut stats = ProcessingStats::default();
+        assert_eq!(stats.real_time_factor(), None);
+
+        stats.record(Duration::from_millis(1200), 5.0);
+        stats.record(Duration::from_millis(800), 5.0);
+        let metrics = compute(&[], &stats);
+
+        assert!(approx(metrics.real_time_factor, 0.2));
+        assert_eq!(metrics.transcription_chunks, 2);
+        assert_eq!(metrics.transcribed_audio_seconds, 10.0);
+        assert_eq!(stats.processing_time.as_millis(), 2000);
+
+        stats.record_hallucination();
+        assert_eq!(compute(&[], &stats).hallucinations_filtered, 1);
+    }
+
+    #[test]
+    fn test_serialized_field_names() {
+        let value = serde_json::to_value(compute(&[json!({"confidence": 0.9})], &ProcessingStats::default())).unwrap();
+        assert!(value.get("averageConfidence").is_some());
+        assert!(value.get("estimatedErrorRateFromConfidence").is_some());
+        assert_eq!(value["hallucinationsFiltered"], 0);
+        assert!(value.get("wordErrorRate").is_none());
+        assert!(value["realTimeFactor"].is_null());
+    }
+}
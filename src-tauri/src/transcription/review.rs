@@ -0,0 +1,216 @@
+//! Low-confidence segment review
+//!
+//! Segments whose decoder confidence falls below the session's review
+//! threshold are marked `needsReview` when stored and emitted, so gibberish
+//! from a bad stretch of audio stands out from the rest of the transcript.
+//! A marked segment can be transcribed again from the session recording with
+//! a higher tier; the new text replaces the old, which is kept alongside.
+
+use serde_json::Value;
+use std::fs;
+use std::future::Future;
+use std::io::{self, BufReader};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::asr::types::{ASRError, ASRResult, ModelTier};
+use crate::audio::types::{AudioData, AudioSource};
+use crate::transcription::quality_metrics::LOW_CONFIDENCE_THRESHOLD;
+
+/// Segment field set on segments below the review threshold
+pub const NEEDS_REVIEW_FIELD: &str = "needsReview";
+/// Default `reviewConfidenceThreshold`
+pub const DEFAULT_REVIEW_THRESHOLD: f32 = LOW_CONFIDENCE_THRESHOLD;
+
+/// Mark `segment` for review when its confidence is below `threshold`;
+/// returns the flag. Segments without a confidence are not marked.
+pub fn flag_segment(segment: &mut Value, threshold: f32) -> bool {
+    let needs_review = segment["confidence"].as_f64().is_some_and(|confidence| (confidence as f32) < threshold);
+    segment[NEEDS_REVIEW_FIELD] = Value::Bool(needs_review);
+    needs_review
+}
+
+/// Segments currently marked for review
+pub fn needs_review_count(segments: &[Value]) -> usize {
+    segments.iter().filter(|segment| segment[NEEDS_REVIEW_FIELD].as_bool() == Some(true)).count()
+}
+
+/// Transcribe `audio` again with `transcribe` and put the result into
+/// `segment` (text, confidence, tier), keeping the replaced text in
+/// `previousText`. Returns whether the new text still needs review. An empty
+/// result leaves the segment unchanged.
+pub async fn retranscribe<F, Fut>(
+    segment: &mut Value,
+    audio: AudioData,
+    tier: ModelTier,
+    threshold: f32,
+    transcribe: F,
+) -> Result<bool, ASRError>
+where
+    F: FnOnce(AudioData) -> Fut,
+    Fut: Future<Output = Result<ASRResult, ASRError>>,
+{
+    let result = transcribe(audio).await?;
+    let text = result.text.trim();
+    if text.is_empty() {
+        return Err(ASRError::TranscriptionFailed {
+            message: format!("The {} tier heard no speech in the segment", tier.quality_tier()),
+        });
+    }
+
+    if segment.get("previousText").is_none() {
+        segment["previousText"] = segment["text"].clone();
+        segment["previousConfidence"] = segment["confidence"].clone();
+    }
+    segment["text"] = Value::from(text);
+    segment["rawText"] = Value::from(text);
+    segment["confidence"] = Value::from(result.confidence);
+    segment["modelTier"] = Value::from(tier.quality_tier());
+    segment["retranscribed"] = Value::Bool(true);
+    Ok(flag_segment(segment, threshold))
+}
+
+/// Read `start..end` seconds of a WAV recording
+pub fn read_recording_window(path: &Path, start: f32, end: f32) -> io::Result<AudioData> {
+    let to_io = |e: hound::Error| match e {
+        hound::Error::IoError(e) => e,
+        other => io::Error::other(other.to_string()),
+    };
+    let mut reader = hound::WavReader::new(BufReader::new(fs::File::open(path)?)).map_err(to_io)?;
+    let spec = reader.spec();
+    let total_frames = reader.duration();
+    let start_frame = ((start.max(0.0) * spec.sample_rate as f32) as u32).min(total_frames);
+    let end_frame = ((end.max(start) * spec.sample_rate as f32).ceil() as u32).min(total_frames);
+    if end_frame <= start_frame {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!(
+            "{:.2}s..{:.2}s is outside the {:.2}s recording", start, end, total_frames as f32 / spec.sample_rate as f32
+        )));
+    }
+    reader.seek(start_frame)?;
+
+    let sample_count = (end_frame - start_frame) as usize * spec.channels as usize;
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().take(sample_count).collect::<Result<_, _>>().map_err(to_io)?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+            reader.samples::<i32>()
+                .take(sample_count)
+                .map(|sample| sample.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(to_io)?
+        }
+    };
+    Ok(AudioData {
+        duration_seconds: samples.len() as f32 / (spec.sample_rate * spec.channels as u32) as f32,
+        samples,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        timestamp: SystemTime::now(),
+        source_channel: AudioSource::Microphone,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::RefCell;
+
+    /// Engine stand-in returning scripted results in order
+    struct ScriptedEngine {
+        results: RefCell<Vec<(&'static str, f32)>>,
+    }
+
+    impl ScriptedEngine {
+        fn new(results: Vec<(&'static str, f32)>) -> Self {
+            Self { results: RefCell::new(results) }
+        }
+
+        async fn transcribe(&self, _audio: AudioData) -> Result<ASRResult, ASRError> {
+            let (text, confidence) = self.results.borrow_mut().remove(0);
+            Ok(ASRResult {
+                text: text.to_string(),
+                confidence,
+                language: "en".to_string(),
+                language_confidence: 1.0,
+                words: Vec::new(),
+                word_timestamps: false,
+                estimated_snr: None,
+                speaker_consistency_score: None,
+                language_segments: None,
+                decode_temperature: 0.0,
+                decode_attempts: 1,
+                decode_failed: false,
+                decode_budget_exhausted: false,
+                prompt_budget: None,
+                device: None,
+            })
+        }
+    }
+
+    fn clip() -> AudioData {
+        AudioData {
+            samples: vec![0.0; 16000],
+            sample_rate: 16000,
+            channels: 1,
+            timestamp: SystemTime::now(),
+            source_channel: AudioSource::Microphone,
+            duration_seconds: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_flags_segments_below_threshold() {
+        let mut segments = vec![
+            json!({"text": "fine", "confidence": 0.92}),
+            json!({"text": "blurb glorp", "confidence": 0.3}),
+            json!({"text": "no score"}),
+        ];
+        let flags: Vec<bool> = segments.iter_mut().map(|s| flag_segment(s, DEFAULT_REVIEW_THRESHOLD)).collect();
+        assert_eq!(flags, vec![false, true, false]);
+        assert_eq!(needs_review_count(&segments), 1);
+        assert_eq!(segments[0][NEEDS_REVIEW_FIELD], json!(false));
+    }
+
+    #[tokio::test]
+    async fn test_retranscription_replaces_text_and_reflags() {
+        let engine = ScriptedEngine::new(vec![("  ", 0.0), ("still mumbled", 0.4), ("the budget is approved", 0.91)]);
+        let mut segment = json!({"text": "blurb glorp", "confidence": 0.3, "needsReview": true, "modelTier": "turbo"});
+
+        // No speech: the segment keeps its text
+        let empty = retranscribe(&mut segment, clip(), ModelTier::Standard, 0.6, |audio| engine.transcribe(audio)).await;
+        assert!(empty.is_err());
+        assert_eq!(segment["text"], "blurb glorp");
+
+        let still_low = retranscribe(&mut segment, clip(), ModelTier::Standard, 0.6, |audio| engine.transcribe(audio)).await;
+        assert!(still_low.unwrap());
+        assert_eq!(segment["text"], "still mumbled");
+
+        let improved = retranscribe(&mut segment, clip(), ModelTier::HighAccuracy, 0.6, |audio| engine.transcribe(audio)).await;
+        assert!(!improved.unwrap());
+        assert_eq!(segment["text"], "the budget is approved");
+        assert_eq!(segment["modelTier"], "high-accuracy");
+        assert_eq!(segment[NEEDS_REVIEW_FIELD], json!(false));
+        // The original decode is kept, not the intermediate one
+        assert_eq!((segment["previousText"].clone(), segment["previousConfidence"].clone()), (json!("blurb glorp"), json!(0.3)));
+    }
+
+    #[test]
+    fn test_reads_recording_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for second in 0..3 {
+            for _ in 0..16000 {
+                writer.write_sample((second as i16 + 1) * 1000).unwrap();
+            }
+        }
+        writer.finalize().unwrap();
+
+        let audio = read_recording_window(&path, 1.0, 2.0).unwrap();
+        assert_eq!(audio.samples.len(), 16000);
+        assert!(audio.samples.iter().all(|s| (s - 2000.0 / 32768.0).abs() < 1e-6));
+        assert!(read_recording_window(&path, 5.0, 6.0).is_err());
+    }
+}
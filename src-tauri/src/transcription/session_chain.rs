@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::diarization::muting::SpeakerMutes;
+
+/// Finalized transcription session kept for exports and continuation chains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    /// Session this one continues after an automatic rollover
+    #[serde(rename = "continuationOf")]
+    pub continuation_of: Option<String>,
+    /// Session start time (seconds since UNIX epoch)
+    #[serde(rename = "startTime")]
+    pub start_time: u64,
+    #[serde(rename = "totalDuration")]
+    pub total_duration: f32,
+    pub segments: Vec<serde_json::Value>,
+    /// Retained session audio, which may live outside the data directory
+    #[serde(rename = "audioPath", default)]
+    pub audio_path: Option<String>,
+    /// Speakers excluded from the transcript stream and default exports
+    #[serde(rename = "speakerMutes", default)]
+    pub speaker_mutes: SpeakerMutes,
+}
+
+/// Decides when a running session should be split into a continuation
+#[derive(Debug, Clone, Copy)]
+pub struct RolloverPolicy {
+    max_duration_secs: Option<u64>,
+}
+
+impl RolloverPolicy {
+    pub fn new(max_duration_secs: Option<u64>) -> Self {
+        // A zero limit would roll over on every iteration, treat it as disabled
+        Self {
+            max_duration_secs: max_duration_secs.filter(|&secs| secs > 0),
+        }
+    }
+
+    /// Check whether a session running for `elapsed_secs` has reached its limit
+    pub fn is_due(&self, elapsed_secs: u64) -> bool {
+        match self.max_duration_secs {
+            Some(max) => elapsed_secs >= max,
+            None => false,
+        }
+    }
+}
+
+/// Collect the full continuation chain containing `session_id`, oldest part first
+pub fn collect_chain<'a>(
+    records: &'a HashMap<String, SessionRecord>,
+    session_id: &str,
+) -> Vec<&'a SessionRecord> {
+    let Some(mut root) = records.get(session_id) else {
+        return Vec::new();
+    };
+
+    // Walk back to the first part (guarding against accidental cycles)
+    let mut steps = 0;
+    while let Some(previous) = root.continuation_of.as_ref().and_then(|id| records.get(id)) {
+        root = previous;
+        steps += 1;
+        if steps > records.len() {
+            break;
+        }
+    }
+
+    // Walk forward through continuations
+    let mut chain = vec![root];
+    while chain.len() <= records.len() {
+        let current: &'a SessionRecord = chain[chain.len() - 1];
+        match records.values().find(|r| r.continuation_of.as_deref() == Some(current.session_id.as_str())) {
+            Some(next) => chain.push(next),
+            None => break,
+        }
+    }
+
+    chain
+}
+
+/// Stitch a continuation chain into a single document with timestamps relative to the first part
+pub fn stitch_chain(chain: &[&SessionRecord]) -> serde_json::Value {
+    let Some(first) = chain.first() else {
+        return serde_json::json!({ "parts": [], "segments": [], "totalDuration": 0.0 });
+    };
+
+    let mut parts = Vec::with_capacity(chain.len());
+    let mut segments = Vec::new();
+    let mut total_duration = 0.0f32;
+
+    for record in chain {
+        let offset = record.start_time.saturating_sub(first.start_time) as f32;
+        parts.push(serde_json::json!({
+            "sessionId": record.session_id,
+            "continuationOf": record.continuation_of,
+            "offset": offset,
+            "duration": record.total_duration,
+            "segmentCount": record.segments.len()
+        }));
+
+        for segment in &record.segments {
+            let mut segment = segment.clone();
+            if let Some(obj) = segment.as_object_mut() {
+                for key in ["startTime", "endTime"] {
+                    if let Some(time) = obj.get(key).and_then(|v| v.as_f64()) {
+                        obj.insert(key.to_string(), serde_json::json!(time as f32 + offset));
+                    }
+                }
+                obj.insert("sessionId".to_string(), serde_json::json!(record.session_id));
+            }
+            segments.push(segment);
+        }
+
+        total_duration = total_duration.max(offset + record.total_duration);
+    }
+
+    serde_json::json!({
+        "rootSessionId": first.session_id,
+        "parts": parts,
+        "segments": segments,
+        "totalDuration": total_duration
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, continuation_of: Option<&str>, start_time: u64, texts: &[(&str, f32, f32)]) -> SessionRecord {
+        SessionRecord {
+            session_id: id.to_string(),
+            continuation_of: continuation_of.map(|s| s.to_string()),
+            start_time,
+            total_duration: 60.0,
+            segments: texts
+                .iter()
+                .map(|(text, start, end)| serde_json::json!({ "text": text, "startTime": start, "endTime": end }))
+                .collect(),
+            audio_path: None,
+            speaker_mutes: SpeakerMutes::default(),
+        }
+    }
+
+    #[test]
+    fn test_rollover_policy() {
+        let policy = RolloverPolicy::new(Some(60));
+        assert!(!policy.is_due(59));
+        assert!(policy.is_due(60));
+
+        assert!(!RolloverPolicy::new(None).is_due(u64::MAX));
+        assert!(!RolloverPolicy::new(Some(0)).is_due(10));
+    }
+
+    #[test]
+    fn test_collect_chain_from_any_part() {
+        let mut records = HashMap::new();
+        records.insert("a".to_string(), record("a", None, 1000, &[("first part", 1.0, 3.0)]));
+        records.insert("b".to_string(), record("b", Some("a"), 1060, &[("second part", 0.0, 2.0)]));
+        records.insert("other".to_string(), record("other", None, 5000, &[]));
+
+        let ids: Vec<&str> = collect_chain(&records, "b").iter().map(|r| r.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        let ids: Vec<&str> = collect_chain(&records, "a").iter().map(|r| r.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        assert!(collect_chain(&records, "missing").is_empty());
+    }
+
+    #[test]
+    fn test_stitched_export_keeps_boundary_segment_in_order() {
+        let mut records = HashMap::new();
+        records.insert("a".to_string(), record("a", None, 1000, &[("before the split", 50.0, 58.0)]));
+        // The in-flight buffer at the boundary is transcribed into the continuation
+        records.insert("b".to_string(), record("b", Some("a"), 1060, &[("across the boundary", 0.0, 4.0), ("after", 5.0, 6.0)]));
+
+        let chain = collect_chain(&records, "a");
+        let doc = stitch_chain(&chain);
+        let segments = doc["segments"].as_array().unwrap();
+
+        let texts: Vec<&str> = segments.iter().map(|s| s["text"].as_str().unwrap()).collect();
+        assert_eq!(texts, vec!["before the split", "across the boundary", "after"]);
+        assert_eq!(segments[1]["startTime"].as_f64().unwrap(), 60.0);
+        assert_eq!(segments[1]["sessionId"], "b");
+        assert_eq!(doc["parts"].as_array().unwrap().len(), 2);
+        assert_eq!(doc["totalDuration"].as_f64().unwrap(), 120.0);
+    }
+}
@@ -0,0 +1,130 @@
+//! Session audio timebase shared by transcription and diarization
+//!
+//! The diarization engine times its embeddings and speaker segments relative
+//! to the buffer it was given, while transcript segments are placed by the
+//! stream offset of that buffer. A SessionClock is owned by the session state
+//! and stamps every chunk as it leaves the capture service; both the ASR
+//! segment builder and the diarization results are placed on its timeline,
+//! so speaker turns and text stay aligned however long the session runs.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::stream_clock::StreamClock;
+use crate::diarization::types::{SpeakerEmbedding, SpeakerSegment};
+
+/// Monotonic audio-time offsets of a session, shared by the session state and its loop
+#[derive(Debug, Clone, Default)]
+pub struct SessionClock {
+    stream: Arc<Mutex<StreamClock>>,
+}
+
+impl SessionClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn stream(&self) -> MutexGuard<'_, StreamClock> {
+        self.stream.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Seconds of audio consumed so far
+    pub fn consumed_seconds(&self) -> f64 {
+        self.stream().consumed_seconds()
+    }
+
+    /// Stamp a chunk delivered by the capture service; returns its start offset
+    pub fn advance(&self, samples: usize, sample_rate: u32) -> f64 {
+        self.stream().advance(samples, sample_rate)
+    }
+
+    pub fn begin_buffer(&self, chunk_start: f64) {
+        self.stream().begin_buffer(chunk_start);
+    }
+
+    pub fn trim_buffer(&self, samples: usize, sample_rate: u32) {
+        self.stream().trim_buffer(samples, sample_rate);
+    }
+
+    pub fn clear_buffer(&self) {
+        self.stream().clear_buffer();
+    }
+
+    /// Start and end offset of a buffer holding `samples` samples
+    pub fn buffer_span(&self, samples: usize, sample_rate: u32) -> (f64, f64) {
+        self.stream().buffer_span(samples, sample_rate)
+    }
+
+    /// Restart the timeline at zero for a continuation session
+    pub fn rebase(&self) {
+        self.stream().rebase();
+    }
+
+    /// Move embeddings timed within a buffer starting at `buffer_start` onto the session timeline
+    pub fn place_embeddings(embeddings: &mut [SpeakerEmbedding], buffer_start: f64) {
+        let offset = buffer_start as f32;
+        for embedding in embeddings {
+            embedding.timestamp_start += offset;
+            embedding.timestamp_end += offset;
+        }
+    }
+
+    /// Move speaker segments timed within a buffer starting at `buffer_start` onto the session timeline
+    pub fn place_segments(segments: &mut [SpeakerSegment], buffer_start: f64) {
+        let offset = buffer_start as f32;
+        for segment in segments {
+            segment.start_time += offset;
+            segment.end_time += offset;
+            if let Some(embedding) = segment.embedding.as_mut() {
+                Self::place_embeddings(std::slice::from_mut(embedding), buffer_start);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedding(start: f32, end: f32) -> SpeakerEmbedding {
+        SpeakerEmbedding {
+            vector: vec![0.0; 4],
+            confidence: 0.9,
+            timestamp_start: start,
+            timestamp_end: end,
+            speaker_id: None,
+            quality: 0.9,
+            extracted_at: 0,
+            audio_duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_handles_share_one_timeline() {
+        let clock = SessionClock::new();
+        let loop_handle = clock.clone();
+        loop_handle.advance(16000, 16000);
+        let chunk_start = loop_handle.advance(1600, 16000);
+        loop_handle.begin_buffer(chunk_start);
+
+        assert_eq!(clock.consumed_seconds(), 1.1);
+        assert_eq!(clock.buffer_span(1600, 16000), (1.0, 1.1));
+        clock.rebase();
+        assert_eq!(loop_handle.consumed_seconds(), 0.0);
+    }
+
+    #[test]
+    fn test_place_embeddings_on_session_time() {
+        let clock = SessionClock::new();
+        clock.advance(16000 * 600, 16000);
+        let chunk_start = clock.advance(16000 * 3, 16000);
+        clock.begin_buffer(chunk_start);
+        let (buffer_start, buffer_end) = clock.buffer_span(16000 * 3, 16000);
+
+        // Diarization reports times within the 3 s buffer
+        let mut embeddings = vec![embedding(0.5, 1.5), embedding(1.5, 2.75)];
+        SessionClock::place_embeddings(&mut embeddings, buffer_start);
+        assert_eq!(embeddings[0].timestamp_start, 600.5);
+        assert_eq!(embeddings[1].timestamp_end, 602.75);
+        assert!(embeddings.iter().all(|e| e.timestamp_start as f64 >= buffer_start && e.timestamp_end as f64 <= buffer_end));
+    }
+}
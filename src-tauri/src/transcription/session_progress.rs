@@ -0,0 +1,124 @@
+//! Live session progress
+//!
+//! What get_active_sessions reports about a running session beyond its
+//! config: how far the model load got, how many segments were stored and
+//! when the last one arrived, and the most recent audio level. The
+//! transcription loop updates it as it runs; elapsed time is derived from the
+//! session start when the progress is read.
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::levels::LevelReading;
+
+/// Whisper model state of a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelStatus {
+    /// Downloading or loading the model; no audio is transcribed yet
+    Downloading,
+    Ready,
+    /// The model could not be loaded; the session will not transcribe
+    Error,
+}
+
+/// Audio level of the latest chunk the transcription loop received
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioLevelSnapshot {
+    /// Meter value on the 0-1 scale of the `audio-level` event
+    pub level: f32,
+    #[serde(rename = "peakDbfs")]
+    pub peak_dbfs: f32,
+    #[serde(rename = "rmsDbfs")]
+    pub rms_dbfs: f32,
+    pub clipping: bool,
+    /// Unix time of the reading (milliseconds)
+    pub timestamp: u64,
+}
+
+impl AudioLevelSnapshot {
+    pub fn new(reading: &LevelReading, timestamp: u64) -> Self {
+        Self {
+            level: reading.legacy_level(),
+            peak_dbfs: reading.peak_dbfs,
+            rms_dbfs: reading.rms_dbfs,
+            clipping: reading.clipping,
+            timestamp,
+        }
+    }
+}
+
+/// Counters the transcription loop maintains on the session state
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionProgress {
+    pub model_status: ModelStatus,
+    pub segment_count: usize,
+    /// Unix time the last segment was stored (milliseconds)
+    pub last_segment_at: Option<u64>,
+    pub current_audio_level: Option<AudioLevelSnapshot>,
+}
+
+impl Default for SessionProgress {
+    fn default() -> Self {
+        Self {
+            model_status: ModelStatus::Downloading,
+            segment_count: 0,
+            last_segment_at: None,
+            current_audio_level: None,
+        }
+    }
+}
+
+impl SessionProgress {
+    pub fn record_segment(&mut self, now_ms: u64) {
+        self.segment_count += 1;
+        self.last_segment_at = Some(now_ms);
+    }
+
+    pub fn record_level(&mut self, reading: &LevelReading, now_ms: u64) {
+        self.current_audio_level = Some(AudioLevelSnapshot::new(reading, now_ms));
+    }
+
+    /// Seconds since `start_time` (Unix seconds), zero for a clock set backwards
+    pub fn elapsed_seconds(start_time: u64, now_secs: u64) -> u64 {
+        now_secs.saturating_sub(start_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::levels;
+
+    #[test]
+    fn test_fake_loop_advances_counters() {
+        let mut progress = SessionProgress::default();
+        assert_eq!(progress.model_status, ModelStatus::Downloading);
+        progress.model_status = ModelStatus::Ready;
+
+        // 100 ms chunks of a quiet tone, a segment stored every tenth chunk
+        let chunk: Vec<f32> = (0..1600).map(|i| (i as f32 * 0.05).sin() * 0.05).collect();
+        let start_ms = 1_700_000_000_000u64;
+        for index in 0..50u64 {
+            let now_ms = start_ms + index * 100;
+            progress.record_level(&levels::measure(&chunk), now_ms);
+            if index % 10 == 9 {
+                progress.record_segment(now_ms);
+            }
+        }
+
+        assert_eq!(progress.segment_count, 5);
+        assert_eq!(progress.last_segment_at, Some(start_ms + 4_900));
+        let level = progress.current_audio_level.unwrap();
+        assert_eq!(level.timestamp, start_ms + 4_900);
+        assert!(level.level > 0.0 && level.level <= 1.0);
+        assert!(level.rms_dbfs < level.peak_dbfs);
+        assert!(!level.clipping);
+    }
+
+    #[test]
+    fn test_elapsed_seconds() {
+        assert_eq!(SessionProgress::elapsed_seconds(1_000, 1_720), 720);
+        assert_eq!(SessionProgress::elapsed_seconds(1_000, 900), 0);
+        assert_eq!(serde_json::to_value(ModelStatus::Downloading).unwrap(), "downloading");
+    }
+}
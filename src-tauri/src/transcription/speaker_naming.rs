@@ -0,0 +1,499 @@
+//! Speaker name suggestions from self-introductions
+//!
+//! Meetings often open with "Hi, this is Tanaka from the Osaka office" or
+//! "営業部の田中と申します". Early segments are matched against a small set of
+//! English and Japanese introduction patterns; a hit proposes a display name for
+//! the segment's speaker. Suggestions are never applied automatically, the user
+//! confirms them through the speaker rename command.
+//!
+//! Patterns only match at the start of a clause (after an optional greeting), so
+//! sentences that merely mention a name ("tell Tanaka about it") are ignored.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::asr::types::Language;
+
+/// Default length of the opening window in which introductions are looked for (seconds)
+pub const DEFAULT_WINDOW_SECS: u64 = 180;
+
+/// Clause-opening words skipped before an English pattern ("Hi everyone, ...")
+const EN_GREETINGS: &[&str] = &[
+    "hi", "hello", "hey", "good", "morning", "afternoon", "evening", "everyone", "everybody",
+    "all", "folks", "team", "there", "so", "ok", "okay", "yes", "yeah", "and", "um", "uh",
+    "well", "again", "thanks", "thank", "you",
+];
+
+/// Capitalized words that are not names ("This is Monday", "I'm Japanese")
+const EN_NOT_NAMES: &[&str] = &[
+    "i", "a", "the", "it", "not", "just", "sorry", "here", "going", "sure", "fine", "back", "done",
+    "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday", "today",
+    "tomorrow", "january", "february", "march", "april", "may", "june", "july", "august",
+    "september", "october", "november", "december", "english", "japanese", "zoom", "teams",
+];
+
+/// Words that may follow a name in an introduction ("Tanaka from Osaka")
+const EN_AFFILIATIONS: &[&str] = &["from", "with", "at", "of", "in"];
+
+/// Japanese greetings that may precede a bare "Xです"
+const JA_GREETINGS: &[&str] = &[
+    "こんにちは", "こんばんは", "おはようございます", "お疲れ様です", "お疲れさまです",
+    "はじめまして", "初めまして", "もしもし", "失礼します",
+];
+
+/// Common predicates of "私はXです" that are not names
+const JA_NOT_NAMES: &[&str] = &[
+    "学生", "社員", "担当", "担当者", "日本人", "新人", "大丈夫", "元気", "賛成", "反対",
+    "エンジニア", "マネージャー", "リーダー",
+];
+
+/// Honorifics: a name carrying one refers to someone else
+const JA_HONORIFICS: &[&str] = &["さん", "様", "さま", "くん", "君", "ちゃん", "先生", "氏"];
+
+/// Introduction pattern that produced a suggestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntroductionPattern {
+    /// "my name is X"
+    MyNameIs,
+    /// "this is X"
+    ThisIs,
+    /// "I'm X" / "I am X"
+    IAm,
+    /// "call me X"
+    CallMe,
+    /// "X here" / "X speaking"
+    NameHere,
+    /// "Xと申します"
+    ToMoushimasu,
+    /// "Xといいます"
+    ToIimasu,
+    /// "私はXです"
+    WatashiWa,
+    /// "こんにちは、Xです"
+    GreetingDesu,
+}
+
+/// A self-introduction found in a piece of text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Introduction {
+    pub name: String,
+    pub pattern: IntroductionPattern,
+    pub confidence: f32,
+    /// Sentence the name was taken from
+    pub evidence: String,
+}
+
+/// Existing speaker profile that may be the introduced person
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileMatch {
+    #[serde(rename = "profileId")]
+    pub profile_id: String,
+    #[serde(rename = "profileName")]
+    pub profile_name: String,
+    /// 1.0 for the same name, lower when only part of the name matches
+    pub similarity: f32,
+}
+
+/// Display name proposed for a session speaker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NameSuggestion {
+    #[serde(rename = "speakerId")]
+    pub speaker_id: String,
+    #[serde(rename = "suggestedName")]
+    pub suggested_name: String,
+    pub evidence: String,
+    pub confidence: f32,
+    pub pattern: IntroductionPattern,
+    #[serde(rename = "segmentStartTime")]
+    pub segment_start_time: f32,
+    #[serde(rename = "profileMatch")]
+    pub profile_match: Option<ProfileMatch>,
+}
+
+/// Looks for introductions in the opening segments of a session
+#[derive(Debug, Clone)]
+pub struct SpeakerNameSuggester {
+    window_secs: f32,
+    /// (speaker, name) pairs already suggested
+    suggested: HashSet<(String, String)>,
+}
+
+impl SpeakerNameSuggester {
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window_secs: window_secs as f32,
+            suggested: HashSet::new(),
+        }
+    }
+
+    /// Whether segments starting at `segment_start` (seconds) are still examined
+    pub fn in_window(&self, segment_start: f32) -> bool {
+        segment_start <= self.window_secs
+    }
+
+    /// Check a new segment; each name is suggested once per speaker
+    pub fn observe(
+        &mut self,
+        speaker_id: &str,
+        text: &str,
+        language: Option<Language>,
+        segment_start: f32,
+    ) -> Option<NameSuggestion> {
+        if !self.in_window(segment_start) {
+            return None;
+        }
+        let introduction = detect_introduction(text, language)?;
+        if !self.suggested.insert((speaker_id.to_string(), introduction.name.clone())) {
+            return None;
+        }
+        Some(NameSuggestion {
+            speaker_id: speaker_id.to_string(),
+            suggested_name: introduction.name,
+            evidence: introduction.evidence,
+            confidence: introduction.confidence,
+            pattern: introduction.pattern,
+            segment_start_time: segment_start,
+            profile_match: None,
+        })
+    }
+}
+
+/// Find a self-introduction, using Japanese patterns for Japanese text and English ones otherwise
+pub fn detect_introduction(text: &str, language: Option<Language>) -> Option<Introduction> {
+    let japanese = match language.map(|l| l.code()) {
+        Some("ja") => true,
+        Some("en") => false,
+        _ => text.chars().any(is_kana),
+    };
+    if japanese {
+        detect_japanese(text)
+    } else {
+        detect_english(text)
+    }
+}
+
+/// Best matching profile for a suggested name, given (profile id, profile name) pairs
+pub fn match_profile<'a>(name: &str, profiles: impl IntoIterator<Item = (&'a str, &'a str)>) -> Option<ProfileMatch> {
+    let wanted = name.trim().to_lowercase();
+    let wanted_tokens: Vec<&str> = wanted.split_whitespace().collect();
+    let mut best: Option<ProfileMatch> = None;
+
+    for (profile_id, profile_name) in profiles {
+        let candidate = profile_name.trim().to_lowercase();
+        let similarity = if candidate == wanted {
+            1.0
+        } else if !wanted_tokens.is_empty()
+            && wanted_tokens.iter().all(|t| candidate.split_whitespace().any(|c| c == *t))
+        {
+            0.8
+        } else if wanted.chars().count() >= 2 && !wanted.contains(' ') && candidate.contains(&wanted) && !candidate.is_ascii() {
+            // "田中" within "田中太郎"
+            0.8
+        } else {
+            continue;
+        };
+        if best.as_ref().map(|b| similarity > b.similarity).unwrap_or(true) {
+            best = Some(ProfileMatch {
+                profile_id: profile_id.to_string(),
+                profile_name: profile_name.to_string(),
+                similarity,
+            });
+        }
+    }
+    best
+}
+
+fn detect_english(text: &str) -> Option<Introduction> {
+    let text = text.replace('’', "'");
+    for sentence in text.split(['.', '!', '?', ';']) {
+        for clause in sentence.split(',') {
+            let words: Vec<&str> = clause.split_whitespace().collect();
+            let start = words.iter()
+                .position(|w| !EN_GREETINGS.contains(&normalize_word(w).as_str()))
+                .unwrap_or(words.len());
+            if let Some((name, pattern, confidence)) = match_english_clause(&words[start..]) {
+                return Some(Introduction {
+                    name,
+                    pattern,
+                    confidence,
+                    evidence: sentence.trim().to_string(),
+                });
+            }
+        }
+    }
+    None
+}
+
+fn match_english_clause(words: &[&str]) -> Option<(String, IntroductionPattern, f32)> {
+    let lower: Vec<String> = words.iter().map(|w| normalize_word(w)).collect();
+    let starts_with = |prefix: &[&str]| lower.len() > prefix.len() && lower.iter().zip(prefix).all(|(w, p)| w == p);
+
+    let (skip, pattern, base): (usize, IntroductionPattern, f32) = if starts_with(&["my", "name", "is"]) {
+        (3, IntroductionPattern::MyNameIs, 0.9)
+    } else if starts_with(&["my", "name's"]) {
+        (2, IntroductionPattern::MyNameIs, 0.9)
+    } else if starts_with(&["call", "me"]) {
+        (2, IntroductionPattern::CallMe, 0.8)
+    } else if starts_with(&["this", "is"]) {
+        (2, IntroductionPattern::ThisIs, 0.6)
+    } else if starts_with(&["i'm"]) {
+        (1, IntroductionPattern::IAm, 0.55)
+    } else if starts_with(&["i", "am"]) {
+        (2, IntroductionPattern::IAm, 0.55)
+    } else {
+        // "Tanaka here" / "Tanaka speaking"
+        let name_len = take_name(words)?.1;
+        return match lower.get(name_len).map(String::as_str) {
+            Some("here") | Some("speaking") => {
+                Some((take_name(words)?.0, IntroductionPattern::NameHere, 0.7))
+            }
+            _ => None,
+        };
+    };
+
+    let rest = &words[skip..];
+    let (name, name_len) = take_name(rest)?;
+    let next = lower.get(skip + name_len).map(String::as_str);
+    let confidence = match next {
+        None => base,
+        Some("here") | Some("speaking") => base + 0.25,
+        Some(word) if EN_AFFILIATIONS.contains(&word) => base + 0.2,
+        // "This is Tanaka's laptop", "I'm Sure that..."
+        Some(_) => return None,
+    };
+    Some((name, pattern, confidence.min(0.95)))
+}
+
+/// Up to three capitalized name words at the start of `words`
+fn take_name(words: &[&str]) -> Option<(String, usize)> {
+    let mut parts = Vec::new();
+    for word in words.iter().take(3) {
+        let trimmed = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'');
+        if !is_name_word(trimmed) {
+            break;
+        }
+        parts.push(trimmed);
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some((parts.join(" "), parts.len()))
+}
+
+fn is_name_word(word: &str) -> bool {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    first.is_uppercase()
+        && chars.all(|c| c.is_alphabetic() || c == '-' || c == '\'')
+        && !word.ends_with("'s")
+        && !EN_NOT_NAMES.contains(&word.to_lowercase().as_str())
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'').to_lowercase()
+}
+
+fn detect_japanese(text: &str) -> Option<Introduction> {
+    let sentences: Vec<&str> = text.split(['。', '！', '？', '!', '?']).map(str::trim).filter(|s| !s.is_empty()).collect();
+    for sentence in sentences {
+        if let Some((name, pattern, confidence)) = match_japanese_sentence(sentence) {
+            return Some(Introduction {
+                name,
+                pattern,
+                confidence,
+                evidence: sentence.to_string(),
+            });
+        }
+    }
+    None
+}
+
+fn match_japanese_sentence(sentence: &str) -> Option<(String, IntroductionPattern, f32)> {
+    let markers: [(&str, IntroductionPattern, f32); 4] = [
+        ("と申します", IntroductionPattern::ToMoushimasu, 0.9),
+        ("ともうします", IntroductionPattern::ToMoushimasu, 0.9),
+        ("といいます", IntroductionPattern::ToIimasu, 0.75),
+        ("と言います", IntroductionPattern::ToIimasu, 0.75),
+    ];
+    for (marker, pattern, confidence) in markers {
+        let Some(position) = sentence.find(marker) else {
+            continue;
+        };
+        // "なんといいますか" is a filler, not an introduction
+        if sentence[position + marker.len()..].starts_with('か') {
+            continue;
+        }
+        let (name, affiliated) = japanese_name_before(&sentence[..position]);
+        if let Some(name) = name.filter(|n| is_japanese_name(n)) {
+            let bonus = if affiliated { 0.05 } else { 0.0 };
+            return Some((name, pattern, (confidence + bonus).min(0.95)));
+        }
+    }
+
+    let clauses: Vec<&str> = sentence.split(['、', ',', ' ', '　']).filter(|c| !c.is_empty()).collect();
+    for (index, clause) in clauses.iter().enumerate() {
+        let Some(body) = clause.strip_suffix("です") else {
+            continue;
+        };
+        for subject in ["私は", "わたしは", "わたくしは", "僕は", "ぼくは"] {
+            if let Some(predicate) = body.strip_prefix(subject) {
+                let (name, affiliated) = japanese_name_before(predicate);
+                if let Some(name) = name.filter(|n| is_japanese_name(n)) {
+                    return Some((name, IntroductionPattern::WatashiWa, if affiliated { 0.8 } else { 0.7 }));
+                }
+            }
+        }
+        // "こんにちは、田中です": a bare "Xです" right after a greeting
+        if index > 0 && JA_GREETINGS.contains(&clauses[index - 1]) {
+            let (name, _) = japanese_name_before(body);
+            if let Some(name) = name.filter(|n| is_japanese_name(n) && n.chars().count() <= 6) {
+                return Some((name, IntroductionPattern::GreetingDesu, 0.65));
+            }
+        }
+    }
+    None
+}
+
+/// Name directly before a marker, after the last delimiter or affiliation ("営業部の田中")
+fn japanese_name_before(text: &str) -> (Option<String>, bool) {
+    let start = text.rfind(['、', ',', ' ', '　', 'の', 'は']).map(|i| {
+        i + text[i..].chars().next().map(char::len_utf8).unwrap_or(1)
+    });
+    let affiliated = start.map(|i| text[..i].ends_with('の')).unwrap_or(false);
+    let name = text[start.unwrap_or(0)..].trim();
+    if name.is_empty() {
+        (None, affiliated)
+    } else {
+        (Some(name.to_string()), affiliated)
+    }
+}
+
+fn is_japanese_name(name: &str) -> bool {
+    let length = name.chars().count();
+    (1..=12).contains(&length)
+        && name.chars().all(|c| is_kanji(c) || is_kana(c) || c.is_ascii_alphabetic() || c == '・')
+        // All-hiragana fragments are almost always ordinary words
+        && name.chars().any(|c| is_kanji(c) || is_katakana(c) || c.is_ascii_alphabetic())
+        && !JA_HONORIFICS.iter().any(|h| name.ends_with(h))
+        && !JA_NOT_NAMES.contains(&name)
+}
+
+fn is_kanji(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '々')
+}
+
+fn is_katakana(c: char) -> bool {
+    matches!(c, '\u{30A0}'..='\u{30FF}')
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{309F}') || is_katakana(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name_of(text: &str, language: Language) -> Option<String> {
+        detect_introduction(text, Some(language)).map(|i| i.name)
+    }
+
+    #[test]
+    fn test_english_introductions() {
+        let english = Language::ENGLISH;
+        let cases = [
+            ("Hi, this is Tanaka from the Osaka office.", "Tanaka"),
+            ("Hello everyone, my name is Yuki Sato.", "Yuki Sato"),
+            ("Good morning, I'm Priya, with the platform team.", "Priya"),
+            ("Okay so, Tanaka here.", "Tanaka"),
+            ("Thanks. This is Maria Lopez speaking.", "Maria Lopez"),
+            ("Everyone, call me Ken.", "Ken"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(name_of(text, english).as_deref(), Some(expected), "{}", text);
+        }
+
+        let introduction = detect_introduction(cases[0].0, Some(english)).unwrap();
+        assert_eq!(introduction.pattern, IntroductionPattern::ThisIs);
+        assert!(introduction.confidence > 0.75);
+        assert_eq!(introduction.evidence, "Hi, this is Tanaka from the Osaka office");
+    }
+
+    #[test]
+    fn test_english_mentions_do_not_trigger() {
+        let sentences = [
+            "Please tell Tanaka about it.",
+            "I think this is Tanaka's laptop.",
+            "This is the plan for Monday.",
+            "I'm sure Sato will join later.",
+            "This is Friday's agenda.",
+            "We met Tanaka here last week.",
+            "I'm not convinced, this is great though.",
+            "Tanaka said his name is on the list.",
+        ];
+        for sentence in sentences {
+            assert_eq!(name_of(sentence, Language::ENGLISH), None, "{}", sentence);
+        }
+    }
+
+    #[test]
+    fn test_japanese_introductions() {
+        let japanese: Language = "ja".parse().unwrap();
+        let cases = [
+            ("はじめまして、大阪オフィスの田中と申します。よろしくお願いします。", "田中"),
+            ("営業部の佐藤といいます。", "佐藤"),
+            ("私は山田です。", "山田"),
+            ("こんにちは、鈴木です。", "鈴木"),
+            ("お疲れ様です、マイクと申します", "マイク"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(name_of(text, japanese).as_deref(), Some(expected), "{}", text);
+        }
+
+        let negatives = [
+            "田中さんに伝えてください。",
+            "田中さんと話しました。",
+            "それは田中さんの資料です。",
+            "なんといいますか、難しいですね。",
+            "私は大丈夫です。",
+            "佐藤さんといいます。",
+        ];
+        for sentence in negatives {
+            assert_eq!(name_of(sentence, japanese), None, "{}", sentence);
+        }
+        // Script decides when the language is unknown
+        assert_eq!(detect_introduction("営業部の佐藤といいます。", None).unwrap().name, "佐藤");
+    }
+
+    #[test]
+    fn test_suggester_window_and_dedup() {
+        let mut suggester = SpeakerNameSuggester::new(60);
+        let intro = "Hi, this is Tanaka from the Osaka office.";
+
+        let suggestion = suggester.observe("speaker_1", intro, Some(Language::ENGLISH), 4.0).unwrap();
+        assert_eq!(suggestion.speaker_id, "speaker_1");
+        assert_eq!(suggestion.suggested_name, "Tanaka");
+        // Same speaker and name only once, but another speaker may use it too
+        assert!(suggester.observe("speaker_1", intro, Some(Language::ENGLISH), 10.0).is_none());
+        assert!(suggester.observe("speaker_2", intro, Some(Language::ENGLISH), 12.0).is_some());
+        // Outside the opening window nothing is suggested
+        assert!(suggester.observe("speaker_3", "My name is Ken.", Some(Language::ENGLISH), 61.0).is_none());
+    }
+
+    #[test]
+    fn test_profile_match() {
+        let profiles = [("p1", "Yuki Tanaka"), ("p2", "Tanaka"), ("p3", "田中太郎"), ("p4", "Sato")];
+
+        let exact = match_profile("tanaka", profiles).unwrap();
+        assert_eq!(exact.profile_id, "p2");
+        assert_eq!(exact.similarity, 1.0);
+
+        let partial = match_profile("Tanaka", profiles.iter().copied().filter(|p| p.0 != "p2")).unwrap();
+        assert_eq!(partial.profile_id, "p1");
+        assert_eq!(match_profile("田中", profiles).unwrap().profile_id, "p3");
+        assert!(match_profile("Priya", profiles).is_none());
+    }
+}
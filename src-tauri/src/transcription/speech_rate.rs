@@ -0,0 +1,202 @@
+use crate::asr::types::WordResult;
+use crate::transcription::boundary_detector::BoundaryType;
+
+/// Speech rate (words/sec) at or below which the shortest window is used
+const SLOW_SPEECH_WPS: f32 = 1.5;
+/// Speech rate (words/sec) at or above which the longest window is used
+const FAST_SPEECH_WPS: f32 = 3.5;
+/// Extra buffering a soft boundary needs beyond the window before it flushes
+const SOFT_BOUNDARY_EXTRA_MS: u64 = 2000;
+
+/// Adapts the transcription buffering window to the measured speech rate.
+///
+/// Slow speech gets shorter windows for lower latency, fast speech gets longer
+/// windows for more context and fewer words cut at chunk boundaries.
+#[derive(Debug, Clone)]
+pub struct AdaptiveChunkSizer {
+    min_window_ms: u64,
+    max_window_ms: u64,
+    /// Smoothing factor for rate and window updates (0-1, higher reacts faster)
+    smoothing: f32,
+    speech_rate: Option<f32>,
+    window_ms: f32,
+}
+
+impl AdaptiveChunkSizer {
+    pub fn new(min_window_ms: u64, max_window_ms: u64, initial_window_ms: u64) -> Self {
+        let max_window_ms = max_window_ms.max(min_window_ms);
+        Self {
+            min_window_ms,
+            max_window_ms,
+            smoothing: 0.3,
+            speech_rate: None,
+            window_ms: initial_window_ms.clamp(min_window_ms, max_window_ms) as f32,
+        }
+    }
+
+    /// Update the rate estimate from the word timestamps of a finished segment
+    pub fn observe(&mut self, words: &[WordResult]) {
+        let Some(rate) = estimate_speech_rate(words) else {
+            return;
+        };
+
+        let smoothed = match self.speech_rate {
+            Some(previous) => previous + self.smoothing * (rate - previous),
+            None => rate,
+        };
+        self.speech_rate = Some(smoothed);
+
+        // Move the window gradually toward the target so it never jumps
+        let target = self.target_window_ms(smoothed) as f32;
+        self.window_ms += self.smoothing * (target - self.window_ms);
+    }
+
+    /// Current buffering window in milliseconds
+    pub fn window_ms(&self) -> u64 {
+        self.window_ms.round() as u64
+    }
+
+    /// Lower bound of the window
+    pub fn min_window_ms(&self) -> u64 {
+        self.min_window_ms
+    }
+
+    pub fn speech_rate(&self) -> Option<f32> {
+        self.speech_rate
+    }
+
+    fn target_window_ms(&self, rate: f32) -> u64 {
+        let position = ((rate - SLOW_SPEECH_WPS) / (FAST_SPEECH_WPS - SLOW_SPEECH_WPS)).clamp(0.0, 1.0);
+        let span = (self.max_window_ms - self.min_window_ms) as f32;
+        self.min_window_ms + (span * position).round() as u64
+    }
+
+    /// Snapshot for diagnostics events
+    pub fn diagnostics(&self) -> serde_json::Value {
+        serde_json::json!({
+            "windowMs": self.window_ms(),
+            "minWindowMs": self.min_window_ms,
+            "maxWindowMs": self.max_window_ms,
+            "speechRateWps": self.speech_rate
+        })
+    }
+}
+
+/// Whether the RMS-gated loop flushes `buffered_ms` of audio at a detected
+/// boundary. Hard boundaries and sentence ends flush once the window is
+/// filled, soft boundaries only well past it.
+pub fn boundary_flush_due(boundary: &BoundaryType, buffered_ms: u64, window_ms: u64) -> bool {
+    match boundary {
+        BoundaryType::HardBoundary | BoundaryType::SentenceEnd => buffered_ms >= window_ms,
+        BoundaryType::SoftBoundary => buffered_ms >= window_ms + SOFT_BOUNDARY_EXTRA_MS,
+        BoundaryType::None => false,
+    }
+}
+
+/// Words per second over the span covered by the word timestamps
+pub fn estimate_speech_rate(words: &[WordResult]) -> Option<f32> {
+    if words.len() < 2 {
+        return None;
+    }
+    let start = words.first()?.start_time;
+    let end = words.last()?.end_time;
+    let span = end - start;
+    if span <= 0.1 {
+        return None;
+    }
+    Some(words.len() as f32 / span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_at_rate(words_per_sec: f32, duration_sec: f32) -> Vec<WordResult> {
+        let count = (words_per_sec * duration_sec) as usize;
+        let step = 1.0 / words_per_sec;
+        (0..count).map(|i| WordResult {
+            word: format!("w{}", i),
+            start_time: i as f32 * step,
+            end_time: i as f32 * step + step * 0.8,
+            confidence: 0.9,
+        }).collect()
+    }
+
+    /// Count chunk boundaries that fall inside a word for a stream split every `window_ms`
+    fn boundary_errors(words: &[WordResult], windows_ms: &[u64]) -> usize {
+        let mut edge = 0.0;
+        let mut errors = 0;
+        for window in windows_ms {
+            edge += *window as f32 / 1000.0;
+            errors += words.iter().filter(|w| w.start_time < edge && w.end_time > edge).count();
+        }
+        errors
+    }
+
+    #[test]
+    fn test_speech_rate_estimate() {
+        let rate = estimate_speech_rate(&words_at_rate(3.0, 4.0)).unwrap();
+        assert!((rate - 3.0).abs() < 0.3);
+        assert!(estimate_speech_rate(&[]).is_none());
+    }
+
+    #[test]
+    fn test_window_adapts_smoothly_within_bounds() {
+        let mut sizer = AdaptiveChunkSizer::new(2500, 8000, 4500);
+        let fast = words_at_rate(5.0, 4.0);
+
+        let mut previous = sizer.window_ms();
+        for _ in 0..10 {
+            sizer.observe(&fast);
+            let current = sizer.window_ms();
+            assert!(current >= previous);
+            assert!(current - previous < 2000, "window should change gradually");
+            previous = current;
+        }
+        assert!(sizer.window_ms() > 7000 && sizer.window_ms() <= 8000);
+    }
+
+    #[test]
+    fn test_ab_fast_speech_reduces_boundary_errors() {
+        let fast = words_at_rate(4.5, 60.0);
+        let mut sizer = AdaptiveChunkSizer::new(2500, 8000, 4500);
+        let mut adaptive_windows = Vec::new();
+        let mut covered = 0;
+        while covered < 60_000 {
+            sizer.observe(&fast[..20]);
+            adaptive_windows.push(sizer.window_ms());
+            covered += sizer.window_ms();
+        }
+        let fixed_windows = vec![4500; 60_000 / 4500 + 1];
+
+        assert!(boundary_errors(&fast, &adaptive_windows) < boundary_errors(&fast, &fixed_windows));
+    }
+
+    #[test]
+    fn test_ab_slow_speech_reduces_latency() {
+        let slow = words_at_rate(1.2, 30.0);
+        let mut sizer = AdaptiveChunkSizer::new(2500, 8000, 4500);
+        for _ in 0..10 {
+            sizer.observe(&slow);
+        }
+        assert!(sizer.window_ms() < 4500);
+        assert!(sizer.window_ms() >= sizer.min_window_ms());
+    }
+
+    #[test]
+    fn test_hard_boundaries_flush_at_the_rate_driven_window() {
+        let mut sizer = AdaptiveChunkSizer::new(2500, 8000, 4500);
+        for _ in 0..10 {
+            sizer.observe(&words_at_rate(5.0, 4.0));
+        }
+        let window = sizer.window_ms();
+
+        // Fast speech keeps buffering through pauses the minimum window would flush at
+        assert!(!boundary_flush_due(&BoundaryType::HardBoundary, sizer.min_window_ms(), window));
+        assert!(!boundary_flush_due(&BoundaryType::SentenceEnd, window - 100, window));
+        assert!(boundary_flush_due(&BoundaryType::HardBoundary, window, window));
+        assert!(!boundary_flush_due(&BoundaryType::SoftBoundary, window, window));
+        assert!(boundary_flush_due(&BoundaryType::SoftBoundary, window + 2000, window));
+        assert!(!boundary_flush_due(&BoundaryType::None, 20_000, window));
+    }
+}
@@ -0,0 +1,152 @@
+//! Audio-stream offsets for segment timestamps
+//!
+//! Segments are placed on the timeline by how much audio the session has
+//! consumed from the capture stream, not by wall-clock time or a counter.
+//! Every delivered chunk advances the clock, including silence that never
+//! reaches a transcription buffer, so gaps between utterances are preserved.
+
+/// Position of the session's audio stream and of the pending transcription buffer
+#[derive(Debug, Clone, Default)]
+pub struct StreamClock {
+    /// Seconds of audio consumed from the capture stream
+    consumed_seconds: f64,
+    /// Stream offset of the first sample in the transcription buffer
+    buffer_start: Option<f64>,
+}
+
+impl StreamClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seconds of audio consumed so far
+    pub fn consumed_seconds(&self) -> f64 {
+        self.consumed_seconds
+    }
+
+    /// Account for a chunk delivered by the capture stream; returns its start offset
+    pub fn advance(&mut self, samples: usize, sample_rate: u32) -> f64 {
+        let chunk_start = self.consumed_seconds;
+        if sample_rate > 0 {
+            self.consumed_seconds += samples as f64 / sample_rate as f64;
+        }
+        chunk_start
+    }
+
+    /// Mark the chunk starting at `chunk_start` as the first one in the buffer,
+    /// unless the buffer already has a start
+    pub fn begin_buffer(&mut self, chunk_start: f64) {
+        self.buffer_start.get_or_insert(chunk_start);
+    }
+
+    /// Samples were dropped from the front of the buffer
+    pub fn trim_buffer(&mut self, samples: usize, sample_rate: u32) {
+        if let (Some(start), true) = (self.buffer_start.as_mut(), sample_rate > 0) {
+            *start += samples as f64 / sample_rate as f64;
+        }
+    }
+
+    /// The buffer was transcribed or discarded
+    pub fn clear_buffer(&mut self) {
+        self.buffer_start = None;
+    }
+
+    /// Start and end offset of a buffer holding `samples` samples
+    pub fn buffer_span(&self, samples: usize, sample_rate: u32) -> (f64, f64) {
+        let start = self.buffer_start.unwrap_or(self.consumed_seconds).max(0.0);
+        let duration = if sample_rate > 0 { samples as f64 / sample_rate as f64 } else { 0.0 };
+        (start, start + duration)
+    }
+
+    /// Restart the timeline at zero for a continuation session. A buffer
+    /// carried across the boundary starts at zero in the new session.
+    pub fn rebase(&mut self) {
+        let offset = self.consumed_seconds;
+        self.consumed_seconds = 0.0;
+        if let Some(start) = self.buffer_start.as_mut() {
+            *start -= offset;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+    const CHUNK: usize = 1600; // 100 ms
+
+    /// Capture simulator: feeds speech/silence runs of 100 ms chunks through the
+    /// same buffering the transcription loop uses and returns the emitted spans
+    fn simulate(runs: &[(bool, usize)], flush_after_silent_chunks: usize) -> Vec<(f64, f64)> {
+        let mut clock = StreamClock::new();
+        let mut buffer = 0usize;
+        let mut silent = 0usize;
+        let mut spans = Vec::new();
+        for &(speech, chunks) in runs {
+            for _ in 0..chunks {
+                let chunk_start = clock.advance(CHUNK, RATE);
+                if speech {
+                    clock.begin_buffer(chunk_start);
+                    buffer += CHUNK;
+                    silent = 0;
+                } else if buffer > 0 {
+                    silent += 1;
+                    if silent >= flush_after_silent_chunks {
+                        spans.push(clock.buffer_span(buffer, RATE));
+                        clock.clear_buffer();
+                        buffer = 0;
+                    } else {
+                        buffer += CHUNK;
+                    }
+                }
+            }
+        }
+        spans
+    }
+
+    #[test]
+    fn test_silence_gaps_advance_the_clock() {
+        // 2 s speech, 30 s silence, 3 s speech, 10 min silence, 1 s speech, 1 s silence
+        let runs = [(true, 20), (false, 300), (true, 30), (false, 6000), (true, 10), (false, 10)];
+        let spans = simulate(&runs, 5);
+        let truth = [(0.0, 2.0), (32.0, 35.0), (635.0, 636.0)];
+        let buffer_length = 0.5; // trailing silence kept in the buffer
+
+        assert_eq!(spans.len(), truth.len());
+        for ((start, end), (true_start, true_end)) in spans.iter().zip(truth) {
+            assert!((start - true_start).abs() < 1e-6, "start {} vs {}", start, true_start);
+            assert!(*end >= true_end && end - true_end <= buffer_length, "end {} vs {}", end, true_end);
+        }
+    }
+
+    #[test]
+    fn test_trimmed_buffer_moves_its_start() {
+        let mut clock = StreamClock::new();
+        let chunk_start = clock.advance(RATE as usize, RATE);
+        clock.begin_buffer(chunk_start);
+        for _ in 0..24 {
+            clock.advance(RATE as usize, RATE);
+        }
+        // 25 s buffered, the first 5 s dropped to stay within the limit
+        clock.trim_buffer(5 * RATE as usize, RATE);
+        assert_eq!(clock.buffer_span(20 * RATE as usize, RATE), (5.0, 25.0));
+        assert_eq!(clock.consumed_seconds(), 25.0);
+    }
+
+    #[test]
+    fn test_rebase_for_continuation_session() {
+        let mut clock = StreamClock::new();
+        clock.advance(10 * RATE as usize, RATE);
+        let chunk_start = clock.advance(RATE as usize, RATE);
+        clock.begin_buffer(chunk_start);
+        clock.rebase();
+
+        assert_eq!(clock.consumed_seconds(), 0.0);
+        assert_eq!(clock.buffer_span(RATE as usize, RATE), (0.0, 1.0));
+        clock.clear_buffer();
+        let next = clock.advance(CHUNK, RATE);
+        assert_eq!(next, 0.0);
+        assert_eq!(clock.buffer_span(0, RATE), (0.1, 0.1));
+    }
+}
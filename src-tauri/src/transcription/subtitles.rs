@@ -0,0 +1,276 @@
+//! SRT and WebVTT subtitle export
+//!
+//! Converts stored transcript segments (`text`, `startTime`/`endTime` in
+//! seconds, `speaker`) into subtitle cues for video editors. Times are rounded
+//! to milliseconds; missing or overlapping times are clamped so no cue ever
+//! has a negative duration.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Cue length used when a segment has no usable end time (ms)
+const DEFAULT_CUE_MS: u64 = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" | "webvtt" => Some(Self::Vtt),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Srt => "application/x-subrip",
+            Self::Vtt => "text/vtt",
+        }
+    }
+}
+
+/// One subtitle cue, times in milliseconds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speaker: Option<String>,
+    /// Non-empty lines of text
+    pub lines: Vec<String>,
+}
+
+/// Rendered subtitle file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleExport {
+    pub format: SubtitleFormat,
+    pub content: String,
+    #[serde(rename = "cueCount")]
+    pub cue_count: usize,
+    #[serde(rename = "fileExtension")]
+    pub file_extension: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+/// `HH:MM:SS,mmm` for SRT, `HH:MM:SS.mmm` for WebVTT; hours grow past two digits
+pub fn format_timestamp(ms: u64, format: SubtitleFormat) -> String {
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::Vtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// Default label for a speaker id without a display name: "speaker_1" -> "Speaker 1"
+pub fn default_speaker_label(speaker_id: &str) -> String {
+    match speaker_id.strip_prefix("speaker_") {
+        Some(number) if !number.is_empty() => format!("Speaker {}", number),
+        _ => speaker_id.to_string(),
+    }
+}
+
+fn seconds_to_ms(value: &serde_json::Value) -> Option<u64> {
+    value.as_f64()
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(|secs| (secs * 1000.0).round() as u64)
+}
+
+/// Build cues from stored segments. Segments without text are skipped, a
+/// missing start follows the previous cue, a missing or inverted end gets a
+/// default length, and an end running into the next cue is cut back to its
+/// start when that leaves a positive duration.
+pub fn cues_from_segments(segments: &[serde_json::Value], speaker_labels: &HashMap<String, String>) -> Vec<Cue> {
+    let mut cues: Vec<Cue> = Vec::new();
+    for segment in segments {
+        let lines: Vec<String> = segment["text"].as_str()
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+
+        let previous_end = cues.last().map(|c| c.end_ms).unwrap_or(0);
+        let start_ms = seconds_to_ms(&segment["startTime"]).unwrap_or(previous_end);
+        let end_ms = seconds_to_ms(&segment["endTime"])
+            .filter(|&end| end > start_ms)
+            .unwrap_or(start_ms + DEFAULT_CUE_MS);
+        let speaker = segment["speaker"].as_str()
+            .filter(|id| !id.is_empty())
+            .map(|id| speaker_labels.get(id).cloned().unwrap_or_else(|| default_speaker_label(id)));
+        cues.push(Cue { start_ms, end_ms, speaker, lines });
+    }
+
+    cues.sort_by_key(|cue| cue.start_ms);
+    for i in 1..cues.len() {
+        let next_start = cues[i].start_ms;
+        let previous = &mut cues[i - 1];
+        if previous.end_ms > next_start && next_start > previous.start_ms {
+            previous.end_ms = next_start;
+        }
+    }
+    cues
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Render cues as a complete SRT or WebVTT document
+pub fn render(cues: &[Cue], format: SubtitleFormat) -> String {
+    let mut output = String::new();
+    if format == SubtitleFormat::Vtt {
+        output.push_str("WEBVTT\n\n");
+    }
+
+    for (index, cue) in cues.iter().enumerate() {
+        if format == SubtitleFormat::Srt {
+            output.push_str(&format!("{}\n", index + 1));
+        }
+        output.push_str(&format!(
+            "{} --> {}\n",
+            format_timestamp(cue.start_ms, format),
+            format_timestamp(cue.end_ms, format)
+        ));
+
+        let mut lines: Vec<String> = match format {
+            // "-->" would end the cue text early in some WebVTT parsers
+            SubtitleFormat::Vtt => cue.lines.iter().map(|l| escape_vtt(l).replace("--&gt;", "\u{2192}")).collect(),
+            SubtitleFormat::Srt => cue.lines.clone(),
+        };
+        if let Some(speaker) = &cue.speaker {
+            lines[0] = match format {
+                SubtitleFormat::Srt => format!("{}: {}", speaker, lines[0]),
+                SubtitleFormat::Vtt => format!("<v {}>{}", escape_vtt(speaker), lines[0]),
+            };
+        }
+        output.push_str(&lines.join("\n"));
+        output.push_str("\n\n");
+    }
+    output
+}
+
+/// Convert transcript segments into a subtitle file
+pub fn export_subtitles(
+    segments: &[serde_json::Value],
+    format: SubtitleFormat,
+    speaker_labels: &HashMap<String, String>,
+) -> SubtitleExport {
+    let cues = cues_from_segments(segments, speaker_labels);
+    SubtitleExport {
+        format,
+        content: render(&cues, format),
+        cue_count: cues.len(),
+        file_extension: format.extension().to_string(),
+        mime_type: format.mime_type().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn export(segments: &[serde_json::Value], format: SubtitleFormat) -> String {
+        export_subtitles(segments, format, &HashMap::new()).content
+    }
+
+    #[test]
+    fn test_timestamps_past_one_hour() {
+        assert_eq!(format_timestamp(0, SubtitleFormat::Srt), "00:00:00,000");
+        assert_eq!(format_timestamp(3_723_004, SubtitleFormat::Srt), "01:02:03,004");
+        assert_eq!(format_timestamp(3_723_004, SubtitleFormat::Vtt), "01:02:03.004");
+        assert_eq!(format_timestamp(360_000_000, SubtitleFormat::Vtt), "100:00:00.000");
+
+        let segments = [json!({"text": "Late remark", "startTime": 3725.5, "endTime": 3727.25, "speaker": "speaker_2"})];
+        assert_eq!(
+            export(&segments, SubtitleFormat::Srt),
+            "1\n01:02:05,500 --> 01:02:07,250\nSpeaker 2: Late remark\n\n"
+        );
+    }
+
+    #[test]
+    fn test_multi_line_text_and_speaker_labels() {
+        let segments = [json!({"text": "First line\n\n  second line  \n", "startTime": 1.0, "endTime": 2.5, "speaker": "speaker_1"})];
+        let labels = HashMap::from([("speaker_1".to_string(), "Aiko".to_string())]);
+
+        let srt = export_subtitles(&segments, SubtitleFormat::Srt, &labels).content;
+        assert_eq!(srt, "1\n00:00:01,000 --> 00:00:02,500\nAiko: First line\nsecond line\n\n");
+
+        let vtt = export_subtitles(&segments, SubtitleFormat::Vtt, &labels).content;
+        assert_eq!(vtt, "WEBVTT\n\n00:00:01.000 --> 00:00:02.500\n<v Aiko>First line\nsecond line\n\n");
+    }
+
+    #[test]
+    fn test_japanese_text_and_vtt_escaping() {
+        let segments = [
+            json!({"text": "本日の会議を始めます。", "startTime": 0.0, "endTime": 1.8, "speaker": "speaker_1"}),
+            json!({"text": "A<B & C --> D", "startTime": 2.0, "endTime": 3.0}),
+        ];
+
+        let srt = export(&segments, SubtitleFormat::Srt);
+        assert!(srt.contains("Speaker 1: 本日の会議を始めます。\n"));
+
+        let vtt = export(&segments, SubtitleFormat::Vtt);
+        assert!(vtt.contains("<v Speaker 1>本日の会議を始めます。\n"));
+        assert!(vtt.contains("A&lt;B &amp; C \u{2192} D\n"));
+        assert_eq!(vtt.matches(" --> ").count(), 2);
+    }
+
+    #[test]
+    fn test_missing_and_overlapping_times_are_clamped() {
+        let segments = [
+            json!({"text": "overlaps the next", "startTime": 0.0, "endTime": 5.0}),
+            json!({"text": "starts inside", "startTime": 3.0, "endTime": 4.0}),
+            json!({"text": "inverted", "startTime": 10.0, "endTime": 9.0}),
+            json!({"text": "no times"}),
+            json!({"text": "negative", "startTime": -1.0, "endTime": f64::NAN}),
+            json!({"text": "   ", "startTime": 20.0, "endTime": 21.0}),
+        ];
+        let cues = cues_from_segments(&segments, &HashMap::new());
+
+        assert_eq!(cues.len(), 5);
+        assert!(cues.iter().all(|cue| cue.end_ms >= cue.start_ms));
+        assert!(cues.windows(2).all(|pair| pair[0].start_ms <= pair[1].start_ms));
+        let overlapping = cues.iter().find(|c| c.lines[0] == "overlaps the next").unwrap();
+        assert_eq!(overlapping.end_ms, 3000);
+        let inverted = cues.iter().find(|c| c.lines[0] == "inverted").unwrap();
+        assert_eq!((inverted.start_ms, inverted.end_ms), (10_000, 12_000));
+        let untimed = cues.iter().find(|c| c.lines[0] == "no times").unwrap();
+        assert_eq!(untimed.start_ms, 12_000);
+    }
+
+    #[test]
+    fn test_format_parsing() {
+        assert_eq!(SubtitleFormat::parse("SRT"), Some(SubtitleFormat::Srt));
+        assert_eq!(SubtitleFormat::parse("webvtt"), Some(SubtitleFormat::Vtt));
+        assert_eq!(SubtitleFormat::parse("docx"), None);
+        assert_eq!(default_speaker_label("speaker_3"), "Speaker 3");
+        assert_eq!(default_speaker_label("guest"), "guest");
+    }
+}
@@ -0,0 +1,194 @@
+//! Payload of the periodic system-status event
+//!
+//! The transcription loop publishes what only it knows (window sizing, the
+//! last prompt budget, capture channel health, ...) as a [`LoopStatus`]. A
+//! reporter task running next to the loop combines the latest one with the
+//! session's processing stats and its own process sampling into a
+//! [`SystemStatus`] every [`SYSTEM_STATUS_INTERVAL`].
+
+use super::quality_metrics::ProcessingStats;
+use crate::asr::prompt_budget::PromptBudget;
+use crate::asr::temperature_fallback::FallbackStats;
+use crate::asr::types::Device;
+use crate::audio::backpressure::ChannelHealth;
+use crate::audio::resampler::ResamplingReport;
+use crate::resource_monitor::ResourceSummary;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Time between system-status events of a session
+pub const SYSTEM_STATUS_INTERVAL: Duration = Duration::from_secs(5);
+
+const BYTES_PER_MB: f32 = 1024.0 * 1024.0;
+
+/// State of the transcription loop reported with the system status
+#[derive(Debug, Clone, Default)]
+pub struct LoopStatus {
+    /// Session the loop currently writes to; changes on rollover
+    pub session_id: String,
+    pub adaptive_chunking: Option<serde_json::Value>,
+    pub prompt_budget: Option<PromptBudget>,
+    pub temperature_fallback: FallbackStats,
+    pub backpressure: Option<ChannelHealth>,
+    pub inference_device: Option<Device>,
+    pub resampling: Option<ResamplingReport>,
+    pub noise_suppression: Option<NoiseSuppressionStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoiseSuppressionStatus {
+    pub enabled: bool,
+    pub profile_ready: bool,
+    pub cpu_percent: f32,
+}
+
+/// Transcription speed and load
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessingMetrics {
+    /// Inference time over transcribed audio time; below 1.0 is faster than real time
+    pub real_time_factor: Option<f32>,
+    /// Mean inference time per transcription call (ms)
+    pub average_latency: u64,
+    /// From capture of the newest chunk in the last transcribed buffer to its transcript (ms)
+    pub last_chunk_latency: Option<u64>,
+    /// Chunks waiting in the capture channel
+    pub queued_segments: usize,
+    /// Percent of one core used by the process
+    pub cpu_usage: f32,
+    /// Resident memory of the process (GB)
+    pub memory_usage: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MemoryUsage {
+    /// Process memory (MB)
+    pub used: f32,
+    /// Memory available to the system (MB)
+    pub available: f32,
+    /// Process memory as a percentage of system memory
+    pub percentage: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayBufferStatus {
+    pub memory_mb: f32,
+    pub retained_seconds: f64,
+}
+
+impl ReplayBufferStatus {
+    pub fn new(memory_bytes: usize, retained_span: Option<(f64, f64)>) -> Self {
+        Self {
+            memory_mb: memory_bytes as f32 / BYTES_PER_MB,
+            retained_seconds: retained_span.map(|(start, end)| end - start).unwrap_or(0.0),
+        }
+    }
+}
+
+/// Payload of the system-status event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemStatus {
+    pub processing_metrics: ProcessingMetrics,
+    pub adaptive_chunking: Option<serde_json::Value>,
+    pub prompt_budget: Option<PromptBudget>,
+    pub temperature_fallback: FallbackStats,
+    pub backpressure: Option<ChannelHealth>,
+    pub inference_device: Option<&'static str>,
+    pub resampling: Option<ResamplingReport>,
+    pub noise_suppression: Option<NoiseSuppressionStatus>,
+    pub memory_usage: MemoryUsage,
+    pub replay_buffer: ReplayBufferStatus,
+    pub resources: ResourceSummary,
+}
+
+impl SystemStatus {
+    pub fn new(loop_status: LoopStatus, stats: &ProcessingStats, resources: ResourceSummary, replay_buffer: ReplayBufferStatus) -> Self {
+        Self {
+            processing_metrics: ProcessingMetrics {
+                real_time_factor: stats.real_time_factor(),
+                average_latency: stats.processing_time.as_millis() as u64 / stats.chunks.max(1) as u64,
+                last_chunk_latency: stats.last_chunk_latency.map(|latency| latency.as_millis() as u64),
+                queued_segments: loop_status.backpressure.map(|health| health.queue_depth).unwrap_or(0),
+                cpu_usage: resources.current_cpu_percent,
+                memory_usage: resources.current_memory_mb / 1024.0,
+            },
+            adaptive_chunking: loop_status.adaptive_chunking,
+            prompt_budget: loop_status.prompt_budget,
+            temperature_fallback: loop_status.temperature_fallback,
+            backpressure: loop_status.backpressure,
+            inference_device: loop_status.inference_device.map(|device| device.label()),
+            resampling: loop_status.resampling,
+            noise_suppression: loop_status.noise_suppression,
+            memory_usage: MemoryUsage {
+                used: resources.current_memory_mb.round(),
+                available: resources.available_system_memory_mb.round(),
+                percentage: if resources.total_system_memory_mb > 0.0 {
+                    (resources.current_memory_mb / resources.total_system_memory_mb * 100.0).round()
+                } else {
+                    0.0
+                },
+            },
+            replay_buffer,
+            resources,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resources() -> ResourceSummary {
+        ResourceSummary {
+            samples: 10,
+            current_memory_mb: 512.0,
+            current_cpu_percent: 140.0,
+            available_system_memory_mb: 6144.0,
+            total_system_memory_mb: 8192.0,
+            ..ResourceSummary::default()
+        }
+    }
+
+    #[test]
+    fn test_metrics_come_from_measurements() {
+        let mut stats = ProcessingStats::default();
+        stats.record(Duration::from_millis(300), 3.0);
+        stats.record(Duration::from_millis(500), 2.0);
+        stats.record_latency(Duration::from_millis(640));
+        let loop_status = LoopStatus {
+            session_id: "session".to_string(),
+            backpressure: Some(ChannelHealth { queue_depth: 4, queue_capacity: 64, ..ChannelHealth::default() }),
+            inference_device: Some(Device::CPU),
+            ..LoopStatus::default()
+        };
+
+        let status = SystemStatus::new(loop_status, &stats, resources(), ReplayBufferStatus::new(2 * 1024 * 1024, Some((10.0, 40.0))));
+        let metrics = status.processing_metrics;
+        assert!((metrics.real_time_factor.unwrap() - 0.16).abs() < 1e-6);
+        assert_eq!((metrics.average_latency, metrics.last_chunk_latency), (400, Some(640)));
+        assert_eq!(metrics.queued_segments, 4);
+        assert_eq!((metrics.cpu_usage, metrics.memory_usage), (140.0, 0.5));
+        assert_eq!(status.memory_usage, MemoryUsage { used: 512.0, available: 6144.0, percentage: 6.0 });
+        assert_eq!(status.replay_buffer, ReplayBufferStatus { memory_mb: 2.0, retained_seconds: 30.0 });
+    }
+
+    #[test]
+    fn test_payload_keeps_frontend_field_names() {
+        let status = SystemStatus::new(LoopStatus::default(), &ProcessingStats::default(), ResourceSummary::default(), ReplayBufferStatus::default());
+        let value = serde_json::to_value(&status).unwrap();
+
+        let metrics = &value["processingMetrics"];
+        for field in ["realTimeFactor", "averageLatency", "lastChunkLatency", "queuedSegments", "cpuUsage", "memoryUsage"] {
+            assert!(metrics.get(field).is_some(), "processingMetrics.{} is missing", field);
+        }
+        assert!(metrics["realTimeFactor"].is_null(), "nothing transcribed yet");
+        assert_eq!(value["memoryUsage"]["percentage"], 0.0);
+        for field in ["adaptiveChunking", "promptBudget", "temperatureFallback", "backpressure", "inferenceDevice", "resampling", "noiseSuppression", "replayBuffer", "resources"] {
+            assert!(value.get(field).is_some(), "{} is missing", field);
+        }
+        assert_eq!(value["replayBuffer"]["retainedSeconds"], 0.0);
+    }
+}
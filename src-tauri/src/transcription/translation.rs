@@ -0,0 +1,75 @@
+//! Translate mode
+//!
+//! With `mode: "translate"` Whisper's translate task turns speech in any
+//! language into English text. The source language is left to Whisper's
+//! detection instead of being pinned to the first configured language, and
+//! segments carry the detected language as `originalLanguage` next to the
+//! English `text`.
+
+use serde_json::Value;
+
+use crate::asr::types::{Language, Task};
+use crate::asr::whisper::WhisperConfig;
+
+pub const MODE_TRANSCRIBE: &str = "transcribe";
+pub const MODE_TRANSLATE: &str = "translate";
+/// Modes accepted in `TranscriptionConfig::mode`
+pub const MODES: &[&str] = &[MODE_TRANSCRIBE, MODE_TRANSLATE];
+
+/// Whisper task of a config mode; anything but "translate" transcribes
+pub fn task_for_mode(mode: &str) -> Task {
+    if mode.trim().eq_ignore_ascii_case(MODE_TRANSLATE) {
+        Task::Translate
+    } else {
+        Task::Transcribe
+    }
+}
+
+/// Point `config` at the task of `mode`. Translating detects the source
+/// language, whatever language the session was pinned to.
+pub fn configure(config: &mut WhisperConfig, mode: &str) {
+    config.task = task_for_mode(mode);
+    if config.task == Task::Translate {
+        config.language = Some(Language::AUTO);
+    }
+}
+
+/// Label a segment decoded with `task`: a translated segment keeps the
+/// detected source language as `originalLanguage` and is English itself
+pub fn label_segment(segment: &mut Value, task: Task) {
+    if task != Task::Translate {
+        return;
+    }
+    segment["originalLanguage"] = segment["language"].clone();
+    segment["language"] = Value::from(Language::ENGLISH.code());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_translate_mode_unpins_language() {
+        let mut config = WhisperConfig { language: Language::parse("ja").ok(), ..WhisperConfig::default() };
+        configure(&mut config, "Translate");
+        assert_eq!(config.task, Task::Translate);
+        assert_eq!(config.language, Some(Language::AUTO));
+
+        let mut config = WhisperConfig { language: Language::parse("ja").ok(), ..WhisperConfig::default() };
+        configure(&mut config, MODE_TRANSCRIBE);
+        assert_eq!(config.task, Task::Transcribe);
+        assert_eq!(config.language, Language::parse("ja").ok());
+    }
+
+    #[test]
+    fn test_translated_segment_keeps_original_language() {
+        let mut segment = json!({"text": "The budget is approved.", "language": "ja"});
+        label_segment(&mut segment, Task::Translate);
+        assert_eq!((segment["language"].clone(), segment["originalLanguage"].clone()), (json!("en"), json!("ja")));
+
+        let mut segment = json!({"text": "予算は承認されました", "language": "ja"});
+        label_segment(&mut segment, Task::Transcribe);
+        assert!(segment.get("originalLanguage").is_none());
+    }
+}
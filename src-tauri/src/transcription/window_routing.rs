@@ -0,0 +1,221 @@
+//! Transcript window routing
+//!
+//! Detached transcript windows (e.g. on a second monitor) receive the full
+//! transcription stream of one session. The router tracks which window shows
+//! which session, resyncs a window with the session backlog when it registers,
+//! follows a session across rollovers, and forgets windows once they are
+//! destroyed so no payloads are serialized for windows that no longer exist.
+//!
+//! A window is registered before its backlog is taken, so a segment emitted in
+//! between reaches it twice rather than not at all; windows drop updates for
+//! segment ids they already hold.
+
+use std::collections::HashMap;
+
+/// Event carrying the segments a window missed before it registered
+pub const TRANSCRIPT_BACKLOG_EVENT: &str = "transcript-window-backlog";
+/// Event carrying live updates for a registered window
+pub const TRANSCRIPT_UPDATE_EVENT: &str = "transcript-window-update";
+
+/// Delivers an event to a single window
+pub trait WindowEmitter {
+    fn emit_to_window(&self, window_label: &str, event: &str, payload: &serde_json::Value) -> Result<(), String>;
+}
+
+impl WindowEmitter for tauri::AppHandle {
+    fn emit_to_window(&self, window_label: &str, event: &str, payload: &serde_json::Value) -> Result<(), String> {
+        use tauri::Emitter;
+        self.emit_to(window_label, event, payload.clone()).map_err(|e| e.to_string())
+    }
+}
+
+/// Window label -> session shown in that window
+#[derive(Debug, Default)]
+pub struct TranscriptWindowRouter {
+    routes: HashMap<String, String>,
+}
+
+impl TranscriptWindowRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Target `session_id` updates at `window_label`. Re-registering a window
+    /// switches it to the new session. Returns whether the window is new to
+    /// the router, i.e. whether it still needs a destroyed handler.
+    pub fn register(&mut self, window_label: &str, session_id: &str) -> bool {
+        let previous = self.routes.insert(window_label.to_string(), session_id.to_string());
+        tracing::info!("🪟 Transcript window '{}' registered for session {}", window_label, session_id);
+        previous.is_none()
+    }
+
+    /// Resync a registered window with the segments of its session so far
+    pub fn send_backlog(
+        &self,
+        emitter: &impl WindowEmitter,
+        window_label: &str,
+        backlog: &[serde_json::Value],
+    ) -> Result<(), String> {
+        let Some(session_id) = self.routes.get(window_label) else {
+            return Ok(());
+        };
+        tracing::debug!("Sending {} backlog segments to transcript window '{}'", backlog.len(), window_label);
+        emitter.emit_to_window(window_label, TRANSCRIPT_BACKLOG_EVENT, &serde_json::json!({
+            "sessionId": session_id,
+            "segments": backlog,
+        }))
+    }
+
+    /// Point the windows of a session that rolled over at its continuation
+    pub fn transfer(&mut self, previous_session_id: &str, next_session_id: &str) {
+        for session in self.routes.values_mut().filter(|session| session.as_str() == previous_session_id) {
+            *session = next_session_id.to_string();
+        }
+    }
+
+    /// Stop routing to `window_label`; returns whether it was registered
+    pub fn deregister(&mut self, window_label: &str) -> bool {
+        let removed = self.routes.remove(window_label).is_some();
+        if removed {
+            tracing::info!("🪟 Transcript window '{}' deregistered", window_label);
+        }
+        removed
+    }
+
+    /// Windows currently showing `session_id`
+    pub fn windows_for(&self, session_id: &str) -> Vec<String> {
+        let mut windows: Vec<String> = self.routes.iter()
+            .filter(|(_, session)| session.as_str() == session_id)
+            .map(|(window, _)| window.clone())
+            .collect();
+        windows.sort();
+        windows
+    }
+
+    /// Forward a transcription update to every window showing `session_id`
+    pub fn route(&self, emitter: &impl WindowEmitter, session_id: &str, payload: &serde_json::Value) {
+        for window in self.windows_for(session_id) {
+            if let Err(e) = emitter.emit_to_window(&window, TRANSCRIPT_UPDATE_EVENT, payload) {
+                tracing::warn!("Failed to send transcript update to window '{}': {}", window, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Records (window, event, payload) instead of emitting
+    #[derive(Default)]
+    struct MockEmitter {
+        sent: RefCell<Vec<(String, String, serde_json::Value)>>,
+    }
+
+    impl WindowEmitter for MockEmitter {
+        fn emit_to_window(&self, window_label: &str, event: &str, payload: &serde_json::Value) -> Result<(), String> {
+            self.sent.borrow_mut().push((window_label.to_string(), event.to_string(), payload.clone()));
+            Ok(())
+        }
+    }
+
+    fn segment(text: &str) -> serde_json::Value {
+        serde_json::json!({ "text": text })
+    }
+
+    #[test]
+    fn test_register_mid_session_delivers_backlog() {
+        let emitter = MockEmitter::default();
+        let mut router = TranscriptWindowRouter::new();
+
+        assert!(router.register("transcript-1", "session-a"));
+        router.send_backlog(&emitter, "transcript-1", &[segment("hello"), segment("world")]).unwrap();
+
+        let sent = emitter.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "transcript-1");
+        assert_eq!(sent[0].1, TRANSCRIPT_BACKLOG_EVENT);
+        assert_eq!(sent[0].2["sessionId"], "session-a");
+        assert_eq!(sent[0].2["segments"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_segment_emitted_before_the_backlog_is_not_lost() {
+        let emitter = MockEmitter::default();
+        let mut router = TranscriptWindowRouter::new();
+
+        // The loop emits between registration and the backlog snapshot
+        router.register("transcript-1", "session-a");
+        router.route(&emitter, "session-a", &segment("in between"));
+        router.send_backlog(&emitter, "transcript-1", &[segment("earlier")]).unwrap();
+
+        let sent = emitter.sent.borrow();
+        let events: Vec<&str> = sent.iter().map(|(_, event, _)| event.as_str()).collect();
+        assert_eq!(events, [TRANSCRIPT_UPDATE_EVENT, TRANSCRIPT_BACKLOG_EVENT]);
+        assert_eq!(sent[0].2["text"], "in between");
+    }
+
+    #[test]
+    fn test_updates_target_only_windows_of_the_session() {
+        let emitter = MockEmitter::default();
+        let mut router = TranscriptWindowRouter::new();
+        router.register("transcript-a", "session-a");
+        router.register("transcript-b", "session-b");
+
+        router.route(&emitter, "session-b", &segment("for b"));
+
+        let sent = emitter.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "transcript-b");
+        assert_eq!(sent[0].1, TRANSCRIPT_UPDATE_EVENT);
+        assert_eq!(sent[0].2["text"], "for b");
+    }
+
+    #[test]
+    fn test_windows_follow_a_rollover() {
+        let emitter = MockEmitter::default();
+        let mut router = TranscriptWindowRouter::new();
+        router.register("transcript-a", "session-a");
+        router.register("transcript-b", "session-b");
+
+        router.transfer("session-a", "session-a2");
+        router.route(&emitter, "session-a", &segment("old part"));
+        router.route(&emitter, "session-a2", &segment("continuation"));
+
+        let sent = emitter.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!((sent[0].0.as_str(), &sent[0].2["text"]), ("transcript-a", &serde_json::json!("continuation")));
+        assert_eq!(router.windows_for("session-b"), vec!["transcript-b".to_string()]);
+    }
+
+    #[test]
+    fn test_no_emissions_after_deregistration() {
+        let emitter = MockEmitter::default();
+        let mut router = TranscriptWindowRouter::new();
+        router.register("transcript-a", "session-a");
+
+        assert!(router.deregister("transcript-a"));
+        assert!(!router.deregister("transcript-a"));
+        router.route(&emitter, "session-a", &segment("late"));
+        router.send_backlog(&emitter, "transcript-a", &[segment("late backlog")]).unwrap();
+
+        assert!(emitter.sent.borrow().is_empty());
+        assert!(router.windows_for("session-a").is_empty());
+    }
+
+    #[test]
+    fn test_reregistering_switches_session() {
+        let mut router = TranscriptWindowRouter::new();
+        assert!(router.register("transcript-a", "session-a"));
+        // Already tracked, so no second destroyed handler
+        assert!(!router.register("transcript-a", "session-b"));
+
+        assert!(router.windows_for("session-a").is_empty());
+        assert_eq!(router.windows_for("session-b"), vec!["transcript-a".to_string()]);
+
+        // A destroyed window's label can be reused by a new window
+        router.deregister("transcript-a");
+        assert!(router.register("transcript-a", "session-b"));
+    }
+}
@@ -0,0 +1,154 @@
+//! A/B comparison of fixed and adaptive chunk windows on recorded speech
+//!
+//! Streams LibriSpeech chapter 1089-134686 through the boundary detector in
+//! 100 ms chunks with the live loop's RMS-gated flush rule, once with the fixed
+//! 4.5 s window and once with the window driven by the speech rate Whisper
+//! measures on each flushed chunk. The recording is time-compressed for the
+//! fast speech run and stretched for the slow one. Reported per run: flushes
+//! that cut through speech (the last chunk was still voiced) and the mean
+//! length of the flushed buffers, which bounds the latency of their text.
+
+use kaginote_lib::asr::types::{Device, ModelTier, Task, TranscriptionContext};
+use kaginote_lib::asr::whisper::{WhisperConfig, WhisperEngine};
+use kaginote_lib::audio::levels;
+use kaginote_lib::audio::types::{AudioData, AudioSource};
+use kaginote_lib::transcription::boundary_detector::{AudioChunk, BoundaryConfig, BoundaryDetector};
+use kaginote_lib::transcription::chunk_window::{DEFAULT_MAX_CHUNK_MS, DEFAULT_MIN_CHUNK_MS};
+use kaginote_lib::transcription::speech_rate::{boundary_flush_due, AdaptiveChunkSizer};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const SAMPLE_RATE: u32 = 16000;
+const CHUNK_SAMPLES: usize = 1600; // 100 ms
+const RECORDINGS: usize = 20;
+const AUDIO_DIR: &str = "tests/diarization_realtime/test_audio/134686";
+
+#[derive(Debug, Default)]
+struct RunReport {
+    flushes: usize,
+    mid_speech_flushes: usize,
+    mean_flush_ms: f64,
+    final_window_ms: u64,
+}
+
+fn load_recordings() -> Vec<f32> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(AUDIO_DIR);
+    let mut samples = Vec::new();
+    for index in 0..RECORDINGS {
+        let path = dir.join(format!("1089-134686-{:04}.flac.wav", index));
+        let mut reader = hound::WavReader::open(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        let spec = reader.spec();
+        assert_eq!((spec.sample_rate, spec.channels, spec.bits_per_sample), (SAMPLE_RATE, 1, 16));
+        samples.extend(reader.samples::<i16>().map(|s| s.unwrap() as f32 / 32768.0));
+    }
+    samples
+}
+
+/// Play the recording `speed` times faster by linear interpolation
+fn time_scale(samples: &[f32], speed: f64) -> Vec<f32> {
+    let length = (samples.len() as f64 / speed) as usize;
+    (0..length).map(|n| {
+        let position = n as f64 * speed;
+        let index = position as usize;
+        let fraction = (position - index as f64) as f32;
+        let current = samples[index.min(samples.len() - 1)];
+        let next = samples[(index + 1).min(samples.len() - 1)];
+        current + (next - current) * fraction
+    }).collect()
+}
+
+async fn stream(engine: &WhisperEngine, audio: &[f32], mut sizer: Option<AdaptiveChunkSizer>) -> RunReport {
+    // Same detector settings as the live transcription loop
+    let config = BoundaryConfig {
+        silence_threshold: 0.015,
+        soft_boundary_ms: 600,
+        hard_boundary_ms: 1000,
+        max_chunks: 50,
+        min_speech_duration_ms: 4500,
+        energy_variance_threshold: 0.05,
+        spectral_analysis_enabled: true,
+    };
+    let silence_threshold = config.silence_threshold;
+    let mut detector = BoundaryDetector::new(config);
+    let mut buffer = Vec::new();
+    let mut flushed_ms = Vec::new();
+    let mut report = RunReport::default();
+
+    for (index, chunk) in audio.chunks(CHUNK_SAMPLES).enumerate() {
+        let energy_level = levels::measure(chunk).legacy_level();
+        let boundary = detector.process_chunk(AudioChunk {
+            samples: chunk.to_vec(),
+            sample_rate: SAMPLE_RATE,
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(index as u64 * 100),
+            energy_level,
+        });
+        buffer.extend_from_slice(chunk);
+
+        let buffered_ms = buffer.len() as u64 * 1000 / SAMPLE_RATE as u64;
+        let window_ms = sizer.as_ref().map_or(DEFAULT_MIN_CHUNK_MS, AdaptiveChunkSizer::window_ms);
+        let flush = boundary_flush_due(&boundary, buffered_ms, window_ms)
+            || buffered_ms >= DEFAULT_MAX_CHUNK_MS
+            || !detector.should_continue_buffering(buffered_ms);
+        if !flush {
+            continue;
+        }
+
+        report.flushes += 1;
+        if energy_level > silence_threshold {
+            report.mid_speech_flushes += 1;
+        }
+        flushed_ms.push(buffered_ms);
+
+        if let Some(sizer) = sizer.as_mut() {
+            let audio_data = AudioData {
+                samples: std::mem::take(&mut buffer),
+                sample_rate: SAMPLE_RATE,
+                channels: 1,
+                timestamp: SystemTime::now(),
+                source_channel: AudioSource::Microphone,
+                duration_seconds: buffered_ms as f32 / 1000.0,
+            };
+            let result = engine.transcribe(&audio_data, &TranscriptionContext::default()).await.expect("transcription");
+            sizer.observe(&result.words);
+        }
+        buffer.clear();
+    }
+
+    report.mean_flush_ms = flushed_ms.iter().sum::<u64>() as f64 / flushed_ms.len().max(1) as f64;
+    report.final_window_ms = sizer.as_ref().map_or(DEFAULT_MIN_CHUNK_MS, AdaptiveChunkSizer::window_ms);
+    report
+}
+
+#[tokio::test]
+#[ignore] // Needs the Whisper Standard model
+async fn test_adaptive_chunking_ab_on_recorded_speech() {
+    let engine = WhisperEngine::new(WhisperConfig {
+        model_tier: ModelTier::Standard,
+        device: Device::Auto,
+        language: Some("en".parse().unwrap()),
+        task: Task::Transcribe,
+        enable_word_timestamps: true,
+        temperature: 0.0,
+        ..Default::default()
+    }).await.expect("Whisper Standard model");
+
+    let recording = load_recordings();
+    for (label, speed) in [("fast", 1.6), ("slow", 0.7)] {
+        let audio = time_scale(&recording, speed);
+        let fixed = stream(&engine, &audio, None).await;
+        let adaptive = stream(&engine, &audio, Some(AdaptiveChunkSizer::new(2500, 8000, DEFAULT_MIN_CHUNK_MS))).await;
+        println!("{} speech, {:.0}s: fixed {:?}", label, audio.len() as f64 / SAMPLE_RATE as f64, fixed);
+        println!("{} speech: adaptive {:?}", label, adaptive);
+
+        if label == "fast" {
+            // Longer windows at fast rates give fewer flushes, so fewer of them cut words
+            assert!(adaptive.final_window_ms > DEFAULT_MIN_CHUNK_MS);
+            assert!(adaptive.flushes <= fixed.flushes);
+            assert!(adaptive.mid_speech_flushes <= fixed.mid_speech_flushes);
+        } else {
+            // Shorter windows at slow rates hand text over sooner
+            assert!(adaptive.final_window_ms < DEFAULT_MIN_CHUNK_MS);
+            assert!(adaptive.mean_flush_ms <= fixed.mean_flush_ms);
+        }
+    }
+}
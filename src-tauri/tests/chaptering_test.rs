@@ -0,0 +1,85 @@
+//! Chaptering of a finished transcript
+//!
+//! Built from the diarization test generator's scenarios: the three-speaker
+//! meeting, then a pause, then a monologue on another subject, then new
+//! people making small talk right after it.
+
+use kaginote_lib::transcription::chapters::{split_chapters, ChapterConfig, ChapterSignal};
+
+#[allow(dead_code)]
+#[path = "diarization_realtime/test_scenarios.rs"]
+mod test_scenarios;
+use test_scenarios::{GroundTruthData, TestScenarioGenerator};
+
+/// Scenario segments as stored transcript segments, shifted by `offset` seconds
+fn transcript_segments(scenario: &GroundTruthData, offset: f32, rename: &[(&str, &str)]) -> Vec<serde_json::Value> {
+    scenario.segments.iter()
+        .map(|segment| {
+            let speaker = rename.iter()
+                .find(|(from, _)| *from == segment.speaker_id)
+                .map(|(_, to)| to.to_string())
+                .unwrap_or_else(|| segment.speaker_id.clone());
+            serde_json::json!({
+                "text": segment.text.clone().unwrap_or_default(),
+                "startTime": segment.start_time + offset,
+                "endTime": segment.end_time + offset,
+                "confidence": segment.confidence,
+                "speaker": speaker,
+                "qualityScore": { "boundaryType": "sentence_end" }
+            })
+        })
+        .collect()
+}
+
+fn config() -> ChapterConfig {
+    ChapterConfig {
+        min_pause_seconds: 10.0,
+        window_seconds: 30.0,
+        min_chapter_seconds: 20.0,
+        ..ChapterConfig::default()
+    }
+}
+
+#[test]
+fn test_meeting_alone_is_one_chapter() {
+    let meeting = TestScenarioGenerator::multi_speaker_meeting();
+    let chapters = split_chapters(&transcript_segments(&meeting, 0.0, &[]), &config());
+
+    assert_eq!(chapters.len(), 1, "{:?}", chapters);
+    assert_eq!(chapters[0].title, "Welcome everyone to today's meeting.");
+    assert_eq!((chapters[0].start_time, chapters[0].end_time), (0.0, meeting.duration));
+    assert_eq!(chapters[0].segment_count, meeting.segments.len());
+    // Most talk time first; all three talk enough to be listed
+    assert_eq!(chapters[0].speakers, ["speaker_1", "speaker_2", "speaker_0"]);
+}
+
+#[test]
+fn test_pause_and_topic_changes_start_chapters() {
+    let meeting = TestScenarioGenerator::multi_speaker_meeting();
+    let monologue = TestScenarioGenerator::single_speaker_monologue();
+    let small_talk = TestScenarioGenerator::simple_two_speaker_conversation();
+
+    // 15 s of silence after the meeting, the small talk follows the monologue directly
+    let mut segments = transcript_segments(&meeting, 0.0, &[]);
+    segments.extend(transcript_segments(&monologue, 60.0, &[]));
+    segments.extend(transcript_segments(&small_talk, 90.5, &[("speaker_0", "speaker_4"), ("speaker_1", "speaker_5")]));
+
+    let chapters = split_chapters(&segments, &config());
+    assert_eq!(chapters.len(), 3, "{:?}", chapters);
+
+    assert_eq!(chapters[1].signals[0], ChapterSignal::Pause);
+    assert_eq!((chapters[1].start_time, chapters[1].end_time), (60.0, 90.0));
+    assert_eq!(chapters[1].speakers, ["speaker_0"]);
+    assert_eq!(chapters[1].title, "Today I want to talk about the importance of artificial…");
+    assert_eq!(chapters[1].first_segment, meeting.segments.len());
+
+    assert_eq!(chapters[2].signals, [ChapterSignal::SpeakerChange, ChapterSignal::LexicalShift]);
+    assert_eq!(chapters[2].start_time, 90.5);
+    assert_eq!(chapters[2].title, "Hello, how are you today?");
+    assert_eq!(chapters[2].speakers, ["speaker_4", "speaker_5"]);
+    assert_eq!(chapters.iter().map(|chapter| chapter.segment_count).sum::<usize>(), segments.len());
+
+    // A long minimum chapter keeps the whole recording in one
+    let long_chapters = ChapterConfig { min_chapter_seconds: 600.0, ..config() };
+    assert_eq!(split_chapters(&segments, &long_chapters).len(), 1);
+}
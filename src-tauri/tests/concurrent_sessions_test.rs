@@ -0,0 +1,113 @@
+//! Concurrent transcription sessions
+//!
+//! With `maxConcurrentSessions` above one, a live session and a background
+//! file session can hold a slot each. Live sessions start a simulated capture
+//! through the same path as start_transcription; file sessions only need the
+//! reservation. Captures and engines are keyed by the session ids.
+
+use kaginote_lib::audio::capture_simulator::SimulatorConfig;
+use kaginote_lib::commands::{self, AppState, TranscriptionConfig};
+use std::path::Path;
+use std::sync::Arc;
+
+/// One second of a quiet 220 Hz tone at 16 kHz mono
+fn write_tone(path: &Path) {
+    let spec = hound::WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for i in 0..16000 {
+        let sample = (2.0 * std::f32::consts::PI * 220.0 * i as f32 / 16000.0).sin() * 0.3;
+        writer.write_sample((sample * i16::MAX as f32) as i16).unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+fn live_config(path: &Path, max_concurrent_sessions: u32) -> TranscriptionConfig {
+    TranscriptionConfig {
+        max_concurrent_sessions,
+        capture_simulator: Some(SimulatorConfig { path: path.to_path_buf(), speed: 0.0, chunk_ms: 100, dropouts: Vec::new() }),
+        ..TranscriptionConfig::default()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_live_and_file_sessions_run_concurrently() {
+    let state = Arc::new(AppState::new());
+    let config = TranscriptionConfig { max_concurrent_sessions: 2, ..TranscriptionConfig::default() };
+
+    let starts: Vec<_> = ["live", "file"].into_iter().map(|session_id| {
+        let state = state.clone();
+        let config = config.clone();
+        tokio::spawn(async move { commands::reserve_session(&state, session_id, &config).await })
+    }).collect();
+    for start in starts {
+        start.await.unwrap().expect("both sessions fit within maxConcurrentSessions");
+    }
+    assert_eq!(state.active_sessions.lock().await.len(), 2);
+
+    let rejected = commands::reserve_session(&state, "third", &config).await.unwrap_err();
+    assert_eq!(rejected.payload().code, "session_already_active");
+    assert_eq!(rejected.payload().details.unwrap()["maxConcurrentSessions"], 2);
+
+    // The file session finishing frees its slot without touching the live one
+    commands::release_session_reservation(&state, "file").await;
+    commands::reserve_session(&state, "third", &config).await.unwrap();
+    let sessions = state.active_sessions.lock().await;
+    assert!(sessions.contains_key("live") && sessions.contains_key("third"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_live_capture_and_file_session_start_together() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("huddle.wav");
+    write_tone(&path);
+    let state = Arc::new(AppState::new());
+    let file_config = TranscriptionConfig { max_concurrent_sessions: 2, ..TranscriptionConfig::default() };
+
+    let live = {
+        let state = state.clone();
+        let config = live_config(&path, 2);
+        tokio::spawn(async move { commands::start_session_capture(&state, "live", &config).await })
+    };
+    let file = {
+        let state = state.clone();
+        tokio::spawn(async move { commands::reserve_session(&state, "file", &file_config).await })
+    };
+    live.await.unwrap().expect("the simulated capture starts");
+    file.await.unwrap().expect("the file session fits next to it");
+
+    let captures = state.audio_captures.lock().await;
+    assert_eq!(captures.keys().collect::<Vec<_>>(), vec!["live"]);
+    assert!(captures["live"].lock().await.is_simulated());
+    assert_eq!(state.active_sessions.lock().await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_limit_is_the_smallest_of_the_active_sessions() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("huddle.wav");
+    write_tone(&path);
+    let state = AppState::new();
+
+    // A live session started alone is not joined by one asking for more room
+    commands::start_session_capture(&state, "live", &live_config(&path, 1)).await.unwrap();
+    let roomy = TranscriptionConfig { max_concurrent_sessions: 2, ..TranscriptionConfig::default() };
+    let rejected = commands::reserve_session(&state, "file", &roomy).await.unwrap_err();
+    assert_eq!(rejected.payload().code, "session_already_active");
+    assert_eq!(rejected.payload().details.unwrap()["maxConcurrentSessions"], 1);
+
+    // Nor the other way round: the new session's own limit counts too
+    let state = AppState::new();
+    commands::reserve_session(&state, "file", &roomy).await.unwrap();
+    let rejected = commands::start_session_capture(&state, "live", &live_config(&path, 1)).await.unwrap_err();
+    assert_eq!(rejected.payload().code, "session_already_active");
+    assert!(state.audio_captures.lock().await.is_empty(), "a rejected start opens no capture");
+}
+
+#[test]
+fn test_recommended_tier_shares_the_machine_between_sessions() {
+    assert_eq!(commands::recommended_tier(16.0, 8, 1), "high-accuracy");
+    assert_eq!(commands::recommended_tier(16.0, 8, 2), "standard");
+    assert_eq!(commands::recommended_tier(16.0, 8, 4), "turbo");
+    // No sessions is planned like the first one
+    assert_eq!(commands::recommended_tier(8.0, 4, 0), "standard");
+}
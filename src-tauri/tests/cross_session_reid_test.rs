@@ -0,0 +1,257 @@
+//! Speaker re-identification across sessions
+//!
+//! Profiles and embeddings from the seed data are stored through the
+//! SpeakerStore and loaded into an EmbeddingIndex the way speaker storage
+//! initialisation does. Embeddings from a "later session" are then matched
+//! the way the live transcription loop does: index search, then each
+//! candidate profile's own confidence threshold.
+
+use kaginote_lib::diarization::known_speakers::{self, KnownSpeaker, KnownSpeakerMatch, UnknownSpeakers};
+use kaginote_lib::diarization::session_stats::{compute_diarization_stats, AttributionCounters};
+use kaginote_lib::diarization::SpeakerEmbedding;
+use kaginote_lib::models::{CreateSpeakerProfileRequest, UpdateSpeakerProfileRequest};
+use kaginote_lib::storage::{Database, EmbeddingIndex, SpeakerStore};
+use std::collections::HashMap;
+use tempfile::NamedTempFile;
+
+const SEED_SQL: &str = include_str!("../seeds/001_test_speakers.sql");
+const SEED_DIMENSION: usize = 8;
+
+struct SeedSpeaker {
+    seed_id: String,
+    name: String,
+    confidence_threshold: f32,
+    is_active: bool,
+}
+
+/// Speaker profiles of the seed file
+fn seed_speakers() -> Vec<SeedSpeaker> {
+    let profiles = SEED_SQL.split("-- Insert sample voice embeddings").next().unwrap();
+    profiles.split("(\n        'test-speaker-").skip(1)
+        .map(|tuple| {
+            let quoted: Vec<&str> = tuple.split('\'').collect();
+            let flags: Vec<&str> = tuple.lines().nth(2).unwrap().split(',').map(str::trim).collect();
+            SeedSpeaker {
+                seed_id: format!("test-speaker-{}", quoted[0]),
+                name: quoted[2].to_string(),
+                confidence_threshold: flags[1].parse().unwrap(),
+                is_active: flags[2] == "1",
+            }
+        })
+        .collect()
+}
+
+/// Seed embeddings by seed speaker id; the blobs hold big-endian floats
+fn seed_embeddings() -> HashMap<String, Vec<Vec<f32>>> {
+    let embeddings = SEED_SQL.split("-- Insert sample voice embeddings").nth(1).unwrap();
+    let mut by_speaker: HashMap<String, Vec<Vec<f32>>> = HashMap::new();
+    for tuple in embeddings.split("(\n        'embed-").skip(1) {
+        let speaker = tuple.split('\'').nth(2).unwrap().to_string();
+        let hex = tuple.split("X'").nth(1).unwrap().split('\'').next().unwrap();
+        let vector = (0..hex.len()).step_by(8)
+            .map(|i| f32::from_bits(u32::from_str_radix(&hex[i..i + 8], 16).unwrap()))
+            .collect::<Vec<f32>>();
+        assert_eq!(vector.len(), SEED_DIMENSION);
+        by_speaker.entry(speaker).or_default().push(vector);
+    }
+    by_speaker
+}
+
+fn live_embedding(vector: Vec<f32>) -> SpeakerEmbedding {
+    SpeakerEmbedding {
+        vector,
+        confidence: 0.9,
+        timestamp_start: 0.0,
+        timestamp_end: 2.0,
+        speaker_id: None,
+        quality: 0.9,
+        extracted_at: 0,
+        audio_duration_ms: 2000,
+    }
+}
+
+/// Store the seed speakers and load the index from the store. Returns the
+/// profile name by seed id.
+async fn seeded_store() -> (NamedTempFile, SpeakerStore, EmbeddingIndex, HashMap<String, String>) {
+    let db_file = NamedTempFile::new().unwrap();
+    let database = Database::new(db_file.path()).await.unwrap();
+    database.migrate().await.unwrap();
+    let store = SpeakerStore::new(database);
+    let embeddings = seed_embeddings();
+
+    let mut names = HashMap::new();
+    for seed in seed_speakers() {
+        let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+            name: seed.name.clone(),
+            description: None,
+            color: None,
+            confidence_threshold: Some(seed.confidence_threshold),
+        }).await.unwrap();
+        if !seed.is_active {
+            store.update_speaker_profile(profile.id, UpdateSpeakerProfileRequest {
+                name: None,
+                description: None,
+                color: None,
+                confidence_threshold: None,
+                is_active: Some(false),
+            }).await.unwrap();
+        }
+        for vector in &embeddings[&seed.seed_id] {
+            store.add_voice_embedding(kaginote_lib::models::VoiceEmbedding::new(
+                profile.id, vector.clone(), "test_model_v1".to_string(), 0.9, 5.0,
+            )).await.unwrap();
+        }
+        names.insert(seed.seed_id, seed.name);
+    }
+
+    let index = EmbeddingIndex::new(SEED_DIMENSION, 8);
+    let mut stored = Vec::new();
+    for profile in store.list_speaker_profiles(false).await.unwrap() {
+        stored.extend(store.get_voice_embeddings(profile.id).await.unwrap());
+    }
+    index.rebuild(stored).unwrap();
+    (db_file, store, index, names)
+}
+
+/// What the live loop does with an embedding the session's speakers did not match
+async fn identify(store: &SpeakerStore, index: &EmbeddingIndex, vector: &[f32]) -> Option<KnownSpeaker> {
+    let candidates = index.find_similar_embeddings(
+        vector,
+        known_speakers::INDEX_SEARCH_FLOOR,
+        known_speakers::MAX_INDEX_CANDIDATES,
+    ).unwrap();
+    let mut profiles = Vec::new();
+    for (profile_id, similarity) in candidates {
+        if let Some(profile) = store.get_speaker_profile(profile_id).await.unwrap() {
+            profiles.push((profile, similarity));
+        }
+    }
+    known_speakers::best_known_speaker(profiles)
+}
+
+#[tokio::test]
+async fn test_seed_speakers_are_recognised_in_a_later_session() {
+    let (_db_file, store, index, names) = seeded_store().await;
+    let embeddings = seed_embeddings();
+
+    for seed in seed_speakers().into_iter().filter(|seed| seed.is_active) {
+        let later_session_vector = embeddings[&seed.seed_id].last().unwrap();
+        let known = identify(&store, &index, later_session_vector).await
+            .unwrap_or_else(|| panic!("{} was not recognised", seed.name));
+        assert_eq!(known.name, names[&seed.seed_id]);
+        assert!(known.similarity >= seed.confidence_threshold);
+
+        // Segments carry the stored profile's id, not a per-session speaker_N
+        let profile = store.get_speaker_profile(known.profile_id).await.unwrap().unwrap();
+        assert_eq!(known.speaker_id(), profile.id.to_string());
+    }
+}
+
+#[tokio::test]
+async fn test_inactive_profiles_are_not_attributed() {
+    let (_db_file, store, index, _names) = seeded_store().await;
+    let inactive = seed_speakers().into_iter().find(|seed| !seed.is_active).unwrap();
+    let vector = &seed_embeddings()[&inactive.seed_id][0];
+
+    let known = identify(&store, &index, vector).await;
+    assert_ne!(known.map(|known| known.name), Some(inactive.name));
+}
+
+#[tokio::test]
+async fn test_unknown_speaker_is_remembered_for_the_next_session() {
+    let (_db_file, store, index, _names) = seeded_store().await;
+    let stranger = vec![1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0];
+    assert!(identify(&store, &index, &stranger).await.is_none());
+
+    // First session: the stranger gets a session label and is saved at session end
+    let mut unknown = UnknownSpeakers::new();
+    unknown.record("speaker_2", live_embedding(stranger.clone()));
+    assert_eq!(unknown.matching_speaker(&live_embedding(stranger.clone()), 0.7), Some("speaker_2"));
+    for speaker in unknown.speakers() {
+        let profile = store.create_speaker_profile(speaker.profile_request("session-1")).await.unwrap();
+        for embedding in speaker.voice_embeddings(profile.id) {
+            store.add_voice_embedding(embedding.clone()).await.unwrap();
+            index.add_embedding(embedding).unwrap();
+        }
+    }
+
+    // Next session: the stranger is recognised by the provisional profile
+    let known = identify(&store, &index, &stranger).await.expect("provisional profile matches");
+    assert_eq!(known.name, "Unconfirmed speaker 2");
+    let profile = store.get_speaker_profile(known.profile_id).await.unwrap().unwrap();
+    assert_eq!(profile.description.as_deref(), Some("Provisional profile from session session-1"));
+}
+
+#[tokio::test]
+async fn test_borderline_voice_respects_each_profiles_threshold() {
+    let db_file = NamedTempFile::new().unwrap();
+    let database = Database::new(db_file.path()).await.unwrap();
+    database.migrate().await.unwrap();
+    let store = SpeakerStore::new(database);
+    let index = EmbeddingIndex::new(SEED_DIMENSION, 8);
+
+    // The user only wants their own voice attributed when the match is close; guests are matched leniently
+    let mut profiles = Vec::new();
+    for (name, threshold, vector) in [
+        ("Me", 0.95, vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+        ("Guest", 0.6, vec![0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+    ] {
+        let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+            name: name.to_string(),
+            description: None,
+            color: None,
+            confidence_threshold: Some(threshold),
+        }).await.unwrap();
+        let embedding = kaginote_lib::models::VoiceEmbedding::new(profile.id, vector, "test_model_v1".to_string(), 0.9, 5.0);
+        store.add_voice_embedding(embedding.clone()).await.unwrap();
+        index.add_embedding(embedding).unwrap();
+        profiles.push(profile);
+    }
+    let global_floor = 0.7;
+    let match_vector = |vector: Vec<f32>| {
+        let store = &store;
+        let index = &index;
+        async move {
+            let candidates = index.find_similar_embeddings(
+                &vector,
+                known_speakers::INDEX_SEARCH_FLOOR,
+                known_speakers::MAX_INDEX_CANDIDATES,
+            ).unwrap();
+            let mut candidate_profiles = Vec::new();
+            for (profile_id, similarity) in candidates {
+                candidate_profiles.push((store.get_speaker_profile(profile_id).await.unwrap().unwrap(), similarity));
+            }
+            known_speakers::match_known_speaker(candidate_profiles, global_floor)
+        }
+    };
+
+    // About 0.85 similar to each profile in turn
+    let borderline_me = vec![1.0, 0.0, 0.62, 0.0, 0.0, 0.0, 0.0, 0.0];
+    let borderline_guest = vec![0.0, 1.0, 0.62, 0.0, 0.0, 0.0, 0.0, 0.0];
+
+    let guest = match match_vector(borderline_guest).await {
+        KnownSpeakerMatch::Known(known) => known,
+        other => panic!("guest was not recognised: {:?}", other),
+    };
+    assert_eq!(guest.profile_id, profiles[1].id);
+
+    let near_miss = match match_vector(borderline_me.clone()).await {
+        KnownSpeakerMatch::NearMiss(near_miss) => near_miss,
+        other => panic!("expected a near miss, got {:?}", other),
+    };
+    assert_eq!(near_miss.profile_id, profiles[0].id);
+    assert!(near_miss.similarity >= global_floor && near_miss.similarity < 0.95);
+    assert_eq!(near_miss.threshold, 0.95);
+
+    // The live loop gives the voice a provisional label and counts the miss
+    let mut unknown = UnknownSpeakers::new();
+    unknown.record("speaker_3", live_embedding(borderline_me.clone()));
+    assert_eq!(unknown.matching_speaker(&live_embedding(borderline_me), global_floor), Some("speaker_3"));
+    let mut counters = AttributionCounters::default();
+    counters.record_near_miss(&near_miss);
+    let stats = compute_diarization_stats("session-2", &[], &counters, None, 0);
+    assert_eq!(stats.near_misses.len(), 1);
+    assert_eq!(stats.near_misses[0].name, "Me");
+    assert_eq!(stats.near_misses[0].count, 1);
+    assert_eq!(stats.near_misses[0].threshold, 0.95);
+}
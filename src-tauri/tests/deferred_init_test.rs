@@ -0,0 +1,67 @@
+//! Deferred initialization of heavy app state
+//!
+//! AppState::new builds only cheap members so the window appears quickly. The
+//! embedding index and the device profile manager are built on first use,
+//! once however many callers race for them, and their startup phases are
+//! recorded then. Commands reaching speaker storage before it initialized get
+//! a clear error instead of waiting.
+
+use kaginote_lib::commands::AppState;
+use std::sync::Arc;
+
+fn phases(state: &AppState) -> Vec<String> {
+    state.startup_report.lock().unwrap().phases.iter().map(|phase| phase.name.clone()).collect()
+}
+
+#[tokio::test]
+async fn test_new_state_builds_no_heavy_members() {
+    let state = AppState::new();
+
+    assert!(state.embedding_index.get().is_none());
+    assert!(state.device_profile_manager.get().is_none());
+    assert_eq!(phases(&state), ["app_state"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_embedding_index_is_built_once_on_first_use() {
+    let state = AppState::new();
+
+    let (first, second) = tokio::join!(state.embedding_index(), state.embedding_index());
+    assert!(Arc::ptr_eq(first, second));
+    assert_eq!(first.lock().await.embedding_dimension(), 512);
+    assert!(state.embedding_index.get().is_some());
+    assert_eq!(phases(&state), ["app_state", "embedding_index"]);
+}
+
+#[tokio::test]
+async fn test_exit_flush_leaves_an_unused_index_unbuilt() {
+    let state = AppState::new();
+    *state.embedding_index_path.lock().await = Some(tempfile::tempdir().unwrap().path().join("index.bin"));
+
+    state.flush_embedding_index().await;
+
+    assert!(state.embedding_index.get().is_none());
+}
+
+#[tokio::test]
+async fn test_device_profiles_load_on_first_use() {
+    let state = AppState::new();
+
+    let manager = state.device_profiles().await.expect("device profiles load");
+    let _stats = manager.lock().await.get_stats();
+    assert!(state.device_profile_manager.get().is_some());
+    assert!(phases(&state).contains(&"device_profiles".to_string()));
+
+    // Later uses get the same manager without loading again
+    state.device_profiles().await.unwrap();
+    assert_eq!(phases(&state).iter().filter(|phase| *phase == "device_profiles").count(), 1);
+}
+
+#[tokio::test]
+async fn test_storage_commands_before_initialization_fail_fast() {
+    let state = AppState::new();
+
+    // Nothing is initializing, so there is nothing to wait for
+    let error = state.speaker_storage_ready().await.unwrap_err();
+    assert_eq!(error, "Speaker storage not initialized");
+}
@@ -0,0 +1,95 @@
+//! Diagnostics bundle of a running app
+//!
+//! The bundle goes to support, so nothing a user said may end up in it: not
+//! through the session settings, not through log lines written before text
+//! was kept out of the log file.
+
+use kaginote_lib::commands::{self, AppState, TranscriptionConfig};
+use kaginote_lib::diagnostics::{DiagnosticsManifest, MANIFEST_FILE_NAME};
+use std::fs::File;
+use std::io::Read;
+
+const SEGMENT_TEXTS: [&str; 2] = ["The merger with Northwind closes on Friday", "Keep the salary numbers out of the deck"];
+
+/// Every file of the zip at `path` by name
+fn read_bundle(path: &std::path::Path) -> Vec<(String, String)> {
+    let mut archive = zip::ZipArchive::new(File::open(path).unwrap()).unwrap();
+    (0..archive.len())
+        .map(|index| {
+            let mut file = archive.by_index(index).unwrap();
+            let mut contents = String::new();
+            file.read_to_string(&mut contents).unwrap();
+            (file.name().to_string(), contents)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_bundle_contains_no_segment_text_while_sessions_are_active() {
+    let state = AppState::new();
+    let config = TranscriptionConfig {
+        custom_vocabulary: Some(vec!["Northwind".to_string()]),
+        ..TranscriptionConfig::default()
+    };
+    commands::reserve_session(&state, "support-case", &config).await.unwrap();
+    {
+        let mut sessions = state.active_sessions.lock().await;
+        let session = sessions.get_mut("support-case").unwrap();
+        for (index, text) in SEGMENT_TEXTS.iter().enumerate() {
+            session.transcription_segments.push(serde_json::json!({
+                "id": format!("segment-{}", index),
+                "text": text,
+                "rawText": text.to_lowercase(),
+                "startTime": index as f64 * 4.0,
+                "endTime": index as f64 * 4.0 + 3.5,
+                "confidence": 0.92
+            }));
+        }
+    }
+
+    // A log file from an older version that still logged transcript text
+    let log_dir = tempfile::tempdir().unwrap();
+    let log_lines: Vec<String> = [
+        ("INFO", "Starting transcription loop for session: support-case".to_string()),
+        ("INFO", format!("Emitting transcription update: '{}'", SEGMENT_TEXTS[0])),
+        ("WARN", "Audio device lost during session support-case".to_string()),
+    ]
+    .into_iter()
+    .map(|(level, message)| serde_json::json!({
+        "timestamp": "2026-10-15T09:00:00.000000Z",
+        "level": level,
+        "message": message,
+        "target": "kaginote_lib::commands"
+    }).to_string())
+    .collect();
+    std::fs::write(log_dir.path().join(kaginote_lib::logging::LOG_FILE_NAME), log_lines.join("\n") + "\n").unwrap();
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let path = out_dir.path().join("kaginote-diagnostics.zip");
+    let manifest = commands::write_diagnostics_bundle(&state, &path, Some(log_dir.path())).await.unwrap();
+
+    let files = read_bundle(&path);
+    assert_eq!(files.len(), manifest.files.len() + 1);
+    for (name, contents) in &files {
+        let contents = contents.to_lowercase();
+        for text in SEGMENT_TEXTS {
+            assert!(!contents.contains(&text.to_lowercase()), "{} contains segment text", name);
+        }
+        assert!(!contents.contains("northwind"), "{} contains the custom vocabulary", name);
+    }
+
+    let (_, manifest_json) = files.iter().find(|(name, _)| name == MANIFEST_FILE_NAME).unwrap();
+    let stored: DiagnosticsManifest = serde_json::from_str(manifest_json).unwrap();
+    assert_eq!(stored.app_version, env!("CARGO_PKG_VERSION"));
+    assert!(stored.redacted_values >= 2);
+
+    // The bundle still says what support needs to know
+    let (_, sessions) = files.iter().find(|(name, _)| name == "active_sessions.json").unwrap();
+    let sessions: serde_json::Value = serde_json::from_str(sessions).unwrap();
+    assert_eq!(sessions[0]["sessionId"], "support-case");
+    assert_eq!(sessions[0]["segmentCount"], 2);
+    let (_, logs) = files.iter().find(|(name, _)| name == "logs.json").unwrap();
+    let logs: serde_json::Value = serde_json::from_str(logs).unwrap();
+    assert_eq!(logs.as_array().unwrap().len(), 3);
+    assert_eq!(logs[2]["message"], "Audio device lost during session support-case");
+}
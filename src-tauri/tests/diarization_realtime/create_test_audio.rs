@@ -2,16 +2,19 @@ use std::path::{Path, PathBuf};
 use std::fs;
 use std::collections::HashMap;
 use hound::{WavWriter, WavSpec, SampleFormat};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde_json;
 
-use super::test_scenarios::{TestScenarioGenerator, GroundTruthData, GroundTruthSegment};
+use super::test_scenarios::{TestScenarioGenerator, GroundTruthData, GroundTruthSegment, DEFAULT_SCENARIO_SEED};
 
 /// Advanced synthetic audio generator for realistic speaker diarization testing
 pub struct TestAudioGenerator {
     pub output_dir: PathBuf,
     pub sample_rate: u32,
     pub noise_level: f32,
+    /// Seed of the voice jitter and background noise, so a scenario always renders the same audio
+    pub seed: u64,
 }
 
 impl TestAudioGenerator {
@@ -26,6 +29,7 @@ impl TestAudioGenerator {
             output_dir,
             sample_rate,
             noise_level: 0.02, // 2% background noise
+            seed: DEFAULT_SCENARIO_SEED,
         }
     }
 
@@ -139,7 +143,7 @@ impl TestAudioGenerator {
         base_frequency: f32,
         confidence: f32,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut rng = rand::thread_rng();
+        let mut rng = StdRng::seed_from_u64(self.seed ^ start_sample as u64);
         let segment_duration = (end_sample - start_sample) as f32 / self.sample_rate as f32;
         
         for i in start_sample..end_sample {
@@ -244,7 +248,7 @@ impl TestAudioGenerator {
 
     /// Add realistic background noise (room tone, HVAC, etc.)
     fn add_realistic_background_noise(&self, audio: &mut [f32]) {
-        let mut rng = rand::thread_rng();
+        let mut rng = StdRng::seed_from_u64(self.seed);
         
         for (i, sample) in audio.iter_mut().enumerate() {
             // White noise component
@@ -6,6 +6,8 @@
 //! capabilities for continuous improvement and benchmarking.
 
 use kaginote_lib::diarization::types::{DiarizationResult, SpeakerSegment, SpeakerEmbedding, ProcessingMetrics};
+use kaginote_lib::quality::{ground_truth, QualityAnalysisError};
+use kaginote_lib::resource_monitor::{self, ResourceSampler, SAMPLE_INTERVAL};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -13,49 +15,8 @@ use std::path::Path;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
-/// Ground truth segment for validation comparison
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GroundTruthSegment {
-    /// True speaker identifier
-    pub speaker_id: String,
-    
-    /// Start time in seconds
-    pub start_time: f32,
-    
-    /// End time in seconds
-    pub end_time: f32,
-    
-    /// Optional text content for reference
-    pub text: Option<String>,
-    
-    /// Audio file path if available
-    pub audio_file: Option<String>,
-    
-    /// Quality score for this ground truth segment (0.0-1.0)
-    pub quality: f32,
-}
-
-/// Complete ground truth data for a recording
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GroundTruthData {
-    /// Recording identifier
-    pub recording_id: String,
-    
-    /// All ground truth segments
-    pub segments: Vec<GroundTruthSegment>,
-    
-    /// Total number of speakers
-    pub total_speakers: usize,
-    
-    /// Recording duration in seconds
-    pub duration: f32,
-    
-    /// Sample rate
-    pub sample_rate: u32,
-    
-    /// Metadata about the recording
-    pub metadata: HashMap<String, String>,
-}
+/// Ground truth segments and recordings, shared with the corpus export commands
+pub use kaginote_lib::quality::ground_truth::{GroundTruthData, GroundTruthSegment};
 
 /// Diarization Error Rate calculation results
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -346,8 +307,11 @@ impl DiarizationValidator {
         // Calculate speaker consistency
         let consistency_result = self.calculate_speaker_consistency(&predicted, &ground_truth)?;
         
-        // Collect performance metrics
-        let performance_metrics = self.performance_monitor.finish_monitoring(start_time);
+        // Collect performance metrics over the audio the segments cover
+        let audio_duration = ground_truth.iter().map(|s| s.end_time)
+            .chain(predicted.iter().map(|s| s.end_time))
+            .fold(0.0f32, f32::max);
+        let performance_metrics = self.performance_monitor.finish_monitoring(start_time, audio_duration);
         
         // Generate summary
         let summary = self.generate_summary(&der_result, &consistency_result, &performance_metrics);
@@ -1059,52 +1023,52 @@ impl MemoryTracker {
         }
     }
     
+    /// Resident memory of the process in bytes
     fn get_current_memory() -> usize {
-        // Simplified memory tracking - in production would use system APIs
-        // For now, return a placeholder value
-        0
+        resource_monitor::current_memory_bytes() as usize
     }
 }
 
 /// Performance monitoring
 struct PerformanceMonitor {
     start_time: Option<SystemTime>,
-    cpu_samples: Vec<f32>,
-    memory_samples: Vec<f32>,
+    sampler: Option<ResourceSampler>,
 }
 
 impl PerformanceMonitor {
     fn new() -> Self {
         Self {
             start_time: None,
-            cpu_samples: Vec::new(),
-            memory_samples: Vec::new(),
+            sampler: None,
         }
     }
     
     fn start_monitoring(&mut self) {
         self.start_time = Some(SystemTime::now());
-        self.cpu_samples.clear();
-        self.memory_samples.clear();
+        self.sampler = Some(ResourceSampler::start(SAMPLE_INTERVAL));
     }
     
-    fn finish_monitoring(&mut self, validation_start: SystemTime) -> PerformanceMetrics {
+    /// Stop sampling; `audio_duration` (seconds) is the audio the run processed
+    fn finish_monitoring(&mut self, validation_start: SystemTime, audio_duration: f32) -> PerformanceMetrics {
+        let resources = self.sampler.take().map(ResourceSampler::finish).unwrap_or_default();
         let end_time = SystemTime::now();
         let start_time = self.start_time.unwrap_or(validation_start);
         
         let total_duration = end_time.duration_since(start_time)
             .unwrap_or_default();
+        let processing_seconds = total_duration.as_secs_f32();
         
-        // Simplified metrics - in production would collect real system data
         PerformanceMetrics {
-            real_time_factor: 0.8, // Placeholder
-            peak_memory_mb: 150.0,
-            average_memory_mb: 120.0,
-            cpu_utilization: 25.0,
-            latency_ms: 1500,
-            throughput: 1.25,
-            memory_allocations: 1000,
-            time_to_first_output_ms: 500,
+            real_time_factor: if audio_duration > 0.0 { processing_seconds / audio_duration } else { 0.0 },
+            peak_memory_mb: resources.peak_memory_mb,
+            average_memory_mb: resources.average_memory_mb,
+            cpu_utilization: resources.average_cpu_percent,
+            latency_ms: total_duration.as_millis() as u64,
+            throughput: if processing_seconds > 0.0 { audio_duration / processing_seconds } else { 0.0 },
+            // Allocations are not counted
+            memory_allocations: 0,
+            // Results are produced in one pass
+            time_to_first_output_ms: total_duration.as_millis() as u64,
             start_time,
             end_time,
         }
@@ -1113,28 +1077,17 @@ impl PerformanceMonitor {
 
 /// Load ground truth data from JSON file
 pub fn load_ground_truth(path: &str) -> Result<GroundTruthData, ValidationError> {
-    let content = fs::read_to_string(path)
-        .map_err(|_| ValidationError::GroundTruthNotFound { 
-            path: path.to_string() 
-        })?;
-    
-    serde_json::from_str(&content)
-        .map_err(|e| ValidationError::InvalidGroundTruth { 
-            message: e.to_string() 
-        })
+    ground_truth::load_ground_truth(path).map_err(|e| match e {
+        QualityAnalysisError::GroundTruthNotFound { path } => ValidationError::GroundTruthNotFound { path },
+        QualityAnalysisError::InvalidGroundTruth { message } => ValidationError::InvalidGroundTruth { message },
+        other => ValidationError::IoError { message: other.to_string() },
+    })
 }
 
 /// Save ground truth data to JSON file
 pub fn save_ground_truth(data: &GroundTruthData, path: &str) -> Result<(), ValidationError> {
-    let content = serde_json::to_string_pretty(data)
-        .map_err(|e| ValidationError::IoError { 
-            message: format!("JSON serialization failed: {}", e) 
-        })?;
-    
-    fs::write(path, content)
-        .map_err(|e| ValidationError::IoError { 
-            message: format!("Failed to write file: {}", e) 
-        })
+    ground_truth::save_ground_truth(data, path)
+        .map_err(|e| ValidationError::IoError { message: e.to_string() })
 }
 
 #[cfg(test)]
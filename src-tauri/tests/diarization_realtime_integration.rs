@@ -1,4 +1,13 @@
 use std::path::PathBuf;
+use std::time::Duration;
+
+use kaginote_lib::audio::capture::{AudioCaptureService, AudioConfig};
+use kaginote_lib::audio::capture_simulator::{SimulatedDropout, SimulatorConfig};
+use kaginote_lib::diarization::clustering::SpeakerClusterer;
+use kaginote_lib::diarization::refinement;
+use kaginote_lib::diarization::SpeakerCountEstimator;
+use kaginote_lib::diarization::types::{DiarizationConfig, SpeakerEmbedding};
+use std::collections::HashMap;
 
 mod diarization_realtime;
 use diarization_realtime::{DiarizationTestRunner, TestScenarioGenerator};
@@ -91,12 +100,20 @@ fn test_audio_noise_and_filtering() {
     
     let original_max = audio_data.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
     
-    // Add background noise
-    SyntheticAudioGenerator::add_background_noise(&mut audio_data, 0.05);
+    // Add background noise; the seed makes it reproducible
+    let clean = audio_data.clone();
+    SyntheticAudioGenerator::add_background_noise(&mut audio_data, 0.05, DEFAULT_SCENARIO_SEED);
     
     let noise_max = audio_data.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
     assert!(noise_max >= original_max, "Noise should increase amplitude");
     
+    let mut again = clean.clone();
+    SyntheticAudioGenerator::add_background_noise(&mut again, 0.05, DEFAULT_SCENARIO_SEED);
+    assert_eq!(again, audio_data, "The same seed should give the same noise");
+    let mut reseeded = clean;
+    SyntheticAudioGenerator::add_background_noise(&mut reseeded, 0.05, DEFAULT_SCENARIO_SEED + 1);
+    assert_ne!(reseeded, audio_data);
+    
     // Apply voice filter
     SyntheticAudioGenerator::apply_voice_filter(&mut audio_data);
     
@@ -333,6 +350,151 @@ fn test_complete_workflow_integration() {
     }
 }
 
+/// Replays a generated scenario through the production capture service, as a
+/// session's loop reads it, with the file simulator standing in for the device
+#[tokio::test]
+async fn test_capture_service_replays_scenario_through_simulator() {
+    let runner = DiarizationTestRunner::new();
+    let ground_truth = TestScenarioGenerator::simple_two_speaker_conversation();
+    // A 48 kHz file, as most devices deliver, exercises the capture's resampling
+    let audio_path = runner.generate_test_audio(&ground_truth, 48000).expect("scenario audio should be generated");
+    let config = AudioConfig {
+        simulator: Some(SimulatorConfig {
+            path: audio_path,
+            speed: 0.0,
+            chunk_ms: 100,
+            dropouts: vec![SimulatedDropout { start_ms: 28_000, duration_ms: 1_000 }],
+        }),
+        ..AudioConfig::default()
+    };
+    let mut capture = AudioCaptureService::new(config).await.expect("simulated capture should open");
+    assert!(capture.is_simulated());
+    capture.start_capture().await.unwrap();
+
+    // Level of each chunk by stream offset, until the replay runs dry
+    let mut offset = 0.0f32;
+    let mut levels = Vec::new();
+    while let Ok(chunk) = tokio::time::timeout(Duration::from_millis(500), capture.get_next_chunk()).await {
+        let chunk = chunk.expect("simulated chunks should not fail");
+        assert_eq!((chunk.sample_rate, chunk.channels), (16000, 1));
+        let rms = (chunk.samples.iter().map(|s| s * s).sum::<f32>() / chunk.samples.len().max(1) as f32).sqrt();
+        levels.push((offset, rms));
+        offset += chunk.duration_seconds;
+    }
+    capture.stop_capture().await.unwrap();
+
+    // Everything but the dropout arrived; the dropout is reported like channel overflow
+    assert!((offset - (ground_truth.duration - 1.0)).abs() < 0.2, "replayed {:.2}s", offset);
+    assert_eq!(capture.channel_health().dropped_ms, 1000);
+
+    // Speech where the ground truth has a speaker, silence in the turn gaps
+    let level_at = |t: f32| levels.iter().find(|(start, _)| *start >= t).map(|(_, rms)| *rms).unwrap();
+    for segment in ground_truth.segments.iter().filter(|segment| segment.end_time < 28.0) {
+        let middle = (segment.start_time + segment.end_time) / 2.0;
+        assert!(level_at(middle) > 0.01, "no speech at {:.1}s", middle);
+    }
+    for gap_start in [5.0, 10.0, 15.0, 20.0] {
+        assert!(level_at(gap_start + 0.2) < 0.01, "speech in the gap at {:.1}s", gap_start);
+    }
+}
+
+fn deterministic_config(seed: u64) -> DiarizationConfig {
+    DiarizationConfig {
+        max_speakers: 4,
+        min_speakers: 1,
+        deterministic_seed: Some(seed),
+        ..Default::default()
+    }
+}
+
+/// Cluster a scenario's synthetic embeddings, generated from the config's seed
+async fn cluster_scenario(ground_truth: &GroundTruthData, config: DiarizationConfig) -> HashMap<String, Vec<SpeakerEmbedding>> {
+    let embeddings = SyntheticEmbeddingGenerator::scenario_embeddings(ground_truth, config.deterministic_seed.unwrap());
+    let mut clusterer = SpeakerClusterer::new(config).await.unwrap();
+    clusterer.cluster_embeddings(&embeddings).await.unwrap()
+}
+
+/// What deterministic diarization must report for a scenario: every window
+/// of a segment under the number its speaker gets by first appearance
+fn expected_detections(ground_truth: &GroundTruthData) -> Vec<(String, f32, f32)> {
+    let by_truth: HashMap<String, String> = ScenarioValidator::deterministic_speaker_mapping(ground_truth)
+        .into_iter()
+        .map(|(detected, truth)| (truth, detected))
+        .collect();
+    let mut expected: Vec<(String, f32, f32)> = ground_truth.segments.iter()
+        .flat_map(|segment| SyntheticEmbeddingGenerator::segment_windows(segment).into_iter()
+            .map(|(start, end)| (by_truth[&segment.speaker_id].clone(), start, end)))
+        .collect();
+    expected.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    expected
+}
+
+/// Scenarios without overlapping speech, where every window belongs to one speaker
+fn exact_match_scenarios() -> Vec<(&'static str, GroundTruthData)> {
+    vec![
+        ("simple_turn_taking", TestScenarioGenerator::simple_two_speaker_conversation()),
+        ("multi_speaker_meeting", TestScenarioGenerator::multi_speaker_meeting()),
+        ("rapid_switching", TestScenarioGenerator::rapid_speaker_switching()),
+        ("long_silences", TestScenarioGenerator::long_silences_scenario()),
+        ("single_speaker", TestScenarioGenerator::single_speaker_monologue()),
+    ]
+}
+
+#[tokio::test]
+async fn test_deterministic_diarization_matches_scenarios_exactly() {
+    for (name, ground_truth) in exact_match_scenarios() {
+        let clusters = cluster_scenario(&ground_truth, deterministic_config(DEFAULT_SCENARIO_SEED)).await;
+        let detected = DetectedSegment::from_clusters(&clusters);
+
+        let actual: Vec<(String, f32, f32)> = detected.iter()
+            .map(|segment| (segment.speaker_id.clone(), segment.start_time, segment.end_time))
+            .collect();
+        assert_eq!(actual, expected_detections(&ground_truth), "scenario {}", name);
+
+        let mapping = ScenarioValidator::deterministic_speaker_mapping(&ground_truth);
+        let exact = ScenarioValidator::validate_exact_mapping(&detected, &ground_truth, &mapping, 0.25);
+        assert_eq!(exact.correct_detections, exact.total_detections, "scenario {}", name);
+        assert_eq!(clusters.len(), ground_truth.total_speakers, "scenario {}", name);
+    }
+}
+
+#[tokio::test]
+async fn test_simple_conversation_diarizes_to_fixed_speakers() {
+    let ground_truth = TestScenarioGenerator::simple_two_speaker_conversation();
+    let clusters = cluster_scenario(&ground_truth, deterministic_config(DEFAULT_SCENARIO_SEED)).await;
+
+    let mut sizes: Vec<(&str, usize)> = clusters.iter().map(|(id, embeddings)| (id.as_str(), embeddings.len())).collect();
+    sizes.sort();
+    assert_eq!(sizes, [("speaker_1", 13), ("speaker_2", 12)]);
+    assert_eq!(clusters["speaker_1"][0].timestamp_start, 0.0);
+    assert_eq!(clusters["speaker_2"][0].timestamp_start, 5.5);
+}
+
+#[tokio::test]
+async fn test_same_seed_gives_identical_diarization() {
+    let serialized = |clusters: HashMap<String, Vec<SpeakerEmbedding>>| {
+        let mut clusters: Vec<_> = clusters.into_iter().collect();
+        clusters.sort_by(|a, b| a.0.cmp(&b.0));
+        serde_json::to_string(&clusters).unwrap()
+    };
+
+    for (name, ground_truth) in exact_match_scenarios() {
+        let first = cluster_scenario(&ground_truth, deterministic_config(DEFAULT_SCENARIO_SEED)).await;
+        let second = cluster_scenario(&ground_truth, deterministic_config(DEFAULT_SCENARIO_SEED)).await;
+        assert_eq!(serialized(first.clone()), serialized(second), "scenario {}", name);
+
+        // Another seed jitters and orders the embeddings differently but numbers the speakers the same
+        let reseeded = cluster_scenario(&ground_truth, deterministic_config(DEFAULT_SCENARIO_SEED + 1)).await;
+        assert_ne!(serialized(first.clone()), serialized(reseeded.clone()), "scenario {}", name);
+        let windows = |clusters: &HashMap<String, Vec<SpeakerEmbedding>>| {
+            DetectedSegment::from_clusters(clusters).into_iter()
+                .map(|segment| (segment.speaker_id, segment.start_time))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(windows(&first), windows(&reseeded), "scenario {}", name);
+    }
+}
+
 /// Performance benchmark test for the testing infrastructure itself
 #[test]
 fn test_performance_benchmarks() {
@@ -373,4 +535,58 @@ fn test_performance_benchmarks() {
     println!("   Validation: {:?}", validation_time);
     
     println!("✅ All performance benchmarks passed");
-}
\ No newline at end of file
+}
+
+/// A scenario's embeddings in the order they are heard, labelled with their
+/// ground truth speaker the way live diarization labels them
+fn labelled_scenario_embeddings(ground_truth: &GroundTruthData) -> Vec<SpeakerEmbedding> {
+    let speaker_of: HashMap<(u32, u32), &str> = ground_truth.segments.iter()
+        .flat_map(|segment| SyntheticEmbeddingGenerator::segment_windows(segment).into_iter()
+            .map(|(start, end)| ((start.to_bits(), end.to_bits()), segment.speaker_id.as_str())))
+        .collect();
+    let mut embeddings = SyntheticEmbeddingGenerator::scenario_embeddings(ground_truth, DEFAULT_SCENARIO_SEED);
+    embeddings.sort_by(|a, b| a.timestamp_start.total_cmp(&b.timestamp_start).then_with(|| a.timestamp_end.total_cmp(&b.timestamp_end)));
+    for embedding in &mut embeddings {
+        let key = (embedding.timestamp_start.to_bits(), embedding.timestamp_end.to_bits());
+        embedding.speaker_id = Some(speaker_of[&key].to_string());
+    }
+    embeddings
+}
+
+#[tokio::test]
+async fn test_speaker_estimate_converges_on_multi_speaker_meeting() {
+    const MIN_CLUSTER_SIZE: usize = 3;
+    let ground_truth = TestScenarioGenerator::multi_speaker_meeting();
+    let config = deterministic_config(DEFAULT_SCENARIO_SEED);
+    let embeddings = labelled_scenario_embeddings(&ground_truth);
+    let mut estimator = SpeakerCountEstimator::new(MIN_CLUSTER_SIZE, 0.6, config.max_speakers);
+
+    // Re-cluster everything heard so far every five seconds of the meeting
+    for heard_until in (5..=ground_truth.duration as usize).step_by(5) {
+        let heard: Vec<SpeakerEmbedding> = embeddings.iter()
+            .filter(|embedding| embedding.timestamp_end <= heard_until as f32 + 1e-3)
+            .cloned()
+            .collect();
+        let mut clusterer = SpeakerClusterer::new(config.clone()).await.unwrap();
+        let clusters = clusterer.cluster_embeddings(&heard).await.unwrap();
+        estimator.update(&clusters);
+
+        // Speakers heard long enough to form a cluster
+        let mut windows: HashMap<&str, usize> = HashMap::new();
+        for embedding in &heard {
+            *windows.entry(embedding.speaker_id.as_deref().unwrap()).or_default() += 1;
+        }
+        let expected = windows.values().filter(|count| **count >= MIN_CLUSTER_SIZE).count();
+        let estimate = estimator.current().unwrap();
+        assert_eq!(estimate.count, expected, "after {}s", heard_until);
+        assert!(estimate.count <= config.max_speakers as usize);
+    }
+    assert_eq!(estimator.current().unwrap().count, ground_truth.total_speakers);
+
+    // The final count comes from the speakers the session-end refinement settles on
+    let refined = refinement::refine_speakers(&embeddings, &config).await.unwrap();
+    let final_estimate = SpeakerCountEstimator::new(MIN_CLUSTER_SIZE, 0.6, config.max_speakers).estimate(&refined.clusters);
+    assert_eq!(refined.speaker_count, ground_truth.total_speakers);
+    assert_eq!(final_estimate.count, refined.speaker_count);
+    assert!(final_estimate.confidence > 0.5);
+}
@@ -0,0 +1,166 @@
+//! File transcription alongside a live session
+//!
+//! A mock live session decodes short chunks while a file transcription decodes
+//! one long buffer, both through leases on the one pooled engine, the way the
+//! transcription loop calls its `SharedTranscriber`. The file decode blocks
+//! its thread the way whisper.cpp does and only returns once every live chunk
+//! was decoded, so the test deadlocks instead of passing if a lease ever has
+//! to wait for another. Per-decode state (the prompt budget) must come back
+//! with each result; per-engine counters sum over every lease.
+
+use futures_util::future::BoxFuture;
+use kaginote_lib::asr::engine_pool::EnginePool;
+use kaginote_lib::asr::prompt_budget::{self, PromptInputs};
+use kaginote_lib::asr::transcriber::{SharedTranscriber, Transcriber};
+use kaginote_lib::asr::types::{ASRError, ASRResult, Device, ModelTier, TranscriptionContext};
+use kaginote_lib::audio::types::{AudioData, AudioSource};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Barrier, Mutex};
+use std::time::{Duration, SystemTime};
+
+const LIVE_CHUNKS: usize = 5;
+/// Longer than this is the file transcription
+const FILE_SECONDS: f32 = 60.0;
+
+/// Engine whose long decodes rendezvous with the test twice: once when they
+/// start and once before they return
+struct GatedEngine {
+    decodes: AtomicU32,
+    file_started: Barrier,
+    live_done: Barrier,
+}
+
+impl GatedEngine {
+    fn new() -> Self {
+        Self { decodes: AtomicU32::new(0), file_started: Barrier::new(2), live_done: Barrier::new(2) }
+    }
+}
+
+fn budget_for(context: &TranscriptionContext) -> prompt_budget::PromptBudget {
+    let inputs = PromptInputs { rolling_context: context.previous_segments.clone(), ..PromptInputs::default() };
+    prompt_budget::build_prompt(&inputs, prompt_budget::DEFAULT_TEXT_CONTEXT, prompt_budget::estimate_tokens).1
+}
+
+impl Transcriber for GatedEngine {
+    fn transcribe<'a>(
+        &'a self,
+        audio: &'a AudioData,
+        context: &'a TranscriptionContext,
+    ) -> BoxFuture<'a, Result<ASRResult, ASRError>> {
+        Box::pin(async move {
+            let budget = budget_for(context);
+            if audio.duration_seconds >= FILE_SECONDS {
+                // Blocks like a whisper.cpp decode, holding this worker thread
+                self.file_started.wait();
+                self.live_done.wait();
+            }
+            self.decodes.fetch_add(1, Ordering::SeqCst);
+            Ok(ASRResult {
+                text: context.previous_segments.join(" "),
+                confidence: 0.9,
+                language: "en".to_string(),
+                language_confidence: 1.0,
+                words: Vec::new(),
+                word_timestamps: false,
+                estimated_snr: None,
+                speaker_consistency_score: None,
+                language_segments: None,
+                decode_temperature: 0.0,
+                decode_attempts: 1,
+                decode_failed: false,
+                decode_budget_exhausted: false,
+                prompt_budget: Some(budget),
+                device: Some(Device::CPU),
+            })
+        })
+    }
+
+    fn model_tier(&self) -> Option<ModelTier> {
+        None
+    }
+
+    fn device(&self) -> Device {
+        Device::CPU
+    }
+}
+
+fn audio(seconds: f32) -> AudioData {
+    AudioData {
+        samples: vec![0.0; (16000.0 * seconds) as usize],
+        sample_rate: 16000,
+        channels: 1,
+        timestamp: SystemTime::now(),
+        source_channel: AudioSource::Microphone,
+        duration_seconds: seconds,
+    }
+}
+
+fn context(text: &str) -> TranscriptionContext {
+    TranscriptionContext { previous_segments: vec![text.to_string()], ..TranscriptionContext::default() }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_file_transcription_does_not_block_live_session() {
+    let pool = Arc::new(Mutex::new(EnginePool::<GatedEngine>::new()));
+    let live_lease = pool.lock().unwrap().register(ModelTier::Standard, GatedEngine::new());
+    let engine = live_lease.clone();
+    let live: SharedTranscriber = Arc::new(live_lease);
+
+    // File transcription on the same tier, leased while the session runs
+    let file_pool = pool.clone();
+    let file = tokio::task::spawn(async move {
+        let lease = file_pool.lock().unwrap().lease(ModelTier::Standard).expect("live engine is pooled");
+        let file: SharedTranscriber = Arc::new(lease);
+        let long_context = context(&"an earlier file segment ".repeat(20));
+        let result = file.transcribe(&audio(FILE_SECONDS), &long_context).await.unwrap();
+        (result, budget_for(&long_context))
+    });
+
+    // Every live chunk is decoded while the file decode is in flight
+    let live_engine = engine.clone();
+    let live_results = tokio::time::timeout(Duration::from_secs(30), async move {
+        tokio::task::block_in_place(|| live_engine.file_started.wait());
+        let mut results = Vec::new();
+        for chunk in 0..LIVE_CHUNKS {
+            let chunk_context = context(&format!("live chunk {}", chunk));
+            let result = live.transcribe(&audio(0.1), &chunk_context).await.unwrap();
+            results.push((result, budget_for(&chunk_context)));
+        }
+        assert_eq!(live_engine.decodes.load(Ordering::SeqCst) as usize, LIVE_CHUNKS, "file decode still in flight");
+        tokio::task::block_in_place(|| live_engine.live_done.wait());
+        results
+    }).await.expect("a live chunk waited for the file transcription");
+    let (file_result, file_budget) = file.await.unwrap();
+
+    // Each result carries the budget of its own prompt, not the last one the engine built
+    for (result, budget) in &live_results {
+        assert_eq!(result.prompt_budget.as_ref(), Some(budget));
+    }
+    assert_eq!(file_result.prompt_budget, Some(file_budget));
+    assert_ne!(live_results[0].0.prompt_budget, file_result.prompt_budget);
+
+    // Engine-wide counters see both consumers
+    assert_eq!(engine.decodes.load(Ordering::SeqCst) as usize, LIVE_CHUNKS + 1);
+
+    // Both consumers used the one pooled engine, and it is freed with them
+    drop(engine);
+    assert!(pool.lock().unwrap().lease(ModelTier::Standard).is_none());
+}
+
+#[tokio::test]
+async fn test_engine_outlives_the_session_while_a_file_holds_it() {
+    let pool = Arc::new(Mutex::new(EnginePool::<GatedEngine>::new()));
+    let session_lease = pool.lock().unwrap().register(ModelTier::Turbo, GatedEngine::new());
+    let file_lease = pool.lock().unwrap().lease(ModelTier::Turbo).unwrap();
+
+    // stop_transcription drops the session's lease
+    drop(session_lease);
+    assert_eq!(pool.lock().unwrap().lease_count(ModelTier::Turbo), 1);
+
+    let result = file_lease.transcribe(&audio(0.1), &context("tail")).await.unwrap();
+    assert_eq!(result.text, "tail");
+    assert_eq!(file_lease.model_tier(), Some(ModelTier::Turbo));
+    assert_eq!(file_lease.decodes.load(Ordering::SeqCst), 1);
+    drop(file_lease);
+    assert!(pool.lock().unwrap().loaded_tiers().is_empty());
+}
@@ -28,7 +28,8 @@ async fn test_persistent_model_caching() {
                 let percent = (downloaded as f64 / total as f64 * 100.0) as u32;
                 println!("📥 Progress: {}% ({}/{} bytes)", percent, downloaded, total);
             }
-        }))
+        })),
+        None
     ).await.expect("Failed to ensure model availability");
     
     let duration = start_time.elapsed();
@@ -68,7 +69,8 @@ async fn test_persistent_model_caching() {
         ModelTier::Standard,
         Some(Box::new(|downloaded, total| {
             println!("📋 Cache hit: {}/{} (should be immediate)", downloaded, total);
-        }))
+        })),
+        None
     ).await.expect("Failed to get cached model");
     
     let cache_duration = cache_start.elapsed();
@@ -88,7 +90,7 @@ async fn test_cache_metadata_persistence() {
     // Create initial model manager and ensure model is available
     {
         let mut model_manager = ModelManager::new().expect("Failed to create ModelManager");
-        let _ = model_manager.ensure_model_available(ModelTier::Standard, None).await
+        let _ = model_manager.ensure_model_available(ModelTier::Standard, None, None).await
             .expect("Failed to ensure model availability");
         
         // Verify cache metadata exists
@@ -144,7 +146,7 @@ async fn test_cache_validation_and_cleanup() {
     let mut model_manager = ModelManager::new().expect("Failed to create ModelManager");
     
     // Ensure we have a model to work with
-    let _ = model_manager.ensure_model_available(ModelTier::Standard, None).await
+    let _ = model_manager.ensure_model_available(ModelTier::Standard, None, None).await
         .expect("Failed to ensure model availability");
     
     // Test cache validation
@@ -90,7 +90,7 @@ async fn test_real_whisper_diarization_integration() -> Result<()> {
     let whisper_config = WhisperConfig {
         model_tier: ModelTier::Standard, // Medium model for balance
         device: Device::Auto, // Metal on macOS if available
-        language: Some("en".to_string()),
+        language: Some("en".parse().unwrap()),
         task: Task::Transcribe,
         enable_word_timestamps: true,
         temperature: 0.0, // Deterministic for testing
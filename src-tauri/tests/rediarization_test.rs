@@ -0,0 +1,75 @@
+//! Re-diarization of a saved session
+//!
+//! A synthetic meeting is diarized with a similarity threshold so permissive
+//! that everyone collapses into one speaker, then re-diarized with a sensible
+//! one. Both passes go through `DiarizationService` on seeded synthetic
+//! embeddings, so no embedding model is needed.
+
+use kaginote_lib::diarization::reattribution::reattribute_segments;
+use kaginote_lib::diarization::service::DiarizationService;
+use kaginote_lib::diarization::types::DiarizationConfig;
+
+mod diarization_realtime;
+use diarization_realtime::test_scenarios::*;
+
+fn config(similarity_threshold: f32) -> DiarizationConfig {
+    DiarizationConfig {
+        max_speakers: 4,
+        min_speakers: 1,
+        similarity_threshold,
+        deterministic_seed: Some(DEFAULT_SCENARIO_SEED),
+        ..Default::default()
+    }
+}
+
+/// Share of segments attributed to their ground truth speaker
+fn accuracy(segments: &[serde_json::Value], ground_truth: &GroundTruthData) -> f32 {
+    let mapping = ScenarioValidator::deterministic_speaker_mapping(ground_truth);
+    let correct = segments.iter().zip(&ground_truth.segments)
+        .filter(|(segment, truth)| {
+            segment["speaker"].as_str().and_then(|speaker| mapping.get(speaker)) == Some(&truth.speaker_id)
+        })
+        .count();
+    correct as f32 / ground_truth.segments.len() as f32
+}
+
+/// Text and timings of each segment, serialized
+fn transcript_bytes(segments: &[serde_json::Value]) -> Vec<String> {
+    segments.iter()
+        .map(|segment| serde_json::to_string(&(&segment["text"], &segment["startTime"], &segment["endTime"])).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_rediarization_improves_attribution_and_preserves_text() {
+    let ground_truth = TestScenarioGenerator::multi_speaker_meeting();
+    let embeddings = SyntheticEmbeddingGenerator::scenario_embeddings(&ground_truth, DEFAULT_SCENARIO_SEED);
+    let mut segments: Vec<serde_json::Value> = ground_truth.segments.iter()
+        .map(|segment| serde_json::json!({
+            "text": segment.text,
+            "startTime": segment.start_time,
+            "endTime": segment.end_time,
+            "confidence": segment.confidence,
+        }))
+        .collect();
+
+    // Live diarization with a threshold that merges every speaker
+    let bad = DiarizationService::new(config(0.05)).await.unwrap();
+    let live = bad.diarize_embeddings(&embeddings).await.unwrap();
+    assert_eq!(live.total_speakers, 1);
+    reattribute_segments(&mut segments, &live.segments, 1);
+    let before = accuracy(&segments, &ground_truth);
+    let saved = transcript_bytes(&segments);
+
+    let good = DiarizationService::new(config(0.7)).await.unwrap();
+    let rediarized = good.diarize_embeddings(&embeddings).await.unwrap();
+    assert_eq!(rediarized.total_speakers, ground_truth.total_speakers);
+    let summary = reattribute_segments(&mut segments, &rediarized.segments, 2);
+
+    let after = accuracy(&segments, &ground_truth);
+    assert!(after > before, "accuracy {} -> {}", before, after);
+    assert_eq!(after, 1.0);
+    assert_eq!(summary.unmatched_segments, 0);
+    assert!(summary.changed_segments > 0);
+    assert_eq!(transcript_bytes(&segments), saved);
+}
@@ -0,0 +1,118 @@
+//! Transcription path without a Whisper model
+//!
+//! A session's final passes go through whatever `session_transcriber` hands
+//! out. These tests put a scripted transcriber on a session and feed it the
+//! buffers of a simulated capture, the way the transcription loop does. The
+//! loop itself emits through a live `AppHandle`, so the capture, windowing
+//! and transcriber are driven here step by step.
+
+use kaginote_lib::asr::transcriber::{ScriptedTranscriber, SharedTranscriber};
+use kaginote_lib::asr::types::{ASRError, ModelTier, TranscriptionContext};
+use kaginote_lib::audio::capture::AudioCaptureService;
+use kaginote_lib::audio::capture_simulator::{SimulatedDropout, SimulatorConfig};
+use kaginote_lib::audio::types::{AudioConfig, AudioData};
+use kaginote_lib::commands::{self, AppState, TranscriptionConfig};
+use kaginote_lib::transcription::chunk_window::ChunkWindow;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+const RATE: u32 = 16000;
+
+/// `seconds` of a 220 Hz tone at 16 kHz mono
+fn write_tone(path: &Path, seconds: f32) {
+    let spec = hound::WavSpec { channels: 1, sample_rate: RATE, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for i in 0..(RATE as f32 * seconds) as usize {
+        let sample = (2.0 * std::f32::consts::PI * 220.0 * i as f32 / RATE as f32).sin() * 0.3;
+        writer.write_sample((sample * i16::MAX as f32) as i16).unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+/// Replay `path` and transcribe every full window with the session's transcriber
+async fn transcribe_replay(state: &AppState, session_id: &str, path: &Path, window: ChunkWindow, dropouts: Vec<SimulatedDropout>) -> Vec<Result<String, ASRError>> {
+    let config = AudioConfig {
+        simulator: Some(SimulatorConfig { path: path.to_path_buf(), speed: 0.0, chunk_ms: 100, dropouts }),
+        ..AudioConfig::default()
+    };
+    let mut capture = AudioCaptureService::new(config).await.expect("simulated capture should open");
+    capture.start_capture().await.unwrap();
+
+    let mut buffer = Vec::new();
+    let mut outcomes = Vec::new();
+    while let Ok(chunk) = tokio::time::timeout(Duration::from_millis(200), capture.get_next_chunk()).await {
+        buffer.extend(chunk.unwrap().samples);
+        if buffer.len() >= window.max_buffer_samples() {
+            let audio = AudioData {
+                duration_seconds: buffer.len() as f32 / RATE as f32,
+                samples: buffer.clone(),
+                sample_rate: RATE,
+                channels: 1,
+                timestamp: SystemTime::now(),
+                source_channel: kaginote_lib::audio::types::AudioSource::Microphone,
+            };
+            let transcriber = commands::session_transcriber(state, session_id).await.expect("the session has a transcriber");
+            outcomes.push(transcriber.transcribe(&audio, &TranscriptionContext::default()).await.map(|result| result.text));
+            window.flush(&mut buffer, RATE, true);
+        }
+    }
+    capture.stop_capture().await.unwrap();
+    outcomes
+}
+
+#[tokio::test]
+async fn test_simulated_session_transcribes_through_scripted_transcriber() {
+    let state = AppState::new();
+    commands::reserve_session(&state, "scripted", &TranscriptionConfig::default()).await.unwrap();
+    // No engine is loaded yet, as while the model downloads
+    assert!(commands::session_transcriber(&state, "scripted").await.is_none());
+
+    let scripted = Arc::new(ScriptedTranscriber::new()
+        .then_text("first window")
+        .then_fail("decoder ran out of memory")
+        .then_text("third window")
+        .with_tier(Some(ModelTier::Turbo)));
+    assert!(commands::set_session_transcriber(&state, "scripted", scripted.clone() as SharedTranscriber).await);
+    assert!(!commands::set_session_transcriber(&state, "missing", scripted.clone() as SharedTranscriber).await);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tone.wav");
+    write_tone(&path, 4.0);
+    let window = ChunkWindow { min_ms: 500, max_ms: 1000, overlap_ms: 0 };
+    // The dropout loses 0.5 s, so 3.5 s arrive: three full windows
+    let outcomes = transcribe_replay(&state, "scripted", &path, window, vec![SimulatedDropout { start_ms: 1000, duration_ms: 500 }]).await;
+
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[0].as_deref().unwrap(), "first window");
+    assert!(matches!(&outcomes[1], Err(ASRError::TranscriptionFailed { message }) if message == "decoder ran out of memory"));
+    assert_eq!(outcomes[2].as_deref().unwrap(), "third window");
+    assert_eq!(scripted.calls(), 3);
+    assert!(scripted.transcribed_seconds().iter().all(|seconds| (seconds - 1.0).abs() < 0.01));
+
+    let transcriber = commands::session_transcriber(&state, "scripted").await.unwrap();
+    assert_eq!(transcriber.model_tier(), Some(ModelTier::Turbo));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_transcriber_latency_and_injected_failures_reach_the_session() {
+    let state = AppState::new();
+    commands::reserve_session(&state, "flaky", &TranscriptionConfig::default()).await.unwrap();
+    let scripted = Arc::new(ScriptedTranscriber::new()
+        .with_latency(Duration::from_millis(1500))
+        .fail_every(2));
+    commands::set_session_transcriber(&state, "flaky", scripted.clone() as SharedTranscriber).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tone.wav");
+    write_tone(&path, 4.0);
+    let started = tokio::time::Instant::now();
+    let window = ChunkWindow { min_ms: 500, max_ms: 2000, overlap_ms: 0 };
+    let outcomes = transcribe_replay(&state, "flaky", &path, window, Vec::new()).await;
+
+    // Two 2 s windows, each waiting out the latency; the second call fails
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes[0].as_ref().is_ok_and(|text| text.is_empty()), "an exhausted script transcribes silence");
+    assert!(matches!(&outcomes[1], Err(ASRError::TranscriptionFailed { .. })));
+    assert!(started.elapsed() >= Duration::from_millis(3000));
+}
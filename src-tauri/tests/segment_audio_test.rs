@@ -0,0 +1,93 @@
+//! Playing back single segments from the session recording
+//!
+//! Streams the test generator's synthetic two-speaker conversation in 100 ms
+//! chunks through a SessionClock the way the transcription loop does. The
+//! recording is only started 5.2 s in and stops early, as when it reaches its
+//! limit. Segments are stamped with their sample offsets and cut back out of
+//! the WAV, which must give the generated audio of that stretch.
+
+use kaginote_lib::audio::recording_writer::{SampleSink, WavFileSink};
+use kaginote_lib::audio::segment_audio::{self, RecordingTimeline};
+use kaginote_lib::transcription::session_clock::SessionClock;
+
+#[allow(dead_code)]
+#[path = "diarization_realtime/test_scenarios.rs"]
+mod test_scenarios;
+use test_scenarios::{SyntheticAudioGenerator, TestScenarioGenerator};
+
+const SAMPLE_RATE: u32 = 16000;
+const CHUNK_SAMPLES: usize = 1600;
+const RECORDING_START_CHUNK: usize = 52;
+const RECORDING_STOP_CHUNK: usize = 270;
+/// The recording holds 16-bit samples
+const TOLERANCE: f32 = 1.0 / 8192.0;
+
+#[test]
+fn test_segment_audio_round_trips_through_the_recording() {
+    let conversation = TestScenarioGenerator::simple_two_speaker_conversation();
+    let audio = SyntheticAudioGenerator::generate_multi_frequency_audio(&conversation, SAMPLE_RATE);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audio.wav");
+
+    let clock = SessionClock::new();
+    let mut sink = None;
+    let mut timeline = None;
+    for (index, chunk) in audio.chunks(CHUNK_SAMPLES).enumerate() {
+        if index == RECORDING_START_CHUNK {
+            sink = Some(Box::new(WavFileSink::create(&path, SAMPLE_RATE).unwrap()));
+            timeline = Some(RecordingTimeline::new(clock.consumed_seconds(), SAMPLE_RATE));
+        }
+        if index == RECORDING_STOP_CHUNK {
+            sink.take().unwrap().finalize().unwrap();
+        }
+        if let Some(sink) = sink.as_mut() {
+            sink.write_samples(chunk).unwrap();
+        }
+        clock.advance(chunk.len(), SAMPLE_RATE);
+    }
+    let timeline = timeline.unwrap();
+    let recording_offset = RECORDING_START_CHUNK * CHUNK_SAMPLES;
+    let recorded_samples = ((RECORDING_STOP_CHUNK - RECORDING_START_CHUNK) * CHUNK_SAMPLES) as u64;
+
+    let segments: Vec<serde_json::Value> = conversation.segments.iter()
+        .map(|truth| {
+            let mut segment = serde_json::json!({ "startTime": truth.start_time, "endTime": truth.end_time });
+            segment_audio::label_segment(&mut segment, Some(&timeline));
+            segment
+        })
+        .collect();
+
+    // Spoken before the recording started
+    assert!(segments[0]["startSample"].is_null() && segments[0]["endSample"].is_null());
+
+    for (segment, truth) in segments.iter().zip(&conversation.segments).skip(1) {
+        let (start, end) = segment_audio::segment_range(segment).unwrap();
+        assert!(((end - start) as f32 - truth.duration() * SAMPLE_RATE as f32).abs() <= 2.0);
+
+        let extracted = segment_audio::read_samples(&path, start, end).unwrap();
+        assert_eq!(extracted.sample_rate, SAMPLE_RATE);
+        assert_eq!(extracted.start_sample, start);
+        assert_eq!(extracted.samples.len() as u64, extracted.end_sample - extracted.start_sample);
+        let original = &audio[recording_offset + start as usize..recording_offset + extracted.end_sample as usize];
+        for (read, generated) in extracted.samples.iter().zip(original) {
+            assert!((read - generated).abs() <= TOLERANCE, "{} != {}", read, generated);
+        }
+
+        // The recording stopped at 27 s; the last segment is cut to what was kept
+        if end > recorded_samples {
+            assert!(extracted.clamped);
+            assert_eq!(extracted.end_sample, recorded_samples);
+        } else {
+            assert!(!extracted.clamped);
+        }
+    }
+    assert!(segment_audio::segment_range(segments.last().unwrap()).unwrap().1 > recorded_samples);
+
+    // As a temporary file: the same samples at full precision
+    let (start, end) = segment_audio::segment_range(&segments[1]).unwrap();
+    let mut as_file = segment_audio::read_samples(&path, start, end).unwrap();
+    let samples = as_file.samples.clone();
+    segment_audio::write_to_file(&mut as_file, &dir.path().join("segment_1.wav")).unwrap();
+    let reread = segment_audio::read_samples(std::path::Path::new(as_file.path.as_deref().unwrap()), 0, samples.len() as u64).unwrap();
+    assert_eq!(reread.samples, samples);
+}
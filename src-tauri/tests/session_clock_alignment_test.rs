@@ -0,0 +1,148 @@
+//! Alignment of transcript and diarization timestamps on the session clock
+//!
+//! Plays a synthetic two-speaker recording through the audio playback
+//! simulator in 100 ms chunks, buffers it the way the transcription loop does
+//! and stands in for the diarization engine with an extractor that times its
+//! embeddings within the buffer. With both placed on the SessionClock, text
+//! segments and speaker segments must agree on their boundaries within one
+//! chunk, also late in the session after long silences.
+
+use kaginote_lib::diarization::types::SpeakerEmbedding;
+use kaginote_lib::transcription::session_clock::SessionClock;
+use std::time::Duration;
+
+#[path = "diarization_realtime/audio_playback_simulator.rs"]
+mod audio_playback_simulator;
+#[path = "diarization_realtime/test_scenarios.rs"]
+mod test_scenarios;
+
+use audio_playback_simulator::{AudioPlaybackConfig, AudioPlaybackSimulator};
+use test_scenarios::{SyntheticAudioGenerator, TestScenarioGenerator};
+
+const SAMPLE_RATE: u32 = 16000;
+const CHUNK_SECONDS: f64 = 0.1;
+const FRAME_SAMPLES: usize = 1600;
+/// Buffers are transcribed at this length even while speech continues
+const MAX_BUFFER_SAMPLES: usize = 3 * SAMPLE_RATE as usize;
+const SPEECH_RMS: f32 = 0.02;
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Diarization stand-in: one embedding per voiced run, timed within the buffer
+fn extract_embeddings(buffer: &[f32]) -> Vec<SpeakerEmbedding> {
+    let mut embeddings = Vec::new();
+    let mut run_start = None;
+    let frames: Vec<bool> = buffer.chunks(FRAME_SAMPLES).map(|frame| rms(frame) > SPEECH_RMS).collect();
+    for (index, voiced) in frames.iter().chain(std::iter::once(&false)).enumerate() {
+        match (voiced, run_start) {
+            (true, None) => run_start = Some(index),
+            (false, Some(start)) => {
+                embeddings.push(SpeakerEmbedding {
+                    vector: vec![0.0; 4],
+                    confidence: 0.9,
+                    timestamp_start: (start * FRAME_SAMPLES) as f32 / SAMPLE_RATE as f32,
+                    timestamp_end: (index * FRAME_SAMPLES).min(buffer.len()) as f32 / SAMPLE_RATE as f32,
+                    speaker_id: None,
+                    quality: 0.9,
+                    extracted_at: 0,
+                    audio_duration_ms: 0,
+                });
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    embeddings
+}
+
+#[tokio::test]
+async fn test_text_and_speaker_segments_share_the_session_timebase() {
+    let scenario = TestScenarioGenerator::long_silences_scenario();
+    let audio = SyntheticAudioGenerator::generate_multi_frequency_audio(&scenario, SAMPLE_RATE);
+    let total_samples = audio.len();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(&scenario.audio_file);
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for sample in &audio {
+        writer.write_sample(*sample).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let mut simulator = AudioPlaybackSimulator::new(AudioPlaybackConfig {
+        speed_multiplier: 20.0,
+        // Large enough that no chunk is dropped while the test drains the channel
+        buffer_size: 1000,
+        enable_metrics: false,
+        ..Default::default()
+    });
+    simulator.load_audio_file(&path).await.expect("WAV should load");
+    let mut chunks = simulator.create_audio_channel();
+    simulator.start_playback().await.expect("playback should start");
+
+    let clock = SessionClock::new();
+    let mut buffer: Vec<f32> = Vec::new();
+    let mut text_segments: Vec<(f64, f64)> = Vec::new();
+    let mut speaker_segments: Vec<(f64, f64)> = Vec::new();
+    let mut flush = |buffer: &mut Vec<f32>, clock: &SessionClock| {
+        if buffer.is_empty() {
+            return;
+        }
+        text_segments.push(clock.buffer_span(buffer.len(), SAMPLE_RATE));
+        let (buffer_start, _) = clock.buffer_span(buffer.len(), SAMPLE_RATE);
+        let mut embeddings = extract_embeddings(buffer);
+        SessionClock::place_embeddings(&mut embeddings, buffer_start);
+        let first = embeddings.first().map(|e| e.timestamp_start as f64);
+        let last = embeddings.last().map(|e| e.timestamp_end as f64);
+        if let (Some(start), Some(end)) = (first, last) {
+            speaker_segments.push((start, end));
+        }
+        clock.clear_buffer();
+        buffer.clear();
+    };
+
+    let mut consumed = 0usize;
+    while consumed < total_samples {
+        let chunk = tokio::time::timeout(Duration::from_secs(5), chunks.recv())
+            .await
+            .expect("simulator should keep streaming")
+            .expect("channel should stay open");
+        consumed += chunk.samples.len();
+        let chunk_start = clock.advance(chunk.samples.len(), chunk.sample_rate);
+        if rms(&chunk.samples) > SPEECH_RMS {
+            clock.begin_buffer(chunk_start);
+            buffer.extend_from_slice(&chunk.samples);
+            if buffer.len() >= MAX_BUFFER_SAMPLES {
+                flush(&mut buffer, &clock);
+            }
+        } else {
+            flush(&mut buffer, &clock);
+        }
+    }
+    flush(&mut buffer, &clock);
+    simulator.stop().await.unwrap();
+
+    assert!(text_segments.len() >= scenario.segments.len());
+    assert_eq!(text_segments.len(), speaker_segments.len());
+    for (text, speaker) in text_segments.iter().zip(&speaker_segments) {
+        assert!((text.0 - speaker.0).abs() <= CHUNK_SECONDS + 1e-3, "start {:?} vs {:?}", text, speaker);
+        assert!((text.1 - speaker.1).abs() <= CHUNK_SECONDS + 1e-3, "end {:?} vs {:?}", text, speaker);
+        // Both lie on the scenario's speech, including the turns after long silences
+        let truth = scenario.segments.iter()
+            .find(|truth| speaker.0 + CHUNK_SECONDS >= truth.start_time as f64 && speaker.1 <= truth.end_time as f64 + CHUNK_SECONDS)
+            .unwrap_or_else(|| panic!("segment {:?} is not on any utterance", speaker));
+        assert!(speaker.0 < truth.end_time as f64);
+    }
+    assert!((speaker_segments.last().unwrap().1 - 40.0).abs() <= CHUNK_SECONDS + 1e-3);
+}
@@ -0,0 +1,82 @@
+//! Concurrent session starts
+//!
+//! Two start_transcription calls racing past their pre-flight checks must not
+//! both get to start audio capture: the session slot is reserved under a
+//! single lock before the capture opens, and a start that fails later gives
+//! it back. The starts run through `start_session_capture`, the part of
+//! start_transcription that needs no `AppHandle`, on simulated captures.
+
+use kaginote_lib::audio::capture_simulator::SimulatorConfig;
+use kaginote_lib::commands::{self, AppState, TranscriptionConfig};
+use std::path::Path;
+use std::sync::Arc;
+
+fn simulated(path: &Path) -> TranscriptionConfig {
+    TranscriptionConfig {
+        capture_simulator: Some(SimulatorConfig { path: path.to_path_buf(), speed: 0.0, chunk_ms: 100, dropouts: Vec::new() }),
+        ..TranscriptionConfig::default()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_only_one_concurrent_start_opens_a_capture() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("silence.wav");
+    let spec = hound::WavSpec { channels: 1, sample_rate: 16000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for _ in 0..16000 {
+        writer.write_sample(0i16).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let state = Arc::new(AppState::new());
+    let barrier = Arc::new(tokio::sync::Barrier::new(2));
+    let starts: Vec<_> = ["first", "second"].into_iter().map(|session_id| {
+        let state = state.clone();
+        let barrier = barrier.clone();
+        let config = simulated(&path);
+        tokio::spawn(async move {
+            barrier.wait().await;
+            commands::start_session_capture(&state, session_id, &config).await.map(|()| session_id)
+        })
+    }).collect();
+    let mut results = Vec::new();
+    for start in starts {
+        results.push(start.await.unwrap());
+    }
+
+    let started: Vec<&str> = results.iter().filter_map(|r| r.as_ref().ok().copied()).collect();
+    assert_eq!(started.len(), 1, "exactly one start should succeed");
+    let rejected = results.into_iter().find_map(Result::err).unwrap();
+    assert_eq!(rejected.payload().code, "session_already_active");
+    assert_eq!(state.active_sessions.lock().await.keys().collect::<Vec<_>>(), vec![started[0]]);
+    let captures = state.audio_captures.lock().await;
+    assert_eq!(captures.keys().collect::<Vec<_>>(), vec![started[0]], "exactly one capture, owned by the started session");
+    assert!(captures[started[0]].lock().await.is_capturing());
+}
+
+#[tokio::test]
+async fn test_capture_failure_releases_the_reservation() {
+    let state = AppState::new();
+    let dir = tempfile::tempdir().unwrap();
+    let missing = simulated(&dir.path().join("missing.wav"));
+
+    assert!(commands::start_session_capture(&state, "failing", &missing).await.is_err());
+    assert!(state.active_sessions.lock().await.is_empty());
+    assert!(state.audio_captures.lock().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_start_releases_its_reservation() {
+    let state = AppState::new();
+    let config = TranscriptionConfig::default();
+
+    commands::reserve_session(&state, "failing", &config).await.unwrap();
+    // Model validation or capture init failed
+    commands::release_session_reservation(&state, "failing").await;
+    assert!(state.active_sessions.lock().await.is_empty());
+
+    commands::reserve_session(&state, "retry", &config).await.unwrap();
+    let sessions = state.active_sessions.lock().await;
+    assert_eq!(sessions.get("retry").map(|s| s.status.as_str()), Some(commands::SESSION_STARTING));
+}
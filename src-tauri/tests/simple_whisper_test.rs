@@ -97,7 +97,7 @@ async fn test_model_manager_download() {
     // Test downloading the standard model
     println!("🔍 Testing model download for Standard tier...");
     
-    match model_manager.ensure_model_available(ModelTier::Standard, None).await {
+    match model_manager.ensure_model_available(ModelTier::Standard, None, None).await {
         Ok(path) => {
             println!("✅ Model available at: {:?}", path);
             
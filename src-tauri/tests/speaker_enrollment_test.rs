@@ -0,0 +1,142 @@
+//! Speaker enrollment from a short voice sample
+//!
+//! Samples come from the synthetic diarization audio, where each speaker is a
+//! tone at its own frequency. Embeddings stand in for the model's: one per
+//! ground-truth segment, holding the segment's energy at each of the
+//! generator's speaker frequencies, so two voices give dissimilar embeddings
+//! and one voice gives near-identical ones.
+
+use kaginote_lib::diarization::enrollment::{self, EnrollmentError};
+use kaginote_lib::diarization::known_speakers;
+use kaginote_lib::diarization::SpeakerEmbedding;
+use kaginote_lib::models::{CreateSpeakerProfileRequest, VoiceEmbedding};
+use kaginote_lib::storage::{Database, EmbeddingIndex, SpeakerStore};
+use tempfile::NamedTempFile;
+
+#[path = "diarization_realtime/test_scenarios.rs"]
+mod test_scenarios;
+
+use test_scenarios::{GroundTruthData, SyntheticAudioGenerator, TestScenarioGenerator};
+
+const SAMPLE_RATE: u32 = 16000;
+const SIMILARITY_THRESHOLD: f32 = 0.7;
+/// Speaker frequencies of the synthetic audio generator
+const SPEAKER_FREQUENCIES: [f32; 8] = [261.63, 329.63, 392.0, 440.0, 523.25, 659.25, 783.99, 880.0];
+/// Length of the stand-in embedding windows, in seconds
+const WINDOW_SECONDS: f32 = 2.0;
+
+/// Energy of `samples` at `frequency`
+fn goertzel(samples: &[f32], frequency: f32) -> f32 {
+    let coefficient = 2.0 * (2.0 * std::f32::consts::PI * frequency / SAMPLE_RATE as f32).cos();
+    let (mut previous, mut before_previous) = (0.0f32, 0.0f32);
+    for &sample in samples {
+        let current = sample + coefficient * previous - before_previous;
+        before_previous = previous;
+        previous = current;
+    }
+    (previous * previous + before_previous * before_previous - coefficient * previous * before_previous).max(0.0).sqrt()
+}
+
+/// Stand-in embeddings for the speech of `scenario`, one per window of each segment
+fn stand_in_embeddings(scenario: &GroundTruthData, audio: &[f32]) -> Vec<SpeakerEmbedding> {
+    let mut embeddings = Vec::new();
+    for segment in &scenario.segments {
+        let mut start = segment.start_time;
+        while start + WINDOW_SECONDS <= segment.end_time + f32::EPSILON {
+            let from = (start * SAMPLE_RATE as f32) as usize;
+            let to = (((start + WINDOW_SECONDS) * SAMPLE_RATE as f32) as usize).min(audio.len());
+            let mut vector: Vec<f32> = SPEAKER_FREQUENCIES.iter().map(|&f| goertzel(&audio[from..to], f)).collect();
+            let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt().max(f32::EPSILON);
+            vector.iter_mut().for_each(|v| *v /= norm);
+            embeddings.push(SpeakerEmbedding {
+                vector,
+                confidence: segment.confidence,
+                timestamp_start: start,
+                timestamp_end: start + WINDOW_SECONDS,
+                speaker_id: None,
+                quality: segment.confidence,
+                extracted_at: 0,
+                audio_duration_ms: (WINDOW_SECONDS * 1000.0) as u32,
+            });
+            start += WINDOW_SECONDS;
+        }
+    }
+    embeddings
+}
+
+#[test]
+fn test_single_voice_sample_is_accepted() {
+    let scenario = TestScenarioGenerator::single_speaker_monologue();
+    let audio = SyntheticAudioGenerator::generate_multi_frequency_audio(&scenario, SAMPLE_RATE);
+
+    let assessment = enrollment::assess_sample(&audio, SAMPLE_RATE).unwrap();
+    assert!(assessment.speech_seconds >= enrollment::MIN_ENROLLMENT_SPEECH_SECONDS);
+    assert!(assessment.speech_dbfs > enrollment::MIN_ENROLLMENT_DBFS);
+
+    let reference = enrollment::combine_embeddings(&stand_in_embeddings(&scenario, &audio), SIMILARITY_THRESHOLD).unwrap();
+    assert_eq!(reference.embedding_count, 15);
+    assert!(reference.quality_score > 0.9);
+}
+
+#[test]
+fn test_short_and_quiet_samples_are_rejected() {
+    let scenario = TestScenarioGenerator::single_speaker_monologue();
+    let audio = SyntheticAudioGenerator::generate_multi_frequency_audio(&scenario, SAMPLE_RATE);
+
+    let first_three_seconds = &audio[..3 * SAMPLE_RATE as usize];
+    let short = enrollment::assess_sample(first_three_seconds, SAMPLE_RATE).unwrap_err();
+    assert!(matches!(short, EnrollmentError::TooShort { speech_seconds } if (speech_seconds - 3.0).abs() < 0.1));
+    assert!(short.to_string().contains("needs at least 5s"));
+
+    let whispered: Vec<f32> = audio.iter().map(|s| s * 0.005).collect();
+    let quiet = enrollment::assess_sample(&whispered, SAMPLE_RATE).unwrap_err();
+    assert!(matches!(quiet, EnrollmentError::TooQuiet { level_dbfs } if level_dbfs < enrollment::MIN_ENROLLMENT_DBFS));
+    assert!(quiet.to_string().contains("too quiet"));
+}
+
+#[test]
+fn test_sample_with_two_voices_is_rejected() {
+    let scenario = TestScenarioGenerator::simple_two_speaker_conversation();
+    let audio = SyntheticAudioGenerator::generate_multi_frequency_audio(&scenario, SAMPLE_RATE);
+
+    // Enough speech at a good level; only the voices give it away
+    assert!(enrollment::assess_sample(&audio, SAMPLE_RATE).is_ok());
+    let rejected = enrollment::combine_embeddings(&stand_in_embeddings(&scenario, &audio), SIMILARITY_THRESHOLD).unwrap_err();
+    assert_eq!(rejected, EnrollmentError::MultipleSpeakers { speakers: 2 });
+    assert!(rejected.to_string().contains("2 different voices"));
+}
+
+#[tokio::test]
+async fn test_enrolled_speaker_is_found_in_the_index() {
+    let db_file = NamedTempFile::new().unwrap();
+    let database = Database::new(db_file.path()).await.unwrap();
+    database.migrate().await.unwrap();
+    let store = SpeakerStore::new(database);
+    let index = EmbeddingIndex::new(SPEAKER_FREQUENCIES.len(), 8);
+
+    let scenario = TestScenarioGenerator::single_speaker_monologue();
+    let audio = SyntheticAudioGenerator::generate_multi_frequency_audio(&scenario, SAMPLE_RATE);
+    let embeddings = stand_in_embeddings(&scenario, &audio);
+    let reference = enrollment::combine_embeddings(&embeddings, SIMILARITY_THRESHOLD).unwrap();
+
+    // Stored the way enroll_speaker stores it
+    let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+        name: "Alice".to_string(),
+        description: None,
+        color: None,
+        confidence_threshold: None,
+    }).await.unwrap();
+    let embedding = VoiceEmbedding::new(
+        profile.id,
+        reference.vector.clone(),
+        known_speakers::LIVE_EMBEDDING_MODEL.to_string(),
+        reference.quality_score,
+        reference.duration_seconds,
+    );
+    store.add_voice_embedding(embedding.clone()).await.unwrap();
+    index.add_embedding(embedding).unwrap();
+
+    assert_eq!(store.get_voice_embeddings(profile.id).await.unwrap().len(), 1);
+    let matches = index.find_similar_embeddings(&embeddings[7].vector, profile.confidence_threshold, 1).unwrap();
+    assert_eq!(matches.first().map(|(id, _)| *id), Some(profile.id));
+}
@@ -0,0 +1,95 @@
+//! Speaker profile export, clear and import
+//!
+//! An export written to JSON and imported into a cleared store must bring the
+//! speakers back searchable: their embeddings re-linked to the recreated
+//! profiles in the database and in the embedding index.
+
+use kaginote_lib::models::{CreateSpeakerProfileRequest, SpeakerSample, VoiceEmbedding};
+use kaginote_lib::storage::profile_transfer::{self, ConflictStrategy, ImportOutcome, SpeakerImport};
+use kaginote_lib::storage::{Database, EmbeddingIndex, SeedManager, SpeakerStore, SPEAKER_SAMPLE_CAP_SECONDS};
+use tempfile::NamedTempFile;
+
+const DIMENSION: usize = 8;
+
+fn voice(axis: usize) -> Vec<f32> {
+    let mut vector = vec![0.05; DIMENSION];
+    vector[axis] = 1.0;
+    vector
+}
+
+#[tokio::test]
+async fn test_export_clear_import_keeps_speakers_searchable() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+    db.migrate().await.unwrap();
+    let store = SpeakerStore::new(db.clone());
+    let index = EmbeddingIndex::new(DIMENSION, 4);
+
+    for (axis, name) in ["Alice", "Bob", "Carol"].into_iter().enumerate() {
+        let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+            name: name.to_string(),
+            description: Some(format!("{} from the weekly sync", name)),
+            color: None,
+            confidence_threshold: Some(0.75),
+        }).await.unwrap();
+        let embedding = VoiceEmbedding::new(profile.id, voice(axis), "test_model".to_string(), 0.9, 5.0);
+        store.add_voice_embedding(embedding.clone()).await.unwrap();
+        index.add_embedding(embedding).unwrap();
+        let sample = SpeakerSample::from_samples(profile.id, &[0.1; 8000], 16000).unwrap();
+        store.add_speaker_sample(sample, SPEAKER_SAMPLE_CAP_SECONDS).await.unwrap();
+    }
+
+    let exported = profile_transfer::export_speakers(&store, true, true).await.unwrap();
+    let file_contents = serde_json::to_string(&exported).unwrap();
+
+    SeedManager::new(db.clone()).clear_all_data().await.unwrap();
+    index.clear().unwrap();
+    assert!(store.list_speaker_profiles(false).await.unwrap().is_empty());
+
+    let import: SpeakerImport = serde_json::from_str(&file_contents).unwrap();
+    let report = profile_transfer::import_speakers(&store, &index, import, ConflictStrategy::Skip).await.unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert_eq!(report.imported_profiles(), 3);
+    assert_eq!((report.imported_embeddings, report.imported_samples), (3, 3));
+    assert!(report.profiles.iter().all(|p| p.outcome == ImportOutcome::Created));
+
+    for (axis, outcome) in report.profiles.iter().enumerate() {
+        let profile_id = outcome.profile_id.unwrap();
+
+        // Database search finds the recreated profile
+        let similar = store.find_similar_speakers(voice(axis), 0.9, 1).await.unwrap();
+        assert_eq!(similar[0].speaker.id, profile_id);
+        assert_eq!(similar[0].speaker.name, outcome.name);
+        assert_eq!(similar[0].speaker.confidence_threshold, 0.75);
+
+        // So does the index
+        let indexed = index.find_similar_embeddings(&voice(axis), 0.9, 1).unwrap();
+        assert_eq!(indexed[0].0, profile_id);
+
+        assert_eq!(store.get_speaker_samples(profile_id).await.unwrap().len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn test_reimport_skips_existing_names() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+    db.migrate().await.unwrap();
+    let store = SpeakerStore::new(db);
+    let index = EmbeddingIndex::new(DIMENSION, 4);
+    let profile = store.create_speaker_profile(CreateSpeakerProfileRequest {
+        name: "Dana".to_string(),
+        description: None,
+        color: None,
+        confidence_threshold: None,
+    }).await.unwrap();
+    store.add_voice_embedding(VoiceEmbedding::new(profile.id, voice(0), "test_model".to_string(), 0.9, 5.0)).await.unwrap();
+
+    let import: SpeakerImport = serde_json::from_value(profile_transfer::export_speakers(&store, true, false).await.unwrap()).unwrap();
+    let report = profile_transfer::import_speakers(&store, &index, import, ConflictStrategy::default()).await.unwrap();
+
+    assert_eq!(report.profiles[0].outcome, ImportOutcome::Skipped);
+    assert_eq!(report.imported_embeddings, 0);
+    assert!(report.errors.is_empty());
+    assert_eq!(store.get_voice_embeddings(profile.id).await.unwrap().len(), 1);
+}
@@ -88,7 +88,7 @@ mod transcription_comparison_tests {
         println!("\nInitializing Whisper engine (same as main app)...");
         let config = WhisperConfig {
             model_type: "medium".to_string(), // Same as default in main app
-            language: Some("en".to_string()),
+            language: Some("en".parse().unwrap()),
             translate: false,
             use_gpu: cfg!(target_os = "macos"), // Use Metal on macOS like main app
             ..Default::default()
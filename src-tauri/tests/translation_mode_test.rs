@@ -0,0 +1,50 @@
+//! File transcription in translate mode with a downloaded Whisper model
+//!
+//! Runs only with `KAGINOTE_WHISPER_TESTS=1`. The LibriSpeech sample checks
+//! that English speech passes through the translate task; set
+//! `KAGINOTE_TRANSLATION_AUDIO` to a recording in another language to check
+//! that it comes back in English with its source language detected.
+
+use kaginote_lib::asr::types::{Language, Task, TranscriptionContext};
+use kaginote_lib::asr::whisper::{WhisperConfig, WhisperEngine};
+use kaginote_lib::transcription::translation;
+
+const WHISPER_TESTS_ENV: &str = "KAGINOTE_WHISPER_TESTS";
+const TRANSLATION_AUDIO_ENV: &str = "KAGINOTE_TRANSLATION_AUDIO";
+const LIBRISPEECH_SAMPLE: &str = "tests/diarization_realtime/test_audio/LibriSpeech/test-clean/1089/134686/1089-134686-0000.flac.wav";
+
+fn whisper_tests_enabled() -> bool {
+    std::env::var(WHISPER_TESTS_ENV).map(|v| !v.is_empty() && v != "0").unwrap_or(false)
+}
+
+async fn translate_file(path: &str) -> kaginote_lib::asr::types::ASRResult {
+    // Pinned to Japanese, which translate mode must not force on the decoder
+    let mut config = WhisperConfig { language: Language::parse("ja").ok(), ..WhisperConfig::default() };
+    translation::configure(&mut config, translation::MODE_TRANSLATE);
+    assert_eq!(config.task, Task::Translate);
+
+    let engine = WhisperEngine::new(config).await.expect("Whisper model should load");
+    assert_eq!(engine.get_config().task, Task::Translate);
+    let audio = kaginote_lib::commands::read_audio_file(path).await.expect("audio should decode");
+    engine.transcribe(&audio, &TranscriptionContext::default()).await.expect("translation should succeed")
+}
+
+#[tokio::test]
+async fn test_translate_mode_file_transcription() {
+    if !whisper_tests_enabled() {
+        println!("Skipping translate mode test, set {}=1 to run it", WHISPER_TESTS_ENV);
+        return;
+    }
+
+    let english = translate_file(LIBRISPEECH_SAMPLE).await;
+    assert!(!english.text.trim().is_empty());
+    assert_eq!(english.language, "en");
+
+    if let Ok(path) = std::env::var(TRANSLATION_AUDIO_ENV) {
+        let translated = translate_file(&path).await;
+        println!("{} ({}): {}", path, translated.language, translated.text);
+        assert_ne!(translated.language, "en", "source language should be detected");
+        let ascii = translated.text.chars().filter(char::is_ascii).count();
+        assert!(ascii * 10 >= translated.text.chars().count() * 9, "translation should be English text");
+    }
+}
@@ -0,0 +1,126 @@
+//! Latency of energy VAD gating in the live transcription path
+//!
+//! Streams synthetic diarization audio through the energy VAD and the utterance
+//! endpointer in 100 ms chunks, as the transcription loop does, and measures
+//! the per-chunk cost against plain RMS gating and how long after the end of
+//! each utterance its flush is decided.
+
+use kaginote_lib::audio::energy_vad::EnergyVad;
+use kaginote_lib::audio::levels;
+use kaginote_lib::audio::utterance::{ChunkDecision, EndpointConfig, UtteranceTracker};
+use std::time::{Duration, Instant};
+
+#[path = "diarization_realtime/test_scenarios.rs"]
+mod test_scenarios;
+
+use test_scenarios::{SyntheticAudioGenerator, TestScenarioGenerator, DEFAULT_SCENARIO_SEED};
+
+const SAMPLE_RATE: u32 = 16000;
+const CHUNK_SAMPLES: usize = 1600; // 100 ms
+
+/// Short decaying 2 kHz bursts standing in for key presses
+fn add_key_clicks(audio: &mut [f32], at_seconds: &[f32]) {
+    for &at in at_seconds {
+        let start = (at * SAMPLE_RATE as f32) as usize;
+        for n in 0..128 {
+            if let Some(sample) = audio.get_mut(start + n) {
+                let t = n as f32 / SAMPLE_RATE as f32;
+                *sample += 0.8 * (-(n as f32) / 40.0).exp() * (2.0 * std::f32::consts::PI * 2000.0 * t).sin();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_vad_gating_latency_on_simulated_meeting() {
+    let scenario = TestScenarioGenerator::long_silences_scenario();
+    let mut audio = SyntheticAudioGenerator::generate_multi_frequency_audio(&scenario, SAMPLE_RATE);
+    // One second of trailing silence so the last utterance can end
+    audio.extend(std::iter::repeat(0.0).take(SAMPLE_RATE as usize));
+    SyntheticAudioGenerator::add_background_noise(&mut audio, 0.01, DEFAULT_SCENARIO_SEED);
+    add_key_clicks(&mut audio, &[8.0, 9.55, 20.3, 33.0]);
+
+    let mut vad = EnergyVad::new();
+    let endpoint = EndpointConfig::default();
+    let mut tracker = UtteranceTracker::new(endpoint);
+    let threshold = 0.5;
+
+    let mut vad_time = Duration::ZERO;
+    let mut rms_time = Duration::ZERO;
+    let mut chunks = 0u32;
+    let mut utterance_start: Option<f64> = None;
+    let mut utterances: Vec<(f64, f64)> = Vec::new();
+    let mut discarded = 0;
+
+    for (index, chunk) in audio.chunks(CHUNK_SAMPLES).enumerate() {
+        let chunk_start = index as f64 * 0.1;
+
+        let started = Instant::now();
+        let probability = vad.speech_probability(chunk);
+        vad_time += started.elapsed();
+
+        let started = Instant::now();
+        let _ = levels::level_probability(levels::measure(chunk).legacy_level());
+        rms_time += started.elapsed();
+        chunks += 1;
+
+        match tracker.observe(probability, threshold, chunk.len() as f64 / SAMPLE_RATE as f64) {
+            ChunkDecision::Speech => {
+                utterance_start.get_or_insert(chunk_start);
+            }
+            ChunkDecision::End => {
+                utterances.push((utterance_start.take().unwrap(), chunk_start));
+            }
+            ChunkDecision::Discard => {
+                utterance_start = None;
+                discarded += 1;
+            }
+            ChunkDecision::Silence | ChunkDecision::Trailing => {}
+        }
+    }
+
+    let vad_per_chunk = vad_time / chunks;
+    let rms_per_chunk = rms_time / chunks;
+    println!("VAD gating: {:?} per 100 ms chunk (RMS gating {:?})", vad_per_chunk, rms_per_chunk);
+
+    // Key clicks never become utterances
+    assert_eq!(utterances.len(), scenario.segments.len(), "utterances {:?}", utterances);
+    println!("Discarded bursts: {}", discarded);
+
+    for ((start, flushed_at), truth) in utterances.iter().zip(&scenario.segments) {
+        let onset_error = (start - truth.start_time as f64).abs();
+        let endpoint_latency = flushed_at - truth.end_time as f64;
+        println!(
+            "{} {:.1}-{:.1}s: onset error {:.2}s, flushed {:.2}s after speech ended",
+            truth.speaker_id, truth.start_time, truth.end_time, onset_error, endpoint_latency
+        );
+        assert!(onset_error <= 0.1, "onset error {:.2}s", onset_error);
+        // The flush waits out the hangover, plus at most one chunk of detection delay
+        assert!(endpoint_latency >= endpoint.hangover_seconds - 0.1);
+        assert!(endpoint_latency <= endpoint.hangover_seconds + 0.15, "endpoint latency {:.2}s", endpoint_latency);
+    }
+
+    // The VAD has to keep up with capture with plenty of headroom
+    assert!(vad_per_chunk < Duration::from_millis(5), "VAD takes {:?} per chunk", vad_per_chunk);
+}
+
+#[test]
+fn test_soft_speech_passes_the_vad() {
+    // Five seconds of speech 40 dB below full scale over a quiet room
+    let scenario = TestScenarioGenerator::single_speaker_monologue();
+    let mut audio = SyntheticAudioGenerator::generate_multi_frequency_audio(&scenario, SAMPLE_RATE);
+    audio.truncate(5 * SAMPLE_RATE as usize);
+    for sample in audio.iter_mut() {
+        *sample *= 0.05;
+    }
+    SyntheticAudioGenerator::add_background_noise(&mut audio, 0.0005, DEFAULT_SCENARIO_SEED);
+
+    let mut vad = EnergyVad::new();
+    let mut tracker = UtteranceTracker::default();
+    let decisions: Vec<ChunkDecision> = audio.chunks(CHUNK_SAMPLES)
+        .map(|chunk| tracker.observe(vad.speech_probability(chunk), 0.5, 0.1))
+        .collect();
+
+    assert_eq!(decisions.len(), 50);
+    assert!(decisions.iter().all(|&d| d == ChunkDecision::Speech), "{:?}", decisions);
+}
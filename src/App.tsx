@@ -11,6 +11,7 @@ import { TranscriptSegment, SpeakerInfo } from "./components/features/Transcript
 import { TranscriptionController, FinalTranscriptionResult, TranscriptionError, TranscriptionUpdateEvent, TranscriptionConfig, TranscriptionControllerRef } from "./components/features/TranscriptionController";
 import { MeetingFile } from "./screens/Dashboard";
 import { ToastProvider } from "./components/ui/ToastContainer";
+import { errorMessage } from "./types/errors";
 import './styles/globals.css';
 
 type AppScreen = 'dashboard' | 'recording' | 'meeting-review' | 'transcripts' | 'settings';
@@ -165,15 +166,18 @@ function App() {
     const setupAudioLevelListener = async () => {
       unlistenAudioLevel = await listen<{
         level: number;
-        vadActivity: boolean;
+        // Speech probability (0-1)
+        vadActivity: number;
+        vadThreshold: number;
+        inUtterance?: boolean;
         sessionId: string;
         timestamp: number;
       }>('audio-level', (event) => {
-        const { level, vadActivity } = event.payload;
+        const { level, vadActivity, vadThreshold, inUtterance } = event.payload;
         setAppState(prev => ({ 
           ...prev, 
           audioLevel: level,
-          vadActivity
+          vadActivity: inUtterance ?? vadActivity > vadThreshold
         }));
       });
     };
@@ -263,6 +267,8 @@ function App() {
         type: string;
         message: string;
         recoverable: boolean;
+        retryCount?: number;
+        manualRetryAvailable?: boolean;
         timestamp: number;
       }>('diarization-warning', (event) => {
         const { type, message, recoverable } = event.payload;
@@ -582,7 +588,7 @@ function App() {
     } catch (error) {
       console.error('Failed to import audio file:', error);
       // TODO: Show error toast/notification
-      alert(`Failed to import audio file: ${error}`);
+      alert(`Failed to import audio file: ${errorMessage(error)}`);
     }
   };
 
@@ -611,22 +617,44 @@ function App() {
     // Convert update to transcript segment and add to live transcript
     const speakerId = (update.segment as any)?.speakerId || update.segment?.speaker || 'speaker_1';
     
+    if (update.updateType === 'partial_discarded') {
+      setAppState(prev => ({
+        ...prev,
+        transcriptSegments: prev.transcriptSegments.filter(segment => segment.id !== update.segmentId)
+      }));
+      return;
+    }
+    
+    if (update.updateType === 'speaker_renamed') {
+      const renamed = new Set(update.segmentIds ?? []);
+      setAppState(prev => ({
+        ...prev,
+        transcriptSegments: prev.transcriptSegments.map(segment =>
+          renamed.has(segment.id) ? { ...segment, speaker: update.displayName ?? segment.speaker } : segment
+        )
+      }));
+      return;
+    }
+    
     const newSegment: TranscriptSegment = {
-      id: `${update.sessionId}-${Date.now()}-${Math.random()}`,
+      id: update.segment?.id || `${update.sessionId}-${Date.now()}-${Math.random()}`,
       startTime: update.segment?.startTime || 0,
       endTime: update.segment?.endTime || 0,
-      speaker: update.segment?.speaker || 'Speaker 1',
+      speaker: update.segment?.speakerName || update.segment?.speaker || 'Speaker 1',
       speakerId: speakerId,
       text: update.segment?.text || '',
       confidence: update.segment?.confidence || 0.95
     };
     
-    // Only add non-empty segments
+    // Only add non-empty segments; partials and their final version share an id
     if (newSegment.text.trim()) {
-      setAppState(prev => ({
-        ...prev,
-        transcriptSegments: [...prev.transcriptSegments, newSegment]
-      }));
+      setAppState(prev => {
+        const existing = prev.transcriptSegments.findIndex(segment => segment.id === newSegment.id);
+        const transcriptSegments = existing >= 0
+          ? prev.transcriptSegments.map((segment, index) => index === existing ? newSegment : segment)
+          : [...prev.transcriptSegments, newSegment];
+        return { ...prev, transcriptSegments };
+      });
     }
   };
 
@@ -36,6 +36,35 @@ export interface TranscriptionSession {
   config: TranscriptionConfig;
   startTime: number;
   status: 'active' | 'stopping' | 'stopped';
+  // Live progress reported by get_active_sessions
+  elapsedSeconds?: number;
+  segmentCount?: number;
+  lastSegmentAt?: number | null;
+  modelStatus?: 'downloading' | 'ready' | 'error';
+  diarizationStatus?: 'disabled' | 'initializing' | 'active' | 'degraded';
+  currentAudioLevel?: {
+    level: number;
+    peakDbfs: number;
+    rmsDbfs: number;
+    clipping: boolean;
+    timestamp: number;
+  } | null;
+}
+
+export interface ConfigIssue {
+  field: string;
+  message: string;
+  original: unknown;
+  adjusted?: unknown;
+}
+
+export interface StartTranscriptionResponse {
+  sessionId: string;
+  effectiveConfig: TranscriptionConfig;
+  validation: {
+    errors: ConfigIssue[];
+    warnings: ConfigIssue[];
+  };
 }
 
 export interface TranscriptionError {
@@ -64,6 +93,8 @@ export interface SystemStatus {
     cpuUsage?: number;
     memoryUsage?: number;
   };
+  // Device inference runs on
+  inferenceDevice?: 'cpu' | 'metal' | 'cuda' | null;
 }
 
 export interface TranscriptionUpdateEvent {
@@ -80,6 +111,7 @@ export interface FinalTranscriptionResult {
   speakers?: any[];
   qualityMetrics: any;
   processingTimeMs: number;
+  audioRecordingPath?: string | null;
 }
 
 export interface SpeakerDetectionEvent {
@@ -100,8 +132,10 @@ export interface SpeakerUpdateEvent {
 export interface DiarizationWarningEvent {
   sessionId: string;
   message: string;
-  type: 'low_confidence' | 'model_fallback' | 'processing_delay';
+  type: 'low_confidence' | 'model_fallback' | 'processing_delay' | 'initialization_failed' | 'embedding_extraction_failed';
   recoveryHint?: string;
+  retryCount?: number;
+  manualRetryAvailable?: boolean;
 }
 
 export interface DiarizationErrorEvent {
@@ -419,13 +453,13 @@ export const TranscriptionController: React.FC<TranscriptionControllerProps> = (
     }
 
     try {
-      const sessionId = await invoke<string>('start_transcription', {
+      const { sessionId, effectiveConfig } = await invoke<StartTranscriptionResponse>('start_transcription', {
         config: config,
       });
 
       const session: TranscriptionSession = {
         sessionId,
-        config: { ...config },
+        config: { ...effectiveConfig },
         startTime: Date.now(),
         status: 'active',
       };
@@ -437,7 +471,7 @@ export const TranscriptionController: React.FC<TranscriptionControllerProps> = (
       onSessionStart(sessionId);
     } catch (err: any) {
       const error: TranscriptionError = {
-        type: err.type || 'transcription_start_failed',
+        type: err.code || err.type || 'transcription_start_failed',
         message: err.message || 'Failed to start transcription',
         timestamp: Date.now(),
         recoveryOptions: err.recoveryOptions,
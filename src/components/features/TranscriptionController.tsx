@@ -8,6 +8,7 @@
 import React, { useState, useEffect, useRef, forwardRef, useImperativeHandle } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { errorMessage, isCommandError, type ErrorCategory } from '@/types/errors';
 
 export interface TranscriptionConfig {
   qualityTier: 'standard' | 'high-accuracy' | 'turbo';
@@ -34,6 +35,35 @@ export interface TranscriptionSession {
   config: TranscriptionConfig;
   startTime: number;
   status: 'active' | 'stopping' | 'stopped';
+  // Live progress reported by get_active_sessions
+  elapsedSeconds?: number;
+  segmentCount?: number;
+  lastSegmentAt?: number | null;
+  modelStatus?: 'downloading' | 'ready' | 'error';
+  diarizationStatus?: 'disabled' | 'initializing' | 'active' | 'degraded';
+  currentAudioLevel?: {
+    level: number;
+    peakDbfs: number;
+    rmsDbfs: number;
+    clipping: boolean;
+    timestamp: number;
+  } | null;
+}
+
+export interface ConfigIssue {
+  field: string;
+  message: string;
+  original: unknown;
+  adjusted?: unknown;
+}
+
+export interface StartTranscriptionResponse {
+  sessionId: string;
+  effectiveConfig: TranscriptionConfig;
+  validation: {
+    errors: ConfigIssue[];
+    warnings: ConfigIssue[];
+  };
 }
 
 export interface TranscriptionError {
@@ -41,10 +71,30 @@ export interface TranscriptionError {
   message: string;
   severity?: 'warning' | 'error' | 'critical';
   recoveryOptions?: string[];
+  /** Category of a command rejection, which picks the error dialog */
+  category?: ErrorCategory;
   timestamp: number;
   sessionId?: string;
 }
 
+/** Error state of a rejected command: its code and recovery steps when it returned a CommandError */
+function commandFailure(err: unknown, fallbackType: string, fallbackMessage: string): TranscriptionError {
+  if (isCommandError(err)) {
+    return {
+      type: err.code,
+      message: err.message || fallbackMessage,
+      recoveryOptions: err.recoveryOptions?.length ? err.recoveryOptions : undefined,
+      category: err.category,
+      timestamp: Date.now(),
+    };
+  }
+  return {
+    type: fallbackType,
+    message: errorMessage(err) || fallbackMessage,
+    timestamp: Date.now(),
+  };
+}
+
 export interface SystemStatus {
   thermalStatus?: {
     temperature: number;
@@ -62,13 +112,22 @@ export interface SystemStatus {
     cpuUsage?: number;
     memoryUsage?: number;
   };
+  // Device inference runs on
+  inferenceDevice?: 'cpu' | 'metal' | 'cuda' | null;
 }
 
 export interface TranscriptionUpdateEvent {
   sessionId: string;
-  segment: any;
-  updateType: 'new' | 'update' | 'correction';
-  processingPass: 1 | 2;
+  segment?: any;
+  // 'partial' segments are provisional and replaced by the 'final' one with the same id
+  updateType: 'new' | 'update' | 'correction' | 'partial' | 'final' | 'partial_discarded' | 'speaker_renamed';
+  processingPass?: 1 | 2;
+  // Id of the retracted partial for 'partial_discarded'
+  segmentId?: string;
+  // For 'speaker_renamed': the renamed speaker and the segments now carrying the name
+  speakerId?: string;
+  displayName?: string;
+  segmentIds?: string[];
 }
 
 export interface FinalTranscriptionResult {
@@ -78,6 +137,7 @@ export interface FinalTranscriptionResult {
   speakers?: any[];
   qualityMetrics: any;
   processingTimeMs: number;
+  audioRecordingPath?: string | null;
 }
 
 export interface TranscriptionControllerProps {
@@ -149,25 +209,21 @@ export const TranscriptionController = forwardRef<TranscriptionControllerRef, Tr
     setError(null);
 
     try {
-      const sessionId = await invoke<string>('start_transcription', {
+      const { sessionId, effectiveConfig } = await invoke<StartTranscriptionResponse>('start_transcription', {
         config: config,
       });
 
       const session: TranscriptionSession = {
         sessionId,
-        config: { ...config },
+        config: { ...effectiveConfig },
         startTime: Date.now(),
         status: 'active',
       };
 
       setCurrentSession(session);
       onSessionStart(sessionId);
-    } catch (err: any) {
-      const error: TranscriptionError = {
-        type: 'transcription_start_failed',
-        message: err.message || 'Failed to start transcription',
-        timestamp: Date.now(),
-      };
+    } catch (err) {
+      const error = commandFailure(err, 'transcription_start_failed', 'Failed to start transcription');
       
       setError(error);
       onError(error);
@@ -189,11 +245,9 @@ export const TranscriptionController = forwardRef<TranscriptionControllerRef, Tr
       onSessionEnd(result);
       setCurrentSession(null);
       setLatestTranscription('');
-    } catch (err: any) {
+    } catch (err) {
       const error: TranscriptionError = {
-        type: 'transcription_stop_failed',
-        message: err.message || 'Failed to stop transcription',
-        timestamp: Date.now(),
+        ...commandFailure(err, 'transcription_stop_failed', 'Failed to stop transcription'),
         sessionId: currentSession.sessionId,
       };
       
@@ -208,11 +262,9 @@ export const TranscriptionController = forwardRef<TranscriptionControllerRef, Tr
       setCurrentSession(null);
       setLatestTranscription('');
       setError(null);
-    } catch (err: any) {
+    } catch (err) {
       const error: TranscriptionError = {
-        type: 'emergency_stop_failed',
-        message: err.message || 'Failed to stop all sessions',
-        timestamp: Date.now(),
+        ...commandFailure(err, 'emergency_stop_failed', 'Failed to stop all sessions'),
         severity: 'critical',
       };
       
@@ -264,7 +316,7 @@ export const TranscriptionController = forwardRef<TranscriptionControllerRef, Tr
           (event) => {
             const update = event.payload;
             if (update.sessionId === currentSession.sessionId) {
-              setLatestTranscription(update.segment.text || '');
+              setLatestTranscription(update.segment?.text || '');
               if (onTranscriptionUpdate) {
                 onTranscriptionUpdate(update);
               }
@@ -595,9 +647,9 @@ export const TranscriptionController = forwardRef<TranscriptionControllerRef, Tr
   const renderErrors = () => {
     if (!error) return null;
 
-    const isModelError = error.type.includes('model');
-    const isAudioError = error.type.includes('audio');
-    const isSystemError = error.type.includes('system');
+    const isModelError = error.category === 'model' || error.type.includes('model');
+    const isAudioError = error.category === 'audio' || error.category === 'permissions' || error.type.includes('audio');
+    const isSystemError = error.category === 'system' || error.type.includes('system');
     const isWhisperBuildError = error.message.includes('whisper-rs') || error.message.includes('cmake') || error.message.includes('build');
     
     return (
@@ -0,0 +1,29 @@
+// TypeScript types for audio input devices
+// These types match the Rust models returned by get_audio_devices
+
+export interface DeviceCapabilities {
+  minSampleRate: number;
+  maxSampleRate: number;
+  supportedChannels: number[];
+  defaultSampleRate: number | null;
+  requiresResampling: boolean; // No input format captures at 16 kHz
+}
+
+export interface AudioDevice {
+  id: string;
+  name: string;
+  is_input_device: boolean;
+  is_default: boolean;
+  sample_rates: number[];
+  channels: number;
+  capabilities: DeviceCapabilities;
+}
+
+// Payload of the audio-devices-changed event
+export interface AudioDevicesChangedEvent {
+  added: AudioDevice[];
+  removed: AudioDevice[];
+  defaultDeviceId: string | null;
+  devices: AudioDevice[];
+  timestamp: number;
+}
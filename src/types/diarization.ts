@@ -231,6 +231,64 @@ export interface DiarizationStatistics {
   avgSegmentLength: number;
 }
 
+/**
+ * Talk time of one speaker of a session (get_diarization_stats)
+ */
+export interface SpeakerTalkTime {
+  speakerId: string;
+  speakingSeconds: number;
+  segmentCount: number;
+  averageConfidence: number | null;
+  /** Share of the summed talk time of all speakers (0-100) */
+  talkTimePercent: number;
+  muted: boolean;
+}
+
+/**
+ * Diarization statistics of a live session, as returned by get_diarization_stats
+ */
+export interface SessionDiarizationStats {
+  sessionId: string;
+
+  /** Distinct speakers of the stored segments */
+  speakersDetected: number;
+
+  /** Most talkative first */
+  speakers: SpeakerTalkTime[];
+
+  totalSegments: number;
+
+  /** Mean speaker attribution confidence of the diarized segments */
+  averageConfidence: number | null;
+
+  /** Consecutive segments with different speakers */
+  speakerSwitches: number;
+
+  /** Speech attributed from a voice embedding */
+  diarizedSeconds: number;
+
+  /** Speech attributed without one (fallback speaker or local microphone) */
+  undiarizedSeconds: number;
+
+  overlappingSegments: number;
+
+  /** Share of speech time with cross-talk (0-1) */
+  overlapRatio: number;
+
+  embeddingFailures: number;
+
+  /** Share of attributed chunks that matched a speaker already heard (0-1) */
+  reidentificationHitRate: number | null;
+
+  /** Speaker count estimated from the cluster structure, once re-clustered */
+  estimatedSpeakerCount: number | null;
+
+  /** Confidence of the estimated speaker count (0-1) */
+  estimatedSpeakerCountConfidence: number | null;
+
+  processingTimeMs: number;
+}
+
 /**
  * Individual speaker statistics
  */
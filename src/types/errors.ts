@@ -0,0 +1,38 @@
+// TypeScript types for errors returned by Tauri commands
+// These types match the Rust CommandError payload (src-tauri/src/command_error.rs)
+
+export type ErrorCategory =
+  | 'model'
+  | 'audio'
+  | 'permissions'
+  | 'diarization'
+  | 'storage'
+  | 'validation'
+  | 'system'
+  | 'network'
+  | 'unknown';
+
+/** Rejection value of every command, also the body of transcription-error events */
+export interface CommandError {
+  /** Stable snake_case identifier, e.g. "session_already_active" */
+  code: string;
+  /** Human-readable description */
+  message: string;
+  category: ErrorCategory;
+  recoveryOptions: string[];
+  details?: Record<string, unknown> | null;
+}
+
+export function isCommandError(error: unknown): error is CommandError {
+  return typeof error === 'object' && error !== null
+    && typeof (error as CommandError).code === 'string'
+    && typeof (error as CommandError).message === 'string';
+}
+
+/** Message of a command rejection, or of any other thrown value */
+export function errorMessage(error: unknown): string {
+  if (isCommandError(error)) {
+    return error.message;
+  }
+  return error instanceof Error ? error.message : String(error);
+}
@@ -64,7 +64,11 @@ describe('TranscriptionController Component', () => {
     mockInvoke.mockImplementation((command: string, args?: any) => {
       switch (command) {
         case 'start_transcription':
-          return Promise.resolve('session-123');
+          return Promise.resolve({
+            sessionId: 'session-123',
+            effectiveConfig: args?.config,
+            validation: { errors: [], warnings: [] },
+          });
         case 'stop_transcription':
           return Promise.resolve({
             sessionId: 'session-123',